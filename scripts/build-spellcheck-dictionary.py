@@ -0,0 +1,137 @@
+#!/usr/bin/env python3
+
+"""
+Spell check dictionary builder for CodeForge IDE
+Counts the words in locally installed documentation and writes the frequent ones to
+src-tauri/src/spellcheck/dictionaries/en.txt, most frequent first. See the README next to that file.
+
+Usage: build-spellcheck-dictionary.py [output] (run on a Debian/Ubuntu host with rustup's rust-docs)
+"""
+
+import collections
+import gzip
+import html
+import os
+import re
+import sys
+
+RUST_DOCS = os.path.expanduser('~/.rustup/toolchains/stable-x86_64-unknown-linux-gnu/share/doc/rust/html')
+MAN_SECTIONS = ['man1', 'man2', 'man3', 'man5', 'man7', 'man8']
+SYSTEM_DOCS = '/usr/share/doc'
+OUTPUT = 'src-tauri/src/spellcheck/dictionaries/en.txt'
+
+# rustdoc pages of single items are mostly identifiers; only every seventh page is read
+RUSTDOC_ITEM_PAGES = ('fn.', 'struct.', 'enum.', 'trait.', 'macro.', 'type.', 'constant.', 'static.',
+                      'primitive.', 'union.', 'keyword.', 'attr.', 'derive.')
+
+WORD = re.compile(r"[A-Za-z]+(?:'[A-Za-z]+)?")
+HTML_NOISE = re.compile(r"<script.*?</script>|<style.*?</style>|<pre.*?</pre>|<code.*?</code>|<[^>]+>", re.S)
+ROFF_NOISE = re.compile(r"\\f[BIRP]|\\-|^\.[A-Za-z]+", re.M)
+LETTERS = 'abcdefghijklmnopqrstuvwxyz'
+
+occurrences = collections.Counter()
+documents = collections.Counter()
+seen_lowercase = set()
+
+
+def feed(text):
+    in_document = set()
+    for match in WORD.finditer(text):
+        word = match.group(0)
+        # camelCase and SHOUTING words are identifiers, not prose
+        if not (word.islower() or (word[0].isupper() and word[1:].islower())):
+            continue
+        lower = word.lower()
+        if word.islower():
+            seen_lowercase.add(lower)
+        occurrences[lower] += 1
+        in_document.add(lower)
+    for word in in_document:
+        documents[word] += 1
+
+
+def read(path):
+    opener = gzip.open if path.endswith('.gz') else open
+    with opener(path, 'rt', errors='ignore') as file:
+        return file.read()
+
+
+def collect():
+    read_pages = 0
+    for directory, _, files in os.walk(RUST_DOCS):
+        for name in files:
+            if not name.endswith('.html'):
+                continue
+            if name.startswith(RUSTDOC_ITEM_PAGES) and read_pages % 7 != 0:
+                continue
+            try:
+                feed(html.unescape(HTML_NOISE.sub(' ', read(os.path.join(directory, name)))))
+            except OSError:
+                continue
+            read_pages += 1
+    for section in MAN_SECTIONS:
+        directory = os.path.join('/usr/share/man', section)
+        if not os.path.isdir(directory):
+            continue
+        for name in os.listdir(directory):
+            try:
+                feed(ROFF_NOISE.sub(' ', read(os.path.join(directory, name))))
+            except OSError:
+                continue
+    for directory, _, files in os.walk(SYSTEM_DOCS):
+        for name in files:
+            if 'copyright' in name.lower():
+                continue
+            try:
+                feed(read(os.path.join(directory, name)))
+            except OSError:
+                continue
+
+
+def neighbours(word):
+    """Words one transposition, deletion, substitution, or insertion away"""
+    for i in range(len(word) - 1):
+        yield 't', word[:i] + word[i + 1] + word[i] + word[i + 2:]
+    for i in range(len(word)):
+        yield 'd', word[:i] + word[i + 1:]
+        for letter in LETTERS:
+            yield 's', word[:i] + letter + word[i + 1:]
+    for i in range(len(word) + 1):
+        for letter in LETTERS:
+            yield 'i', word[:i] + letter + word[i:]
+
+
+def is_likely_typo(word, known):
+    """A rare word next to a far more common one is usually a misspelling of it"""
+    if documents[word] >= 100:
+        return False
+    for kind, other in neighbours(word):
+        if other == word or other not in known:
+            continue
+        # Inflections: 'producers' next to 'producer', 'curved' next to 'curve'
+        if kind == 'd' and other == word[:-1]:
+            continue
+        if kind == 'd' and word.endswith(("'s", 'es', 'ed')) and other.startswith(word[:-2]):
+            continue
+        ratio = occurrences[other] / occurrences[word]
+        if ((kind == 't' and ratio >= 20) or (kind in 'di' and len(word) >= 5 and ratio >= 15)
+                or (kind == 's' and len(word) >= 5 and ratio >= 100)):
+            return True
+    return False
+
+
+def main():
+    collect()
+    words = [word for word, count in occurrences.most_common()
+             if count >= 4 and documents[word] >= 3 and word in seen_lowercase
+             and 2 <= len(word.replace("'", '')) <= 24]
+    known = set(words)
+    words = [word for word in words if not is_likely_typo(word, known)]
+    output = sys.argv[1] if len(sys.argv) > 1 else OUTPUT
+    with open(output, 'w') as file:
+        file.write('\n'.join(words) + '\n')
+    print(f'{len(words)} words written to {output}')
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,344 @@
+/**
+ * File activity tracking for CodeForge IDE
+ * Records per-workspace file modifications locally and aggregates them for the activity heatmap
+ */
+
+use crate::session::workspace_key;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data dir holding one append-only activity log per workspace
+const ACTIVITY_DIR: &str = "activity";
+
+/// Events older than this are dropped when a log is loaded
+const RETENTION_DAYS: u64 = 90;
+
+/// Repeated events for the same file within this window count once (editors save in several steps)
+const COALESCE_SECONDS: u64 = 5;
+
+/// Directories whose churn is not user activity
+const IGNORED_DIRECTORIES: &[&str] = &[".git", "node_modules", "target", "dist", "build", ".venv", "venv"];
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Error types for activity tracking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ActivityError {
+    NoDataDirectory,
+    IOError(String),
+    NotTracking(String),
+}
+
+impl std::fmt::Display for ActivityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ActivityError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            ActivityError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            ActivityError::NotTracking(workspace) => write!(f, "Activity is not tracked for {}", workspace),
+        }
+    }
+}
+
+/// What happened to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityKind {
+    Saved,
+    Modified,
+    Created,
+    Deleted,
+}
+
+/// One recorded event; `path` is relative to the workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub path: String,
+    pub timestamp: u64,
+    pub kind: ActivityKind,
+}
+
+/// Edit count of a file within the queried period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileActivityCount {
+    pub path: String,
+    pub edits: usize,
+    pub last_edited: u64,
+}
+
+/// Number of events on a calendar day (`YYYY-MM-DD`, in the caller's time zone)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyActivity {
+    pub date: String,
+    pub count: usize,
+}
+
+/// Aggregated activity of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitySummary {
+    pub workspace: String,
+    pub since: u64,
+    pub total_events: usize,
+    pub most_edited: Vec<FileActivityCount>,
+    /// Events per hour of the day, index 0 being midnight
+    pub by_hour: Vec<usize>,
+    pub by_day: Vec<DailyActivity>,
+}
+
+struct ActivityLog {
+    file: PathBuf,
+    events: Vec<ActivityEvent>,
+    last_recorded: HashMap<String, u64>,
+}
+
+impl ActivityLog {
+    fn load(file: PathBuf) -> Self {
+        let cutoff = now_seconds().saturating_sub(RETENTION_DAYS * SECONDS_PER_DAY);
+        let content = fs::read_to_string(&file).unwrap_or_default();
+        let total_lines = content.lines().count();
+        let events: Vec<ActivityEvent> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<ActivityEvent>(line).ok())
+            .filter(|event| event.timestamp >= cutoff)
+            .collect();
+
+        // Compact the log once expired or corrupt lines make up a noticeable part of it
+        if total_lines > events.len() + events.len() / 4 {
+            let compacted: String = events
+                .iter()
+                .filter_map(|event| serde_json::to_string(event).ok())
+                .map(|line| line + "\n")
+                .collect();
+            let _ = fs::write(&file, compacted);
+        }
+
+        Self {
+            file,
+            events,
+            last_recorded: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, path: String, kind: ActivityKind) -> Result<(), ActivityError> {
+        let timestamp = now_seconds();
+        if kind == ActivityKind::Modified
+            && self
+                .last_recorded
+                .get(&path)
+                .is_some_and(|last| timestamp.saturating_sub(*last) < COALESCE_SECONDS)
+        {
+            return Ok(());
+        }
+        self.last_recorded.insert(path.clone(), timestamp);
+
+        let event = ActivityEvent { path, timestamp, kind };
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent).map_err(|e| ActivityError::IOError(e.to_string()))?;
+        }
+        let line = serde_json::to_string(&event).map_err(|e| ActivityError::IOError(e.to_string()))?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| ActivityError::IOError(e.to_string()))?;
+        self.events.push(event);
+        Ok(())
+    }
+}
+
+pub struct ActivityService {
+    logs: Arc<Mutex<HashMap<String, Arc<Mutex<ActivityLog>>>>>,
+    watchers: Arc<Mutex<HashMap<String, RecommendedWatcher>>>,
+}
+
+fn now_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn relative_to(workspace: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(workspace).ok()?;
+    let ignored = relative.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| IGNORED_DIRECTORIES.contains(&name))
+    });
+    if ignored || relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Civil date from days since the Unix epoch (Howard Hinnant's algorithm)
+pub(crate) fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+impl ActivityService {
+    pub fn new() -> Self {
+        Self {
+            logs: Arc::new(Mutex::new(HashMap::new())),
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn log(&self, app: &AppHandle, workspace: &str) -> Result<Arc<Mutex<ActivityLog>>, ActivityError> {
+        let mut logs = self.logs.lock().unwrap();
+        if let Some(log) = logs.get(workspace) {
+            return Ok(log.clone());
+        }
+        let data_dir = app.path().app_data_dir().map_err(|_| ActivityError::NoDataDirectory)?;
+        let file = data_dir.join(ACTIVITY_DIR).join(format!("{}.jsonl", workspace_key(workspace)));
+        let log = Arc::new(Mutex::new(ActivityLog::load(file)));
+        logs.insert(workspace.to_string(), log.clone());
+        Ok(log)
+    }
+
+    /// Record an explicit event, such as a save from the editor
+    pub fn record(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        path: &str,
+        kind: ActivityKind,
+    ) -> Result<(), ActivityError> {
+        let Some(relative) = relative_to(Path::new(workspace), Path::new(path)) else {
+            return Ok(());
+        };
+        self.log(app, workspace)?.lock().unwrap().record(relative, kind)
+    }
+
+    /// Watch a workspace and record file changes made by any program
+    pub fn start_tracking(&self, app: &AppHandle, workspace: &str) -> Result<(), ActivityError> {
+        if self.watchers.lock().unwrap().contains_key(workspace) {
+            return Ok(());
+        }
+        let log = self.log(app, workspace)?;
+        let root = PathBuf::from(workspace);
+
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            let kind = match event.kind {
+                EventKind::Create(_) => ActivityKind::Created,
+                EventKind::Modify(_) => ActivityKind::Modified,
+                EventKind::Remove(_) => ActivityKind::Deleted,
+                _ => return,
+            };
+            let mut log = log.lock().unwrap();
+            for path in &event.paths {
+                if path.is_dir() {
+                    continue;
+                }
+                if let Some(relative) = relative_to(&root, path) {
+                    let _ = log.record(relative, kind);
+                }
+            }
+        })
+        .map_err(|e| ActivityError::IOError(e.to_string()))?;
+        watcher
+            .watch(Path::new(workspace), RecursiveMode::Recursive)
+            .map_err(|e| ActivityError::IOError(e.to_string()))?;
+
+        self.watchers.lock().unwrap().insert(workspace.to_string(), watcher);
+        Ok(())
+    }
+
+    /// Stop watching a workspace; recorded activity is kept
+    pub fn stop_tracking(&self, workspace: &str) -> Result<(), ActivityError> {
+        self.watchers
+            .lock()
+            .unwrap()
+            .remove(workspace)
+            .map(|_| ())
+            .ok_or_else(|| ActivityError::NotTracking(workspace.to_string()))
+    }
+
+    /// Workspaces being tracked, one watcher each
+    pub fn watcher_count(&self) -> usize {
+        self.watchers.lock().unwrap().len()
+    }
+
+    /// Aggregate the last `days` days of activity; `utc_offset_minutes` places events in the user's day and hour
+    pub fn summary(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        days: u64,
+        utc_offset_minutes: i64,
+        limit: usize,
+    ) -> Result<ActivitySummary, ActivityError> {
+        let since = now_seconds().saturating_sub(days * SECONDS_PER_DAY);
+        let log = self.log(app, workspace)?;
+        let log = log.lock().unwrap();
+
+        let mut per_file: HashMap<&str, FileActivityCount> = HashMap::new();
+        let mut by_hour = vec![0; 24];
+        let mut per_day: HashMap<i64, usize> = HashMap::new();
+        let mut total_events = 0;
+
+        for event in log.events.iter().filter(|event| event.timestamp >= since) {
+            total_events += 1;
+            let local = event.timestamp as i64 + utc_offset_minutes * 60;
+            by_hour[(local.rem_euclid(SECONDS_PER_DAY as i64) / 3600) as usize] += 1;
+            *per_day.entry(local.div_euclid(SECONDS_PER_DAY as i64)).or_default() += 1;
+
+            if event.kind != ActivityKind::Deleted {
+                let entry = per_file.entry(&event.path).or_insert_with(|| FileActivityCount {
+                    path: event.path.clone(),
+                    edits: 0,
+                    last_edited: 0,
+                });
+                entry.edits += 1;
+                entry.last_edited = entry.last_edited.max(event.timestamp);
+            }
+        }
+
+        let mut most_edited: Vec<FileActivityCount> = per_file.into_values().collect();
+        most_edited.sort_by(|a, b| b.edits.cmp(&a.edits).then(b.last_edited.cmp(&a.last_edited)));
+        most_edited.truncate(limit);
+
+        let mut by_day: Vec<(i64, usize)> = per_day.into_iter().collect();
+        by_day.sort();
+
+        Ok(ActivitySummary {
+            workspace: workspace.to_string(),
+            since,
+            total_events,
+            most_edited,
+            by_hour,
+            by_day: by_day
+                .into_iter()
+                .map(|(day, count)| DailyActivity {
+                    date: civil_date(day),
+                    count,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl Default for ActivityService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,19 @@
+/**
+ * Atomic file writes for CodeForge IDE
+ */
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write `bytes` to a temporary file next to `path`, then rename it over `path`, so a crash mid-write
+/// leaves either the previous content or the new one, never a truncated file
+pub(crate) fn write_atomic(path: &Path, bytes: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, bytes)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
@@ -0,0 +1,236 @@
+/**
+ * Auto-save daemon for CodeForge IDE
+ * Flushes dirty editor buffers to disk after the configured delay or when the window loses focus
+ *
+ * Buffers are saved like a manual save without the save pipeline's transforms: a file that changed on disk
+ * since the editor read it, or that another window has locked, is reported in the event instead of
+ * overwritten.
+ */
+
+use crate::clock::now_millis;
+use crate::file_history::{FileHistoryService, VersionSource};
+use crate::file_locks::{FileLockError, FileLockService};
+use crate::file_system::FileSystemService;
+use crate::settings::SettingsService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted after every auto-save attempt
+pub const AUTO_SAVE_EVENT: &str = "autosave://saved";
+
+/// How long the daemon sleeps when nothing is pending
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+
+/// Outcome of flushing one buffer; the frontend clears its dirty flag only if `version` is still current
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoSaveEvent {
+    pub path: String,
+    pub version: u64,
+    pub saved_at: u64,
+    pub error: Option<String>,
+}
+
+/// A buffer waiting to be written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingBuffer {
+    pub path: String,
+    pub version: u64,
+    /// Milliseconds until the buffer is flushed
+    pub due_in_ms: u64,
+}
+
+struct DirtyBuffer {
+    content: String,
+    version: u64,
+    updated_at: Instant,
+    /// Label of the window editing the buffer, which may write files it has locked
+    owner: String,
+}
+
+enum DaemonMessage {
+    Wake,
+    FlushAll,
+}
+
+pub struct AutoSaveService {
+    buffers: Arc<Mutex<HashMap<String, DirtyBuffer>>>,
+    daemon: Mutex<Option<Sender<DaemonMessage>>>,
+}
+
+/// Current auto-save settings; `None` when auto-save is turned off
+fn auto_save_delay(app: &AppHandle) -> Option<Duration> {
+    let preferences = app.state::<SettingsService>().get_preferences(app).ok()?;
+    preferences
+        .auto_save
+        .then(|| Duration::from_millis(preferences.auto_save_delay as u64))
+}
+
+impl AutoSaveService {
+    pub fn new() -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+            daemon: Mutex::new(None),
+        }
+    }
+
+    /// Send a message to the daemon thread, starting it on first use
+    fn notify_daemon(&self, app: &AppHandle, message: DaemonMessage) {
+        let mut daemon = self.daemon.lock().unwrap();
+        if let Some(sender) = daemon.as_ref() {
+            let _ = sender.send(message);
+            return;
+        }
+
+        let (sender, messages) = mpsc::channel();
+        let buffers = self.buffers.clone();
+        let app = app.clone();
+        thread::spawn(move || loop {
+            let delay = auto_save_delay(&app);
+            // Sleep until the oldest quiet buffer becomes due
+            let wait = match delay {
+                Some(delay) => buffers
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .map(|buffer| (buffer.updated_at + delay).saturating_duration_since(Instant::now()))
+                    .min()
+                    .unwrap_or(IDLE_WAIT),
+                None => IDLE_WAIT,
+            };
+
+            match messages.recv_timeout(wait) {
+                Ok(DaemonMessage::Wake) => continue,
+                Ok(DaemonMessage::FlushAll) => flush(&app, &buffers, None),
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(delay) = delay {
+                        flush(&app, &buffers, Some(delay));
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        });
+        let _ = sender.send(message);
+        *daemon = Some(sender);
+    }
+
+    /// Register (or update) the unsaved content of a buffer edited in window `owner`
+    ///
+    /// Returns `false` when auto-save is disabled, in which case nothing is scheduled.
+    pub fn register_dirty_buffer(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        content: String,
+        version: u64,
+        owner: &str,
+    ) -> bool {
+        if auto_save_delay(app).is_none() {
+            return false;
+        }
+        self.buffers.lock().unwrap().insert(
+            path.to_string(),
+            DirtyBuffer {
+                content,
+                version,
+                updated_at: Instant::now(),
+                owner: owner.to_string(),
+            },
+        );
+        self.notify_daemon(app, DaemonMessage::Wake);
+        true
+    }
+
+    /// Forget a buffer that was saved manually, reverted, or closed without saving
+    pub fn discard_dirty_buffer(&self, path: &str) -> bool {
+        self.buffers.lock().unwrap().remove(path).is_some()
+    }
+
+    /// Flush every pending buffer now, e.g. when the window loses focus
+    pub fn flush_all(&self, app: &AppHandle) {
+        if !self.buffers.lock().unwrap().is_empty() {
+            self.notify_daemon(app, DaemonMessage::FlushAll);
+        }
+    }
+
+    /// Buffers waiting to be flushed
+    pub fn pending_buffers(&self, app: &AppHandle) -> Vec<PendingBuffer> {
+        let delay = auto_save_delay(app).unwrap_or_default();
+        let mut pending: Vec<PendingBuffer> = self
+            .buffers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, buffer)| PendingBuffer {
+                path: path.clone(),
+                version: buffer.version,
+                due_in_ms: (buffer.updated_at + delay).saturating_duration_since(Instant::now()).as_millis() as u64,
+            })
+            .collect();
+        pending.sort_by(|a, b| a.path.cmp(&b.path));
+        pending
+    }
+}
+
+/// Write buffers that have been quiet for `delay` (all of them when `None`) and emit the outcome
+fn flush(app: &AppHandle, buffers: &Arc<Mutex<HashMap<String, DirtyBuffer>>>, delay: Option<Duration>) {
+    let due: Vec<(String, String, u64, String)> = buffers
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, buffer)| delay.is_none_or(|delay| buffer.updated_at.elapsed() >= delay))
+        .map(|(path, buffer)| (path.clone(), buffer.content.clone(), buffer.version, buffer.owner.clone()))
+        .collect();
+
+    for (path, content, version, owner) in due {
+        // Write outside the lock so edits arriving meanwhile are not blocked
+        let error = save(app, &path, &content, &owner).err();
+        if let Some(error) = &error {
+            tracing::warn!(path = %path, error = %error, "auto-save failed");
+        }
+        if error.is_none() {
+            let history = app.state::<FileHistoryService>();
+            if let Err(e) = history.record_version(app, &path, VersionSource::AutoSave) {
+                tracing::warn!(path = %path, error = %e, "recording auto-saved version failed");
+            }
+        }
+
+        {
+            let mut buffers = buffers.lock().unwrap();
+            // A newer version registered during the write stays pending
+            if buffers.get(&path).is_some_and(|buffer| buffer.version == version) {
+                buffers.remove(&path);
+            }
+        }
+
+        let _ = app.emit(
+            AUTO_SAVE_EVENT,
+            AutoSaveEvent {
+                path,
+                version,
+                saved_at: now_millis(),
+                error,
+            },
+        );
+    }
+}
+
+/// Save a buffer unless another window locked the file or it changed on disk since the editor read it
+fn save(app: &AppHandle, path: &str, content: &str, owner: &str) -> Result<(), String> {
+    let fs = app.state::<FileSystemService>();
+    let resolved = fs.authorize(path).map_err(|e| e.to_string())?;
+    if let Some(lock) = app.state::<FileLockService>().query(&resolved).filter(|lock| lock.owner != owner) {
+        return Err(FileLockError::Locked(lock).to_string());
+    }
+    fs.save_file(path, content, false).map(|_| ()).map_err(|e| e.to_string())
+}
+
+impl Default for AutoSaveService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,243 @@
+/**
+ * Hot-exit backups for CodeForge IDE
+ * Periodically snapshots dirty editor buffers to the app data dir so they can be recovered after a crash
+ */
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+use crate::session::workspace_key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data dir holding one snapshot per dirty buffer
+const BACKUPS_DIR: &str = "backups";
+
+/// How often changed buffers are written out
+const BACKUP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Error types for backup operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BackupError {
+    NoDataDirectory,
+    IOError(String),
+    NotFound(String),
+}
+
+impl std::fmt::Display for BackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BackupError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            BackupError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            BackupError::NotFound(id) => write!(f, "No backup for buffer {}", id),
+        }
+    }
+}
+
+/// Snapshot of an unsaved buffer as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BufferBackup {
+    /// Editor buffer id; the file path for saved files, an `untitled:` id otherwise
+    pub buffer_id: String,
+    pub path: Option<String>,
+    pub workspace: Option<String>,
+    pub content: String,
+    pub language: Option<String>,
+    /// Unix time in milliseconds
+    pub backed_up_at: u64,
+    /// Modification time (ms) of the file on disk when the buffer was last snapshotted
+    pub disk_modified_at: Option<u64>,
+}
+
+/// A buffer that can be recovered, without its content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoverableBuffer {
+    pub buffer_id: String,
+    pub path: Option<String>,
+    pub workspace: Option<String>,
+    pub language: Option<String>,
+    pub backed_up_at: u64,
+    pub size: usize,
+    /// The file changed on disk after the snapshot, so restoring may overwrite newer work
+    pub disk_changed: bool,
+}
+
+struct PendingBackup {
+    backup: BufferBackup,
+    written: bool,
+}
+
+pub struct BackupService {
+    buffers: Arc<Mutex<HashMap<String, PendingBackup>>>,
+    started: Mutex<bool>,
+}
+
+fn modified_millis(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
+fn backups_dir(app: &AppHandle) -> Result<PathBuf, BackupError> {
+    let data_dir = app.path().app_data_dir().map_err(|_| BackupError::NoDataDirectory)?;
+    Ok(data_dir.join(BACKUPS_DIR))
+}
+
+fn backup_file(dir: &Path, buffer_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", workspace_key(buffer_id)))
+}
+
+fn read_backup(path: &Path) -> Option<BufferBackup> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Write every snapshot that changed since the last pass
+fn write_pending(app: &AppHandle, buffers: &Mutex<HashMap<String, PendingBackup>>) -> Result<(), BackupError> {
+    let pending: Vec<BufferBackup> = buffers
+        .lock()
+        .unwrap()
+        .values_mut()
+        .filter(|pending| !pending.written)
+        .map(|pending| {
+            pending.written = true;
+            pending.backup.clone()
+        })
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let dir = backups_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| BackupError::IOError(e.to_string()))?;
+    for backup in pending {
+        let path = backup_file(&dir, &backup.buffer_id);
+        let content = serde_json::to_string(&backup).map_err(|e| BackupError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(|e| BackupError::IOError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+impl BackupService {
+    pub fn new() -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+            started: Mutex::new(false),
+        }
+    }
+
+    /// Start the snapshot timer on first use
+    fn ensure_timer(&self, app: &AppHandle) {
+        let mut started = self.started.lock().unwrap();
+        if *started {
+            return;
+        }
+        *started = true;
+
+        let buffers = self.buffers.clone();
+        let app = app.clone();
+        thread::spawn(move || loop {
+            thread::sleep(BACKUP_INTERVAL);
+            if let Err(e) = write_pending(&app, &buffers) {
+                tracing::warn!(error = %e, "writing hot exit backups failed");
+            }
+        });
+    }
+
+    /// Record the latest content of a dirty buffer; it is written on the next snapshot pass
+    pub fn update_backup(
+        &self,
+        app: &AppHandle,
+        buffer_id: &str,
+        path: Option<String>,
+        workspace: Option<String>,
+        content: String,
+        language: Option<String>,
+    ) {
+        let disk_modified_at = path.as_deref().and_then(|path| modified_millis(Path::new(path)));
+        self.buffers.lock().unwrap().insert(
+            buffer_id.to_string(),
+            PendingBackup {
+                backup: BufferBackup {
+                    buffer_id: buffer_id.to_string(),
+                    path,
+                    workspace,
+                    content,
+                    language,
+                    backed_up_at: now_millis(),
+                    disk_modified_at,
+                },
+                written: false,
+            },
+        );
+        self.ensure_timer(app);
+    }
+
+    /// Write all pending snapshots now, e.g. right before the app exits
+    pub fn flush(&self, app: &AppHandle) -> Result<(), BackupError> {
+        write_pending(app, &self.buffers)
+    }
+
+    /// Drop the backup of a buffer that was saved, reverted, or closed without saving
+    pub fn discard_backup(&self, app: &AppHandle, buffer_id: &str) -> Result<(), BackupError> {
+        self.buffers.lock().unwrap().remove(buffer_id);
+        match fs::remove_file(backup_file(&backups_dir(app)?, buffer_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(BackupError::IOError(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Backups left on disk by a previous run, optionally limited to one workspace
+    pub fn recoverable_buffers(
+        &self,
+        app: &AppHandle,
+        workspace: Option<&str>,
+    ) -> Result<Vec<RecoverableBuffer>, BackupError> {
+        let entries = match fs::read_dir(backups_dir(app)?) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(BackupError::IOError(e.to_string())),
+        };
+
+        let live = self.buffers.lock().unwrap();
+        let mut recoverable: Vec<RecoverableBuffer> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "json"))
+            .filter_map(|entry| read_backup(&entry.path()))
+            // Buffers open in this run are not crash leftovers
+            .filter(|backup| !live.contains_key(&backup.buffer_id))
+            .filter(|backup| workspace.is_none() || backup.workspace.as_deref() == workspace)
+            .map(|backup| RecoverableBuffer {
+                disk_changed: backup.path.as_deref().is_some_and(|path| {
+                    modified_millis(Path::new(path)) != backup.disk_modified_at
+                }),
+                size: backup.content.len(),
+                buffer_id: backup.buffer_id,
+                path: backup.path,
+                workspace: backup.workspace,
+                language: backup.language,
+                backed_up_at: backup.backed_up_at,
+            })
+            .collect();
+        recoverable.sort_by_key(|buffer| std::cmp::Reverse(buffer.backed_up_at));
+        Ok(recoverable)
+    }
+
+    /// Full snapshot of a recoverable buffer; the backup stays until the buffer is saved or discarded
+    pub fn recover_buffer(&self, app: &AppHandle, buffer_id: &str) -> Result<BufferBackup, BackupError> {
+        read_backup(&backup_file(&backups_dir(app)?, buffer_id))
+            .ok_or_else(|| BackupError::NotFound(buffer_id.to_string()))
+    }
+}
+
+impl Default for BackupService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
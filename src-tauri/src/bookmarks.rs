@@ -0,0 +1,395 @@
+/**
+ * Bookmark store for CodeForge IDE
+ * Per-workspace bookmarks and notes anchored to a file line, stored in the app data dir and re-anchored by
+ * their content when lines are inserted or removed above them
+ */
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+use crate::session::workspace_key;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying the bookmarks of a file after they were added, edited, removed, or moved
+pub const BOOKMARKS_CHANGED_EVENT: &str = "bookmarks://changed";
+
+/// Directory under the app data dir holding one bookmark file per workspace
+const BOOKMARKS_DIR: &str = "bookmarks";
+
+/// How far from its old line a bookmark's line is searched for after an edit
+const REMAP_SEARCH_LINES: usize = 500;
+
+/// Error types for bookmark operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BookmarkError {
+    NotFound(String),
+    InvalidBookmark(String),
+    IOError(String),
+}
+
+impl std::fmt::Display for BookmarkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BookmarkError::NotFound(id) => write!(f, "Bookmark not found: {}", id),
+            BookmarkError::InvalidBookmark(msg) => write!(f, "Invalid bookmark: {}", msg),
+            BookmarkError::IOError(msg) => write!(f, "IO Error: {}", msg),
+        }
+    }
+}
+
+/// A persisted bookmark; `line` is one-based
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Free-form annotation shown in the Bookmarks panel
+    #[serde(default)]
+    pub note: Option<String>,
+    pub created_at: u64,
+    /// Trimmed text of the line, used to follow the bookmark when lines shift
+    #[serde(default)]
+    pub line_text: String,
+    /// Trimmed text of the line above, which tells apart lines with the same text such as `}`
+    #[serde(default)]
+    pub previous_text: String,
+    /// The line's text was not found after the file changed, so the bookmark may point at the wrong line
+    #[serde(default)]
+    pub detached: bool,
+}
+
+/// New bookmark, or new label and note for the bookmark already on that line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkSpec {
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarksChanged {
+    pub workspace: String,
+    pub path: String,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+struct WorkspaceBookmarks {
+    bookmarks: Vec<Bookmark>,
+    watcher: Option<RecommendedWatcher>,
+}
+
+pub struct BookmarkStore {
+    workspaces: Arc<Mutex<HashMap<String, WorkspaceBookmarks>>>,
+    next_id: AtomicU64,
+}
+
+fn store_error(e: impl std::fmt::Display) -> BookmarkError {
+    BookmarkError::IOError(e.to_string())
+}
+
+fn bookmarks_file(app: &AppHandle, workspace: &str) -> Result<PathBuf, BookmarkError> {
+    let data_dir = app.path().app_data_dir().map_err(store_error)?;
+    Ok(data_dir.join(BOOKMARKS_DIR).join(format!("{}.json", workspace_key(workspace))))
+}
+
+/// Trimmed text of a one-based line, empty past the end of the file
+fn line_text(lines: &[&str], line: usize) -> String {
+    line.checked_sub(1)
+        .and_then(|index| lines.get(index))
+        .map(|text| text.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Point a bookmark at `line` of `lines`, recording the text it is anchored to
+fn anchor(bookmark: &mut Bookmark, lines: &[&str], line: usize) {
+    bookmark.line = line;
+    bookmark.line_text = line_text(lines, line);
+    bookmark.previous_text = line_text(lines, line - 1);
+}
+
+/// New line of a bookmark and whether its text was found. Lines with the bookmark's text are candidates;
+/// one whose line above also matches wins over a nearer one that doesn't, since short lines such as `}` or
+/// `return;` repeat throughout a file.
+fn remap_line(lines: &[&str], bookmark: &Bookmark) -> (usize, bool) {
+    let last_line = lines.len().max(1);
+    if bookmark.line_text.is_empty() {
+        return (bookmark.line.min(last_line), true);
+    }
+
+    let matches = |line: usize| {
+        line >= 1 && lines.get(line - 1).is_some_and(|candidate| candidate.trim() == bookmark.line_text)
+    };
+    let context_matches = |line: usize| line_text(lines, line - 1) == bookmark.previous_text;
+    if matches(bookmark.line) && context_matches(bookmark.line) {
+        return (bookmark.line, true);
+    }
+
+    let mut nearest = None;
+    let nearby = std::iter::once(bookmark.line).chain((1..=REMAP_SEARCH_LINES).flat_map(|distance| {
+        let above = bookmark.line.checked_sub(distance).filter(|line| *line >= 1);
+        std::iter::once(bookmark.line + distance).chain(above)
+    }));
+    for line in nearby.filter(|line| matches(*line)) {
+        if context_matches(line) {
+            return (line, true);
+        }
+        nearest.get_or_insert(line);
+    }
+    match nearest {
+        Some(line) => (line, true),
+        None => (bookmark.line.min(last_line), false),
+    }
+}
+
+impl BookmarkStore {
+    pub fn new() -> Self {
+        Self {
+            workspaces: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Load a workspace's bookmarks on first use and start following edits to their files
+    fn ensure_loaded<'a>(
+        &self,
+        app: &AppHandle,
+        workspaces: &'a mut HashMap<String, WorkspaceBookmarks>,
+        workspace: &str,
+    ) -> Result<&'a mut Vec<Bookmark>, BookmarkError> {
+        if !workspaces.contains_key(workspace) {
+            let bookmarks = fs::read_to_string(bookmarks_file(app, workspace)?)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            workspaces.insert(
+                workspace.to_string(),
+                WorkspaceBookmarks {
+                    bookmarks,
+                    watcher: watch_workspace(app, workspace),
+                },
+            );
+        }
+        Ok(&mut workspaces.get_mut(workspace).unwrap().bookmarks)
+    }
+
+    /// Apply `change` to a workspace's bookmarks, then persist and publish the files it touched
+    fn modify<T>(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        change: impl FnOnce(&mut Vec<Bookmark>) -> Result<(T, BTreeSet<String>), BookmarkError>,
+    ) -> Result<T, BookmarkError> {
+        let (result, updates) = {
+            let mut workspaces = self.workspaces.lock().unwrap();
+            let bookmarks = self.ensure_loaded(app, &mut workspaces, workspace)?;
+            let (result, changed_paths) = change(bookmarks)?;
+            if changed_paths.is_empty() {
+                return Ok(result);
+            }
+
+            let path = bookmarks_file(app, workspace)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(store_error)?;
+            }
+            let content = serde_json::to_string_pretty(bookmarks).map_err(store_error)?;
+            write_atomic(&path, content).map_err(store_error)?;
+
+            let updates: Vec<BookmarksChanged> = changed_paths
+                .into_iter()
+                .map(|path| BookmarksChanged {
+                    workspace: workspace.to_string(),
+                    bookmarks: bookmarks.iter().filter(|b| b.path == path).cloned().collect(),
+                    path,
+                })
+                .collect();
+            (result, updates)
+        };
+
+        for update in updates {
+            let _ = app.emit(BOOKMARKS_CHANGED_EVENT, update);
+        }
+        Ok(result)
+    }
+
+    /// Bookmarks of a workspace, optionally only those in one file, ordered by file and line
+    pub fn list(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        path: Option<&str>,
+    ) -> Result<Vec<Bookmark>, BookmarkError> {
+        let mut workspaces = self.workspaces.lock().unwrap();
+        let bookmarks = self.ensure_loaded(app, &mut workspaces, workspace)?;
+        let mut bookmarks: Vec<Bookmark> = bookmarks
+            .iter()
+            .filter(|bookmark| path.is_none_or(|path| bookmark.path == path))
+            .cloned()
+            .collect();
+        bookmarks.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        Ok(bookmarks)
+    }
+
+    /// Add a bookmark, or replace the label and note of the one already on that line
+    pub fn add(&self, app: &AppHandle, workspace: &str, spec: BookmarkSpec) -> Result<Bookmark, BookmarkError> {
+        if spec.line == 0 {
+            return Err(BookmarkError::InvalidBookmark("lines are one-based".to_string()));
+        }
+        let content = fs::read_to_string(&spec.path).unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+        let created_at = now_millis();
+        let id = format!("bm-{}-{}", created_at, self.next_id.fetch_add(1, Ordering::SeqCst));
+
+        self.modify(app, workspace, |bookmarks| {
+            let bookmark = match bookmarks.iter_mut().find(|b| b.path == spec.path && b.line == spec.line) {
+                Some(existing) => {
+                    existing.label = spec.label;
+                    existing.note = spec.note;
+                    existing.detached = false;
+                    anchor(existing, &lines, spec.line);
+                    existing.clone()
+                }
+                None => {
+                    let mut bookmark = Bookmark {
+                        id,
+                        path: spec.path,
+                        line: spec.line,
+                        label: spec.label,
+                        note: spec.note,
+                        created_at,
+                        line_text: String::new(),
+                        previous_text: String::new(),
+                        detached: false,
+                    };
+                    anchor(&mut bookmark, &lines, spec.line);
+                    bookmarks.push(bookmark.clone());
+                    bookmark
+                }
+            };
+            Ok((bookmark.clone(), BTreeSet::from([bookmark.path])))
+        })
+    }
+
+    /// Change the label or note of a bookmark; fields left `None` are kept, empty strings clear them
+    pub fn update(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        id: &str,
+        label: Option<String>,
+        note: Option<String>,
+    ) -> Result<Bookmark, BookmarkError> {
+        let cleared = |value: String| Some(value).filter(|value| !value.is_empty());
+        self.modify(app, workspace, |bookmarks| {
+            let bookmark = bookmarks
+                .iter_mut()
+                .find(|bookmark| bookmark.id == id)
+                .ok_or_else(|| BookmarkError::NotFound(id.to_string()))?;
+            if let Some(label) = label {
+                bookmark.label = cleared(label);
+            }
+            if let Some(note) = note {
+                bookmark.note = cleared(note);
+            }
+            Ok((bookmark.clone(), BTreeSet::from([bookmark.path.clone()])))
+        })
+    }
+
+    pub fn remove(&self, app: &AppHandle, workspace: &str, id: &str) -> Result<(), BookmarkError> {
+        self.modify(app, workspace, |bookmarks| {
+            let index = bookmarks
+                .iter()
+                .position(|bookmark| bookmark.id == id)
+                .ok_or_else(|| BookmarkError::NotFound(id.to_string()))?;
+            let removed = bookmarks.remove(index);
+            Ok(((), BTreeSet::from([removed.path])))
+        })
+    }
+
+    /// Remove every bookmark of a workspace, or only those in one file, returning how many were removed
+    pub fn clear(&self, app: &AppHandle, workspace: &str, path: Option<&str>) -> Result<usize, BookmarkError> {
+        self.modify(app, workspace, |bookmarks| {
+            let (removed, kept): (Vec<Bookmark>, Vec<Bookmark>) =
+                bookmarks.drain(..).partition(|bookmark| path.is_none_or(|path| bookmark.path == path));
+            *bookmarks = kept;
+            let count = removed.len();
+            Ok((count, removed.into_iter().map(|bookmark| bookmark.path).collect()))
+        })
+    }
+
+    /// Re-anchor the bookmarks of a file after it changed on disk
+    pub fn remap_file(&self, app: &AppHandle, workspace: &str, path: &str) -> Result<(), BookmarkError> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(());
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        self.modify(app, workspace, |bookmarks| {
+            let mut changed = false;
+            for bookmark in bookmarks.iter_mut().filter(|bookmark| bookmark.path == path) {
+                let (line, found) = remap_line(&lines, bookmark);
+                changed |= line != bookmark.line || found == bookmark.detached;
+                bookmark.detached = !found;
+                // A detached bookmark keeps its old anchor, so it reattaches if the text comes back
+                if found {
+                    anchor(bookmark, &lines, line);
+                } else {
+                    bookmark.line = line;
+                }
+            }
+            let changed = if changed { BTreeSet::from([path.to_string()]) } else { BTreeSet::new() };
+            Ok(((), changed))
+        })
+    }
+
+    /// Workspaces whose bookmarked files are being followed
+    pub fn watcher_count(&self) -> usize {
+        self.workspaces.lock().unwrap().values().filter(|workspace| workspace.watcher.is_some()).count()
+    }
+}
+
+/// Remap bookmarks whenever a file that has some is written
+fn watch_workspace(app: &AppHandle, workspace: &str) -> Option<RecommendedWatcher> {
+    let app = app.clone();
+    let watched_workspace = workspace.to_string();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+            return;
+        }
+        let store = app.state::<BookmarkStore>();
+        let tracked: BTreeSet<String> = store
+            .list(&app, &watched_workspace, None)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bookmark| bookmark.path)
+            .collect();
+        for path in event.paths.iter().map(|path| path.to_string_lossy().to_string()) {
+            if tracked.contains(&path) {
+                if let Err(e) = store.remap_file(&app, &watched_workspace, &path) {
+                    tracing::warn!(path = %path, error = %e, "remapping bookmarks failed");
+                }
+            }
+        }
+    })
+    .ok()?;
+    watcher.watch(Path::new(workspace), RecursiveMode::Recursive).ok()?;
+    Some(watcher)
+}
+
+impl Default for BookmarkStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,405 @@
+/**
+ * Bulk rename for the file explorer
+ * Renames many entries from one name template, with placeholders for parts of the old name, regex capture
+ * groups, and a sequential counter. A plan is built first so the explorer can preview it; applying it renames
+ * everything or nothing.
+ *
+ * Template placeholders, with `{{` and `}}` for literal braces:
+ * - `{name}` the name without its extension, `{ext}` the extension with its dot, `{file_name}` both, and
+ *   `{parent}` the containing folder's name
+ * - `{n}` the counter, `{n:3}` padded with zeros to three digits
+ * - `{0}` the whole regex match, `{1}`... its capture groups, and named groups by name
+ * - `:lower` or `:upper` after any text placeholder changes its case, e.g. `{name:lower}`
+ */
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::file_system::FileSystemService;
+use crate::fs_undo::FsOperation;
+use crate::path_utils::{self, Platform};
+
+/// Error types for bulk renames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BulkRenameError {
+    InvalidPattern(String),
+    /// The plan has entries that can't be renamed; nothing was changed
+    Conflicts(usize),
+    RenameFailed { path: String, message: String },
+}
+
+impl std::fmt::Display for BulkRenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BulkRenameError::InvalidPattern(msg) => write!(f, "Invalid rename pattern: {}", msg),
+            BulkRenameError::Conflicts(count) => {
+                write!(f, "{} entries can't be renamed as planned; nothing was renamed", count)
+            }
+            BulkRenameError::RenameFailed { path, message } => {
+                write!(f, "Renaming {} failed, all renames were reverted: {}", path, message)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamePattern {
+    /// Template for the new name; see the module docs for placeholders
+    pub template: String,
+    /// Regex matched against each name; entries it doesn't match are left alone
+    #[serde(default)]
+    pub find: Option<String>,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// First counter value
+    #[serde(default = "default_counter_start")]
+    pub start: i64,
+    #[serde(default = "default_counter_step")]
+    pub step: i64,
+}
+
+fn default_counter_start() -> i64 {
+    1
+}
+
+fn default_counter_step() -> i64 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameStatus {
+    Rename,
+    /// The new name is the old name
+    Unchanged,
+    /// `find` didn't match the name
+    NoMatch,
+    /// The new name is empty or not allowed as a file name
+    Invalid,
+    /// Another entry gets the same name, or an existing entry not being renamed has it
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameEntry {
+    pub path: String,
+    pub new_path: String,
+    pub new_name: String,
+    pub status: RenameStatus,
+    pub message: Option<String>,
+}
+
+/// A planned or applied bulk rename
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkRenamePlan {
+    /// In the order the paths were given
+    pub entries: Vec<RenameEntry>,
+    pub renamed: usize,
+    /// Entries with status `Invalid` or `Conflict`; a plan with any is not applied
+    pub problems: usize,
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Keep,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Counter(usize),
+    Field(String, Case),
+}
+
+const FIELDS: &[&str] = &["name", "ext", "file_name", "parent"];
+
+/// Split a template into literal text and placeholders, checking every placeholder up front so a typo fails
+/// the whole plan instead of producing odd names
+fn parse_template(template: &str, find: Option<&Regex>) -> Result<Vec<Segment>, BulkRenameError> {
+    let invalid = |msg: String| BulkRenameError::InvalidPattern(msg);
+    let groups: HashSet<&str> = find.map(|find| find.capture_names().flatten().collect()).unwrap_or_default();
+    let group_count = find.map_or(0, Regex::captures_len);
+
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return Err(invalid("unmatched '}', write '}}' for a literal brace".to_string())),
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err(invalid("unclosed '{', write '{{' for a literal brace".to_string())),
+                    }
+                }
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                let (key, modifier) = match spec.split_once(':') {
+                    Some((key, modifier)) => (key.trim(), Some(modifier.trim())),
+                    None => (spec.trim(), None),
+                };
+                if key == "n" {
+                    let width = match modifier {
+                        Some(width) => width
+                            .parse()
+                            .ok()
+                            .filter(|width| *width <= 20)
+                            .ok_or_else(|| invalid(format!("'{}' is not a counter width", width)))?,
+                        None => 0,
+                    };
+                    segments.push(Segment::Counter(width));
+                    continue;
+                }
+                let known = FIELDS.contains(&key)
+                    || groups.contains(key)
+                    || key.parse::<usize>().is_ok_and(|index| index < group_count.max(1));
+                if !known {
+                    return Err(invalid(format!("unknown placeholder {{{}}}", key)));
+                }
+                let case = match modifier {
+                    None => Case::Keep,
+                    Some("lower") => Case::Lower,
+                    Some("upper") => Case::Upper,
+                    Some(other) => return Err(invalid(format!("unknown modifier '{}' in {{{}}}", other, spec))),
+                };
+                segments.push(Segment::Field(key.to_string(), case));
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+fn format_counter(value: i64, width: usize) -> String {
+    match value < 0 {
+        true => format!("-{:0width$}", value.unsigned_abs(), width = width.saturating_sub(1)),
+        false => format!("{:0width$}", value, width = width),
+    }
+}
+
+/// Why a new name can't be used, if it can't; it must be a legal name on this OS
+fn name_problem(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some("the new name is empty".to_string());
+    }
+    let check = path_utils::validate_file_name(name, &[Platform::current()]);
+    check.problems.first().map(|problem| format!("the new name {}", problem.message))
+}
+
+/// Work out the new name of every path without touching the file system, apart from checking for existing
+/// entries in the way
+pub fn plan(paths: &[String], pattern: &RenamePattern) -> Result<BulkRenamePlan, BulkRenameError> {
+    let find = pattern
+        .find
+        .as_deref()
+        .map(|find| {
+            RegexBuilder::new(find)
+                .case_insensitive(pattern.case_insensitive)
+                .build()
+                .map_err(|e| BulkRenameError::InvalidPattern(e.to_string()))
+        })
+        .transpose()?;
+    let segments = parse_template(&pattern.template, find.as_ref())?;
+
+    let mut counter = pattern.start;
+    let mut entries = Vec::with_capacity(paths.len());
+    for path in paths {
+        let file = Path::new(path);
+        let file_name = file.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let mut entry = RenameEntry {
+            path: path.clone(),
+            new_path: path.clone(),
+            new_name: file_name.clone(),
+            status: RenameStatus::Unchanged,
+            message: None,
+        };
+        let captures = match &find {
+            Some(find) => match find.captures(&file_name) {
+                Some(captures) => Some(captures),
+                None => {
+                    entry.status = RenameStatus::NoMatch;
+                    entries.push(entry);
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        // A leading dot starts a hidden name, not an extension
+        let (stem, extension) = match file_name.rfind('.') {
+            Some(dot) if dot > 0 && !file.is_dir() => (&file_name[..dot], &file_name[dot..]),
+            _ => (file_name.as_str(), ""),
+        };
+        let parent = file.parent().and_then(Path::file_name).map(|name| name.to_string_lossy().to_string());
+        let mut new_name = String::new();
+        for segment in &segments {
+            let value = match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Counter(width) => format_counter(counter, *width),
+                Segment::Field(key, case) => {
+                    // Named groups take precedence over the fields they might shadow
+                    let group = captures.as_ref().and_then(|captures| captures.name(key));
+                    let value = match key.as_str() {
+                        _ if group.is_some() => group.map(|m| m.as_str()),
+                        "name" => Some(stem),
+                        "ext" => Some(extension),
+                        "file_name" => Some(file_name.as_str()),
+                        "parent" => parent.as_deref(),
+                        index => index
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| match &captures {
+                                Some(captures) => captures.get(index).map(|m| m.as_str()),
+                                None if index == 0 => Some(file_name.as_str()),
+                                None => None,
+                            }),
+                    };
+                    let value = value.unwrap_or_default();
+                    match case {
+                        Case::Keep => value.to_string(),
+                        Case::Lower => value.to_lowercase(),
+                        Case::Upper => value.to_uppercase(),
+                    }
+                }
+            };
+            new_name.push_str(&value);
+        }
+        counter = counter.saturating_add(pattern.step);
+
+        entry.new_path = file.with_file_name(&new_name).to_string_lossy().to_string();
+        entry.status = match name_problem(&new_name) {
+            Some(problem) => {
+                entry.message = Some(problem);
+                RenameStatus::Invalid
+            }
+            None if new_name == file_name => RenameStatus::Unchanged,
+            None => RenameStatus::Rename,
+        };
+        entry.new_name = new_name;
+        entries.push(entry);
+    }
+
+    mark_conflicts(&mut entries);
+    let renamed = entries.iter().filter(|entry| entry.status == RenameStatus::Rename).count();
+    let problems = entries
+        .iter()
+        .filter(|entry| matches!(entry.status, RenameStatus::Invalid | RenameStatus::Conflict))
+        .count();
+    Ok(BulkRenamePlan {
+        entries,
+        renamed,
+        problems,
+        applied: false,
+    })
+}
+
+/// Names are compared case-insensitively on platforms whose file systems usually are
+fn name_key(path: &str) -> String {
+    match cfg!(any(windows, target_os = "macos")) {
+        true => path.to_lowercase(),
+        false => path.to_string(),
+    }
+}
+
+/// Flag renames onto the same target, and onto existing entries that aren't themselves moving away
+fn mark_conflicts(entries: &mut [RenameEntry]) {
+    let moving: HashSet<String> = entries
+        .iter()
+        .filter(|entry| entry.status == RenameStatus::Rename)
+        .map(|entry| name_key(&entry.path))
+        .collect();
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.status != RenameStatus::NoMatch) {
+        *targets.entry(name_key(&entry.new_path)).or_default() += 1;
+    }
+
+    for entry in entries.iter_mut().filter(|entry| entry.status == RenameStatus::Rename) {
+        let key = name_key(&entry.new_path);
+        let message = if targets.get(&key).copied().unwrap_or(0) > 1 {
+            Some("another entry gets the same name")
+        } else if key == name_key(&entry.path) {
+            // A change of case only, on a case-insensitive file system
+            None
+        } else if !moving.contains(&key) && fs::symlink_metadata(&entry.new_path).is_ok() {
+            Some("an entry with this name already exists")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            entry.status = RenameStatus::Conflict;
+            entry.message = Some(message.to_string());
+        }
+    }
+}
+
+/// Rename every entry in `plan` marked `Rename`, returning what was done for undo. Entries first move to
+/// temporary names and then to their targets, so names can be swapped or shifted along (`2` to `3`, `1` to
+/// `2`) and a change of case works on case-insensitive file systems. On failure every step is reversed.
+pub fn apply(fs: &FileSystemService, plan: &BulkRenamePlan) -> Result<Vec<FsOperation>, BulkRenameError> {
+    if plan.problems > 0 {
+        return Err(BulkRenameError::Conflicts(plan.problems));
+    }
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let renames: Vec<(&RenameEntry, PathBuf)> = plan
+        .entries
+        .iter()
+        .filter(|entry| entry.status == RenameStatus::Rename)
+        .enumerate()
+        .map(|(index, entry)| {
+            let temporary = format!(".{}.{}.{}.renaming", entry.new_name, stamp, index);
+            (entry, Path::new(&entry.path).with_file_name(temporary))
+        })
+        .collect();
+
+    let steps: Vec<(String, String)> = renames
+        .iter()
+        .map(|(entry, temporary)| (entry.path.clone(), temporary.to_string_lossy().to_string()))
+        .chain(
+            renames
+                .iter()
+                .map(|(entry, temporary)| (temporary.to_string_lossy().to_string(), entry.new_path.clone())),
+        )
+        .collect();
+
+    let mut done: Vec<FsOperation> = Vec::with_capacity(steps.len());
+    for (from, to) in steps {
+        if let Err(e) = fs.rename(&from, &to) {
+            for (from, to) in done.iter().rev().filter_map(|operation| match operation {
+                FsOperation::Rename { from, to } => Some((from, to)),
+                _ => None,
+            }) {
+                if let Err(rollback) = fs.rename(to, from) {
+                    tracing::warn!(path = %to, error = %rollback, "reverting a bulk rename step failed");
+                }
+            }
+            return Err(BulkRenameError::RenameFailed {
+                path: from,
+                message: e.to_string(),
+            });
+        }
+        done.push(FsOperation::Rename { from, to });
+    }
+    Ok(done)
+}
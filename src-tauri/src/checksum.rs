@@ -0,0 +1,138 @@
+/**
+ * File checksums for CodeForge IDE
+ * Streams files through md5/sha1/sha256/blake3 in chunks, for verifying downloads and for comparing
+ * file contents in the duplicate finder and sync
+ */
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+/// Event carrying progress of a `hash_file` call on a large file
+pub const HASH_PROGRESS_EVENT: &str = "fs://hash-progress";
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// How often progress is reported while a file is hashed
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+/// Incremental hasher for any supported algorithm
+pub enum FileHasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl FileHasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => FileHasher::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => FileHasher::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => FileHasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => FileHasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            FileHasher::Md5(hasher) => hasher.update(data),
+            FileHasher::Sha1(hasher) => hasher.update(data),
+            FileHasher::Sha256(hasher) => hasher.update(data),
+            FileHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Lowercase hex digest
+    pub fn finalize(self) -> String {
+        let digest = match self {
+            FileHasher::Md5(hasher) => hasher.finalize().to_vec(),
+            FileHasher::Sha1(hasher) => hasher.finalize().to_vec(),
+            FileHasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            FileHasher::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+        };
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHash {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    pub hash: String,
+    pub size: u64,
+}
+
+/// Progress of hashing one file, emitted as `fs://hash-progress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashProgress {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    pub bytes_hashed: u64,
+    pub total_bytes: u64,
+}
+
+/// Hash a file in chunks, calling `on_progress` periodically while it is read
+pub fn hash_file(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    mut on_progress: impl FnMut(HashProgress),
+) -> Result<FileHash, FileSystemError> {
+    let mut file = File::open(path).map_err(map_io_error)?;
+    let metadata = file.metadata().map_err(map_io_error)?;
+    if metadata.is_dir() {
+        return Err(FileSystemError::InvalidPath);
+    }
+
+    let display_path = path.to_string_lossy().to_string();
+    let mut hasher = FileHasher::new(algorithm);
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut bytes_hashed = 0u64;
+    let mut last_progress = Instant::now();
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(map_io_error(e)),
+        };
+        hasher.update(&buffer[..read]);
+        bytes_hashed += read as u64;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            on_progress(HashProgress {
+                path: display_path.clone(),
+                algorithm,
+                bytes_hashed,
+                total_bytes: metadata.len(),
+            });
+        }
+    }
+
+    Ok(FileHash {
+        path: display_path,
+        algorithm,
+        hash: hasher.finalize(),
+        // The file may have grown or shrunk since it was opened
+        size: bytes_hashed,
+    })
+}
@@ -0,0 +1,104 @@
+/**
+ * System clipboard access for CodeForge IDE
+ * Reads and writes the OS clipboard through arboard, so copying paths and pasting images behave the same
+ * on every platform regardless of webview clipboard permissions
+ */
+
+use arboard::Clipboard;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Error types for clipboard operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClipboardError {
+    Unavailable(String),
+    EncodingError(String),
+}
+
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ClipboardError::Unavailable(msg) => write!(f, "Clipboard is unavailable: {}", msg),
+            ClipboardError::EncodingError(msg) => write!(f, "Encoding Error: {}", msg),
+        }
+    }
+}
+
+/// An image from the clipboard, encoded as PNG
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    /// Base64 PNG data
+    pub png: String,
+}
+
+fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Result<Vec<u8>, ClipboardError> {
+    let encoding_error = |e: png::EncodingError| ClipboardError::EncodingError(e.to_string());
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(encoding_error)?;
+    writer.write_image_data(rgba).map_err(encoding_error)?;
+    writer.finish().map_err(encoding_error)?;
+    Ok(png)
+}
+
+pub struct ClipboardService {
+    /// Opened on first use and kept: on X11 and Wayland the owner has to stay alive for what it wrote to
+    /// remain pasteable
+    clipboard: Arc<Mutex<Option<Clipboard>>>,
+}
+
+impl ClipboardService {
+    pub fn new() -> Self {
+        Self {
+            clipboard: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Run `operation` on the clipboard; an empty clipboard or one holding another format reads as `None`
+    fn with_clipboard<T>(
+        &self,
+        operation: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>,
+    ) -> Result<Option<T>, ClipboardError> {
+        let mut guard = self.clipboard.lock().unwrap();
+        let clipboard = match guard.as_mut() {
+            Some(clipboard) => clipboard,
+            None => guard.insert(Clipboard::new().map_err(|e| ClipboardError::Unavailable(e.to_string()))?),
+        };
+        match operation(clipboard) {
+            Ok(value) => Ok(Some(value)),
+            Err(arboard::Error::ContentNotAvailable) => Ok(None),
+            Err(e) => Err(ClipboardError::Unavailable(e.to_string())),
+        }
+    }
+
+    pub fn read_text(&self) -> Result<Option<String>, ClipboardError> {
+        self.with_clipboard(|clipboard| clipboard.get_text())
+    }
+
+    pub fn write_text(&self, text: &str) -> Result<(), ClipboardError> {
+        self.with_clipboard(|clipboard| clipboard.set_text(text)).map(|_| ())
+    }
+
+    pub fn read_image(&self) -> Result<Option<ClipboardImage>, ClipboardError> {
+        let Some(image) = self.with_clipboard(|clipboard| clipboard.get_image())? else {
+            return Ok(None);
+        };
+        let png = encode_png(image.width, image.height, &image.bytes)?;
+        Ok(Some(ClipboardImage {
+            width: image.width,
+            height: image.height,
+            png: base64::engine::general_purpose::STANDARD.encode(png),
+        }))
+    }
+}
+
+impl Default for ClipboardService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
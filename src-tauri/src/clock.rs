@@ -0,0 +1,13 @@
+/**
+ * Wall clock helpers for CodeForge IDE
+ */
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current Unix time in milliseconds; 0 if the clock is set before 1970
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
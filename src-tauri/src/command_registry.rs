@@ -0,0 +1,297 @@
+/**
+ * Command registry for CodeForge IDE
+ * Every action the command palette can run, registered with an id, title, category, and `when` clause.
+ * Built-in actions run either in the backend or, through `command://execute`, in the frontend; plugin
+ * commands come from their manifests and run in the plugin host.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::file_system::FileSystemService;
+use crate::fs_undo::FsUndoService;
+use crate::plugins::{PluginCommand, PluginError, PluginService};
+use crate::window_manager::WindowManager;
+
+/// Event asking the frontend to run one of its commands
+pub const COMMAND_EXECUTE_EVENT: &str = "command://execute";
+
+/// Built-in commands the frontend implements: id, title, category, and `when` clause
+const FRONTEND_COMMANDS: &[(&str, &str, &str, Option<&str>)] = &[
+    ("workbench.action.showCommands", "Show All Commands", "View", None),
+    ("workbench.action.quickOpen", "Go to File...", "Go", None),
+    ("workbench.action.openRecent", "Open Recent...", "File", None),
+    ("workbench.action.files.newUntitledFile", "New Untitled File", "File", None),
+    ("workbench.action.files.openFile", "Open File...", "File", None),
+    ("workbench.action.files.save", "Save", "File", Some("editorIsOpen")),
+    ("workbench.action.files.saveAll", "Save All", "File", None),
+    ("workbench.action.closeActiveEditor", "Close Editor", "View", Some("editorIsOpen")),
+    ("workbench.action.toggleSidebarVisibility", "Toggle Primary Side Bar Visibility", "View", None),
+    ("workbench.action.togglePanel", "Toggle Panel Visibility", "View", None),
+    ("workbench.action.terminal.toggleTerminal", "Toggle Terminal", "Terminal", None),
+    ("workbench.action.openSettings", "Open Settings", "Preferences", None),
+    ("workbench.action.openKeybindings", "Open Keyboard Shortcuts", "Preferences", None),
+    ("workbench.action.findInFiles", "Find in Files", "Search", None),
+    ("workbench.view.scm", "Show Source Control", "View", None),
+    ("workbench.action.splitEditor", "Split Editor", "View", Some("editorIsOpen")),
+    ("editor.action.formatDocument", "Format Document", "Editor", Some("editorTextFocus")),
+    ("editor.action.rename", "Rename Symbol", "Editor", Some("editorTextFocus")),
+    ("editor.action.revealDefinition", "Go to Definition", "Go", Some("editorTextFocus")),
+    ("editor.action.gotoLine", "Go to Line...", "Go", Some("editorFocus")),
+    ("workbench.action.debug.start", "Start Debugging", "Debug", Some("!inDebugMode")),
+    ("workbench.action.debug.stop", "Stop Debugging", "Debug", Some("inDebugMode")),
+    ("editor.debug.action.toggleBreakpoint", "Toggle Breakpoint", "Debug", Some("editorTextFocus")),
+];
+
+/// Error types for command registry operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CommandError {
+    NotFound(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CommandError::NotFound(id) => write!(f, "Command not found: {}", id),
+            CommandError::Failed(msg) => write!(f, "Command failed: {}", msg),
+        }
+    }
+}
+
+/// Where a command comes from and so where it runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CommandSource {
+    Backend,
+    Frontend,
+    Plugin { plugin_id: String },
+}
+
+/// A command as the palette lists it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandDescriptor {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Context expression the command is available in, e.g. `editorTextFocus && !inDebugMode`
+    #[serde(default)]
+    pub when: Option<String>,
+    pub source: CommandSource,
+}
+
+/// Payload of `command://execute`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandInvocation {
+    pub id: String,
+    pub args: Value,
+}
+
+pub type CommandHandler = Arc<dyn Fn(&AppHandle, Value) -> Result<Value, String> + Send + Sync>;
+
+struct RegisteredCommand {
+    descriptor: CommandDescriptor,
+    /// `None` for commands the frontend runs
+    handler: Option<CommandHandler>,
+}
+
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(value)) => *value,
+        Some(Value::Number(number)) => number.as_f64().is_some_and(|number| number != 0.0),
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn matches_value(value: Option<&Value>, expected: &str) -> bool {
+    let expected = expected.trim().trim_matches(|c| c == '\'' || c == '"');
+    match value {
+        Some(Value::String(text)) => text == expected,
+        Some(Value::Null) | None => false,
+        Some(value) => serde_json::from_str::<Value>(expected).is_ok_and(|expected| *value == expected),
+    }
+}
+
+/// Evaluate a `when` clause against context keys. Supports `key`, `!key`, `key == value`, `key != value`,
+/// `&&`, and `||` (which binds looser); there are no parentheses.
+pub fn evaluate_when(when: &str, context: &Map<String, Value>) -> bool {
+    when.split("||").any(|alternative| {
+        alternative.split("&&").all(|term| {
+            let term = term.trim();
+            if let Some((key, expected)) = term.split_once("!=") {
+                !matches_value(context.get(key.trim()), expected)
+            } else if let Some((key, expected)) = term.split_once("==") {
+                matches_value(context.get(key.trim()), expected)
+            } else if let Some(key) = term.strip_prefix('!') {
+                !is_truthy(context.get(key.trim()))
+            } else {
+                term.is_empty() || is_truthy(context.get(term))
+            }
+        })
+    })
+}
+
+fn descriptor(id: &str, title: &str, category: &str, when: Option<&str>) -> CommandDescriptor {
+    CommandDescriptor {
+        id: id.to_string(),
+        title: title.to_string(),
+        category: Some(category.to_string()),
+        when: when.map(str::to_string),
+        // Overwritten by whichever `register_*` the descriptor is passed to
+        source: CommandSource::Backend,
+    }
+}
+
+pub struct CommandRegistry {
+    commands: Arc<Mutex<HashMap<String, RegisteredCommand>>>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        let registry = Self {
+            commands: Arc::new(Mutex::new(HashMap::new())),
+        };
+        for (id, title, category, when) in FRONTEND_COMMANDS {
+            registry.register_frontend(descriptor(id, title, category, *when));
+        }
+        registry.register_builtin_handlers();
+        registry
+    }
+
+    fn register_builtin_handlers(&self) {
+        self.register_backend(
+            descriptor("workbench.action.newWindow", "New Window", "File", None),
+            |app, args| {
+                let workspace = args.get("workspace").and_then(Value::as_str);
+                let label = app.state::<WindowManager>().open_window(app, workspace).map_err(|e| e.to_string())?;
+                Ok(Value::String(label))
+            },
+        );
+        self.register_backend(
+            descriptor("explorer.undo", "Undo File Operation", "Explorer", None),
+            |app, _| {
+                let fs = app.state::<FileSystemService>();
+                let undone = app.state::<FsUndoService>().undo(&fs).map_err(|e| e.to_string())?;
+                serde_json::to_value(undone).map_err(|e| e.to_string())
+            },
+        );
+        self.register_backend(
+            descriptor("explorer.redo", "Redo File Operation", "Explorer", None),
+            |app, _| {
+                let fs = app.state::<FileSystemService>();
+                let redone = app.state::<FsUndoService>().redo(&fs).map_err(|e| e.to_string())?;
+                serde_json::to_value(redone).map_err(|e| e.to_string())
+            },
+        );
+        self.register_backend(
+            descriptor("workbench.action.reloadPlugins", "Reload Plugins", "Developer", None),
+            |app, _| {
+                let scan = app.state::<PluginService>().rescan(app).map_err(|e| e.to_string())?;
+                serde_json::to_value(scan).map_err(|e| e.to_string())
+            },
+        );
+    }
+
+    /// Register a command that runs in the backend, replacing any command with the same id
+    pub fn register_backend(
+        &self,
+        descriptor: CommandDescriptor,
+        handler: impl Fn(&AppHandle, Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) {
+        let descriptor = CommandDescriptor {
+            source: CommandSource::Backend,
+            ..descriptor
+        };
+        self.commands.lock().unwrap().insert(
+            descriptor.id.clone(),
+            RegisteredCommand {
+                descriptor,
+                handler: Some(Arc::new(handler)),
+            },
+        );
+    }
+
+    /// Register a command the frontend runs when it receives `command://execute`
+    pub fn register_frontend(&self, descriptor: CommandDescriptor) {
+        let descriptor = CommandDescriptor {
+            source: CommandSource::Frontend,
+            ..descriptor
+        };
+        self.commands.lock().unwrap().insert(
+            descriptor.id.clone(),
+            RegisteredCommand {
+                descriptor,
+                handler: None,
+            },
+        );
+    }
+
+    /// Registered and plugin-contributed commands sorted by category and title. With a context, only
+    /// commands whose `when` clause holds are returned.
+    pub fn list(
+        &self,
+        plugin_commands: Vec<PluginCommand>,
+        context: Option<&Map<String, Value>>,
+    ) -> Vec<CommandDescriptor> {
+        let mut commands: Vec<CommandDescriptor> =
+            self.commands.lock().unwrap().values().map(|command| command.descriptor.clone()).collect();
+        commands.extend(plugin_commands.into_iter().map(|command| CommandDescriptor {
+            id: command.contribution.command,
+            title: command.contribution.title,
+            category: command.contribution.category,
+            when: None,
+            source: CommandSource::Plugin {
+                plugin_id: command.plugin_id,
+            },
+        }));
+        if let Some(context) = context {
+            commands.retain(|command| command.when.as_deref().is_none_or(|when| evaluate_when(when, context)));
+        }
+        commands.sort_by(|a, b| (&a.category, &a.title, &a.id).cmp(&(&b.category, &b.title, &b.id)));
+        commands
+    }
+
+    /// Run a command wherever it lives. Frontend commands are handed over through `command://execute`
+    /// and return `null`; plugin commands return what the plugin returned, as a string. Blocks while a
+    /// plugin runs, so call it off the main thread.
+    pub fn execute(&self, app: &AppHandle, id: &str, args: Value) -> Result<Value, CommandError> {
+        let registered = self
+            .commands
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|command| command.handler.clone());
+        match registered {
+            Some(Some(handler)) => handler(app, args).map_err(CommandError::Failed),
+            Some(None) => {
+                let invocation = CommandInvocation {
+                    id: id.to_string(),
+                    args,
+                };
+                app.emit(COMMAND_EXECUTE_EVENT, invocation).map_err(|e| CommandError::Failed(e.to_string()))?;
+                Ok(Value::Null)
+            }
+            None => {
+                let args = if args.is_null() { String::new() } else { args.to_string() };
+                match app.state::<PluginService>().execute_command(app, id, &args) {
+                    Ok(output) => Ok(output.map(Value::String).unwrap_or(Value::Null)),
+                    Err(PluginError::CommandNotFound(_)) => Err(CommandError::NotFound(id.to_string())),
+                    Err(e) => Err(CommandError::Failed(e.to_string())),
+                }
+            }
+        }
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
@@ -0,0 +1,49 @@
+// File activity heatmap commands
+
+use crate::activity::{ActivityKind, ActivityService, ActivitySummary};
+use tauri::{AppHandle, State};
+
+/// Start recording file changes in a workspace
+#[tauri::command]
+pub fn start_activity_tracking(
+    app: AppHandle,
+    activity: State<'_, ActivityService>,
+    workspace: String,
+) -> Result<(), String> {
+    activity.start_tracking(&app, &workspace).map_err(|e| e.to_string())
+}
+
+/// Stop recording file changes in a workspace
+#[tauri::command]
+pub fn stop_activity_tracking(activity: State<'_, ActivityService>, workspace: String) -> Result<(), String> {
+    activity.stop_tracking(&workspace).map_err(|e| e.to_string())
+}
+
+/// Record a file event the watcher can't attribute, such as an editor save
+#[tauri::command]
+pub fn record_file_activity(
+    app: AppHandle,
+    activity: State<'_, ActivityService>,
+    workspace: String,
+    path: String,
+    kind: Option<ActivityKind>,
+) -> Result<(), String> {
+    activity
+        .record(&app, &workspace, &path, kind.unwrap_or(ActivityKind::Saved))
+        .map_err(|e| e.to_string())
+}
+
+/// Most edited files and activity by hour and day over the last `days` days
+#[tauri::command]
+pub fn get_file_activity(
+    app: AppHandle,
+    activity: State<'_, ActivityService>,
+    workspace: String,
+    days: Option<u64>,
+    utc_offset_minutes: Option<i64>,
+    limit: Option<usize>,
+) -> Result<ActivitySummary, String> {
+    activity
+        .summary(&app, &workspace, days.unwrap_or(7), utc_offset_minutes.unwrap_or(0), limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,47 @@
+// Auto-save commands; the frontend reports dirty buffers and the backend decides when to write them
+
+use crate::autosave::{AutoSaveService, PendingBuffer};
+use crate::file_system::FileSystemService;
+use tauri::{AppHandle, State, Window};
+
+/// Register the latest unsaved content of a buffer; returns `false` when auto-save is disabled
+#[tauri::command]
+pub fn register_dirty_buffer(
+    app: AppHandle,
+    window: Window,
+    fs: State<'_, FileSystemService>,
+    autosave: State<'_, AutoSaveService>,
+    path: String,
+    content: String,
+    version: u64,
+) -> Result<bool, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    Ok(autosave.register_dirty_buffer(&app, &path, content, version, window.label()))
+}
+
+/// Stop tracking a buffer after a manual save, revert, or close
+#[tauri::command]
+pub fn discard_dirty_buffer(
+    fs: State<'_, FileSystemService>,
+    autosave: State<'_, AutoSaveService>,
+    path: String,
+) -> Result<bool, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    Ok(autosave.discard_dirty_buffer(&path))
+}
+
+/// Flush all dirty buffers immediately, called when the window loses focus
+#[tauri::command]
+pub fn flush_dirty_buffers(app: AppHandle, autosave: State<'_, AutoSaveService>) -> Result<(), String> {
+    autosave.flush_all(&app);
+    Ok(())
+}
+
+/// Buffers waiting to be auto-saved
+#[tauri::command]
+pub fn get_pending_auto_saves(
+    app: AppHandle,
+    autosave: State<'_, AutoSaveService>,
+) -> Result<Vec<PendingBuffer>, String> {
+    Ok(autosave.pending_buffers(&app))
+}
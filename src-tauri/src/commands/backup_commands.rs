@@ -0,0 +1,62 @@
+// Hot-exit backup and crash recovery commands
+
+use crate::backup::{BackupService, BufferBackup, RecoverableBuffer};
+use crate::file_system::FileSystemService;
+use tauri::{AppHandle, Manager, State};
+
+/// Record the unsaved content of a buffer; snapshots are written to disk periodically
+#[tauri::command]
+pub fn backup_buffer(
+    app: AppHandle,
+    backups: State<'_, BackupService>,
+    buffer_id: String,
+    path: Option<String>,
+    workspace: Option<String>,
+    content: String,
+    language: Option<String>,
+) -> Result<(), String> {
+    let fs = app.state::<FileSystemService>();
+    for path in path.iter().chain(&workspace) {
+        fs.authorize(path).map_err(|e| e.to_string())?;
+    }
+    backups.update_backup(&app, &buffer_id, path, workspace, content, language);
+    Ok(())
+}
+
+/// Write all pending snapshots immediately
+#[tauri::command]
+pub fn flush_backups(app: AppHandle, backups: State<'_, BackupService>) -> Result<(), String> {
+    backups.flush(&app).map_err(|e| e.to_string())
+}
+
+/// Remove the backup of a buffer once it is saved or closed
+#[tauri::command]
+pub fn discard_backup(app: AppHandle, backups: State<'_, BackupService>, buffer_id: String) -> Result<(), String> {
+    backups.discard_backup(&app, &buffer_id).map_err(|e| e.to_string())
+}
+
+/// Buffers left unsaved by a previous run
+#[tauri::command]
+pub fn list_recoverable_buffers(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    backups: State<'_, BackupService>,
+    workspace: Option<String>,
+) -> Result<Vec<RecoverableBuffer>, String> {
+    if let Some(workspace) = &workspace {
+        fs.authorize(workspace).map_err(|e| e.to_string())?;
+    }
+    backups
+        .recoverable_buffers(&app, workspace.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Content and metadata of a recoverable buffer
+#[tauri::command]
+pub fn recover_buffer(
+    app: AppHandle,
+    backups: State<'_, BackupService>,
+    buffer_id: String,
+) -> Result<BufferBackup, String> {
+    backups.recover_buffer(&app, &buffer_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,60 @@
+// Bookmark commands for the Bookmarks panel; bookmarks are kept per workspace
+
+use crate::bookmarks::{Bookmark, BookmarkSpec, BookmarkStore};
+use tauri::{AppHandle, State};
+
+/// Bookmarks of a workspace, optionally only those of one file
+#[tauri::command]
+pub fn list_bookmarks(
+    app: AppHandle,
+    store: State<'_, BookmarkStore>,
+    workspace: String,
+    path: Option<String>,
+) -> Result<Vec<Bookmark>, String> {
+    store.list(&app, &workspace, path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Add a bookmark, or replace the label and note of the one on that line
+#[tauri::command]
+pub fn add_bookmark(
+    app: AppHandle,
+    store: State<'_, BookmarkStore>,
+    workspace: String,
+    bookmark: BookmarkSpec,
+) -> Result<Bookmark, String> {
+    store.add(&app, &workspace, bookmark).map_err(|e| e.to_string())
+}
+
+/// Edit the label or note of a bookmark; omitted fields are kept and empty ones cleared
+#[tauri::command]
+pub fn update_bookmark(
+    app: AppHandle,
+    store: State<'_, BookmarkStore>,
+    workspace: String,
+    id: String,
+    label: Option<String>,
+    note: Option<String>,
+) -> Result<Bookmark, String> {
+    store.update(&app, &workspace, &id, label, note).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_bookmark(
+    app: AppHandle,
+    store: State<'_, BookmarkStore>,
+    workspace: String,
+    id: String,
+) -> Result<(), String> {
+    store.remove(&app, &workspace, &id).map_err(|e| e.to_string())
+}
+
+/// Remove all bookmarks of a workspace, or of one file, returning how many were removed
+#[tauri::command]
+pub fn clear_bookmarks(
+    app: AppHandle,
+    store: State<'_, BookmarkStore>,
+    workspace: String,
+    path: Option<String>,
+) -> Result<usize, String> {
+    store.clear(&app, &workspace, path.as_deref()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,48 @@
+// Persistent breakpoint commands; changes are pushed to running debug sessions of the workspace
+
+use crate::debug::{BreakpointSpec, BreakpointStore, StoredBreakpoint};
+use tauri::{AppHandle, State};
+
+/// Breakpoints of a workspace, optionally only those of one file
+#[tauri::command]
+pub fn list_breakpoints(
+    app: AppHandle,
+    store: State<'_, BreakpointStore>,
+    workspace: String,
+    path: Option<String>,
+) -> Result<Vec<StoredBreakpoint>, String> {
+    store.list(&app, &workspace, path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Add a breakpoint, or update the condition, hit count, or log message of the one on that line
+#[tauri::command]
+pub fn add_breakpoint(
+    app: AppHandle,
+    store: State<'_, BreakpointStore>,
+    workspace: String,
+    breakpoint: BreakpointSpec,
+) -> Result<StoredBreakpoint, String> {
+    store.add(&app, &workspace, breakpoint).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_breakpoint(
+    app: AppHandle,
+    store: State<'_, BreakpointStore>,
+    workspace: String,
+    id: String,
+) -> Result<(), String> {
+    store.remove(&app, &workspace, &id).map_err(|e| e.to_string())
+}
+
+/// Enable or disable a breakpoint; flips it when `enabled` is omitted
+#[tauri::command]
+pub fn toggle_breakpoint(
+    app: AppHandle,
+    store: State<'_, BreakpointStore>,
+    workspace: String,
+    id: String,
+    enabled: Option<bool>,
+) -> Result<StoredBreakpoint, String> {
+    store.toggle(&app, &workspace, &id, enabled).map_err(|e| e.to_string())
+}
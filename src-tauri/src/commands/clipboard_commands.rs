@@ -0,0 +1,21 @@
+// System clipboard commands
+
+use crate::clipboard::{ClipboardImage, ClipboardService};
+use tauri::State;
+
+/// Text on the clipboard, or `None` when it holds no text
+#[tauri::command]
+pub fn clipboard_read_text(clipboard: State<'_, ClipboardService>) -> Result<Option<String>, String> {
+    clipboard.read_text().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clipboard_write_text(clipboard: State<'_, ClipboardService>, text: String) -> Result<(), String> {
+    clipboard.write_text(&text).map_err(|e| e.to_string())
+}
+
+/// Image on the clipboard as base64 PNG, or `None` when it holds no image
+#[tauri::command]
+pub async fn clipboard_read_image(clipboard: State<'_, ClipboardService>) -> Result<Option<ClipboardImage>, String> {
+    clipboard.read_image().map_err(|e| e.to_string())
+}
@@ -0,0 +1,28 @@
+// Command palette commands
+
+use crate::command_registry::{CommandDescriptor, CommandRegistry};
+use crate::plugins::PluginService;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+/// Commands for the palette; with `context`, only those whose `when` clause holds in it
+#[tauri::command]
+pub fn list_commands(
+    registry: State<'_, CommandRegistry>,
+    plugins: State<'_, PluginService>,
+    context: Option<Map<String, Value>>,
+) -> Vec<CommandDescriptor> {
+    registry.list(plugins.contributed_commands(), context.as_ref())
+}
+
+/// Run a command by id; plugin commands may block, so this runs off the main thread
+#[tauri::command]
+pub async fn execute_command(app: AppHandle, id: String, args: Option<Value>) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<CommandRegistry>()
+            .execute(&app, &id, args.unwrap_or(Value::Null))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
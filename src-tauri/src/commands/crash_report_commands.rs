@@ -0,0 +1,37 @@
+// Crash report commands
+
+use crate::crash_reports::{CrashReport, CrashReportService, CrashReportSummary};
+use tauri::{AppHandle, State};
+
+/// Crash reports on this machine, newest first
+#[tauri::command]
+pub fn list_crash_reports(
+    app: AppHandle,
+    reports: State<'_, CrashReportService>,
+) -> Result<Vec<CrashReportSummary>, String> {
+    reports.list(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_crash_report(
+    app: AppHandle,
+    reports: State<'_, CrashReportService>,
+    id: String,
+) -> Result<CrashReport, String> {
+    reports.get(&app, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_crash_report(
+    app: AppHandle,
+    reports: State<'_, CrashReportService>,
+    id: String,
+) -> Result<(), String> {
+    reports.delete(&app, &id).map_err(|e| e.to_string())
+}
+
+/// Send a report to the configured `crash_report_url`; fails when none is configured
+#[tauri::command]
+pub async fn submit_crash_report(app: AppHandle, id: String) -> Result<CrashReport, String> {
+    CrashReportService::submit(&app, &id).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,67 @@
+// Database explorer commands
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::database::{DatabaseInfo, DatabaseService, QueryResult, TableInfo, DEFAULT_PAGE_SIZE};
+use crate::file_system::FileSystemService;
+
+/// Open a SQLite file of the workspace; `read_only` keeps queries from changing it
+#[tauri::command]
+pub fn open_sqlite(
+    fs: State<'_, FileSystemService>,
+    databases: State<'_, DatabaseService>,
+    path: String,
+    read_only: Option<bool>,
+) -> Result<DatabaseInfo, String> {
+    let path = fs.authorize(&path).map_err(|e| e.to_string())?;
+    databases.open_sqlite(&path, read_only.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn close_database(databases: State<'_, DatabaseService>, id: String) -> Result<(), String> {
+    databases.close(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_open_databases(databases: State<'_, DatabaseService>) -> Vec<DatabaseInfo> {
+    databases.list()
+}
+
+/// Tables and views of an open database, with their columns
+#[tauri::command]
+pub async fn list_tables(app: AppHandle, id: String) -> Result<Vec<TableInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DatabaseService>().list_tables(&id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Rows in a table or view, counted when the user asks since it reads the whole table
+#[tauri::command]
+pub async fn count_rows(app: AppHandle, id: String, table: String) -> Result<u64, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DatabaseService>().count_rows(&id, &table).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Run one SQL statement, returning the page of `limit` rows (500 by default) starting at `offset`; statements
+/// that write return all their rows at once
+#[tauri::command]
+pub async fn run_query(
+    app: AppHandle,
+    id: String,
+    sql: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<QueryResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DatabaseService>()
+            .run_query(&id, &sql, limit.unwrap_or(DEFAULT_PAGE_SIZE), offset.unwrap_or(0))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,126 @@
+// Debugger commands; requests to the debug adapter run off the main thread
+
+use crate::debug::{
+    Breakpoint, DebugLaunchRequest, DebugService, DebugSessionInfo, DebugThread, EvaluateResult, Scope,
+    SourceBreakpoint, StackFrame, Variable,
+};
+use tauri::{AppHandle, Manager, State};
+
+/// Run a blocking debug adapter call on the blocking thread pool
+async fn with_debugger<T: Send + 'static>(
+    app: AppHandle,
+    call: impl FnOnce(&AppHandle, &DebugService) -> Result<T, crate::debug::DebugError> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        call(&app, &app.state::<DebugService>()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Start a debug session (launch or attach); stops and output stream as `debug://` events
+#[tauri::command]
+pub async fn debug_start(app: AppHandle, request: DebugLaunchRequest) -> Result<DebugSessionInfo, String> {
+    with_debugger(app, move |app, debug| debug.start_session(app, request)).await
+}
+
+/// End a debug session
+#[tauri::command]
+pub async fn debug_stop(app: AppHandle, session_id: String) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.stop_session(&session_id)).await
+}
+
+/// Active debug sessions
+#[tauri::command]
+pub fn debug_sessions(debug: State<'_, DebugService>) -> Result<Vec<DebugSessionInfo>, String> {
+    Ok(debug.list_sessions())
+}
+
+/// Replace the breakpoints of a file in a running session
+#[tauri::command]
+pub async fn debug_set_breakpoints(
+    app: AppHandle,
+    session_id: String,
+    path: String,
+    breakpoints: Vec<SourceBreakpoint>,
+) -> Result<Vec<Breakpoint>, String> {
+    with_debugger(app, move |_, debug| debug.set_breakpoints(&session_id, &path, &breakpoints)).await
+}
+
+/// Resume a thread
+#[tauri::command]
+pub async fn debug_continue(app: AppHandle, session_id: String, thread_id: i64) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.control(&session_id, "continue", thread_id)).await
+}
+
+/// Step over the current line
+#[tauri::command]
+pub async fn debug_step_over(app: AppHandle, session_id: String, thread_id: i64) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.control(&session_id, "next", thread_id)).await
+}
+
+/// Step into the call on the current line
+#[tauri::command]
+pub async fn debug_step_in(app: AppHandle, session_id: String, thread_id: i64) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.control(&session_id, "stepIn", thread_id)).await
+}
+
+/// Run until the current function returns
+#[tauri::command]
+pub async fn debug_step_out(app: AppHandle, session_id: String, thread_id: i64) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.control(&session_id, "stepOut", thread_id)).await
+}
+
+/// Suspend a running thread
+#[tauri::command]
+pub async fn debug_pause(app: AppHandle, session_id: String, thread_id: i64) -> Result<(), String> {
+    with_debugger(app, move |_, debug| debug.control(&session_id, "pause", thread_id)).await
+}
+
+/// Threads of the debuggee
+#[tauri::command]
+pub async fn debug_threads(app: AppHandle, session_id: String) -> Result<Vec<DebugThread>, String> {
+    with_debugger(app, move |_, debug| debug.threads(&session_id)).await
+}
+
+/// Call stack of a stopped thread
+#[tauri::command]
+pub async fn debug_stack_trace(
+    app: AppHandle,
+    session_id: String,
+    thread_id: i64,
+    levels: Option<usize>,
+) -> Result<Vec<StackFrame>, String> {
+    with_debugger(app, move |_, debug| debug.stack_trace(&session_id, thread_id, levels)).await
+}
+
+/// Variable scopes (locals, globals, registers, ...) of a stack frame
+#[tauri::command]
+pub async fn debug_scopes(app: AppHandle, session_id: String, frame_id: i64) -> Result<Vec<Scope>, String> {
+    with_debugger(app, move |_, debug| debug.scopes(&session_id, frame_id)).await
+}
+
+/// Children of a scope or structured variable
+#[tauri::command]
+pub async fn debug_variables(
+    app: AppHandle,
+    session_id: String,
+    variables_reference: i64,
+) -> Result<Vec<Variable>, String> {
+    with_debugger(app, move |_, debug| debug.variables(&session_id, variables_reference)).await
+}
+
+/// Evaluate an expression in the context of a stack frame
+#[tauri::command]
+pub async fn debug_evaluate(
+    app: AppHandle,
+    session_id: String,
+    expression: String,
+    frame_id: Option<i64>,
+    context: Option<String>,
+) -> Result<EvaluateResult, String> {
+    with_debugger(app, move |_, debug| {
+        debug.evaluate(&session_id, &expression, frame_id, context.as_deref().unwrap_or("repl"))
+    })
+    .await
+}
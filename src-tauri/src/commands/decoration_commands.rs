@@ -0,0 +1,9 @@
+// Inline decoration commands: color swatches and asset links
+
+use crate::decorations::{self, DocumentDecorations};
+
+/// Color literals and asset references of a file; root-relative assets resolve against `workspace`
+#[tauri::command]
+pub fn get_document_decorations(path: String, workspace: Option<String>) -> Result<DocumentDecorations, String> {
+    decorations::document_decorations(&path, workspace.as_deref()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,50 @@
+// Desktop integration commands: native file dialogs and handing files to the OS
+
+use crate::desktop::{self, OpenDialogOptions, SaveDialogOptions};
+use crate::file_system::FileSystemService;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Native open dialog; resolves to `null` when cancelled. Picked paths are allowed for file operations.
+#[tauri::command]
+pub async fn show_open_dialog(
+    app: AppHandle,
+    options: Option<OpenDialogOptions>,
+) -> Result<Option<Vec<String>>, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || desktop::show_open_dialog(&app, &options))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Native save dialog; resolves to `null` when cancelled
+#[tauri::command]
+pub async fn show_save_dialog(
+    app: AppHandle,
+    options: Option<SaveDialogOptions>,
+) -> Result<Option<String>, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || desktop::show_save_dialog(&app, &options))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reveal_in_file_manager(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    desktop::reveal_in_file_manager(&app, Path::new(&path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn open_with_default_app(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    desktop::open_with_default_app(&app, Path::new(&path)).map_err(|e| e.to_string())
+}
@@ -0,0 +1,48 @@
+// Dev container commands
+
+use crate::devcontainer::{DevContainer, DevContainerConfig, DevContainerService};
+use tauri::{AppHandle, Manager, State};
+
+/// The workspace's `devcontainer.json`, resolved; `null` when it has none
+#[tauri::command]
+pub fn get_dev_container_config(
+    devcontainers: State<'_, DevContainerService>,
+    workspace: String,
+) -> Result<Option<DevContainerConfig>, String> {
+    devcontainers.get_config(&workspace).map_err(|e| e.to_string())
+}
+
+/// Build and start the workspace's container and open the workspace inside it; the returned `uri` is the
+/// workspace root to use from then on. Output streams as `devcontainer://log` events.
+#[tauri::command]
+pub async fn open_dev_container(
+    app: AppHandle,
+    workspace: String,
+    rebuild: Option<bool>,
+) -> Result<DevContainer, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DevContainerService>()
+            .open(&app, &workspace, rebuild.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Stop the workspace's container, and with `remove` delete it
+#[tauri::command]
+pub async fn stop_dev_container(app: AppHandle, workspace: String, remove: Option<bool>) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DevContainerService>()
+            .stop(&app, &workspace, remove.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Workspaces open in containers
+#[tauri::command]
+pub fn list_dev_containers(devcontainers: State<'_, DevContainerService>) -> Vec<DevContainer> {
+    devcontainers.list()
+}
@@ -0,0 +1,37 @@
+// Diagnostics commands for the Problems panel and build output parsing
+
+use crate::diagnostics::{Diagnostic, DiagnosticsService, PublishDiagnostics};
+use crate::problem_matcher::{ProblemCollector, ProblemMatcherSpec};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Diagnostics reported by tasks and tools, optionally for one file
+#[tauri::command]
+pub fn get_diagnostics(
+    diagnostics: State<'_, DiagnosticsService>,
+    file: Option<String>,
+) -> Result<Vec<PublishDiagnostics>, String> {
+    Ok(diagnostics.list(file.as_deref()))
+}
+
+/// Drop everything a publisher reported, e.g. `task:cargo: build`
+#[tauri::command]
+pub fn clear_diagnostics(
+    app: AppHandle,
+    diagnostics: State<'_, DiagnosticsService>,
+    owner: String,
+) -> Result<(), String> {
+    diagnostics.clear(&app, &owner);
+    Ok(())
+}
+
+/// Run problem matchers over captured tool output; relative paths resolve against `cwd`
+#[tauri::command]
+pub fn match_problems(
+    output: String,
+    matchers: Vec<ProblemMatcherSpec>,
+    cwd: String,
+) -> Result<Vec<Diagnostic>, String> {
+    let mut collector = ProblemCollector::new(&matchers, Path::new(&cwd))?;
+    Ok(collector.collect(&output))
+}
@@ -0,0 +1,91 @@
+// Diff commands for editor gutters and the diff viewer
+
+use crate::diff::{self, DiffOptions, DiffResult};
+use crate::file_system::FileSystemService;
+use crate::git::GitService;
+use crate::merge::{self, MergeLabels, MergeResult};
+use crate::types::FileSystemError;
+use tauri::State;
+
+/// Line diff between two texts
+#[tauri::command]
+pub fn compute_diff(
+    old_text: String,
+    new_text: String,
+    options: Option<DiffOptions>,
+) -> Result<DiffResult, String> {
+    Ok(diff::compute_diff(&old_text, &new_text, options.unwrap_or_default()))
+}
+
+/// Line diff between two files on disk
+#[tauri::command]
+pub fn diff_files(
+    fs: State<'_, FileSystemService>,
+    path_a: String,
+    path_b: String,
+    options: Option<DiffOptions>,
+) -> Result<DiffResult, String> {
+    let old = fs.read_file(&path_a).map_err(|e| e.to_string())?;
+    let new = fs.read_file(&path_b).map_err(|e| e.to_string())?;
+
+    if old.is_binary || new.is_binary {
+        return Err("Cannot diff binary files".to_string());
+    }
+
+    Ok(diff::compute_diff(&old.content, &new.content, options.unwrap_or_default()))
+}
+
+/// Unsaved buffer compared with the file on disk ("compare with saved"); a missing file diffs as empty
+#[tauri::command]
+pub fn diff_against_disk(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    buffer_content: String,
+    options: Option<DiffOptions>,
+) -> Result<DiffResult, String> {
+    let saved = match fs.read_file(&path) {
+        Ok(file) if file.is_binary => return Err("Cannot diff binary files".to_string()),
+        Ok(file) => file.content,
+        Err(FileSystemError::NotFound) => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    Ok(diff::compute_diff(&saved, &buffer_content, options.unwrap_or_default()))
+}
+
+/// File compared with its committed version at HEAD ("compare with HEAD"); uses the unsaved buffer
+/// when given, the file on disk otherwise, and diffs untracked files against an empty text
+#[tauri::command]
+pub fn diff_against_git_head(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    buffer_content: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<DiffResult, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    let head = git.head_content(&path).map_err(|e| e.to_string())?.unwrap_or_default();
+    let current = match buffer_content {
+        Some(content) => content,
+        None => {
+            let file = fs.read_file(&path).map_err(|e| e.to_string())?;
+            if file.is_binary {
+                return Err("Cannot diff binary files".to_string());
+            }
+            file.content
+        }
+    };
+
+    Ok(diff::compute_diff(&head, &current, options.unwrap_or_default()))
+}
+
+/// Three-way merge of two texts derived from a common base
+#[tauri::command]
+pub fn merge_three_way(
+    base: String,
+    ours: String,
+    theirs: String,
+    labels: Option<MergeLabels>,
+) -> Result<MergeResult, String> {
+    Ok(merge::merge_three_way(&base, &ours, &theirs, &labels.unwrap_or_default()))
+}
@@ -0,0 +1,38 @@
+// Workspace environment commands for `.env` files
+
+use crate::environment::{self, EnvEntry, EnvVariable};
+use crate::file_system::FileSystemService;
+use tauri::State;
+
+/// Assignments of one env file
+#[tauri::command]
+pub fn load_env_file(fs: State<'_, FileSystemService>, path: String) -> Result<Vec<EnvEntry>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    environment::load_env_file(&path).map_err(|e| e.to_string())
+}
+
+/// Effective workspace environment with the source of each value; the IDE's own environment is
+/// included unless `include_process` is false
+#[tauri::command]
+pub fn get_merged_env(
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+    include_process: Option<bool>,
+) -> Result<Vec<EnvVariable>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    environment::merged_env(&workspace, include_process.unwrap_or(true)).map_err(|e| e.to_string())
+}
+
+/// Set or remove (`value` omitted) a variable in a workspace env file, `.env.local` by default
+#[tauri::command]
+pub fn set_env_override(
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+    name: String,
+    value: Option<String>,
+    file: Option<String>,
+) -> Result<Vec<EnvEntry>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    environment::set_env_override(&workspace, file.as_deref(), &name, value.as_deref())
+        .map_err(|e| e.to_string())
+}
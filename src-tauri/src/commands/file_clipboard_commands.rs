@@ -0,0 +1,92 @@
+// File clipboard commands: cut, copy, and paste of explorer entries
+
+use crate::file_clipboard::{ClipboardMode, FileClipboard, FileClipboardService};
+use crate::file_import::{ImportResult, ImportStrategy, IMPORT_PROGRESS_EVENT};
+use crate::file_system::FileSystemService;
+use crate::operation_log::{log_operation, OperationKind, OperationRecord, PendingOverwrites};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+fn set_clipboard(
+    fs: &FileSystemService,
+    clipboard: &FileClipboardService,
+    mode: ClipboardMode,
+    paths: Vec<String>,
+) -> Result<FileClipboard, String> {
+    for path in &paths {
+        fs.authorize_link(path).map_err(|e| e.to_string())?;
+    }
+    Ok(clipboard.set(mode, paths))
+}
+
+#[tauri::command]
+pub fn clipboard_copy_paths(
+    fs: State<'_, FileSystemService>,
+    clipboard: State<'_, FileClipboardService>,
+    paths: Vec<String>,
+) -> Result<FileClipboard, String> {
+    set_clipboard(&fs, &clipboard, ClipboardMode::Copy, paths)
+}
+
+#[tauri::command]
+pub fn clipboard_cut_paths(
+    fs: State<'_, FileSystemService>,
+    clipboard: State<'_, FileClipboardService>,
+    paths: Vec<String>,
+) -> Result<FileClipboard, String> {
+    set_clipboard(&fs, &clipboard, ClipboardMode::Cut, paths)
+}
+
+/// What is on the file clipboard, so cut entries can be shown dimmed
+#[tauri::command]
+pub fn get_file_clipboard(clipboard: State<'_, FileClipboardService>) -> Option<FileClipboard> {
+    clipboard.get()
+}
+
+#[tauri::command]
+pub fn clear_file_clipboard(clipboard: State<'_, FileClipboardService>) {
+    clipboard.clear();
+}
+
+/// Paste into `destination` (renaming on collisions by default); copy progress streams as
+/// `fs://import-progress`
+#[tauri::command]
+pub async fn clipboard_paste(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    clipboard: State<'_, FileClipboardService>,
+    destination: String,
+    strategy: Option<ImportStrategy>,
+) -> Result<ImportResult, String> {
+    // The scope may have changed since the paths were put on the clipboard
+    let contents = clipboard.get();
+    for path in contents.iter().flat_map(|contents| &contents.paths) {
+        fs.authorize_link(path).map_err(|e| e.to_string())?;
+    }
+    fs.authorize(&destination).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let strategy = strategy.unwrap_or(ImportStrategy::Rename);
+        let paths = contents.as_ref().map(|contents| contents.paths.as_slice()).unwrap_or_default();
+        let overwrites = PendingOverwrites::prepare(&app, paths, &destination, strategy);
+        let result = app
+            .state::<FileClipboardService>()
+            .paste(Path::new(&destination), strategy, |progress| {
+                let _ = app.emit(IMPORT_PROGRESS_EVENT, progress);
+            })
+            .map_err(|e| e.to_string())?;
+
+        overwrites.log(&app, &result, "clipboard_paste");
+        let cut = contents.is_some_and(|contents| contents.mode == ClipboardMode::Cut);
+        if cut && !result.imported.is_empty() {
+            let (sources, targets) =
+                result.imported.iter().map(|moved| (moved.source.clone(), moved.destination.clone())).unzip();
+            let record =
+                OperationRecord::new(OperationKind::Move, sources, "clipboard_paste").with_destinations(targets);
+            log_operation(&app, record);
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,80 @@
+// Local file history commands
+
+use crate::file_history::{FileHistoryService, FileVersion, VersionSource};
+use crate::file_system::FileSystemService;
+use crate::operation_log::{log_operation, OperationKind, OperationRecord, OperationSnapshot};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Record the saved content of a file; returns `None` when it matches the latest version
+#[tauri::command]
+pub fn record_file_version(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    history: State<'_, FileHistoryService>,
+    path: String,
+    source: Option<VersionSource>,
+) -> Result<Option<FileVersion>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    history
+        .record_version(&app, &path, source.unwrap_or(VersionSource::Save))
+        .map_err(|e| e.to_string())
+}
+
+/// Local versions of a file, newest first
+#[tauri::command]
+pub fn get_file_history(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    history: State<'_, FileHistoryService>,
+    path: String,
+) -> Result<Vec<FileVersion>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    history.history(&app, &path).map_err(|e| e.to_string())
+}
+
+/// Content of a single version
+#[tauri::command]
+pub fn get_file_version_content(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    history: State<'_, FileHistoryService>,
+    path: String,
+    version_id: String,
+) -> Result<String, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    history
+        .version_content(&app, &path, &version_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace a file with one of its versions; the overwrite is logged
+#[tauri::command]
+pub fn restore_file_version(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    history: State<'_, FileHistoryService>,
+    path: String,
+    version_id: String,
+) -> Result<FileVersion, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    let existed = Path::new(&path).is_file();
+    let version = history
+        .restore_version(&app, &path, &version_id)
+        .map_err(|e| e.to_string())?;
+
+    // Restoring keeps the replaced content as the newest version, unless there was no file to replace
+    let snapshots = history
+        .history(&app, &path)
+        .ok()
+        .and_then(|versions| versions.into_iter().next())
+        .filter(|_| existed)
+        .map(|latest| OperationSnapshot {
+            path: path.clone(),
+            version_id: latest.id,
+        });
+    let record = OperationRecord::new(OperationKind::Overwrite, vec![path], "restore_file_version")
+        .with_snapshots(snapshots.into_iter().collect());
+    log_operation(&app, record);
+    Ok(version)
+}
@@ -0,0 +1,51 @@
+// Advisory file lock commands for coordinating IDE windows
+
+use crate::file_locks::{FileLock, FileLockChange, FileLockService, FILE_LOCK_CHANGED_EVENT};
+use crate::file_system::FileSystemService;
+use tauri::{AppHandle, Emitter, State, Window};
+
+/// Claim a file for the calling window; fails when another window holds it
+#[tauri::command]
+pub fn lock_file(
+    app: AppHandle,
+    window: Window,
+    fs: State<'_, FileSystemService>,
+    locks: State<'_, FileLockService>,
+    path: String,
+) -> Result<FileLock, String> {
+    let resolved = fs.authorize(&path).map_err(|e| e.to_string())?;
+    let lock = locks.lock(&resolved, &path, window.label()).map_err(|e| e.to_string())?;
+    let change = FileLockChange {
+        path,
+        lock: Some(lock.clone()),
+    };
+    let _ = app.emit(FILE_LOCK_CHANGED_EVENT, change);
+    Ok(lock)
+}
+
+/// Release a lock held by the calling window; unlocking a file nobody holds does nothing
+#[tauri::command]
+pub fn unlock_file(
+    app: AppHandle,
+    window: Window,
+    fs: State<'_, FileSystemService>,
+    locks: State<'_, FileLockService>,
+    path: String,
+) -> Result<(), String> {
+    let resolved = fs.authorize(&path).map_err(|e| e.to_string())?;
+    if locks.unlock(&resolved, window.label()).map_err(|e| e.to_string())?.is_some() {
+        let _ = app.emit(FILE_LOCK_CHANGED_EVENT, FileLockChange { path, lock: None });
+    }
+    Ok(())
+}
+
+/// Who holds a file, if anyone
+#[tauri::command]
+pub fn query_lock(
+    fs: State<'_, FileSystemService>,
+    locks: State<'_, FileLockService>,
+    path: String,
+) -> Result<Option<FileLock>, String> {
+    let resolved = fs.authorize(&path).map_err(|e| e.to_string())?;
+    Ok(locks.query(&resolved))
+}
@@ -0,0 +1,554 @@
+// File system commands backed by the FileSystemService
+
+use crate::bulk_rename::{self, BulkRenamePlan, RenamePattern, RenameStatus};
+use crate::checksum::{self, FileHash, HashAlgorithm, HASH_PROGRESS_EVENT};
+use crate::disk_usage::{self, DirectorySize, DIRECTORY_SIZE_PROGRESS_EVENT};
+use crate::extended_attributes::{self, AttributeEncoding, ExtendedAttribute};
+use crate::file_icons::FileIconMap;
+use crate::file_nesting::FileNesting;
+use crate::file_stats::{self, FileStats};
+use crate::file_import::{self, ImportResult, ImportStrategy, IMPORT_PROGRESS_EVENT};
+use crate::file_system::{FileSystemScope, FileSystemService};
+use crate::file_templates::{self, NewFile};
+use crate::file_type::{self, FileType};
+use crate::fs_undo::{FsOperation, FsUndoService};
+use crate::fs_watch::WatchStatus;
+use crate::operation_log::{self, log_operation, OperationKind, OperationRecord, PendingOverwrites};
+use crate::save_pipeline::{self, AppliedTransforms, FormatStatus, SavedFile};
+use crate::types::{
+    DirectoryFilter, DirectoryListing, DirectoryPage, DirectorySort, FileContent, FileMetadata,
+    FileOperationConfig, FileOperationResult, FileSystemError, HexDump,
+};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Allow file operations under a workspace folder once it is opened
+#[tauri::command]
+pub fn add_workspace_root(fs: State<'_, FileSystemService>, path: String) -> Result<FileSystemScope, String> {
+    fs.add_workspace_root(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_workspace_root(fs: State<'_, FileSystemService>, path: String) -> Result<FileSystemScope, String> {
+    fs.remove_workspace_root(&path).map_err(|e| e.to_string())
+}
+
+/// Allow one file or directory outside the workspace, after the user picked it in a dialog
+#[tauri::command]
+pub fn allow_file_system_path(
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<FileSystemScope, String> {
+    fs.allow_path(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_file_system_scope(fs: State<'_, FileSystemService>) -> Result<FileSystemScope, String> {
+    Ok(fs.scope())
+}
+
+#[tauri::command]
+pub fn get_file_operation_config(fs: State<'_, FileSystemService>) -> Result<FileOperationConfig, String> {
+    Ok(fs.get_config())
+}
+
+/// Change how file operations treat existing files, parent directories, permissions, and symlinks
+#[tauri::command]
+pub fn set_file_operation_config(
+    fs: State<'_, FileSystemService>,
+    config: FileOperationConfig,
+) -> Result<FileOperationConfig, String> {
+    fs.set_config(config);
+    Ok(fs.get_config())
+}
+
+/// Copy a directory tree; symlink loops are skipped and reported in the result message
+#[tauri::command]
+pub fn copy_directory(
+    fs: State<'_, FileSystemService>,
+    source: String,
+    destination: String,
+) -> Result<FileOperationResult, String> {
+    fs.copy_directory(&source, &destination).map_err(|e| e.to_string())
+}
+
+/// Create a symlink; `target` may be relative to the link's directory
+#[tauri::command]
+pub fn create_symlink(
+    fs: State<'_, FileSystemService>,
+    target: String,
+    link_path: String,
+) -> Result<FileOperationResult, String> {
+    fs.create_symlink(&target, &link_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn read_link_target(fs: State<'_, FileSystemService>, path: String) -> Result<String, String> {
+    fs.read_link_target(&path).map_err(|e| e.to_string())
+}
+
+/// Change Unix permission bits from an octal mode such as `644`; returns the updated metadata
+#[tauri::command]
+pub fn set_permissions(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    mode: String,
+) -> Result<FileMetadata, String> {
+    fs.set_permissions(&path, &mode).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_readonly(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    readonly: bool,
+) -> Result<FileMetadata, String> {
+    fs.set_readonly(&path, readonly).map_err(|e| e.to_string())
+}
+
+/// Extended attributes (alternate data streams on Windows) of a file
+#[tauri::command]
+pub fn list_extended_attributes(
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<Vec<ExtendedAttribute>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    extended_attributes::list_attributes(Path::new(&path)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_extended_attribute(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    name: String,
+) -> Result<ExtendedAttribute, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    extended_attributes::get_attribute(Path::new(&path), &name).map_err(|e| e.to_string())
+}
+
+/// Set an attribute; binary values are passed base64-encoded with `encoding: "base64"`
+#[tauri::command]
+pub fn set_extended_attribute(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    name: String,
+    value: String,
+    encoding: Option<AttributeEncoding>,
+) -> Result<ExtendedAttribute, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    let encoding = encoding.unwrap_or(AttributeEncoding::Utf8);
+    extended_attributes::set_attribute(Path::new(&path), &name, &value, encoding).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_extended_attribute(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    name: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    extended_attributes::remove_attribute(Path::new(&path), &name).map_err(|e| e.to_string())
+}
+
+/// Total size of a directory per immediate child; running totals stream as `fs://directory-size-progress`
+#[tauri::command]
+pub async fn compute_directory_size(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    respect_ignore: Option<bool>,
+) -> Result<DirectorySize, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        disk_usage::compute_directory_size(Path::new(&path), respect_ignore.unwrap_or(false), |progress| {
+            let _ = app.emit(DIRECTORY_SIZE_PROGRESS_EVENT, progress);
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Checksum of a file; progress on large files streams as `fs://hash-progress`
+#[tauri::command]
+pub async fn hash_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    algorithm: HashAlgorithm,
+) -> Result<FileHash, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        checksum::hash_file(Path::new(&path), algorithm, |progress| {
+            let _ = app.emit(HASH_PROGRESS_EVENT, progress);
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Copy files dropped from the OS into `destination_dir`; progress streams as `fs://import-progress`
+#[tauri::command]
+pub async fn import_paths(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    source_paths: Vec<String>,
+    destination_dir: String,
+    strategy: ImportStrategy,
+) -> Result<ImportResult, String> {
+    // Dropped paths are allowed when the drop happens, so anything else is still rejected here
+    for source in &source_paths {
+        fs.authorize(source).map_err(|e| e.to_string())?;
+    }
+    fs.authorize(&destination_dir).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let overwrites = PendingOverwrites::prepare(&app, &source_paths, &destination_dir, strategy);
+        let result = file_import::import_paths(&source_paths, Path::new(&destination_dir), strategy, |progress| {
+            let _ = app.emit(IMPORT_PROGRESS_EVENT, progress);
+        })
+        .map_err(|e| e.to_string())?;
+        overwrites.log(&app, &result, "import_paths");
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Hex and ASCII rows of part of a file, for binaries the text editor can't show
+#[tauri::command]
+pub fn read_file_hex(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    offset: u64,
+    length: u64,
+) -> Result<HexDump, String> {
+    fs.read_file_hex(&path, offset, length).map_err(|e| e.to_string())
+}
+
+/// Type of a file from its content, falling back to its extension
+#[tauri::command]
+pub fn detect_file_type(fs: State<'_, FileSystemService>, path: String) -> Result<FileType, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    file_type::detect_file_type(Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// Line, word, and character counts, line endings, and indentation of a file for the status bar
+#[tauri::command]
+pub async fn get_file_stats(fs: State<'_, FileSystemService>, path: String) -> Result<FileStats, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        file_stats::file_stats(Path::new(&path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Explorer icon mapping: the bundled map with the user's `file-icons.json` applied. Reloads the
+/// overrides, so the frontend calls this again after the file changes.
+#[tauri::command]
+pub fn get_icon_theme_map(app: AppHandle, fs: State<'_, FileSystemService>) -> Result<FileIconMap, String> {
+    let config_dir = app.path().app_config_dir().ok();
+    fs.load_icon_map(config_dir.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Directory contents sorted and filtered on the backend, so the explorer doesn't re-sort large folders.
+/// With the `file_nesting` preference on, companion files are in their parent's `children_nested`.
+#[tauri::command]
+pub fn list_directory(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    include_hidden: Option<bool>,
+    sort: Option<DirectorySort>,
+    filter: Option<DirectoryFilter>,
+) -> Result<DirectoryListing, String> {
+    let filter = filter.unwrap_or_default();
+    let nesting = FileNesting::configured(&app, &path);
+    let include_hidden = include_hidden.unwrap_or(false);
+    fs.list_directory(&path, include_hidden, sort.unwrap_or_default(), &filter, nesting.as_ref())
+        .map_err(|e| e.to_string())
+}
+
+/// A page of a directory listing; pass the returned cursor back for the next page
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn list_directory_page(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    cursor: Option<String>,
+    page_size: usize,
+    sort: Option<DirectorySort>,
+    filter: Option<DirectoryFilter>,
+    include_hidden: Option<bool>,
+) -> Result<DirectoryPage, String> {
+    // Later pages are served from the first call's snapshot, which is already nested
+    let nesting = match cursor {
+        Some(_) => None,
+        None => FileNesting::configured(&app, &path),
+    };
+    fs.list_directory_page(
+        &path,
+        cursor.as_deref(),
+        page_size,
+        sort.unwrap_or_default(),
+        &filter.unwrap_or_default(),
+        include_hidden.unwrap_or(false),
+        nesting.as_ref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Watch a directory, recursively unless `recursive` is false. Changes arrive as `fs://change` events, and
+/// `fs://rescan` asks for the subtree to be re-read when events were lost or the directory was recreated.
+#[tauri::command]
+pub fn watch_directory(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    recursive: Option<bool>,
+) -> Result<WatchStatus, String> {
+    fs.watch_directory(&app, &path, recursive.unwrap_or(true)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn stop_watching_directory(fs: State<'_, FileSystemService>, watch_id: String) -> Result<(), String> {
+    fs.stop_watching(&watch_id).map_err(|e| e.to_string())
+}
+
+/// Watch one file, e.g. the file open in an editor. Changes made outside the IDE arrive as coalesced
+/// `fs://file-change` events; the IDE's own saves aren't reported.
+#[tauri::command]
+pub fn watch_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<WatchStatus, String> {
+    fs.watch_file(&app, &path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn stop_watching_file(fs: State<'_, FileSystemService>, watch_id: String) -> Result<(), String> {
+    fs.stop_watching(&watch_id).map_err(|e| e.to_string())
+}
+
+/// Directory and file watches with their health, for diagnosing missed changes
+#[tauri::command]
+pub fn list_directory_watches(fs: State<'_, FileSystemService>) -> Vec<WatchStatus> {
+    fs.watch_statuses()
+}
+
+#[tauri::command]
+pub fn read_file_content(fs: State<'_, FileSystemService>, path: String) -> Result<FileContent, String> {
+    fs.read_file(&path).map_err(|e| e.to_string())
+}
+
+/// Save a file, failing with `ConflictingChange` when it changed on disk since it was read. The error is
+/// returned as is rather than as a message so the frontend gets the disk content for its merge prompt.
+///
+/// Unless `transform` is false, the save pipeline first runs the formatter when format on save is on, then
+/// trims trailing whitespace, adds a final newline, and normalizes line endings as settings and
+/// `.editorconfig` ask; the result says which steps ran and what they changed.
+#[tauri::command]
+pub async fn write_file_content(
+    app: AppHandle,
+    path: String,
+    content: String,
+    force: Option<bool>,
+    transform: Option<bool>,
+) -> Result<SavedFile, FileSystemError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let transforms = save_pipeline::resolve_transforms(&app, &path);
+        let (content, format, applied) = match transform.unwrap_or(true) {
+            true => {
+                let (formatted, format) = save_pipeline::format(&app, &path, content, &transforms);
+                let (transformed, applied) = save_pipeline::apply(&formatted, &transforms);
+                (transformed, format, applied)
+            }
+            false => (content, None, AppliedTransforms::default()),
+        };
+        let fs = app.state::<FileSystemService>();
+        let result = fs.save_file(&path, &content, force.unwrap_or(false))?;
+        let changed = format.as_ref().is_some_and(|format| format.status == FormatStatus::Formatted)
+            || applied.changed();
+        Ok(SavedFile {
+            result,
+            content: changed.then_some(content),
+            transforms,
+            format,
+            applied,
+        })
+    })
+    .await
+    .map_err(|e| FileSystemError::UnknownError(e.to_string()))?
+}
+
+/// Create a file filled from the file template for its extension, or with just the license header
+#[tauri::command]
+pub fn create_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    path: String,
+) -> Result<FileOperationResult, String> {
+    create_file_from_template(app, fs, undo, path, None).map(|created| created.result)
+}
+
+/// Create a file from the file template for its extension, or else for `language` (the editor's language
+/// id), returning what was written
+#[tauri::command]
+pub fn create_file_from_template(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    path: String,
+    language: Option<String>,
+) -> Result<NewFile, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    let (content, template) = file_templates::render(&app, &path, language.as_deref());
+    let result = fs.create_file(&path, &content).map_err(|e| e.to_string())?;
+    undo.record(FsOperation::Create { path });
+    Ok(NewFile {
+        result,
+        content,
+        template,
+    })
+}
+
+#[tauri::command]
+pub fn create_directory(
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    path: String,
+) -> Result<FileOperationResult, String> {
+    let result = fs.create_directory(&path).map_err(|e| e.to_string())?;
+    undo.record(FsOperation::Create { path });
+    Ok(result)
+}
+
+/// Move a file or directory to the OS trash; unlike the delete commands this can be undone
+#[tauri::command]
+pub fn move_to_trash(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    path: String,
+) -> Result<FileOperationResult, String> {
+    fs.authorize_link(&path).map_err(|e| e.to_string())?;
+    let snapshots = operation_log::snapshot_files(&app, std::slice::from_ref(&path));
+    let result = fs.move_to_trash(&path).map_err(|e| e.to_string())?;
+    undo.record(FsOperation::Trash { path: path.clone() });
+    let record =
+        OperationRecord::new(OperationKind::Delete, vec![path], "move_to_trash").with_snapshots(snapshots);
+    log_operation(&app, record);
+    Ok(result)
+}
+
+/// Delete a file; its content is kept in local history and the deletion is logged
+#[tauri::command]
+pub fn delete_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<FileOperationResult, String> {
+    fs.authorize_link(&path).map_err(|e| e.to_string())?;
+    let snapshots = operation_log::snapshot_files(&app, std::slice::from_ref(&path));
+    let result = fs.delete_file(&path).map_err(|e| e.to_string())?;
+    let record =
+        OperationRecord::new(OperationKind::Delete, vec![path], "delete_file").with_snapshots(snapshots);
+    log_operation(&app, record);
+    Ok(result)
+}
+
+/// Delete a directory and everything in it; the deletion is logged
+#[tauri::command]
+pub fn delete_directory(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<FileOperationResult, String> {
+    let result = fs.delete_directory(&path).map_err(|e| e.to_string())?;
+    log_operation(&app, OperationRecord::new(OperationKind::Delete, vec![path], "delete_directory"));
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn rename_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    old_path: String,
+    new_path: String,
+) -> Result<FileOperationResult, String> {
+    let result = fs.rename(&old_path, &new_path).map_err(|e| e.to_string())?;
+    undo.record(FsOperation::Rename {
+        from: old_path.clone(),
+        to: new_path.clone(),
+    });
+    let record = OperationRecord::new(OperationKind::Rename, vec![old_path], "rename_file")
+        .with_destinations(vec![new_path]);
+    log_operation(&app, record);
+    Ok(result)
+}
+
+/// Rename many entries from a name template. With `dry_run` nothing changes and the plan is returned for a
+/// preview; otherwise every entry is renamed or none is, and the whole batch is one undo step.
+#[tauri::command]
+pub async fn bulk_rename(
+    app: AppHandle,
+    paths: Vec<String>,
+    pattern: RenamePattern,
+    dry_run: Option<bool>,
+) -> Result<BulkRenamePlan, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let fs = app.state::<FileSystemService>();
+        for path in &paths {
+            fs.authorize_link(path).map_err(|e| e.to_string())?;
+        }
+        let mut plan = bulk_rename::plan(&paths, &pattern).map_err(|e| e.to_string())?;
+        if dry_run.unwrap_or(false) || plan.renamed == 0 {
+            return Ok(plan);
+        }
+        for entry in &plan.entries {
+            fs.authorize_link(&entry.new_path).map_err(|e| e.to_string())?;
+        }
+
+        let steps = bulk_rename::apply(&fs, &plan).map_err(|e| e.to_string())?;
+        app.state::<FsUndoService>().record(FsOperation::Batch { operations: steps });
+        let (sources, destinations) = plan
+            .entries
+            .iter()
+            .filter(|entry| entry.status == RenameStatus::Rename)
+            .map(|entry| (entry.path.clone(), entry.new_path.clone()))
+            .unzip();
+        let record =
+            OperationRecord::new(OperationKind::Rename, sources, "bulk_rename").with_destinations(destinations);
+        log_operation(&app, record);
+        plan.applied = true;
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Move a file or directory, e.g. when dragged to another folder in the explorer
+#[tauri::command]
+pub fn move_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+    source: String,
+    destination: String,
+) -> Result<FileOperationResult, String> {
+    let result = fs.rename(&source, &destination).map_err(|e| e.to_string())?;
+    undo.record(FsOperation::Rename {
+        from: source.clone(),
+        to: destination.clone(),
+    });
+    let record = OperationRecord::new(OperationKind::Move, vec![source], "move_file")
+        .with_destinations(vec![destination]);
+    log_operation(&app, record);
+    Ok(result)
+}
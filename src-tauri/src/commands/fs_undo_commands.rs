@@ -0,0 +1,49 @@
+// Undo and redo commands for file explorer operations
+
+use crate::file_system::FileSystemService;
+use crate::fs_undo::{FsOperation, FsUndoService};
+use crate::operation_log::{log_operation, OperationKind, OperationRecord};
+use tauri::{AppHandle, State};
+
+/// Log the trashes and renames that undo and redo perform, like any other destructive operation
+fn log_replayed(app: &AppHandle, operation: &FsOperation, source: &str) {
+    let record = match operation {
+        FsOperation::Trash { path } => OperationRecord::new(OperationKind::Delete, vec![path.clone()], source),
+        FsOperation::Rename { from, to } => OperationRecord::new(OperationKind::Rename, vec![from.clone()], source)
+            .with_destinations(vec![to.clone()]),
+        FsOperation::Batch { operations } => {
+            operations.iter().for_each(|operation| log_replayed(app, operation, source));
+            return;
+        }
+        FsOperation::Create { .. } | FsOperation::Restore { .. } => return,
+    };
+    log_operation(app, record);
+}
+
+/// Reverse the newest explorer operation; returns what was done, or `None` when there is nothing to undo
+#[tauri::command]
+pub fn undo_last_fs_operation(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+) -> Result<Option<FsOperation>, String> {
+    let applied = undo.undo(&fs).map_err(|e| e.to_string())?;
+    if let Some(operation) = &applied {
+        log_replayed(&app, operation, "undo_last_fs_operation");
+    }
+    Ok(applied)
+}
+
+/// Redo the newest undone explorer operation; returns what was done, or `None` when there is nothing to redo
+#[tauri::command]
+pub fn redo_fs_operation(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    undo: State<'_, FsUndoService>,
+) -> Result<Option<FsOperation>, String> {
+    let applied = undo.redo(&fs).map_err(|e| e.to_string())?;
+    if let Some(operation) = &applied {
+        log_replayed(&app, operation, "redo_fs_operation");
+    }
+    Ok(applied)
+}
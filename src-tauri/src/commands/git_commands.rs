@@ -0,0 +1,279 @@
+// Git commands backed by the libgit2 GitService
+
+use crate::file_system::FileSystemService;
+use crate::git::{
+    BlameRange, BranchInfo, CommitDetails, CommitNode, ConflictVersions, ConflictedFile, FetchSummary, GitCredentials,
+    GitHunk, GitService, GitignoreUpdate, MergeOutcome, RemoteInfo, RepositoryStatus,
+};
+use tauri::{AppHandle, Manager, State};
+
+/// Status of every independent repository found under a workspace
+#[tauri::command]
+pub fn git_workspace_repositories(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    workspace: String,
+) -> Result<Vec<RepositoryStatus>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    git.workspace_repositories_status(&workspace).map_err(|e| e.to_string())
+}
+
+/// Files with unresolved merge conflicts in the repository containing `path`
+#[tauri::command]
+pub fn git_conflicted_files(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<Vec<ConflictedFile>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.conflicted_files(&path).map_err(|e| e.to_string())
+}
+
+/// Base, ours, and theirs versions of a conflicted file
+#[tauri::command]
+pub fn git_conflict_versions(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<ConflictVersions, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.conflict_versions(&path).map_err(|e| e.to_string())
+}
+
+/// Per-line-range blame for a file
+#[tauri::command]
+pub fn git_blame(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<Vec<BlameRange>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.blame(&path).map_err(|e| e.to_string())
+}
+
+/// Local (and optionally remote-tracking) branches
+#[tauri::command]
+pub fn git_list_branches(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    include_remote: Option<bool>,
+) -> Result<Vec<BranchInfo>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.list_branches(&path, include_remote.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Create a branch, optionally from a start point and checking it out
+#[tauri::command]
+pub fn git_create_branch(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+    start_point: Option<String>,
+    checkout: Option<bool>,
+) -> Result<BranchInfo, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.create_branch(&path, &name, start_point.as_deref(), checkout.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Switch branches; fails on uncommitted changes unless forced
+#[tauri::command]
+pub fn git_checkout(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.checkout(&path, &name, force.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a local branch; unmerged branches require `force`
+#[tauri::command]
+pub fn git_delete_branch(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.delete_branch(&path, &name, force.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Merge a branch into the current branch
+#[tauri::command]
+pub fn git_merge_branch(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+) -> Result<MergeOutcome, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.merge_branch(&path, &name).map_err(|e| e.to_string())
+}
+
+/// Commit history with graph lanes for the log viewer
+#[tauri::command]
+pub fn git_commit_graph(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    limit: usize,
+    branch_filter: Option<String>,
+) -> Result<Vec<CommitNode>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.commit_graph(&path, limit, branch_filter.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Metadata and full diff of a commit
+#[tauri::command]
+pub fn git_commit_details(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    hash: String,
+) -> Result<CommitDetails, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.commit_details(&path, &hash).map_err(|e| e.to_string())
+}
+
+/// Configured remotes of a repository
+#[tauri::command]
+pub fn git_list_remotes(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<Vec<RemoteInfo>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.list_remotes(&path).map_err(|e| e.to_string())
+}
+
+/// Add a remote
+#[tauri::command]
+pub fn git_add_remote(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+    url: String,
+) -> Result<RemoteInfo, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.add_remote(&path, &name, &url).map_err(|e| e.to_string())
+}
+
+/// Remove a remote
+#[tauri::command]
+pub fn git_remove_remote(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    name: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.remove_remote(&path, &name).map_err(|e| e.to_string())
+}
+
+/// Fetch a remote off the main thread; progress streams as `git://fetch-progress` events
+#[tauri::command]
+pub async fn git_fetch(
+    app: AppHandle,
+    path: String,
+    remote: String,
+    credentials: Option<GitCredentials>,
+) -> Result<FetchSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<FileSystemService>().authorize(&path).map_err(|e| e.to_string())?;
+        app.state::<GitService>()
+            .fetch(&app, &path, &remote, credentials.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Clone a repository in the background; progress and completion stream as `git://clone-progress` events
+#[tauri::command]
+pub fn git_clone(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    url: String,
+    destination: String,
+    credentials: Option<GitCredentials>,
+) -> Result<String, String> {
+    fs.authorize(&destination).map_err(|e| e.to_string())?;
+    git.clone_repository(&app, &url, &destination, credentials.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a running clone
+#[tauri::command]
+pub fn git_cancel_clone(git: State<'_, GitService>, clone_id: String) -> Result<(), String> {
+    git.cancel_clone(&clone_id).map_err(|e| e.to_string())
+}
+
+/// Unstaged hunks of a file
+#[tauri::command]
+pub fn git_diff_hunks(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<Vec<GitHunk>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.diff_hunks(&path).map_err(|e| e.to_string())
+}
+
+/// Stage a single hunk of a file
+#[tauri::command]
+pub fn git_stage_hunk(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    hunk_id: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.stage_hunk(&path, &hunk_id).map_err(|e| e.to_string())
+}
+
+/// Discard a single hunk of a file from the working tree
+#[tauri::command]
+pub fn git_revert_hunk(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+    hunk_id: String,
+) -> Result<(), String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.revert_hunk(&path, &hunk_id).map_err(|e| e.to_string())
+}
+
+/// Add a path or pattern to the nearest .gitignore
+#[tauri::command]
+pub fn add_to_gitignore(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    workspace: String,
+    pattern: String,
+) -> Result<GitignoreUpdate, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    git.add_to_gitignore(&workspace, &pattern).map_err(|e| e.to_string())
+}
+
+/// Whether git ignores a path
+#[tauri::command]
+pub fn is_path_ignored(
+    fs: State<'_, FileSystemService>,
+    git: State<'_, GitService>,
+    path: String,
+) -> Result<bool, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    git.is_path_ignored(&path).map_err(|e| e.to_string())
+}
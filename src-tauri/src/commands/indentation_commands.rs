@@ -0,0 +1,48 @@
+// Indentation commands for "Convert Indentation"
+
+use crate::file_stats;
+use crate::file_system::FileSystemService;
+use crate::indentation::{self, IndentStyle, Indentation, IndentationConversion};
+use crate::operation_log::{self, log_operation, OperationKind, OperationRecord};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Whether a file is indented with tabs or spaces, and how many; `None` when no line is indented
+#[tauri::command]
+pub fn detect_indentation(
+    fs: State<'_, FileSystemService>,
+    path: String,
+) -> Result<Option<Indentation>, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    let stats = file_stats::file_stats(Path::new(&path)).map_err(|e| e.to_string())?;
+    Ok(stats.indentation)
+}
+
+/// Rewrite a file's leading whitespace to tabs or to `width` spaces. The previous content is kept in local
+/// history and the change is logged.
+#[tauri::command]
+pub fn convert_indentation(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    path: String,
+    style: IndentStyle,
+    width: usize,
+) -> Result<IndentationConversion, String> {
+    let file = fs.read_file(&path).map_err(|e| e.to_string())?;
+    if file.is_binary {
+        return Err(format!("Cannot convert the indentation of a binary file: {}", path));
+    }
+    let (content, changed_lines) = indentation::convert_indentation(&file.content, style, width);
+    if changed_lines > 0 {
+        let snapshots = operation_log::snapshot_files(&app, std::slice::from_ref(&path));
+        fs.save_file(&path, &content, false).map_err(|e| e.to_string())?;
+        let record = OperationRecord::new(OperationKind::BulkReplace, vec![path.clone()], "convert_indentation")
+            .with_snapshots(snapshots);
+        log_operation(&app, record);
+    }
+    Ok(IndentationConversion {
+        indentation: indentation::detect_indentation(&content),
+        path,
+        changed_lines,
+    })
+}
@@ -0,0 +1,37 @@
+// Keybinding commands backed by the KeymapService
+
+use crate::keymap::{self, Keymap, KeymapService};
+use tauri::{AppHandle, State};
+
+/// Active keybindings with user overrides applied, plus conflicts
+#[tauri::command]
+pub fn get_keybindings(app: AppHandle, keymap: State<'_, KeymapService>) -> Result<Keymap, String> {
+    keymap.keybindings(&app).map_err(|e| e.to_string())
+}
+
+/// Bind a command to a key chord, or unbind it when `key` is omitted; emits `keymap://changed`
+#[tauri::command]
+pub fn update_keybinding(
+    app: AppHandle,
+    keymap: State<'_, KeymapService>,
+    command: String,
+    key: Option<String>,
+    when: Option<String>,
+    replace_conflicts: Option<bool>,
+) -> Result<Keymap, String> {
+    keymap
+        .update_keybinding(&app, &command, key.as_deref(), when.as_deref(), replace_conflicts.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Restore the default binding of a command; emits `keymap://changed`
+#[tauri::command]
+pub fn reset_keybinding(app: AppHandle, keymap: State<'_, KeymapService>, command: String) -> Result<Keymap, String> {
+    keymap.reset_keybinding(&app, &command).map_err(|e| e.to_string())
+}
+
+/// Normalize a key chord, e.g. `ctrl+shift+p` to `Ctrl+Shift+P`, or explain why it is invalid
+#[tauri::command]
+pub fn validate_key_chord(chord: String) -> Result<String, String> {
+    keymap::normalize_chord(&chord).map_err(|e| e.to_string())
+}
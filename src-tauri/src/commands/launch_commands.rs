@@ -0,0 +1,24 @@
+// Run configuration commands for the run panel
+
+use crate::launch::{LaunchConfig, LaunchConfigService, ResolvedLaunchConfig};
+use tauri::State;
+
+/// Configurations from `.codeforge/launch.json`, each with any validation problems
+#[tauri::command]
+pub fn list_launch_configs(
+    launch: State<'_, LaunchConfigService>,
+    workspace: String,
+) -> Result<Vec<LaunchConfig>, String> {
+    launch.list_configs(&workspace).map_err(|e| e.to_string())
+}
+
+/// Substitute `${workspaceFolder}`, `${file}`, and friends, returning a request for `debug_start`
+#[tauri::command]
+pub fn resolve_launch_config(
+    launch: State<'_, LaunchConfigService>,
+    workspace: String,
+    name: String,
+    active_file: Option<String>,
+) -> Result<ResolvedLaunchConfig, String> {
+    launch.resolve_config(&workspace, &name, active_file.as_deref()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,62 @@
+// License header commands for finding and fixing missing or outdated headers
+
+use crate::file_system::FileSystemService;
+use crate::license_headers::{self, LicenseHeader, LicenseHeaderReport};
+use crate::operation_log::{self, log_operation, OperationKind, OperationRecord};
+use crate::workspace_edit::{self, WorkspaceEditResult};
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+
+/// Source files in the workspace whose license header is missing or differs from the `license_header`
+/// preference
+#[tauri::command]
+pub async fn check_license_headers(app: AppHandle, workspace: String) -> Result<LicenseHeaderReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = app.state::<FileSystemService>().authorize(&workspace).map_err(|e| e.to_string())?;
+        let header = LicenseHeader::configured(&app, &root).map_err(|e| e.to_string())?;
+        Ok(license_headers::check_workspace(&root, &header))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Put the configured header into `paths`, all or nothing, replacing outdated headers. Files that already
+/// have it are left alone. Changed files are kept in local history first and the change is logged.
+#[tauri::command]
+pub async fn apply_license_headers(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+    paths: Vec<String>,
+) -> Result<WorkspaceEditResult, String> {
+    let root = fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    for path in &paths {
+        fs.authorize(path).map_err(|e| e.to_string())?;
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let header = LicenseHeader::configured(&app, &root).map_err(|e| e.to_string())?;
+        let mut edits = Vec::new();
+        for path in &paths {
+            if let Some(edit) = license_headers::header_edit(Path::new(path), &root, &header)
+                .map_err(|e| e.to_string())?
+            {
+                edits.push(edit);
+            }
+        }
+        if edits.is_empty() {
+            return Ok(WorkspaceEditResult::default());
+        }
+
+        let changed: Vec<String> = edits.iter().flat_map(|edit| edit.paths()).map(str::to_string).collect();
+        let snapshots = operation_log::snapshot_files(&app, &changed);
+        let result = workspace_edit::apply_workspace_edit(&edits).map_err(|e| e.to_string())?;
+        let record =
+            OperationRecord::new(OperationKind::BulkReplace, result.edited.clone(), "apply_license_headers")
+                .with_snapshots(snapshots);
+        log_operation(&app, record);
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,29 @@
+// Backend log commands for the Output panel
+
+use crate::logging::{LogEntry, LogFilter, LogLevel, LogLevels, LogService};
+use tauri::State;
+
+/// The last `tail` log entries matching `filter`, oldest first
+#[tauri::command]
+pub fn get_log_entries(
+    logs: State<'_, LogService>,
+    filter: Option<LogFilter>,
+    tail: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    logs.entries(&filter.unwrap_or_default(), tail).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_log_levels(logs: State<'_, LogService>) -> LogLevels {
+    logs.levels()
+}
+
+/// Change the level of all logs, or of one subsystem such as `git`; no level resets it
+#[tauri::command]
+pub fn set_log_level(
+    logs: State<'_, LogService>,
+    level: Option<LogLevel>,
+    subsystem: Option<String>,
+) -> Result<LogLevels, String> {
+    logs.set_level(level, subsystem.as_deref()).map_err(|e| e.to_string())
+}
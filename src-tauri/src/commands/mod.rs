@@ -0,0 +1,108 @@
+// CodeForge IDE - Tauri command handlers
+// Commands are thin wrappers that delegate to the backend services
+
+mod activity_commands;
+mod autosave_commands;
+mod backup_commands;
+mod bookmark_commands;
+mod breakpoint_commands;
+mod clipboard_commands;
+mod command_registry_commands;
+mod crash_report_commands;
+mod database_commands;
+mod debug_commands;
+mod decoration_commands;
+mod desktop_commands;
+mod devcontainer_commands;
+mod diagnostics_commands;
+mod diff_commands;
+mod environment_commands;
+mod file_clipboard_commands;
+mod file_history_commands;
+mod file_lock_commands;
+mod file_system_commands;
+mod fs_undo_commands;
+mod git_commands;
+mod indentation_commands;
+mod keymap_commands;
+mod launch_commands;
+mod license_header_commands;
+mod log_commands;
+mod notification_commands;
+mod operation_log_commands;
+mod path_commands;
+mod performance_commands;
+mod plugin_commands;
+mod port_commands;
+mod preview_commands;
+mod project_template_commands;
+mod recent_commands;
+mod regex_commands;
+mod resource_commands;
+mod rest_client_commands;
+mod session_commands;
+mod settings_commands;
+mod snippet_commands;
+mod spellcheck_commands;
+mod syntax_commands;
+mod system_commands;
+mod tail_commands;
+mod task_commands;
+mod terminal_commands;
+mod theme_commands;
+mod window_commands;
+mod workspace_edit_commands;
+mod workspace_stats_commands;
+
+pub use activity_commands::*;
+pub use autosave_commands::*;
+pub use backup_commands::*;
+pub use bookmark_commands::*;
+pub use breakpoint_commands::*;
+pub use clipboard_commands::*;
+pub use command_registry_commands::*;
+pub use crash_report_commands::*;
+pub use database_commands::*;
+pub use debug_commands::*;
+pub use decoration_commands::*;
+pub use desktop_commands::*;
+pub use devcontainer_commands::*;
+pub use diagnostics_commands::*;
+pub use diff_commands::*;
+pub use environment_commands::*;
+pub use file_clipboard_commands::*;
+pub use file_history_commands::*;
+pub use file_lock_commands::*;
+pub use file_system_commands::*;
+pub use fs_undo_commands::*;
+pub use git_commands::*;
+pub use indentation_commands::*;
+pub use keymap_commands::*;
+pub use launch_commands::*;
+pub use license_header_commands::*;
+pub use log_commands::*;
+pub use notification_commands::*;
+pub use operation_log_commands::*;
+pub use path_commands::*;
+pub use performance_commands::*;
+pub use plugin_commands::*;
+pub use port_commands::*;
+pub use preview_commands::*;
+pub use project_template_commands::*;
+pub use recent_commands::*;
+pub use regex_commands::*;
+pub use resource_commands::*;
+pub use rest_client_commands::*;
+pub use session_commands::*;
+pub use settings_commands::*;
+pub use snippet_commands::*;
+pub use spellcheck_commands::*;
+pub use syntax_commands::*;
+pub use system_commands::*;
+pub use tail_commands::*;
+pub use task_commands::*;
+pub use terminal_commands::*;
+pub use theme_commands::*;
+pub use window_commands::*;
+pub use workspace_edit_commands::*;
+pub use workspace_stats_commands::*;
@@ -0,0 +1,29 @@
+// Notification commands
+
+use crate::notifications::{Notification, NotificationService};
+use tauri::{AppHandle, State};
+
+/// Notifications to show when a window opens; later changes arrive as `notification://update` events
+#[tauri::command]
+pub fn list_notifications(notifications: State<'_, NotificationService>) -> Vec<Notification> {
+    notifications.list()
+}
+
+/// Ask the work behind a progress notification to stop
+#[tauri::command]
+pub fn cancel_notification(
+    app: AppHandle,
+    notifications: State<'_, NotificationService>,
+    id: String,
+) -> Result<(), String> {
+    notifications.cancel(&app, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn dismiss_notification(
+    app: AppHandle,
+    notifications: State<'_, NotificationService>,
+    id: String,
+) -> Result<(), String> {
+    notifications.dismiss(&app, &id).map_err(|e| e.to_string())
+}
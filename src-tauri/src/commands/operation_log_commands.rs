@@ -0,0 +1,20 @@
+// Operation log commands
+
+use crate::operation_log::{OperationLogService, OperationRecord};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Logged deletes, overwrites, renames, moves, and bulk replaces in a workspace, newest first. Without a
+/// workspace, operations on paths outside every workspace are returned.
+#[tauri::command]
+pub fn get_operation_log(
+    app: AppHandle,
+    log: State<'_, OperationLogService>,
+    workspace: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<OperationRecord>, String> {
+    // Logs are keyed by the resolved workspace root
+    let workspace =
+        workspace.map(|workspace| Path::new(&workspace).canonicalize().unwrap_or_else(|_| workspace.into()));
+    log.operations(&app, workspace.as_deref(), limit).map_err(|e| e.to_string())
+}
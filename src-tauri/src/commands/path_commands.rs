@@ -0,0 +1,75 @@
+// Path utility commands, so the frontend handles paths the same way the backend does
+
+use crate::environment;
+use crate::file_system::FileSystemService;
+use crate::globs::{self, GlobExpansion, GlobMatcher, GlobOptions};
+use crate::path_utils::{self, ExpandedPath, FileNameCheck, PathError, Platform, SeparatorStyle};
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+
+#[tauri::command]
+pub fn normalize_path(path: String) -> String {
+    path_utils::normalize(Path::new(&path)).to_string_lossy().to_string()
+}
+
+/// `path` relative to `base`, or to the workspace folder containing it when `base` is left out
+#[tauri::command]
+pub fn relativize_path(
+    fs: State<'_, FileSystemService>,
+    path: String,
+    base: Option<String>,
+) -> Result<String, String> {
+    let base = match base {
+        Some(base) => base.into(),
+        None => fs.workspace_root(&path).ok_or_else(|| PathError::NotInWorkspace(path.clone()).to_string())?,
+    };
+    path_utils::relativize(Path::new(&path), &base).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn join_paths(base: String, segments: Vec<String>) -> String {
+    path_utils::join(Path::new(&base), &segments).to_string_lossy().to_string()
+}
+
+/// Expand `~` and environment variables; with a workspace, its env files take precedence over the IDE's
+/// own environment
+#[tauri::command]
+pub fn expand_path(path: String, workspace: Option<String>) -> Result<ExpandedPath, String> {
+    let env = match workspace {
+        Some(workspace) => environment::workspace_env(&workspace).map_err(|e| e.to_string())?,
+        None => Default::default(),
+    };
+    Ok(path_utils::expand(&path, &env))
+}
+
+#[tauri::command]
+pub fn convert_path_separators(path: String, style: SeparatorStyle) -> String {
+    path_utils::convert_separators(&path, style)
+}
+
+/// Whether `name` can be used as a file name on each of `platforms`, or on all of them when left out
+#[tauri::command]
+pub fn validate_file_name(name: String, platforms: Option<Vec<Platform>>) -> FileNameCheck {
+    path_utils::validate_file_name(&name, platforms.as_deref().unwrap_or(&Platform::ALL))
+}
+
+/// Entries under `root` matching any of `patterns` and none of `exclude`, relative to `root`. Only the
+/// directories the patterns can match in are walked, and excluded directories are skipped whole.
+#[tauri::command]
+pub async fn expand_glob(
+    app: AppHandle,
+    root: String,
+    patterns: Vec<String>,
+    exclude: Option<Vec<String>>,
+    options: Option<GlobOptions>,
+) -> Result<GlobExpansion, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = app.state::<FileSystemService>().authorize(&root).map_err(|e| e.to_string())?;
+        let options = options.unwrap_or_default();
+        let matcher = GlobMatcher::new(&patterns, &exclude.unwrap_or_default(), options.case_insensitive)
+            .map_err(|e| e.to_string())?;
+        Ok(globs::expand(&root, &matcher, &options))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,15 @@
+// IPC performance statistics commands
+
+use crate::performance::{CommandStats, PerformanceService};
+use tauri::State;
+
+/// Invocation counts, latency histograms, and payload sizes per command, slowest in total first
+#[tauri::command]
+pub fn get_performance_stats(performance: State<'_, PerformanceService>) -> Vec<CommandStats> {
+    performance.stats()
+}
+
+#[tauri::command]
+pub fn reset_performance_stats(performance: State<'_, PerformanceService>) {
+    performance.reset();
+}
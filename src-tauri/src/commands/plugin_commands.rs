@@ -0,0 +1,93 @@
+// Plugin host commands; loading and calling plugins runs off the main thread
+
+use crate::plugins::{
+    ExtensionSummary, PluginCommand, PluginError, PluginFileSystem, PluginInfo, PluginScanResult, PluginService,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+/// Run a plugin call on the blocking thread pool
+async fn with_plugins<T: Send + 'static>(
+    app: AppHandle,
+    call: impl FnOnce(&AppHandle, &PluginService) -> Result<T, PluginError> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        call(&app, &app.state::<PluginService>()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Discover installed plugins and activate those that run at startup or match the workspace
+#[tauri::command]
+pub async fn scan_plugins(app: AppHandle, workspace: Option<String>) -> Result<PluginScanResult, String> {
+    with_plugins(app, move |app, plugins| plugins.scan(app, workspace.map(PathBuf::from))).await
+}
+
+#[tauri::command]
+pub fn list_plugins(plugins: State<'_, PluginService>) -> Result<Vec<PluginInfo>, String> {
+    Ok(plugins.list())
+}
+
+#[tauri::command]
+pub async fn activate_plugin(app: AppHandle, id: String) -> Result<PluginInfo, String> {
+    with_plugins(app, move |app, plugins| plugins.activate(app, &id)).await
+}
+
+/// Commands plugins contribute to the command palette
+#[tauri::command]
+pub fn get_plugin_commands(plugins: State<'_, PluginService>) -> Result<Vec<PluginCommand>, String> {
+    Ok(plugins.contributed_commands())
+}
+
+/// URI schemes plugins serve, whose paths every file command accepts
+#[tauri::command]
+pub fn get_plugin_file_systems(plugins: State<'_, PluginService>) -> Result<Vec<PluginFileSystem>, String> {
+    Ok(plugins.contributed_file_systems())
+}
+
+/// Tell plugins a file was opened, activating `onFileOpen` and `onLanguage` plugins
+#[tauri::command]
+pub async fn notify_file_opened(app: AppHandle, path: String, language: Option<String>) -> Result<(), String> {
+    with_plugins(app, move |app, plugins| {
+        plugins.file_opened(app, &path, language.as_deref());
+        Ok(())
+    })
+    .await
+}
+
+/// Run a plugin-contributed command; JSON arguments are passed to the plugin as text
+#[tauri::command]
+pub async fn execute_plugin_command(
+    app: AppHandle,
+    command: String,
+    args: Option<Value>,
+) -> Result<Option<String>, String> {
+    let args = args.map(|args| args.to_string()).unwrap_or_default();
+    with_plugins(app, move |app, plugins| plugins.execute_command(app, &command, &args)).await
+}
+
+/// Search the configured extension registry
+#[tauri::command]
+pub async fn search_extensions(app: AppHandle, query: String) -> Result<Vec<ExtensionSummary>, String> {
+    PluginService::search_extensions(&app, &query).await.map_err(|e| e.to_string())
+}
+
+/// Download, verify, and install an extension; installs the latest version when `version` is omitted
+#[tauri::command]
+pub async fn install_extension(
+    app: AppHandle,
+    id: String,
+    version: Option<String>,
+) -> Result<PluginInfo, String> {
+    PluginService::install_extension(&app, &id, version.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Unload an extension and remove every installed version of it
+#[tauri::command]
+pub async fn uninstall_extension(app: AppHandle, id: String) -> Result<(), String> {
+    with_plugins(app, move |app, plugins| plugins.uninstall(app, &id)).await
+}
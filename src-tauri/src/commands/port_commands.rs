@@ -0,0 +1,70 @@
+// Ports panel commands
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::file_system::FileSystemService;
+use crate::port_forwarding::{DetectedPort, PortForward, PortService};
+use crate::ports::{self, ListeningPort};
+use crate::terminal::TerminalService;
+
+/// Listening TCP ports with their owning processes
+#[tauri::command]
+pub fn list_listening_ports() -> Result<Vec<ListeningPort>, String> {
+    ports::list_listening_ports()
+}
+
+/// Terminate the process listening on a port
+#[tauri::command]
+pub fn kill_port_process(pid: u32) -> Result<(), String> {
+    ports::kill_process(pid)
+}
+
+/// Listening ports, with the terminal or task that opened them and their labels
+#[tauri::command]
+pub fn list_ports(
+    ports: State<'_, PortService>,
+    terminal: State<'_, TerminalService>,
+) -> Result<Vec<DetectedPort>, String> {
+    ports.list_ports(&terminal).map_err(|e| e.to_string())
+}
+
+/// Name a port, or clear its name with `None`
+#[tauri::command]
+pub fn set_port_label(ports: State<'_, PortService>, port: u16, label: Option<String>) {
+    ports.set_label(port, label);
+}
+
+/// Forward a local port to a port on a workspace's machine
+#[tauri::command]
+pub async fn forward_port(
+    app: AppHandle,
+    workspace: Option<String>,
+    remote_port: u16,
+    local_port: Option<u16>,
+    label: Option<String>,
+) -> Result<PortForward, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let fs = app.state::<FileSystemService>();
+        app.state::<PortService>()
+            .forward(&fs, workspace.as_deref(), remote_port, local_port, label)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn stop_port_forward(ports: State<'_, PortService>, id: String) -> Result<(), String> {
+    ports.stop_forward(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_port_forwards(ports: State<'_, PortService>) -> Vec<PortForward> {
+    ports.list_forwards()
+}
+
+/// Open a port in the default browser, returning the URL opened
+#[tauri::command]
+pub fn open_port_in_browser(app: AppHandle, ports: State<'_, PortService>, port: u16) -> Result<String, String> {
+    ports.open_in_browser(&app, port).map_err(|e| e.to_string())
+}
@@ -0,0 +1,27 @@
+// Preview server commands
+
+use crate::file_system::FileSystemService;
+use crate::preview_server::{PreviewServerInfo, PreviewServerService};
+use tauri::State;
+
+/// Serve a workspace directory on localhost with live reload; any free port when `port` is omitted
+#[tauri::command]
+pub fn start_preview_server(
+    fs: State<'_, FileSystemService>,
+    previews: State<'_, PreviewServerService>,
+    root: String,
+    port: Option<u16>,
+) -> Result<PreviewServerInfo, String> {
+    let root = fs.authorize(&root).map_err(|e| e.to_string())?;
+    previews.start(&root, port).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn stop_preview_server(previews: State<'_, PreviewServerService>, port: u16) -> Result<(), String> {
+    previews.stop(port).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_preview_servers(previews: State<'_, PreviewServerService>) -> Vec<PreviewServerInfo> {
+    previews.list()
+}
@@ -0,0 +1,34 @@
+// Project template commands backed by the ProjectTemplateService
+
+use crate::file_system::FileSystemService;
+use crate::project_templates::{CreatedProject, ProjectTemplate, ProjectTemplateService};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, State};
+
+/// Built-in and user templates for the New Project dialog
+#[tauri::command]
+pub fn list_project_templates(
+    app: AppHandle,
+    templates: State<'_, ProjectTemplateService>,
+) -> Result<Vec<ProjectTemplate>, String> {
+    templates.list_templates(&app).map_err(|e| e.to_string())
+}
+
+/// Create a project from a template in `destination`, which must be in scope (e.g. allowed after the user
+/// picked it) and must not exist or be empty. Variables left out take the template's defaults.
+#[tauri::command]
+pub async fn create_project_from_template(
+    app: AppHandle,
+    template_id: String,
+    destination: String,
+    variables: Option<BTreeMap<String, String>>,
+) -> Result<CreatedProject, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let destination = app.state::<FileSystemService>().authorize(&destination).map_err(|e| e.to_string())?;
+        app.state::<ProjectTemplateService>()
+            .create_project(&app, &template_id, &destination, variables.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,82 @@
+// Recently opened files and workspaces commands
+
+use crate::recent::{RecentEntry, RecentKind, RecentService};
+use tauri::{AppHandle, State};
+
+/// Record that a file was opened, optionally within a workspace
+#[tauri::command]
+pub fn record_recent_file(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    path: String,
+    workspace: Option<String>,
+) -> Result<(), String> {
+    recent
+        .record(&app, RecentKind::File, &path, workspace.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Record that a workspace was opened
+#[tauri::command]
+pub fn record_recent_workspace(app: AppHandle, recent: State<'_, RecentService>, path: String) -> Result<(), String> {
+    recent.record(&app, RecentKind::Workspace, &path, None).map_err(|e| e.to_string())
+}
+
+/// Recently opened files, pinned first, optionally restricted to one workspace
+#[tauri::command]
+pub fn get_recent_files(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    workspace: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentEntry>, String> {
+    recent
+        .recent_files(&app, workspace.as_deref(), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// Recently opened workspaces, pinned first
+#[tauri::command]
+pub fn get_recent_workspaces(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentEntry>, String> {
+    recent.recent_workspaces(&app, limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
+/// Pin or unpin a recent file or workspace
+#[tauri::command]
+pub fn pin_recent_entry(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    kind: RecentKind,
+    path: String,
+    pinned: bool,
+) -> Result<(), String> {
+    recent.set_pinned(&app, kind, &path, pinned).map_err(|e| e.to_string())
+}
+
+/// Remove one entry from the recent history
+#[tauri::command]
+pub fn remove_recent_entry(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    kind: RecentKind,
+    path: String,
+) -> Result<(), String> {
+    recent.remove(&app, kind, &path).map_err(|e| e.to_string())
+}
+
+/// Clear recent history (both lists when `kind` is omitted), keeping pinned entries unless `include_pinned`
+#[tauri::command]
+pub fn clear_history(
+    app: AppHandle,
+    recent: State<'_, RecentService>,
+    kind: Option<RecentKind>,
+    include_pinned: Option<bool>,
+) -> Result<(), String> {
+    recent
+        .clear(&app, kind, include_pinned.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,9 @@
+// Regex playground commands
+
+use crate::regex_tester::{self, RegexTestOptions, RegexTestResult};
+
+/// Evaluate a pattern against sample text, reporting matches, groups, and pattern errors
+#[tauri::command]
+pub fn test_regex(pattern: String, text: String, options: Option<RegexTestOptions>) -> RegexTestResult {
+    regex_tester::test_regex(&pattern, &text, &options.unwrap_or_default())
+}
@@ -0,0 +1,12 @@
+// Process Explorer commands
+
+use crate::resource_usage::{self, ResourceUsage};
+use tauri::AppHandle;
+
+/// Memory and threads of the backend and its child processes, with open watchers and sessions
+#[tauri::command]
+pub async fn get_resource_usage(app: AppHandle) -> Result<ResourceUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || resource_usage::resource_usage(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
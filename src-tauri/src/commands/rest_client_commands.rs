@@ -0,0 +1,25 @@
+// REST client commands for `.http` / `.rest` files
+
+use crate::rest_client::{self, HttpClientOptions, HttpClientService, HttpFile, HttpResponse};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Requests and variables declared in a request file
+#[tauri::command]
+pub fn parse_http_requests(content: String) -> HttpFile {
+    rest_client::parse_http_file(&content)
+}
+
+/// Execute the `index`-th request of a request file; `environment` overrides file variables
+#[tauri::command]
+pub async fn send_http_request(
+    http: State<'_, HttpClientService>,
+    content: String,
+    index: usize,
+    environment: Option<HashMap<String, String>>,
+    options: Option<HttpClientOptions>,
+) -> Result<HttpResponse, String> {
+    http.send_request(&content, index, &environment.unwrap_or_default(), &options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,91 @@
+// Workspace session persistence and navigation history commands
+
+use crate::file_system::FileSystemService;
+use crate::navigation::{NavigationLocation, NavigationState};
+use crate::session::{SessionService, WorkspaceSession};
+use tauri::{AppHandle, State};
+
+/// Persist open tabs, cursor positions, and layout of a workspace
+#[tauri::command]
+pub fn save_session(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    session: WorkspaceSession,
+) -> Result<(), String> {
+    fs.authorize(&session.workspace).map_err(|e| e.to_string())?;
+    sessions.save_session(&app, session).map_err(|e| e.to_string())
+}
+
+/// Saved session of a workspace, if any
+#[tauri::command]
+pub fn restore_session(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+) -> Result<Option<WorkspaceSession>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.restore_session(&app, &workspace).map_err(|e| e.to_string())
+}
+
+/// Discard the saved session of a workspace
+#[tauri::command]
+pub fn clear_session(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+) -> Result<(), String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.clear_session(&app, &workspace).map_err(|e| e.to_string())
+}
+
+/// Record a location the editor moved to, whether by the user or a language server jump
+#[tauri::command]
+pub fn push_location(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+    location: NavigationLocation,
+) -> Result<NavigationState, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.push_location(&app, &workspace, location).map_err(|e| e.to_string())
+}
+
+/// Go Back; `None` when already at the oldest location
+#[tauri::command]
+pub fn navigate_back(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+) -> Result<Option<NavigationState>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.navigate(&app, &workspace, -1).map_err(|e| e.to_string())
+}
+
+/// Go Forward; `None` when already at the newest location
+#[tauri::command]
+pub fn navigate_forward(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+) -> Result<Option<NavigationState>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.navigate(&app, &workspace, 1).map_err(|e| e.to_string())
+}
+
+/// Current location and whether Go Back and Go Forward are available, e.g. after a window reload
+#[tauri::command]
+pub fn get_navigation_state(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    sessions: State<'_, SessionService>,
+    workspace: String,
+) -> Result<NavigationState, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    sessions.navigation_state(&app, &workspace).map_err(|e| e.to_string())
+}
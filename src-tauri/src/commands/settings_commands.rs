@@ -0,0 +1,40 @@
+// Preferences commands backed by the SettingsService
+
+use crate::settings::{EffectiveSettings, SettingsScope, SettingsService};
+use crate::types::AppPreferences;
+use tauri::{AppHandle, State};
+
+/// Current application preferences
+#[tauri::command]
+pub fn get_preferences(app: AppHandle, settings: State<'_, SettingsService>) -> Result<AppPreferences, String> {
+    settings.get_preferences(&app).map_err(|e| e.to_string())
+}
+
+/// Apply a partial preferences update; emits `settings://preferences-changed`
+#[tauri::command]
+pub fn update_preferences(
+    app: AppHandle,
+    settings: State<'_, SettingsService>,
+    preferences: serde_json::Value,
+) -> Result<AppPreferences, String> {
+    settings.update_preferences(&app, preferences).map_err(|e| e.to_string())
+}
+
+/// Restore default preferences; emits `settings://preferences-changed`
+#[tauri::command]
+pub fn reset_preferences(app: AppHandle, settings: State<'_, SettingsService>) -> Result<AppPreferences, String> {
+    settings.reset_preferences(&app).map_err(|e| e.to_string())
+}
+
+/// Settings merged from defaults, user, and workspace layers, with the source of each value
+#[tauri::command]
+pub fn get_effective_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsService>,
+    scope: SettingsScope,
+    workspace: Option<String>,
+) -> Result<EffectiveSettings, String> {
+    settings
+        .effective_settings(&app, scope, workspace.as_deref())
+        .map_err(|e| e.to_string())
+}
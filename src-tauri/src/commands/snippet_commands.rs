@@ -0,0 +1,41 @@
+// User snippet commands backed by the SnippetService
+
+use crate::snippets::{self, ParsedSnippet, Snippet, SnippetInput, SnippetService};
+use tauri::{AppHandle, State};
+
+/// Snippets for a language plus the global snippets that apply to it
+#[tauri::command]
+pub fn get_snippets(
+    app: AppHandle,
+    snippets: State<'_, SnippetService>,
+    language: String,
+) -> Result<Vec<Snippet>, String> {
+    snippets.get_snippets(&app, &language).map_err(|e| e.to_string())
+}
+
+/// Add or replace a snippet in `<language>.json`, or in `global.json` when `language` is `global`
+#[tauri::command]
+pub fn save_snippet(
+    app: AppHandle,
+    snippets: State<'_, SnippetService>,
+    language: String,
+    snippet: SnippetInput,
+) -> Result<Snippet, String> {
+    snippets.save_snippet(&app, &language, snippet).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_snippet(
+    app: AppHandle,
+    snippets: State<'_, SnippetService>,
+    language: String,
+    name: String,
+) -> Result<(), String> {
+    snippets.delete_snippet(&app, &language, &name).map_err(|e| e.to_string())
+}
+
+/// Parse a snippet body without saving it, e.g. for previews while editing
+#[tauri::command]
+pub fn parse_snippet_body(body: String) -> Result<ParsedSnippet, String> {
+    snippets::parse_snippet(&body).map_err(|e| e.to_string())
+}
@@ -0,0 +1,54 @@
+// Spell checking commands backed by the SpellcheckService
+
+use crate::file_system::FileSystemService;
+use crate::spellcheck::{self, SpellcheckResult, SpellcheckService};
+use crate::syntax::SyntaxService;
+use tauri::{AppHandle, Manager, State};
+
+/// Misspelled words in comments and strings of `content`, or in all of it when `language` has no grammar.
+/// Words in the dictionary of `workspace` are accepted.
+#[tauri::command]
+pub async fn spellcheck_text(
+    app: AppHandle,
+    content: String,
+    language: Option<String>,
+    workspace: Option<String>,
+) -> Result<SpellcheckResult, String> {
+    if let Some(workspace) = &workspace {
+        app.state::<FileSystemService>().authorize(workspace).map_err(|e| e.to_string())?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<SpellcheckService>()
+            .check(&app.state::<SyntaxService>(), &content, language.as_deref(), workspace.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_spellcheck_words(fs: State<'_, FileSystemService>, workspace: String) -> Result<Vec<String>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    spellcheck::workspace_words(&workspace).map_err(|e| e.to_string())
+}
+
+/// Accept a word in the workspace, returning the updated word list
+#[tauri::command]
+pub fn add_spellcheck_word(
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    spellcheck::add_workspace_word(&workspace, &word).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_spellcheck_word(
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    spellcheck::remove_workspace_word(&workspace, &word).map_err(|e| e.to_string())
+}
@@ -0,0 +1,96 @@
+// Syntax commands backed by the tree-sitter SyntaxService
+
+use crate::file_system::FileSystemService;
+use crate::syntax::{
+    DocumentSymbol, ExportFormat, FoldingRange, HighlightResult, LineRange, MarkdownOptions, RenderedMarkdown,
+    SyntaxService, WorkspaceRenamePreview,
+};
+use crate::types::FileOperationResult;
+use tauri::State;
+
+/// Highlight tokens for a file, optionally limited to a line range
+#[tauri::command]
+pub fn get_highlight_tokens(
+    syntax: State<'_, SyntaxService>,
+    path: String,
+    range: Option<LineRange>,
+) -> Result<HighlightResult, String> {
+    syntax.highlight_file(&path, range).map_err(|e| e.to_string())
+}
+
+/// Hierarchical symbol outline for a file
+#[tauri::command]
+pub fn get_document_symbols(
+    syntax: State<'_, SyntaxService>,
+    path: String,
+) -> Result<Vec<DocumentSymbol>, String> {
+    syntax.document_symbols(&path).map_err(|e| e.to_string())
+}
+
+/// Foldable regions for a file
+#[tauri::command]
+pub fn get_folding_ranges(
+    syntax: State<'_, SyntaxService>,
+    path: String,
+) -> Result<Vec<FoldingRange>, String> {
+    syntax.folding_ranges(&path).map_err(|e| e.to_string())
+}
+
+/// Whole-word rename edits across the workspace, for languages without a language server
+#[tauri::command]
+pub fn prepare_workspace_rename(
+    fs: State<'_, FileSystemService>,
+    syntax: State<'_, SyntaxService>,
+    workspace: String,
+    path: String,
+    old_name: String,
+    new_name: String,
+) -> Result<WorkspaceRenamePreview, String> {
+    fs.authorize(&workspace).map_err(|e| e.to_string())?;
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    syntax
+        .prepare_workspace_rename(&workspace, &path, &old_name, &new_name)
+        .map_err(|e| e.to_string())
+}
+
+/// Render a file to HTML or PDF with highlighting and line numbers
+#[tauri::command]
+pub fn export_file(
+    fs: State<'_, FileSystemService>,
+    syntax: State<'_, SyntaxService>,
+    path: String,
+    format: ExportFormat,
+    destination: Option<String>,
+) -> Result<FileOperationResult, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    if let Some(destination) = &destination {
+        fs.authorize(destination).map_err(|e| e.to_string())?;
+    }
+    syntax
+        .export_file(&path, format, destination.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Render Markdown to sanitized HTML for the preview; renders `content` when given (e.g. an unsaved
+/// buffer), the file at `path` otherwise
+#[tauri::command]
+pub fn render_markdown(
+    fs: State<'_, FileSystemService>,
+    syntax: State<'_, SyntaxService>,
+    path: Option<String>,
+    content: Option<String>,
+    options: Option<MarkdownOptions>,
+) -> Result<RenderedMarkdown, String> {
+    let source = match (content, path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => {
+            let file = fs.read_file(&path).map_err(|e| e.to_string())?;
+            if file.is_binary {
+                return Err("Cannot render binary files".to_string());
+            }
+            file.content
+        }
+        (None, None) => return Err("Nothing to render: give the Markdown or the path of a file".to_string()),
+    };
+    Ok(syntax.render_markdown(&source, &options.unwrap_or_default()))
+}
@@ -0,0 +1,31 @@
+// System information commands
+
+use std::path::Path;
+
+use crate::mounts::{self, MountInfo};
+use crate::system_info;
+use crate::types::SystemInfo;
+use crate::wsl::{self, WslDistro};
+
+/// OS, CPU, memory, disks, and GPUs for the About/Diagnostics panel
+#[tauri::command]
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+    tauri::async_runtime::spawn_blocking(system_info::system_info).await.map_err(|e| e.to_string())
+}
+
+/// File system type, network and removable flags, and free space of the disk `path` is on; the path
+/// needn't exist yet
+#[tauri::command]
+pub async fn get_mount_info(path: String) -> Result<MountInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        mounts::mount_info(Path::new(&path)).ok_or_else(|| format!("No file system found for {}", path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Installed WSL distros, for opening folders inside them; empty except on Windows with WSL enabled
+#[tauri::command]
+pub async fn list_wsl_distros() -> Result<Vec<WslDistro>, String> {
+    tauri::async_runtime::spawn_blocking(wsl::list_distros).await.map_err(|e| e.to_string())?
+}
@@ -0,0 +1,25 @@
+// Log tail/follow commands
+
+use crate::file_system::FileSystemService;
+use crate::tail::{TailResult, TailService};
+use tauri::{AppHandle, State};
+
+/// Last `lines` lines of a file; with `follow`, appended lines stream as `tail://lines` events
+#[tauri::command]
+pub fn tail_file(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    tail: State<'_, TailService>,
+    path: String,
+    lines: usize,
+    follow: bool,
+) -> Result<TailResult, String> {
+    fs.authorize(&path).map_err(|e| e.to_string())?;
+    tail.tail_file(&app, &path, lines, follow).map_err(|e| e.to_string())
+}
+
+/// Stop following a file
+#[tauri::command]
+pub fn stop_tail(tail: State<'_, TailService>, tail_id: String) -> Result<(), String> {
+    tail.stop_tail(&tail_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,65 @@
+// Task runner commands
+
+use crate::file_system::FileSystemService;
+use crate::tasks::{TaskDefinition, TaskRunSummary, TaskService, WatchTaskStatus};
+use tauri::{AppHandle, State};
+
+/// Tasks from `.codeforge/tasks.json` plus auto-detected npm, cargo, and make tasks
+#[tauri::command]
+pub fn list_tasks(
+    tasks: State<'_, TaskService>,
+    fs: State<'_, FileSystemService>,
+    workspace: String,
+) -> Result<Vec<TaskDefinition>, String> {
+    tasks.list_tasks(&fs, &workspace).map_err(|e| e.to_string())
+}
+
+/// Start a task (and its dependencies), returning the run id
+#[tauri::command]
+pub fn run_task(
+    app: AppHandle,
+    tasks: State<'_, TaskService>,
+    workspace: String,
+    label: String,
+) -> Result<String, String> {
+    tasks.run_task(&app, &workspace, &label).map_err(|e| e.to_string())
+}
+
+/// Status of a task run, including every task in its dependency graph
+#[tauri::command]
+pub fn get_task_run(tasks: State<'_, TaskService>, run_id: String) -> Result<TaskRunSummary, String> {
+    tasks.run_summary(&run_id).map_err(|e| e.to_string())
+}
+
+/// Cancel a task run
+#[tauri::command]
+pub fn stop_task(tasks: State<'_, TaskService>, run_id: String) -> Result<(), String> {
+    tasks.stop_run(&run_id).map_err(|e| e.to_string())
+}
+
+/// Re-run a task automatically whenever files matching `patterns` change
+#[tauri::command]
+pub fn start_watch_task(
+    app: AppHandle,
+    tasks: State<'_, TaskService>,
+    workspace: String,
+    label: String,
+    patterns: Vec<String>,
+    debounce_ms: Option<u64>,
+) -> Result<WatchTaskStatus, String> {
+    tasks
+        .start_watch_task(&app, &workspace, &label, patterns, debounce_ms)
+        .map_err(|e| e.to_string())
+}
+
+/// Stop a watch task
+#[tauri::command]
+pub fn stop_watch_task(tasks: State<'_, TaskService>, id: String) -> Result<(), String> {
+    tasks.stop_watch_task(&id).map_err(|e| e.to_string())
+}
+
+/// Status of all watch tasks
+#[tauri::command]
+pub fn list_watch_tasks(tasks: State<'_, TaskService>) -> Result<Vec<WatchTaskStatus>, String> {
+    Ok(tasks.list_watch_tasks())
+}
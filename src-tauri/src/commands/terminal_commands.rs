@@ -0,0 +1,105 @@
+// Terminal scrollback commands
+
+use crate::file_system::FileSystemService;
+use crate::fs_provider::ProcessLaunch;
+use crate::terminal::{
+    detect_links, render_line, PathExistsCache, ScrollbackMatch, ScrollbackQuery, TerminalLink, TerminalService,
+};
+use crate::types::FileOperationResult;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Search a terminal session's scrollback
+#[tauri::command]
+pub fn search_terminal_output(
+    terminal: State<'_, TerminalService>,
+    session_id: String,
+    query: ScrollbackQuery,
+) -> Result<Vec<ScrollbackMatch>, String> {
+    terminal.search(&session_id, &query).map_err(|e| e.to_string())
+}
+
+/// Export a terminal session's scrollback to a text file
+#[tauri::command]
+pub fn export_terminal_output(
+    fs: State<'_, FileSystemService>,
+    terminal: State<'_, TerminalService>,
+    session_id: String,
+    destination: String,
+) -> Result<FileOperationResult, String> {
+    fs.authorize(&destination).map_err(|e| e.to_string())?;
+    terminal.export(&session_id, &destination).map_err(|e| e.to_string())
+}
+
+/// Clear a terminal session's scrollback
+#[tauri::command]
+pub fn clear_terminal_output(
+    terminal: State<'_, TerminalService>,
+    session_id: String,
+) -> Result<(), String> {
+    terminal.clear_scrollback(&session_id).map_err(|e| e.to_string())
+}
+
+/// Forget a closed terminal session and its scrollback
+#[tauri::command]
+pub fn close_terminal_session(terminal: State<'_, TerminalService>, session_id: String) {
+    terminal.close_session(&session_id);
+}
+
+/// Set the working directory used to resolve relative paths in a session's output
+#[tauri::command]
+pub fn set_terminal_cwd(
+    fs: State<'_, FileSystemService>,
+    terminal: State<'_, TerminalService>,
+    session_id: String,
+    cwd: String,
+) -> Result<(), String> {
+    fs.authorize(&cwd).map_err(|e| e.to_string())?;
+    terminal.set_session_cwd(&session_id, &cwd);
+    Ok(())
+}
+
+/// Record the process a session runs, so ports it opens show up as the session's
+#[tauri::command]
+pub fn set_terminal_process(
+    terminal: State<'_, TerminalService>,
+    session_id: String,
+    pid: u32,
+    title: Option<String>,
+) -> Result<(), String> {
+    terminal.set_session_process(&session_id, pid, title.as_deref());
+    Ok(())
+}
+
+/// How to start a shell in `cwd`, on the machine of the workspace `cwd` is in when it's a URI
+#[tauri::command]
+pub fn get_terminal_launch(fs: State<'_, FileSystemService>, cwd: String) -> Result<ProcessLaunch, String> {
+    let (provider, path) = fs.provider(&cwd).map_err(|e| e.to_string())?;
+    provider.terminal(&path).map_err(|e| e.to_string())
+}
+
+/// Detect file and URL links in a block of terminal output
+#[tauri::command]
+pub fn detect_terminal_links(
+    fs: State<'_, FileSystemService>,
+    terminal: State<'_, TerminalService>,
+    text: String,
+    session_id: Option<String>,
+    cwd: Option<String>,
+) -> Result<Vec<TerminalLink>, String> {
+    if let Some(cwd) = &cwd {
+        fs.authorize(cwd).map_err(|e| e.to_string())?;
+    }
+    let cwd = cwd
+        .map(PathBuf::from)
+        .or_else(|| session_id.and_then(|id| terminal.session_cwd(&id)));
+
+    let mut paths = PathExistsCache::default();
+    Ok(text
+        .lines()
+        .enumerate()
+        .flat_map(|(line_number, line)| {
+            detect_links(line_number, &render_line(line), cwd.as_deref(), &mut paths)
+        })
+        .collect())
+}
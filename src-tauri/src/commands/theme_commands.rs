@@ -0,0 +1,46 @@
+// Color and icon theme commands backed by the ThemeService
+
+use crate::theme::{CompiledIconTheme, CompiledTheme, IconThemeSummary, ThemeService, ThemeSummary};
+use tauri::{AppHandle, State};
+
+/// Built-in and user color themes; themes that fail to compile carry their error
+#[tauri::command]
+pub fn list_themes(app: AppHandle, themes: State<'_, ThemeService>) -> Result<Vec<ThemeSummary>, String> {
+    themes.list_themes(&app).map_err(|e| e.to_string())
+}
+
+/// A color theme with its `extends` chain resolved
+#[tauri::command]
+pub fn get_theme(app: AppHandle, themes: State<'_, ThemeService>, id: String) -> Result<CompiledTheme, String> {
+    themes.get_theme(&app, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_icon_themes(
+    app: AppHandle,
+    themes: State<'_, ThemeService>,
+) -> Result<Vec<IconThemeSummary>, String> {
+    themes.list_icon_themes(&app).map_err(|e| e.to_string())
+}
+
+/// An icon theme with validated mappings and absolute icon paths
+#[tauri::command]
+pub fn get_icon_theme(
+    app: AppHandle,
+    themes: State<'_, ThemeService>,
+    id: String,
+) -> Result<CompiledIconTheme, String> {
+    themes.get_icon_theme(&app, &id).map_err(|e| e.to_string())
+}
+
+/// Reload themes as their files change; results arrive as `theme://changed` events
+#[tauri::command]
+pub fn start_theme_watch(app: AppHandle, themes: State<'_, ThemeService>) -> Result<(), String> {
+    themes.start_watch(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn stop_theme_watch(themes: State<'_, ThemeService>) -> Result<(), String> {
+    themes.stop_watch();
+    Ok(())
+}
@@ -0,0 +1,36 @@
+// Window management commands
+
+use crate::window_manager::{WindowInfo, WindowManager};
+use tauri::{AppHandle, State, Window};
+
+/// Open a window for `workspace`, or focus the window that already has it open; returns the window label
+#[tauri::command]
+pub fn open_new_window(
+    app: AppHandle,
+    windows: State<'_, WindowManager>,
+    workspace: Option<String>,
+) -> Result<String, String> {
+    windows.open_window(&app, workspace.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_windows(app: AppHandle, windows: State<'_, WindowManager>) -> Vec<WindowInfo> {
+    windows.list(&app)
+}
+
+#[tauri::command]
+pub fn focus_window(app: AppHandle, windows: State<'_, WindowManager>, label: String) -> Result<(), String> {
+    windows.focus(&app, &label).map_err(|e| e.to_string())
+}
+
+/// Workspace the calling window should open at startup, if it was opened for one
+#[tauri::command]
+pub fn get_window_workspace(window: Window, windows: State<'_, WindowManager>) -> Option<String> {
+    windows.workspace(window.label())
+}
+
+/// Record the workspace the calling window switched to, so opening it elsewhere focuses this window
+#[tauri::command]
+pub fn set_window_workspace(window: Window, windows: State<'_, WindowManager>, workspace: Option<String>) {
+    windows.set_workspace(window.label(), workspace.as_deref());
+}
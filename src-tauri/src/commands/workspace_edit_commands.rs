@@ -0,0 +1,67 @@
+// Workspace edit commands for applying refactorings across files
+
+use crate::file_system::FileSystemService;
+use crate::operation_log::{self, log_operation, OperationKind, OperationRecord};
+use crate::workspace_edit::{self, WorkspaceEditOperation, WorkspaceEditResult};
+use tauri::{AppHandle, State};
+
+/// Apply text edits and file create/rename/delete operations in order, all or nothing. Edited and deleted
+/// files are kept in local history first and the changes are logged.
+#[tauri::command]
+pub async fn apply_workspace_edit(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    edits: Vec<WorkspaceEditOperation>,
+) -> Result<WorkspaceEditResult, String> {
+    // Check every path up front so a batch never gets half-way before hitting the sandbox
+    for operation in &edits {
+        for path in operation.paths() {
+            fs.authorize(path).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let changed: Vec<String> = edits
+            .iter()
+            .filter_map(|operation| match operation {
+                WorkspaceEditOperation::Edit { path, .. } | WorkspaceEditOperation::Delete { path, .. } => {
+                    Some(path.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let snapshots = operation_log::snapshot_files(&app, &changed);
+        let result = workspace_edit::apply_workspace_edit(&edits).map_err(|e| e.to_string())?;
+
+        let snapshots_of = |paths: &[String]| {
+            snapshots.iter().filter(|snapshot| paths.contains(&snapshot.path)).cloned().collect()
+        };
+        if !result.edited.is_empty() {
+            let record = OperationRecord::new(
+                OperationKind::BulkReplace,
+                result.edited.clone(),
+                "apply_workspace_edit",
+            )
+            .with_snapshots(snapshots_of(&result.edited));
+            log_operation(&app, record);
+        }
+        if !result.renamed.is_empty() {
+            let (old_paths, new_paths) = result.renamed.iter().cloned().unzip();
+            let record = OperationRecord::new(OperationKind::Rename, old_paths, "apply_workspace_edit")
+                .with_destinations(new_paths);
+            log_operation(&app, record);
+        }
+        if !result.deleted.is_empty() {
+            let record = OperationRecord::new(
+                OperationKind::Delete,
+                result.deleted.clone(),
+                "apply_workspace_edit",
+            )
+            .with_snapshots(snapshots_of(&result.deleted));
+            log_operation(&app, record);
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
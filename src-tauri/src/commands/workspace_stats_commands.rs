@@ -0,0 +1,27 @@
+// Project dashboard commands
+
+use crate::file_system::FileSystemService;
+use crate::git::GitService;
+use crate::workspace_stats::{self, WorkspaceStats, WORKSPACE_STATS_PROGRESS_EVENT};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Lines per language, largest files, and git history of a workspace; the breakdown so far streams as
+/// `workspace://stats-progress`
+#[tauri::command]
+pub async fn get_workspace_stats(
+    app: AppHandle,
+    fs: State<'_, FileSystemService>,
+    root: String,
+) -> Result<WorkspaceStats, String> {
+    fs.authorize(&root).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let git = app.state::<GitService>();
+        workspace_stats::compute_workspace_stats(Path::new(&root), &git, |progress| {
+            let _ = app.emit(WORKSPACE_STATS_PROGRESS_EVENT, progress);
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
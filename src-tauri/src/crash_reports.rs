@@ -0,0 +1,251 @@
+/**
+ * Crash reporting for CodeForge IDE
+ * A panic hook writes a report with the backtrace and the latest log lines to the app data dir, so backend
+ * failures can be diagnosed after the fact. Reports stay local unless the user chooses to submit one.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use crate::clock::now_millis;
+use crate::logging::{self, LogService};
+use crate::settings::SettingsService;
+
+/// Directory under the app data dir holding the reports
+const CRASH_REPORTS_DIR: &str = "crash-reports";
+
+/// Reports kept, oldest deleted first
+const MAX_CRASH_REPORTS: usize = 20;
+
+/// Log lines attached to each report
+const REPORT_LOG_LINES: usize = 200;
+
+/// Error types for crash report operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CrashReportError {
+    NoDataDirectory,
+    NotFound(String),
+    IOError(String),
+    /// No `crash_report_url` is configured
+    SubmissionDisabled,
+    SubmitFailed(String),
+}
+
+impl std::fmt::Display for CrashReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CrashReportError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            CrashReportError::NotFound(id) => write!(f, "Crash report not found: {}", id),
+            CrashReportError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            CrashReportError::SubmissionDisabled => write!(f, "No crash report URL is configured"),
+            CrashReportError::SubmitFailed(msg) => write!(f, "Submitting the crash report failed: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub log_lines: Vec<String>,
+    /// Unix time in milliseconds the report was submitted, if it was
+    #[serde(default)]
+    pub submitted_at: Option<u64>,
+}
+
+/// What the crash report list shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub timestamp: u64,
+    pub app_version: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub submitted_at: Option<u64>,
+}
+
+fn io_error(e: std::io::Error) -> CrashReportError {
+    CrashReportError::IOError(e.to_string())
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, CrashReportError> {
+    let data_dir = app.path().app_data_dir().map_err(|_| CrashReportError::NoDataDirectory)?;
+    Ok(data_dir.join(CRASH_REPORTS_DIR))
+}
+
+/// Report ids double as file names, so anything but `crash-<digits>` is rejected
+fn report_path(dir: &Path, id: &str) -> Result<PathBuf, CrashReportError> {
+    let valid = id
+        .strip_prefix("crash-")
+        .is_some_and(|stamp| !stamp.is_empty() && stamp.chars().all(|c| c.is_ascii_digit()));
+    if !valid {
+        return Err(CrashReportError::NotFound(id.to_string()));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+fn load_report(path: &Path, id: &str) -> Result<CrashReport, CrashReportError> {
+    let content = fs::read_to_string(path).map_err(|_| CrashReportError::NotFound(id.to_string()))?;
+    serde_json::from_str(&content).map_err(|e| CrashReportError::IOError(e.to_string()))
+}
+
+/// Reports in `dir`, newest first
+fn load_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "json"))
+        .filter_map(|entry| serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok())
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.timestamp));
+    reports
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// Build and write the report for a panic. Runs inside the panic hook, so it touches no shared state and
+/// gives up quietly on any error.
+fn write_report(dir: &Path, log_dir: Option<&Path>, info: &PanicHookInfo) {
+    let timestamp = now_millis();
+    let report = CrashReport {
+        id: format!("crash-{}", timestamp),
+        timestamp,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().map(str::to_string),
+        message: panic_message(info),
+        location: info.location().map(|location| location.to_string()),
+        backtrace: Backtrace::force_capture().to_string(),
+        log_lines: log_dir.map(|dir| logging::recent_lines(dir, REPORT_LOG_LINES)).unwrap_or_default(),
+        submitted_at: None,
+    };
+    let Ok(content) = serde_json::to_string_pretty(&report) else {
+        return;
+    };
+    if fs::create_dir_all(dir).is_err() || fs::write(dir.join(format!("{}.json", report.id)), content).is_err() {
+        return;
+    }
+    for old in load_reports(dir).into_iter().skip(MAX_CRASH_REPORTS) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+}
+
+pub struct CrashReportService {
+    /// Serializes rewrites when a report is marked submitted
+    lock: Arc<Mutex<()>>,
+}
+
+impl CrashReportService {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Write a crash report on every panic, then run the previously installed hook. Call after logging is
+    /// initialized so reports include log lines.
+    pub fn install_panic_hook(&self, app: &AppHandle) -> Result<(), CrashReportError> {
+        let dir = reports_dir(app)?;
+        let log_dir = app.state::<LogService>().dir();
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            write_report(&dir, log_dir.as_deref(), info);
+            tracing::error!(panic = %panic_message(info), location = ?info.location(), "backend panicked");
+            previous(info);
+        }));
+        Ok(())
+    }
+
+    pub fn list(&self, app: &AppHandle) -> Result<Vec<CrashReportSummary>, CrashReportError> {
+        let reports = load_reports(&reports_dir(app)?);
+        Ok(reports
+            .into_iter()
+            .map(|report| CrashReportSummary {
+                id: report.id,
+                timestamp: report.timestamp,
+                app_version: report.app_version,
+                message: report.message,
+                location: report.location,
+                submitted_at: report.submitted_at,
+            })
+            .collect())
+    }
+
+    pub fn get(&self, app: &AppHandle, id: &str) -> Result<CrashReport, CrashReportError> {
+        load_report(&report_path(&reports_dir(app)?, id)?, id)
+    }
+
+    pub fn delete(&self, app: &AppHandle, id: &str) -> Result<(), CrashReportError> {
+        let path = report_path(&reports_dir(app)?, id)?;
+        let _guard = self.lock.lock().unwrap();
+        fs::remove_file(&path).map_err(|_| CrashReportError::NotFound(id.to_string()))
+    }
+
+    fn mark_submitted(&self, app: &AppHandle, id: &str) -> Result<CrashReport, CrashReportError> {
+        let path = report_path(&reports_dir(app)?, id)?;
+        let _guard = self.lock.lock().unwrap();
+        let mut report = load_report(&path, id)?;
+        report.submitted_at = Some(now_millis());
+        let content =
+            serde_json::to_string_pretty(&report).map_err(|e| CrashReportError::IOError(e.to_string()))?;
+        fs::write(&path, content).map_err(io_error)?;
+        Ok(report)
+    }
+
+    /// Post a report to the `crash_report_url` preference. Only ever called on the user's request; nothing
+    /// is sent automatically.
+    pub async fn submit(app: &AppHandle, id: &str) -> Result<CrashReport, CrashReportError> {
+        let preferences = app
+            .state::<SettingsService>()
+            .get_preferences(app)
+            .map_err(|e| CrashReportError::IOError(e.to_string()))?;
+        if preferences.crash_report_url.is_empty() {
+            return Err(CrashReportError::SubmissionDisabled);
+        }
+        let report = app.state::<CrashReportService>().get(app, id)?;
+        let submit_error = |e: reqwest::Error| CrashReportError::SubmitFailed(e.to_string());
+        let body = serde_json::to_vec(&report).map_err(|e| CrashReportError::IOError(e.to_string()))?;
+        reqwest::Client::new()
+            .post(&preferences.crash_report_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(submit_error)?
+            .error_for_status()
+            .map_err(submit_error)?;
+        tracing::info!(report = %id, "crash report submitted");
+        app.state::<CrashReportService>().mark_submitted(app, id)
+    }
+}
+
+impl Default for CrashReportService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
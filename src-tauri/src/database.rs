@@ -0,0 +1,357 @@
+/**
+ * Database explorer for CodeForge IDE
+ * Opens the SQLite files that live in projects (app databases, test fixtures, caches) to browse their tables
+ * and run queries. Results of read-only queries come back a page at a time, so selecting from a large table
+ * stays cheap; row counts are only taken when asked for, since counting a table reads all of it.
+ *
+ * A database can't reach other files: ATTACH is refused, and with it `VACUUM` (which attaches its target),
+ * so a query can't read or write outside the file that was authorized when it was opened.
+ */
+
+use base64::Engine as _;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::limits::Limit;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a statement waits for a lock held by another process, e.g. the app that owns the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Rows per page when the caller doesn't say
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Bytes of a blob sent for display; the rest is only counted
+const BLOB_PREVIEW_LENGTH: usize = 4096;
+
+/// Error types for database operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DatabaseError {
+    Open(String),
+    NotOpen(String),
+    Query(String),
+}
+
+impl std::fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DatabaseError::Open(msg) => write!(f, "Can't open the database: {}", msg),
+            DatabaseError::NotOpen(id) => write!(f, "No open database {}", id),
+            DatabaseError::Query(msg) => write!(f, "Query failed: {}", msg),
+        }
+    }
+}
+
+fn query_error(e: rusqlite::Error) -> DatabaseError {
+    DatabaseError::Query(e.to_string())
+}
+
+/// An open database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    pub id: String,
+    pub path: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableKind {
+    Table,
+    View,
+}
+
+/// A column of a table, as declared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    /// Declared type, e.g. `INTEGER` or `VARCHAR(40)`; SQLite columns may have none
+    pub declared_type: Option<String>,
+    pub not_null: bool,
+    pub primary_key: bool,
+    /// The default as SQL text, e.g. `'draft'` or `CURRENT_TIMESTAMP`
+    pub default_value: Option<String>,
+}
+
+/// A table or view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub name: String,
+    pub kind: TableKind,
+    pub columns: Vec<TableColumn>,
+}
+
+/// A column of a result set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultColumn {
+    pub name: String,
+    /// Declared type of the table column it comes from, `None` for expressions
+    pub declared_type: Option<String>,
+}
+
+/// A value of a result set, with SQLite's storage class as `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob {
+        size: usize,
+        /// Base64 of at most the first `BLOB_PREVIEW_LENGTH` bytes
+        data: String,
+    },
+}
+
+impl From<ValueRef<'_>> for SqlValue {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => SqlValue::Null,
+            ValueRef::Integer(value) => SqlValue::Integer(value),
+            ValueRef::Real(value) => SqlValue::Real(value),
+            ValueRef::Text(text) => SqlValue::Text(String::from_utf8_lossy(text).to_string()),
+            ValueRef::Blob(bytes) => SqlValue::Blob {
+                size: bytes.len(),
+                data: base64::engine::general_purpose::STANDARD
+                    .encode(&bytes[..bytes.len().min(BLOB_PREVIEW_LENGTH)]),
+            },
+        }
+    }
+}
+
+/// A page of a query's results, or what a statement changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    /// Empty for statements that return no rows
+    pub columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<SqlValue>>,
+    /// Index of the first row of this page
+    pub offset: usize,
+    /// Rows remain after this page
+    pub has_more: bool,
+    /// Rows inserted, updated or deleted, for statements that can change the database
+    pub rows_affected: Option<u64>,
+    pub duration_ms: u64,
+}
+
+struct OpenDatabase {
+    info: DatabaseInfo,
+    connection: Arc<Mutex<Connection>>,
+}
+
+pub struct DatabaseService {
+    databases: Mutex<HashMap<String, OpenDatabase>>,
+    next_id: AtomicU64,
+}
+
+impl DatabaseService {
+    pub fn new() -> Self {
+        Self {
+            databases: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Open an existing SQLite file; opening one that is already open returns it again
+    pub fn open_sqlite(&self, path: &Path, read_only: bool) -> Result<DatabaseInfo, DatabaseError> {
+        let path_text = path.to_string_lossy().to_string();
+        let mut databases = self.databases.lock().unwrap();
+        if let Some(open) = databases
+            .values()
+            .find(|open| open.info.path == path_text && open.info.read_only == read_only)
+        {
+            return Ok(open.info.clone());
+        }
+
+        // Without `SQLITE_OPEN_CREATE`, so a mistyped path doesn't leave an empty database behind
+        let flags = if read_only {
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+        } else {
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+        };
+        let connection = Connection::open_with_flags(path, flags | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .map_err(|e| DatabaseError::Open(e.to_string()))?;
+        connection.busy_timeout(BUSY_TIMEOUT).map_err(|e| DatabaseError::Open(e.to_string()))?;
+        connection
+            .set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0)
+            .map_err(|e| DatabaseError::Open(e.to_string()))?;
+        connection.authorizer(Some(|context: AuthContext<'_>| match context.action {
+            AuthAction::Attach { .. } => Authorization::Deny,
+            _ => Authorization::Allow,
+        }));
+        // SQLite opens anything lazily; reading the schema is what rejects a file that isn't a database
+        connection
+            .query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .map_err(|e| DatabaseError::Open(e.to_string()))?;
+
+        let info = DatabaseInfo {
+            id: format!("db-{}", self.next_id.fetch_add(1, Ordering::SeqCst)),
+            path: path_text,
+            read_only,
+        };
+        databases.insert(
+            info.id.clone(),
+            OpenDatabase {
+                info: info.clone(),
+                connection: Arc::new(Mutex::new(connection)),
+            },
+        );
+        Ok(info)
+    }
+
+    pub fn close(&self, id: &str) -> Result<(), DatabaseError> {
+        self.databases
+            .lock()
+            .unwrap()
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| DatabaseError::NotOpen(id.to_string()))
+    }
+
+    pub fn list(&self) -> Vec<DatabaseInfo> {
+        let mut databases: Vec<DatabaseInfo> =
+            self.databases.lock().unwrap().values().map(|open| open.info.clone()).collect();
+        databases.sort_by(|a, b| a.path.cmp(&b.path));
+        databases
+    }
+
+    /// Tables and views with their columns, without SQLite's internal tables
+    pub fn list_tables(&self, id: &str) -> Result<Vec<TableInfo>, DatabaseError> {
+        let connection = self.connection(id)?;
+        let connection = connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(
+                "SELECT name, type FROM sqlite_master \
+                 WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            )
+            .map_err(query_error)?;
+        let tables = statement
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(query_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(query_error)?;
+
+        tables
+            .into_iter()
+            .map(|(name, kind)| {
+                Ok(TableInfo {
+                    columns: table_columns(&connection, &name)?,
+                    name,
+                    kind: if kind == "view" { TableKind::View } else { TableKind::Table },
+                })
+            })
+            .collect()
+    }
+
+    /// Rows in a table or view; this reads all of it, or runs the view's query
+    pub fn count_rows(&self, id: &str, table: &str) -> Result<u64, DatabaseError> {
+        let connection = self.connection(id)?;
+        let connection = connection.lock().unwrap();
+        let count: i64 = connection
+            .query_row(&format!("SELECT count(*) FROM {}", quote_identifier(table)), [], |row| row.get(0))
+            .map_err(query_error)?;
+        Ok(count as u64)
+    }
+
+    /// Run one statement. Read-only statements return up to `limit` of their rows starting at `offset`; others
+    /// run once whatever the page, so an `INSERT ... RETURNING` never repeats, and return every row.
+    pub fn run_query(
+        &self,
+        id: &str,
+        sql: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<QueryResult, DatabaseError> {
+        let connection = self.connection(id)?;
+        let connection = connection.lock().unwrap();
+        let started = Instant::now();
+        let mut statement = connection.prepare(sql).map_err(query_error)?;
+        let read_only = statement.readonly();
+        let (offset, limit) = if read_only { (offset, limit) } else { (0, usize::MAX) };
+
+        let columns: Vec<ResultColumn> = statement
+            .columns()
+            .iter()
+            .map(|column| ResultColumn {
+                name: column.name().to_string(),
+                declared_type: column.decl_type().map(str::to_string),
+            })
+            .collect();
+        let mut rows = Vec::new();
+        let mut has_more = false;
+        let mut results = statement.query([]).map_err(query_error)?;
+        let mut index = 0;
+        while let Some(row) = results.next().map_err(query_error)? {
+            if index >= offset.saturating_add(limit) {
+                has_more = true;
+                break;
+            }
+            if index >= offset {
+                let values = (0..columns.len())
+                    .map(|column| row.get_ref(column).map(SqlValue::from))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(query_error)?;
+                rows.push(values);
+            }
+            index += 1;
+        }
+        drop(results);
+
+        Ok(QueryResult {
+            columns,
+            rows,
+            offset,
+            has_more,
+            rows_affected: (!read_only).then(|| connection.changes()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// The connection of an open database, held outside the service's lock so a slow query doesn't block others
+    fn connection(&self, id: &str) -> Result<Arc<Mutex<Connection>>, DatabaseError> {
+        self.databases
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|open| open.connection.clone())
+            .ok_or_else(|| DatabaseError::NotOpen(id.to_string()))
+    }
+}
+
+impl Default for DatabaseService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn table_columns(connection: &Connection, table: &str) -> Result<Vec<TableColumn>, DatabaseError> {
+    let mut statement = connection
+        .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1)")
+        .map_err(query_error)?;
+    let columns = statement
+        .query_map([table], |row| {
+            let declared_type: String = row.get(1)?;
+            Ok(TableColumn {
+                name: row.get(0)?,
+                declared_type: (!declared_type.is_empty()).then_some(declared_type),
+                not_null: row.get(2)?,
+                primary_key: row.get::<_, i64>(4)? > 0,
+                default_value: row.get(3)?,
+            })
+        })
+        .map_err(query_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(query_error)?;
+    Ok(columns)
+}
+
+/// `name` as an SQL identifier, whatever characters it has
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,47 @@
+/**
+ * Launch descriptions of the debug adapters CodeForge knows how to start
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// How the IDE talks to a spawned adapter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AdapterTransport {
+    /// DAP over the adapter's stdin/stdout
+    Stdio,
+    /// The adapter listens on a TCP port; `{port}` in its arguments is replaced with a free port
+    Tcp,
+}
+
+/// Command that starts a debug adapter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdapterCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub transport: AdapterTransport,
+}
+
+fn adapter(command: &str, args: &[&str], transport: AdapterTransport) -> AdapterCommand {
+    AdapterCommand {
+        command: command.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        transport,
+    }
+}
+
+/// Default adapter for a debug type; the adapters themselves must be installed and on PATH
+pub fn builtin_adapter(debug_type: &str) -> Option<AdapterCommand> {
+    Some(match debug_type {
+        "lldb" | "codelldb" => adapter("codelldb", &["--port", "{port}"], AdapterTransport::Tcp),
+        "python" | "debugpy" => adapter(
+            if cfg!(windows) { "python" } else { "python3" },
+            &["-m", "debugpy.adapter"],
+            AdapterTransport::Stdio,
+        ),
+        // vscode-js-debug's standalone DAP server
+        "node" | "pwa-node" => adapter("js-debug-adapter", &["{port}"], AdapterTransport::Tcp),
+        _ => return None,
+    })
+}
@@ -0,0 +1,378 @@
+/**
+ * Breakpoint persistence
+ * Per-workspace breakpoints stored in the app data dir, re-anchored when their files change
+ * and pushed to running debug sessions
+ */
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+use crate::session::workspace_key;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::{DebugError, DebugService, SourceBreakpoint};
+
+/// Event carrying the breakpoints of a file after they were added, removed, toggled, or moved
+pub const BREAKPOINTS_CHANGED_EVENT: &str = "debug://breakpoints-changed";
+
+/// Directory under the app data dir holding one breakpoint file per workspace
+const BREAKPOINTS_DIR: &str = "breakpoints";
+
+/// How far from its old line a breakpoint's source line is searched for after an edit
+const REMAP_SEARCH_LINES: usize = 200;
+
+/// A persisted breakpoint; `line` is one-based
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredBreakpoint {
+    pub id: String,
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub condition: Option<String>,
+    /// Hit count condition such as `>= 3`
+    #[serde(default)]
+    pub hit_condition: Option<String>,
+    #[serde(default)]
+    pub log_message: Option<String>,
+    pub enabled: bool,
+    /// Source text of the line, used to follow the breakpoint when lines are inserted or removed
+    #[serde(default)]
+    pub line_text: String,
+}
+
+/// New breakpoint, or new settings for the breakpoint already on that line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointSpec {
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub hit_condition: Option<String>,
+    #[serde(default)]
+    pub log_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointsChanged {
+    pub workspace: String,
+    pub path: String,
+    pub breakpoints: Vec<StoredBreakpoint>,
+}
+
+struct WorkspaceBreakpoints {
+    breakpoints: Vec<StoredBreakpoint>,
+    watcher: Option<RecommendedWatcher>,
+}
+
+pub struct BreakpointStore {
+    workspaces: Arc<Mutex<HashMap<String, WorkspaceBreakpoints>>>,
+    next_id: AtomicU64,
+}
+
+fn store_error(e: impl std::fmt::Display) -> DebugError {
+    DebugError::IOError(e.to_string())
+}
+
+fn breakpoints_file(app: &AppHandle, workspace: &str) -> Result<PathBuf, DebugError> {
+    let data_dir = app.path().app_data_dir().map_err(store_error)?;
+    Ok(data_dir.join(BREAKPOINTS_DIR).join(format!("{}.json", workspace_key(workspace))))
+}
+
+fn source_line(path: &str, line: usize) -> String {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.lines().nth(line.saturating_sub(1)).map(|text| text.trim().to_string()))
+        .unwrap_or_default()
+}
+
+/// New line of a breakpoint: unchanged if its text is still there, otherwise the nearest line with the
+/// same text, otherwise clamped to the end of the file
+fn remap_line(lines: &[&str], breakpoint: &StoredBreakpoint) -> usize {
+    let last_line = lines.len().max(1);
+    let text = breakpoint.line_text.as_str();
+    if text.is_empty() {
+        return breakpoint.line.min(last_line);
+    }
+
+    let matches =
+        |line: usize| line >= 1 && lines.get(line - 1).is_some_and(|candidate| candidate.trim() == text);
+    if matches(breakpoint.line) {
+        return breakpoint.line;
+    }
+    for distance in 1..=REMAP_SEARCH_LINES {
+        if matches(breakpoint.line + distance) {
+            return breakpoint.line + distance;
+        }
+        if distance < breakpoint.line && matches(breakpoint.line - distance) {
+            return breakpoint.line - distance;
+        }
+    }
+    breakpoint.line.min(last_line)
+}
+
+impl BreakpointStore {
+    pub fn new() -> Self {
+        Self {
+            workspaces: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Load a workspace's breakpoints on first use and start following edits to their files
+    fn ensure_loaded<'a>(
+        &self,
+        app: &AppHandle,
+        workspaces: &'a mut HashMap<String, WorkspaceBreakpoints>,
+        workspace: &str,
+    ) -> Result<&'a mut Vec<StoredBreakpoint>, DebugError> {
+        if !workspaces.contains_key(workspace) {
+            let breakpoints = fs::read_to_string(breakpoints_file(app, workspace)?)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            workspaces.insert(
+                workspace.to_string(),
+                WorkspaceBreakpoints {
+                    breakpoints,
+                    watcher: watch_workspace(app, workspace),
+                },
+            );
+        }
+        Ok(&mut workspaces.get_mut(workspace).unwrap().breakpoints)
+    }
+
+    /// Apply `change` to a workspace's breakpoints, then persist and publish the files it touched
+    fn modify<T>(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        change: impl FnOnce(&mut Vec<StoredBreakpoint>) -> Result<(T, BTreeSet<String>), DebugError>,
+    ) -> Result<T, DebugError> {
+        let (result, updates) = {
+            let mut workspaces = self.workspaces.lock().unwrap();
+            let breakpoints = self.ensure_loaded(app, &mut workspaces, workspace)?;
+            let (result, changed_paths) = change(breakpoints)?;
+            if changed_paths.is_empty() {
+                return Ok(result);
+            }
+
+            let path = breakpoints_file(app, workspace)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(store_error)?;
+            }
+            let content = serde_json::to_string_pretty(breakpoints).map_err(store_error)?;
+            write_atomic(&path, content).map_err(store_error)?;
+
+            let updates: Vec<BreakpointsChanged> = changed_paths
+                .into_iter()
+                .map(|path| BreakpointsChanged {
+                    workspace: workspace.to_string(),
+                    breakpoints: breakpoints.iter().filter(|b| b.path == path).cloned().collect(),
+                    path,
+                })
+                .collect();
+            (result, updates)
+        };
+
+        let debug = app.state::<DebugService>();
+        for update in updates {
+            debug.sync_breakpoints(workspace, &update.path, enabled_source_breakpoints(&update.breakpoints));
+            let _ = app.emit(BREAKPOINTS_CHANGED_EVENT, update);
+        }
+        Ok(result)
+    }
+
+    /// Breakpoints of a workspace, optionally only those in one file
+    pub fn list(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        path: Option<&str>,
+    ) -> Result<Vec<StoredBreakpoint>, DebugError> {
+        let mut workspaces = self.workspaces.lock().unwrap();
+        let breakpoints = self.ensure_loaded(app, &mut workspaces, workspace)?;
+        let mut breakpoints: Vec<StoredBreakpoint> = breakpoints
+            .iter()
+            .filter(|breakpoint| path.is_none_or(|path| breakpoint.path == path))
+            .cloned()
+            .collect();
+        breakpoints.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        Ok(breakpoints)
+    }
+
+    /// Add a breakpoint, or update the settings of the one already on that line
+    pub fn add(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        spec: BreakpointSpec,
+    ) -> Result<StoredBreakpoint, DebugError> {
+        if spec.line == 0 {
+            return Err(DebugError::InvalidBreakpoint("lines are one-based".to_string()));
+        }
+        let line_text = source_line(&spec.path, spec.line);
+        let id = format!("bp-{}-{}", now_millis(), self.next_id.fetch_add(1, Ordering::SeqCst));
+
+        self.modify(app, workspace, |breakpoints| {
+            let breakpoint = match breakpoints.iter_mut().find(|b| b.path == spec.path && b.line == spec.line) {
+                Some(existing) => {
+                    existing.column = spec.column;
+                    existing.condition = spec.condition;
+                    existing.hit_condition = spec.hit_condition;
+                    existing.log_message = spec.log_message;
+                    existing.line_text = line_text;
+                    existing.clone()
+                }
+                None => {
+                    let breakpoint = StoredBreakpoint {
+                        id,
+                        path: spec.path,
+                        line: spec.line,
+                        column: spec.column,
+                        condition: spec.condition,
+                        hit_condition: spec.hit_condition,
+                        log_message: spec.log_message,
+                        enabled: true,
+                        line_text,
+                    };
+                    breakpoints.push(breakpoint.clone());
+                    breakpoint
+                }
+            };
+            Ok((breakpoint.clone(), BTreeSet::from([breakpoint.path])))
+        })
+    }
+
+    pub fn remove(&self, app: &AppHandle, workspace: &str, id: &str) -> Result<(), DebugError> {
+        self.modify(app, workspace, |breakpoints| {
+            let index = breakpoints
+                .iter()
+                .position(|breakpoint| breakpoint.id == id)
+                .ok_or_else(|| DebugError::BreakpointNotFound(id.to_string()))?;
+            let removed = breakpoints.remove(index);
+            Ok(((), BTreeSet::from([removed.path])))
+        })
+    }
+
+    /// Enable or disable a breakpoint; flips it when `enabled` is `None`
+    pub fn toggle(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        id: &str,
+        enabled: Option<bool>,
+    ) -> Result<StoredBreakpoint, DebugError> {
+        self.modify(app, workspace, |breakpoints| {
+            let breakpoint = breakpoints
+                .iter_mut()
+                .find(|breakpoint| breakpoint.id == id)
+                .ok_or_else(|| DebugError::BreakpointNotFound(id.to_string()))?;
+            breakpoint.enabled = enabled.unwrap_or(!breakpoint.enabled);
+            Ok((breakpoint.clone(), BTreeSet::from([breakpoint.path.clone()])))
+        })
+    }
+
+    /// Re-anchor the breakpoints of a file after it changed on disk
+    pub fn remap_file(&self, app: &AppHandle, workspace: &str, path: &str) -> Result<(), DebugError> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(());
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        self.modify(app, workspace, |breakpoints| {
+            let mut moved = false;
+            for breakpoint in breakpoints.iter_mut().filter(|breakpoint| breakpoint.path == path) {
+                let line = remap_line(&lines, breakpoint);
+                moved |= line != breakpoint.line;
+                breakpoint.line = line;
+                breakpoint.line_text =
+                    lines.get(line - 1).map(|text| text.trim().to_string()).unwrap_or_default();
+            }
+            let changed = if moved { BTreeSet::from([path.to_string()]) } else { BTreeSet::new() };
+            Ok(((), changed))
+        })
+    }
+
+    /// Workspaces whose breakpoint files are being followed
+    pub fn watcher_count(&self) -> usize {
+        self.workspaces.lock().unwrap().values().filter(|workspace| workspace.watcher.is_some()).count()
+    }
+
+    /// Enabled breakpoints of a workspace grouped by file, ready to send to a debug adapter
+    pub fn session_breakpoints(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+    ) -> Result<HashMap<String, Vec<SourceBreakpoint>>, DebugError> {
+        let mut by_path: HashMap<String, Vec<StoredBreakpoint>> = HashMap::new();
+        for breakpoint in self.list(app, workspace, None)? {
+            by_path.entry(breakpoint.path.clone()).or_default().push(breakpoint);
+        }
+        Ok(by_path
+            .into_iter()
+            .map(|(path, breakpoints)| (path, enabled_source_breakpoints(&breakpoints)))
+            .filter(|(_, breakpoints)| !breakpoints.is_empty())
+            .collect())
+    }
+}
+
+fn enabled_source_breakpoints(breakpoints: &[StoredBreakpoint]) -> Vec<SourceBreakpoint> {
+    breakpoints
+        .iter()
+        .filter(|breakpoint| breakpoint.enabled)
+        .map(|breakpoint| SourceBreakpoint {
+            line: breakpoint.line,
+            column: breakpoint.column,
+            condition: breakpoint.condition.clone(),
+            hit_condition: breakpoint.hit_condition.clone(),
+            log_message: breakpoint.log_message.clone(),
+        })
+        .collect()
+}
+
+/// Remap breakpoints whenever a file that has some is written
+fn watch_workspace(app: &AppHandle, workspace: &str) -> Option<RecommendedWatcher> {
+    let app = app.clone();
+    let watched_workspace = workspace.to_string();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+            return;
+        }
+        let store = app.state::<BreakpointStore>();
+        let tracked: BTreeSet<String> = store
+            .list(&app, &watched_workspace, None)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|breakpoint| breakpoint.path)
+            .collect();
+        for path in event.paths.iter().map(|path| path.to_string_lossy().to_string()) {
+            if tracked.contains(&path) {
+                if let Err(e) = store.remap_file(&app, &watched_workspace, &path) {
+                    tracing::warn!(path = %path, error = %e, "remapping breakpoints failed");
+                }
+            }
+        }
+    })
+    .ok()?;
+    watcher.watch(Path::new(workspace), RecursiveMode::Recursive).ok()?;
+    Some(watcher)
+}
+
+impl Default for BreakpointStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
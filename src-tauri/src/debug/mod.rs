@@ -0,0 +1,711 @@
+/**
+ * Debug Service for CodeForge IDE
+ * Drives debug adapters (codelldb, debugpy, js-debug) over the Debug Adapter Protocol
+ */
+
+mod adapters;
+mod breakpoints;
+mod protocol;
+
+pub use adapters::{builtin_adapter, AdapterCommand, AdapterTransport};
+pub use breakpoints::{BreakpointSpec, BreakpointStore, StoredBreakpoint};
+
+use crate::environment;
+use protocol::{AdapterMessage, DapConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted when a thread stops (breakpoint, step, exception, pause)
+pub const DEBUG_STOPPED_EVENT: &str = "debug://stopped";
+/// Event emitted when execution resumes
+pub const DEBUG_CONTINUED_EVENT: &str = "debug://continued";
+/// Event carrying debuggee and adapter output
+pub const DEBUG_OUTPUT_EVENT: &str = "debug://output";
+/// Event emitted once a session has ended
+pub const DEBUG_TERMINATED_EVENT: &str = "debug://terminated";
+
+/// Timeout for ordinary requests
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Launching can involve building or starting an interpreter
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a TCP adapter gets to start listening
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error types for debug operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DebugError {
+    UnknownDebugType(String),
+    AdapterStartFailed(String),
+    AdapterExited,
+    SessionNotFound(String),
+    RequestFailed(String),
+    Timeout(String),
+    BreakpointNotFound(String),
+    InvalidBreakpoint(String),
+    IOError(String),
+    Environment(String),
+}
+
+impl std::fmt::Display for DebugError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DebugError::UnknownDebugType(kind) => write!(f, "No debug adapter for type '{}'", kind),
+            DebugError::AdapterStartFailed(msg) => write!(f, "Failed to start debug adapter: {}", msg),
+            DebugError::AdapterExited => write!(f, "Debug adapter exited"),
+            DebugError::SessionNotFound(id) => write!(f, "Debug session not found: {}", id),
+            DebugError::RequestFailed(msg) => write!(f, "Debug request failed: {}", msg),
+            DebugError::Timeout(command) => write!(f, "Debug adapter did not answer '{}' in time", command),
+            DebugError::BreakpointNotFound(id) => write!(f, "Breakpoint not found: {}", id),
+            DebugError::InvalidBreakpoint(msg) => write!(f, "Invalid breakpoint: {}", msg),
+            DebugError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            DebugError::Environment(msg) => write!(f, "Invalid workspace environment: {}", msg),
+        }
+    }
+}
+
+/// Whether to start the debuggee or connect to a running process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DebugRequestKind {
+    Launch,
+    Attach,
+}
+
+/// A breakpoint as set by the user; `line` is one-based
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceBreakpoint {
+    pub line: usize,
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub hit_condition: Option<String>,
+    #[serde(default)]
+    pub log_message: Option<String>,
+}
+
+/// Everything needed to start a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugLaunchRequest {
+    pub name: String,
+    /// Debug type such as `lldb`, `python`, or `node`
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: DebugRequestKind,
+    /// Overrides the built-in adapter for the debug type
+    #[serde(default)]
+    pub adapter: Option<AdapterCommand>,
+    /// Adapter-specific launch or attach arguments (`program`, `args`, `cwd`, `port`, ...)
+    #[serde(default)]
+    pub configuration: Map<String, Value>,
+    /// Breakpoints per absolute file path, sent before the debuggee starts
+    #[serde(default)]
+    pub breakpoints: HashMap<String, Vec<SourceBreakpoint>>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Workspace whose stored breakpoints are added for files not listed in `breakpoints`
+    #[serde(default)]
+    pub workspace: Option<String>,
+}
+
+/// A running debug session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugSessionInfo {
+    pub id: String,
+    pub name: String,
+    pub debug_type: String,
+    pub request: DebugRequestKind,
+    pub workspace: Option<String>,
+    /// Capabilities the adapter reported in its `initialize` response
+    pub capabilities: Value,
+}
+
+/// A breakpoint as the adapter resolved it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Breakpoint {
+    pub id: Option<i64>,
+    pub verified: bool,
+    pub line: Option<usize>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugThread {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackFrame {
+    pub id: i64,
+    pub name: String,
+    pub source_path: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scope {
+    pub name: String,
+    pub variables_reference: i64,
+    pub expensive: bool,
+}
+
+/// A variable; a non-zero `variables_reference` means it has children
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    pub type_name: Option<String>,
+    pub variables_reference: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluateResult {
+    pub result: String,
+    pub type_name: Option<String>,
+    pub variables_reference: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugStoppedEvent {
+    pub session_id: String,
+    pub reason: String,
+    pub thread_id: Option<i64>,
+    pub description: Option<String>,
+    pub all_threads_stopped: bool,
+    pub hit_breakpoint_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugContinuedEvent {
+    pub session_id: String,
+    pub thread_id: Option<i64>,
+    pub all_threads_continued: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugOutputEvent {
+    pub session_id: String,
+    /// `console`, `stdout`, `stderr`, ...
+    pub category: String,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugTerminatedEvent {
+    pub session_id: String,
+    pub exit_code: Option<i64>,
+}
+
+struct DebugSession {
+    info: Mutex<DebugSessionInfo>,
+    connection: Arc<DapConnection>,
+    adapter: Mutex<Child>,
+}
+
+impl Drop for DebugSession {
+    fn drop(&mut self) {
+        let mut adapter = self.adapter.lock().unwrap();
+        let _ = adapter.kill();
+        let _ = adapter.wait();
+    }
+}
+
+type SessionMap = Arc<Mutex<HashMap<String, Arc<DebugSession>>>>;
+
+pub struct DebugService {
+    sessions: SessionMap,
+    next_id: AtomicU64,
+}
+
+fn as_usize(value: &Value) -> usize {
+    value.as_u64().unwrap_or(0) as usize
+}
+
+fn breakpoints_arguments(path: &str, breakpoints: &[SourceBreakpoint]) -> Value {
+    let breakpoints: Vec<Value> = breakpoints
+        .iter()
+        .map(|breakpoint| {
+            let mut value = json!({ "line": breakpoint.line });
+            if let Some(column) = breakpoint.column {
+                value["column"] = json!(column);
+            }
+            if let Some(condition) = &breakpoint.condition {
+                value["condition"] = json!(condition);
+            }
+            if let Some(hit_condition) = &breakpoint.hit_condition {
+                value["hitCondition"] = json!(hit_condition);
+            }
+            if let Some(log_message) = &breakpoint.log_message {
+                value["logMessage"] = json!(log_message);
+            }
+            value
+        })
+        .collect();
+    json!({ "source": { "path": path }, "breakpoints": breakpoints })
+}
+
+/// The adapter process with the reading and writing halves of its DAP channel
+type AdapterChannel = (Child, Box<dyn Read + Send>, Box<dyn Write + Send>);
+
+/// Start the adapter process and open the DAP channel to it
+fn spawn_adapter(adapter: &AdapterCommand, cwd: Option<&str>) -> Result<AdapterChannel, DebugError> {
+    let start_error = |e: std::io::Error| DebugError::AdapterStartFailed(format!("{}: {}", adapter.command, e));
+    let port = match adapter.transport {
+        AdapterTransport::Tcp => {
+            // Reserve a free port, then release it for the adapter to bind
+            let listener = TcpListener::bind("127.0.0.1:0").map_err(start_error)?;
+            Some(listener.local_addr().map_err(start_error)?.port())
+        }
+        AdapterTransport::Stdio => None,
+    };
+
+    let mut command = Command::new(&adapter.command);
+    command
+        .args(adapter.args.iter().map(|arg| match port {
+            Some(port) => arg.replace("{port}", &port.to_string()),
+            None => arg.clone(),
+        }))
+        .stdin(if port.is_none() { Stdio::piped() } else { Stdio::null() })
+        .stdout(if port.is_none() { Stdio::piped() } else { Stdio::null() })
+        .stderr(Stdio::null());
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command.spawn().map_err(start_error)?;
+
+    let Some(port) = port else {
+        let reader = child.stdout.take().ok_or(DebugError::AdapterExited)?;
+        let writer = child.stdin.take().ok_or(DebugError::AdapterExited)?;
+        return Ok((child, Box::new(reader), Box::new(writer)));
+    };
+
+    let started = Instant::now();
+    loop {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => {
+                let reader = stream.try_clone().map_err(start_error)?;
+                return Ok((child, Box::new(reader), Box::new(stream)));
+            }
+            Err(e) if started.elapsed() > CONNECT_TIMEOUT || child.try_wait().ok().flatten().is_some() => {
+                let _ = child.kill();
+                return Err(start_error(e));
+            }
+            Err(_) => thread::sleep(Duration::from_millis(50)),
+        }
+    }
+}
+
+impl DebugService {
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn session(&self, session_id: &str) -> Result<Arc<DebugSession>, DebugError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| DebugError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// Start an adapter, run the initialize / launch / configuration handshake, and return the session
+    pub fn start_session(
+        &self,
+        app: &AppHandle,
+        mut request: DebugLaunchRequest,
+    ) -> Result<DebugSessionInfo, DebugError> {
+        if let Some(workspace) = &request.workspace {
+            for (path, breakpoints) in app.state::<BreakpointStore>().session_breakpoints(app, workspace)? {
+                request.breakpoints.entry(path).or_insert(breakpoints);
+            }
+            // The debuggee gets the workspace `.env` variables under the configuration's own `env`
+            let workspace_env =
+                environment::workspace_env(workspace).map_err(|e| DebugError::Environment(e.to_string()))?;
+            if !workspace_env.is_empty() {
+                let env = request.configuration.entry("env").or_insert_with(|| json!({}));
+                if let Value::Object(env) = env {
+                    for (name, value) in workspace_env {
+                        env.entry(name).or_insert(Value::String(value));
+                    }
+                }
+            }
+        }
+        let adapter = request
+            .adapter
+            .clone()
+            .or_else(|| builtin_adapter(&request.debug_type))
+            .ok_or_else(|| DebugError::UnknownDebugType(request.debug_type.clone()))?;
+        let (child, reader, writer) = spawn_adapter(&adapter, request.cwd.as_deref())?;
+
+        let session_id = format!("debug-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+        let (initialized_sender, initialized) = mpsc::channel();
+        let initialized_sender = Mutex::new(Some(initialized_sender));
+        let exit_code = Mutex::new(None);
+        let event_app = app.clone();
+        let event_session_id = session_id.clone();
+        let sessions = self.sessions.clone();
+        let connection = DapConnection::start(reader, writer, move |message| {
+            if let AdapterMessage::Event { event, .. } = &message {
+                if event == "initialized" {
+                    if let Some(sender) = initialized_sender.lock().unwrap().take() {
+                        let _ = sender.send(());
+                    }
+                    return;
+                }
+            }
+            handle_message(&event_app, &sessions, &event_session_id, &exit_code, message);
+        });
+
+        let session = Arc::new(DebugSession {
+            info: Mutex::new(DebugSessionInfo {
+                id: session_id.clone(),
+                name: request.name.clone(),
+                debug_type: request.debug_type.clone(),
+                request: request.request,
+                workspace: request.workspace.clone(),
+                capabilities: Value::Null,
+            }),
+            connection: connection.clone(),
+            adapter: Mutex::new(child),
+        });
+
+        // Dropping `session` on any error below kills the adapter
+        let capabilities = connection.request(
+            "initialize",
+            json!({
+                "clientID": "codeforge",
+                "clientName": "CodeForge IDE",
+                "adapterID": request.debug_type,
+                "linesStartAt1": true,
+                "columnsStartAt1": true,
+                "pathFormat": "path",
+                "supportsVariableType": true,
+                "supportsRunInTerminalRequest": false,
+            }),
+            REQUEST_TIMEOUT,
+        )?;
+
+        let command = match request.request {
+            DebugRequestKind::Launch => "launch",
+            DebugRequestKind::Attach => "attach",
+        };
+        let started = connection.send_request(command, Value::Object(request.configuration.clone()))?;
+
+        // Adapters announce `initialized` when ready for breakpoints; many only answer the launch
+        // request after `configurationDone`, while a failing launch is answered right away
+        let deadline = Instant::now() + LAUNCH_TIMEOUT;
+        let mut configured = false;
+        let mut launched = false;
+        while !(configured && launched) {
+            if Instant::now() > deadline {
+                return Err(DebugError::Timeout(command.to_string()));
+            }
+            if !launched {
+                if let Ok(result) = started.try_recv() {
+                    result?;
+                    launched = true;
+                }
+            }
+            if !configured && initialized.try_recv().is_ok() {
+                for (path, breakpoints) in &request.breakpoints {
+                    let arguments = breakpoints_arguments(path, breakpoints);
+                    connection.request("setBreakpoints", arguments, REQUEST_TIMEOUT)?;
+                }
+                connection.request("setExceptionBreakpoints", json!({ "filters": [] }), REQUEST_TIMEOUT)?;
+                if capabilities["supportsConfigurationDoneRequest"].as_bool().unwrap_or(false) {
+                    connection.request("configurationDone", json!({}), REQUEST_TIMEOUT)?;
+                }
+                configured = true;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let info = {
+            let mut info = session.info.lock().unwrap();
+            info.capabilities = capabilities;
+            info.clone()
+        };
+        self.sessions.lock().unwrap().insert(session_id, session);
+        Ok(info)
+    }
+
+    /// Process ids of the running debug adapters
+    pub fn adapter_pids(&self) -> Vec<u32> {
+        self.sessions.lock().unwrap().values().map(|session| session.adapter.lock().unwrap().id()).collect()
+    }
+
+    /// Running sessions
+    pub fn list_sessions(&self) -> Vec<DebugSessionInfo> {
+        let mut sessions: Vec<DebugSessionInfo> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .values()
+            .map(|session| session.info.lock().unwrap().clone())
+            .collect();
+        sessions.sort_by(|a, b| a.id.cmp(&b.id));
+        sessions
+    }
+
+    /// End a session, terminating a launched debuggee (attached processes keep running)
+    pub fn stop_session(&self, session_id: &str) -> Result<(), DebugError> {
+        let session = self.session(session_id)?;
+        let terminate = session.info.lock().unwrap().request == DebugRequestKind::Launch;
+        let _ = session.connection.request(
+            "disconnect",
+            json!({ "terminateDebuggee": terminate }),
+            Duration::from_secs(2),
+        );
+        self.sessions.lock().unwrap().remove(session_id);
+        Ok(())
+    }
+
+    /// Replace all breakpoints of a file
+    pub fn set_breakpoints(
+        &self,
+        session_id: &str,
+        path: &str,
+        breakpoints: &[SourceBreakpoint],
+    ) -> Result<Vec<Breakpoint>, DebugError> {
+        let body = self.session(session_id)?.connection.request(
+            "setBreakpoints",
+            breakpoints_arguments(path, breakpoints),
+            REQUEST_TIMEOUT,
+        )?;
+        Ok(body["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|breakpoint| Breakpoint {
+                id: breakpoint["id"].as_i64(),
+                verified: breakpoint["verified"].as_bool().unwrap_or(false),
+                line: breakpoint["line"].as_u64().map(|line| line as usize),
+                message: breakpoint["message"].as_str().map(str::to_string),
+            })
+            .collect())
+    }
+
+    /// Push a file's breakpoints to every session of the workspace without waiting for the adapters
+    pub fn sync_breakpoints(&self, workspace: &str, path: &str, breakpoints: Vec<SourceBreakpoint>) {
+        let sessions: Vec<Arc<DebugSession>> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|session| session.info.lock().unwrap().workspace.as_deref() == Some(workspace))
+            .cloned()
+            .collect();
+        for session in sessions {
+            let arguments = breakpoints_arguments(path, &breakpoints);
+            thread::spawn(move || {
+                let _ = session.connection.request("setBreakpoints", arguments, REQUEST_TIMEOUT);
+            });
+        }
+    }
+
+    /// `continue`, `next`, `stepIn`, `stepOut`, or `pause` for one thread
+    pub fn control(&self, session_id: &str, command: &str, thread_id: i64) -> Result<(), DebugError> {
+        if !matches!(command, "continue" | "next" | "stepIn" | "stepOut" | "pause") {
+            return Err(DebugError::RequestFailed(format!("Unsupported command: {}", command)));
+        }
+        self.session(session_id)?
+            .connection
+            .request(command, json!({ "threadId": thread_id }), REQUEST_TIMEOUT)?;
+        Ok(())
+    }
+
+    pub fn threads(&self, session_id: &str) -> Result<Vec<DebugThread>, DebugError> {
+        let body = self.session(session_id)?.connection.request("threads", json!({}), REQUEST_TIMEOUT)?;
+        Ok(body["threads"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|thread| DebugThread {
+                id: thread["id"].as_i64().unwrap_or(0),
+                name: thread["name"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect())
+    }
+
+    pub fn stack_trace(
+        &self,
+        session_id: &str,
+        thread_id: i64,
+        levels: Option<usize>,
+    ) -> Result<Vec<StackFrame>, DebugError> {
+        let mut arguments = json!({ "threadId": thread_id });
+        if let Some(levels) = levels {
+            arguments["levels"] = json!(levels);
+        }
+        let body = self.session(session_id)?.connection.request("stackTrace", arguments, REQUEST_TIMEOUT)?;
+        Ok(body["stackFrames"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|frame| StackFrame {
+                id: frame["id"].as_i64().unwrap_or(0),
+                name: frame["name"].as_str().unwrap_or_default().to_string(),
+                source_path: frame["source"]["path"].as_str().map(str::to_string),
+                line: as_usize(&frame["line"]),
+                column: as_usize(&frame["column"]),
+            })
+            .collect())
+    }
+
+    pub fn scopes(&self, session_id: &str, frame_id: i64) -> Result<Vec<Scope>, DebugError> {
+        let body = self
+            .session(session_id)?
+            .connection
+            .request("scopes", json!({ "frameId": frame_id }), REQUEST_TIMEOUT)?;
+        Ok(body["scopes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|scope| Scope {
+                name: scope["name"].as_str().unwrap_or_default().to_string(),
+                variables_reference: scope["variablesReference"].as_i64().unwrap_or(0),
+                expensive: scope["expensive"].as_bool().unwrap_or(false),
+            })
+            .collect())
+    }
+
+    pub fn variables(&self, session_id: &str, variables_reference: i64) -> Result<Vec<Variable>, DebugError> {
+        let body = self.session(session_id)?.connection.request(
+            "variables",
+            json!({ "variablesReference": variables_reference }),
+            REQUEST_TIMEOUT,
+        )?;
+        Ok(body["variables"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|variable| Variable {
+                name: variable["name"].as_str().unwrap_or_default().to_string(),
+                value: variable["value"].as_str().unwrap_or_default().to_string(),
+                type_name: variable["type"].as_str().map(str::to_string),
+                variables_reference: variable["variablesReference"].as_i64().unwrap_or(0),
+            })
+            .collect())
+    }
+
+    /// Evaluate an expression; `context` is `watch`, `repl`, or `hover`
+    pub fn evaluate(
+        &self,
+        session_id: &str,
+        expression: &str,
+        frame_id: Option<i64>,
+        context: &str,
+    ) -> Result<EvaluateResult, DebugError> {
+        let mut arguments = json!({ "expression": expression, "context": context });
+        if let Some(frame_id) = frame_id {
+            arguments["frameId"] = json!(frame_id);
+        }
+        let body = self.session(session_id)?.connection.request("evaluate", arguments, REQUEST_TIMEOUT)?;
+        Ok(EvaluateResult {
+            result: body["result"].as_str().unwrap_or_default().to_string(),
+            type_name: body["type"].as_str().map(str::to_string),
+            variables_reference: body["variablesReference"].as_i64().unwrap_or(0),
+        })
+    }
+}
+
+/// Translate adapter events into IDE events; the session is dropped once the adapter is done
+fn handle_message(
+    app: &AppHandle,
+    sessions: &SessionMap,
+    session_id: &str,
+    exit_code: &Mutex<Option<i64>>,
+    message: AdapterMessage,
+) {
+    let session_id = session_id.to_string();
+    let body = match message {
+        AdapterMessage::Event { event, body } => match event.as_str() {
+            "stopped" => {
+                let _ = app.emit(
+                    DEBUG_STOPPED_EVENT,
+                    DebugStoppedEvent {
+                        session_id,
+                        reason: body["reason"].as_str().unwrap_or_default().to_string(),
+                        thread_id: body["threadId"].as_i64(),
+                        description: body["description"].as_str().or(body["text"].as_str()).map(str::to_string),
+                        all_threads_stopped: body["allThreadsStopped"].as_bool().unwrap_or(false),
+                        hit_breakpoint_ids: body["hitBreakpointIds"]
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_i64)
+                            .collect(),
+                    },
+                );
+                return;
+            }
+            "continued" => {
+                let _ = app.emit(
+                    DEBUG_CONTINUED_EVENT,
+                    DebugContinuedEvent {
+                        session_id,
+                        thread_id: body["threadId"].as_i64(),
+                        all_threads_continued: body["allThreadsContinued"].as_bool().unwrap_or(true),
+                    },
+                );
+                return;
+            }
+            "output" => {
+                let _ = app.emit(
+                    DEBUG_OUTPUT_EVENT,
+                    DebugOutputEvent {
+                        session_id,
+                        category: body["category"].as_str().unwrap_or("console").to_string(),
+                        output: body["output"].as_str().unwrap_or_default().to_string(),
+                    },
+                );
+                return;
+            }
+            "exited" => {
+                *exit_code.lock().unwrap() = body["exitCode"].as_i64();
+                return;
+            }
+            "terminated" => body,
+            _ => return,
+        },
+        AdapterMessage::Closed => Value::Null,
+    };
+
+    // `terminated` and the adapter closing both end the session; report it once
+    let Some(session) = sessions.lock().unwrap().remove(&session_id) else {
+        return;
+    };
+    if !body.is_null() {
+        let _ = session.connection.send_request("disconnect", json!({}));
+    }
+    let _ = app.emit(
+        DEBUG_TERMINATED_EVENT,
+        DebugTerminatedEvent {
+            session_id,
+            exit_code: *exit_code.lock().unwrap(),
+        },
+    );
+}
+
+impl Default for DebugService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,173 @@
+/**
+ * Debug Adapter Protocol wire format
+ * Content-Length framed JSON messages with request/response correlation by sequence number
+ */
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::DebugError;
+
+/// Something the adapter sent that isn't a response to one of our requests
+pub(super) enum AdapterMessage {
+    Event { event: String, body: Value },
+    /// The adapter closed its output, usually because it exited
+    Closed,
+}
+
+type PendingResponse = Sender<Result<Value, DebugError>>;
+
+pub(super) struct DapConnection {
+    writer: Mutex<Box<dyn Write + Send>>,
+    next_seq: AtomicI64,
+    pending: Mutex<HashMap<i64, PendingResponse>>,
+}
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+impl DapConnection {
+    /// Start reading adapter output on a background thread; events go to `on_message`
+    pub fn start(
+        reader: Box<dyn Read + Send>,
+        writer: Box<dyn Write + Send>,
+        on_message: impl Fn(AdapterMessage) + Send + 'static,
+    ) -> Arc<Self> {
+        let connection = Arc::new(Self {
+            writer: Mutex::new(writer),
+            next_seq: AtomicI64::new(1),
+            pending: Mutex::new(HashMap::new()),
+        });
+
+        let reader_connection = connection.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            while let Some(message) = read_message(&mut reader) {
+                match message.get("type").and_then(Value::as_str) {
+                    Some("response") => reader_connection.complete(message),
+                    Some("event") => on_message(AdapterMessage::Event {
+                        event: message["event"].as_str().unwrap_or_default().to_string(),
+                        body: message.get("body").cloned().unwrap_or(Value::Null),
+                    }),
+                    Some("request") => reader_connection.decline_reverse_request(&message),
+                    _ => {}
+                }
+            }
+
+            for (_, pending) in reader_connection.pending.lock().unwrap().drain() {
+                let _ = pending.send(Err(DebugError::AdapterExited));
+            }
+            on_message(AdapterMessage::Closed);
+        });
+
+        connection
+    }
+
+    fn complete(&self, response: Value) {
+        let Some(request_seq) = response.get("request_seq").and_then(Value::as_i64) else {
+            return;
+        };
+        let Some(pending) = self.pending.lock().unwrap().remove(&request_seq) else {
+            return;
+        };
+
+        let result = if response["success"].as_bool().unwrap_or(false) {
+            Ok(response.get("body").cloned().unwrap_or(Value::Null))
+        } else {
+            // Prefer the formatted error from the body over the short `message` code
+            let message = response["body"]["error"]["format"]
+                .as_str()
+                .or(response["message"].as_str())
+                .unwrap_or("Request failed");
+            Err(DebugError::RequestFailed(message.to_string()))
+        };
+        let _ = pending.send(result);
+    }
+
+    /// Reverse requests such as `runInTerminal` and `startDebugging` are not supported; answering
+    /// with an error lets the adapter fall back instead of waiting forever
+    fn decline_reverse_request(&self, request: &Value) {
+        let response = json!({
+            "seq": self.next_seq.fetch_add(1, Ordering::SeqCst),
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": false,
+            "command": request["command"],
+            "message": "Not supported by CodeForge",
+        });
+        let _ = self.write(&response);
+    }
+
+    fn write(&self, message: &Value) -> Result<(), DebugError> {
+        let body = message.to_string();
+        let mut writer = self.writer.lock().unwrap();
+        write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)
+            .and_then(|_| writer.flush())
+            .map_err(|_| DebugError::AdapterExited)
+    }
+
+    /// Send a request without waiting; the receiver yields the response body
+    pub fn send_request(
+        &self,
+        command: &str,
+        arguments: Value,
+    ) -> Result<Receiver<Result<Value, DebugError>>, DebugError> {
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = mpsc::channel();
+        self.pending.lock().unwrap().insert(seq, sender);
+
+        let message = json!({
+            "seq": seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+        if let Err(e) = self.write(&message) {
+            self.pending.lock().unwrap().remove(&seq);
+            return Err(e);
+        }
+        Ok(receiver)
+    }
+
+    /// Send a request and wait for its response body
+    pub fn request(&self, command: &str, arguments: Value, timeout: Duration) -> Result<Value, DebugError> {
+        let receiver = self.send_request(command, arguments)?;
+        wait_response(command, &receiver, timeout)
+    }
+}
+
+pub(super) fn wait_response(
+    command: &str,
+    receiver: &Receiver<Result<Value, DebugError>>,
+    timeout: Duration,
+) -> Result<Value, DebugError> {
+    receiver
+        .recv_timeout(timeout)
+        .map_err(|_| DebugError::Timeout(command.to_string()))?
+}
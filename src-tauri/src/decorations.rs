@@ -0,0 +1,305 @@
+/**
+ * Inline decorations for CodeForge IDE
+ * Finds color literals and asset references so the editor can draw swatches and flag broken assets
+ */
+
+use crate::types::FileSystemError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Extensions that mark a string literal in script files as an asset path
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "avif", "ico", "bmp", "woff", "woff2", "ttf", "otf", "eot", "mp3",
+    "mp4", "webm", "wav", "ogg",
+];
+
+/// RGBA color with components in 0..=1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rgba {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+/// A color literal on a single line
+///
+/// Columns are measured in UTF-16 code units to match JavaScript string offsets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorReference {
+    pub line: usize,
+    pub start_column: usize,
+    pub length: usize,
+    pub text: String,
+    pub color: Rgba,
+}
+
+/// A reference to a file on disk, such as `url(...)` or `include_bytes!(...)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetReference {
+    pub line: usize,
+    pub start_column: usize,
+    pub length: usize,
+    pub reference: String,
+    /// Absolute path the reference points to; `None` when it cannot be resolved locally
+    pub resolved_path: Option<String>,
+    pub exists: bool,
+}
+
+/// Decorations found in a document
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentDecorations {
+    pub path: String,
+    pub colors: Vec<ColorReference>,
+    pub assets: Vec<AssetReference>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DocumentKind {
+    Stylesheet,
+    Script,
+    Rust,
+}
+
+impl DocumentKind {
+    fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "css" | "scss" | "sass" | "less" => Some(DocumentKind::Stylesheet),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Some(DocumentKind::Script),
+            "rs" => Some(DocumentKind::Rust),
+            _ => None,
+        }
+    }
+}
+
+fn color_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i)#[0-9a-f]{3,8}\b|\b(?:rgba?|hsla?)\(\s*[^()]*\)").expect("valid color regex")
+    })
+}
+
+fn string_literal_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|`(?:[^`\\]|\\.)*`"#).expect("valid string regex")
+    })
+}
+
+fn stylesheet_asset_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"url\(\s*['"]?([^'")\s]+)['"]?\s*\)|@import\s+['"]([^'"]+)['"]"#).expect("valid url regex")
+    })
+}
+
+fn rust_asset_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"include_(?:str|bytes)!\(\s*"([^"]+)""#).expect("valid include regex"))
+}
+
+/// Scan a CSS/SCSS, JavaScript/TypeScript, or Rust file; other files yield no decorations
+pub fn document_decorations(path: &str, workspace: Option<&str>) -> Result<DocumentDecorations, FileSystemError> {
+    let file_path = Path::new(path);
+    let mut decorations = DocumentDecorations {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let Some(kind) = DocumentKind::for_path(file_path) else {
+        return Ok(decorations);
+    };
+
+    let source = fs::read_to_string(file_path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => FileSystemError::NotFound,
+        io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+        _ => FileSystemError::IOError(e.to_string()),
+    })?;
+    let base = file_path.parent().unwrap_or(Path::new(""));
+
+    for (line_number, line) in source.lines().enumerate() {
+        let mut push_asset = |start: usize, reference: &str| {
+            let resolved = resolve_asset(reference, base, workspace);
+            decorations.assets.push(AssetReference {
+                line: line_number,
+                start_column: utf16_column(line, start),
+                length: reference.encode_utf16().count(),
+                reference: reference.to_string(),
+                exists: resolved.as_ref().is_some_and(|resolved| resolved.exists()),
+                resolved_path: resolved.map(|resolved| resolved.to_string_lossy().to_string()),
+            });
+        };
+
+        match kind {
+            DocumentKind::Stylesheet => {
+                for captures in stylesheet_asset_regex().captures_iter(line) {
+                    if let Some(reference) = captures.get(1).or_else(|| captures.get(2)) {
+                        push_asset(reference.start(), reference.as_str());
+                    }
+                }
+            }
+            DocumentKind::Script => {
+                for literal in string_literal_regex().find_iter(line) {
+                    let content = &literal.as_str()[1..literal.len() - 1];
+                    if is_local_asset(content) {
+                        push_asset(literal.start() + 1, content);
+                    }
+                }
+            }
+            DocumentKind::Rust => {
+                for captures in rust_asset_regex().captures_iter(line) {
+                    let reference = captures.get(1).expect("include path group");
+                    push_asset(reference.start(), reference.as_str());
+                }
+            }
+        }
+
+        if kind == DocumentKind::Stylesheet {
+            // `#id` selectors look like hex colors; only declaration lines hold color values
+            let trimmed = line.trim_end();
+            if !trimmed.ends_with('{') && !trimmed.ends_with(',') {
+                push_colors(&mut decorations.colors, line_number, line, 0, line);
+            }
+        } else {
+            // In code, only string literals carry colors; this skips `#private` fields and attributes
+            for literal in string_literal_regex().find_iter(line) {
+                push_colors(&mut decorations.colors, line_number, line, literal.start(), literal.as_str());
+            }
+        }
+    }
+
+    Ok(decorations)
+}
+
+fn push_colors(colors: &mut Vec<ColorReference>, line_number: usize, line: &str, offset: usize, text: &str) {
+    for found in color_regex().find_iter(text) {
+        if let Some(color) = parse_color(found.as_str()) {
+            colors.push(ColorReference {
+                line: line_number,
+                start_column: utf16_column(line, offset + found.start()),
+                length: found.as_str().encode_utf16().count(),
+                text: found.as_str().to_string(),
+                color,
+            });
+        }
+    }
+}
+
+fn utf16_column(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset.min(line.len())].encode_utf16().count()
+}
+
+/// Relative or root-relative path with a known asset extension
+fn is_local_asset(reference: &str) -> bool {
+    let local = reference.starts_with("./") || reference.starts_with("../") || reference.starts_with('/');
+    let path = reference.split(['?', '#']).next().unwrap_or(reference);
+    local
+        && !reference.starts_with("//")
+        && Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Resolve a reference against the referencing file, or the workspace root for `/`-prefixed paths
+fn resolve_asset(reference: &str, base: &Path, workspace: Option<&str>) -> Option<PathBuf> {
+    if reference.contains("://") || reference.starts_with("//") || reference.starts_with("data:") {
+        return None;
+    }
+    // Cache-busting queries and font fragments are not part of the file name
+    let path = reference.split(['?', '#']).next().unwrap_or(reference);
+    if path.is_empty() {
+        return None;
+    }
+
+    let resolved = match path.strip_prefix('/') {
+        Some(rooted) => Path::new(workspace?).join(rooted),
+        None => base.join(path),
+    };
+    Some(resolved.components().filter(|c| *c != Component::CurDir).collect())
+}
+
+/// Parse hex, `rgb()`/`rgba()`, or `hsl()`/`hsla()` notation
+fn parse_color(text: &str) -> Option<Rgba> {
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    let (function, arguments) = text.split_once('(')?;
+    let arguments: Vec<&str> = arguments
+        .trim_end_matches(')')
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|argument| !argument.is_empty())
+        .collect();
+    if arguments.len() < 3 || arguments.len() > 4 {
+        return None;
+    }
+    let alpha = match arguments.get(3) {
+        Some(alpha) => parse_component(alpha, 1.0)?,
+        None => 1.0,
+    };
+
+    match function.to_lowercase().as_str() {
+        "rgb" | "rgba" => Some(Rgba {
+            red: parse_component(arguments[0], 255.0)?,
+            green: parse_component(arguments[1], 255.0)?,
+            blue: parse_component(arguments[2], 255.0)?,
+            alpha,
+        }),
+        "hsl" | "hsla" => {
+            let hue = arguments[0].trim_end_matches("deg").parse::<f32>().ok()?;
+            let saturation = parse_component(arguments[1], 100.0)?;
+            let lightness = parse_component(arguments[2], 100.0)?;
+            let (red, green, blue) = hsl_to_rgb(hue, saturation, lightness);
+            Some(Rgba { red, green, blue, alpha })
+        }
+        _ => None,
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<_>>()?;
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|digit| digit * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+        _ => return None,
+    };
+
+    Some(Rgba {
+        red: channels[0] as f32 / 255.0,
+        green: channels[1] as f32 / 255.0,
+        blue: channels[2] as f32 / 255.0,
+        alpha: channels.get(3).map(|alpha| *alpha as f32 / 255.0).unwrap_or(1.0),
+    })
+}
+
+/// A number on the given scale, or a percentage, normalized to 0..=1
+fn parse_component(text: &str, scale: f32) -> Option<f32> {
+    let value = match text.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => text.parse::<f32>().ok()? / scale,
+    };
+    Some(value.clamp(0.0, 1.0))
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (f32, f32, f32) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    (red + m, green + m, blue + m)
+}
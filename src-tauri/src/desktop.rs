@@ -0,0 +1,142 @@
+/**
+ * Desktop integration for CodeForge IDE
+ * Native open/save dialogs, revealing files in the system file manager, and opening files with their
+ * default application
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::file_system::FileSystemService;
+use crate::file_type::{self, FileCategory};
+use crate::types::FileSystemError;
+
+/// Extensions the OS runs rather than opens, beyond what content detection flags as executable
+const LAUNCHABLE_EXTENSIONS: &[&str] = &[
+    "app", "bat", "cmd", "com", "command", "cpl", "exe", "hta", "jar", "js", "jse", "lnk", "msc", "msi", "pif",
+    "ps1", "reg", "scr", "sh", "url", "vbe", "vbs", "wsf", "wsh",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogFilter {
+    pub name: String,
+    /// Without the dot, e.g. `rs`
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenDialogOptions {
+    pub title: Option<String>,
+    /// Folder to start in, or a file to preselect
+    pub default_path: Option<String>,
+    pub filters: Vec<DialogFilter>,
+    pub multiple: bool,
+    /// Pick folders instead of files
+    pub directory: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveDialogOptions {
+    pub title: Option<String>,
+    /// Folder to start in, or a suggested file path
+    pub default_path: Option<String>,
+    pub filters: Vec<DialogFilter>,
+}
+
+fn dialog(
+    app: &AppHandle,
+    title: Option<&str>,
+    default_path: Option<&str>,
+    filters: &[DialogFilter],
+) -> FileDialogBuilder<tauri::Wry> {
+    let mut builder = app.dialog().file();
+    if let Some(title) = title {
+        builder = builder.set_title(title);
+    }
+    if let Some(default_path) = default_path.map(Path::new) {
+        if default_path.is_dir() {
+            builder = builder.set_directory(default_path);
+        } else {
+            if let Some(parent) = default_path.parent().filter(|parent| parent.is_dir()) {
+                builder = builder.set_directory(parent);
+            }
+            if let Some(name) = default_path.file_name() {
+                builder = builder.set_file_name(name.to_string_lossy());
+            }
+        }
+    }
+    for filter in filters {
+        let extensions: Vec<&str> = filter.extensions.iter().map(|ext| ext.trim_start_matches('.')).collect();
+        builder = builder.add_filter(&filter.name, &extensions);
+    }
+    builder
+}
+
+/// Picked paths become accessible to the other file commands, like paths dropped onto the window
+fn allow_picked(app: &AppHandle, picked: Vec<FilePath>) -> Vec<String> {
+    let fs = app.state::<FileSystemService>();
+    picked
+        .into_iter()
+        .filter_map(|path| path.into_path().ok())
+        .map(|path| {
+            let path = path.to_string_lossy().to_string();
+            let _ = fs.allow_path(&path);
+            path
+        })
+        .collect()
+}
+
+/// Show a native open dialog and wait for it; `None` when the user cancels. Blocks, so call it off the
+/// main thread.
+pub fn show_open_dialog(app: &AppHandle, options: &OpenDialogOptions) -> Option<Vec<String>> {
+    let builder = dialog(app, options.title.as_deref(), options.default_path.as_deref(), &options.filters);
+    let picked = match (options.directory, options.multiple) {
+        (false, false) => builder.blocking_pick_file().map(|path| vec![path]),
+        (false, true) => builder.blocking_pick_files(),
+        (true, false) => builder.blocking_pick_folder().map(|path| vec![path]),
+        (true, true) => builder.blocking_pick_folders(),
+    }?;
+    Some(allow_picked(app, picked))
+}
+
+/// Show a native save dialog and wait for it; `None` when the user cancels. Blocks, so call it off the
+/// main thread.
+pub fn show_save_dialog(app: &AppHandle, options: &SaveDialogOptions) -> Option<String> {
+    let builder = dialog(app, options.title.as_deref(), options.default_path.as_deref(), &options.filters);
+    let picked = builder.set_can_create_directories(true).blocking_save_file()?;
+    allow_picked(app, vec![picked]).pop()
+}
+
+/// Show a file or folder selected in Finder, Explorer, or the Linux file manager
+pub fn reveal_in_file_manager(app: &AppHandle, path: &Path) -> Result<(), FileSystemError> {
+    if !path.exists() {
+        return Err(FileSystemError::NotFound);
+    }
+    app.opener().reveal_item_in_dir(path).map_err(|e| FileSystemError::IOError(e.to_string()))
+}
+
+/// Open a file with the application the OS associates with it. Programs and scripts are refused: opening
+/// them would run them.
+pub fn open_with_default_app(app: &AppHandle, path: &Path) -> Result<(), FileSystemError> {
+    if !path.exists() {
+        return Err(FileSystemError::NotFound);
+    }
+    // macOS `.app` bundles are directories, so the extension is checked for those too
+    let launchable_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| LAUNCHABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    let executable = path.is_file()
+        && file_type::detect_file_type(path).is_ok_and(|detected| detected.category == FileCategory::Executable);
+    if launchable_extension || executable {
+        return Err(FileSystemError::Unsupported("opening programs and scripts".to_string()));
+    }
+    app.opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| FileSystemError::IOError(e.to_string()))
+}
@@ -0,0 +1,224 @@
+/**
+ * `devcontainer.json` parsing
+ * The subset of the Dev Container spec for single containers: an image or a Dockerfile, the workspace
+ * mount and folder, users, environment, ports, extra mounts and `docker run` arguments, and the
+ * `postCreateCommand` / `postStartCommand` lifecycle commands. Docker Compose configs aren't supported.
+ *
+ * `${localWorkspaceFolder}`, `${localWorkspaceFolderBasename}`, `${containerWorkspaceFolder}`,
+ * `${containerWorkspaceFolderBasename}`, `${devcontainerId}` and `${localEnv:NAME[:default]}` are
+ * substituted in every string.
+ */
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::DevContainerError;
+use crate::jsonc;
+
+/// Where a workspace's config is looked for, in order
+const CONFIG_LOCATIONS: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// How the image is built from a Dockerfile; paths are relative to the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildConfig {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+    pub target: Option<String>,
+}
+
+/// A lifecycle command: a shell line, a program with arguments, or several of those by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    Line(String),
+    Program(Vec<String>),
+    Named(BTreeMap<String, LifecycleCommand>),
+}
+
+/// An entry of `forwardPorts`; `"service:port"` entries name Docker Compose services
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PortEntry {
+    Port(u16),
+    Service(String),
+}
+
+/// An entry of `mounts`, in `docker run --mount` syntax or as its fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MountEntry {
+    Spec(String),
+    Fields {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: String,
+    },
+}
+
+impl MountEntry {
+    /// The argument for `docker run --mount`
+    pub fn spec(&self) -> String {
+        match self {
+            MountEntry::Spec(spec) => spec.clone(),
+            MountEntry::Fields { source, target, kind } => match source {
+                Some(source) => format!("type={},source={},target={}", kind, source, target),
+                None => format!("type={},target={}", kind, target),
+            },
+        }
+    }
+}
+
+/// A parsed `devcontainer.json`, with variables substituted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<BuildConfig>,
+    /// Older spelling of `build.dockerfile`
+    #[serde(skip_serializing)]
+    docker_file: Option<String>,
+    #[serde(skip_serializing)]
+    docker_compose_file: Option<serde_json::Value>,
+    /// Where the workspace is in the container; `/workspaces/<folder name>` unless set
+    pub workspace_folder: Option<String>,
+    /// `docker run --mount` argument mounting the workspace; a bind mount at `workspaceFolder` unless set
+    pub workspace_mount: Option<String>,
+    /// Who the container's main process runs as
+    pub container_user: Option<String>,
+    /// Who terminals, tasks, file operations and lifecycle commands run as; `containerUser` unless set
+    pub remote_user: Option<String>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub forward_ports: Vec<PortEntry>,
+    #[serde(default)]
+    pub mounts: Vec<MountEntry>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    /// Keep the container running with a command that sleeps instead of the image's; defaults to true
+    pub override_command: Option<bool>,
+    pub post_create_command: Option<LifecycleCommand>,
+    pub post_start_command: Option<LifecycleCommand>,
+    /// The file this was read from
+    #[serde(default)]
+    pub config_path: PathBuf,
+}
+
+impl DevContainerConfig {
+    /// The Dockerfile to build, if the image is built rather than pulled
+    pub fn dockerfile(&self) -> Option<PathBuf> {
+        let dockerfile = self.build.as_ref().and_then(|build| build.dockerfile.as_ref());
+        let dockerfile = dockerfile.or(self.docker_file.as_ref())?;
+        Some(self.config_dir().join(dockerfile))
+    }
+
+    /// Directory the image is built in
+    pub fn build_context(&self) -> PathBuf {
+        let context = self.build.as_ref().and_then(|build| build.context.as_deref()).unwrap_or(".");
+        self.config_dir().join(context)
+    }
+
+    pub fn config_dir(&self) -> &Path {
+        self.config_path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Ports published on localhost; Compose service ports don't apply to a single container
+    pub fn ports(&self) -> Vec<u16> {
+        self.forward_ports
+            .iter()
+            .filter_map(|entry| match entry {
+                PortEntry::Port(port) => Some(*port),
+                PortEntry::Service(text) => text.parse().ok(),
+            })
+            .collect()
+    }
+
+    /// Who processes started in the running container run as
+    pub fn user(&self) -> Option<&str> {
+        self.remote_user.as_deref().or(self.container_user.as_deref())
+    }
+}
+
+/// The config file of a workspace, if it has one
+pub fn find_config(workspace: &Path) -> Option<PathBuf> {
+    CONFIG_LOCATIONS.iter().map(|location| workspace.join(location)).find(|path| path.is_file())
+}
+
+/// Read and resolve the config at `path` for the workspace at `workspace`, whose container is identified
+/// by `id`
+pub fn load_config(path: &Path, workspace: &Path, id: &str) -> Result<DevContainerConfig, DevContainerError> {
+    let invalid = |message: String| DevContainerError::InvalidConfig(format!("{}: {}", path.display(), message));
+    let content = fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&jsonc::strip(&content)).map_err(|e| invalid(e.to_string()))?;
+
+    let basename = |path: &str| path.trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_string();
+    let local_folder = workspace.to_string_lossy().to_string();
+    let mut variables = HashMap::from([
+        ("localWorkspaceFolder".to_string(), local_folder.clone()),
+        ("localWorkspaceFolderBasename".to_string(), basename(&local_folder.replace('\\', "/"))),
+        ("devcontainerId".to_string(), id.to_string()),
+    ]);
+    // The container folder is itself a setting, which can only use the other variables
+    let default_folder = format!("/workspaces/{}", variables["localWorkspaceFolderBasename"]);
+    let container_folder = match value.get("workspaceFolder").and_then(|folder| folder.as_str()) {
+        Some(folder) => substitute(folder, &variables),
+        None => default_folder,
+    };
+    if !container_folder.starts_with('/') {
+        return Err(invalid(format!("\"workspaceFolder\" {} isn't an absolute path", container_folder)));
+    }
+    variables.insert("containerWorkspaceFolderBasename".to_string(), basename(&container_folder));
+    variables.insert("containerWorkspaceFolder".to_string(), container_folder.clone());
+    substitute_value(&mut value, &variables);
+
+    let mut config: DevContainerConfig = serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+    if config.docker_compose_file.is_some() {
+        return Err(DevContainerError::Unsupported("Docker Compose dev containers".to_string()));
+    }
+    config.config_path = path.to_path_buf();
+    if config.image.is_none() && config.dockerfile().is_none() {
+        return Err(invalid("needs an \"image\" or a \"build.dockerfile\"".to_string()));
+    }
+    config.workspace_folder = Some(container_folder);
+    Ok(config)
+}
+
+fn variable_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\$\{(localEnv:([A-Za-z_][A-Za-z0-9_]*)(?::([^}]*))?|[A-Za-z]+)\}").unwrap()
+    })
+}
+
+/// Replace `${name}` and `${localEnv:NAME}` references; unknown names are left alone
+fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    variable_pattern()
+        .replace_all(text, |captures: &Captures| match captures.get(2) {
+            Some(name) => std::env::var(name.as_str()).unwrap_or_else(|_| {
+                captures.get(3).map(|default| default.as_str()).unwrap_or_default().to_string()
+            }),
+            None => variables.get(&captures[1]).cloned().unwrap_or_else(|| captures[0].to_string()),
+        })
+        .into_owned()
+}
+
+fn substitute_value(value: &mut serde_json::Value, variables: &HashMap<String, String>) {
+    match value {
+        serde_json::Value::String(text) => *text = substitute(text, variables),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|item| substitute_value(item, variables)),
+        serde_json::Value::Object(fields) => {
+            fields.values_mut().for_each(|field| substitute_value(field, variables))
+        }
+        _ => {}
+    }
+}
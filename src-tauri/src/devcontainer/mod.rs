@@ -0,0 +1,487 @@
+/**
+ * Dev containers for CodeForge IDE
+ * Opens a workspace that has a `.devcontainer/devcontainer.json` inside a container made from it, so
+ * everyone working on the project gets the same toolchain
+ *
+ * Images are built and containers started with the `docker` CLI, which talks to whichever Docker Engine
+ * API it is set up for. The running container is then a workspace of its own,
+ * `container://[user@]name/workspaces/project`, whose file operations, tasks, and terminals all run inside
+ * it through `docker exec`.
+ *
+ * Each workspace has one container, named after it and labelled with a hash of its config: opening the
+ * workspace again reuses the container, and changing the config replaces it.
+ */
+
+mod config;
+
+pub use config::{DevContainerConfig, LifecycleCommand};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::file_system::FileSystemService;
+use crate::fs_provider::{self, ContainerTarget, FileSystemProvider, RemoteUri, CONTAINER_SCHEME, DOCKER};
+use crate::notifications::{NotificationService, ProgressHandle};
+
+/// Event carrying each line of output of building, starting, and setting up a container
+pub const DEV_CONTAINER_LOG_EVENT: &str = "devcontainer://log";
+
+/// Labels identifying the containers of workspaces
+const WORKSPACE_LABEL: &str = "codeforge.workspace";
+const CONFIG_LABEL: &str = "codeforge.config";
+
+/// What the container runs instead of its image's command with `overrideCommand`, so it stays up until
+/// stopped
+const SLEEP_FOREVER: &str = "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done";
+
+/// Lines of output kept to explain a failed command
+const ERROR_TAIL_LINES: usize = 20;
+
+/// Error types for dev container operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DevContainerError {
+    NoConfig(String),
+    InvalidConfig(String),
+    Unsupported(String),
+    Docker(String),
+    CommandFailed { command: String, output: String },
+    NotOpen(String),
+}
+
+impl std::fmt::Display for DevContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DevContainerError::NoConfig(workspace) => write!(f, "No devcontainer.json in {}", workspace),
+            DevContainerError::InvalidConfig(msg) => write!(f, "Invalid dev container config: {}", msg),
+            DevContainerError::Unsupported(what) => write!(f, "Not supported: {}", what),
+            DevContainerError::Docker(msg) => write!(f, "Docker failed: {}", msg),
+            DevContainerError::CommandFailed { command, output } => write!(f, "{} failed: {}", command, output),
+            DevContainerError::NotOpen(workspace) => write!(f, "No dev container open for {}", workspace),
+        }
+    }
+}
+
+/// The container a workspace is open in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevContainer {
+    /// The workspace on this machine
+    pub workspace: String,
+    /// `name` from the config, or the workspace's folder name
+    pub name: String,
+    /// Name of the Docker container
+    pub container: String,
+    pub image: String,
+    /// Where the workspace is mounted in the container
+    pub workspace_folder: String,
+    pub remote_user: Option<String>,
+    /// Ports published on localhost
+    pub ports: Vec<u16>,
+    /// The workspace inside the container, to open as a workspace root
+    pub uri: String,
+}
+
+/// A line of output, emitted as `devcontainer://log`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevContainerLog {
+    pub workspace: String,
+    pub line: String,
+}
+
+pub struct DevContainerService {
+    /// Open containers by local workspace
+    containers: Arc<Mutex<HashMap<String, DevContainer>>>,
+}
+
+impl DevContainerService {
+    pub fn new() -> Self {
+        Self {
+            containers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The workspace's dev container config, or `None` when it has none
+    pub fn get_config(&self, workspace: &str) -> Result<Option<DevContainerConfig>, DevContainerError> {
+        let workspace = local_workspace(workspace)?;
+        match config::find_config(&workspace) {
+            Some(path) => config::load_config(&path, &workspace, &container_id(&workspace)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Build and start the workspace's container, or reuse the one already there, and open the workspace
+    /// inside it. `rebuild` replaces the container, and rebuilds the image, even if the config is unchanged.
+    pub fn open(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        rebuild: bool,
+    ) -> Result<DevContainer, DevContainerError> {
+        let workspace_path = local_workspace(workspace)?;
+        let id = container_id(&workspace_path);
+        let config_path = config::find_config(&workspace_path)
+            .ok_or_else(|| DevContainerError::NoConfig(workspace.to_string()))?;
+        let config = config::load_config(&config_path, &workspace_path, &id)?;
+
+        let name = config.name.clone().unwrap_or_else(|| folder_name(&workspace_path));
+        let progress = app.state::<NotificationService>().start_progress(
+            app,
+            "devcontainer",
+            &format!("Opening {} in a container", name),
+            false,
+        );
+        let setup = Setup {
+            app,
+            workspace: workspace_path.to_string_lossy().to_string(),
+            config: &config,
+            container: container_name(&workspace_path, &id),
+            progress: &progress,
+        };
+        let container = setup.start(rebuild).and_then(|image| setup.open_workspace(name, image));
+        match &container {
+            Ok(container) => {
+                self.containers.lock().unwrap().insert(container.workspace.clone(), container.clone());
+                progress.complete(Some(&container.uri));
+            }
+            Err(e) => progress.fail(&e.to_string()),
+        }
+        container
+    }
+
+    /// Close the workspace's container workspace and stop the container; `remove` deletes it too, so the
+    /// next open starts from the image again
+    pub fn stop(&self, app: &AppHandle, workspace: &str, remove: bool) -> Result<(), DevContainerError> {
+        let workspace_path = local_workspace(workspace)?;
+        let key = workspace_path.to_string_lossy().to_string();
+        let open = self.containers.lock().unwrap().remove(&key);
+        if let Some(open) = &open {
+            let _ = app.state::<FileSystemService>().remove_workspace_root(&open.uri);
+        }
+
+        let container = container_name(&workspace_path, &container_id(&workspace_path));
+        let exists = docker(["inspect", "--type", "container", "--format", "{{.Id}}", &container]).is_ok();
+        if !exists {
+            return match open {
+                Some(_) => Ok(()),
+                None => Err(DevContainerError::NotOpen(workspace.to_string())),
+            };
+        }
+        match remove {
+            true => docker(["rm", "--force", &container]).map(|_| ()),
+            false => docker(["stop", &container]).map(|_| ()),
+        }
+    }
+
+    /// Workspaces open in containers
+    pub fn list(&self) -> Vec<DevContainer> {
+        let mut containers: Vec<DevContainer> = self.containers.lock().unwrap().values().cloned().collect();
+        containers.sort_by(|a, b| a.workspace.cmp(&b.workspace));
+        containers
+    }
+}
+
+impl Default for DevContainerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Getting one workspace's container up
+struct Setup<'a> {
+    app: &'a AppHandle,
+    workspace: String,
+    config: &'a DevContainerConfig,
+    container: String,
+    progress: &'a ProgressHandle,
+}
+
+impl Setup<'_> {
+    /// Get the container running, returning its image
+    fn start(&self, rebuild: bool) -> Result<String, DevContainerError> {
+        docker(["version", "--format", "{{.Server.Version}}"]).map_err(|e| match e {
+            DevContainerError::Docker(msg) => {
+                DevContainerError::Docker(format!("can't reach the daemon: {}", msg))
+            }
+            e => e,
+        })?;
+        let hash = self.config_hash()?;
+        let image = match self.config.dockerfile() {
+            Some(_) => format!("{}:latest", self.container),
+            None => self.config.image.clone().unwrap_or_default(),
+        };
+
+        // `true|<hash>` for a running container made from the same config
+        let format = format!("{{{{.State.Running}}}}|{{{{index .Config.Labels \"{}\"}}}}", CONFIG_LABEL);
+        let existing = docker(["inspect", "--type", "container", "--format", &format, &self.container]).ok();
+        if let Some(existing) = existing {
+            let (running, existing_hash) = existing.split_once('|').unwrap_or((&existing, ""));
+            if !rebuild && existing_hash == hash {
+                if running != "true" {
+                    self.progress.report(None, Some("Starting container"));
+                    docker(["start", &self.container])?;
+                    self.lifecycle("postStartCommand", self.config.post_start_command.as_ref())?;
+                }
+                return Ok(image);
+            }
+            self.progress.report(None, Some("Removing outdated container"));
+            docker(["rm", "--force", &self.container])?;
+        }
+
+        if let Some(dockerfile) = self.config.dockerfile() {
+            self.progress.report(None, Some("Building image"));
+            self.build(&dockerfile, &image, rebuild)?;
+        }
+        self.progress.report(None, Some("Creating container"));
+        let mut command = self.run_command(&image, &hash);
+        self.logged(&mut command, "docker run")?;
+        self.lifecycle("postCreateCommand", self.config.post_create_command.as_ref())?;
+        self.lifecycle("postStartCommand", self.config.post_start_command.as_ref())?;
+        Ok(image)
+    }
+
+    /// Open the workspace inside the running container
+    fn open_workspace(&self, name: String, image: String) -> Result<DevContainer, DevContainerError> {
+        let container = DevContainer {
+            workspace: self.workspace.clone(),
+            name,
+            container: self.container.clone(),
+            image,
+            workspace_folder: self.workspace_folder().to_string(),
+            remote_user: self.config.user().map(str::to_string),
+            ports: self.config.ports(),
+            uri: self.uri(),
+        };
+        self.app
+            .state::<FileSystemService>()
+            .add_workspace_root(&container.uri)
+            .map_err(|e| DevContainerError::Docker(format!("can't open {}: {}", container.uri, e)))?;
+        Ok(container)
+    }
+
+    fn workspace_folder(&self) -> &str {
+        self.config.workspace_folder.as_deref().unwrap_or("/")
+    }
+
+    fn uri(&self) -> String {
+        let target = ContainerTarget {
+            user: self.config.user().map(str::to_string),
+            container: self.container.clone(),
+        };
+        format!("{}://{}{}", CONTAINER_SCHEME, target, self.workspace_folder())
+    }
+
+    /// Changes with anything that needs a new container: the config, and the Dockerfile it builds
+    fn config_hash(&self) -> Result<String, DevContainerError> {
+        let mut hasher = blake3::Hasher::new();
+        let config =
+            serde_json::to_vec(self.config).map_err(|e| DevContainerError::InvalidConfig(e.to_string()))?;
+        hasher.update(&config);
+        if let Some(dockerfile) = self.config.dockerfile() {
+            let content = fs::read(&dockerfile).map_err(|e| {
+                DevContainerError::InvalidConfig(format!("can't read {}: {}", dockerfile.display(), e))
+            })?;
+            hasher.update(&content);
+        }
+        Ok(hasher.finalize().to_hex()[..16].to_string())
+    }
+
+    fn build(&self, dockerfile: &Path, image: &str, no_cache: bool) -> Result<(), DevContainerError> {
+        let mut command = Command::new(DOCKER);
+        command.arg("build").arg("--file").arg(dockerfile).arg("--tag").arg(image);
+        if no_cache {
+            command.arg("--no-cache");
+        }
+        if let Some(build) = &self.config.build {
+            for (name, value) in &build.args {
+                command.arg("--build-arg").arg(format!("{}={}", name, value));
+            }
+            if let Some(target) = &build.target {
+                command.arg("--target").arg(target);
+            }
+        }
+        command.arg(self.config.build_context());
+        self.logged(&mut command, "docker build")
+    }
+
+    fn run_command(&self, image: &str, hash: &str) -> Command {
+        let config = self.config;
+        let mut command = Command::new(DOCKER);
+        command
+            .args(["run", "--detach", "--name", &self.container])
+            .arg("--label")
+            .arg(format!("{}={}", WORKSPACE_LABEL, self.workspace))
+            .arg("--label")
+            .arg(format!("{}={}", CONFIG_LABEL, hash));
+        match &config.workspace_mount {
+            Some(mount) => command.arg("--mount").arg(mount),
+            None => command.arg("--volume").arg(format!("{}:{}", self.workspace, self.workspace_folder())),
+        };
+        command.arg("--workdir").arg(self.workspace_folder());
+        for (name, value) in &config.container_env {
+            command.arg("--env").arg(format!("{}={}", name, value));
+        }
+        if let Some(user) = &config.container_user {
+            command.arg("--user").arg(user);
+        }
+        for port in config.ports() {
+            command.arg("--publish").arg(format!("127.0.0.1:{}:{}", port, port));
+        }
+        for mount in &config.mounts {
+            command.arg("--mount").arg(mount.spec());
+        }
+        command.args(&config.run_args);
+        match config.override_command.unwrap_or(true) {
+            true => command.args(["--entrypoint", "/bin/sh", image, "-c", SLEEP_FOREVER]),
+            false => command.arg(image),
+        };
+        command
+    }
+
+    /// Run a lifecycle command inside the container, in the workspace folder
+    fn lifecycle(&self, name: &str, command: Option<&LifecycleCommand>) -> Result<(), DevContainerError> {
+        let Some(command) = command else {
+            return Ok(());
+        };
+        self.progress.report(None, Some(&format!("Running {}", name)));
+        let uri = RemoteUri::parse(&self.uri()).ok_or_else(|| DevContainerError::Docker(self.uri()))?;
+        let provider = fs_provider::connect(&uri).map_err(|e| DevContainerError::Docker(e.to_string()))?;
+        self.run_lifecycle(provider.as_ref(), name, command)
+    }
+
+    fn run_lifecycle(
+        &self,
+        provider: &dyn FileSystemProvider,
+        name: &str,
+        command: &LifecycleCommand,
+    ) -> Result<(), DevContainerError> {
+        let env = HashMap::new();
+        let folder = self.workspace_folder();
+        let mut process = match command {
+            LifecycleCommand::Line(line) => provider.shell_command(line, folder, &env),
+            LifecycleCommand::Program(words) => match words.split_first() {
+                Some((program, args)) => provider.command(program, args, folder, &env),
+                None => return Ok(()),
+            },
+            // Run one after another rather than in parallel, so their output stays readable
+            LifecycleCommand::Named(commands) => {
+                for (label, command) in commands {
+                    self.run_lifecycle(provider, &format!("{} {}", name, label), command)?;
+                }
+                return Ok(());
+            }
+        }
+        .map_err(|e| DevContainerError::Docker(e.to_string()))?;
+        self.logged(&mut process, name)
+    }
+
+    /// Run `command`, emitting its output line by line
+    fn logged(&self, command: &mut Command, description: &str) -> Result<(), DevContainerError> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(docker_spawn_error)?;
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let readers: Vec<_> = [
+            child.stdout.take().map(|stdout| Box::new(stdout) as Box<dyn Read + Send>),
+            child.stderr.take().map(|stderr| Box::new(stderr) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|output| {
+            let (app, workspace, tail) = (self.app.clone(), self.workspace.clone(), tail.clone());
+            thread::spawn(move || {
+                for line in BufReader::new(output).split(b'\n').map_while(Result::ok) {
+                    let line = String::from_utf8_lossy(&line).trim_end().to_string();
+                    {
+                        let mut tail = tail.lock().unwrap();
+                        tail.push_back(line.clone());
+                        if tail.len() > ERROR_TAIL_LINES {
+                            tail.pop_front();
+                        }
+                    }
+                    let log = DevContainerLog {
+                        workspace: workspace.clone(),
+                        line,
+                    };
+                    let _ = app.emit(DEV_CONTAINER_LOG_EVENT, log);
+                }
+            })
+        })
+        .collect();
+        for reader in readers {
+            let _ = reader.join();
+        }
+        let status = child.wait().map_err(|e| DevContainerError::Docker(e.to_string()))?;
+        if status.success() {
+            return Ok(());
+        }
+        let output: Vec<String> = tail.lock().unwrap().iter().filter(|line| !line.is_empty()).cloned().collect();
+        Err(DevContainerError::CommandFailed {
+            command: description.to_string(),
+            output: output.join("\n"),
+        })
+    }
+}
+
+/// The canonical path of a workspace on this machine
+fn local_workspace(workspace: &str) -> Result<PathBuf, DevContainerError> {
+    if RemoteUri::parse(workspace).is_some() {
+        let what = "dev containers for workspaces on other machines".to_string();
+        return Err(DevContainerError::Unsupported(what));
+    }
+    Path::new(workspace).canonicalize().map_err(|_| DevContainerError::NoConfig(workspace.to_string()))
+}
+
+/// Stable for a workspace folder, so its container is found again
+fn container_id(workspace: &Path) -> String {
+    blake3::hash(workspace.to_string_lossy().as_bytes()).to_hex()[..12].to_string()
+}
+
+fn folder_name(workspace: &Path) -> String {
+    workspace.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// `codeforge-<folder>-<id>`, in the characters Docker allows in container names and image tags
+fn container_name(workspace: &Path, id: &str) -> String {
+    let mut slug = String::new();
+    for c in folder_name(workspace).chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    match slug.trim_end_matches('-') {
+        "" => format!("codeforge-{}", id),
+        slug => format!("codeforge-{}-{}", slug, id),
+    }
+}
+
+fn docker_spawn_error(error: io::Error) -> DevContainerError {
+    match error.kind() {
+        io::ErrorKind::NotFound => DevContainerError::Docker(format!("{} isn't installed", DOCKER)),
+        _ => DevContainerError::Docker(error.to_string()),
+    }
+}
+
+/// Run `docker` with `args`, returning its trimmed standard output
+fn docker<I, S>(args: I) -> Result<String, DevContainerError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new(DOCKER).args(args).stdin(Stdio::null()).output().map_err(docker_spawn_error)?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => Err(DevContainerError::Docker(String::from_utf8_lossy(&output.stderr).trim().to_string())),
+    }
+}
@@ -0,0 +1,139 @@
+/**
+ * Diagnostics store for CodeForge IDE
+ * Collects errors and warnings from non-LSP sources (task output, build tools) for the Problems panel
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying the complete diagnostics of one file from one source
+pub const DIAGNOSTICS_EVENT: &str = "diagnostics://publish";
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl DiagnosticSeverity {
+    pub fn parse(text: &str) -> Self {
+        match text.trim().to_lowercase().as_str() {
+            "error" | "fatal error" | "fatal" | "e" => DiagnosticSeverity::Error,
+            "warning" | "warn" | "w" => DiagnosticSeverity::Warning,
+            _ => DiagnosticSeverity::Info,
+        }
+    }
+}
+
+/// An error or warning located in a file; `line` and `column` are one-based
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: Option<usize>,
+    pub end_column: Option<usize>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub code: Option<String>,
+    /// Tool that reported it, e.g. `rustc` or `tsc`
+    pub source: String,
+}
+
+/// All diagnostics of one file from one publisher; an empty list clears the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishDiagnostics {
+    /// Publisher, e.g. `task:cargo: build`
+    pub owner: String,
+    pub file: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// owner -> file -> diagnostics
+type DiagnosticsByOwner = HashMap<String, BTreeMap<String, Vec<Diagnostic>>>;
+
+pub struct DiagnosticsService {
+    diagnostics: Arc<Mutex<DiagnosticsByOwner>>,
+}
+
+impl DiagnosticsService {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Add one diagnostic and republish its file
+    pub fn add(&self, app: &AppHandle, owner: &str, diagnostic: Diagnostic) {
+        let file = diagnostic.file.clone();
+        let diagnostics = {
+            let mut store = self.diagnostics.lock().unwrap();
+            let files = store.entry(owner.to_string()).or_default();
+            let list = files.entry(file.clone()).or_default();
+            if list.contains(&diagnostic) {
+                return;
+            }
+            list.push(diagnostic);
+            list.clone()
+        };
+        let _ = app.emit(
+            DIAGNOSTICS_EVENT,
+            PublishDiagnostics {
+                owner: owner.to_string(),
+                file,
+                diagnostics,
+            },
+        );
+    }
+
+    /// Remove everything an owner reported, publishing empty lists so editors drop their squiggles
+    pub fn clear(&self, app: &AppHandle, owner: &str) {
+        let Some(files) = self.diagnostics.lock().unwrap().remove(owner) else {
+            return;
+        };
+        for file in files.into_keys() {
+            let _ = app.emit(
+                DIAGNOSTICS_EVENT,
+                PublishDiagnostics {
+                    owner: owner.to_string(),
+                    file,
+                    diagnostics: Vec::new(),
+                },
+            );
+        }
+    }
+
+    /// Current diagnostics across all owners, optionally for a single file, errors first
+    pub fn list(&self, file: Option<&str>) -> Vec<PublishDiagnostics> {
+        let store = self.diagnostics.lock().unwrap();
+        let mut published: Vec<PublishDiagnostics> = store
+            .iter()
+            .flat_map(|(owner, files)| {
+                files
+                    .iter()
+                    .filter(|(path, _)| file.is_none_or(|file| file == path.as_str()))
+                    .map(|(path, diagnostics)| {
+                        let mut diagnostics = diagnostics.clone();
+                        diagnostics.sort_by_key(|d| (d.severity, d.line, d.column));
+                        PublishDiagnostics {
+                            owner: owner.clone(),
+                            file: path.clone(),
+                            diagnostics,
+                        }
+                    })
+            })
+            .collect();
+        published.sort_by(|a, b| a.file.cmp(&b.file).then(a.owner.cmp(&b.owner)));
+        published
+    }
+}
+
+impl Default for DiagnosticsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,172 @@
+/**
+ * Text diffing for CodeForge IDE
+ * Line-based diffs used by editor gutters and the diff viewer
+ */
+
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, ChangeTag, DiffOp, TextDiff};
+use std::time::Duration;
+
+/// Default number of unchanged lines kept around each hunk
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Upper bound on diff computation before falling back to a coarser result
+const DIFF_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Diff algorithm selection
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum DiffAlgorithm {
+    Myers,
+    Patience,
+}
+
+impl From<DiffAlgorithm> for Algorithm {
+    fn from(algorithm: DiffAlgorithm) -> Self {
+        match algorithm {
+            DiffAlgorithm::Myers => Algorithm::Myers,
+            DiffAlgorithm::Patience => Algorithm::Patience,
+        }
+    }
+}
+
+/// Kind of a single line edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEditKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Summary classification of a hunk, used to paint gutter markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HunkKind {
+    Added,
+    Deleted,
+    Modified,
+}
+
+/// A single line in a hunk; line numbers are 1-based
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineEdit {
+    pub kind: LineEditKind,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+/// A contiguous region of changes with surrounding context
+///
+/// Start lines are 1-based as in unified diff headers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub kind: HunkKind,
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub header: String,
+    pub edits: Vec<LineEdit>,
+}
+
+/// Result of comparing two texts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffResult {
+    pub hunks: Vec<DiffHunk>,
+    pub additions: usize,
+    pub deletions: usize,
+    pub identical: bool,
+}
+
+/// Options controlling how a diff is computed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DiffOptions {
+    pub algorithm: DiffAlgorithm,
+    pub context_lines: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: DiffAlgorithm::Myers,
+            context_lines: DEFAULT_CONTEXT_LINES,
+        }
+    }
+}
+
+/// Compute a line diff between two texts
+pub fn compute_diff(old_text: &str, new_text: &str, options: DiffOptions) -> DiffResult {
+    let diff = TextDiff::configure()
+        .algorithm(options.algorithm.into())
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(old_text, new_text);
+
+    let mut additions = 0;
+    let mut deletions = 0;
+    let mut hunks = Vec::new();
+
+    for group in diff.grouped_ops(options.context_lines) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+
+        let mut edits = Vec::new();
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let kind = match change.tag() {
+                    ChangeTag::Equal => LineEditKind::Equal,
+                    ChangeTag::Insert => {
+                        additions += 1;
+                        LineEditKind::Insert
+                    }
+                    ChangeTag::Delete => {
+                        deletions += 1;
+                        LineEditKind::Delete
+                    }
+                };
+                edits.push(LineEdit {
+                    kind,
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    content: change.to_string_lossy().trim_end_matches(['\n', '\r']).to_string(),
+                });
+            }
+        }
+
+        let has_insert = group.iter().any(|op| matches!(op, DiffOp::Insert { .. } | DiffOp::Replace { .. }));
+        let has_delete = group.iter().any(|op| matches!(op, DiffOp::Delete { .. } | DiffOp::Replace { .. }));
+        let kind = match (has_insert, has_delete) {
+            (true, false) => HunkKind::Added,
+            (false, true) => HunkKind::Deleted,
+            _ => HunkKind::Modified,
+        };
+
+        // Unified diff convention: an empty side points at the line before the change
+        let old_start = if old_range.is_empty() { old_range.start } else { old_range.start + 1 };
+        let new_start = if new_range.is_empty() { new_range.start } else { new_range.start + 1 };
+
+        hunks.push(DiffHunk {
+            kind,
+            old_start,
+            old_lines: old_range.len(),
+            new_start,
+            new_lines: new_range.len(),
+            header: format!(
+                "@@ -{},{} +{},{} @@",
+                old_start,
+                old_range.len(),
+                new_start,
+                new_range.len()
+            ),
+            edits,
+        });
+    }
+
+    DiffResult {
+        identical: hunks.is_empty(),
+        hunks,
+        additions,
+        deletions,
+    }
+}
@@ -0,0 +1,242 @@
+/**
+ * Directory sizes for CodeForge IDE
+ * Walks a tree in parallel and totals it per immediate child, for folder sizes in the explorer and the
+ * workspace size report
+ */
+
+use ignore::{DirEntry, ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+/// Event carrying running totals of a `compute_directory_size` walk
+pub const DIRECTORY_SIZE_PROGRESS_EVENT: &str = "fs://directory-size-progress";
+
+/// How often progress is reported while a walk is running
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Running totals of a walk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectorySizeProgress {
+    pub path: String,
+    pub total_size: u64,
+    pub file_count: u64,
+    pub directory_count: u64,
+}
+
+/// Totals of one immediate child of the walked directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryChildSize {
+    pub name: String,
+    pub path: String,
+    pub is_directory: bool,
+    pub size: u64,
+    pub file_count: u64,
+    pub directory_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectorySize {
+    pub path: String,
+    /// Apparent size: the sum of file lengths, not the blocks they occupy
+    pub total_size: u64,
+    pub file_count: u64,
+    pub directory_count: u64,
+    /// Largest first
+    pub children: Vec<DirectoryChildSize>,
+    /// Entries that couldn't be read and are missing from the totals
+    pub errors: u64,
+}
+
+#[derive(Default)]
+struct Totals {
+    size: u64,
+    files: u64,
+    directories: u64,
+    is_directory: bool,
+}
+
+struct Counters {
+    size: AtomicU64,
+    files: AtomicU64,
+    directories: AtomicU64,
+    errors: AtomicU64,
+}
+
+/// Hands each walker thread a visitor; the visitors merge their per-child totals when dropped
+struct SizeVisitorBuilder<'s> {
+    root: &'s Path,
+    counters: &'s Counters,
+    children: &'s Mutex<HashMap<OsString, Totals>>,
+}
+
+impl<'s> ParallelVisitorBuilder<'s> for SizeVisitorBuilder<'s> {
+    fn build(&mut self) -> Box<dyn ParallelVisitor + 's> {
+        Box::new(SizeVisitor {
+            root: self.root,
+            counters: self.counters,
+            children: self.children,
+            local: HashMap::new(),
+        })
+    }
+}
+
+struct SizeVisitor<'s> {
+    root: &'s Path,
+    counters: &'s Counters,
+    children: &'s Mutex<HashMap<OsString, Totals>>,
+    local: HashMap<OsString, Totals>,
+}
+
+impl SizeVisitor<'_> {
+    fn record(&mut self, entry: &DirEntry) {
+        let Some(child) = entry.path().strip_prefix(self.root).ok().and_then(|p| p.iter().next()) else {
+            return;
+        };
+        let totals = self.local.entry(child.to_os_string()).or_default();
+        if entry.depth() == 1 {
+            totals.is_directory = entry.file_type().is_some_and(|t| t.is_dir());
+        }
+
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            totals.directories += 1;
+            self.counters.directories.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        // Symlinks aren't followed; they count with the size of the link itself
+        match entry.metadata() {
+            Ok(metadata) => {
+                totals.size += metadata.len();
+                totals.files += 1;
+                self.counters.size.fetch_add(metadata.len(), Ordering::Relaxed);
+                self.counters.files.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {
+                self.counters.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl ParallelVisitor for SizeVisitor<'_> {
+    fn visit(&mut self, entry: Result<DirEntry, ignore::Error>) -> WalkState {
+        match entry {
+            Ok(entry) if entry.depth() > 0 => self.record(&entry),
+            Ok(_) => {}
+            Err(_) => {
+                self.counters.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        WalkState::Continue
+    }
+}
+
+impl Drop for SizeVisitor<'_> {
+    fn drop(&mut self) {
+        let mut children = self.children.lock().unwrap();
+        for (name, local) in self.local.drain() {
+            let totals = children.entry(name).or_default();
+            totals.size += local.size;
+            totals.files += local.files;
+            totals.directories += local.directories;
+            totals.is_directory |= local.is_directory;
+        }
+    }
+}
+
+/// Total the tree under `path`, calling `on_progress` with running totals while it is walked.
+/// With `respect_ignore`, .gitignore/.ignore rules apply and `.git` directories are skipped.
+pub fn compute_directory_size(
+    path: &Path,
+    respect_ignore: bool,
+    on_progress: impl Fn(DirectorySizeProgress) + Sync,
+) -> Result<DirectorySize, FileSystemError> {
+    let metadata = fs::metadata(path).map_err(map_io_error)?;
+    let display_path = path.to_string_lossy().to_string();
+    if !metadata.is_dir() {
+        return Ok(DirectorySize {
+            path: display_path,
+            total_size: metadata.len(),
+            file_count: 1,
+            directory_count: 0,
+            children: Vec::new(),
+            errors: 0,
+        });
+    }
+
+    let walker = WalkBuilder::new(path)
+        .standard_filters(respect_ignore)
+        // Hidden files take up space too; only ignore rules decide what's left out
+        .hidden(false)
+        .follow_links(false)
+        .filter_entry(move |entry| !(respect_ignore && entry.file_name() == ".git"))
+        .build_parallel();
+
+    let counters = Counters {
+        size: AtomicU64::new(0),
+        files: AtomicU64::new(0),
+        directories: AtomicU64::new(0),
+        errors: AtomicU64::new(0),
+    };
+    let snapshot = |counters: &Counters| DirectorySizeProgress {
+        path: display_path.clone(),
+        total_size: counters.size.load(Ordering::Relaxed),
+        file_count: counters.files.load(Ordering::Relaxed),
+        directory_count: counters.directories.load(Ordering::Relaxed),
+    };
+    let children = Mutex::new(HashMap::new());
+    let done = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            while !done.load(Ordering::SeqCst) {
+                std::thread::park_timeout(PROGRESS_INTERVAL);
+                if !done.load(Ordering::SeqCst) {
+                    on_progress(snapshot(&counters));
+                }
+            }
+        });
+        walker.visit(&mut SizeVisitorBuilder {
+            root: path,
+            counters: &counters,
+            children: &children,
+        });
+        done.store(true, Ordering::SeqCst);
+        reporter.thread().unpark();
+    });
+
+    let mut children: Vec<DirectoryChildSize> = children
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|(name, totals)| DirectoryChildSize {
+            name: name.to_string_lossy().to_string(),
+            path: path.join(&name).to_string_lossy().to_string(),
+            is_directory: totals.is_directory,
+            size: totals.size,
+            file_count: totals.files,
+            // The child directory itself is not counted inside it
+            directory_count: totals.directories.saturating_sub(totals.is_directory as u64),
+        })
+        .collect();
+    children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let progress = snapshot(&counters);
+    on_progress(progress.clone());
+    Ok(DirectorySize {
+        path: progress.path,
+        total_size: progress.total_size,
+        file_count: progress.file_count,
+        directory_count: progress.directory_count,
+        children,
+        errors: counters.errors.load(Ordering::Relaxed),
+    })
+}
@@ -0,0 +1,130 @@
+/**
+ * EditorConfig support
+ * Resolves the `.editorconfig` properties that apply to a file: files nearer to it override those further up,
+ * later sections override earlier ones, and the search stops at a file declaring `root = true`
+ */
+
+use globset::GlobBuilder;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+/// Properties for one file, with the `.editorconfig` files they were read from
+#[derive(Debug, Clone, Default)]
+pub struct EditorConfig {
+    /// Lowercased keys and values; properties set to `unset` are removed
+    pub properties: BTreeMap<String, String>,
+    pub files: Vec<PathBuf>,
+}
+
+impl EditorConfig {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    /// `true`/`false` properties; anything else counts as not set
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+struct Section {
+    pattern: String,
+    properties: Vec<(String, String)>,
+}
+
+struct ConfigFile {
+    root: bool,
+    sections: Vec<Section>,
+}
+
+/// Parse the INI dialect of EditorConfig; `#` and `;` start comment lines and malformed lines are skipped
+fn parse(content: &str) -> ConfigFile {
+    let mut file = ConfigFile {
+        root: false,
+        sections: Vec::new(),
+    };
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            file.sections.push(Section {
+                pattern: pattern.to_string(),
+                properties: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_lowercase(), value.trim().to_lowercase());
+        match file.sections.last_mut() {
+            Some(section) => section.properties.push((key, value)),
+            None if key == "root" => file.root = value == "true",
+            None => {}
+        }
+    }
+    file
+}
+
+/// Whether a section pattern matches `relative`, the path from the `.editorconfig` directory. Patterns
+/// without a `/` match the file name at any depth; others are anchored to that directory.
+fn section_matches(pattern: &str, relative: &str) -> bool {
+    let glob = match pattern.contains('/') {
+        true => pattern.trim_start_matches('/').to_string(),
+        false => format!("**/{}", pattern),
+    };
+    // Numeric ranges such as `{1..3}` aren't supported and never match
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .is_ok_and(|glob| glob.compile_matcher().is_match(relative))
+}
+
+/// The EditorConfig properties applying to `path`
+pub fn resolve(path: &Path) -> EditorConfig {
+    let mut files = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        let config_path = dir.join(EDITORCONFIG_FILE);
+        let Ok(content) = fs::read_to_string(&config_path) else {
+            continue;
+        };
+        let file = parse(&content);
+        let root = file.root;
+        files.push((dir, config_path, file));
+        if root {
+            break;
+        }
+    }
+
+    let mut config = EditorConfig::default();
+    // Furthest first, so nearer files override
+    for (dir, config_path, file) in files.into_iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let mut applied = false;
+        for section in file.sections.iter().filter(|section| section_matches(&section.pattern, &relative)) {
+            applied = true;
+            for (key, value) in &section.properties {
+                match value.as_str() {
+                    "unset" => config.properties.remove(key),
+                    _ => config.properties.insert(key.clone(), value.clone()),
+                };
+            }
+        }
+        if applied {
+            config.files.push(config_path);
+        }
+    }
+    config
+}
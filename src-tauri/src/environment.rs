@@ -0,0 +1,354 @@
+/**
+ * Workspace environment variables
+ * Parses, edits, and layers `.env` files so tasks and debug sessions see the workspace environment
+ */
+
+use crate::atomic_file::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Env files of a workspace, lowest precedence first
+pub const ENV_FILES: &[&str] = &[".env", ".env.local"];
+
+/// File `set_env_override` edits unless told otherwise; meant to stay out of version control
+pub const DEFAULT_OVERRIDE_FILE: &str = ".env.local";
+
+/// Error types for environment operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EnvError {
+    IOError(String),
+    Parse { file: String, line: usize, message: String },
+    InvalidName(String),
+    InvalidFile(String),
+}
+
+impl std::fmt::Display for EnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EnvError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            EnvError::Parse { file, line, message } => write!(f, "{}:{}: {}", file, line, message),
+            EnvError::InvalidName(name) => write!(f, "Not a valid variable name: {}", name),
+            EnvError::InvalidFile(file) => write!(f, "Env files must be inside the workspace: {}", file),
+        }
+    }
+}
+
+/// One assignment in an env file; lines are one-based and `end_line` differs for multi-line values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvEntry {
+    pub name: String,
+    pub value: String,
+    pub line: usize,
+    pub end_line: usize,
+}
+
+/// Where the effective value of a variable comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnvSource {
+    Process,
+    /// Env file, relative to the workspace
+    File(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVariable {
+    pub name: String,
+    pub value: String,
+    pub source: EnvSource,
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expand `${NAME}` references at the start of `chars`, returning the value and the characters consumed
+fn expand_reference(chars: &[char], lookup: &dyn Fn(&str) -> Option<String>) -> Option<(String, usize)> {
+    if chars.get(1) != Some(&'{') {
+        return None;
+    }
+    let end = chars.iter().position(|c| *c == '}')?;
+    let name: String = chars[2..end].iter().collect();
+    Some((lookup(&name).unwrap_or_default(), end + 1))
+}
+
+/// Parse env file content; `${NAME}` in unquoted and double-quoted values expands to earlier
+/// assignments in the file, then to `outer`
+pub fn parse_env(
+    file: &str,
+    content: &str,
+    outer: &dyn Fn(&str) -> Option<String>,
+) -> Result<Vec<EnvEntry>, EnvError> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries: Vec<EnvEntry> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let start_line = index + 1;
+        let parse_error = |line: usize, message: &str| EnvError::Parse {
+            file: file.to_string(),
+            line,
+            message: message.to_string(),
+        };
+        let line = lines[index].trim_start();
+        index += 1;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (name, raw) = line.split_once('=').ok_or_else(|| parse_error(start_line, "expected NAME=value"))?;
+        let name = name.trim();
+        if !is_valid_name(name) {
+            return Err(parse_error(start_line, &format!("invalid variable name '{}'", name)));
+        }
+
+        let lookup = |reference: &str| {
+            entries
+                .iter()
+                .rev()
+                .find(|entry| entry.name == reference)
+                .map(|entry| entry.value.clone())
+                .or_else(|| outer(reference))
+        };
+        let raw = raw.trim_start();
+        let mut value = String::new();
+        let quote = raw.chars().next().filter(|c| *c == '"' || *c == '\'');
+
+        let Some(quote) = quote else {
+            // Unquoted: ends at a comment preceded by whitespace, surrounding whitespace dropped
+            let raw = raw.find(" #").or_else(|| raw.find("\t#")).map_or(raw, |comment| &raw[..comment]);
+            let chars: Vec<char> = raw.trim_end().chars().collect();
+            let mut position = 0;
+            while position < chars.len() {
+                if chars[position] == '$' {
+                    if let Some((expanded, consumed)) = expand_reference(&chars[position..], &lookup) {
+                        value.push_str(&expanded);
+                        position += consumed;
+                        continue;
+                    }
+                }
+                value.push(chars[position]);
+                position += 1;
+            }
+            entries.push(EnvEntry {
+                name: name.to_string(),
+                value,
+                line: start_line,
+                end_line: start_line,
+            });
+            continue;
+        };
+
+        // Quoted values may continue over the following lines until the closing quote
+        let mut chars: Vec<char> = raw.chars().skip(1).collect();
+        let mut position = 0;
+        loop {
+            if position >= chars.len() {
+                if index >= lines.len() {
+                    return Err(parse_error(start_line, "unterminated quoted value"));
+                }
+                value.push('\n');
+                chars = lines[index].chars().collect();
+                position = 0;
+                index += 1;
+                continue;
+            }
+
+            let c = chars[position];
+            if c == quote {
+                position += 1;
+                break;
+            }
+            if quote == '"' && c == '\\' && position + 1 < chars.len() {
+                match chars[position + 1] {
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    't' => value.push('\t'),
+                    other @ ('"' | '\\' | '$') => value.push(other),
+                    // Unknown escapes are kept literally
+                    other => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                }
+                position += 2;
+                continue;
+            }
+            if quote == '"' && c == '$' {
+                if let Some((expanded, consumed)) = expand_reference(&chars[position..], &lookup) {
+                    value.push_str(&expanded);
+                    position += consumed;
+                    continue;
+                }
+            }
+            value.push(c);
+            position += 1;
+        }
+
+        let trailing: String = chars[position..].iter().collect();
+        let trailing = trailing.trim();
+        if !trailing.is_empty() && !trailing.starts_with('#') {
+            return Err(parse_error(index, "unexpected text after closing quote"));
+        }
+        entries.push(EnvEntry {
+            name: name.to_string(),
+            value,
+            line: start_line,
+            end_line: index,
+        });
+    }
+    Ok(entries)
+}
+
+/// `NAME=value` with the value quoted and escaped when it would not survive parsing as-is
+pub fn format_assignment(name: &str, value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:,@+%=".contains(c));
+    if plain {
+        return format!("{}={}", name, value);
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    format!("{}=\"{}\"", name, quoted)
+}
+
+/// Resolve an env file name against the workspace, refusing paths that leave it
+fn env_file_path(workspace: &str, file: &str) -> Result<PathBuf, EnvError> {
+    let relative = Path::new(file);
+    if file.is_empty() || !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(EnvError::InvalidFile(file.to_string()));
+    }
+    Ok(Path::new(workspace).join(relative))
+}
+
+/// Entries of one env file; variables it references resolve against the process environment
+pub fn load_env_file(path: &str) -> Result<Vec<EnvEntry>, EnvError> {
+    let content = fs::read_to_string(path).map_err(|e| EnvError::IOError(e.to_string()))?;
+    parse_env(path, &content, &|name| std::env::var(name).ok())
+}
+
+/// Variables from the workspace env files, each layer overriding the one before it
+fn layered_files(workspace: &str) -> Result<Vec<EnvVariable>, EnvError> {
+    let mut variables: Vec<EnvVariable> = Vec::new();
+    for file in ENV_FILES {
+        let path = env_file_path(workspace, file)?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(EnvError::IOError(e.to_string())),
+        };
+        let lookup = |name: &str| {
+            variables
+                .iter()
+                .find(|variable| variable.name == name)
+                .map(|variable| variable.value.clone())
+                .or_else(|| std::env::var(name).ok())
+        };
+        let entries = parse_env(file, &content, &lookup)?;
+        for entry in entries {
+            variables.retain(|variable| variable.name != entry.name);
+            variables.push(EnvVariable {
+                name: entry.name,
+                value: entry.value,
+                source: EnvSource::File(file.to_string()),
+            });
+        }
+    }
+    Ok(variables)
+}
+
+/// Variables the workspace env files add to the environment of processes started for it
+pub fn workspace_env(workspace: &str) -> Result<HashMap<String, String>, EnvError> {
+    Ok(layered_files(workspace)?
+        .into_iter()
+        .map(|variable| (variable.name, variable.value))
+        .collect())
+}
+
+/// Effective environment of the workspace, optionally on top of the IDE's own process environment
+pub fn merged_env(workspace: &str, include_process: bool) -> Result<Vec<EnvVariable>, EnvError> {
+    let mut merged: HashMap<String, EnvVariable> = HashMap::new();
+    if include_process {
+        for (name, value) in std::env::vars() {
+            let variable = EnvVariable {
+                name: name.clone(),
+                value,
+                source: EnvSource::Process,
+            };
+            merged.insert(name, variable);
+        }
+    }
+    for variable in layered_files(workspace)? {
+        merged.insert(variable.name.clone(), variable);
+    }
+
+    let mut variables: Vec<EnvVariable> = merged.into_values().collect();
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(variables)
+}
+
+/// Set (or with `None`, remove) a variable in a workspace env file, keeping its other lines and comments
+pub fn set_env_override(
+    workspace: &str,
+    file: Option<&str>,
+    name: &str,
+    value: Option<&str>,
+) -> Result<Vec<EnvEntry>, EnvError> {
+    if !is_valid_name(name) {
+        return Err(EnvError::InvalidName(name.to_string()));
+    }
+    let file = file.unwrap_or(DEFAULT_OVERRIDE_FILE);
+    let path = env_file_path(workspace, file)?;
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(EnvError::IOError(e.to_string())),
+    };
+
+    // References are not expanded here; only the line spans of the entries matter
+    let entries = parse_env(file, &content, &|_| None)?;
+    let assigned: Vec<&EnvEntry> = entries.iter().filter(|entry| entry.name == name).collect();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let assignment = value.map(|value| format_assignment(name, value));
+
+    // Later assignments are removed so the edited one is the effective value
+    for entry in assigned.iter().skip(1).rev() {
+        lines.drain(entry.line - 1..entry.end_line);
+    }
+    match (assigned.first(), assignment) {
+        (Some(entry), Some(assignment)) => {
+            lines.splice(entry.line - 1..entry.end_line, [assignment]);
+        }
+        (Some(entry), None) => {
+            lines.drain(entry.line - 1..entry.end_line);
+        }
+        (None, Some(assignment)) => lines.push(assignment),
+        (None, None) => {}
+    }
+
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| EnvError::IOError(e.to_string()))?;
+    }
+    write_atomic(&path, &content).map_err(|e| EnvError::IOError(e.to_string()))?;
+
+    parse_env(file, &content, &|name| std::env::var(name).ok())
+}
@@ -0,0 +1,187 @@
+/**
+ * Extended attributes for CodeForge IDE
+ * Reads and writes xattrs on macOS/Linux and alternate data streams on Windows, e.g. the macOS
+ * quarantine flag, Finder tags, or custom metadata written by plugins
+ */
+
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+/// Values larger than this are reported by size only when listing
+const MAX_LISTED_VALUE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeEncoding {
+    Utf8,
+    Base64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtendedAttribute {
+    pub name: String,
+    pub size: usize,
+    /// `None` when the value was too large to include in a listing
+    pub value: Option<String>,
+    /// Text values are returned as-is; anything else (e.g. binary plists) as base64
+    pub encoding: AttributeEncoding,
+}
+
+/// `ENODATA` on Linux and `ENOATTR` on macOS: the attribute doesn't exist
+#[cfg(target_os = "linux")]
+const NO_ATTRIBUTE_ERROR: Option<i32> = Some(61);
+#[cfg(target_os = "macos")]
+const NO_ATTRIBUTE_ERROR: Option<i32> = Some(93);
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const NO_ATTRIBUTE_ERROR: Option<i32> = None;
+
+fn map_attribute_error(e: io::Error) -> FileSystemError {
+    if NO_ATTRIBUTE_ERROR.is_some() && e.raw_os_error() == NO_ATTRIBUTE_ERROR {
+        return FileSystemError::NotFound;
+    }
+    match e.kind() {
+        io::ErrorKind::Unsupported => {
+            FileSystemError::Unsupported("this file system does not support extended attributes".to_string())
+        }
+        _ => map_io_error(e),
+    }
+}
+
+fn to_attribute(name: String, bytes: &[u8], include_value: bool) -> ExtendedAttribute {
+    let text = std::str::from_utf8(bytes)
+        .ok()
+        .filter(|text| !text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')));
+    let encoding = if text.is_some() {
+        AttributeEncoding::Utf8
+    } else {
+        AttributeEncoding::Base64
+    };
+    let value = include_value.then(|| match text {
+        Some(text) => text.to_string(),
+        None => base64::engine::general_purpose::STANDARD.encode(bytes),
+    });
+    ExtendedAttribute {
+        name,
+        size: bytes.len(),
+        value,
+        encoding,
+    }
+}
+
+fn decode_value(value: &str, encoding: AttributeEncoding) -> Result<Vec<u8>, FileSystemError> {
+    match encoding {
+        AttributeEncoding::Utf8 => Ok(value.as_bytes().to_vec()),
+        AttributeEncoding::Base64 => base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .map_err(|e| FileSystemError::UnknownError(format!("Invalid base64 attribute value: {}", e))),
+    }
+}
+
+fn check_name(name: &str) -> Result<(), FileSystemError> {
+    // A NUL would truncate an xattr name; stream names also can't contain separators on Windows
+    let forbidden: &[char] = if cfg!(windows) { &['\0', '/', '\\', ':'] } else { &['\0'] };
+    if name.is_empty() || name.contains(forbidden) {
+        return Err(FileSystemError::UnknownError(format!("Invalid attribute name '{}'", name)));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn list(path: &Path) -> io::Result<Vec<String>> {
+        Ok(xattr::list_deref(path)?.map(|name| name.to_string_lossy().to_string()).collect())
+    }
+
+    pub fn get(path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        xattr::get_deref(path, name)
+    }
+
+    pub fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        xattr::set_deref(path, name, value)
+    }
+
+    pub fn remove(path: &Path, name: &str) -> io::Result<()> {
+        xattr::remove_deref(path, name)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::OsString;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    /// Alternate data streams are opened as `file:stream`
+    fn stream_path(path: &Path, name: &str) -> PathBuf {
+        let mut stream: OsString = path.as_os_str().to_owned();
+        stream.push(":");
+        stream.push(name);
+        PathBuf::from(stream)
+    }
+
+    pub fn list(_path: &Path) -> io::Result<Vec<String>> {
+        // Enumerating streams needs FindFirstStreamW, which std doesn't expose
+        Err(io::Error::new(io::ErrorKind::Unsupported, "listing alternate data streams"))
+    }
+
+    pub fn get(path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(stream_path(path, name)) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && path.exists() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        fs::write(stream_path(path, name), value)
+    }
+
+    pub fn remove(path: &Path, name: &str) -> io::Result<()> {
+        fs::remove_file(stream_path(path, name))
+    }
+}
+
+/// Every attribute of a file with its value, except very large values
+pub fn list_attributes(path: &Path) -> Result<Vec<ExtendedAttribute>, FileSystemError> {
+    let mut attributes = Vec::new();
+    for name in platform::list(path).map_err(map_attribute_error)? {
+        // Attributes can disappear between listing and reading them
+        if let Some(value) = platform::get(path, &name).map_err(map_attribute_error)? {
+            let include_value = value.len() <= MAX_LISTED_VALUE;
+            attributes.push(to_attribute(name, &value, include_value));
+        }
+    }
+    attributes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(attributes)
+}
+
+pub fn get_attribute(path: &Path, name: &str) -> Result<ExtendedAttribute, FileSystemError> {
+    check_name(name)?;
+    let value = platform::get(path, name).map_err(map_attribute_error)?.ok_or(FileSystemError::NotFound)?;
+    Ok(to_attribute(name.to_string(), &value, true))
+}
+
+pub fn set_attribute(
+    path: &Path,
+    name: &str,
+    value: &str,
+    encoding: AttributeEncoding,
+) -> Result<ExtendedAttribute, FileSystemError> {
+    check_name(name)?;
+    let bytes = decode_value(value, encoding)?;
+    platform::set(path, name, &bytes).map_err(map_attribute_error)?;
+    Ok(to_attribute(name.to_string(), &bytes, true))
+}
+
+pub fn remove_attribute(path: &Path, name: &str) -> Result<(), FileSystemError> {
+    check_name(name)?;
+    platform::remove(path, name).map_err(map_attribute_error)
+}
@@ -0,0 +1,139 @@
+/**
+ * File clipboard for CodeForge IDE
+ * Holds the paths cut or copied in the explorer and pastes them into a folder: copies go through the
+ * import code, cuts are moved and leave the clipboard empty
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::file_import::{
+    self, ImportFailure, ImportProgress, ImportResult, ImportStrategy, ImportedPath, Placement,
+};
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardMode {
+    Copy,
+    Cut,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileClipboard {
+    pub mode: ClipboardMode,
+    pub paths: Vec<String>,
+}
+
+/// Move `source` into `destination`, falling back to copy and delete across file systems
+fn move_one(
+    source: &Path,
+    destination: &Path,
+    strategy: ImportStrategy,
+) -> Result<Option<String>, FileSystemError> {
+    let (target, replace) = match file_import::placement(source, destination, strategy, true)? {
+        Placement::Skip => return Ok(None),
+        Placement::Place { target, replace } => (target, replace),
+    };
+
+    // Whatever is being replaced is set aside until the move has worked
+    let set_aside = if replace {
+        let name = target.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let aside = (0..)
+            .map(|n| destination.join(format!(".{}.codeforge-replaced-{}", name, n)))
+            .find(|candidate| fs::symlink_metadata(candidate).is_err())
+            .unwrap();
+        fs::rename(&target, &aside).map_err(map_io_error)?;
+        Some(aside)
+    } else {
+        None
+    };
+
+    let moved = match fs::rename(source, &target) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => file_import::copy_to(source, &target, false)
+            .and_then(|_| file_import::remove_entry(source).map_err(map_io_error)),
+        result => result.map_err(map_io_error),
+    };
+    match (&moved, set_aside) {
+        (Ok(_), Some(aside)) => {
+            let _ = file_import::remove_entry(&aside);
+        }
+        (Err(_), Some(aside)) => {
+            let _ = fs::rename(aside, &target);
+        }
+        _ => {}
+    }
+    moved.map(|_| Some(target.to_string_lossy().to_string()))
+}
+
+pub struct FileClipboardService {
+    contents: Arc<Mutex<Option<FileClipboard>>>,
+}
+
+impl FileClipboardService {
+    pub fn new() -> Self {
+        Self {
+            contents: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn set(&self, mode: ClipboardMode, paths: Vec<String>) -> FileClipboard {
+        let clipboard = FileClipboard { mode, paths };
+        *self.contents.lock().unwrap() = Some(clipboard.clone());
+        clipboard
+    }
+
+    pub fn get(&self) -> Option<FileClipboard> {
+        self.contents.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        *self.contents.lock().unwrap() = None;
+    }
+
+    /// Paste into `destination`. Copied paths stay on the clipboard for pasting again; cut paths are
+    /// moved and the clipboard is emptied, even if some of them failed to move.
+    pub fn paste(
+        &self,
+        destination: &Path,
+        strategy: ImportStrategy,
+        on_progress: impl FnMut(ImportProgress),
+    ) -> Result<ImportResult, FileSystemError> {
+        let empty = || FileSystemError::UnknownError("The clipboard is empty".to_string());
+        let clipboard = self.get().ok_or_else(empty)?;
+        if clipboard.mode == ClipboardMode::Copy {
+            return file_import::import_paths(&clipboard.paths, destination, strategy, on_progress);
+        }
+
+        if !fs::metadata(destination).map_err(map_io_error)?.is_dir() {
+            return Err(FileSystemError::InvalidPath);
+        }
+        // Cut paths are used up by the paste
+        self.clear();
+        let mut result = ImportResult::default();
+        for source in clipboard.paths {
+            match move_one(Path::new(&source), destination, strategy) {
+                Ok(Some(target)) => result.imported.push(ImportedPath {
+                    source,
+                    destination: target,
+                }),
+                Ok(None) => result.skipped.push(source),
+                Err(e) => result.failed.push(ImportFailure {
+                    source,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Default for FileClipboardService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
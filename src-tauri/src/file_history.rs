@@ -0,0 +1,234 @@
+/**
+ * Local file history for CodeForge IDE
+ * Keeps timestamped copies of saved files in the app data dir for timeline restore independent of git
+ */
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+use crate::session::workspace_key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data dir holding one folder of versions per file
+const HISTORY_DIR: &str = "history";
+
+const INDEX_FILE: &str = "entries.json";
+
+/// Files larger than this are not copied
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Versions kept per file, newest first
+const MAX_VERSIONS_PER_FILE: usize = 50;
+
+/// Total size of the versions kept per file
+const MAX_HISTORY_BYTES_PER_FILE: u64 = 20 * 1024 * 1024;
+
+/// Versions older than this are pruned
+const MAX_VERSION_AGE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+/// Error types for file history operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FileHistoryError {
+    NoDataDirectory,
+    IOError(String),
+    FileTooLarge(u64),
+    VersionNotFound(String),
+}
+
+impl std::fmt::Display for FileHistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FileHistoryError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            FileHistoryError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            FileHistoryError::FileTooLarge(size) => {
+                write!(f, "File is too large for local history ({} bytes)", size)
+            }
+            FileHistoryError::VersionNotFound(id) => write!(f, "Version not found: {}", id),
+        }
+    }
+}
+
+/// Why a version was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionSource {
+    Save,
+    AutoSave,
+    /// Content replaced by restoring an older version
+    Restore,
+    /// Content about to be deleted or overwritten by a file operation
+    Operation,
+}
+
+/// One saved copy of a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersion {
+    pub id: String,
+    pub path: String,
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub size: u64,
+    pub source: VersionSource,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    path: String,
+    versions: Vec<FileVersion>,
+}
+
+pub struct FileHistoryService {
+    locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+}
+
+fn io_error(e: std::io::Error) -> FileHistoryError {
+    FileHistoryError::IOError(e.to_string())
+}
+
+fn history_dir(app: &AppHandle, path: &str) -> Result<PathBuf, FileHistoryError> {
+    let data_dir = app.path().app_data_dir().map_err(|_| FileHistoryError::NoDataDirectory)?;
+    Ok(data_dir.join(HISTORY_DIR).join(workspace_key(path)))
+}
+
+fn load_index(dir: &Path, path: &str) -> HistoryIndex {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| HistoryIndex {
+            path: path.to_string(),
+            versions: Vec::new(),
+        })
+}
+
+fn store_index(dir: &Path, index: &HistoryIndex) -> Result<(), FileHistoryError> {
+    let content = serde_json::to_string_pretty(index).map_err(|e| FileHistoryError::IOError(e.to_string()))?;
+    write_atomic(&dir.join(INDEX_FILE), content).map_err(io_error)
+}
+
+/// Drop versions beyond the count, size, and age limits; `versions` is newest first
+fn prune(dir: &Path, versions: &mut Vec<FileVersion>) {
+    let cutoff = now_millis().saturating_sub(MAX_VERSION_AGE_MS);
+    let mut total = 0;
+    let mut keep = 0;
+    for (index, version) in versions.iter().enumerate() {
+        total += version.size;
+        // The newest version always survives so the last save can be restored
+        if index > 0
+            && (index >= MAX_VERSIONS_PER_FILE || total > MAX_HISTORY_BYTES_PER_FILE || version.timestamp < cutoff)
+        {
+            break;
+        }
+        keep = index + 1;
+    }
+    for version in versions.drain(keep..) {
+        let _ = fs::remove_file(dir.join(&version.id));
+    }
+}
+
+impl FileHistoryService {
+    pub fn new() -> Self {
+        Self {
+            locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Serializes history updates per file so concurrent saves don't clobber the index
+    fn file_lock(&self, path: &str) -> Arc<Mutex<()>> {
+        self.locks.lock().unwrap().entry(path.to_string()).or_default().clone()
+    }
+
+    /// Copy the current content of a file into its history, skipping it when unchanged since the last version
+    pub fn record_version(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        source: VersionSource,
+    ) -> Result<Option<FileVersion>, FileHistoryError> {
+        let size = fs::metadata(path).map_err(io_error)?.len();
+        if size > MAX_FILE_SIZE {
+            return Err(FileHistoryError::FileTooLarge(size));
+        }
+        let content = fs::read(path).map_err(io_error)?;
+
+        let lock = self.file_lock(path);
+        let _guard = lock.lock().unwrap();
+        let dir = history_dir(app, path)?;
+        fs::create_dir_all(&dir).map_err(io_error)?;
+        let mut index = load_index(&dir, path);
+
+        if let Some(latest) = index.versions.first() {
+            if latest.size == size && fs::read(dir.join(&latest.id)).is_ok_and(|previous| previous == content) {
+                return Ok(None);
+            }
+        }
+
+        let timestamp = now_millis();
+        // Several saves can land in the same millisecond
+        let mut id = timestamp.to_string();
+        let mut suffix = 1;
+        while index.versions.iter().any(|version| version.id == id) {
+            id = format!("{}-{}", timestamp, suffix);
+            suffix += 1;
+        }
+        fs::write(dir.join(&id), &content).map_err(io_error)?;
+
+        let version = FileVersion {
+            id,
+            path: path.to_string(),
+            timestamp,
+            size,
+            source,
+        };
+        index.versions.insert(0, version.clone());
+        prune(&dir, &mut index.versions);
+        store_index(&dir, &index)?;
+        Ok(Some(version))
+    }
+
+    /// Saved versions of a file, newest first
+    pub fn history(&self, app: &AppHandle, path: &str) -> Result<Vec<FileVersion>, FileHistoryError> {
+        let dir = history_dir(app, path)?;
+        Ok(load_index(&dir, path).versions)
+    }
+
+    /// Content of one version, e.g. to diff it against the current buffer
+    pub fn version_content(&self, app: &AppHandle, path: &str, version_id: &str) -> Result<String, FileHistoryError> {
+        let dir = history_dir(app, path)?;
+        if !load_index(&dir, path).versions.iter().any(|version| version.id == version_id) {
+            return Err(FileHistoryError::VersionNotFound(version_id.to_string()));
+        }
+        let content = fs::read(dir.join(version_id)).map_err(io_error)?;
+        Ok(String::from_utf8_lossy(&content).to_string())
+    }
+
+    /// Overwrite a file with one of its versions; the content being replaced is recorded first
+    pub fn restore_version(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        version_id: &str,
+    ) -> Result<FileVersion, FileHistoryError> {
+        let dir = history_dir(app, path)?;
+        let version = load_index(&dir, path)
+            .versions
+            .into_iter()
+            .find(|version| version.id == version_id)
+            .ok_or_else(|| FileHistoryError::VersionNotFound(version_id.to_string()))?;
+        let content = fs::read(dir.join(version_id)).map_err(io_error)?;
+
+        if Path::new(path).is_file() {
+            self.record_version(app, path, VersionSource::Restore)?;
+        }
+        fs::write(path, content).map_err(io_error)?;
+        Ok(version)
+    }
+}
+
+impl Default for FileHistoryService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,94 @@
+/**
+ * File icon mapping for CodeForge IDE
+ * Maps file names, extensions, and folder names to explorer icon ids. The bundled map is overlaid with
+ * the user's `file-icons.json` from the config directory.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::jsonc;
+use crate::types::FileSystemError;
+
+/// User overrides inside the app config directory
+pub const FILE_ICONS_FILE: &str = "file-icons.json";
+
+const BUNDLED_ICONS: &str = include_str!("default.json");
+
+/// Icon ids by name; keys are lowercase and extensions have no leading dot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileIconMap {
+    pub file: String,
+    pub folder: String,
+    /// Compound extensions such as `d.ts` or `tar.gz` win over their last part
+    pub file_extensions: HashMap<String, String>,
+    pub file_names: HashMap<String, String>,
+    pub folder_names: HashMap<String, String>,
+}
+
+/// Same shape as the map, with every field optional so users only list what they change
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct FileIconOverrides {
+    file: Option<String>,
+    folder: Option<String>,
+    file_extensions: HashMap<String, String>,
+    file_names: HashMap<String, String>,
+    folder_names: HashMap<String, String>,
+}
+
+fn normalize(entries: HashMap<String, String>) -> impl Iterator<Item = (String, String)> {
+    entries.into_iter().map(|(key, icon)| (key.trim_start_matches('.').to_lowercase(), icon))
+}
+
+impl FileIconMap {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_ICONS).expect("bundled file icon map is valid")
+    }
+
+    /// The bundled map with `file-icons.json` from `config_dir` applied, if there is one
+    pub fn load(config_dir: Option<&Path>) -> Result<Self, FileSystemError> {
+        let mut map = Self::bundled();
+        let Some(path) = config_dir.map(|dir| dir.join(FILE_ICONS_FILE)).filter(|path| path.exists()) else {
+            return Ok(map);
+        };
+
+        let content = fs::read_to_string(&path).map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        let overrides: FileIconOverrides = serde_json::from_str(&jsonc::strip(&content))
+            .map_err(|e| FileSystemError::UnknownError(format!("Invalid {}: {}", FILE_ICONS_FILE, e)))?;
+        if let Some(file) = overrides.file {
+            map.file = file;
+        }
+        if let Some(folder) = overrides.folder {
+            map.folder = folder;
+        }
+        map.file_extensions.extend(normalize(overrides.file_extensions));
+        // File names keep their leading dot (`.gitignore`)
+        map.file_names.extend(overrides.file_names.into_iter().map(|(name, icon)| (name.to_lowercase(), icon)));
+        map.folder_names.extend(overrides.folder_names.into_iter().map(|(name, icon)| (name.to_lowercase(), icon)));
+        Ok(map)
+    }
+
+    /// Icon id for an entry: exact name first, then the longest matching extension, then the default
+    pub fn icon_for(&self, name: &str, is_directory: bool) -> String {
+        let name = name.to_lowercase();
+        if is_directory {
+            return self.folder_names.get(&name).unwrap_or(&self.folder).clone();
+        }
+        if let Some(icon) = self.file_names.get(&name) {
+            return icon.clone();
+        }
+
+        // `app.test.ts` tries `test.ts` before `ts`; a leading dot doesn't start an extension
+        let stem_start = usize::from(name.starts_with('.'));
+        name[stem_start..]
+            .match_indices('.')
+            .map(|(index, _)| &name[stem_start + index + 1..])
+            .find_map(|extension| self.file_extensions.get(extension))
+            .unwrap_or(&self.file)
+            .clone()
+    }
+}
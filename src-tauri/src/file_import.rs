@@ -0,0 +1,275 @@
+/**
+ * Importing external files for CodeForge IDE
+ * Copies files and folders dropped onto the explorer from the OS into a workspace folder, resolving
+ * name collisions and reporting progress
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use crate::file_system::{copy_symlink, map_io_error, remove_symlink};
+use crate::types::FileSystemError;
+
+/// Event carrying progress of an `import_paths` call
+pub const IMPORT_PROGRESS_EVENT: &str = "fs://import-progress";
+
+/// How often progress is reported while files are copied
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What to do when the destination already has an entry with the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStrategy {
+    /// Import as `name (1).ext`, `name (2).ext`, ...
+    Rename,
+    /// Replace the existing entry once the import has been copied in full
+    Overwrite,
+    Skip,
+}
+
+/// Progress of an import, emitted as `fs://import-progress`; skipped entries count as processed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub destination: String,
+    pub current: Option<String>,
+    pub files_processed: u64,
+    pub total_files: u64,
+    pub bytes_processed: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedPath {
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: Vec<ImportedPath>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// Files and bytes under a path; links count as files and are never followed
+fn measure(path: &Path) -> (u64, u64) {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .fold((0, 0), |(files, bytes), entry| {
+            let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            (files + 1, bytes + size)
+        })
+}
+
+/// First of `name (1).ext`, `name (2).ext`, ... that doesn't exist in `directory`
+fn unique_target(directory: &Path, name: &str) -> PathBuf {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+    (1..)
+        .map(|n| directory.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap()
+}
+
+pub(crate) fn remove_entry(path: &Path) -> std::io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        remove_symlink(path)
+    } else if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+struct Reporter<'a> {
+    progress: ImportProgress,
+    last_report: Instant,
+    on_progress: &'a mut dyn FnMut(ImportProgress),
+}
+
+impl Reporter<'_> {
+    fn advance(&mut self, path: &Path, files: u64, bytes: u64) {
+        self.progress.files_processed += files;
+        self.progress.bytes_processed += bytes;
+        if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.last_report = Instant::now();
+            self.progress.current = Some(path.to_string_lossy().to_string());
+            (self.on_progress)(self.progress.clone());
+        }
+    }
+}
+
+/// Copy `source` to `target` without following links
+fn copy_entry(source: &Path, target: &Path, reporter: &mut Reporter) -> Result<(), FileSystemError> {
+    let metadata = fs::symlink_metadata(source).map_err(map_io_error)?;
+    if metadata.file_type().is_symlink() {
+        copy_symlink(source, target)?;
+        reporter.advance(source, 1, metadata.len());
+    } else if metadata.is_dir() {
+        fs::create_dir(target).map_err(map_io_error)?;
+        for entry in fs::read_dir(source).map_err(map_io_error)? {
+            let entry = entry.map_err(map_io_error)?;
+            copy_entry(&entry.path(), &target.join(entry.file_name()), reporter)?;
+        }
+    } else {
+        fs::copy(source, target).map_err(map_io_error)?;
+        reporter.advance(source, 1, metadata.len());
+    }
+    Ok(())
+}
+
+/// Where an entry named like `source` goes inside `destination`
+pub(crate) enum Placement {
+    /// Skipped, or there is nothing to do
+    Skip,
+    Place { target: PathBuf, replace: bool },
+}
+
+/// Resolve name collisions for `source` in `destination`. When moving, finding `source` itself already
+/// there means there is nothing to do.
+pub(crate) fn placement(
+    source: &Path,
+    destination: &Path,
+    strategy: ImportStrategy,
+    moving: bool,
+) -> Result<Placement, FileSystemError> {
+    let name = source.file_name().ok_or(FileSystemError::InvalidPath)?.to_string_lossy().to_string();
+    // Dangling links have nothing to resolve but can still be imported as links
+    let canonical_source = source.canonicalize().ok();
+    let canonical_destination = destination.canonicalize().map_err(map_io_error)?;
+    if canonical_source.as_ref().is_some_and(|source| canonical_destination.starts_with(source)) {
+        // A folder can't be imported into itself
+        return Err(FileSystemError::InvalidPath);
+    }
+
+    let target = destination.join(&name);
+    if fs::symlink_metadata(&target).is_err() {
+        return Ok(Placement::Place { target, replace: false });
+    }
+    // Dropping a file onto the folder it's already in has nothing to overwrite
+    let is_source = canonical_source.is_some() && target.canonicalize().ok() == canonical_source;
+    Ok(match strategy {
+        _ if is_source && moving => Placement::Skip,
+        ImportStrategy::Skip => Placement::Skip,
+        ImportStrategy::Overwrite if is_source => Placement::Skip,
+        ImportStrategy::Overwrite => Placement::Place { target, replace: true },
+        ImportStrategy::Rename => Placement::Place {
+            target: unique_target(destination, &name),
+            replace: false,
+        },
+    })
+}
+
+/// Copy next to the target first, then move it into place, so a failed copy never destroys what it
+/// would replace
+fn place_copy(
+    source: &Path,
+    target: &Path,
+    replace: bool,
+    reporter: &mut Reporter,
+) -> Result<(), FileSystemError> {
+    let directory = target.parent().ok_or(FileSystemError::InvalidPath)?;
+    let name = target.file_name().ok_or(FileSystemError::InvalidPath)?.to_string_lossy().to_string();
+    let staging = (0..)
+        .map(|n| directory.join(format!(".{}.codeforge-import-{}", name, n)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap();
+    copy_entry(source, &staging, reporter)
+        .and_then(|_| {
+            if replace {
+                remove_entry(target).map_err(map_io_error)?;
+            }
+            fs::rename(&staging, target).map_err(map_io_error)
+        })
+        .inspect_err(|_| {
+            let _ = remove_entry(&staging);
+        })
+}
+
+/// Copy `source` to exactly `target`, replacing what is there when `replace` is set
+pub(crate) fn copy_to(source: &Path, target: &Path, replace: bool) -> Result<(), FileSystemError> {
+    let mut reporter = Reporter {
+        progress: ImportProgress::default(),
+        last_report: Instant::now(),
+        on_progress: &mut |_| {},
+    };
+    place_copy(source, target, replace, &mut reporter)
+}
+
+fn import_one(
+    source: &Path,
+    destination: &Path,
+    strategy: ImportStrategy,
+    reporter: &mut Reporter,
+) -> Result<Option<PathBuf>, FileSystemError> {
+    match placement(source, destination, strategy, false)? {
+        Placement::Skip => Ok(None),
+        Placement::Place { target, replace } => {
+            place_copy(source, &target, replace, reporter)?;
+            Ok(Some(target))
+        }
+    }
+}
+
+/// Copy `sources` into the `destination` directory, continuing past entries that fail
+pub fn import_paths(
+    sources: &[String],
+    destination: &Path,
+    strategy: ImportStrategy,
+    mut on_progress: impl FnMut(ImportProgress),
+) -> Result<ImportResult, FileSystemError> {
+    if !fs::metadata(destination).map_err(map_io_error)?.is_dir() {
+        return Err(FileSystemError::InvalidPath);
+    }
+
+    let sizes: Vec<(u64, u64)> = sources.iter().map(|source| measure(Path::new(source))).collect();
+    let mut reporter = Reporter {
+        progress: ImportProgress {
+            destination: destination.to_string_lossy().to_string(),
+            total_files: sizes.iter().map(|(files, _)| files).sum(),
+            total_bytes: sizes.iter().map(|(_, bytes)| bytes).sum(),
+            ..Default::default()
+        },
+        last_report: Instant::now(),
+        on_progress: &mut on_progress,
+    };
+
+    let mut result = ImportResult::default();
+    for (source, (files, bytes)) in sources.iter().zip(sizes) {
+        let before = (reporter.progress.files_processed, reporter.progress.bytes_processed);
+        match import_one(Path::new(source), destination, strategy, &mut reporter) {
+            Ok(Some(target)) => result.imported.push(ImportedPath {
+                source: source.clone(),
+                destination: target.to_string_lossy().to_string(),
+            }),
+            Ok(None) => result.skipped.push(source.clone()),
+            Err(e) => result.failed.push(ImportFailure {
+                source: source.clone(),
+                error: e.to_string(),
+            }),
+        }
+        // Whatever wasn't copied still counts as done so the totals add up
+        reporter.progress.files_processed = before.0 + files;
+        reporter.progress.bytes_processed = before.1 + bytes;
+    }
+
+    let mut progress = reporter.progress;
+    progress.current = None;
+    on_progress(progress);
+    Ok(result)
+}
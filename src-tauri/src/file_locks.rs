@@ -0,0 +1,112 @@
+/**
+ * Advisory file locks for CodeForge IDE
+ * Lets IDE windows claim the files they are editing so other windows can warn before editing the same
+ * file. Locks are held per window and released when the window closes; nothing stops writes to a locked
+ * file.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Event sent to every window when a file is locked or unlocked
+pub const FILE_LOCK_CHANGED_EVENT: &str = "fs://lock-changed";
+
+/// Error types for file lock operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FileLockError {
+    /// Another window holds the lock
+    Locked(FileLock),
+    NotOwner(FileLock),
+}
+
+impl std::fmt::Display for FileLockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FileLockError::Locked(lock) => write!(f, "{} is locked by window {}", lock.path, lock.owner),
+            FileLockError::NotOwner(lock) => {
+                write!(f, "{} is locked by window {}, not this one", lock.path, lock.owner)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLock {
+    pub path: String,
+    /// Label of the window holding the lock
+    pub owner: String,
+    /// Unix time in milliseconds
+    pub acquired_at: u64,
+}
+
+/// Payload of `fs://lock-changed`; `lock` is `None` once the file is unlocked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLockChange {
+    pub path: String,
+    pub lock: Option<FileLock>,
+}
+
+pub struct FileLockService {
+    /// Keyed by resolved path so different spellings of one file share a lock
+    locks: Arc<Mutex<HashMap<PathBuf, FileLock>>>,
+}
+
+impl FileLockService {
+    pub fn new() -> Self {
+        Self {
+            locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Lock `resolved` for `owner`; locking a file the owner already holds returns the existing lock
+    pub fn lock(&self, resolved: &Path, path: &str, owner: &str) -> Result<FileLock, FileLockError> {
+        let mut locks = self.locks.lock().unwrap();
+        if let Some(existing) = locks.get(resolved) {
+            if existing.owner != owner {
+                return Err(FileLockError::Locked(existing.clone()));
+            }
+            return Ok(existing.clone());
+        }
+
+        let lock = FileLock {
+            path: path.to_string(),
+            owner: owner.to_string(),
+            acquired_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or(0),
+        };
+        locks.insert(resolved.to_path_buf(), lock.clone());
+        Ok(lock)
+    }
+
+    /// Release a lock held by `owner`; returns whether there was one to release
+    pub fn unlock(&self, resolved: &Path, owner: &str) -> Result<Option<FileLock>, FileLockError> {
+        let mut locks = self.locks.lock().unwrap();
+        match locks.get(resolved) {
+            Some(existing) if existing.owner != owner => Err(FileLockError::NotOwner(existing.clone())),
+            _ => Ok(locks.remove(resolved)),
+        }
+    }
+
+    pub fn query(&self, resolved: &Path) -> Option<FileLock> {
+        self.locks.lock().unwrap().get(resolved).cloned()
+    }
+
+    /// Release every lock held by a window, e.g. once it has closed
+    pub fn release_owner(&self, owner: &str) -> Vec<FileLock> {
+        let mut locks = self.locks.lock().unwrap();
+        let released: Vec<PathBuf> =
+            locks.iter().filter(|(_, lock)| lock.owner == owner).map(|(path, _)| path.clone()).collect();
+        released.iter().filter_map(|path| locks.remove(path)).collect()
+    }
+}
+
+impl Default for FileLockService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
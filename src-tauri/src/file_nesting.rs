@@ -0,0 +1,205 @@
+/**
+ * File nesting for CodeForge IDE
+ * Groups companion files under the file they belong to in explorer listings, e.g. `main.js.map` under
+ * `main.js` and `Cargo.lock` under `Cargo.toml`, from rules in the `file_nesting_patterns` preference
+ *
+ * A rule maps a parent name pattern with at most one `*` to comma-separated child patterns. In the child
+ * patterns `${capture}` is what the `*` matched and `${basename}` is the parent's name without its last
+ * extension; what's left may use glob syntax. Only files nest, and only within one directory. A file that
+ * would nest under a file that is itself nested goes under the outermost one.
+ */
+
+use globset::GlobSet;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::file_system::FileSystemService;
+use crate::globs;
+use crate::settings::{SettingsLayer, SettingsService};
+
+/// Rules in the preferences until the user changes them
+const DEFAULT_PATTERNS: &[(&str, &str)] = &[
+    ("*.js", "${capture}.js.map, ${capture}.min.js, ${capture}.d.ts"),
+    ("*.ts", "${capture}.js, ${capture}.d.ts, ${capture}.js.map"),
+    ("*.tsx", "${capture}.js, ${capture}.jsx, ${capture}.js.map"),
+    ("*.scss", "${capture}.css, ${capture}.css.map"),
+    ("Cargo.toml", "Cargo.lock"),
+    ("package.json", "package-lock.json, npm-shrinkwrap.json, yarn.lock, pnpm-lock.yaml, bun.lockb"),
+    ("pyproject.toml", "poetry.lock, uv.lock, pdm.lock"),
+    ("Pipfile", "Pipfile.lock"),
+    ("go.mod", "go.sum"),
+    ("Gemfile", "Gemfile.lock"),
+    ("composer.json", "composer.lock"),
+    (".gitignore", ".gitattributes, .gitmodules"),
+];
+
+pub fn default_patterns() -> BTreeMap<String, String> {
+    DEFAULT_PATTERNS.iter().map(|(parent, children)| (parent.to_string(), children.to_string())).collect()
+}
+
+/// Why a parent pattern can't be used, or `None` when it's fine
+pub fn parent_pattern_problem(pattern: &str) -> Option<String> {
+    if pattern.trim().is_empty() {
+        Some("file nesting parent pattern must not be empty".to_string())
+    } else if pattern.matches('*').count() > 1 {
+        Some(format!("file nesting parent pattern '{}' may contain at most one '*'", pattern))
+    } else if pattern.contains(['/', '\\']) {
+        Some(format!("file nesting parent pattern '{}' must be a file name, not a path", pattern))
+    } else {
+        None
+    }
+}
+
+struct NestingRule {
+    /// Text before and after the `*`, or the whole name when there's no `*`
+    prefix: String,
+    suffix: Option<String>,
+    children: Vec<String>,
+}
+
+impl NestingRule {
+    /// What the `*` matched, or an empty string for a rule without `*`
+    fn capture<'a>(&self, name: &'a str) -> Option<&'a str> {
+        match &self.suffix {
+            None => (name == self.prefix).then_some(""),
+            Some(suffix) => name.strip_prefix(self.prefix.as_str())?.strip_suffix(suffix.as_str()),
+        }
+    }
+}
+
+/// Compiled nesting rules
+pub struct FileNesting {
+    rules: Vec<NestingRule>,
+}
+
+impl FileNesting {
+    /// Rules from `patterns`; parent patterns with problems are left out
+    pub fn new(patterns: &BTreeMap<String, String>) -> Self {
+        let rules = patterns
+            .iter()
+            .filter(|(parent, _)| parent_pattern_problem(parent).is_none())
+            .map(|(parent, children)| {
+                let (prefix, suffix) = match parent.split_once('*') {
+                    Some((prefix, suffix)) => (prefix.to_string(), Some(suffix.to_string())),
+                    None => (parent.to_string(), None),
+                };
+                NestingRule {
+                    prefix,
+                    suffix,
+                    children: children
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|child| !child.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                }
+            })
+            .collect();
+        FileNesting { rules }
+    }
+
+    /// The rules for listings of `path` from the settings of its workspace, or `None` when nesting is off.
+    /// Unreadable settings turn nesting off rather than failing the listing.
+    pub fn configured(app: &AppHandle, path: &str) -> Option<Self> {
+        let workspace = app.state::<FileSystemService>().workspace_root(path);
+        let workspace = workspace.map(|root| root.to_string_lossy().to_string());
+        let scope = match workspace {
+            Some(_) => SettingsLayer::Workspace,
+            None => SettingsLayer::User,
+        };
+        let effective = app.state::<SettingsService>().effective_settings(app, scope, workspace.as_deref());
+        let settings = match effective {
+            Ok(effective) => effective.settings,
+            Err(e) => {
+                tracing::warn!(path = %path, error = %e, "reading file nesting settings failed");
+                return None;
+            }
+        };
+        let setting = |key: &str| settings.get(key).map(|setting| &setting.value);
+
+        if !setting("file_nesting").and_then(Value::as_bool).unwrap_or(false) {
+            return None;
+        }
+        let patterns: BTreeMap<String, String> = setting("file_nesting_patterns")
+            .and_then(Value::as_object)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|(parent, children)| Some((parent.clone(), children.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(FileNesting::new(&patterns))
+    }
+
+    /// For each entry of a directory, given as its name and whether it's a file, the index of the top-level
+    /// entry it nests under, if any. Where two files could both be a file's parent, the one listed first wins.
+    pub fn nest(&self, entries: &[(&str, bool)]) -> Vec<Option<usize>> {
+        let index: HashMap<&str, usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, is_file))| *is_file)
+            .map(|(i, (name, _))| (*name, i))
+            .collect();
+        let mut parents: Vec<Option<usize>> = vec![None; entries.len()];
+
+        for (i, (name, is_file)) in entries.iter().enumerate() {
+            if !is_file {
+                continue;
+            }
+            for rule in &self.rules {
+                let Some(capture) = rule.capture(name) else {
+                    continue;
+                };
+                let basename = Path::new(name).file_stem().map(|stem| stem.to_string_lossy().to_string());
+                let basename = basename.as_deref().unwrap_or(name);
+                for child in &rule.children {
+                    for j in child_matches(child, capture, basename, entries, &index) {
+                        // A file can't nest under itself or anything nested under it
+                        let cycle = j == i || ancestors(&parents, i).any(|ancestor| ancestor == j);
+                        if parents[j].is_none() && !cycle {
+                            parents[j] = Some(i);
+                        }
+                    }
+                }
+            }
+        }
+
+        (0..entries.len()).map(|i| ancestors(&parents, i).last()).collect()
+    }
+}
+
+/// `i`'s parent, grandparent, and so on
+fn ancestors(parents: &[Option<usize>], i: usize) -> impl Iterator<Item = usize> + '_ {
+    std::iter::successors(parents[i], move |parent| parents[*parent])
+}
+
+/// Indices of the files a child pattern names once the parent's values are filled in
+fn child_matches(
+    child: &str,
+    capture: &str,
+    basename: &str,
+    entries: &[(&str, bool)],
+    index: &HashMap<&str, usize>,
+) -> Vec<usize> {
+    let literal = child.replace("${capture}", capture).replace("${basename}", basename);
+    if !globs::is_pattern(&child.replace("${capture}", "").replace("${basename}", "")) {
+        return index.get(literal.as_str()).copied().into_iter().collect();
+    }
+    // Brackets and stars in the parent's own name are matched literally
+    let pattern = child
+        .replace("${capture}", &globset::escape(capture))
+        .replace("${basename}", &globset::escape(basename));
+    let set: GlobSet = match globs::build_set(&[pattern], false) {
+        Ok(set) => set,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, (name, is_file))| *is_file && set.is_match(name))
+        .map(|(j, _)| j)
+        .collect()
+}
@@ -0,0 +1,116 @@
+/**
+ * File statistics for the status bar
+ * Counts lines, words, and characters and detects line endings and indentation in the backend, so large
+ * files never have to be sent to the frontend just to be measured
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::file_system::map_io_error;
+use crate::indentation::{Indentation, IndentationCounter};
+use crate::types::FileSystemError;
+
+/// Bytes checked for a NUL byte to tell binary files apart
+const BINARY_SNIFF_LENGTH: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both occur
+    Mixed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStats {
+    pub path: String,
+    pub size: u64,
+    pub is_binary: bool,
+    /// Lines of text; a final line break doesn't start another
+    pub line_count: u64,
+    pub word_count: u64,
+    /// Characters including line breaks
+    pub char_count: u64,
+    /// In characters, without the line break
+    pub max_line_length: u64,
+    /// One-based line number of the longest line
+    pub longest_line: u64,
+    /// `None` for files without line breaks
+    pub line_ending: Option<LineEnding>,
+    pub final_newline: bool,
+    /// `None` when no line is indented
+    pub indentation: Option<Indentation>,
+}
+
+/// Measure a text file line by line without holding it in memory. Binary files are reported as such with
+/// every count left at zero.
+pub fn file_stats(path: &Path) -> Result<FileStats, FileSystemError> {
+    let file = File::open(path).map_err(map_io_error)?;
+    let size = file.metadata().map_err(map_io_error)?.len();
+    let mut reader = BufReader::new(file);
+    let mut stats = FileStats {
+        path: path.to_string_lossy().to_string(),
+        size,
+        is_binary: false,
+        line_count: 0,
+        word_count: 0,
+        char_count: 0,
+        max_line_length: 0,
+        longest_line: 0,
+        line_ending: None,
+        final_newline: false,
+        indentation: None,
+    };
+    let head = reader.fill_buf().map_err(map_io_error)?;
+    if head[..head.len().min(BINARY_SNIFF_LENGTH)].contains(&0) {
+        stats.is_binary = true;
+        return Ok(stats);
+    }
+
+    let mut indentation = IndentationCounter::default();
+    let (mut lf, mut crlf) = (false, false);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer).map_err(map_io_error)? == 0 {
+            break;
+        }
+        let raw = String::from_utf8_lossy(&buffer);
+        stats.line_count += 1;
+        stats.char_count += raw.chars().count() as u64;
+        stats.final_newline = raw.ends_with('\n');
+        let line = match raw.strip_suffix("\r\n") {
+            Some(line) => {
+                crlf = true;
+                line
+            }
+            None => match raw.strip_suffix('\n') {
+                Some(line) => {
+                    lf = true;
+                    line
+                }
+                None => &raw,
+            },
+        };
+        stats.word_count += line.split_whitespace().count() as u64;
+        let length = line.chars().count() as u64;
+        if length > stats.max_line_length {
+            stats.max_line_length = length;
+            stats.longest_line = stats.line_count;
+        }
+        indentation.record(line);
+    }
+
+    stats.line_ending = match (lf, crlf) {
+        (true, true) => Some(LineEnding::Mixed),
+        (false, true) => Some(LineEnding::Crlf),
+        (true, false) => Some(LineEnding::Lf),
+        (false, false) => None,
+    };
+    stats.indentation = indentation.indentation();
+    Ok(stats)
+}
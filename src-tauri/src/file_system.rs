@@ -3,38 +3,574 @@
  * Provides comprehensive file operations with error handling and performance optimization
  */
 
+use crate::file_icons::FileIconMap;
+use crate::file_nesting::FileNesting;
+use crate::file_type;
+use crate::fs_provider::{
+    self, EntryKind, FileSystemProvider, LocalProvider, OpenProvider, ProviderStat, RemoteUri, WslPath,
+    WslProvider,
+};
+use crate::fs_watch::{self, DirectoryWatch, WatchKind, WatchStatus};
 use crate::types::*;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use notify::{Watcher, RecursiveMode, Event};
 use serde_json;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::fs::{self, File, OpenOptions};
-use std::io::{self, Read, Write, BufRead, BufReader};
-use std::path::{Path, PathBuf};
+use std::io::{self, Read, Seek, SeekFrom, Write, BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
 use tauri::async_runtime::spawn;
 use tokio::sync::mpsc;
 
+/// Bytes per row of a hex dump
+const HEX_BYTES_PER_ROW: usize = 16;
+
+/// Most bytes a single hex dump request may read
+const MAX_HEX_DUMP_LENGTH: u64 = 256 * 1024;
+
+/// Most entries a single directory page may hold
+const MAX_PAGE_SIZE: usize = 10_000;
+
+/// Paged listings kept open for their cursors; the oldest is dropped beyond this
+const MAX_OPEN_LISTINGS: usize = 16;
+
+/// Paths file operations may touch: the open workspace roots plus explicitly allowed paths
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSystemScope {
+    pub workspace_roots: Vec<PathBuf>,
+    /// Files or directories opened from outside any workspace, e.g. through an open dialog
+    pub allowed_paths: Vec<PathBuf>,
+    /// Workspaces on other machines, as URIs such as `ssh://dev@build-box/home/dev/project`
+    #[serde(default)]
+    pub remote_roots: Vec<String>,
+}
+
+impl FileSystemScope {
+    fn contains(&self, path: &Path) -> bool {
+        self.workspace_roots
+            .iter()
+            .chain(self.allowed_paths.iter())
+            .any(|allowed| path.starts_with(allowed))
+    }
+
+    fn contains_remote(&self, uri: &RemoteUri) -> bool {
+        self.remote_roots
+            .iter()
+            .filter_map(|root| RemoteUri::parse(root))
+            .any(|root| uri.starts_with(&root))
+    }
+
+    /// The innermost workspace root containing `path`
+    fn workspace_root(&self, path: &Path) -> Option<&PathBuf> {
+        self.workspace_roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+}
+
+/// Resolve a path the way the OS will, including symlinks and `..`, even when it doesn't exist yet
+///
+/// The nearest existing ancestor is canonicalized and the remaining components are appended, so a
+/// new file is judged by the directory it would be created in.
+fn resolve_path(path: &Path) -> Result<PathBuf, FileSystemError> {
+    if !path.is_absolute() {
+        return Err(FileSystemError::InvalidPath);
+    }
+
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(resolved) => {
+                let mut resolved = resolved;
+                for component in missing.iter().rev() {
+                    match component {
+                        Component::Normal(name) => resolved.push(name),
+                        Component::CurDir => {}
+                        // Can't tell where `..` after a missing directory leads without the directory
+                        _ => return Err(FileSystemError::InvalidPath),
+                    }
+                }
+                return Ok(resolved);
+            }
+            Err(_) => {
+                let component = existing.components().next_back().ok_or(FileSystemError::InvalidPath)?;
+                missing.push(component);
+                existing = existing.parent().ok_or(FileSystemError::InvalidPath)?;
+            }
+        }
+    }
+}
+
+/// Resolve a path without following a symlink in its last component, so links themselves can be
+/// inspected, renamed, or deleted even when they point outside the scope
+fn resolve_link_path(path: &Path) -> Result<PathBuf, FileSystemError> {
+    let name = path.file_name().ok_or(FileSystemError::InvalidPath)?;
+    let parent = path.parent().ok_or(FileSystemError::InvalidPath)?;
+    Ok(resolve_path(parent)?.join(name))
+}
+
+pub(crate) fn map_io_error(e: io::Error) -> FileSystemError {
+    match e.kind() {
+        io::ErrorKind::NotFound => FileSystemError::NotFound,
+        io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+        io::ErrorKind::AlreadyExists => FileSystemError::AlreadyExists,
+        _ => FileSystemError::IOError(e.to_string()),
+    }
+}
+
+#[cfg(unix)]
+fn make_symlink(target: &Path, link: &Path, _is_dir: bool) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn make_symlink(target: &Path, link: &Path, is_dir: bool) -> io::Result<()> {
+    // Windows needs to know up front whether the link is for a file or a directory
+    if is_dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+/// Recreate the link at `path` as `destination`, pointing at the same target
+pub(crate) fn copy_symlink(path: &Path, destination: &Path) -> Result<(), FileSystemError> {
+    let target = fs::read_link(path).map_err(map_io_error)?;
+    make_symlink(&target, destination, path.is_dir()).map_err(map_io_error)
+}
+
+/// Remove a symlink itself, never what it points to
+pub(crate) fn remove_symlink(path: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    if path.is_dir() {
+        return fs::remove_dir(path);
+    }
+    fs::remove_file(path)
+}
+
+/// Name for a uid or gid from `/etc/passwd` or `/etc/group`
+#[cfg(unix)]
+fn lookup_id_name(database: &str, id: u32) -> Option<String> {
+    let content = fs::read_to_string(database).ok()?;
+    content.lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let entry_id: u32 = fields.nth(1)?.parse().ok()?;
+            (entry_id == id).then(|| name.to_string())
+        })
+}
+
+/// Sorted snapshot of a directory that the pages of a listing are served from
+struct ListingSnapshot {
+    path: String,
+    entries: Vec<ListedPath>,
+    hidden_count: usize,
+    filtered_count: usize,
+    /// Entries of a listing from a provider, which pages are served from instead of the disk
+    provided: Option<Arc<HashMap<PathBuf, DirectoryEntry>>>,
+}
+
+/// A top-level entry of a listing and the files nested under it
+#[derive(Clone)]
+struct ListedPath {
+    path: PathBuf,
+    nested: Vec<PathBuf>,
+}
+
+/// Compiled `DirectoryFilter`, matched against lowercase names
+struct NameFilter {
+    patterns: Option<GlobSet>,
+    extensions: Vec<String>,
+    filter_directories: bool,
+}
+
+impl NameFilter {
+    fn new(filter: &DirectoryFilter) -> Result<Self, FileSystemError> {
+        let patterns = if filter.patterns.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &filter.patterns {
+                let glob = GlobBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| {
+                        FileSystemError::UnknownError(format!("Invalid filter pattern '{}': {}", pattern, e))
+                    })?;
+                builder.add(glob);
+            }
+            Some(builder.build().map_err(|e| FileSystemError::UnknownError(e.to_string()))?)
+        };
+
+        Ok(Self {
+            patterns,
+            extensions: filter.extensions.iter()
+                .map(|extension| extension.trim_start_matches('.').to_lowercase())
+                .collect(),
+            filter_directories: filter.filter_directories,
+        })
+    }
+
+    fn matches(&self, name: &str, is_directory: bool) -> bool {
+        if is_directory && !self.filter_directories {
+            return true;
+        }
+        let pattern_match = self.patterns.as_ref().is_none_or(|patterns| patterns.is_match(name));
+        let extension_match = self.extensions.is_empty()
+            || self.extensions.iter().any(|extension| {
+                name.strip_suffix(extension.as_str()).is_some_and(|stem| stem.len() > 1 && stem.ends_with('.'))
+            });
+        pattern_match && extension_match
+    }
+}
+
+/// What a directory entry is sorted by; names and extensions are lowercase
+struct SortItem {
+    path: PathBuf,
+    name: String,
+    extension: String,
+    is_directory: bool,
+    size: u64,
+    modified: u64,
+}
+
+/// Sort listed items, directories first, and group them by the nesting rules
+fn arrange(mut items: Vec<SortItem>, sort: DirectorySort, nesting: Option<&FileNesting>) -> Vec<ListedPath> {
+    // Directories first, then by the sort key, with ties broken by name
+    items.sort_by(|a, b| {
+        b.is_directory.cmp(&a.is_directory).then_with(|| {
+            let ordering = match sort.key {
+                DirectorySortKey::Name => std::cmp::Ordering::Equal,
+                DirectorySortKey::Size => a.size.cmp(&b.size),
+                DirectorySortKey::Modified => a.modified.cmp(&b.modified),
+                DirectorySortKey::Type => a.extension.cmp(&b.extension),
+            }
+            .then_with(|| a.name.cmp(&b.name));
+            if sort.descending { ordering.reverse() } else { ordering }
+        })
+    });
+
+    // Nested files keep their order within their parent
+    let parents = match nesting {
+        Some(nesting) => {
+            let names: Vec<(String, bool)> = items.iter()
+                .map(|item| {
+                    let name = item.path.file_name().map(|name| name.to_string_lossy().to_string());
+                    (name.unwrap_or_default(), !item.is_directory)
+                })
+                .collect();
+            let names: Vec<(&str, bool)> = names.iter()
+                .map(|(name, is_file)| (name.as_str(), *is_file))
+                .collect();
+            nesting.nest(&names)
+        }
+        None => vec![None; items.len()],
+    };
+    let mut positions: Vec<Option<usize>> = vec![None; items.len()];
+    let mut entries: Vec<ListedPath> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        if parents[i].is_none() {
+            positions[i] = Some(entries.len());
+            entries.push(ListedPath { path: item.path.clone(), nested: Vec::new() });
+        }
+    }
+    for (i, item) in items.into_iter().enumerate() {
+        if let Some(position) = parents[i].and_then(|parent| positions[parent]) {
+            entries[position].nested.push(item.path);
+        }
+    }
+    entries
+}
+
+/// What a file looked like on disk when it was last read or saved, to notice changes made elsewhere
+struct DiskVersion {
+    modified: Option<SystemTime>,
+    size: u64,
+    hash: Vec<u8>,
+}
+
+impl DiskVersion {
+    fn new(metadata: &fs::Metadata, content: &[u8]) -> Self {
+        DiskVersion {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+            hash: Sha256::digest(content).to_vec(),
+        }
+    }
+
+    fn provided(stat: &ProviderStat, content: &[u8]) -> Self {
+        DiskVersion {
+            modified: stat.modified.map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds)),
+            size: stat.size,
+            hash: Sha256::digest(content).to_vec(),
+        }
+    }
+}
+
+/// A path on another machine's file system and the provider connected to it
+struct ProvidedPath {
+    provider: Arc<dyn FileSystemProvider>,
+    uri: RemoteUri,
+}
+
 pub struct FileSystemService {
-    watchers: Arc<Mutex<HashMap<String, notify::RecommendedWatcher>>>,
-    config: FileOperationConfig,
+    watchers: Arc<Mutex<HashMap<String, DirectoryWatch>>>,
+    next_watch_id: AtomicU64,
+    config: Mutex<FileOperationConfig>,
+    scope: Arc<Mutex<FileSystemScope>>,
+    icons: Arc<Mutex<FileIconMap>>,
+    listings: Arc<Mutex<VecDeque<(u64, ListingSnapshot)>>>,
+    next_listing_id: AtomicU64,
+    /// Keyed by resolved path, or by URI for files from providers
+    disk_versions: Arc<Mutex<HashMap<PathBuf, DiskVersion>>>,
+    local: Arc<LocalProvider>,
+    /// Providers of the open URI workspaces and of registered schemes, by scheme and authority
+    providers: Arc<Mutex<HashMap<String, Arc<dyn FileSystemProvider>>>>,
+    /// Schemes registered at runtime, like plugins' virtual file systems
+    schemes: Arc<Mutex<HashMap<String, OpenProvider>>>,
 }
 
 impl FileSystemService {
     pub fn new() -> Self {
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
-            config: FileOperationConfig {
+            next_watch_id: AtomicU64::new(1),
+            config: Mutex::new(FileOperationConfig {
                 overwrite: false,
                 create_parent_dirs: true,
                 preserve_permissions: true,
                 follow_symlinks: false,
-            },
+            }),
+            scope: Arc::new(Mutex::new(FileSystemScope::default())),
+            icons: Arc::new(Mutex::new(FileIconMap::bundled())),
+            listings: Arc::new(Mutex::new(VecDeque::new())),
+            next_listing_id: AtomicU64::new(1),
+            disk_versions: Arc::new(Mutex::new(HashMap::new())),
+            local: Arc::new(LocalProvider::new()),
+            providers: Arc::new(Mutex::new(HashMap::new())),
+            schemes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Open file system watchers
+    pub fn watcher_count(&self) -> usize {
+        self.watchers.lock().unwrap().len()
+    }
+
+    /// Watch a directory and send its changes to the frontend as `fs://change` events. The watch survives the
+    /// directory being deleted and recreated; `fs://rescan` says when events were lost.
+    pub fn watch_directory(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        recursive: bool,
+    ) -> Result<WatchStatus, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.watch_provided(app, provided, recursive, WatchKind::Directory);
+        }
+        let root = self.authorize(path)?;
+        if !root.is_dir() {
+            return Err(FileSystemError::InvalidPath);
+        }
+        let id = format!("watch-{}", self.next_watch_id.fetch_add(1, Ordering::SeqCst));
+        let watch = fs_watch::watch_directory(app, id.clone(), root, recursive)?;
+        let status = watch.status();
+        self.watchers.lock().unwrap().insert(id, watch);
+        Ok(status)
+    }
+
+    /// Watch one file and send changes made outside the IDE to the frontend as `fs://file-change` events
+    pub fn watch_file(&self, app: &AppHandle, path: &str) -> Result<WatchStatus, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.watch_provided(app, provided, false, WatchKind::File);
+        }
+        let resolved = self.authorize(path)?;
+        if !resolved.is_file() {
+            return Err(if resolved.exists() { FileSystemError::InvalidPath } else { FileSystemError::NotFound });
+        }
+        let id = format!("watch-{}", self.next_watch_id.fetch_add(1, Ordering::SeqCst));
+        let watch = fs_watch::watch_file(app, id.clone(), resolved)?;
+        let status = watch.status();
+        self.watchers.lock().unwrap().insert(id, watch);
+        Ok(status)
+    }
+
+    pub fn stop_watching(&self, watch_id: &str) -> Result<(), FileSystemError> {
+        self.watchers
+            .lock()
+            .unwrap()
+            .remove(watch_id)
+            .map(|_| ())
+            .ok_or(FileSystemError::NotFound)
+    }
+
+    /// Directory and file watches and how healthy each is
+    pub fn watch_statuses(&self) -> Vec<WatchStatus> {
+        let mut statuses: Vec<WatchStatus> = self.watchers.lock().unwrap()
+            .values()
+            .map(DirectoryWatch::status)
+            .collect();
+        statuses.sort_by_key(|status| status.started_at);
+        statuses
+    }
+
+    /// Allow operations anywhere under a workspace root. A URI root is on another machine, which is
+    /// connected to first.
+    pub fn add_workspace_root(&self, path: &str) -> Result<FileSystemScope, FileSystemError> {
+        if let Some(uri) = RemoteUri::parse(path) {
+            return self.add_remote_root(uri);
+        }
+        let root = Path::new(path).canonicalize()
+            .map_err(|_| FileSystemError::NotFound)?;
+        if !root.is_dir() {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let mut scope = self.scope.lock().unwrap();
+        if !scope.workspace_roots.contains(&root) {
+            scope.workspace_roots.push(root);
+        }
+        Ok(scope.clone())
+    }
+
+    /// Stop allowing operations under a workspace root, e.g. when its folder is closed
+    pub fn remove_workspace_root(&self, path: &str) -> Result<FileSystemScope, FileSystemError> {
+        if let Some(uri) = RemoteUri::parse(path) {
+            return Ok(self.remove_remote_root(&uri));
+        }
+        let root = Path::new(path).canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(path));
+
+        let mut scope = self.scope.lock().unwrap();
+        scope.workspace_roots.retain(|existing| existing != &root);
+        Ok(scope.clone())
+    }
+
+    /// Allow a single file or directory outside the workspace, such as one picked in an open dialog
+    pub fn allow_path(&self, path: &str) -> Result<FileSystemScope, FileSystemError> {
+        let allowed = resolve_path(Path::new(path))?;
+
+        let mut scope = self.scope.lock().unwrap();
+        if !scope.allowed_paths.contains(&allowed) {
+            scope.allowed_paths.push(allowed);
+        }
+        Ok(scope.clone())
+    }
+
+    pub fn scope(&self) -> FileSystemScope {
+        self.scope.lock().unwrap().clone()
+    }
+
+    /// Workspace root a path belongs to, if any; a symlink belongs where it is, not where it points
+    pub fn workspace_root(&self, path: &str) -> Option<PathBuf> {
+        let resolved = resolve_link_path(Path::new(path)).ok()?;
+        self.scope.lock().unwrap().workspace_root(&resolved).cloned()
+    }
+
+    /// Reject paths outside the scope; symlinks are resolved first so they can't be used to escape it
+    pub fn authorize(&self, path: &str) -> Result<PathBuf, FileSystemError> {
+        Self::reject_uri(path)?;
+        self.check_scope(path, resolve_path(Path::new(path))?)
+    }
+
+    /// Like `authorize`, but judges a symlink by where it is rather than where it points
+    pub fn authorize_link(&self, path: &str) -> Result<PathBuf, FileSystemError> {
+        Self::reject_uri(path)?;
+        self.check_scope(path, resolve_link_path(Path::new(path))?)
+    }
+
+    /// Operations that authorize a local path haven't been taught to go through a provider
+    fn reject_uri(path: &str) -> Result<(), FileSystemError> {
+        match RemoteUri::parse(path) {
+            Some(uri) if uri.scheme == fs_provider::ARCHIVE_SCHEME => {
+                Err(FileSystemError::Unsupported("this operation inside archives".to_string()))
+            }
+            Some(uri) => Err(FileSystemError::Unsupported(format!("this operation on {}:// paths", uri.scheme))),
+            None => Ok(()),
+        }
+    }
+
+    /// The provider for a URI inside the scope, or `None` for a local path. Registered schemes serve
+    /// only what their owner makes up, so any of their URIs is in scope; paths inside an archive are in
+    /// scope when the archive is.
+    fn provided(&self, path: &str) -> Result<Option<ProvidedPath>, FileSystemError> {
+        let Some(uri) = RemoteUri::parse(path) else {
+            return Ok(None);
+        };
+        let registered = self.schemes.lock().unwrap().contains_key(&uri.scheme);
+        let archive = uri.scheme == fs_provider::ARCHIVE_SCHEME;
+        if archive && !self.scope.lock().unwrap().contains_remote(&uri) {
+            self.authorize(&uri.authority)?;
+        }
+        if registered || archive {
+            let connected = self.providers.lock().unwrap().get(&uri.origin()).cloned();
+            let provider = match connected {
+                Some(provider) => provider,
+                None => {
+                    let provider = self.open_provider(&uri)?;
+                    self.providers.lock().unwrap().entry(uri.origin()).or_insert(provider).clone()
+                }
+            };
+            return Ok(Some(ProvidedPath { provider, uri }));
+        }
+        let denied = || FileSystemError::AccessDenied(path.to_string());
+        if !self.scope.lock().unwrap().contains_remote(&uri) {
+            return Err(denied());
         }
+        let provider = self.providers.lock().unwrap().get(&uri.origin()).cloned().ok_or_else(denied)?;
+        Ok(Some(ProvidedPath { provider, uri }))
+    }
+
+    /// The provider a workspace's files and processes are on, with the workspace's path there. Local paths
+    /// must be inside the scope and are passed through as they are, going to WSL on Windows for paths inside
+    /// a distro; URIs must be inside an open URI workspace.
+    pub fn provider(&self, path: &str) -> Result<(Arc<dyn FileSystemProvider>, String), FileSystemError> {
+        match self.provided(path)? {
+            Some(ProvidedPath { provider, uri }) => Ok((provider, uri.path)),
+            None => {
+                self.authorize(path)?;
+                match WslPath::parse(path) {
+                    Some(wsl) if cfg!(windows) => {
+                        Ok((Arc::new(WslProvider::new(&wsl.distro)), path.to_string()))
+                    }
+                    _ => Ok((self.local.clone(), path.to_string())),
+                }
+            }
+        }
+    }
+
+    fn check_scope(&self, path: &str, resolved: PathBuf) -> Result<PathBuf, FileSystemError> {
+        if self.scope.lock().unwrap().contains(&resolved) {
+            Ok(resolved)
+        } else {
+            Err(FileSystemError::AccessDenied(path.to_string()))
+        }
+    }
+
+    fn is_symlink(path: &Path) -> bool {
+        fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_type().is_symlink())
+            .unwrap_or(false)
     }
 
     /// Read file content as string
     pub fn read_file(&self, path: &str) -> Result<FileContent, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.read_provided(&provided);
+        }
+        let resolved = self.authorize(path)?;
+
         let file_path = Path::new(path);
 
         if !file_path.exists() {
@@ -45,6 +581,10 @@ impl FileSystemService {
             return Err(FileSystemError::InvalidPath);
         }
 
+        if !self.get_config().follow_symlinks && Self::is_symlink(file_path) {
+            return Err(FileSystemError::InvalidPath);
+        }
+
         // Check if file is binary
         let is_binary = self.is_binary_file(file_path)?;
 
@@ -72,6 +612,8 @@ impl FileSystemService {
         let metadata = file_path.metadata()
             .map_err(|e| FileSystemError::IOError(e.to_string()))?;
 
+        self.disk_versions.lock().unwrap().insert(resolved, DiskVersion::new(&metadata, content.as_bytes()));
+
         Ok(FileContent {
             path: path.to_string(),
             content,
@@ -81,12 +623,65 @@ impl FileSystemService {
         })
     }
 
+    /// Read `length` bytes from `offset` as hex and ASCII rows, for viewing binaries
+    pub fn read_file_hex(&self, path: &str, offset: u64, length: u64) -> Result<HexDump, FileSystemError> {
+        self.authorize(path)?;
+
+        let file_path = Path::new(path);
+
+        if !self.get_config().follow_symlinks && Self::is_symlink(file_path) {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let mut file = File::open(file_path)
+            .map_err(map_io_error)?;
+        let metadata = file.metadata()
+            .map_err(map_io_error)?;
+        if !metadata.is_file() {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let mut buffer = Vec::new();
+        file.seek(SeekFrom::Start(offset))
+            .map_err(map_io_error)?;
+        file.take(length.min(MAX_HEX_DUMP_LENGTH))
+            .read_to_end(&mut buffer)
+            .map_err(map_io_error)?;
+
+        let rows = buffer
+            .chunks(HEX_BYTES_PER_ROW)
+            .enumerate()
+            .map(|(index, chunk)| HexRow {
+                offset: offset + (index * HEX_BYTES_PER_ROW) as u64,
+                hex: chunk.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" "),
+                ascii: chunk
+                    .iter()
+                    .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(HexDump {
+            path: path.to_string(),
+            offset,
+            length: buffer.len() as u64,
+            total_size: metadata.len(),
+            bytes_per_row: HEX_BYTES_PER_ROW,
+            rows,
+        })
+    }
+
     /// Write content to file
     pub fn write_file(&self, path: &str, content: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.write_provided(&provided, content);
+        }
+        self.authorize(path)?;
+
         let file_path = Path::new(path);
 
         // Create parent directories if they don't exist
-        if self.config.create_parent_dirs {
+        if self.get_config().create_parent_dirs {
             if let Some(parent) = file_path.parent() {
                 fs::create_dir_all(parent)
                     .map_err(|e| FileSystemError::IOError(e.to_string()))?;
@@ -94,7 +689,7 @@ impl FileSystemService {
         }
 
         // Check if file exists and we're not allowed to overwrite
-        if file_path.exists() && !self.config.overwrite {
+        if file_path.exists() && !self.get_config().overwrite {
             return Err(FileSystemError::AlreadyExists);
         }
 
@@ -122,8 +717,114 @@ impl FileSystemService {
         })
     }
 
-    /// Create a new file
-    pub fn create_file(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+    /// Save editor content over a file, refusing with `ConflictingChange` when the file changed on disk
+    /// since it was last read or saved here. `force` skips the check, e.g. once the user chose to
+    /// overwrite.
+    pub fn save_file(
+        &self,
+        path: &str,
+        content: &str,
+        force: bool,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.save_provided(&provided, content, force);
+        }
+        let resolved = self.authorize(path)?;
+
+        let file_path = Path::new(path);
+
+        if !force {
+            self.check_unchanged(&resolved)?;
+        }
+
+        if self.get_config().create_parent_dirs {
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+            }
+        }
+
+        fs::write(file_path, content)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+                _ => FileSystemError::IOError(e.to_string()),
+            })?;
+
+        let metadata = fs::metadata(file_path)
+            .map_err(map_io_error)?;
+        self.disk_versions.lock().unwrap().insert(resolved, DiskVersion::new(&metadata, content.as_bytes()));
+
+        Ok(FileOperationResult {
+            success: true,
+            message: "File saved successfully".to_string(),
+            path: Some(path.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// Whether a file is as it was when last read or saved here; false for files never read here
+    pub(crate) fn matches_known_version(&self, resolved: &Path) -> bool {
+        let mut versions = self.disk_versions.lock().unwrap();
+        let Some(known) = versions.get_mut(resolved) else {
+            return false;
+        };
+        let Ok(metadata) = fs::metadata(resolved) else {
+            return false;
+        };
+        if metadata.modified().ok() == known.modified && metadata.len() == known.size {
+            return true;
+        }
+        let Ok(content) = fs::read(resolved) else {
+            return false;
+        };
+        let current = DiskVersion::new(&metadata, &content);
+        let matches = current.hash == known.hash;
+        if matches {
+            *known = current;
+        }
+        matches
+    }
+
+    /// Compare a file with the version last read or saved; files never read here, and files deleted
+    /// since, have nothing to conflict with
+    fn check_unchanged(&self, resolved: &Path) -> Result<(), FileSystemError> {
+        let mut versions = self.disk_versions.lock().unwrap();
+        let Some(known) = versions.get_mut(resolved) else {
+            return Ok(());
+        };
+        let Ok(metadata) = fs::metadata(resolved) else {
+            return Ok(());
+        };
+        if metadata.modified().ok() == known.modified && metadata.len() == known.size {
+            return Ok(());
+        }
+
+        // A touched or identically rewritten file isn't a conflict
+        let disk_content = fs::read(resolved)
+            .map_err(map_io_error)?;
+        let current = DiskVersion::new(&metadata, &disk_content);
+        if current.hash == known.hash {
+            *known = current;
+            return Ok(());
+        }
+
+        Err(FileSystemError::ConflictingChange {
+            disk_content: String::from_utf8_lossy(&disk_content).to_string(),
+            modified: metadata.modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or(0),
+        })
+    }
+
+    /// Create a new file with initial content, e.g. from a file template
+    pub fn create_file(&self, path: &str, content: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.create_file_provided(&provided, content);
+        }
+        self.authorize(path)?;
+
         let file_path = Path::new(path);
 
         if file_path.exists() {
@@ -136,11 +837,13 @@ impl FileSystemService {
                 .map_err(|e| FileSystemError::IOError(e.to_string()))?;
         }
 
-        File::create(file_path)
+        let mut file = File::create(file_path)
             .map_err(|e| match e.kind() {
                 io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
                 _ => FileSystemError::IOError(e.to_string()),
             })?;
+        file.write_all(content.as_bytes())
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
 
         Ok(FileOperationResult {
             success: true,
@@ -152,6 +855,11 @@ impl FileSystemService {
 
     /// Create a new directory
     pub fn create_directory(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.create_directory_provided(&provided);
+        }
+        self.authorize(path)?;
+
         let dir_path = Path::new(path);
 
         if dir_path.exists() {
@@ -172,19 +880,23 @@ impl FileSystemService {
         })
     }
 
-    /// Delete a file
+    /// Delete a file; a symlink is removed itself, whatever it points to
     pub fn delete_file(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.delete_provided(&provided, false);
+        }
+        self.authorize_link(path)?;
+
         let file_path = Path::new(path);
 
-        if !file_path.exists() {
-            return Err(FileSystemError::NotFound);
-        }
+        let metadata = fs::symlink_metadata(file_path)
+            .map_err(map_io_error)?;
 
-        if !file_path.is_file() {
+        if metadata.is_dir() {
             return Err(FileSystemError::InvalidPath);
         }
 
-        fs::remove_file(file_path)
+        remove_symlink(file_path)
             .map_err(|e| match e.kind() {
                 io::ErrorKind::NotFound => FileSystemError::NotFound,
                 io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
@@ -200,14 +912,34 @@ impl FileSystemService {
     }
 
     /// Delete a directory
+    ///
+    /// Symlinks are never deleted through: a link to a directory is removed as a link, and links
+    /// inside the directory are removed without touching their targets.
     pub fn delete_directory(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.delete_provided(&provided, true);
+        }
+        self.authorize_link(path)?;
+
         let dir_path = Path::new(path);
 
-        if !dir_path.exists() {
-            return Err(FileSystemError::NotFound);
+        let metadata = fs::symlink_metadata(dir_path)
+            .map_err(map_io_error)?;
+
+        if metadata.file_type().is_symlink() {
+            if !dir_path.is_dir() {
+                return Err(FileSystemError::InvalidPath);
+            }
+            remove_symlink(dir_path).map_err(map_io_error)?;
+            return Ok(FileOperationResult {
+                success: true,
+                message: "Directory link deleted successfully".to_string(),
+                path: Some(path.to_string()),
+                error_code: None,
+            });
         }
 
-        if !dir_path.is_dir() {
+        if !metadata.is_dir() {
             return Err(FileSystemError::InvalidPath);
         }
 
@@ -226,16 +958,74 @@ impl FileSystemService {
         })
     }
 
+    /// Move a file or directory to the OS trash so it can be restored
+    pub fn move_to_trash(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        self.authorize_link(path)?;
+
+        if fs::symlink_metadata(path).is_err() {
+            return Err(FileSystemError::NotFound);
+        }
+
+        trash::delete(path)
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: "Moved to trash successfully".to_string(),
+            path: Some(path.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// Put the most recently trashed entry that came from `path` back where it was
+    #[cfg(not(target_os = "macos"))]
+    pub fn restore_from_trash(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        self.authorize_link(path)?;
+
+        if fs::symlink_metadata(path).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+
+        let item = trash::os_limited::list()
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?
+            .into_iter()
+            .filter(|item| item.original_path() == Path::new(path))
+            .max_by_key(|item| item.time_deleted)
+            .ok_or(FileSystemError::NotFound)?;
+        trash::os_limited::restore_all([item])
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: "Restored from trash successfully".to_string(),
+            path: Some(path.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// The macOS Trash can't be read back without Finder, so nothing can be restored from it
+    #[cfg(target_os = "macos")]
+    pub fn restore_from_trash(&self, path: &str) -> Result<FileOperationResult, FileSystemError> {
+        self.authorize_link(path)?;
+        Err(FileSystemError::Unsupported("restoring from the Trash on macOS".to_string()))
+    }
+
     /// Rename a file or directory
     pub fn rename(&self, old_path: &str, new_path: &str) -> Result<FileOperationResult, FileSystemError> {
+        if let (Some(old), Some(new)) = (self.provided(old_path)?, self.provided(new_path)?) {
+            return self.rename_provided(&old, &new);
+        }
+        self.authorize_link(old_path)?;
+        self.authorize_link(new_path)?;
+
         let old = Path::new(old_path);
         let new = Path::new(new_path);
 
-        if !old.exists() {
+        if fs::symlink_metadata(old).is_err() {
             return Err(FileSystemError::NotFound);
         }
 
-        if new.exists() && !self.config.overwrite {
+        if fs::symlink_metadata(new).is_ok() && !self.get_config().overwrite {
             return Err(FileSystemError::AlreadyExists);
         }
 
@@ -254,20 +1044,28 @@ impl FileSystemService {
         })
     }
 
-    /// Copy a file
+    /// Copy a file; without `follow_symlinks` a symlink is copied as a link to the same target
     pub fn copy_file(&self, source: &str, destination: &str) -> Result<FileOperationResult, FileSystemError> {
+        let copy_link = !self.get_config().follow_symlinks && Self::is_symlink(Path::new(source));
+        if copy_link {
+            self.authorize_link(source)?;
+        } else {
+            self.authorize(source)?;
+        }
+        self.authorize_link(destination)?;
+
         let src = Path::new(source);
         let dst = Path::new(destination);
 
-        if !src.exists() {
+        if !copy_link && !src.exists() {
             return Err(FileSystemError::NotFound);
         }
 
-        if !src.is_file() {
+        if !copy_link && !src.is_file() {
             return Err(FileSystemError::InvalidPath);
         }
 
-        if dst.exists() && !self.config.overwrite {
+        if fs::symlink_metadata(dst).is_ok() && !self.get_config().overwrite {
             return Err(FileSystemError::AlreadyExists);
         }
 
@@ -277,6 +1075,21 @@ impl FileSystemService {
                 .map_err(|e| FileSystemError::IOError(e.to_string()))?;
         }
 
+        // Replace an existing link rather than writing through it to wherever it points
+        if Self::is_symlink(dst) {
+            remove_symlink(dst).map_err(map_io_error)?;
+        }
+
+        if copy_link {
+            copy_symlink(src, dst)?;
+            return Ok(FileOperationResult {
+                success: true,
+                message: "Link copied successfully".to_string(),
+                path: Some(destination.to_string()),
+                error_code: None,
+            });
+        }
+
         fs::copy(src, dst)
             .map_err(|e| match e.kind() {
                 io::ErrorKind::NotFound => FileSystemError::NotFound,
@@ -292,16 +1105,168 @@ impl FileSystemService {
         })
     }
 
-    /// Get file or directory metadata
-    pub fn get_metadata(&self, path: &str) -> Result<FileMetadata, FileSystemError> {
-        let file_path = Path::new(path);
+    /// Copy a directory tree
+    ///
+    /// With `follow_symlinks` linked directories are copied as real directories, skipping any link
+    /// that leads back into a directory being copied; otherwise links are recreated as links.
+    pub fn copy_directory(
+        &self,
+        source: &str,
+        destination: &str,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let src_root = self.authorize(source)?;
+        let dst_root = self.authorize_link(destination)?;
 
-        if !file_path.exists() {
-            return Err(FileSystemError::NotFound);
+        let src = Path::new(source);
+        let dst = Path::new(destination);
+
+        if !src.is_dir() {
+            return Err(if src.exists() { FileSystemError::InvalidPath } else { FileSystemError::NotFound });
         }
 
-        let metadata = file_path.metadata()
-            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        if dst_root.starts_with(&src_root) {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let config = self.get_config();
+        if fs::symlink_metadata(dst).is_ok() && !config.overwrite {
+            return Err(FileSystemError::AlreadyExists);
+        }
+
+        let existed = dst.exists();
+        let mut ancestors = Vec::new();
+        let skipped_loops = self.copy_tree(src, dst, config.follow_symlinks, &mut ancestors)
+            .inspect_err(|_| {
+                // Don't leave a half-copied tree behind
+                if !existed {
+                    let _ = fs::remove_dir_all(dst);
+                }
+            })?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: if skipped_loops > 0 {
+                format!("Directory copied successfully; skipped {} symlink loop(s)", skipped_loops)
+            } else {
+                "Directory copied successfully".to_string()
+            },
+            path: Some(destination.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// Copy `src` into `dst`, returning how many symlink loops were skipped
+    fn copy_tree(
+        &self,
+        src: &Path,
+        dst: &Path,
+        follow_symlinks: bool,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> Result<usize, FileSystemError> {
+        let canonical = src.canonicalize()
+            .map_err(map_io_error)?;
+        if ancestors.contains(&canonical) {
+            return Ok(1);
+        }
+
+        fs::create_dir_all(dst)
+            .map_err(map_io_error)?;
+
+        ancestors.push(canonical);
+        let mut skipped_loops = 0;
+        for entry in fs::read_dir(src).map_err(map_io_error)? {
+            let entry = entry.map_err(map_io_error)?;
+            let path = entry.path();
+            let target = dst.join(entry.file_name());
+            let file_type = entry.file_type()
+                .map_err(map_io_error)?;
+
+            if file_type.is_symlink() && !follow_symlinks {
+                copy_symlink(&path, &target)?;
+            } else if file_type.is_symlink() && fs::metadata(&path).is_err() {
+                // Nothing to follow; keep the dangling link as it is
+                copy_symlink(&path, &target)?;
+            } else if path.is_dir() {
+                // Followed links must lead somewhere inside the sandbox as well
+                self.authorize(&path.to_string_lossy())?;
+                skipped_loops += self.copy_tree(&path, &target, follow_symlinks, ancestors)?;
+            } else {
+                self.authorize(&path.to_string_lossy())?;
+                fs::copy(&path, &target)
+                    .map_err(map_io_error)?;
+            }
+        }
+        ancestors.pop();
+        Ok(skipped_loops)
+    }
+
+    /// Create a symlink at `link_path` pointing to `target`; relative targets are relative to the link
+    pub fn create_symlink(&self, target: &str, link_path: &str) -> Result<FileOperationResult, FileSystemError> {
+        self.authorize_link(link_path)?;
+
+        let link = Path::new(link_path);
+        let target_path = Path::new(target);
+        let resolved_target = match link.parent() {
+            Some(parent) if target_path.is_relative() => parent.join(target_path),
+            _ => target_path.to_path_buf(),
+        };
+        // Links may not point outside the sandbox, even if the target doesn't exist yet
+        self.authorize(&resolved_target.to_string_lossy())?;
+
+        if fs::symlink_metadata(link).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+
+        make_symlink(target_path, link, resolved_target.is_dir())
+            .map_err(map_io_error)?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: "Symlink created successfully".to_string(),
+            path: Some(link_path.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// Where a symlink points, exactly as stored in the link
+    pub fn read_link_target(&self, path: &str) -> Result<String, FileSystemError> {
+        self.authorize_link(path)?;
+
+        let link = Path::new(path);
+
+        if !Self::is_symlink(link) {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        fs::read_link(link)
+            .map(|target| target.to_string_lossy().to_string())
+            .map_err(map_io_error)
+    }
+
+    /// Get file or directory metadata; with `follow_symlinks` a link reports its target's size and type
+    pub fn get_metadata(&self, path: &str) -> Result<FileMetadata, FileSystemError> {
+        if let Some(provided) = self.provided(path)? {
+            return self.metadata_provided(&provided);
+        }
+        self.authorize_link(path)?;
+
+        let file_path = Path::new(path);
+
+        let link_metadata = fs::symlink_metadata(file_path)
+            .map_err(map_io_error)?;
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let symlink_target = if is_symlink {
+            fs::read_link(file_path).ok().map(|target| target.to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        // A dangling link has no target to describe, so it describes itself
+        let metadata = if is_symlink && self.get_config().follow_symlinks {
+            fs::metadata(file_path).unwrap_or(link_metadata)
+        } else {
+            link_metadata
+        };
 
         let created = metadata.created().ok()
             .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
@@ -319,6 +1284,14 @@ impl FileSystemService {
             .and_then(|ext| ext.to_str())
             .map(|s| s.to_string());
 
+        let (owner, group) = self.get_owner(&metadata);
+
+        let mime_type = if metadata.is_file() {
+            file_type::detect_file_type(file_path).ok().map(|file_type| file_type.mime_type)
+        } else {
+            None
+        };
+
         Ok(FileMetadata {
             path: path.to_string(),
             name: file_path.file_name()
@@ -328,20 +1301,135 @@ impl FileSystemService {
             size: metadata.len(),
             is_directory: metadata.is_dir(),
             is_file: metadata.is_file(),
-            is_symlink: metadata.is_symlink(),
+            is_symlink,
+            symlink_target,
             readonly: metadata.permissions().readonly(),
             hidden: self.is_hidden(file_path),
+            owner,
+            group,
             created,
             modified,
             accessed,
             permissions: format!("{:o}", self.get_permissions(&metadata)),
             extension,
-            mime_type: self.get_mime_type(&extension),
+            mime_type,
+        })
+    }
+
+    /// List directory contents, sorted and filtered, with files grouped by `nesting` when given
+    pub fn list_directory(
+        &self,
+        path: &str,
+        include_hidden: bool,
+        sort: DirectorySort,
+        filter: &DirectoryFilter,
+        nesting: Option<&FileNesting>,
+    ) -> Result<DirectoryListing, FileSystemError> {
+        let snapshot = match self.provided(path)? {
+            Some(provided) => self.snapshot_provided(path, &provided, include_hidden, sort, filter, nesting)?,
+            None => {
+                self.authorize(path)?;
+                self.snapshot_directory(path, include_hidden, sort, filter, nesting)?
+            }
+        };
+        let directory_entries = self.directory_entries(&snapshot.entries, snapshot.provided.as_deref())?;
+
+        Ok(DirectoryListing {
+            path: path.to_string(),
+            total_count: directory_entries.len(),
+            entries: directory_entries,
+            hidden_count: snapshot.hidden_count,
+            filtered_count: snapshot.filtered_count,
+            error: None,
+        })
+    }
+
+    /// List a directory a page at a time. The first call (without a cursor) takes a sorted snapshot of the
+    /// names; later pages come from that snapshot, so sorting, filtering, and nesting only apply to the first
+    /// call. Nested files come with their parent and don't count towards the page size.
+    #[allow(clippy::too_many_arguments)]
+    pub fn list_directory_page(
+        &self,
+        path: &str,
+        cursor: Option<&str>,
+        page_size: usize,
+        sort: DirectorySort,
+        filter: &DirectoryFilter,
+        include_hidden: bool,
+        nesting: Option<&FileNesting>,
+    ) -> Result<DirectoryPage, FileSystemError> {
+        let provided = self.provided(path)?;
+        if provided.is_none() {
+            self.authorize(path)?;
+        }
+
+        let expired = || FileSystemError::UnknownError(
+            "Directory listing cursor has expired; list the directory again".to_string()
+        );
+        let (id, offset) = match cursor {
+            Some(cursor) => cursor.split_once(':')
+                .and_then(|(id, offset)| Some((id.parse::<u64>().ok()?, offset.parse::<usize>().ok()?)))
+                .ok_or_else(expired)?,
+            None => {
+                let snapshot = match &provided {
+                    Some(provided) => {
+                        self.snapshot_provided(path, provided, include_hidden, sort, filter, nesting)?
+                    }
+                    None => self.snapshot_directory(path, include_hidden, sort, filter, nesting)?,
+                };
+                let id = self.next_listing_id.fetch_add(1, Ordering::SeqCst);
+                let mut listings = self.listings.lock().unwrap();
+                listings.push_back((id, snapshot));
+                if listings.len() > MAX_OPEN_LISTINGS {
+                    listings.pop_front();
+                }
+                (id, 0)
+            }
+        };
+
+        let mut listings = self.listings.lock().unwrap();
+        let index = listings.iter()
+            .position(|(listing_id, _)| *listing_id == id)
+            .ok_or_else(expired)?;
+        let snapshot = &listings[index].1;
+        if snapshot.path != path {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let total_count = snapshot.entries.len();
+        let hidden_count = snapshot.hidden_count;
+        let filtered_count = snapshot.filtered_count;
+        let start = offset.min(total_count);
+        let end = (start + page_size.clamp(1, MAX_PAGE_SIZE)).min(total_count);
+        let listed = snapshot.entries[start..end].to_vec();
+        let provided_entries = snapshot.provided.clone();
+        let next_cursor = if end < total_count {
+            Some(format!("{}:{}", id, end))
+        } else {
+            listings.remove(index);
+            None
+        };
+        drop(listings);
+
+        Ok(DirectoryPage {
+            path: path.to_string(),
+            entries: self.directory_entries(&listed, provided_entries.as_deref())?,
+            cursor: next_cursor,
+            total_count,
+            hidden_count,
+            filtered_count,
         })
     }
 
-    /// List directory contents
-    pub fn list_directory(&self, path: &str, include_hidden: bool) -> Result<DirectoryListing, FileSystemError> {
+    /// Names of a directory's entries in listing order, with only the metadata the sort needs
+    fn snapshot_directory(
+        &self,
+        path: &str,
+        include_hidden: bool,
+        sort: DirectorySort,
+        filter: &DirectoryFilter,
+        nesting: Option<&FileNesting>,
+    ) -> Result<ListingSnapshot, FileSystemError> {
         let dir_path = Path::new(path);
 
         if !dir_path.exists() {
@@ -353,65 +1441,135 @@ impl FileSystemService {
         }
 
         let entries = fs::read_dir(dir_path)
-            .map_err(|e| match e.kind() {
-                io::ErrorKind::NotFound => FileSystemError::NotFound,
-                io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
-                _ => FileSystemError::IOError(e.to_string()),
-            })?;
+            .map_err(map_io_error)?;
 
-        let mut directory_entries = Vec::new();
+        let matcher = NameFilter::new(filter)?;
+        let needs_metadata = matches!(sort.key, DirectorySortKey::Size | DirectorySortKey::Modified);
+        let mut items = Vec::new();
         let mut hidden_count = 0;
+        let mut filtered_count = 0;
 
         for entry in entries {
             let entry = entry.map_err(|e| FileSystemError::IOError(e.to_string()))?;
             let entry_path = entry.path();
 
-            let is_hidden = self.is_hidden(&entry_path);
-            if is_hidden {
+            if self.is_hidden(&entry_path) {
                 hidden_count += 1;
                 if !include_hidden {
                     continue;
                 }
             }
 
-            let metadata = entry.metadata()
+            let file_type = entry.file_type()
                 .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+            let follow = file_type.is_symlink() && self.get_config().follow_symlinks;
+            let metadata = if needs_metadata || follow {
+                let metadata = if follow { fs::metadata(&entry_path) } else { entry.metadata() };
+                metadata.or_else(|_| entry.metadata()).ok()
+            } else {
+                None
+            };
+
+            let is_directory = metadata.as_ref().map_or(file_type.is_dir(), |metadata| metadata.is_dir());
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if !matcher.matches(&name, is_directory) {
+                filtered_count += 1;
+                continue;
+            }
 
-            let name = entry.file_name()
-                .to_str()
-                .unwrap_or("")
-                .to_string();
-
-            let modified = metadata.modified().ok()
-                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                .map(|d| d.as_secs());
-
-            directory_entries.push(DirectoryEntry {
-                name: name.clone(),
-                path: entry_path.to_str().unwrap_or("").to_string(),
-                is_directory: metadata.is_dir(),
-                size: if metadata.is_file() { Some(metadata.len()) } else { None },
-                modified,
-                permissions: format!("{:o}", self.get_permissions(&metadata)),
-                icon: self.get_file_icon(&name, metadata.is_dir()),
+            items.push(SortItem {
+                extension: Path::new(&name).extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                is_directory,
+                size: metadata.as_ref()
+                    .filter(|metadata| metadata.is_file())
+                    .map_or(0, |metadata| metadata.len()),
+                modified: metadata.as_ref()
+                    .and_then(|metadata| metadata.modified().ok())
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map_or(0, |d| d.as_secs()),
+                name,
+                path: entry_path,
             });
         }
 
-        // Sort entries: directories first, then files, alphabetically
-        directory_entries.sort_by(|a, b| {
-            match (a.is_directory, b.is_directory) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            }
-        });
+        let entries = arrange(items, sort, nesting);
 
-        Ok(DirectoryListing {
+        Ok(ListingSnapshot {
             path: path.to_string(),
-            entries: directory_entries,
-            total_count: directory_entries.len(),
+            entries,
             hidden_count,
-            error: None,
+            filtered_count,
+            provided: None,
+        })
+    }
+
+    /// Explorer entries for listed paths, skipping any that disappeared since they were listed. Entries of
+    /// a provider listing come from `provided` rather than the disk.
+    fn directory_entries(
+        &self,
+        listed: &[ListedPath],
+        provided: Option<&HashMap<PathBuf, DirectoryEntry>>,
+    ) -> Result<Vec<DirectoryEntry>, FileSystemError> {
+        let directory_entry = |path: &Path| match provided {
+            Some(provided) => provided.get(path).cloned().ok_or(FileSystemError::NotFound),
+            None => self.directory_entry(path),
+        };
+        let mut directory_entries = Vec::with_capacity(listed.len());
+        for listed_path in listed {
+            let mut entry = match directory_entry(&listed_path.path) {
+                Ok(entry) => entry,
+                Err(FileSystemError::NotFound) => continue,
+                Err(e) => return Err(e),
+            };
+            for nested_path in &listed_path.nested {
+                match directory_entry(nested_path) {
+                    Ok(nested) => entry.children_nested.push(nested),
+                    Err(FileSystemError::NotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            directory_entries.push(entry);
+        }
+        Ok(directory_entries)
+    }
+
+    fn directory_entry(&self, entry_path: &Path) -> Result<DirectoryEntry, FileSystemError> {
+        let link_metadata = fs::symlink_metadata(entry_path)
+            .map_err(map_io_error)?;
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let symlink_target = if is_symlink {
+            fs::read_link(entry_path).ok().map(|target| target.to_string_lossy().to_string())
+        } else {
+            None
+        };
+        let metadata = if is_symlink && self.get_config().follow_symlinks {
+            fs::metadata(entry_path).unwrap_or(link_metadata)
+        } else {
+            link_metadata
+        };
+
+        let name = entry_path.file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let modified = metadata.modified().ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+
+        Ok(DirectoryEntry {
+            icon: self.get_file_icon(&name, metadata.is_dir()),
+            name,
+            path: entry_path.to_str().unwrap_or("").to_string(),
+            is_directory: metadata.is_dir(),
+            is_symlink,
+            symlink_target,
+            size: if metadata.is_file() { Some(metadata.len()) } else { None },
+            modified,
+            permissions: format!("{:o}", self.get_permissions(&metadata)),
+            children_nested: Vec::new(),
         })
     }
 
@@ -428,14 +1586,44 @@ impl FileSystemService {
         Ok(buffer[..bytes_read].contains(&0))
     }
 
-    /// Check if file/directory is hidden
+    /// Check if file/directory is hidden: a dot name, or the hidden attribute on Windows
     fn is_hidden(&self, path: &Path) -> bool {
-        path.file_name()
+        let dot_name = path.file_name()
             .and_then(|name| name.to_str())
             .map(|name| name.starts_with('.'))
+            .unwrap_or(false);
+        dot_name || self.has_hidden_attribute(path)
+    }
+
+    #[cfg(windows)]
+    fn has_hidden_attribute(&self, path: &Path) -> bool {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
             .unwrap_or(false)
     }
 
+    #[cfg(not(windows))]
+    fn has_hidden_attribute(&self, _path: &Path) -> bool {
+        false
+    }
+
+    /// Owner and group names, falling back to the numeric ids when they have no entry
+    #[cfg(unix)]
+    fn get_owner(&self, metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+        use std::os::unix::fs::MetadataExt;
+        let owner = lookup_id_name("/etc/passwd", metadata.uid()).unwrap_or_else(|| metadata.uid().to_string());
+        let group = lookup_id_name("/etc/group", metadata.gid()).unwrap_or_else(|| metadata.gid().to_string());
+        (Some(owner), Some(group))
+    }
+
+    #[cfg(windows)]
+    fn get_owner(&self, _metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+        // Windows ownership is an ACL matter with no simple owner/group pair
+        (None, None)
+    }
+
     /// Get file permissions as octal number
     #[cfg(unix)]
     fn get_permissions(&self, metadata: &fs::Metadata) -> u32 {
@@ -449,62 +1637,456 @@ impl FileSystemService {
         0o644
     }
 
-    /// Get MIME type based on file extension
-    fn get_mime_type(&self, extension: &Option<String>) -> Option<String> {
-        match extension.as_deref() {
-            Some("txt") => Some("text/plain".to_string()),
-            Some("html") => Some("text/html".to_string()),
-            Some("css") => Some("text/css".to_string()),
-            Some("js") => Some("application/javascript".to_string()),
-            Some("json") => Some("application/json".to_string()),
-            Some("xml") => Some("application/xml".to_string()),
-            Some("pdf") => Some("application/pdf".to_string()),
-            Some("png") => Some("image/png".to_string()),
-            Some("jpg") | Some("jpeg") => Some("image/jpeg".to_string()),
-            Some("gif") => Some("image/gif".to_string()),
-            Some("svg") => Some("image/svg+xml".to_string()),
-            Some("mp4") => Some("video/mp4".to_string()),
-            Some("mp3") => Some("audio/mpeg".to_string()),
-            _ => None,
+    /// Change Unix permission bits, given in octal such as `755` or `0644`
+    #[cfg(unix)]
+    pub fn set_permissions(&self, path: &str, mode: &str) -> Result<FileMetadata, FileSystemError> {
+        use std::os::unix::fs::PermissionsExt;
+        self.authorize(path)?;
+
+        let bits = u32::from_str_radix(mode.trim(), 8)
+            .ok()
+            .filter(|bits| *bits <= 0o7777)
+            .ok_or_else(|| FileSystemError::UnknownError(format!(
+                "Invalid permission mode '{}', expected octal such as 755", mode
+            )))?;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(bits))
+            .map_err(map_io_error)?;
+
+        self.get_metadata(path)
+    }
+
+    #[cfg(windows)]
+    pub fn set_permissions(&self, path: &str, _mode: &str) -> Result<FileMetadata, FileSystemError> {
+        self.authorize(path)?;
+        Err(FileSystemError::Unsupported("Unix permissions are not available on Windows".to_string()))
+    }
+
+    /// Make a file read-only, or writable again by its owner
+    pub fn set_readonly(&self, path: &str, readonly: bool) -> Result<FileMetadata, FileSystemError> {
+        self.authorize(path)?;
+
+        let mut permissions = fs::metadata(path)
+            .map_err(map_io_error)?
+            .permissions();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            // Only the owner gets write access back; `set_readonly(false)` would make it world-writable
+            let mode = permissions.mode();
+            permissions.set_mode(if readonly { mode & !0o222 } else { mode | 0o200 });
         }
+
+        #[cfg(windows)]
+        permissions.set_readonly(readonly);
+
+        fs::set_permissions(path, permissions)
+            .map_err(map_io_error)?;
+
+        self.get_metadata(path)
     }
 
     /// Get appropriate icon for file type
     fn get_file_icon(&self, name: &str, is_directory: bool) -> String {
-        if is_directory {
-            return "folder".to_string();
+        self.icons.lock().unwrap().icon_for(name, is_directory)
+    }
+
+    /// Reload the icon map with the user's overrides from `config_dir`
+    pub fn load_icon_map(&self, config_dir: Option<&Path>) -> Result<FileIconMap, FileSystemError> {
+        let map = FileIconMap::load(config_dir)?;
+        *self.icons.lock().unwrap() = map.clone();
+        Ok(map)
+    }
+
+    /// Set configuration for file operations; applies to operations started afterwards
+    pub fn set_config(&self, config: FileOperationConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    /// Get current configuration
+    pub fn get_config(&self) -> FileOperationConfig {
+        self.config.lock().unwrap().clone()
+    }
+}
+
+/// Operations on URI paths, through the provider of the machine they are on. They follow the local
+/// operations of the same name, minus what providers can't tell: symlink policies, owners, and the trash.
+impl FileSystemService {
+    fn add_remote_root(&self, uri: RemoteUri) -> Result<FileSystemScope, FileSystemError> {
+        let origin = uri.origin();
+        let connected = self.providers.lock().unwrap().get(&origin).cloned();
+        let provider = match &connected {
+            Some(provider) => provider.clone(),
+            None => self.open_provider(&uri)?,
+        };
+        let is_directory = provider.stat(&uri.path).map(|stat| stat.kind == EntryKind::Directory);
+        if !matches!(is_directory, Ok(true)) {
+            if connected.is_none() {
+                provider.disconnect();
+            }
+            return Err(is_directory.err().unwrap_or(FileSystemError::InvalidPath));
         }
+        self.providers.lock().unwrap().entry(origin).or_insert(provider);
 
-        let extension = Path::new(name)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
+        let mut scope = self.scope.lock().unwrap();
+        let root = uri.to_string();
+        if !scope.remote_roots.contains(&root) {
+            scope.remote_roots.push(root);
+        }
+        Ok(scope.clone())
+    }
 
-        match extension {
-            "rs" => "rust".to_string(),
-            "js" | "jsx" => "javascript".to_string(),
-            "ts" | "tsx" => "typescript".to_string(),
-            "py" => "python".to_string(),
-            "html" => "html".to_string(),
-            "css" => "css".to_string(),
-            "json" => "json".to_string(),
-            "md" => "markdown".to_string(),
-            "txt" => "text".to_string(),
-            "png" | "jpg" | "jpeg" | "gif" | "svg" => "image".to_string(),
-            "pdf" => "pdf".to_string(),
-            "zip" | "tar" | "gz" => "archive".to_string(),
-            _ => "file".to_string(),
+    fn open_provider(&self, uri: &RemoteUri) -> Result<Arc<dyn FileSystemProvider>, FileSystemError> {
+        let open = self.schemes.lock().unwrap().get(&uri.scheme).cloned();
+        match open {
+            Some(open) => open(uri),
+            None => fs_provider::connect(uri),
         }
     }
 
-    /// Set configuration for file operations
-    pub fn set_config(&mut self, config: FileOperationConfig) {
-        self.config = config;
+    /// Serve URIs with `scheme` through the providers `open` returns, one per authority, replacing what
+    /// served them before
+    pub fn register_scheme(&self, scheme: &str, open: OpenProvider) {
+        self.schemes.lock().unwrap().insert(scheme.to_string(), open);
+        self.disconnect_scheme(scheme);
     }
 
-    /// Get current configuration
-    pub fn get_config(&self) -> &FileOperationConfig {
-        &self.config
+    /// Stop serving URIs with `scheme`; open workspaces with it fail until it is registered again
+    pub fn unregister_scheme(&self, scheme: &str) {
+        self.schemes.lock().unwrap().remove(scheme);
+        self.disconnect_scheme(scheme);
+    }
+
+    fn disconnect_scheme(&self, scheme: &str) {
+        let prefix = format!("{}://", scheme);
+        let mut providers = self.providers.lock().unwrap();
+        let origins: Vec<String> =
+            providers.keys().filter(|origin| origin.starts_with(&prefix)).cloned().collect();
+        for origin in origins {
+            if let Some(provider) = providers.remove(&origin) {
+                provider.disconnect();
+            }
+        }
+    }
+
+    /// Forget a URI root, disconnecting from its machine when no other open workspace is there
+    fn remove_remote_root(&self, uri: &RemoteUri) -> FileSystemScope {
+        let origin = uri.origin();
+        let mut scope = self.scope.lock().unwrap();
+        let root = uri.to_string();
+        scope.remote_roots.retain(|existing| existing != &root);
+        let still_used = scope
+            .remote_roots
+            .iter()
+            .filter_map(|root| RemoteUri::parse(root))
+            .any(|root| root.origin() == origin);
+        let scope = scope.clone();
+        if !still_used {
+            let provider = self.providers.lock().unwrap().remove(&origin);
+            if let Some(provider) = provider {
+                provider.disconnect();
+            }
+        }
+        scope
+    }
+
+    fn watch_provided(
+        &self,
+        app: &AppHandle,
+        provided: ProvidedPath,
+        recursive: bool,
+        kind: WatchKind,
+    ) -> Result<WatchStatus, FileSystemError> {
+        let id = format!("watch-{}", self.next_watch_id.fetch_add(1, Ordering::SeqCst));
+        let watch = fs_watch::watch_provided(app, id.clone(), provided.provider, provided.uri, recursive, kind)?;
+        let status = watch.status();
+        self.watchers.lock().unwrap().insert(id, watch);
+        Ok(status)
+    }
+
+    fn read_provided(&self, provided: &ProvidedPath) -> Result<FileContent, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        let stat = provider.stat(&uri.path)?;
+        if stat.kind != EntryKind::File {
+            return Err(FileSystemError::InvalidPath);
+        }
+        let bytes = provider.read(&uri.path)?;
+        let path = uri.to_string();
+        // The same test as `is_binary_file`
+        if bytes.iter().take(8192).any(|byte| *byte == 0) {
+            return Ok(FileContent {
+                path,
+                content: String::new(),
+                encoding: "binary".to_string(),
+                size: bytes.len() as u64,
+                is_binary: true,
+            });
+        }
+        let content = String::from_utf8(bytes).map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        let version = DiskVersion::provided(&stat, content.as_bytes());
+        self.disk_versions.lock().unwrap().insert(PathBuf::from(&path), version);
+        Ok(FileContent {
+            path,
+            size: content.len() as u64,
+            content,
+            encoding: "utf-8".to_string(),
+            is_binary: false,
+        })
+    }
+
+    fn write_provided(
+        &self,
+        provided: &ProvidedPath,
+        content: &str,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        if !self.get_config().overwrite && provider.stat(&uri.path).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+        provider.write(&uri.path, content.as_bytes())?;
+        Ok(FileOperationResult {
+            success: true,
+            message: "File written successfully".to_string(),
+            path: Some(uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    fn save_provided(
+        &self,
+        provided: &ProvidedPath,
+        content: &str,
+        force: bool,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        if !force {
+            self.check_unchanged_provided(provided)?;
+        }
+        provider.write(&uri.path, content.as_bytes())?;
+        let stat = provider.stat(&uri.path)?;
+        let version = DiskVersion::provided(&stat, content.as_bytes());
+        self.disk_versions.lock().unwrap().insert(PathBuf::from(uri.to_string()), version);
+        Ok(FileOperationResult {
+            success: true,
+            message: "File saved successfully".to_string(),
+            path: Some(uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// The version of a provider's file on disk now, when it differs from the one last read or saved here.
+    /// The lock isn't held while the provider is asked, which can take a network round trip.
+    fn changed_provided_version(
+        &self,
+        provided: &ProvidedPath,
+    ) -> Result<Option<(ProviderStat, Vec<u8>)>, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        let key = PathBuf::from(uri.to_string());
+        let Some((modified, size, hash)) = self.disk_versions.lock().unwrap()
+            .get(&key)
+            .map(|known| (known.modified, known.size, known.hash.clone()))
+        else {
+            return Ok(None);
+        };
+        let Ok(stat) = provider.stat(&uri.path) else {
+            return Ok(None);
+        };
+        let current = DiskVersion::provided(&stat, &[]);
+        if current.modified == modified && current.size == size {
+            return Ok(None);
+        }
+        let content = provider.read(&uri.path)?;
+        let current = DiskVersion::provided(&stat, &content);
+        if current.hash == hash {
+            self.disk_versions.lock().unwrap().insert(key, current);
+            return Ok(None);
+        }
+        Ok(Some((stat, content)))
+    }
+
+    fn check_unchanged_provided(&self, provided: &ProvidedPath) -> Result<(), FileSystemError> {
+        match self.changed_provided_version(provided)? {
+            None => Ok(()),
+            Some((stat, content)) => Err(FileSystemError::ConflictingChange {
+                disk_content: String::from_utf8_lossy(&content).to_string(),
+                modified: stat.modified.map_or(0, |seconds| seconds * 1000),
+            }),
+        }
+    }
+
+    /// Like `matches_known_version`, for a URI
+    pub(crate) fn matches_known_provided_version(&self, path: &str) -> bool {
+        let Ok(Some(provided)) = self.provided(path) else {
+            return false;
+        };
+        let known = self.disk_versions.lock().unwrap().contains_key(&PathBuf::from(path));
+        known && matches!(self.changed_provided_version(&provided), Ok(None))
+    }
+
+    fn create_file_provided(
+        &self,
+        provided: &ProvidedPath,
+        content: &str,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        if provider.stat(&uri.path).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+        provider.write(&uri.path, content.as_bytes())?;
+        Ok(FileOperationResult {
+            success: true,
+            message: "File created successfully".to_string(),
+            path: Some(uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    fn create_directory_provided(
+        &self,
+        provided: &ProvidedPath,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        provider.create_directory(&uri.path)?;
+        Ok(FileOperationResult {
+            success: true,
+            message: "Directory created successfully".to_string(),
+            path: Some(uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    /// Delete a file, or with `directory` a directory and everything in it
+    fn delete_provided(
+        &self,
+        provided: &ProvidedPath,
+        directory: bool,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        let is_directory = provider.stat(&uri.path)?.kind == EntryKind::Directory;
+        if is_directory != directory {
+            return Err(FileSystemError::InvalidPath);
+        }
+        provider.remove(&uri.path, directory)?;
+        Ok(FileOperationResult {
+            success: true,
+            message: match directory {
+                true => "Directory deleted successfully".to_string(),
+                false => "File deleted successfully".to_string(),
+            },
+            path: Some(uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    fn rename_provided(
+        &self,
+        old: &ProvidedPath,
+        new: &ProvidedPath,
+    ) -> Result<FileOperationResult, FileSystemError> {
+        if old.uri.origin() != new.uri.origin() {
+            return Err(FileSystemError::Unsupported("renaming across machines".to_string()));
+        }
+        old.provider.rename(&old.uri.path, &new.uri.path, self.get_config().overwrite)?;
+        Ok(FileOperationResult {
+            success: true,
+            message: "Renamed successfully".to_string(),
+            path: Some(new.uri.to_string()),
+            error_code: None,
+        })
+    }
+
+    fn metadata_provided(&self, provided: &ProvidedPath) -> Result<FileMetadata, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        let stat = provider.stat(&uri.path)?;
+        let name = uri.name().to_string();
+        Ok(FileMetadata {
+            path: uri.to_string(),
+            size: stat.size,
+            is_directory: stat.kind == EntryKind::Directory,
+            is_file: stat.kind == EntryKind::File,
+            is_symlink: stat.kind == EntryKind::Symlink,
+            symlink_target: None,
+            readonly: stat.permissions & 0o222 == 0,
+            hidden: name.starts_with('.'),
+            owner: None,
+            group: None,
+            created: None,
+            modified: stat.modified,
+            accessed: None,
+            permissions: format!("{:o}", stat.permissions),
+            extension: Path::new(&name).extension().map(|extension| extension.to_string_lossy().to_string()),
+            mime_type: None,
+            name,
+        })
+    }
+
+    /// Like `snapshot_directory`, from one listing by the provider; the entries are kept for the pages
+    fn snapshot_provided(
+        &self,
+        path: &str,
+        provided: &ProvidedPath,
+        include_hidden: bool,
+        sort: DirectorySort,
+        filter: &DirectoryFilter,
+        nesting: Option<&FileNesting>,
+    ) -> Result<ListingSnapshot, FileSystemError> {
+        let ProvidedPath { provider, uri } = provided;
+        if provider.stat(&uri.path)?.kind != EntryKind::Directory {
+            return Err(FileSystemError::InvalidPath);
+        }
+
+        let matcher = NameFilter::new(filter)?;
+        let mut items = Vec::new();
+        let mut entries = HashMap::new();
+        let mut hidden_count = 0;
+        let mut filtered_count = 0;
+        for entry in provider.list(&uri.path)? {
+            if entry.name.starts_with('.') {
+                hidden_count += 1;
+                if !include_hidden {
+                    continue;
+                }
+            }
+            let is_directory = entry.stat.kind == EntryKind::Directory;
+            let name = entry.name.to_lowercase();
+            if !matcher.matches(&name, is_directory) {
+                filtered_count += 1;
+                continue;
+            }
+
+            let entry_uri = uri.join(&entry.name).to_string();
+            items.push(SortItem {
+                extension: Path::new(&name).extension()
+                    .map(|ext| ext.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                is_directory,
+                size: entry.stat.size,
+                modified: entry.stat.modified.unwrap_or(0),
+                name,
+                path: PathBuf::from(&entry_uri),
+            });
+            entries.insert(PathBuf::from(&entry_uri), DirectoryEntry {
+                icon: self.get_file_icon(&entry.name, is_directory),
+                name: entry.name,
+                path: entry_uri,
+                is_directory,
+                is_symlink: entry.stat.kind == EntryKind::Symlink,
+                symlink_target: None,
+                size: (entry.stat.kind == EntryKind::File).then_some(entry.stat.size),
+                modified: entry.stat.modified,
+                permissions: format!("{:o}", entry.stat.permissions),
+                children_nested: Vec::new(),
+            });
+        }
+
+        Ok(ListingSnapshot {
+            path: path.to_string(),
+            entries: arrange(items, sort, nesting),
+            hidden_count,
+            filtered_count,
+            provided: Some(Arc::new(entries)),
+        })
     }
 }
 
@@ -0,0 +1,154 @@
+/**
+ * New-file templates for CodeForge IDE
+ * Boilerplate written into files created from the explorer: the `file_templates` preference for the file's
+ * extension or language, or else just the license header, with `{{variable}}` placeholders filled in
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::file_system::FileSystemService;
+use crate::project_templates::{git_identity, substitute, today};
+use crate::settings::{SettingsLayer, SettingsService};
+use crate::types::FileOperationResult;
+use crate::workspace_stats::{comment_syntax, CommentSyntax};
+
+/// Template used for files without one of their own
+const DEFAULT_TEMPLATE: &str = "{{license_header}}";
+
+/// Result of `create_file_from_template`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewFile {
+    #[serde(flatten)]
+    pub result: FileOperationResult,
+    /// What was written, so the editor can open the file without reading it back
+    pub content: String,
+    /// Key in `file_templates` that was used; `None` for the default template
+    pub template: Option<String>,
+}
+
+/// Turn `text` into a comment in `syntax`, preferring line comments. Blank lines keep the bare marker so the
+/// comment stays one block.
+pub fn comment(text: &str, syntax: CommentSyntax) -> String {
+    let lines: Vec<&str> = text.trim_end().lines().map(str::trim_end).collect();
+    let mut output = String::new();
+    match (syntax.line, syntax.block) {
+        (Some(prefix), _) => {
+            for line in lines {
+                match line.is_empty() {
+                    true => output.push_str(prefix),
+                    false => output.push_str(&format!("{} {}", prefix, line)),
+                }
+                output.push('\n');
+            }
+        }
+        // ` * ` continuation lines are only idiomatic in C-style block comments
+        (None, Some(("/*", close))) => {
+            output.push_str("/*\n");
+            for line in lines {
+                output.push_str(format!(" * {}", line).trim_end());
+                output.push('\n');
+            }
+            output.push_str(&format!(" {}\n", close));
+        }
+        (None, Some((open, close))) => {
+            output.push_str(open);
+            output.push('\n');
+            for line in lines {
+                output.push_str(line);
+                output.push('\n');
+            }
+            output.push_str(close);
+            output.push('\n');
+        }
+        (None, None) => {}
+    }
+    output
+}
+
+/// Placeholder values that depend on where the file is: its name, directory, and path in the workspace
+pub fn path_variables(path: &Path, workspace: Option<&Path>) -> BTreeMap<String, String> {
+    let name_of = |path: Option<&Path>| {
+        path.and_then(Path::file_name).map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    };
+    let relative = workspace.and_then(|workspace| path.strip_prefix(workspace).ok()).unwrap_or(path);
+    BTreeMap::from([
+        ("file_name".to_string(), name_of(Some(path))),
+        (
+            "file_stem".to_string(),
+            path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+        ),
+        (
+            "extension".to_string(),
+            path.extension().map(|extension| extension.to_string_lossy().to_string()).unwrap_or_default(),
+        ),
+        ("directory_name".to_string(), name_of(path.parent())),
+        ("workspace_name".to_string(), name_of(workspace)),
+        ("relative_path".to_string(), relative.to_string_lossy().replace('\\', "/")),
+    ])
+}
+
+/// Values for placeholders in a new file's template. `license_header` is the header as a comment followed by
+/// a blank line, or empty when there is no header or the language has no comments.
+pub fn template_variables(
+    path: &Path,
+    language: Option<&str>,
+    workspace: Option<&Path>,
+    license_header: &str,
+) -> BTreeMap<String, String> {
+    let mut variables = path_variables(path, workspace);
+    variables.insert("language".to_string(), language.unwrap_or_default().to_string());
+
+    // The file doesn't exist yet, so the repository is looked up from its workspace
+    let (author, email) = git_identity(workspace.or(path.parent()));
+    variables.insert("author".to_string(), author);
+    variables.insert("email".to_string(), email);
+    let date = today();
+    variables.insert("year".to_string(), date[..4].to_string());
+    variables.insert("date".to_string(), date);
+
+    let header = substitute(license_header, &variables);
+    let header = match comment_syntax(path, language) {
+        Some(syntax) if !header.trim().is_empty() => format!("{}\n", comment(&header, syntax)),
+        _ => String::new(),
+    };
+    variables.insert("license_header".to_string(), header);
+    variables
+}
+
+/// Content for a new file at `path` from the preferences of its workspace, and the template key used.
+/// Unreadable settings fall back to an empty file rather than failing the create.
+pub fn render(app: &AppHandle, path: &str, language: Option<&str>) -> (String, Option<String>) {
+    let workspace = app.state::<FileSystemService>().workspace_root(path);
+    let workspace_name = workspace.as_ref().map(|root| root.to_string_lossy().to_string());
+    let scope = match workspace {
+        Some(_) => SettingsLayer::Workspace,
+        None => SettingsLayer::User,
+    };
+    let effective = app.state::<SettingsService>().effective_settings(app, scope, workspace_name.as_deref());
+    let settings = match effective {
+        Ok(effective) => effective.settings,
+        Err(e) => {
+            tracing::warn!(path = %path, error = %e, "reading file template settings failed");
+            return (String::new(), None);
+        }
+    };
+    let setting = |key: &str| settings.get(key).map(|setting| &setting.value);
+
+    let file = Path::new(path);
+    let extension = file.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    let templates = setting("file_templates");
+    let template = [extension.as_deref(), language]
+        .into_iter()
+        .flatten()
+        .find_map(|key| Some((key.to_string(), templates?.get(key)?.as_str()?.to_string())));
+    let license_header = setting("license_header").and_then(Value::as_str).unwrap_or_default();
+    let variables = template_variables(file, language, workspace.as_deref(), license_header);
+    match template {
+        Some((key, template)) => (substitute(&template, &variables), Some(key)),
+        None => (substitute(DEFAULT_TEMPLATE, &variables), None),
+    }
+}
@@ -0,0 +1,370 @@
+/**
+ * File type detection for CodeForge IDE
+ * Classifies files by their content (magic bytes, shebangs, markup prologues) and falls back to the
+ * extension, so files without one or with a misleading one still open in the right viewer
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+/// Bytes read from the start of a file to sniff its type
+const SNIFF_LENGTH: u64 = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Text,
+    Image,
+    Audio,
+    Video,
+    Archive,
+    Document,
+    Font,
+    Executable,
+    Binary,
+}
+
+/// What the detected type was based on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectionSource {
+    Content,
+    Extension,
+    /// Neither content nor extension said anything more specific than text or binary
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileType {
+    pub mime_type: String,
+    pub category: FileCategory,
+    pub is_binary: bool,
+    pub source: DetectionSource,
+}
+
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    mime: &'static str,
+    category: FileCategory,
+    /// Short signatures that plain text could start with only count for binary content
+    binary_only: bool,
+}
+
+const fn signature(offset: usize, magic: &'static [u8], mime: &'static str, category: FileCategory) -> Signature {
+    Signature {
+        offset,
+        magic,
+        mime,
+        category,
+        binary_only: false,
+    }
+}
+
+const fn weak_signature(magic: &'static [u8], mime: &'static str, category: FileCategory) -> Signature {
+    Signature {
+        offset: 0,
+        magic,
+        mime,
+        category,
+        binary_only: true,
+    }
+}
+
+use FileCategory::*;
+
+/// Magic bytes of common formats; RIFF and ISO base media containers are told apart in `sniff_container`
+const SIGNATURES: &[Signature] = &[
+    signature(0, b"\x89PNG\r\n\x1a\n", "image/png", Image),
+    signature(0, b"\xff\xd8\xff", "image/jpeg", Image),
+    signature(0, b"GIF87a", "image/gif", Image),
+    signature(0, b"GIF89a", "image/gif", Image),
+    signature(0, b"II*\0", "image/tiff", Image),
+    signature(0, b"MM\0*", "image/tiff", Image),
+    signature(0, b"\0\0\x01\0", "image/x-icon", Image),
+    signature(0, b"8BPS", "image/vnd.adobe.photoshop", Image),
+    weak_signature(b"BM", "image/bmp", Image),
+    signature(0, b"%PDF-", "application/pdf", Document),
+    signature(0, b"{\\rtf", "application/rtf", Document),
+    signature(0, b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "application/x-ole-storage", Document),
+    signature(0, b"PK\x03\x04", "application/zip", Archive),
+    signature(0, b"PK\x05\x06", "application/zip", Archive),
+    signature(0, b"\x1f\x8b", "application/gzip", Archive),
+    signature(0, b"BZh", "application/x-bzip2", Archive),
+    signature(0, b"\xfd7zXZ\0", "application/x-xz", Archive),
+    signature(0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed", Archive),
+    signature(0, b"Rar!\x1a\x07", "application/vnd.rar", Archive),
+    signature(0, b"\x28\xb5\x2f\xfd", "application/zstd", Archive),
+    signature(257, b"ustar", "application/x-tar", Archive),
+    signature(0, b"\x7fELF", "application/x-elf", Executable),
+    signature(0, b"\xcf\xfa\xed\xfe", "application/x-mach-binary", Executable),
+    signature(0, b"\xce\xfa\xed\xfe", "application/x-mach-binary", Executable),
+    signature(0, b"\xca\xfe\xba\xbe", "application/x-mach-binary", Executable),
+    signature(0, b"\0asm", "application/wasm", Executable),
+    weak_signature(b"MZ", "application/vnd.microsoft.portable-executable", Executable),
+    signature(0, b"SQLite format 3\0", "application/vnd.sqlite3", Binary),
+    signature(0, b"ID3", "audio/mpeg", Audio),
+    signature(0, b"\xff\xfb", "audio/mpeg", Audio),
+    signature(0, b"\xff\xf3", "audio/mpeg", Audio),
+    signature(0, b"OggS", "audio/ogg", Audio),
+    signature(0, b"fLaC", "audio/flac", Audio),
+    signature(0, b"MThd", "audio/midi", Audio),
+    signature(0, b"\x1a\x45\xdf\xa3", "video/webm", Video),
+    signature(0, b"FLV\x01", "video/x-flv", Video),
+    signature(0, b"wOFF", "font/woff", Font),
+    signature(0, b"wOF2", "font/woff2", Font),
+    signature(0, b"\0\x01\0\0\0", "font/ttf", Font),
+    signature(0, b"OTTO", "font/otf", Font),
+];
+
+/// RIFF (`RIFF....WEBP`) and ISO base media (`....ftypisom`) containers, keyed on their subtype
+fn sniff_container(header: &[u8]) -> Option<(&'static str, FileCategory)> {
+    let subtype = header.get(8..12)?;
+    if header.starts_with(b"RIFF") {
+        return match subtype {
+            b"WEBP" => Some(("image/webp", Image)),
+            b"WAVE" => Some(("audio/wav", Audio)),
+            b"AVI " => Some(("video/x-msvideo", Video)),
+            _ => None,
+        };
+    }
+    if header.get(4..8) == Some(b"ftyp") {
+        return match subtype {
+            b"avif" | b"avis" => Some(("image/avif", Image)),
+            b"heic" | b"heix" | b"mif1" => Some(("image/heic", Image)),
+            b"M4A " => Some(("audio/mp4", Audio)),
+            b"qt  " => Some(("video/quicktime", Video)),
+            _ => Some(("video/mp4", Video)),
+        };
+    }
+    None
+}
+
+fn sniff_binary(header: &[u8], is_text: bool) -> Option<(&'static str, FileCategory)> {
+    SIGNATURES
+        .iter()
+        .find(|signature| {
+            !(signature.binary_only && is_text)
+                && header.get(signature.offset..signature.offset + signature.magic.len()) == Some(signature.magic)
+        })
+        .map(|signature| (signature.mime, signature.category))
+        .or_else(|| sniff_container(header))
+}
+
+/// Scripts and markup recognizable from their first line
+fn sniff_text(header: &[u8]) -> Option<(&'static str, FileCategory)> {
+    let text = String::from_utf8_lossy(header);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    if let Some(shebang) = text.strip_prefix("#!") {
+        let line = shebang.lines().next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let mut interpreter = words.next().and_then(|path| path.rsplit('/').next()).unwrap_or("");
+        if interpreter == "env" {
+            interpreter = words.find(|word| !word.starts_with('-')).unwrap_or("");
+        }
+        let mime = match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "sh" | "bash" | "zsh" | "dash" | "ksh" | "fish" => "text/x-shellscript",
+            "python" => "text/x-python",
+            "node" | "deno" | "bun" => "text/javascript",
+            "ruby" => "text/x-ruby",
+            "perl" => "text/x-perl",
+            "php" => "application/x-httpd-php",
+            "lua" => "text/x-lua",
+            _ => "text/plain",
+        };
+        return Some((mime, Text));
+    }
+
+    let lower = text.get(..text.len().min(512)).unwrap_or(text).to_ascii_lowercase();
+    if lower.starts_with("<svg") || (lower.starts_with("<?xml") && lower.contains("<svg")) {
+        Some(("image/svg+xml", Image))
+    } else if lower.starts_with("<?xml") {
+        Some(("application/xml", Text))
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        Some(("text/html", Text))
+    } else {
+        None
+    }
+}
+
+/// No NUL bytes and either valid UTF-8 (possibly cut off mid-character) or free of control characters
+fn looks_like_text(header: &[u8]) -> bool {
+    if header.starts_with(b"\xff\xfe") || header.starts_with(b"\xfe\xff") {
+        // UTF-16 text is full of NULs, but its byte order mark gives it away
+        return true;
+    }
+    if header.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(header) {
+        Ok(_) => true,
+        Err(e) if e.error_len().is_none() => true,
+        // Legacy 8-bit encodings
+        Err(_) => !header.iter().any(|&byte| byte < 0x20 && !b"\t\n\r\x0c\x1b".contains(&byte)),
+    }
+}
+
+/// MIME type and category for an extension
+pub fn mime_from_extension(extension: &str) -> Option<(&'static str, FileCategory)> {
+    let mime = match extension.to_ascii_lowercase().as_str() {
+        "txt" | "text" | "log" => ("text/plain", Text),
+        "md" | "markdown" => ("text/markdown", Text),
+        "html" | "htm" => ("text/html", Text),
+        "css" => ("text/css", Text),
+        "scss" => ("text/x-scss", Text),
+        "less" => ("text/x-less", Text),
+        "js" | "mjs" | "cjs" => ("text/javascript", Text),
+        "jsx" => ("text/jsx", Text),
+        "ts" | "mts" | "cts" => ("text/typescript", Text),
+        "tsx" => ("text/tsx", Text),
+        "json" | "jsonc" => ("application/json", Text),
+        "xml" | "xsd" | "xsl" => ("application/xml", Text),
+        "yaml" | "yml" => ("application/yaml", Text),
+        "toml" => ("application/toml", Text),
+        "ini" | "cfg" | "conf" => ("text/plain", Text),
+        "csv" => ("text/csv", Text),
+        "tsv" => ("text/tab-separated-values", Text),
+        "rs" => ("text/x-rust", Text),
+        "py" | "pyi" => ("text/x-python", Text),
+        "go" => ("text/x-go", Text),
+        "c" | "h" => ("text/x-c", Text),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" => ("text/x-c++", Text),
+        "cs" => ("text/x-csharp", Text),
+        "java" => ("text/x-java", Text),
+        "kt" | "kts" => ("text/x-kotlin", Text),
+        "swift" => ("text/x-swift", Text),
+        "rb" => ("text/x-ruby", Text),
+        "php" => ("application/x-httpd-php", Text),
+        "pl" | "pm" => ("text/x-perl", Text),
+        "lua" => ("text/x-lua", Text),
+        "sh" | "bash" | "zsh" | "fish" => ("text/x-shellscript", Text),
+        "ps1" => ("text/x-powershell", Text),
+        "sql" => ("application/sql", Text),
+        "vue" => ("text/x-vue", Text),
+        "svelte" => ("text/x-svelte", Text),
+        "svg" => ("image/svg+xml", Image),
+        "png" => ("image/png", Image),
+        "jpg" | "jpeg" => ("image/jpeg", Image),
+        "gif" => ("image/gif", Image),
+        "webp" => ("image/webp", Image),
+        "bmp" => ("image/bmp", Image),
+        "ico" => ("image/x-icon", Image),
+        "tif" | "tiff" => ("image/tiff", Image),
+        "avif" => ("image/avif", Image),
+        "heic" => ("image/heic", Image),
+        "psd" => ("image/vnd.adobe.photoshop", Image),
+        "mp3" => ("audio/mpeg", Audio),
+        "wav" => ("audio/wav", Audio),
+        "ogg" | "oga" => ("audio/ogg", Audio),
+        "flac" => ("audio/flac", Audio),
+        "m4a" => ("audio/mp4", Audio),
+        "mid" | "midi" => ("audio/midi", Audio),
+        "mp4" | "m4v" => ("video/mp4", Video),
+        "mov" => ("video/quicktime", Video),
+        "webm" => ("video/webm", Video),
+        "mkv" => ("video/x-matroska", Video),
+        "avi" => ("video/x-msvideo", Video),
+        "flv" => ("video/x-flv", Video),
+        "pdf" => ("application/pdf", Document),
+        "rtf" => ("application/rtf", Document),
+        "doc" => ("application/msword", Document),
+        "xls" => ("application/vnd.ms-excel", Document),
+        "ppt" => ("application/vnd.ms-powerpoint", Document),
+        "msi" => ("application/x-msi", Executable),
+        "docx" => ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", Archive),
+        "xlsx" => ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", Archive),
+        "pptx" => ("application/vnd.openxmlformats-officedocument.presentationml.presentation", Archive),
+        "odt" => ("application/vnd.oasis.opendocument.text", Archive),
+        "epub" => ("application/epub+zip", Archive),
+        "jar" => ("application/java-archive", Archive),
+        "apk" => ("application/vnd.android.package-archive", Archive),
+        "vsix" => ("application/vsix", Archive),
+        "zip" => ("application/zip", Archive),
+        "gz" | "tgz" => ("application/gzip", Archive),
+        "bz2" => ("application/x-bzip2", Archive),
+        "xz" => ("application/x-xz", Archive),
+        "7z" => ("application/x-7z-compressed", Archive),
+        "rar" => ("application/vnd.rar", Archive),
+        "zst" => ("application/zstd", Archive),
+        "tar" => ("application/x-tar", Archive),
+        "exe" | "dll" => ("application/vnd.microsoft.portable-executable", Executable),
+        "so" => ("application/x-elf", Executable),
+        "dylib" => ("application/x-mach-binary", Executable),
+        "class" => ("application/java-vm", Executable),
+        "wasm" => ("application/wasm", Executable),
+        "woff" => ("font/woff", Font),
+        "woff2" => ("font/woff2", Font),
+        "ttf" => ("font/ttf", Font),
+        "otf" => ("font/otf", Font),
+        "sqlite" | "db" => ("application/vnd.sqlite3", Binary),
+        _ => return None,
+    };
+    Some(mime)
+}
+
+fn classify(header: Option<&[u8]>, extension: Option<&str>) -> FileType {
+    let by_extension = extension.and_then(mime_from_extension);
+    let file_type = |(mime, category): (&str, FileCategory), is_binary, source| FileType {
+        mime_type: mime.to_string(),
+        category,
+        is_binary,
+        source,
+    };
+
+    // Content that couldn't be read leaves only the name to go by
+    let Some(header) = header else {
+        return match by_extension {
+            Some(found) => file_type(found, found.1 != Text && found.0 != "image/svg+xml", DetectionSource::Extension),
+            None => file_type(("application/octet-stream", Binary), true, DetectionSource::Default),
+        };
+    };
+
+    let is_text = looks_like_text(header);
+    if let Some(sniffed) = sniff_binary(header, is_text) {
+        // Containers are generic (a .docx is a zip, a .class shares Mach-O's magic): a matching
+        // extension is more specific
+        return match by_extension {
+            Some(found) if found.1 == sniffed.1 => file_type(found, true, DetectionSource::Extension),
+            _ => file_type(sniffed, true, DetectionSource::Content),
+        };
+    }
+    if !is_text {
+        return match by_extension {
+            Some(found) if found.1 != Text => file_type(found, true, DetectionSource::Extension),
+            _ => file_type(("application/octet-stream", Binary), true, DetectionSource::Default),
+        };
+    }
+
+    match by_extension {
+        Some(found) if found.1 == Text || found.0 == "image/svg+xml" => {
+            file_type(found, false, DetectionSource::Extension)
+        }
+        _ => match sniff_text(header) {
+            Some(sniffed) => file_type(sniffed, false, DetectionSource::Content),
+            None => file_type(("text/plain", Text), false, DetectionSource::Default),
+        },
+    }
+}
+
+/// Detect the type of content in memory by its first bytes, e.g. a download without a usable Content-Type
+pub fn detect_content_type(content: &[u8]) -> FileType {
+    classify(Some(&content[..content.len().min(SNIFF_LENGTH as usize)]), None)
+}
+
+/// Detect a file's type from its first bytes, falling back to its extension when they can't be read
+pub fn detect_file_type(path: &Path) -> Result<FileType, FileSystemError> {
+    if fs::metadata(path).map_err(map_io_error)?.is_dir() {
+        return Err(FileSystemError::InvalidPath);
+    }
+
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let mut header = Vec::new();
+    let read = File::open(path).and_then(|file| file.take(SNIFF_LENGTH).read_to_end(&mut header));
+    Ok(classify(read.ok().map(|_| header.as_slice()), extension))
+}
@@ -0,0 +1,123 @@
+/**
+ * External formatters for CodeForge IDE
+ * Pipes a buffer through a formatter program (rustfmt, prettier, black, ...) and returns its output, killing
+ * the program if it doesn't finish in time
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running formatter is checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Placeholder in formatter arguments replaced with the path of the file being formatted
+const FILE_PLACEHOLDER: &str = "${file}";
+
+/// Lines of stderr kept when a formatter fails
+const STDERR_LINES: usize = 20;
+
+/// Error types for formatting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FormatError {
+    SpawnFailed(String),
+    Timeout(u64),
+    Failed { code: Option<i32>, stderr: String },
+    InvalidOutput(String),
+    IOError(String),
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FormatError::SpawnFailed(msg) => write!(f, "Failed to start formatter: {}", msg),
+            FormatError::Timeout(ms) => write!(f, "Formatter did not finish within {} ms", ms),
+            FormatError::Failed { code: Some(code), stderr } => {
+                write!(f, "Formatter exited with code {}: {}", code, stderr)
+            }
+            FormatError::Failed { code: None, stderr } => write!(f, "Formatter was terminated: {}", stderr),
+            FormatError::InvalidOutput(msg) => write!(f, "Formatter output is not valid UTF-8: {}", msg),
+            FormatError::IOError(msg) => write!(f, "IO Error: {}", msg),
+        }
+    }
+}
+
+/// Read a pipe to the end on its own thread, so a formatter filling one pipe can't block on the other
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+fn wait(child: &mut Child, timeout: Duration) -> Result<ExitStatus, FormatError> {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(exit)) => return Ok(exit),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(FormatError::Timeout(timeout.as_millis() as u64));
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(FormatError::IOError(e.to_string())),
+        }
+    }
+}
+
+/// Run `command` with `content` on stdin and return what it writes to stdout. A non-zero exit is an error
+/// carrying the end of stderr, since formatters report syntax errors there.
+pub fn run_external(
+    command: &str,
+    args: &[String],
+    path: &Path,
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    content: &str,
+    timeout: Duration,
+) -> Result<String, FormatError> {
+    let file = path.to_string_lossy();
+    let mut child = Command::new(command)
+        .args(args.iter().map(|arg| arg.replace(FILE_PLACEHOLDER, &file)))
+        .current_dir(cwd)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| FormatError::SpawnFailed(format!("{}: {}", command, e)))?;
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let stdin = child.stdin.take();
+    let input = content.to_string();
+    // A formatter that exits without reading everything closes the pipe; that shows up in its exit status
+    thread::spawn(move || {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+
+    // After a timeout the pipes may still be held open by processes the formatter started, so the readers
+    // are left to finish on their own
+    let exit = wait(&mut child, timeout)?;
+    let stdout = stdout.join().unwrap_or_default();
+    if exit.success() {
+        return String::from_utf8(stdout).map_err(|e| FormatError::InvalidOutput(e.to_string()));
+    }
+    let stderr = stderr.join().unwrap_or_default();
+    let stderr = String::from_utf8_lossy(&stderr);
+    let lines: Vec<&str> = stderr.trim_end().lines().collect();
+    Err(FormatError::Failed {
+        code: exit.code(),
+        stderr: lines[lines.len().saturating_sub(STDERR_LINES)..].join("\n"),
+    })
+}
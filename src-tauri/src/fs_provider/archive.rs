@@ -0,0 +1,439 @@
+/**
+ * Files inside zip and tar archives on this machine, read-only
+ * The archive's table of contents is read once and again whenever the archive changes; file content is
+ * read from the archive each time it's asked for.
+ */
+
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{join_path, normalize_path, EntryKind, FileSystemProvider, ProviderEntry, ProviderStat};
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+/// Extensions of zip archives, including formats that are zips underneath
+const ZIP_EXTENSIONS: &[&str] = &[".zip", ".jar", ".war", ".ear", ".vsix", ".whl", ".nupkg", ".apk", ".aar"];
+const TAR_EXTENSIONS: &[&str] = &[".tar"];
+const TAR_GZ_EXTENSIONS: &[&str] = &[".tar.gz", ".tgz", ".crate"];
+
+const TAR_BLOCK: u64 = 512;
+
+/// Largest entry that will be read into memory
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl Format {
+    fn of(archive: &str) -> Option<Self> {
+        let name = archive.to_lowercase();
+        let has = |extensions: &[&str]| extensions.iter().any(|extension| name.ends_with(extension));
+        if has(ZIP_EXTENSIONS) {
+            Some(Format::Zip)
+        } else if has(TAR_GZ_EXTENSIONS) {
+            Some(Format::TarGz)
+        } else if has(TAR_EXTENSIONS) {
+            Some(Format::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `path` names an archive this provider can open, going by its extension
+pub fn is_archive(path: &str) -> bool {
+    Format::of(path).is_some()
+}
+
+/// Where an entry's content is in the archive
+#[derive(Debug, Clone, Copy)]
+enum Location {
+    Directory,
+    /// Index in the zip's central directory
+    Zip(usize),
+    /// Offset of the content in the uncompressed tar stream
+    Tar(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    stat: ProviderStat,
+    location: Location,
+}
+
+/// Every entry of an archive by its path inside it, `/` being the archive itself
+struct ArchiveIndex {
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+impl ArchiveIndex {
+    fn new(archive_modified: Option<u64>) -> Self {
+        let mut index = ArchiveIndex { entries: BTreeMap::new() };
+        index.entries.insert("/".to_string(), directory_entry(archive_modified));
+        index
+    }
+
+    /// Add an entry and the directories above it that the archive doesn't list itself
+    fn insert(&mut self, name: &str, entry: IndexEntry) {
+        let path = normalize_path(name);
+        if path == "/" {
+            return;
+        }
+        let mut parent = path.as_str();
+        while let Some((above, _)) = parent.rsplit_once('/') {
+            let above = if above.is_empty() { "/" } else { above };
+            if self.entries.contains_key(above) {
+                break;
+            }
+            self.entries.insert(above.to_string(), directory_entry(entry.stat.modified));
+            parent = above;
+        }
+        self.entries.insert(path, entry);
+    }
+
+    fn get(&self, path: &str) -> Result<&IndexEntry, FileSystemError> {
+        self.entries.get(&normalize_path(path)).ok_or(FileSystemError::NotFound)
+    }
+}
+
+fn directory_entry(modified: Option<u64>) -> IndexEntry {
+    IndexEntry {
+        stat: ProviderStat {
+            kind: EntryKind::Directory,
+            size: 0,
+            modified,
+            permissions: 0o755,
+        },
+        location: Location::Directory,
+    }
+}
+
+fn invalid_archive(e: impl std::fmt::Display) -> FileSystemError {
+    FileSystemError::IOError(format!("Can't read archive: {}", e))
+}
+
+/// Days since the Unix epoch of a civil date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Unix time of a zip entry's time stamp, which has no time zone and is taken as UTC
+fn zip_time(time: zip::DateTime) -> Option<u64> {
+    let days = days_from_civil(time.year() as i64, time.month() as i64, time.day() as i64);
+    let seconds = days * 86_400 + time.hour() as i64 * 3600 + time.minute() as i64 * 60 + time.second() as i64;
+    u64::try_from(seconds).ok()
+}
+
+fn index_zip(archive: &Path, archive_modified: Option<u64>) -> Result<ArchiveIndex, FileSystemError> {
+    let file = File::open(archive).map_err(map_io_error)?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(invalid_archive)?;
+    let mut index = ArchiveIndex::new(archive_modified);
+    for i in 0..zip.len() {
+        let entry = zip.by_index_raw(i).map_err(invalid_archive)?;
+        let modified = entry.last_modified().and_then(zip_time).or(archive_modified);
+        let (kind, location) = match (entry.is_dir(), entry.is_symlink()) {
+            (true, _) => (EntryKind::Directory, Location::Directory),
+            (false, true) => (EntryKind::Symlink, Location::Zip(i)),
+            (false, false) => (EntryKind::File, Location::Zip(i)),
+        };
+        let default_permissions = if kind == EntryKind::Directory { 0o755 } else { 0o644 };
+        let stat = ProviderStat {
+            kind,
+            size: if kind == EntryKind::File { entry.size() } else { 0 },
+            modified,
+            permissions: entry.unix_mode().map_or(default_permissions, |mode| mode & 0o7777),
+        };
+        index.insert(entry.name(), IndexEntry { stat, location });
+    }
+    Ok(index)
+}
+
+/// An octal number field of a tar header, or a base-256 one when its first byte has the high bit set
+fn tar_number(field: &[u8]) -> Option<u64> {
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        let mut value: u64 = (field[0] & 0x7f) as u64;
+        for byte in &field[1..] {
+            value = value.checked_mul(256)?.checked_add(*byte as u64)?;
+        }
+        return Some(value);
+    }
+    let text = std::str::from_utf8(field).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(text, 8).ok()
+}
+
+fn tar_text(field: &[u8]) -> String {
+    let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+/// The `path` record of a PAX extended header, whose records are `<length> <key>=<value>\n`
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = data;
+    while !rest.is_empty() {
+        let space = rest.iter().position(|byte| *byte == b' ')?;
+        let length: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..length)?;
+        if let Some(value) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(value.strip_suffix(b"\n").unwrap_or(value)).to_string());
+        }
+        rest = &rest[length..];
+    }
+    None
+}
+
+/// Read exactly `length` bytes, or fewer at the end of the stream
+fn read_up_to(reader: &mut impl Read, length: u64) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.take(length).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn skip(reader: &mut impl Read, length: u64) -> io::Result<u64> {
+    io::copy(&mut reader.take(length), &mut io::sink())
+}
+
+fn padded(size: u64) -> u64 {
+    size.div_ceil(TAR_BLOCK) * TAR_BLOCK
+}
+
+/// Index a tar stream: 512-byte headers, each followed by its content padded to whole blocks, with GNU long
+/// names and PAX paths applying to the header after them
+fn index_tar(mut reader: impl Read, archive_modified: Option<u64>) -> Result<ArchiveIndex, FileSystemError> {
+    let mut index = ArchiveIndex::new(archive_modified);
+    let mut offset = 0;
+    let mut long_name: Option<String> = None;
+    loop {
+        let header = read_up_to(&mut reader, TAR_BLOCK).map_err(invalid_archive)?;
+        if header.iter().all(|byte| *byte == 0) {
+            break;
+        }
+        if header.len() < TAR_BLOCK as usize {
+            return Err(invalid_archive("not a tar archive"));
+        }
+        offset += TAR_BLOCK;
+        let checksum = tar_number(&header[148..156]).ok_or_else(|| invalid_archive("not a tar archive"))?;
+        let sum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| if (148..156).contains(&i) { b' ' as u64 } else { *byte as u64 })
+            .sum();
+        if sum != checksum {
+            return Err(invalid_archive("not a tar archive"));
+        }
+
+        let size = tar_number(&header[124..136]).ok_or_else(|| invalid_archive("bad entry size"))?;
+        let type_flag = header[156];
+        if matches!(type_flag, b'L' | b'x') {
+            let data = read_up_to(&mut reader, padded(size)).map_err(invalid_archive)?;
+            let data = &data[..(size as usize).min(data.len())];
+            long_name = match type_flag {
+                b'L' => Some(tar_text(data)),
+                _ => pax_path(data).or(long_name),
+            };
+            offset += padded(size);
+            continue;
+        }
+
+        let name = long_name.take().unwrap_or_else(|| {
+            let name = tar_text(&header[0..100]);
+            let prefix = tar_text(&header[345..500]);
+            let ustar = &header[257..262] == b"ustar";
+            if ustar && !prefix.is_empty() { format!("{}/{}", prefix, name) } else { name }
+        });
+        let kind = match type_flag {
+            b'0' | b'\0' | b'7' => Some(EntryKind::File),
+            b'5' => Some(EntryKind::Directory),
+            b'2' => Some(EntryKind::Symlink),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let stat = ProviderStat {
+                kind,
+                size: if kind == EntryKind::File { size } else { 0 },
+                modified: tar_number(&header[136..148]),
+                permissions: tar_number(&header[100..108]).map_or(0o644, |mode| mode as u32 & 0o7777),
+            };
+            let location = match kind {
+                EntryKind::Directory => Location::Directory,
+                _ => Location::Tar(offset),
+            };
+            index.insert(&name, IndexEntry { stat, location });
+        }
+        let skipped = skip(&mut reader, padded(size)).map_err(invalid_archive)?;
+        offset += skipped;
+        if skipped < padded(size) {
+            break;
+        }
+    }
+    Ok(index)
+}
+
+/// What the index was built from, to notice when the archive changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ArchiveStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+/// A zip or tar archive on this machine, opened read-only
+pub struct ArchiveProvider {
+    archive: PathBuf,
+    format: Format,
+    index: Mutex<Option<(ArchiveStamp, Arc<ArchiveIndex>)>>,
+}
+
+impl ArchiveProvider {
+    pub fn new(archive: PathBuf) -> Result<Self, FileSystemError> {
+        let format = Format::of(&archive.to_string_lossy()).ok_or(FileSystemError::InvalidPath)?;
+        Ok(ArchiveProvider {
+            archive,
+            format,
+            index: Mutex::new(None),
+        })
+    }
+
+    fn index(&self) -> Result<Arc<ArchiveIndex>, FileSystemError> {
+        let metadata = fs::metadata(&self.archive).map_err(map_io_error)?;
+        if !metadata.is_file() {
+            return Err(FileSystemError::InvalidPath);
+        }
+        let stamp = ArchiveStamp {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        };
+        let mut cached = self.index.lock().unwrap();
+        if let Some((cached_stamp, index)) = cached.as_ref() {
+            if *cached_stamp == stamp {
+                return Ok(index.clone());
+            }
+        }
+        let modified = stamp
+            .modified
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+        let index = Arc::new(match self.format {
+            Format::Zip => index_zip(&self.archive, modified)?,
+            Format::Tar => {
+                let file = File::open(&self.archive).map_err(map_io_error)?;
+                index_tar(BufReader::new(file), modified)?
+            }
+            Format::TarGz => {
+                let file = File::open(&self.archive).map_err(map_io_error)?;
+                index_tar(GzDecoder::new(BufReader::new(file)), modified)?
+            }
+        });
+        *cached = Some((stamp, index.clone()));
+        Ok(index)
+    }
+
+    fn read_entry(&self, location: Location, size: u64) -> Result<Vec<u8>, FileSystemError> {
+        let too_large = || FileSystemError::Unsupported("entries larger than 256 MiB".to_string());
+        if size > MAX_ENTRY_BYTES {
+            return Err(too_large());
+        }
+        let mut file = File::open(&self.archive).map_err(map_io_error)?;
+        let content = match location {
+            Location::Directory => return Err(FileSystemError::InvalidPath),
+            // The size in the header can lie, so nothing is allocated from it and the limit is enforced on
+            // the bytes actually inflated
+            Location::Zip(i) => {
+                let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(invalid_archive)?;
+                let mut entry = zip.by_index(i).map_err(invalid_archive)?;
+                let content = read_up_to(&mut entry, MAX_ENTRY_BYTES + 1).map_err(invalid_archive)?;
+                if content.len() as u64 > MAX_ENTRY_BYTES {
+                    return Err(too_large());
+                }
+                content
+            }
+            Location::Tar(offset) if self.format == Format::Tar => {
+                file.seek(SeekFrom::Start(offset)).map_err(map_io_error)?;
+                read_up_to(&mut BufReader::new(file), size).map_err(invalid_archive)?
+            }
+            Location::Tar(offset) => {
+                let mut decoder = GzDecoder::new(BufReader::new(file));
+                skip(&mut decoder, offset).map_err(invalid_archive)?;
+                read_up_to(&mut decoder, size).map_err(invalid_archive)?
+            }
+        };
+        Ok(content)
+    }
+
+    fn read_only() -> FileSystemError {
+        FileSystemError::Unsupported("changing files inside archives".to_string())
+    }
+}
+
+impl FileSystemProvider for ArchiveProvider {
+    fn name(&self) -> String {
+        self.archive.to_string_lossy().to_string()
+    }
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError> {
+        Ok(self.index()?.get(path)?.stat)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        let index = self.index()?;
+        let entry = index.get(path)?;
+        match entry.stat.kind {
+            EntryKind::File => self.read_entry(entry.location, entry.stat.size),
+            _ => Err(FileSystemError::InvalidPath),
+        }
+    }
+
+    fn write(&self, _path: &str, _content: &[u8]) -> Result<(), FileSystemError> {
+        Err(Self::read_only())
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError> {
+        let index = self.index()?;
+        let directory = normalize_path(path);
+        if index.get(&directory)?.stat.kind != EntryKind::Directory {
+            return Err(FileSystemError::InvalidPath);
+        }
+        let prefix = join_path(&directory, "");
+        Ok(index
+            .entries
+            .range(prefix.clone()..)
+            .take_while(|(entry_path, _)| entry_path.starts_with(&prefix))
+            .filter(|(entry_path, _)| {
+                let name = &entry_path[prefix.len()..];
+                !name.is_empty() && !name.contains('/')
+            })
+            .map(|(entry_path, entry)| ProviderEntry {
+                name: entry_path[prefix.len()..].to_string(),
+                stat: entry.stat,
+            })
+            .collect())
+    }
+
+    fn create_directory(&self, _path: &str) -> Result<(), FileSystemError> {
+        Err(Self::read_only())
+    }
+
+    fn remove(&self, _path: &str, _recursive: bool) -> Result<(), FileSystemError> {
+        Err(Self::read_only())
+    }
+
+    fn rename(&self, _from: &str, _to: &str, _overwrite: bool) -> Result<(), FileSystemError> {
+        Err(Self::read_only())
+    }
+}
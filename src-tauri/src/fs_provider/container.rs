@@ -0,0 +1,94 @@
+/**
+ * Files and processes inside a running container
+ *
+ * Everything runs through `docker exec`, so whatever the `docker` CLI is pointed at works: the local
+ * daemon, a remote one through `DOCKER_HOST` or a context, or Podman's Docker-compatible CLI. The container
+ * needs `sh` and the tools the shell scripts use, which every image with a shell has.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use super::shell::ShellTransport;
+use crate::types::FileSystemError;
+
+/// The Docker CLI
+pub const DOCKER: &str = "docker";
+
+/// Exit statuses of `docker exec` failing itself, or not finding `sh` in the container
+const DOCKER_FAILED: [i32; 3] = [125, 126, 127];
+
+/// `[user@]container` of a `container://` URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContainerTarget {
+    /// Who processes run as; the image's user when `None`
+    pub user: Option<String>,
+    /// A container name or ID
+    pub container: String,
+}
+
+impl ContainerTarget {
+    pub fn parse(authority: &str) -> Result<Self, FileSystemError> {
+        let (user, container) = match authority.rsplit_once('@') {
+            Some((user, container)) => (Some(user.to_string()), container),
+            None => (None, authority),
+        };
+        // Docker's own rule for names, which IDs follow too; it also keeps `-` from starting an option
+        let valid = container.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && container.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+            && !user.as_deref().is_some_and(|user| user.is_empty() || user.starts_with('-'));
+        if !valid {
+            return Err(FileSystemError::UnknownError(format!("Invalid container '{}'", authority)));
+        }
+        Ok(ContainerTarget {
+            user,
+            container: container.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for ContainerTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.user {
+            Some(user) => write!(f, "{}@{}", user, self.container),
+            None => write!(f, "{}", self.container),
+        }
+    }
+}
+
+pub struct DockerExec {
+    target: ContainerTarget,
+}
+
+impl DockerExec {
+    pub fn new(target: ContainerTarget) -> Self {
+        DockerExec { target }
+    }
+}
+
+impl ShellTransport for DockerExec {
+    fn name(&self) -> String {
+        format!("container://{}", self.target)
+    }
+
+    fn client(&self) -> &str {
+        DOCKER
+    }
+
+    fn script_command(&self, script: &str, tty: bool) -> Command {
+        let mut command = Command::new(DOCKER);
+        command.args(["exec", if tty { "-it" } else { "-i" }]);
+        if let Some(user) = &self.target.user {
+            command.arg("--user").arg(user);
+        }
+        command.arg(&self.target.container).args(["sh", "-c", script]);
+        command
+    }
+
+    fn client_error(&self, code: Option<i32>, stderr: &str) -> Option<FileSystemError> {
+        let failed = code.is_some_and(|code| DOCKER_FAILED.contains(&code))
+            || stderr.starts_with("Error response from daemon")
+            || stderr.starts_with("Cannot connect to the Docker daemon");
+        failed.then(|| FileSystemError::IOError(format!("container {}: {}", self.target.container, stderr)))
+    }
+}
@@ -0,0 +1,173 @@
+/**
+ * The file system and processes of the machine the IDE runs on
+ */
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+use super::{EntryKind, FileSystemProvider, ProcessLaunch, ProviderEntry, ProviderStat};
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+
+pub struct LocalProvider;
+
+impl LocalProvider {
+    pub fn new() -> Self {
+        LocalProvider
+    }
+}
+
+impl Default for LocalProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn provider_stat(path: &Path) -> Result<ProviderStat, FileSystemError> {
+    // A dangling link has no target to describe, so it describes itself
+    let metadata = fs::metadata(path).or_else(|_| fs::symlink_metadata(path)).map_err(map_io_error)?;
+    let kind = if metadata.is_dir() {
+        EntryKind::Directory
+    } else if metadata.is_file() {
+        EntryKind::File
+    } else if metadata.file_type().is_symlink() {
+        EntryKind::Symlink
+    } else {
+        EntryKind::Other
+    };
+    Ok(ProviderStat {
+        kind,
+        size: if metadata.is_file() { metadata.len() } else { 0 },
+        modified: metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs()),
+        permissions: permissions(&metadata),
+    })
+}
+
+#[cfg(unix)]
+fn permissions(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn permissions(metadata: &fs::Metadata) -> u32 {
+    match metadata.permissions().readonly() {
+        true => 0o444,
+        false => 0o644,
+    }
+}
+
+/// The user's shell, for terminals
+fn user_shell() -> String {
+    if cfg!(windows) {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    } else {
+        std::env::var("SHELL").ok().filter(|shell| !shell.is_empty()).unwrap_or_else(|| "/bin/sh".to_string())
+    }
+}
+
+impl FileSystemProvider for LocalProvider {
+    fn name(&self) -> String {
+        "local".to_string()
+    }
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError> {
+        provider_stat(Path::new(path))
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        fs::read(path).map_err(map_io_error)
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), FileSystemError> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(map_io_error)?;
+        }
+        fs::write(path, content).map_err(map_io_error)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(path).map_err(map_io_error)? {
+            let entry = entry.map_err(map_io_error)?;
+            // Gone since the directory was read
+            let Ok(stat) = provider_stat(&entry.path()) else {
+                continue;
+            };
+            entries.push(ProviderEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                stat,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn create_directory(&self, path: &str) -> Result<(), FileSystemError> {
+        if fs::symlink_metadata(path).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+        fs::create_dir_all(path).map_err(map_io_error)
+    }
+
+    fn remove(&self, path: &str, recursive: bool) -> Result<(), FileSystemError> {
+        let metadata = fs::symlink_metadata(path).map_err(map_io_error)?;
+        match (metadata.is_dir(), recursive) {
+            (true, true) => fs::remove_dir_all(path).map_err(map_io_error),
+            (true, false) => Err(FileSystemError::InvalidPath),
+            (false, _) => crate::file_system::remove_symlink(Path::new(path)).map_err(map_io_error),
+        }
+    }
+
+    fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileSystemError> {
+        if !overwrite && fs::symlink_metadata(to).is_ok() {
+            return Err(FileSystemError::AlreadyExists);
+        }
+        fs::rename(from, to).map_err(map_io_error)
+    }
+
+    fn command(
+        &self,
+        program: &str,
+        args: &[String],
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        let mut command = Command::new(program);
+        command.args(args).current_dir(cwd).envs(env);
+        Ok(command)
+    }
+
+    fn shell_command(
+        &self,
+        line: &str,
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.args(["/C", line]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", line]);
+            command
+        };
+        command.current_dir(cwd).envs(env);
+        Ok(command)
+    }
+
+    fn terminal(&self, cwd: &str) -> Result<ProcessLaunch, FileSystemError> {
+        Ok(ProcessLaunch {
+            program: user_shell(),
+            args: Vec::new(),
+            cwd: Some(cwd.to_string()),
+        })
+    }
+}
@@ -0,0 +1,298 @@
+/**
+ * File system providers for CodeForge IDE
+ * What the file system service, the watchers, and the task runner need from the machine a workspace is on,
+ * so the workspace can be on this machine or another one
+ *
+ * Local paths go to `LocalProvider`. Paths written as URIs, `ssh://[user@]host[:port]/home/me/project`, go
+ * to the provider connected for that scheme and authority when the URI's workspace was opened. Providers
+ * take paths as they are on their own file system, `/home/me/project` in that example.
+ *
+ * Schemes can also be registered at runtime, which is how plugins contribute virtual file systems like
+ * `zip://` or `memfs://`.
+ *
+ * Files inside a zip or tar archive on this machine are written `archive.zip!/src/main.rs` and served
+ * read-only by `ArchiveProvider`. Running containers are `container://[user@]name/workspaces/project`.
+ *
+ * On Windows, local paths inside WSL distros (`\\wsl.localhost\Ubuntu\home\me`) go to `WslProvider`, which
+ * runs their processes inside the distro.
+ */
+
+mod archive;
+mod container;
+mod local;
+mod shell;
+mod ssh;
+mod wsl;
+
+pub use archive::{is_archive, ArchiveProvider};
+pub use container::{ContainerTarget, DockerExec, DOCKER};
+pub use local::LocalProvider;
+pub use shell::{ShellProvider, ShellTransport};
+pub use ssh::{SshTarget, SshTransport};
+pub use wsl::{WslPath, WslProvider};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::types::FileSystemError;
+
+/// Scheme of URIs for workspaces on another machine over SSH
+pub const SSH_SCHEME: &str = "ssh";
+
+/// Scheme of URIs for workspaces inside running containers
+pub const CONTAINER_SCHEME: &str = "container";
+
+/// Scheme of paths inside archives, whose authority is the archive's local path
+pub const ARCHIVE_SCHEME: &str = "archive";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    File,
+    Directory,
+    /// A link whose target doesn't exist; links that resolve report their target's kind
+    Symlink,
+    Other,
+}
+
+/// What a provider reports about a file or directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderStat {
+    pub kind: EntryKind,
+    pub size: u64,
+    /// Unix time in seconds
+    pub modified: Option<u64>,
+    /// Unix permission bits, e.g. `0o644`
+    pub permissions: u32,
+}
+
+/// An entry of a directory listed by a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderEntry {
+    pub name: String,
+    pub stat: ProviderStat,
+}
+
+/// How to start a process, for callers like the frontend's terminal that spawn it themselves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessLaunch {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Local working directory; `None` when the arguments take care of it
+    pub cwd: Option<String>,
+}
+
+/// Opens the provider for a URI's authority, for schemes registered at runtime
+pub type OpenProvider =
+    Arc<dyn Fn(&RemoteUri) -> Result<Arc<dyn FileSystemProvider>, FileSystemError> + Send + Sync>;
+
+/// File operations and process execution on the machine a workspace is on
+pub trait FileSystemProvider: Send + Sync {
+    /// Shown in logs and errors, e.g. `local` or `ssh://dev@build-box`
+    fn name(&self) -> String;
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError>;
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError>;
+
+    /// Replace a file's content, creating the file and its parent directories as needed
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), FileSystemError>;
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError>;
+
+    /// Create a directory and its parents; fails with `AlreadyExists` when something is at `path`
+    fn create_directory(&self, path: &str) -> Result<(), FileSystemError>;
+
+    /// Remove a file or link, or with `recursive` a directory and everything in it
+    fn remove(&self, path: &str, recursive: bool) -> Result<(), FileSystemError>;
+
+    /// Fails with `AlreadyExists` when something is at `to`, unless `overwrite`
+    fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileSystemError>;
+
+    /// `path` and every entry under it (only its own entries unless `recursive`) with the path each is
+    /// at, for watching by polling. Subdirectories that can't be listed are left out.
+    fn snapshot(&self, path: &str, recursive: bool) -> Result<Vec<(String, ProviderStat)>, FileSystemError> {
+        let mut entries = vec![(path.to_string(), self.stat(path)?)];
+        let mut pending = vec![path.to_string()];
+        while let Some(directory) = pending.pop() {
+            let listed = match self.list(&directory) {
+                Ok(listed) => listed,
+                Err(e) if directory == path => return Err(e),
+                Err(_) => continue,
+            };
+            for entry in listed {
+                let child = join_path(&directory, &entry.name);
+                if recursive && entry.stat.kind == EntryKind::Directory {
+                    pending.push(child.clone());
+                }
+                entries.push((child, entry.stat));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// A process running `program` with `args` in `cwd`, with `env` added to its environment. Providers
+    /// with nowhere to run processes, like virtual file systems, keep the default.
+    fn command(
+        &self,
+        _program: &str,
+        _args: &[String],
+        _cwd: &str,
+        _env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        Err(self.no_processes())
+    }
+
+    /// A process running `line` with the shell in `cwd`, with `env` added to its environment
+    fn shell_command(
+        &self,
+        _line: &str,
+        _cwd: &str,
+        _env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        Err(self.no_processes())
+    }
+
+    /// How a terminal starts an interactive shell in `cwd`
+    fn terminal(&self, _cwd: &str) -> Result<ProcessLaunch, FileSystemError> {
+        Err(self.no_processes())
+    }
+
+    /// A process whose standard input and output are a TCP connection to `host:port` as seen from the
+    /// provider's machine, for forwarding ports from there
+    fn tunnel(&self, _host: &str, _port: u16) -> Result<Command, FileSystemError> {
+        Err(self.no_processes())
+    }
+
+    fn no_processes(&self) -> FileSystemError {
+        FileSystemError::Unsupported(format!("running processes on {}", self.name()))
+    }
+
+    /// Release connections when the last workspace using the provider closes
+    fn disconnect(&self) {}
+}
+
+/// `name` inside the provider directory `directory`
+pub fn join_path(directory: &str, name: &str) -> String {
+    format!("{}/{}", directory.trim_end_matches('/'), name)
+}
+
+/// Resolve `.` and `..` in a `/`-separated path and collapse repeated separators; `..` above the root is
+/// dropped
+pub(crate) fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+/// A path on a provider's file system written as `scheme://authority/path`, or as `archive!/path` for a
+/// path inside an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUri {
+    pub scheme: String,
+    /// `[user@]host[:port]` for SSH, `[user@]container` for containers, the archive's local path for
+    /// archives; may be empty
+    pub authority: String,
+    /// Absolute and normalized
+    pub path: String,
+}
+
+impl RemoteUri {
+    /// `None` for anything that isn't a URI or a path inside an archive, like local paths; a one-letter
+    /// scheme would be a Windows drive
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::parse_uri(text).or_else(|| Self::parse_archive(text))
+    }
+
+    fn parse_uri(text: &str) -> Option<Self> {
+        let (scheme, rest) = text.split_once("://")?;
+        let valid_scheme = scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !valid_scheme {
+            return None;
+        }
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, "/"),
+        };
+        Some(RemoteUri {
+            scheme: scheme.to_ascii_lowercase(),
+            authority: authority.to_string(),
+            path: normalize_path(path),
+        })
+    }
+
+    /// `archive.zip!/path` or `archive.zip!\path` where the part before the first such `!` is an archive
+    fn parse_archive(text: &str) -> Option<Self> {
+        text.match_indices('!').find_map(|(bang, _)| {
+            let (archive, rest) = (&text[..bang], &text[bang + 1..]);
+            let inside = rest.is_empty() || rest.starts_with(['/', '\\']);
+            (inside && is_archive(archive)).then(|| RemoteUri {
+                scheme: ARCHIVE_SCHEME.to_string(),
+                authority: archive.to_string(),
+                path: normalize_path(&rest.replace('\\', "/")),
+            })
+        })
+    }
+
+    /// `scheme://authority`, which identifies the provider
+    pub fn origin(&self) -> String {
+        format!("{}://{}", self.scheme, self.authority)
+    }
+
+    pub fn with_path(&self, path: &str) -> Self {
+        RemoteUri {
+            path: normalize_path(path),
+            ..self.clone()
+        }
+    }
+
+    pub fn join(&self, name: &str) -> Self {
+        self.with_path(&join_path(&self.path, name))
+    }
+
+    /// Last component; empty for the root
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// Whether this is `root` or inside it, on the same provider
+    pub fn starts_with(&self, root: &RemoteUri) -> bool {
+        let inside = root.path == "/"
+            || self.path == root.path
+            || self.path.strip_prefix(root.path.as_str()).is_some_and(|rest| rest.starts_with('/'));
+        self.scheme == root.scheme && self.authority == root.authority && inside
+    }
+}
+
+impl std::fmt::Display for RemoteUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.scheme.as_str() {
+            ARCHIVE_SCHEME => write!(f, "{}!{}", self.authority, self.path),
+            _ => write!(f, "{}{}", self.origin(), self.path),
+        }
+    }
+}
+
+/// Open a provider for the scheme and authority of `uri`
+pub fn connect(uri: &RemoteUri) -> Result<Arc<dyn FileSystemProvider>, FileSystemError> {
+    match uri.scheme.as_str() {
+        SSH_SCHEME => Ok(Arc::new(ShellProvider::new(SshTransport::new(SshTarget::parse(&uri.authority)?)))),
+        CONTAINER_SCHEME => {
+            Ok(Arc::new(ShellProvider::new(DockerExec::new(ContainerTarget::parse(&uri.authority)?))))
+        }
+        ARCHIVE_SCHEME => Ok(Arc::new(ArchiveProvider::new(uri.authority.clone().into())?)),
+        scheme => Err(FileSystemError::Unsupported(format!("{}:// paths", scheme))),
+    }
+}
@@ -0,0 +1,299 @@
+/**
+ * Files and processes on a machine reached through a command-line client
+ *
+ * File operations are small POSIX shell scripts, which need nothing installed on the other side beyond `sh`,
+ * `stat`, `find`, and `xargs` (GNU, BSD, and BusyBox all do). A `ShellTransport` says how a script gets
+ * there: `ssh` for hosts, `docker exec` for containers.
+ */
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use super::{EntryKind, FileSystemProvider, ProcessLaunch, ProviderEntry, ProviderStat};
+use crate::types::FileSystemError;
+
+/// Exit statuses the scripts report errors with, above those the tools they run use
+const EXIT_NOT_FOUND: i32 = 70;
+const EXIT_WRONG_KIND: i32 = 71;
+const EXIT_EXISTS: i32 = 72;
+const EXIT_PERMISSION_DENIED: i32 = 73;
+
+/// Puts the arguments for `stat` in `$@`: a format printing kind, size, modification time, permissions,
+/// and name separated by `|`, in GNU and BusyBox syntax or else BSD syntax
+const STAT_FORMAT: &str = "if stat -c %s / >/dev/null 2>&1; then set -- -c '%F|%s|%Y|%a|%n'; \
+    else set -- -f '%HT|%z|%m|%Lp|%N'; fi";
+
+/// How scripts reach the machine a `ShellProvider` works on
+pub trait ShellTransport: Send + Sync {
+    /// Shown in logs and errors, e.g. `ssh://dev@build-box`
+    fn name(&self) -> String;
+
+    /// The client program, e.g. `ssh`
+    fn client(&self) -> &str;
+
+    /// A process running `script` with `sh` over there; `tty` attaches it to a terminal
+    fn script_command(&self, script: &str, tty: bool) -> Command;
+
+    /// The error when a script exited with `code` because the client failed rather than the script,
+    /// e.g. the host was unreachable
+    fn client_error(&self, code: Option<i32>, stderr: &str) -> Option<FileSystemError>;
+
+    /// A process connected to `host:port` over there through its standard input and output; by default
+    /// with whichever of `nc`, `socat`, and `bash` is installed
+    fn tunnel_command(&self, host: &str, port: u16) -> Command {
+        let (h, p) = (quote(host), port);
+        let script = format!(
+            "if command -v nc >/dev/null 2>&1; then exec nc {h} {p}; \
+            elif command -v socat >/dev/null 2>&1; then exec socat - TCP:{h}:{p}; \
+            elif command -v bash >/dev/null 2>&1; then \
+            exec bash -c 'exec 3<>\"/dev/tcp/$0/$1\" || exit 1; cat <&3 & exec cat >&3' {h} {p}; fi; \
+            echo 'forwarding ports needs nc, socat, or bash' >&2; exit 127"
+        );
+        self.script_command(&script, false)
+    }
+
+    /// Release whatever the client keeps open between scripts
+    fn disconnect(&self) {}
+}
+
+/// Quote `text` as one word for `sh`
+pub(crate) fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// One line of `stat` output in `STAT_FORMAT`, with its name
+fn parse_stat_line(line: &str) -> Option<(String, ProviderStat)> {
+    let mut fields = line.splitn(5, '|');
+    let kind = fields.next()?.to_lowercase();
+    let size = fields.next()?.trim().parse().ok()?;
+    let modified = fields.next()?.trim().parse::<i64>().ok().and_then(|seconds| u64::try_from(seconds).ok());
+    let permissions = u32::from_str_radix(fields.next()?.trim(), 8).ok()?;
+    let name = fields.next()?.to_string();
+    let kind = if kind.contains("directory") {
+        EntryKind::Directory
+    } else if kind.contains("regular") {
+        EntryKind::File
+    } else if kind.contains("link") {
+        EntryKind::Symlink
+    } else {
+        EntryKind::Other
+    };
+    let stat = ProviderStat {
+        kind,
+        size: if kind == EntryKind::File { size } else { 0 },
+        modified,
+        permissions,
+    };
+    Some((name, stat))
+}
+
+/// `cd` into `cwd` and run `command_line` with `env` set
+fn command_script(command_line: &str, cwd: &str, env: &HashMap<String, String>) -> String {
+    let assignments: String = env
+        .iter()
+        .map(|(name, value)| format!(" {}", quote(&format!("{}={}", name, value))))
+        .collect();
+    format!("cd {} || exit 1; exec env{} {}", quote(cwd), assignments, command_line)
+}
+
+pub struct ShellProvider<T> {
+    transport: T,
+}
+
+impl<T: ShellTransport> ShellProvider<T> {
+    pub fn new(transport: T) -> Self {
+        ShellProvider { transport }
+    }
+
+    /// Run `script` with `input` on its standard input, returning its standard output
+    fn run(&self, script: &str, input: Option<&[u8]>) -> Result<Vec<u8>, FileSystemError> {
+        let mut child = self
+            .transport
+            .script_command(script, false)
+            .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => FileSystemError::Unsupported(format!(
+                    "{} without {} installed",
+                    self.transport.name(),
+                    self.transport.client()
+                )),
+                _ => FileSystemError::IOError(e.to_string()),
+            })?;
+        // Written from another thread so a large file can't deadlock against a full output pipe
+        let writer = match (input, child.stdin.take()) {
+            (Some(input), Some(mut stdin)) => {
+                let input = input.to_vec();
+                Some(thread::spawn(move || stdin.write_all(&input)))
+            }
+            _ => None,
+        };
+        let output = child.wait_with_output().map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        if let Some(writer) = writer {
+            let written = writer.join().unwrap_or_else(|_| Err(io::Error::other("writer panicked")));
+            if output.status.success() {
+                written.map_err(|e| FileSystemError::IOError(e.to_string()))?;
+            }
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        match output.status.code() {
+            Some(0) => Ok(output.stdout),
+            Some(EXIT_NOT_FOUND) => Err(FileSystemError::NotFound),
+            Some(EXIT_WRONG_KIND) => Err(FileSystemError::InvalidPath),
+            Some(EXIT_EXISTS) => Err(FileSystemError::AlreadyExists),
+            Some(EXIT_PERMISSION_DENIED) => Err(FileSystemError::PermissionDenied),
+            code => match self.transport.client_error(code, &stderr) {
+                Some(error) => Err(error),
+                None if stderr.contains("Permission denied") => Err(FileSystemError::PermissionDenied),
+                None => Err(FileSystemError::IOError(stderr)),
+            },
+        }
+    }
+
+    /// Stat lines printed by a script, by name
+    fn run_stat(&self, script: &str) -> Result<Vec<(String, ProviderStat)>, FileSystemError> {
+        let output = self.run(script, None)?;
+        Ok(String::from_utf8_lossy(&output).lines().filter_map(parse_stat_line).collect())
+    }
+}
+
+impl<T: ShellTransport> FileSystemProvider for ShellProvider<T> {
+    fn name(&self) -> String {
+        self.transport.name()
+    }
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError> {
+        let p = quote(path);
+        let script = format!(
+            "{STAT_FORMAT}; [ -e {p} ] || [ -L {p} ] || exit {EXIT_NOT_FOUND}; \
+            stat -L \"$@\" -- {p} 2>/dev/null || exec stat \"$@\" -- {p}"
+        );
+        let mut stats = self.run_stat(&script)?;
+        stats.pop().map(|(_, stat)| stat).ok_or_else(|| FileSystemError::IOError(format!("can't stat {}", path)))
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        let p = quote(path);
+        let script = format!(
+            "[ -e {p} ] || exit {EXIT_NOT_FOUND}; [ -f {p} ] || exit {EXIT_WRONG_KIND}; \
+            [ -r {p} ] || exit {EXIT_PERMISSION_DENIED}; exec cat -- {p}"
+        );
+        self.run(&script, None)
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), FileSystemError> {
+        let p = quote(path);
+        let script = format!(
+            "[ -d {p} ] && exit {EXIT_WRONG_KIND}; [ -e {p} ] && [ ! -w {p} ] && exit {EXIT_PERMISSION_DENIED}; \
+            mkdir -p -- \"$(dirname -- {p})\" && exec cat > {p}"
+        );
+        self.run(&script, Some(content)).map(|_| ())
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError> {
+        let p = quote(path);
+        // Links whose targets are gone fail `stat -L` and are left out
+        let script = format!(
+            "{STAT_FORMAT}; [ -e {p} ] || exit {EXIT_NOT_FOUND}; [ -d {p} ] || exit {EXIT_WRONG_KIND}; \
+            cd {p} || exit {EXIT_PERMISSION_DENIED}; \
+            find . -mindepth 1 -maxdepth 1 -print0 | xargs -0 stat -L \"$@\" -- 2>/dev/null; exit 0"
+        );
+        Ok(self
+            .run_stat(&script)?
+            .into_iter()
+            .map(|(name, stat)| ProviderEntry {
+                name: name.strip_prefix("./").unwrap_or(&name).to_string(),
+                stat,
+            })
+            .collect())
+    }
+
+    fn create_directory(&self, path: &str) -> Result<(), FileSystemError> {
+        let p = quote(path);
+        let script = format!("[ -e {p} ] || [ -L {p} ] && exit {EXIT_EXISTS}; exec mkdir -p -- {p}");
+        self.run(&script, None).map(|_| ())
+    }
+
+    fn remove(&self, path: &str, recursive: bool) -> Result<(), FileSystemError> {
+        let p = quote(path);
+        let exists = format!("[ -e {p} ] || [ -L {p} ] || exit {EXIT_NOT_FOUND}");
+        let script = match recursive {
+            true => format!("{exists}; exec rm -rf -- {p}"),
+            false => {
+                format!("{exists}; [ -d {p} ] && [ ! -L {p} ] && exit {EXIT_WRONG_KIND}; exec rm -f -- {p}")
+            }
+        };
+        self.run(&script, None).map(|_| ())
+    }
+
+    fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileSystemError> {
+        let (from, to) = (quote(from), quote(to));
+        let mut script = format!("[ -e {from} ] || [ -L {from} ] || exit {EXIT_NOT_FOUND}; ");
+        if !overwrite {
+            script.push_str(&format!("[ -e {to} ] || [ -L {to} ] && exit {EXIT_EXISTS}; "));
+        }
+        script.push_str(&format!("exec mv -f -- {from} {to}"));
+        self.run(&script, None).map(|_| ())
+    }
+
+    /// One `find` over the tree rather than a listing per directory
+    fn snapshot(&self, path: &str, recursive: bool) -> Result<Vec<(String, ProviderStat)>, FileSystemError> {
+        let p = quote(path);
+        let depth = if recursive { "" } else { " -maxdepth 1" };
+        let script = format!(
+            "{STAT_FORMAT}; [ -e {p} ] || exit {EXIT_NOT_FOUND}; \
+            find -H {p}{depth} -print0 | xargs -0 stat -L \"$@\" -- 2>/dev/null; exit 0"
+        );
+        self.run_stat(&script)
+    }
+
+    fn command(
+        &self,
+        program: &str,
+        args: &[String],
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        let words: Vec<String> =
+            std::iter::once(program).chain(args.iter().map(String::as_str)).map(quote).collect();
+        Ok(self.transport.script_command(&command_script(&words.join(" "), cwd, env), false))
+    }
+
+    fn shell_command(
+        &self,
+        line: &str,
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        let script = command_script(&format!("sh -c {}", quote(line)), cwd, env);
+        Ok(self.transport.script_command(&script, false))
+    }
+
+    /// The user's login shell over there in a terminal, started in `cwd`; where `$SHELL` isn't set, as
+    /// in most containers, `bash` if it's installed
+    fn terminal(&self, cwd: &str) -> Result<ProcessLaunch, FileSystemError> {
+        let script = format!(
+            "cd {} || exit 1; exec \"${{SHELL:-$(command -v bash || echo sh)}}\" -l",
+            quote(cwd)
+        );
+        let command = self.transport.script_command(&script, true);
+        Ok(ProcessLaunch {
+            program: command.get_program().to_string_lossy().to_string(),
+            args: command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect(),
+            cwd: None,
+        })
+    }
+
+    fn tunnel(&self, host: &str, port: u16) -> Result<Command, FileSystemError> {
+        Ok(self.transport.tunnel_command(host, port))
+    }
+
+    fn disconnect(&self) {
+        self.transport.disconnect();
+    }
+}
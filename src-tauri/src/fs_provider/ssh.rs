@@ -0,0 +1,189 @@
+/**
+ * Files and processes on another machine over SSH
+ *
+ * Everything runs through the system's `ssh` client, so `~/.ssh/config`, keys, the agent, and jump hosts
+ * work as they do in a terminal. On Linux and macOS one connection per host is shared between operations
+ * (OpenSSH's `ControlMaster`), so only the first operation pays for the handshake.
+ *
+ * `ssh` runs in batch mode, so hosts that ask for a password can't be opened; key or agent authentication
+ * has to be set up first.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::shell::{quote, ShellTransport};
+use crate::types::FileSystemError;
+
+/// How long idle shared connections stay open, in seconds
+const CONTROL_PERSIST_SECONDS: u32 = 300;
+
+/// Exit status of `ssh` itself failing, as opposed to the remote command
+const SSH_FAILED: i32 = 255;
+
+/// `[user@]host[:port]` of an `ssh://` URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SshTarget {
+    pub user: Option<String>,
+    /// A host name, address, or `Host` alias from the SSH config
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl SshTarget {
+    pub fn parse(authority: &str) -> Result<Self, FileSystemError> {
+        let invalid = || FileSystemError::UnknownError(format!("Invalid SSH host '{}'", authority));
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) => (Some(user.to_string()), host_port),
+            None => (None, authority),
+        };
+        // `[::1]:2222` for IPv6 addresses
+        let (host, port) = match host_port.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+                (host, rest.strip_prefix(':'))
+            }
+            None => match host_port.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_port, None),
+            },
+        };
+        let port = port.map(|port| port.parse::<u16>().map_err(|_| invalid())).transpose()?;
+        if !is_safe_name(host) || user.as_deref().is_some_and(|user| !is_safe_name(user)) {
+            return Err(invalid());
+        }
+        Ok(SshTarget {
+            user,
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// What `ssh` is told to connect to; `--` goes before it, though `parse` already keeps it from looking
+    /// like an option
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// Directory of the shared connections' sockets, readable by the user alone: anyone who could create a
+/// socket there first would sit between the IDE and every host. `None` where it can't be made private, or
+/// where OpenSSH can't share connections.
+#[cfg(unix)]
+fn control_directory() -> Option<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    // Both are private to the user already; `/tmp` is not
+    let parent = match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(runtime) => PathBuf::from(runtime),
+        None => PathBuf::from(crate::path_utils::home_dir()?).join(".ssh"),
+    };
+    let directory = parent.join("codeforge");
+    if let Err(e) = std::fs::DirBuilder::new().recursive(true).mode(0o700).create(&directory) {
+        tracing::warn!(directory = %directory.display(), error = %e, "SSH connections won't be shared");
+        return None;
+    }
+    // Only the owner may change the mode, so this also makes sure the directory is the user's
+    if let Err(e) = std::fs::set_permissions(&directory, std::fs::Permissions::from_mode(0o700)) {
+        tracing::warn!(directory = %directory.display(), error = %e, "SSH connections won't be shared");
+        return None;
+    }
+    Some(directory)
+}
+
+#[cfg(not(unix))]
+fn control_directory() -> Option<PathBuf> {
+    None
+}
+
+/// A user or host that `ssh` can't mistake for an option (`-oProxyCommand=...` runs a command) and that
+/// doesn't smuggle in more arguments or a config line
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('-') && !name.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+pub struct SshTransport {
+    target: SshTarget,
+    /// Socket of the shared connection; `None` where OpenSSH can't share connections
+    control_path: Option<PathBuf>,
+}
+
+impl SshTransport {
+    pub fn new(target: SshTarget) -> Self {
+        // `%C` is a hash of the connection, which keeps the socket path short enough for Unix sockets
+        let control_path = control_directory().map(|directory| directory.join("%C"));
+        SshTransport { target, control_path }
+    }
+
+    /// `ssh` with the options every invocation shares; options after the destination would be taken for
+    /// the remote command
+    fn options(&self) -> Command {
+        let mut command = Command::new("ssh");
+        command.args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=15", "-o", "ServerAliveInterval=15"]);
+        if let Some(control_path) = &self.control_path {
+            command
+                .args(["-o", "ControlMaster=auto"])
+                .arg("-o")
+                .arg(format!("ControlPath={}", control_path.display()))
+                .arg("-o")
+                .arg(format!("ControlPersist={}", CONTROL_PERSIST_SECONDS));
+        }
+        if let Some(port) = self.target.port {
+            command.arg("-p").arg(port.to_string());
+        }
+        command
+    }
+}
+
+impl ShellTransport for SshTransport {
+    fn name(&self) -> String {
+        format!("ssh://{}", self.target.destination())
+    }
+
+    fn client(&self) -> &str {
+        "ssh"
+    }
+
+    /// Runs the script with `sh` on the host, whatever the login shell there is
+    fn script_command(&self, script: &str, tty: bool) -> Command {
+        let mut command = self.options();
+        command
+            .arg(if tty { "-t" } else { "-T" })
+            .arg("--")
+            .arg(self.target.destination())
+            .arg(format!("sh -c {}", quote(script)));
+        command
+    }
+
+    /// `ssh -W`, which needs nothing installed on the host
+    fn tunnel_command(&self, host: &str, port: u16) -> Command {
+        let mut command = self.options();
+        command.arg("-W").arg(format!("{}:{}", host, port)).arg("--").arg(self.target.destination());
+        command
+    }
+
+    fn client_error(&self, code: Option<i32>, stderr: &str) -> Option<FileSystemError> {
+        (code == Some(SSH_FAILED))
+            .then(|| FileSystemError::IOError(format!("ssh {}: {}", self.target.destination(), stderr)))
+    }
+
+    /// Close the shared connection instead of leaving it open until `ControlPersist` runs out
+    fn disconnect(&self) {
+        if self.control_path.is_none() {
+            return;
+        }
+        let mut command = self.options();
+        command
+            .args(["-O", "exit", "--"])
+            .arg(self.target.destination())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Err(e) = command.status() {
+            tracing::warn!(host = %self.target.host, error = %e, "closing SSH connection failed");
+        }
+    }
+}
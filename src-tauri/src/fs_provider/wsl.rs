@@ -0,0 +1,172 @@
+/**
+ * Workspaces inside WSL distros on Windows
+ *
+ * Their files are reachable from Windows at `\\wsl.localhost\<distro>\...` (or `\\wsl$\<distro>\...`),
+ * which is how they are read and written. Processes are another matter: Windows tools running on those
+ * paths are slow and see a different toolchain than the distro's, so commands and terminals run inside the
+ * distro with `wsl.exe`, in the Linux path of their working directory.
+ */
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use super::shell::{ShellProvider, ShellTransport};
+use super::{FileSystemProvider, LocalProvider, ProcessLaunch, ProviderEntry, ProviderStat};
+use crate::types::FileSystemError;
+
+/// The WSL launcher
+const WSL: &str = "wsl.exe";
+
+/// Exit status of `wsl.exe` failing itself, e.g. for a distro that isn't installed
+const WSL_FAILED: i32 = -1;
+
+/// Hosts Windows serves WSL distros' files under
+const WSL_HOSTS: [&str; 2] = ["wsl.localhost", "wsl$"];
+
+/// A path inside a WSL distro, from its Windows form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslPath {
+    pub distro: String,
+    /// The Linux path, e.g. `/home/me/project`
+    pub path: String,
+}
+
+impl WslPath {
+    /// `\\wsl.localhost\Ubuntu\home\me` or `\\wsl$\Ubuntu\home\me`, with either slash and with or without
+    /// the `\\?\UNC\` prefix; `None` for anything else
+    pub fn parse(path: &str) -> Option<Self> {
+        let path = path.replace('/', "\\");
+        let rest = path
+            .strip_prefix(r"\\?\UNC\")
+            .or_else(|| path.strip_prefix(r"\\?\unc\"))
+            .or_else(|| path.strip_prefix(r"\\"))?;
+        let mut parts = rest.split('\\');
+        let host = parts.next()?;
+        if !WSL_HOSTS.iter().any(|wsl| wsl.eq_ignore_ascii_case(host)) {
+            return None;
+        }
+        let distro = parts.next().filter(|distro| !distro.is_empty())?;
+        let parts: Vec<&str> = parts.filter(|part| !part.is_empty()).collect();
+        Some(WslPath {
+            distro: distro.to_string(),
+            path: format!("/{}", parts.join("/")),
+        })
+    }
+
+    /// The path as Windows reaches it
+    pub fn to_windows(&self) -> String {
+        format!(r"\\{}\{}{}", WSL_HOSTS[0], self.distro, self.path.replace('/', "\\"))
+    }
+}
+
+pub struct WslTransport {
+    distro: String,
+}
+
+impl ShellTransport for WslTransport {
+    fn name(&self) -> String {
+        format!(r"\\{}\{}", WSL_HOSTS[0], self.distro)
+    }
+
+    fn client(&self) -> &str {
+        WSL
+    }
+
+    /// `wsl.exe` gives processes a terminal whenever it has one itself, so `tty` needs no flag
+    fn script_command(&self, script: &str, _tty: bool) -> Command {
+        let mut command = Command::new(WSL);
+        // Otherwise its own messages are UTF-16
+        command.env("WSL_UTF8", "1").args(["--distribution", &self.distro, "--exec", "sh", "-c", script]);
+        command
+    }
+
+    fn client_error(&self, code: Option<i32>, stderr: &str) -> Option<FileSystemError> {
+        (code == Some(WSL_FAILED)).then(|| FileSystemError::IOError(format!("WSL {}: {}", self.distro, stderr)))
+    }
+}
+
+/// Files through Windows' share of the distro, processes through `wsl.exe`
+pub struct WslProvider {
+    distro: String,
+    local: LocalProvider,
+    shell: ShellProvider<WslTransport>,
+}
+
+impl WslProvider {
+    pub fn new(distro: &str) -> Self {
+        WslProvider {
+            distro: distro.to_string(),
+            local: LocalProvider::new(),
+            shell: ShellProvider::new(WslTransport {
+                distro: distro.to_string(),
+            }),
+        }
+    }
+
+    /// The Linux path of a working directory in this distro
+    fn linux_path(&self, cwd: &str) -> Result<String, FileSystemError> {
+        match WslPath::parse(cwd) {
+            Some(wsl) if wsl.distro.eq_ignore_ascii_case(&self.distro) => Ok(wsl.path),
+            Some(_) => Err(FileSystemError::InvalidPath),
+            None if cwd.starts_with('/') => Ok(cwd.to_string()),
+            None => Err(FileSystemError::InvalidPath),
+        }
+    }
+}
+
+impl FileSystemProvider for WslProvider {
+    fn name(&self) -> String {
+        self.shell.name()
+    }
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError> {
+        self.local.stat(path)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        self.local.read(path)
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), FileSystemError> {
+        self.local.write(path, content)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError> {
+        self.local.list(path)
+    }
+
+    fn create_directory(&self, path: &str) -> Result<(), FileSystemError> {
+        self.local.create_directory(path)
+    }
+
+    fn remove(&self, path: &str, recursive: bool) -> Result<(), FileSystemError> {
+        self.local.remove(path, recursive)
+    }
+
+    fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileSystemError> {
+        self.local.rename(from, to, overwrite)
+    }
+
+    fn command(
+        &self,
+        program: &str,
+        args: &[String],
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        self.shell.command(program, args, &self.linux_path(cwd)?, env)
+    }
+
+    fn shell_command(
+        &self,
+        line: &str,
+        cwd: &str,
+        env: &HashMap<String, String>,
+    ) -> Result<Command, FileSystemError> {
+        self.shell.shell_command(line, &self.linux_path(cwd)?, env)
+    }
+
+    fn terminal(&self, cwd: &str) -> Result<ProcessLaunch, FileSystemError> {
+        self.shell.terminal(&self.linux_path(cwd)?)
+    }
+}
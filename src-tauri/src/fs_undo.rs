@@ -0,0 +1,139 @@
+/**
+ * Undo and redo for file explorer operations
+ * Creates, renames, moves, and deletes made from the explorer are recorded as they happen; undoing one
+ * applies its inverse. Deletes go through the OS trash so they can be brought back.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::file_system::FileSystemService;
+use crate::types::FileSystemError;
+
+/// Operations kept on each stack; the oldest are forgotten beyond this
+const MAX_UNDO_OPERATIONS: usize = 100;
+
+/// A file operation that can be reversed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FsOperation {
+    /// A new file or directory was created
+    Create { path: String },
+    /// Renamed or moved
+    Rename { from: String, to: String },
+    /// Moved to the OS trash
+    Trash { path: String },
+    /// Brought back from the OS trash
+    Restore { path: String },
+    /// Several operations done as one, e.g. a bulk rename; undone and redone together
+    Batch { operations: Vec<FsOperation> },
+}
+
+impl FsOperation {
+    /// The operation that reverses this one. A created entry is undone by trashing it, not deleting it,
+    /// so redo can restore it with whatever was written to it since.
+    fn inverse(&self) -> FsOperation {
+        match self {
+            FsOperation::Create { path } | FsOperation::Restore { path } => {
+                FsOperation::Trash { path: path.clone() }
+            }
+            FsOperation::Trash { path } => FsOperation::Restore { path: path.clone() },
+            FsOperation::Rename { from, to } => FsOperation::Rename {
+                from: to.clone(),
+                to: from.clone(),
+            },
+            FsOperation::Batch { operations } => FsOperation::Batch {
+                operations: operations.iter().rev().map(FsOperation::inverse).collect(),
+            },
+        }
+    }
+
+    fn apply(&self, fs: &FileSystemService) -> Result<(), FileSystemError> {
+        match self {
+            // Only ever recorded, never replayed: the inverse of a trash is a restore
+            FsOperation::Create { .. } => Ok(()),
+            FsOperation::Rename { from, to } => fs.rename(from, to).map(|_| ()),
+            FsOperation::Trash { path } => fs.move_to_trash(path).map(|_| ()),
+            FsOperation::Restore { path } => fs.restore_from_trash(path).map(|_| ()),
+            // All or nothing: a failure part way puts back what was already done
+            FsOperation::Batch { operations } => {
+                for (index, operation) in operations.iter().enumerate() {
+                    if let Err(e) = operation.apply(fs) {
+                        for done in operations[..index].iter().rev() {
+                            if let Err(rollback) = done.inverse().apply(fs) {
+                                tracing::warn!(error = %rollback, "rolling back a batch operation failed");
+                            }
+                        }
+                        return Err(e);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct UndoStacks {
+    undo: Vec<FsOperation>,
+    redo: Vec<FsOperation>,
+}
+
+fn push_bounded(stack: &mut Vec<FsOperation>, operation: FsOperation) {
+    stack.push(operation);
+    if stack.len() > MAX_UNDO_OPERATIONS {
+        stack.remove(0);
+    }
+}
+
+pub struct FsUndoService {
+    stacks: Arc<Mutex<UndoStacks>>,
+}
+
+impl FsUndoService {
+    pub fn new() -> Self {
+        Self {
+            stacks: Arc::new(Mutex::new(UndoStacks::default())),
+        }
+    }
+
+    /// Record an operation that just succeeded; anything undone before it can no longer be redone
+    pub fn record(&self, operation: FsOperation) {
+        let mut stacks = self.stacks.lock().unwrap();
+        push_bounded(&mut stacks.undo, operation);
+        stacks.redo.clear();
+    }
+
+    /// Reverse the newest operation, returning what was done to reverse it, or `None` when there is
+    /// nothing to undo. A failed undo stays on the stack.
+    pub fn undo(&self, fs: &FileSystemService) -> Result<Option<FsOperation>, FileSystemError> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let Some(operation) = stacks.undo.last() else {
+            return Ok(None);
+        };
+        let inverse = operation.inverse();
+        inverse.apply(fs)?;
+        stacks.undo.pop();
+        push_bounded(&mut stacks.redo, inverse.clone());
+        Ok(Some(inverse))
+    }
+
+    /// Reverse the newest undo, returning what was done, or `None` when there is nothing to redo
+    pub fn redo(&self, fs: &FileSystemService) -> Result<Option<FsOperation>, FileSystemError> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let Some(operation) = stacks.redo.last() else {
+            return Ok(None);
+        };
+        let inverse = operation.inverse();
+        inverse.apply(fs)?;
+        stacks.redo.pop();
+        push_bounded(&mut stacks.undo, inverse.clone());
+        Ok(Some(inverse))
+    }
+}
+
+impl Default for FsUndoService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
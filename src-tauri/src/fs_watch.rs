@@ -0,0 +1,789 @@
+/**
+ * Directory and file watching for CodeForge IDE
+ * Forwards file system changes under a directory to the frontend and keeps the watch alive: when the
+ * backend's event queue overflows, or the directory is deleted, recreated, or replaced, the watch is
+ * re-registered and the frontend is told to re-read the whole subtree instead of trusting the events it got
+ *
+ * Each watch runs a thread that owns its notify watcher, so it can drop and recreate it, and checks the
+ * directory between events to catch deletions the backend never reports.
+ *
+ * Native events miss changes made on the other side of network shares and VM shared folders, so paths on
+ * those are polled instead, as the `file_watcher_mode` preference allows.
+ *
+ * A single file is watched through its directory, so deleting it, renaming it, and replacing it with a
+ * rename (as atomic saves do) are all seen. Bursts of events become one `fs://file-change`, and changes
+ * that leave the file as the IDE last read or saved it, such as its own saves, aren't reported.
+ *
+ * Paths on other machines, written as URIs, are watched by listing them through their provider at the
+ * polling interval and comparing the listings.
+ */
+
+use notify::event::ModifyKind;
+use notify::{Config, EventKind, PollWatcher, RecursiveMode, Watcher};
+use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::now_millis;
+use crate::file_system::FileSystemService;
+use crate::fs_provider::{EntryKind, FileSystemProvider, ProviderStat, RemoteUri};
+use crate::mounts;
+use crate::settings::{SettingsLayer, SettingsService};
+use crate::types::FileSystemError;
+
+/// Event carrying changes under a watched directory
+pub const FS_CHANGE_EVENT: &str = "fs://change";
+
+/// Event telling the frontend that events for a watched directory were lost and it should be re-read
+pub const FS_RESCAN_EVENT: &str = "fs://rescan";
+
+/// Event carrying a change to a watched file made outside the IDE
+pub const FS_FILE_CHANGE_EVENT: &str = "fs://file-change";
+
+/// Quiet period after a watched file's last event before the change is reported
+const FILE_CHANGE_DELAY: Duration = Duration::from_millis(100);
+
+/// How often a watch checks that its directory is still the one it registered
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest wait between attempts to re-register a watch that keeps failing
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchHealth {
+    Healthy,
+    /// The directory is gone; the watch resumes when it comes back
+    Lost,
+    /// Re-registering failed and is being retried
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchBackend {
+    /// The OS's change notifications
+    Native,
+    /// Rescanning the directory every `poll_interval_ms`
+    Polling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchKind {
+    Directory,
+    File,
+}
+
+/// State of a directory or file watch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchStatus {
+    pub id: String,
+    pub path: String,
+    pub kind: WatchKind,
+    pub recursive: bool,
+    pub backend: WatchBackend,
+    pub poll_interval_ms: Option<u64>,
+    /// The path is on a network share or VM shared folder
+    pub network: bool,
+    pub health: WatchHealth,
+    /// Times the watch was registered again after the directory came back or was replaced
+    pub rewatch_count: u32,
+    /// Times the backend dropped events because its queue was full
+    pub overflow_count: u32,
+    pub error_count: u32,
+    pub last_error: Option<String>,
+    /// Unix time in milliseconds
+    pub started_at: u64,
+    pub last_event_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+/// Paths that changed under a watched directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsChangeEvent {
+    pub watch_id: String,
+    pub kind: ChangeKind,
+    /// For a rename, the old path then the new one when the backend reports both
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RescanReason {
+    /// The backend dropped events
+    Overflow,
+    /// The directory was deleted
+    Removed,
+    /// The directory was recreated or replaced and is watched again
+    Rewatched,
+}
+
+/// Everything under `path` may have changed without events being sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsRescanEvent {
+    pub watch_id: String,
+    pub path: String,
+    pub reason: RescanReason,
+    pub status: WatchStatus,
+}
+
+/// A watched file was changed, deleted, renamed, or recreated by something other than the IDE
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChangeEvent {
+    pub watch_id: String,
+    pub path: String,
+    /// `Created` when the file is back after being deleted
+    pub kind: ChangeKind,
+    /// Where the file went, for a rename the backend reported with both names
+    pub new_path: Option<String>,
+    /// Modification time on disk in Unix milliseconds, when the file exists
+    pub modified: Option<u64>,
+}
+
+enum WatchMessage {
+    Event(notify::Result<notify::Event>),
+    Stop,
+}
+
+/// A running directory or file watch; dropping it stops the watch
+pub struct DirectoryWatch {
+    status: Arc<Mutex<WatchStatus>>,
+    control: Sender<WatchMessage>,
+}
+
+impl DirectoryWatch {
+    pub fn status(&self) -> WatchStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+impl Drop for DirectoryWatch {
+    fn drop(&mut self) {
+        let _ = self.control.send(WatchMessage::Stop);
+    }
+}
+
+/// What identifies a directory on disk, so a directory replaced by another of the same name is noticed;
+/// `None` when there is no directory at `path`
+#[cfg(unix)]
+fn directory_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::metadata(path).ok().filter(|metadata| metadata.is_dir())?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn directory_identity(path: &Path) -> Option<(u64, u64)> {
+    fs::metadata(path).ok().filter(|metadata| metadata.is_dir()).map(|_| (0, 0))
+}
+
+/// How a watch is registered, from the preferences of the workspace it's in
+#[derive(Debug, Clone, Copy)]
+struct WatchConfig {
+    backend: WatchBackend,
+    network: bool,
+    poll_interval: Duration,
+    compare_contents: bool,
+}
+
+impl WatchConfig {
+    /// Unreadable settings fall back to the defaults rather than failing the watch
+    fn configured(app: &AppHandle, root: &Path) -> Self {
+        let path = root.to_string_lossy().to_string();
+        let workspace = app.state::<FileSystemService>().workspace_root(&path);
+        let workspace = workspace.map(|root| root.to_string_lossy().to_string());
+        let scope = match workspace {
+            Some(_) => SettingsLayer::Workspace,
+            None => SettingsLayer::User,
+        };
+        let effective = app.state::<SettingsService>().effective_settings(app, scope, workspace.as_deref());
+        let settings = match effective {
+            Ok(effective) => effective.settings,
+            Err(e) => {
+                tracing::warn!(path = %path, error = %e, "reading file watcher settings failed");
+                Default::default()
+            }
+        };
+        let setting = |key: &str| settings.get(key).map(|setting| &setting.value);
+
+        let network = mounts::is_network_path(root);
+        let backend = match setting("file_watcher_mode").and_then(Value::as_str).unwrap_or("auto") {
+            "polling" => WatchBackend::Polling,
+            "native" => WatchBackend::Native,
+            _ if network => WatchBackend::Polling,
+            _ => WatchBackend::Native,
+        };
+        let poll_interval = setting("file_watcher_poll_interval").and_then(Value::as_u64).unwrap_or(2000);
+        WatchConfig {
+            backend,
+            network,
+            poll_interval: Duration::from_millis(poll_interval),
+            compare_contents: setting("file_watcher_poll_contents").and_then(Value::as_bool).unwrap_or(false),
+        }
+    }
+}
+
+fn register(
+    root: &Path,
+    recursive: bool,
+    config: &WatchConfig,
+    sender: Sender<WatchMessage>,
+) -> notify::Result<Box<dyn Watcher + Send>> {
+    let handler = move |result: notify::Result<notify::Event>| {
+        let _ = sender.send(WatchMessage::Event(result));
+    };
+    let mut watcher: Box<dyn Watcher + Send> = match config.backend {
+        WatchBackend::Native => Box::new(notify::recommended_watcher(handler)?),
+        WatchBackend::Polling => Box::new(PollWatcher::new(
+            handler,
+            Config::default()
+                .with_poll_interval(config.poll_interval)
+                .with_compare_contents(config.compare_contents),
+        )?),
+    };
+    let mode = match recursive {
+        true => RecursiveMode::Recursive,
+        false => RecursiveMode::NonRecursive,
+    };
+    watcher.watch(root, mode)?;
+    Ok(watcher)
+}
+
+fn change_kind(kind: &EventKind) -> Option<ChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(ChangeKind::Created),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(ChangeKind::Renamed),
+        EventKind::Modify(_) => Some(ChangeKind::Modified),
+        EventKind::Remove(_) => Some(ChangeKind::Removed),
+        _ => None,
+    }
+}
+
+/// Start watching `root` and forwarding its changes as `id`
+pub fn watch_directory(
+    app: &AppHandle,
+    id: String,
+    root: PathBuf,
+    recursive: bool,
+) -> Result<DirectoryWatch, FileSystemError> {
+    start(app, id, root, recursive, None)
+}
+
+/// Start watching the file at `path` and reporting changes made outside the IDE as `id`
+pub fn watch_file(app: &AppHandle, id: String, path: PathBuf) -> Result<DirectoryWatch, FileSystemError> {
+    let directory = path.parent().map(Path::to_path_buf).ok_or(FileSystemError::InvalidPath)?;
+    let target = FileTarget {
+        seen: file_fingerprint(&path),
+        path,
+        flush_at: None,
+        renamed_to: None,
+    };
+    start(app, id, directory, false, Some(target))
+}
+
+fn start(
+    app: &AppHandle,
+    id: String,
+    root: PathBuf,
+    recursive: bool,
+    file: Option<FileTarget>,
+) -> Result<DirectoryWatch, FileSystemError> {
+    let identity = directory_identity(&root).ok_or(FileSystemError::NotFound)?;
+    let (control, messages) = mpsc::channel();
+    let mut config = WatchConfig::configured(app, &root);
+    let watcher = match register(&root, recursive, &config, control.clone()) {
+        Ok(watcher) => watcher,
+        // Out of inotify watches and the like; polling needs no OS resources
+        Err(e) if config.backend == WatchBackend::Native => {
+            tracing::warn!(path = %root.display(), error = %e, "native watch failed, polling instead");
+            config.backend = WatchBackend::Polling;
+            register(&root, recursive, &config, control.clone())
+                .map_err(|e| FileSystemError::IOError(e.to_string()))?
+        }
+        Err(e) => return Err(FileSystemError::IOError(e.to_string())),
+    };
+
+    let (path, kind) = match &file {
+        Some(file) => (file.path.to_string_lossy().to_string(), WatchKind::File),
+        None => (root.to_string_lossy().to_string(), WatchKind::Directory),
+    };
+    let status = Arc::new(Mutex::new(WatchStatus {
+        id,
+        path,
+        kind,
+        recursive,
+        backend: config.backend,
+        poll_interval_ms: match config.backend {
+            WatchBackend::Polling => Some(config.poll_interval.as_millis() as u64),
+            WatchBackend::Native => None,
+        },
+        network: config.network,
+        health: WatchHealth::Healthy,
+        rewatch_count: 0,
+        overflow_count: 0,
+        error_count: 0,
+        last_error: None,
+        started_at: now_millis(),
+        last_event_at: None,
+    }));
+    let mut worker = WatchWorker {
+        app: app.clone(),
+        root,
+        recursive,
+        config,
+        status: status.clone(),
+        sender: control.clone(),
+        watcher: Some(watcher),
+        identity,
+        retry_interval: HEALTH_CHECK_INTERVAL,
+        next_check: Instant::now() + HEALTH_CHECK_INTERVAL,
+        file,
+    };
+    thread::spawn(move || loop {
+        let flush_at = worker.file.as_ref().and_then(|file| file.flush_at);
+        let wake_at = flush_at.map_or(worker.next_check, |flush_at| flush_at.min(worker.next_check));
+        match messages.recv_timeout(wake_at.saturating_duration_since(Instant::now())) {
+            Ok(WatchMessage::Event(result)) => worker.handle(result),
+            Ok(WatchMessage::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        // Checked even while events keep coming, which is when queues overflow
+        if Instant::now() >= worker.next_check {
+            worker.check();
+        }
+        let flush_at = worker.file.as_ref().and_then(|file| file.flush_at);
+        if flush_at.is_some_and(|flush_at| Instant::now() >= flush_at) {
+            worker.flush_file();
+        }
+    });
+
+    Ok(DirectoryWatch { status, control })
+}
+
+/// Size and modification time of a file, or `None` when there's no file at `path`
+fn file_fingerprint(path: &Path) -> Option<(u64, Option<SystemTime>)> {
+    let metadata = fs::metadata(path).ok().filter(|metadata| metadata.is_file())?;
+    Some((metadata.len(), metadata.modified().ok()))
+}
+
+/// The watched file of a file watch
+struct FileTarget {
+    path: PathBuf,
+    /// When the current burst of events is reported; `None` when nothing is pending
+    flush_at: Option<Instant>,
+    renamed_to: Option<PathBuf>,
+    /// Fingerprint when last reported or started, so events that changed nothing are dropped
+    seen: Option<(u64, Option<SystemTime>)>,
+}
+
+/// State owned by a watch's thread
+struct WatchWorker {
+    app: AppHandle,
+    root: PathBuf,
+    recursive: bool,
+    config: WatchConfig,
+    status: Arc<Mutex<WatchStatus>>,
+    sender: Sender<WatchMessage>,
+    /// `None` while the directory is lost or re-registering is failing
+    watcher: Option<Box<dyn Watcher + Send>>,
+    identity: (u64, u64),
+    retry_interval: Duration,
+    next_check: Instant,
+    file: Option<FileTarget>,
+}
+
+impl WatchWorker {
+    fn id(&self) -> String {
+        self.status.lock().unwrap().id.clone()
+    }
+
+    fn handle(&mut self, result: notify::Result<notify::Event>) {
+        // Events queued by a watcher that was since dropped describe a directory that's gone
+        if self.watcher.is_none() {
+            return;
+        }
+        let event = match result {
+            Ok(event) => event,
+            Err(e) => {
+                let mut status = self.status.lock().unwrap();
+                status.error_count += 1;
+                status.last_error = Some(e.to_string());
+                drop(status);
+                // Whether the watch survived the error is for the next check to find out
+                self.next_check = Instant::now();
+                return;
+            }
+        };
+        self.status.lock().unwrap().last_event_at = Some(now_millis());
+
+        if event.need_rescan() {
+            self.status.lock().unwrap().overflow_count += 1;
+            self.rescan(RescanReason::Overflow);
+            return;
+        }
+        let Some(kind) = change_kind(&event.kind) else {
+            return;
+        };
+        if let Some(file) = &mut self.file {
+            if event.paths.contains(&file.path) {
+                if kind == ChangeKind::Renamed && event.paths.len() == 2 && event.paths[0] == file.path {
+                    file.renamed_to = Some(event.paths[1].clone());
+                }
+                file.flush_at = Some(Instant::now() + FILE_CHANGE_DELAY);
+            }
+        }
+        let root_moved =
+            matches!(kind, ChangeKind::Removed | ChangeKind::Renamed) && event.paths.contains(&self.root);
+        if self.file.is_none() {
+            let _ = self.app.emit(
+                FS_CHANGE_EVENT,
+                FsChangeEvent {
+                    watch_id: self.id(),
+                    kind,
+                    paths: event.paths.iter().map(|path| path.to_string_lossy().to_string()).collect(),
+                },
+            );
+        }
+        // Events from here on would describe the directory in its new place, so re-register right away
+        if root_moved {
+            self.next_check = Instant::now();
+        }
+    }
+
+    /// Notice the directory going away, coming back, or being replaced, and re-register the watch
+    fn check(&mut self) {
+        let identity = directory_identity(&self.root);
+        let health = self.status.lock().unwrap().health;
+        match (health, identity) {
+            (WatchHealth::Healthy, Some(identity)) if identity == self.identity => {}
+            (WatchHealth::Healthy, None) => {
+                self.watcher = None;
+                self.status.lock().unwrap().health = WatchHealth::Lost;
+                self.rescan(RescanReason::Removed);
+            }
+            (_, Some(identity)) => self.rewatch(identity),
+            (WatchHealth::Failed, None) => self.status.lock().unwrap().health = WatchHealth::Lost,
+            (WatchHealth::Lost, None) => {}
+        }
+        let interval = match self.status.lock().unwrap().health {
+            WatchHealth::Failed => self.retry_interval,
+            _ => HEALTH_CHECK_INTERVAL,
+        };
+        self.next_check = Instant::now() + interval;
+    }
+
+    fn rewatch(&mut self, identity: (u64, u64)) {
+        // The old watcher must go first; some backends refuse to watch a path twice
+        self.watcher = None;
+        match register(&self.root, self.recursive, &self.config, self.sender.clone()) {
+            Ok(watcher) => {
+                self.watcher = Some(watcher);
+                self.identity = identity;
+                self.retry_interval = HEALTH_CHECK_INTERVAL;
+                let mut status = self.status.lock().unwrap();
+                status.health = WatchHealth::Healthy;
+                status.rewatch_count += 1;
+                drop(status);
+                self.rescan(RescanReason::Rewatched);
+            }
+            Err(e) => {
+                self.retry_interval = (self.retry_interval * 2).min(MAX_RETRY_INTERVAL);
+                let mut status = self.status.lock().unwrap();
+                status.error_count += 1;
+                status.last_error = Some(e.to_string());
+                if status.health != WatchHealth::Failed {
+                    tracing::warn!(path = %status.path, error = %e, "re-registering directory watch failed");
+                }
+                status.health = WatchHealth::Failed;
+            }
+        }
+    }
+
+    /// Report what happened to the watched file since it was last looked at, unless the file is as the IDE
+    /// last read or saved it
+    fn flush_file(&mut self) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        file.flush_at = None;
+        let renamed_to = file.renamed_to.take();
+        let fingerprint = file_fingerprint(&file.path);
+        let kind = match (file.seen, fingerprint) {
+            (None, None) => return,
+            (Some(_), None) if renamed_to.is_some() => ChangeKind::Renamed,
+            (Some(_), None) => ChangeKind::Removed,
+            (None, Some(_)) => ChangeKind::Created,
+            (Some(seen), Some(current)) if seen == current => return,
+            (Some(_), Some(_)) => ChangeKind::Modified,
+        };
+        file.seen = fingerprint;
+        let own_version =
+            fingerprint.is_some() && self.app.state::<FileSystemService>().matches_known_version(&file.path);
+        if own_version {
+            return;
+        }
+        let event = FileChangeEvent {
+            watch_id: self.status.lock().unwrap().id.clone(),
+            path: file.path.to_string_lossy().to_string(),
+            kind,
+            new_path: renamed_to.map(|path| path.to_string_lossy().to_string()),
+            modified: fingerprint
+                .and_then(|(_, modified)| modified)
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis() as u64),
+        };
+        let _ = self.app.emit(FS_FILE_CHANGE_EVENT, event);
+    }
+
+    fn rescan(&mut self, reason: RescanReason) {
+        // A file watch has one file to look at rather than a subtree to re-read
+        if let Some(file) = &mut self.file {
+            file.flush_at = Some(Instant::now());
+            return;
+        }
+        let status = self.status.lock().unwrap().clone();
+        let _ = self.app.emit(
+            FS_RESCAN_EVENT,
+            FsRescanEvent {
+                watch_id: status.id.clone(),
+                path: status.path.clone(),
+                reason,
+                status,
+            },
+        );
+    }
+}
+
+/// Start watching a path on a provider's file system by polling it, reporting changes as `id` with paths
+/// written as URIs
+pub fn watch_provided(
+    app: &AppHandle,
+    id: String,
+    provider: Arc<dyn FileSystemProvider>,
+    uri: RemoteUri,
+    recursive: bool,
+    kind: WatchKind,
+) -> Result<DirectoryWatch, FileSystemError> {
+    let expected = match kind {
+        WatchKind::Directory => EntryKind::Directory,
+        WatchKind::File => EntryKind::File,
+    };
+    if provider.stat(&uri.path)?.kind != expected {
+        return Err(FileSystemError::InvalidPath);
+    }
+    // URI workspaces have no settings file on this machine, so the user's settings apply
+    let poll_interval = WatchConfig::configured(app, Path::new(&uri.to_string())).poll_interval;
+    let mut worker = ProvidedWorker {
+        app: app.clone(),
+        provider,
+        uri: uri.clone(),
+        recursive,
+        kind,
+        status: Arc::new(Mutex::new(WatchStatus {
+            id,
+            path: uri.to_string(),
+            kind,
+            recursive,
+            backend: WatchBackend::Polling,
+            poll_interval_ms: Some(poll_interval.as_millis() as u64),
+            network: true,
+            health: WatchHealth::Healthy,
+            rewatch_count: 0,
+            overflow_count: 0,
+            error_count: 0,
+            last_error: None,
+            started_at: now_millis(),
+            last_event_at: None,
+        })),
+        known: HashMap::new(),
+        poll_interval,
+        retry_interval: poll_interval,
+    };
+    worker.known = worker.entries()?;
+
+    let (control, messages) = mpsc::channel();
+    let status = worker.status.clone();
+    thread::spawn(move || loop {
+        match messages.recv_timeout(worker.wait()) {
+            Ok(WatchMessage::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+            Ok(WatchMessage::Event(_)) | Err(RecvTimeoutError::Timeout) => worker.poll(),
+        }
+    });
+
+    Ok(DirectoryWatch { status, control })
+}
+
+/// State owned by the thread of a watch on a provider's file system
+struct ProvidedWorker {
+    app: AppHandle,
+    provider: Arc<dyn FileSystemProvider>,
+    uri: RemoteUri,
+    recursive: bool,
+    kind: WatchKind,
+    status: Arc<Mutex<WatchStatus>>,
+    /// What the last poll found, by provider path
+    known: HashMap<String, ProviderStat>,
+    poll_interval: Duration,
+    retry_interval: Duration,
+}
+
+impl ProvidedWorker {
+    fn wait(&self) -> Duration {
+        match self.status.lock().unwrap().health {
+            WatchHealth::Failed => self.retry_interval,
+            _ => self.poll_interval,
+        }
+    }
+
+    /// Entries under the watched directory, without the directory itself, or the watched file if it exists
+    fn entries(&self) -> Result<HashMap<String, ProviderStat>, FileSystemError> {
+        match self.kind {
+            WatchKind::Directory => Ok(self
+                .provider
+                .snapshot(&self.uri.path, self.recursive)?
+                .into_iter()
+                .filter(|(path, _)| *path != self.uri.path)
+                .collect()),
+            WatchKind::File => match self.provider.stat(&self.uri.path) {
+                Ok(stat) => Ok(HashMap::from([(self.uri.path.clone(), stat)])),
+                Err(FileSystemError::NotFound) => Ok(HashMap::new()),
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    fn poll(&mut self) {
+        let current = match self.entries() {
+            Ok(current) => current,
+            Err(e) => return self.fail(e),
+        };
+        let health = self.status.lock().unwrap().health;
+        if health != WatchHealth::Healthy {
+            self.retry_interval = self.poll_interval;
+            let mut status = self.status.lock().unwrap();
+            status.health = WatchHealth::Healthy;
+            status.rewatch_count += 1;
+            drop(status);
+            // What changed while the directory was gone or unreachable is unknown
+            if self.kind == WatchKind::Directory {
+                self.known = current;
+                self.rescan(RescanReason::Rewatched);
+                return;
+            }
+        }
+        match self.kind {
+            WatchKind::Directory => self.report_directory(&current),
+            WatchKind::File => self.report_file(&current),
+        }
+        self.known = current;
+    }
+
+    fn fail(&mut self, e: FileSystemError) {
+        let mut status = self.status.lock().unwrap();
+        if matches!(e, FileSystemError::NotFound) {
+            if status.health == WatchHealth::Healthy {
+                status.health = WatchHealth::Lost;
+                drop(status);
+                self.known.clear();
+                self.rescan(RescanReason::Removed);
+            }
+            return;
+        }
+        status.error_count += 1;
+        status.last_error = Some(e.to_string());
+        if status.health != WatchHealth::Failed {
+            tracing::warn!(path = %status.path, error = %e, "polling watched path failed");
+            status.health = WatchHealth::Failed;
+        } else {
+            self.retry_interval = (self.retry_interval * 2).min(MAX_RETRY_INTERVAL);
+        }
+    }
+
+    fn report_directory(&self, current: &HashMap<String, ProviderStat>) {
+        let mut changes: Vec<(ChangeKind, Vec<String>)> = vec![
+            (ChangeKind::Removed, Vec::new()),
+            (ChangeKind::Created, Vec::new()),
+            (ChangeKind::Modified, Vec::new()),
+        ];
+        for path in self.known.keys().filter(|path| !current.contains_key(*path)) {
+            changes[0].1.push(path.clone());
+        }
+        for (path, stat) in current {
+            match self.known.get(path) {
+                None => changes[1].1.push(path.clone()),
+                // A directory's time changes with its entries, which are reported themselves
+                Some(known) if known != stat && stat.kind != EntryKind::Directory => {
+                    changes[2].1.push(path.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        let id = self.status.lock().unwrap().id.clone();
+        for (kind, mut paths) in changes.into_iter().filter(|(_, paths)| !paths.is_empty()) {
+            paths.sort();
+            self.status.lock().unwrap().last_event_at = Some(now_millis());
+            let _ = self.app.emit(
+                FS_CHANGE_EVENT,
+                FsChangeEvent {
+                    watch_id: id.clone(),
+                    kind,
+                    paths: paths.iter().map(|path| self.uri.with_path(path).to_string()).collect(),
+                },
+            );
+        }
+    }
+
+    fn report_file(&self, current: &HashMap<String, ProviderStat>) {
+        let before = self.known.get(&self.uri.path);
+        let after = current.get(&self.uri.path);
+        let kind = match (before, after) {
+            (None, None) => return,
+            (Some(_), None) => ChangeKind::Removed,
+            (None, Some(_)) => ChangeKind::Created,
+            (Some(before), Some(after)) if before == after => return,
+            (Some(_), Some(_)) => ChangeKind::Modified,
+        };
+        let path = self.uri.to_string();
+        self.status.lock().unwrap().last_event_at = Some(now_millis());
+        if after.is_some() && self.app.state::<FileSystemService>().matches_known_provided_version(&path) {
+            return;
+        }
+        let event = FileChangeEvent {
+            watch_id: self.status.lock().unwrap().id.clone(),
+            path,
+            kind,
+            new_path: None,
+            modified: after.and_then(|stat| stat.modified).map(|seconds| seconds * 1000),
+        };
+        let _ = self.app.emit(FS_FILE_CHANGE_EVENT, event);
+    }
+
+    fn rescan(&self, reason: RescanReason) {
+        let status = self.status.lock().unwrap().clone();
+        let _ = self.app.emit(
+            FS_RESCAN_EVENT,
+            FsRescanEvent {
+                watch_id: status.id.clone(),
+                path: status.path.clone(),
+                reason,
+                status,
+            },
+        );
+    }
+}
@@ -0,0 +1,102 @@
+/**
+ * Line blame for inline annotations
+ */
+
+use git2::{BlameOptions, Oid};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::{GitError, GitService};
+
+/// Blame information for a contiguous range of lines; lines are 1-based
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameRange {
+    pub start_line: usize,
+    pub line_count: usize,
+    pub commit_hash: String,
+    pub author: String,
+    pub author_email: String,
+    pub timestamp: i64,
+    pub summary: String,
+    pub is_uncommitted: bool,
+}
+
+/// Cached blame for one file, valid while the file and HEAD are unchanged
+pub(super) struct BlameCacheEntry {
+    modified: SystemTime,
+    head: Option<Oid>,
+    ranges: Vec<BlameRange>,
+}
+
+impl GitService {
+    /// Blame every line of a file, including uncommitted working tree changes
+    pub fn blame(&self, path: &str) -> Result<Vec<BlameRange>, GitError> {
+        let repo = self.open(path)?;
+        let relative_path = self.relative_path(&repo, path)?;
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|_| GitError::InvalidPath)?;
+        let head = repo.head().ok().and_then(|head| head.target());
+
+        if let Some(entry) = self.blame_cache.lock().unwrap().get(path) {
+            if entry.modified == modified && entry.head == head {
+                return Ok(entry.ranges.clone());
+            }
+        }
+
+        let mut options = BlameOptions::new();
+        let committed = repo.blame_file(Path::new(&relative_path), Some(&mut options))?;
+        // Blame the buffer on disk so line numbers match what the editor shows
+        let content = fs::read(path).map_err(|_| GitError::InvalidPath)?;
+        let blame = committed.blame_buffer(&content)?;
+
+        let mut ranges = Vec::with_capacity(blame.len());
+        for hunk in blame.iter() {
+            let commit_id = hunk.final_commit_id();
+            let is_uncommitted = commit_id.is_zero();
+            // Uncommitted hunks carry no signature at all
+            let range = if is_uncommitted {
+                BlameRange {
+                    start_line: hunk.final_start_line(),
+                    line_count: hunk.lines_in_hunk(),
+                    commit_hash: commit_id.to_string(),
+                    author: "Not Committed Yet".to_string(),
+                    author_email: String::new(),
+                    timestamp: 0,
+                    summary: "Uncommitted changes".to_string(),
+                    is_uncommitted,
+                }
+            } else {
+                let signature = hunk.final_signature();
+                BlameRange {
+                    start_line: hunk.final_start_line(),
+                    line_count: hunk.lines_in_hunk(),
+                    commit_hash: commit_id.to_string(),
+                    author: signature.name().unwrap_or("").to_string(),
+                    author_email: signature.email().unwrap_or("").to_string(),
+                    timestamp: signature.when().seconds(),
+                    summary: repo
+                        .find_commit(commit_id)
+                        .ok()
+                        .and_then(|commit| commit.summary().map(|s| s.to_string()))
+                        .unwrap_or_default(),
+                    is_uncommitted,
+                }
+            };
+            ranges.push(range);
+        }
+
+        self.blame_cache.lock().unwrap().insert(
+            path.to_string(),
+            BlameCacheEntry {
+                modified,
+                head,
+                ranges: ranges.clone(),
+            },
+        );
+
+        Ok(ranges)
+    }
+}
@@ -0,0 +1,255 @@
+/**
+ * Branch management for the status-bar branch picker
+ */
+
+use git2::{build::CheckoutBuilder, BranchType, MergeAnalysis, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+
+use super::{GitError, GitService};
+
+/// A local or remote-tracking branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub is_remote: bool,
+    pub is_head: bool,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub last_commit: Option<String>,
+    pub last_commit_summary: Option<String>,
+    pub last_commit_time: Option<i64>,
+}
+
+/// How a merge was carried out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeKind {
+    UpToDate,
+    FastForward,
+    Merged,
+    Conflicts,
+}
+
+/// Result of merging a branch into the current branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeOutcome {
+    pub kind: MergeKind,
+    pub commit: Option<String>,
+    pub conflicts: Vec<String>,
+}
+
+impl GitService {
+    /// Whether tracked files have staged or unstaged modifications
+    pub fn has_uncommitted_changes(&self, repo: &Repository) -> Result<bool, GitError> {
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        Ok(!repo.statuses(Some(&mut options))?.is_empty())
+    }
+
+    fn ensure_clean(&self, repo: &Repository) -> Result<(), GitError> {
+        if self.has_uncommitted_changes(repo)? {
+            return Err(GitError::UncommittedChanges);
+        }
+        Ok(())
+    }
+
+    /// List local branches, and remote-tracking branches when requested
+    pub fn list_branches(&self, path: &str, include_remote: bool) -> Result<Vec<BranchInfo>, GitError> {
+        let repo = self.open(path)?;
+        let filter = if include_remote { None } else { Some(BranchType::Local) };
+
+        let mut branches = Vec::new();
+        for branch in repo.branches(filter)? {
+            let (branch, branch_type) = branch?;
+            let Some(name) = branch.name()?.map(|name| name.to_string()) else {
+                continue;
+            };
+            // origin/HEAD is a symbolic alias, not a branch
+            if branch_type == BranchType::Remote && name.ends_with("/HEAD") {
+                continue;
+            }
+
+            let upstream = branch.upstream().ok();
+            let upstream_name = upstream
+                .as_ref()
+                .and_then(|upstream| upstream.name().ok().flatten().map(|name| name.to_string()));
+            let (ahead, behind) = match (branch.get().target(), upstream.as_ref().and_then(|u| u.get().target())) {
+                (Some(local), Some(remote)) => repo.graph_ahead_behind(local, remote).unwrap_or((0, 0)),
+                _ => (0, 0),
+            };
+            let commit = branch.get().peel_to_commit().ok();
+
+            branches.push(BranchInfo {
+                name,
+                is_remote: branch_type == BranchType::Remote,
+                is_head: branch.is_head(),
+                upstream: upstream_name,
+                ahead,
+                behind,
+                last_commit: commit.as_ref().map(|c| c.id().to_string()),
+                last_commit_summary: commit.as_ref().and_then(|c| c.summary().map(|s| s.to_string())),
+                last_commit_time: commit.as_ref().map(|c| c.time().seconds()),
+            });
+        }
+
+        // Local branches first, then remotes, each alphabetical
+        branches.sort_by(|a, b| a.is_remote.cmp(&b.is_remote).then(a.name.cmp(&b.name)));
+        Ok(branches)
+    }
+
+    /// Create a branch at `start_point` (default HEAD), optionally checking it out
+    pub fn create_branch(
+        &self,
+        path: &str,
+        name: &str,
+        start_point: Option<&str>,
+        checkout: bool,
+    ) -> Result<BranchInfo, GitError> {
+        let repo = self.open(path)?;
+        let target = match start_point {
+            Some(revision) => repo.revparse_single(revision)?.peel_to_commit()?,
+            None => repo.head()?.peel_to_commit()?,
+        };
+        repo.branch(name, &target, false)?;
+
+        if checkout {
+            self.checkout(path, name, false)?;
+        }
+
+        self.list_branches(path, false)?
+            .into_iter()
+            .find(|branch| branch.name == name)
+            .ok_or_else(|| GitError::NotFound(name.to_string()))
+    }
+
+    /// Switch to a branch. A remote-tracking branch gets a local tracking branch.
+    /// Refuses to run with uncommitted changes unless `force` is set.
+    pub fn checkout(&self, path: &str, name: &str, force: bool) -> Result<(), GitError> {
+        let repo = self.open(path)?;
+        if !force {
+            self.ensure_clean(&repo)?;
+        }
+
+        let local_name = match repo.find_branch(name, BranchType::Local) {
+            Ok(_) => name.to_string(),
+            Err(_) => {
+                let remote = repo
+                    .find_branch(name, BranchType::Remote)
+                    .map_err(|_| GitError::NotFound(name.to_string()))?;
+                let local_name = name.split_once('/').map(|(_, rest)| rest).unwrap_or(name).to_string();
+                let commit = remote.get().peel_to_commit()?;
+                let mut local = repo.branch(&local_name, &commit, false)?;
+                local.set_upstream(Some(name))?;
+                local_name
+            }
+        };
+
+        let reference = format!("refs/heads/{}", local_name);
+        let tree = repo.revparse_single(&reference)?;
+        let mut checkout = CheckoutBuilder::new();
+        if force {
+            checkout.force();
+        } else {
+            checkout.safe();
+        }
+        repo.checkout_tree(&tree, Some(&mut checkout))?;
+        repo.set_head(&reference)?;
+        Ok(())
+    }
+
+    /// Delete a local branch. Unmerged branches require `force`.
+    pub fn delete_branch(&self, path: &str, name: &str, force: bool) -> Result<(), GitError> {
+        let repo = self.open(path)?;
+        let mut branch = repo
+            .find_branch(name, BranchType::Local)
+            .map_err(|_| GitError::NotFound(name.to_string()))?;
+
+        if branch.is_head() {
+            return Err(GitError::Git(format!("Cannot delete the checked out branch {}", name)));
+        }
+
+        if !force {
+            let branch_oid = branch.get().target().ok_or(GitError::InvalidPath)?;
+            let head_oid = repo.head()?.target().ok_or(GitError::InvalidPath)?;
+            let merged = branch_oid == head_oid || repo.graph_descendant_of(head_oid, branch_oid)?;
+            if !merged {
+                return Err(GitError::Git(format!("Branch {} is not fully merged", name)));
+            }
+        }
+
+        branch.delete()?;
+        Ok(())
+    }
+
+    /// Merge a branch into the current branch
+    pub fn merge_branch(&self, path: &str, name: &str) -> Result<MergeOutcome, GitError> {
+        let repo = self.open(path)?;
+        self.ensure_clean(&repo)?;
+
+        let reference = repo
+            .find_branch(name, BranchType::Local)
+            .or_else(|_| repo.find_branch(name, BranchType::Remote))
+            .map_err(|_| GitError::NotFound(name.to_string()))?
+            .into_reference();
+        let incoming = repo.reference_to_annotated_commit(&reference)?;
+        let (analysis, _) = repo.merge_analysis(&[&incoming])?;
+
+        if analysis.contains(MergeAnalysis::ANALYSIS_UP_TO_DATE) {
+            return Ok(MergeOutcome {
+                kind: MergeKind::UpToDate,
+                commit: None,
+                conflicts: Vec::new(),
+            });
+        }
+
+        if analysis.contains(MergeAnalysis::ANALYSIS_FASTFORWARD) {
+            // The work tree has to move first: once HEAD points at the new commit a safe checkout compares
+            // against it and finds nothing to update
+            let target = repo.find_commit(incoming.id())?;
+            repo.checkout_tree(target.as_object(), Some(CheckoutBuilder::new().safe()))?;
+            repo.head()?.set_target(incoming.id(), &format!("merge {}: Fast-forward", name))?;
+            return Ok(MergeOutcome {
+                kind: MergeKind::FastForward,
+                commit: Some(incoming.id().to_string()),
+                conflicts: Vec::new(),
+            });
+        }
+
+        repo.merge(&[&incoming], None, None)?;
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            // Leave the repository mid-merge so the merge editor can resolve conflicts
+            let conflicts = self
+                .conflicted_files(path)?
+                .into_iter()
+                .map(|file| file.relative_path)
+                .collect();
+            return Ok(MergeOutcome {
+                kind: MergeKind::Conflicts,
+                commit: None,
+                conflicts,
+            });
+        }
+
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = repo.signature()?;
+        let head_commit = repo.head()?.peel_to_commit()?;
+        let incoming_commit = repo.find_commit(incoming.id())?;
+        let message = format!("Merge branch '{}'", name);
+        let commit = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[&head_commit, &incoming_commit],
+        )?;
+        repo.cleanup_state()?;
+
+        Ok(MergeOutcome {
+            kind: MergeKind::Merged,
+            commit: Some(commit.to_string()),
+            conflicts: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,182 @@
+/**
+ * Repository cloning for the welcome screen, with progress events and cancellation
+ */
+
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::FetchOptions;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::remotes::{credential_callbacks, GitCredentials};
+use super::{GitError, GitService};
+use crate::notifications::{NotificationService, ProgressHandle};
+
+/// Event carrying progress and completion of a clone
+pub const CLONE_PROGRESS_EVENT: &str = "git://clone-progress";
+
+/// Phase of a clone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloneStage {
+    Receiving,
+    CheckingOut,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Progress of a clone, emitted as `git://clone-progress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneProgress {
+    pub clone_id: String,
+    pub url: String,
+    pub destination: String,
+    pub stage: CloneStage,
+    pub received_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+    pub checked_out_files: usize,
+    pub total_files: usize,
+    pub error: Option<String>,
+}
+
+impl GitService {
+    /// Start cloning `url` into `destination` in the background, returning the clone id
+    pub fn clone_repository(
+        &self,
+        app: &AppHandle,
+        url: &str,
+        destination: &str,
+        credentials: GitCredentials,
+    ) -> Result<String, GitError> {
+        let target = Path::new(destination);
+        let occupied = target
+            .read_dir()
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(target.exists());
+        if occupied {
+            return Err(GitError::DestinationExists(destination.to_string()));
+        }
+
+        let clone_id = format!("clone-{}", self.next_clone_id.fetch_add(1, Ordering::SeqCst));
+        // Cancelling the clone or its notification both stop it
+        let notification =
+            app.state::<NotificationService>().start_progress(app, "git", &format!("Cloning {}", url), true);
+        self.clones.lock().unwrap().insert(clone_id.clone(), notification.cancel_flag());
+
+        let app = app.clone();
+        let mut progress = CloneProgress {
+            clone_id: clone_id.clone(),
+            url: url.to_string(),
+            destination: destination.to_string(),
+            stage: CloneStage::Receiving,
+            received_objects: 0,
+            total_objects: 0,
+            received_bytes: 0,
+            checked_out_files: 0,
+            total_files: 0,
+            error: None,
+        };
+        std::thread::spawn(move || {
+            let created_destination = !Path::new(&progress.destination).exists();
+            let result = run_clone(&app, &mut progress, &notification, credentials);
+
+            progress.stage = match result {
+                Ok(()) => CloneStage::Completed,
+                Err(_) if notification.is_cancelled() => CloneStage::Cancelled,
+                Err(e) => {
+                    progress.error = Some(e.to_string());
+                    CloneStage::Failed
+                }
+            };
+            if progress.stage != CloneStage::Completed {
+                // Leave no half-cloned repository behind
+                let destination = Path::new(&progress.destination);
+                if created_destination {
+                    let _ = fs::remove_dir_all(destination);
+                } else if let Ok(entries) = destination.read_dir() {
+                    for entry in entries.flatten() {
+                        let _ = fs::remove_dir_all(entry.path()).or_else(|_| fs::remove_file(entry.path()));
+                    }
+                }
+            }
+
+            app.state::<GitService>().clones.lock().unwrap().remove(&progress.clone_id);
+            match &progress.error {
+                Some(error) => notification.fail(error),
+                None => notification.complete(Some(&progress.destination)),
+            }
+            let _ = app.emit(CLONE_PROGRESS_EVENT, progress);
+        });
+
+        Ok(clone_id)
+    }
+
+    /// Abort a running clone; the partial checkout is removed
+    pub fn cancel_clone(&self, clone_id: &str) -> Result<(), GitError> {
+        let clones = self.clones.lock().unwrap();
+        let cancelled = clones.get(clone_id).ok_or_else(|| GitError::NotFound(clone_id.to_string()))?;
+        cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn run_clone(
+    app: &AppHandle,
+    progress: &mut CloneProgress,
+    notification: &ProgressHandle,
+    credentials: GitCredentials,
+) -> Result<(), GitError> {
+    let url = progress.url.clone();
+    let destination = progress.destination.clone();
+    let state = std::cell::RefCell::new(progress);
+
+    let mut callbacks = {
+        // Credentials need a repository config; a clone has none yet, so fall back to the global one
+        let config = git2::Config::open_default().ok();
+        credential_callbacks(config, credentials)
+    };
+    let mut last_percent = None;
+    callbacks.transfer_progress(|stats| {
+        let mut progress = state.borrow_mut();
+        progress.received_objects = stats.received_objects();
+        progress.total_objects = stats.total_objects();
+        progress.received_bytes = stats.received_bytes();
+
+        let percent = stats.received_objects() * 100 / stats.total_objects().max(1);
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            let _ = app.emit(CLONE_PROGRESS_EVENT, progress.clone());
+            notification.report(Some(percent as u8), Some("Receiving objects"));
+        }
+        // Returning false aborts the transfer
+        !notification.is_cancelled()
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    let mut checkout = CheckoutBuilder::new();
+    let mut last_checkout_percent = None;
+    checkout.progress(|_, completed, total| {
+        let mut progress = state.borrow_mut();
+        progress.stage = CloneStage::CheckingOut;
+        progress.checked_out_files = completed;
+        progress.total_files = total;
+
+        let percent = completed * 100 / total.max(1);
+        if last_checkout_percent != Some(percent) {
+            last_checkout_percent = Some(percent);
+            let _ = app.emit(CLONE_PROGRESS_EVENT, progress.clone());
+            notification.report(Some(percent as u8), Some("Checking out files"));
+        }
+    });
+
+    RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .with_checkout(checkout)
+        .clone(&url, Path::new(&destination))?;
+    Ok(())
+}
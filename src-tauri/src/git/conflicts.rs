@@ -0,0 +1,99 @@
+/**
+ * Merge conflict inspection for the merge editor
+ */
+
+use git2::{IndexEntry, Repository};
+use serde::{Deserialize, Serialize};
+
+use super::{GitError, GitService};
+
+/// A file with unresolved merge conflicts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub relative_path: String,
+    pub has_base: bool,
+    pub has_ours: bool,
+    pub has_theirs: bool,
+}
+
+/// The three index stages of a conflicted file; a missing stage means the file
+/// was added or deleted on that side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictVersions {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+fn entry_path(entry: &IndexEntry) -> String {
+    String::from_utf8_lossy(&entry.path).to_string()
+}
+
+fn blob_text(repo: &Repository, entry: Option<&IndexEntry>) -> Result<Option<String>, GitError> {
+    match entry {
+        Some(entry) => {
+            let blob = repo.find_blob(entry.id)?;
+            Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+impl GitService {
+    /// List files with unresolved conflicts in the repository containing `path`
+    pub fn conflicted_files(&self, path: &str) -> Result<Vec<ConflictedFile>, GitError> {
+        let repo = self.open(path)?;
+        let workdir = repo.workdir().ok_or(GitError::InvalidPath)?;
+        let index = repo.index()?;
+
+        let mut files = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let relative_path = conflict
+                .our
+                .as_ref()
+                .or(conflict.their.as_ref())
+                .or(conflict.ancestor.as_ref())
+                .map(entry_path)
+                .unwrap_or_default();
+
+            files.push(ConflictedFile {
+                path: workdir.join(&relative_path).to_string_lossy().to_string(),
+                relative_path,
+                has_base: conflict.ancestor.is_some(),
+                has_ours: conflict.our.is_some(),
+                has_theirs: conflict.their.is_some(),
+            });
+        }
+
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        Ok(files)
+    }
+
+    /// Base, ours, and theirs contents of a conflicted file, ready for a three-way merge
+    pub fn conflict_versions(&self, path: &str) -> Result<ConflictVersions, GitError> {
+        let repo = self.open(path)?;
+        let relative_path = self.relative_path(&repo, path)?;
+        let index = repo.index()?;
+
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let matches = [&conflict.ancestor, &conflict.our, &conflict.their]
+                .iter()
+                .any(|entry| entry.as_ref().is_some_and(|e| entry_path(e) == relative_path));
+
+            if matches {
+                return Ok(ConflictVersions {
+                    path: path.to_string(),
+                    base: blob_text(&repo, conflict.ancestor.as_ref())?,
+                    ours: blob_text(&repo, conflict.our.as_ref())?,
+                    theirs: blob_text(&repo, conflict.their.as_ref())?,
+                });
+            }
+        }
+
+        Err(GitError::Git(format!("{} has no merge conflicts", relative_path)))
+    }
+}
@@ -0,0 +1,247 @@
+/**
+ * Commit history for the log viewer
+ * Produces topologically ordered commits with lane assignments for drawing the graph
+ */
+
+use git2::{Delta, DiffOptions, Oid, Patch, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{GitError, GitService};
+
+/// Edge from a commit to one of its parents. `to_column` is the lane the edge
+/// travels down; it joins the parent's own column at the parent's row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub parent: String,
+    pub from_column: usize,
+    pub to_column: usize,
+}
+
+/// A commit in the history graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitNode {
+    pub hash: String,
+    pub short_hash: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<String>,
+    pub author: String,
+    pub author_email: String,
+    pub timestamp: i64,
+    pub summary: String,
+    pub column: usize,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A file changed by a commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitFileChange {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub patch: String,
+}
+
+/// Full information about a single commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitDetails {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub author_email: String,
+    pub author_time: i64,
+    pub committer: String,
+    pub committer_email: String,
+    pub committer_time: i64,
+    pub message: String,
+    pub files: Vec<CommitFileChange>,
+}
+
+/// Names of branches, tags, and HEAD pointing at each commit
+fn collect_refs(repo: &Repository) -> Result<HashMap<Oid, Vec<String>>, GitError> {
+    let mut refs: HashMap<Oid, Vec<String>> = HashMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        let Some(name) = reference.shorthand().map(|s| s.to_string()) else {
+            continue;
+        };
+        if name.ends_with("/HEAD") {
+            continue;
+        }
+        if let Ok(commit) = reference.peel_to_commit() {
+            refs.entry(commit.id()).or_default().push(name);
+        }
+    }
+    if let Ok(head) = repo.head() {
+        if let Some(oid) = head.target() {
+            refs.entry(oid).or_default().insert(0, "HEAD".to_string());
+        }
+    }
+    Ok(refs)
+}
+
+fn delta_status(delta: Delta) -> &'static str {
+    match delta {
+        Delta::Added => "added",
+        Delta::Deleted => "deleted",
+        Delta::Modified => "modified",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        _ => "unknown",
+    }
+}
+
+impl GitService {
+    /// Commits reachable from `branch_filter` (or all local and remote branches),
+    /// newest first in topological order, with graph lanes assigned
+    pub fn commit_graph(&self, path: &str, limit: usize, branch_filter: Option<&str>) -> Result<Vec<CommitNode>, GitError> {
+        let repo = self.open(path)?;
+        let refs = collect_refs(&repo)?;
+
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        match branch_filter {
+            Some(branch) => {
+                let oid = repo.revparse_single(branch)?.peel_to_commit()?.id();
+                walk.push(oid)?;
+            }
+            None => {
+                walk.push_glob("refs/heads")?;
+                walk.push_glob("refs/remotes")?;
+                // An empty repository has no HEAD yet
+                let _ = walk.push_head();
+            }
+        }
+
+        // Each lane holds the commit it expects next
+        let mut lanes: Vec<Option<Oid>> = Vec::new();
+        let mut nodes = Vec::new();
+
+        for oid in walk.take(limit) {
+            let oid = oid?;
+            let commit = repo.find_commit(oid)?;
+
+            let column = match lanes.iter().position(|lane| *lane == Some(oid)) {
+                Some(column) => column,
+                None => match lanes.iter().position(|lane| lane.is_none()) {
+                    Some(free) => free,
+                    None => {
+                        lanes.push(None);
+                        lanes.len() - 1
+                    }
+                },
+            };
+            // Other lanes converging on this commit end here
+            for lane in lanes.iter_mut() {
+                if *lane == Some(oid) {
+                    *lane = None;
+                }
+            }
+
+            let parents: Vec<Oid> = commit.parent_ids().collect();
+            let mut edges = Vec::with_capacity(parents.len());
+            for (index, parent) in parents.iter().enumerate() {
+                let to_column = if index == 0 {
+                    lanes[column] = Some(*parent);
+                    column
+                } else if let Some(existing) = lanes.iter().position(|lane| *lane == Some(*parent)) {
+                    existing
+                } else if let Some(free) = lanes.iter().position(|lane| lane.is_none()) {
+                    lanes[free] = Some(*parent);
+                    free
+                } else {
+                    lanes.push(Some(*parent));
+                    lanes.len() - 1
+                };
+                edges.push(GraphEdge {
+                    parent: parent.to_string(),
+                    from_column: column,
+                    to_column,
+                });
+            }
+            while lanes.last() == Some(&None) {
+                lanes.pop();
+            }
+
+            let author = commit.author();
+            let hash = oid.to_string();
+            nodes.push(CommitNode {
+                short_hash: hash[..7.min(hash.len())].to_string(),
+                hash,
+                parents: parents.iter().map(|p| p.to_string()).collect(),
+                refs: refs.get(&oid).cloned().unwrap_or_default(),
+                author: author.name().unwrap_or("").to_string(),
+                author_email: author.email().unwrap_or("").to_string(),
+                timestamp: author.when().seconds(),
+                summary: commit.summary().unwrap_or("").to_string(),
+                column,
+                edges,
+            });
+        }
+
+        Ok(nodes)
+    }
+
+    /// Metadata and full diff (against the first parent) of a commit
+    pub fn commit_details(&self, path: &str, hash: &str) -> Result<CommitDetails, GitError> {
+        let repo = self.open(path)?;
+        let commit = repo.revparse_single(hash)?.peel_to_commit()?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let mut options = DiffOptions::new();
+        let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+        diff.find_similar(None)?;
+
+        let mut files = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(mut patch) = Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
+            let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
+            let status = delta_status(delta.status());
+            let (_, additions, deletions) = patch.line_stats()?;
+
+            let mut text = Vec::new();
+            patch.print(&mut |_, _, line| {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    text.push(line.origin() as u8);
+                }
+                text.extend_from_slice(line.content());
+                true
+            })?;
+
+            files.push(CommitFileChange {
+                path: new_path.clone().or(old_path.clone()).unwrap_or_default(),
+                old_path: if old_path != new_path { old_path } else { None },
+                status: status.to_string(),
+                additions,
+                deletions,
+                patch: String::from_utf8_lossy(&text).to_string(),
+            });
+        }
+
+        let author = commit.author();
+        let committer = commit.committer();
+        Ok(CommitDetails {
+            hash: commit.id().to_string(),
+            parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+            author: author.name().unwrap_or("").to_string(),
+            author_email: author.email().unwrap_or("").to_string(),
+            author_time: author.when().seconds(),
+            committer: committer.name().unwrap_or("").to_string(),
+            committer_email: committer.email().unwrap_or("").to_string(),
+            committer_time: committer.when().seconds(),
+            message: commit.message().unwrap_or("").to_string(),
+            files,
+        })
+    }
+}
@@ -0,0 +1,137 @@
+/**
+ * Hunk-level staging and discarding for the source control view
+ */
+
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository};
+use serde::{Deserialize, Serialize};
+
+use super::{GitError, GitService};
+use crate::diff::{DiffHunk, HunkKind, LineEdit, LineEditKind};
+
+/// An unstaged hunk of a file; `id` identifies it for staging or reverting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHunk {
+    pub id: String,
+    #[serde(flatten)]
+    pub hunk: DiffHunk,
+}
+
+/// Hunk ids are derived from the hunk header, oriented index -> working tree
+fn hunk_id(old_start: u32, old_lines: u32, new_start: u32, new_lines: u32) -> String {
+    format!("{}-{}-{}-{}", old_start, old_lines, new_start, new_lines)
+}
+
+impl GitService {
+    /// Working tree changes of a file relative to the index, split into hunks
+    pub fn diff_hunks(&self, path: &str) -> Result<Vec<GitHunk>, GitError> {
+        let repo = self.open(path)?;
+        let relative = self.relative_path(&repo, path)?;
+        let diff = workdir_diff(&repo, &relative, false)?;
+        let Some(patch) = Patch::from_diff(&diff, 0)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut hunks = Vec::new();
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut edits = Vec::with_capacity(line_count);
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(hunk_index, line_index)?;
+                let kind = match line.origin() {
+                    '+' => LineEditKind::Insert,
+                    '-' => LineEditKind::Delete,
+                    ' ' => LineEditKind::Equal,
+                    // "\ No newline at end of file" markers
+                    _ => continue,
+                };
+                edits.push(LineEdit {
+                    kind,
+                    old_line: line.old_lineno().map(|line| line as usize),
+                    new_line: line.new_lineno().map(|line| line as usize),
+                    content: String::from_utf8_lossy(line.content()).trim_end_matches(['\n', '\r']).to_string(),
+                });
+            }
+
+            let kind = match (hunk.old_lines(), hunk.new_lines()) {
+                (_, 0) => HunkKind::Deleted,
+                (0, _) => HunkKind::Added,
+                _ if edits.iter().all(|edit| edit.kind != LineEditKind::Delete) => HunkKind::Added,
+                _ if edits.iter().all(|edit| edit.kind != LineEditKind::Insert) => HunkKind::Deleted,
+                _ => HunkKind::Modified,
+            };
+            hunks.push(GitHunk {
+                id: hunk_id(hunk.old_start(), hunk.old_lines(), hunk.new_start(), hunk.new_lines()),
+                hunk: DiffHunk {
+                    kind,
+                    old_start: hunk.old_start() as usize,
+                    old_lines: hunk.old_lines() as usize,
+                    new_start: hunk.new_start() as usize,
+                    new_lines: hunk.new_lines() as usize,
+                    header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                    edits,
+                },
+            });
+        }
+        Ok(hunks)
+    }
+
+    /// Add a single hunk of a file to the index
+    pub fn stage_hunk(&self, path: &str, hunk_id: &str) -> Result<(), GitError> {
+        let repo = self.open(path)?;
+        let relative = self.relative_path(&repo, path)?;
+        let diff = workdir_diff(&repo, &relative, false)?;
+        apply_hunk(&repo, &diff, hunk_id, false, ApplyLocation::Index)
+    }
+
+    /// Discard a single hunk of a file from the working tree, restoring the index version
+    pub fn revert_hunk(&self, path: &str, hunk_id: &str) -> Result<(), GitError> {
+        let repo = self.open(path)?;
+        let relative = self.relative_path(&repo, path)?;
+        let diff = workdir_diff(&repo, &relative, true)?;
+        apply_hunk(&repo, &diff, hunk_id, true, ApplyLocation::WorkDir)
+    }
+}
+
+fn workdir_diff<'a>(repo: &'a Repository, relative: &str, reverse: bool) -> Result<Diff<'a>, GitError> {
+    let mut options = DiffOptions::new();
+    options
+        .pathspec(relative)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .show_untracked_content(true)
+        .reverse(reverse);
+    Ok(repo.diff_index_to_workdir(None, Some(&mut options))?)
+}
+
+/// Apply only the hunk with the given id; a reversed diff has old and new sides swapped
+fn apply_hunk(
+    repo: &Repository,
+    diff: &Diff,
+    target: &str,
+    reversed: bool,
+    location: ApplyLocation,
+) -> Result<(), GitError> {
+    let mut found = false;
+    let mut options = ApplyOptions::new();
+    options.hunk_callback(|hunk| {
+        let Some(hunk) = hunk else {
+            return false;
+        };
+        let id = if reversed {
+            hunk_id(hunk.new_start(), hunk.new_lines(), hunk.old_start(), hunk.old_lines())
+        } else {
+            hunk_id(hunk.old_start(), hunk.old_lines(), hunk.new_start(), hunk.new_lines())
+        };
+        let matches = id == target;
+        found |= matches;
+        matches
+    });
+
+    repo.apply(diff, location, Some(&mut options))?;
+    drop(options);
+    if found {
+        Ok(())
+    } else {
+        Err(GitError::NotFound(target.to_string()))
+    }
+}
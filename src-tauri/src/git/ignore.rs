@@ -0,0 +1,103 @@
+/**
+ * .gitignore helpers for the explorer's "Add to .gitignore" action
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{GitError, GitService};
+
+const GITIGNORE: &str = ".gitignore";
+
+/// Result of adding an entry to a .gitignore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitignoreUpdate {
+    pub gitignore_path: String,
+    pub entry: String,
+    /// False when the entry was already present
+    pub added: bool,
+}
+
+impl GitService {
+    /// Add `pattern` to the nearest .gitignore
+    ///
+    /// A pattern naming an existing file or directory is written relative to the .gitignore closest to it,
+    /// anchored so only that path matches; any other pattern is added verbatim to the workspace's .gitignore.
+    pub fn add_to_gitignore(&self, workspace: &str, pattern: &str) -> Result<GitignoreUpdate, GitError> {
+        let workspace = Path::new(workspace).canonicalize().map_err(|_| GitError::InvalidPath)?;
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(GitError::InvalidPath);
+        }
+        // The topmost directory whose .gitignore still applies
+        let boundary = self
+            .open(&workspace.to_string_lossy())
+            .ok()
+            .and_then(|repo| repo.workdir().and_then(|workdir| workdir.canonicalize().ok()))
+            .unwrap_or_else(|| workspace.clone());
+
+        let target = workspace
+            .join(pattern)
+            .canonicalize()
+            .ok()
+            .filter(|target| target.starts_with(&workspace) && *target != workspace);
+
+        let (gitignore_dir, entry) = match target {
+            Some(target) => {
+                let start = target.parent().unwrap_or(&workspace);
+                let dir = nearest_gitignore_dir(start, &boundary).unwrap_or_else(|| workspace.clone());
+                let relative = target.strip_prefix(&dir).map_err(|_| GitError::InvalidPath)?;
+                let mut entry = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+                if target.is_dir() {
+                    entry.push('/');
+                }
+                (dir, entry)
+            }
+            None => {
+                let dir = nearest_gitignore_dir(&workspace, &boundary).unwrap_or_else(|| workspace.clone());
+                (dir, pattern.to_string())
+            }
+        };
+
+        let gitignore_path = gitignore_dir.join(GITIGNORE);
+        let mut content = fs::read_to_string(&gitignore_path).unwrap_or_default();
+        let added = !content.lines().any(|line| line.trim() == entry);
+        if added {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&entry);
+            content.push('\n');
+            fs::write(&gitignore_path, content).map_err(|e| GitError::Git(e.to_string()))?;
+        }
+
+        Ok(GitignoreUpdate {
+            gitignore_path: gitignore_path.to_string_lossy().to_string(),
+            entry,
+            added,
+        })
+    }
+
+    /// Whether git ignores a path, honoring nested .gitignore files, info/exclude, and global excludes
+    pub fn is_path_ignored(&self, path: &str) -> Result<bool, GitError> {
+        let repo = self.open(path)?;
+        let mut relative = self.relative_path(&repo, path)?;
+        if relative.is_empty() {
+            return Ok(false);
+        }
+        if Path::new(path).is_dir() {
+            relative.push('/');
+        }
+        Ok(repo.is_path_ignored(&relative)?)
+    }
+}
+
+/// Closest directory from `start` up to `boundary` (inclusive) that has a .gitignore
+fn nearest_gitignore_dir(start: &Path, boundary: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .take_while(|dir| dir.starts_with(boundary))
+        .find(|dir| dir.join(GITIGNORE).is_file())
+        .map(Path::to_path_buf)
+}
@@ -0,0 +1,189 @@
+/**
+ * Git Service for CodeForge IDE
+ * Repository operations backed by libgit2, so the IDE works without the git CLI
+ */
+
+mod blame;
+mod branches;
+mod clone;
+mod conflicts;
+mod history;
+mod hunks;
+mod ignore;
+mod remotes;
+mod repositories;
+
+pub use blame::BlameRange;
+pub use branches::{BranchInfo, MergeOutcome};
+pub use conflicts::{ConflictVersions, ConflictedFile};
+pub use history::{CommitDetails, CommitNode};
+pub use hunks::GitHunk;
+pub use ignore::GitignoreUpdate;
+pub use remotes::{FetchSummary, GitCredentials, RemoteInfo};
+pub use repositories::RepositoryStatus;
+
+use crate::types::GitInfo;
+use git2::{BranchType, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::{Arc, Mutex};
+
+/// Error types for git operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GitError {
+    NotARepository(String),
+    InvalidPath,
+    NotFound(String),
+    UncommittedChanges,
+    DestinationExists(String),
+    Git(String),
+}
+
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GitError::NotARepository(path) => write!(f, "Not a git repository: {}", path),
+            GitError::InvalidPath => write!(f, "Invalid path"),
+            GitError::NotFound(name) => write!(f, "Not found: {}", name),
+            GitError::UncommittedChanges => write!(f, "Commit or stash your changes first"),
+            GitError::DestinationExists(path) => write!(f, "Destination is not empty: {}", path),
+            GitError::Git(msg) => write!(f, "Git error: {}", msg),
+        }
+    }
+}
+
+impl From<git2::Error> for GitError {
+    fn from(err: git2::Error) -> Self {
+        GitError::Git(err.message().to_string())
+    }
+}
+
+pub struct GitService {
+    blame_cache: Arc<Mutex<HashMap<String, blame::BlameCacheEntry>>>,
+    clones: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    next_clone_id: AtomicU64,
+}
+
+impl GitService {
+    pub fn new() -> Self {
+        Self {
+            blame_cache: Arc::new(Mutex::new(HashMap::new())),
+            clones: Arc::new(Mutex::new(HashMap::new())),
+            next_clone_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Open the repository containing a path
+    pub fn open(&self, path: &str) -> Result<Repository, GitError> {
+        // A file missing from the work tree, e.g. deleted in a conflict, is found through its directory
+        let start = match Path::new(path) {
+            missing if !missing.exists() => parent_directory(missing),
+            existing => existing,
+        };
+        Repository::discover(start).map_err(|_| GitError::NotARepository(path.to_string()))
+    }
+
+    /// Summarize branch, remote, and working tree state of a repository
+    pub fn repository_info(&self, repo: &Repository) -> Result<GitInfo, GitError> {
+        let branch = self.branch_name(repo);
+        let (ahead, behind) = self.ahead_behind(repo, &branch).unwrap_or((0, 0));
+
+        Ok(GitInfo {
+            remote_url: self.origin_url(repo),
+            has_changes: self.dirty_count(repo)? > 0,
+            branch,
+            ahead,
+            behind,
+        })
+    }
+
+    /// Name of the checked out branch, or `HEAD` when detached
+    pub fn branch_name(&self, repo: &Repository) -> String {
+        match repo.head() {
+            Ok(head) if head.is_branch() => head.shorthand().unwrap_or("HEAD").to_string(),
+            Ok(_) => "HEAD".to_string(),
+            // Unborn branch in a freshly initialized repository
+            Err(_) => repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(|t| t.trim_start_matches("refs/heads/").to_string()))
+                .unwrap_or_else(|| "HEAD".to_string()),
+        }
+    }
+
+    /// URL of the `origin` remote, if configured
+    pub fn origin_url(&self, repo: &Repository) -> Option<String> {
+        repo.find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(|url| url.to_string()))
+    }
+
+    /// Count changed, staged, and untracked entries in the working tree
+    pub fn dirty_count(&self, repo: &Repository) -> Result<usize, GitError> {
+        if repo.is_bare() {
+            return Ok(0);
+        }
+
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).include_ignored(false).recurse_untracked_dirs(false);
+        Ok(repo.statuses(Some(&mut options))?.len())
+    }
+
+    /// Commits ahead of and behind the upstream of a local branch
+    pub fn ahead_behind(&self, repo: &Repository, branch: &str) -> Result<(usize, usize), GitError> {
+        let local = repo.find_branch(branch, BranchType::Local)?;
+        let upstream = local.upstream()?;
+        let local_oid = local.get().target().ok_or(GitError::InvalidPath)?;
+        let upstream_oid = upstream.get().target().ok_or(GitError::InvalidPath)?;
+        Ok(repo.graph_ahead_behind(local_oid, upstream_oid)?)
+    }
+
+    /// Resolve a path relative to the repository work tree. The file itself may be missing, e.g. deleted on
+    /// one side of a conflict, as long as its directory exists.
+    pub fn relative_path(&self, repo: &Repository, path: &str) -> Result<String, GitError> {
+        let workdir = repo.workdir().ok_or(GitError::InvalidPath)?;
+        let path = Path::new(path);
+        let absolute = match path.canonicalize() {
+            Ok(absolute) => absolute,
+            Err(_) => {
+                let name = path.file_name().ok_or(GitError::InvalidPath)?;
+                parent_directory(path).canonicalize().map_err(|_| GitError::InvalidPath)?.join(name)
+            }
+        };
+        let workdir = workdir.canonicalize().map_err(|_| GitError::InvalidPath)?;
+        let relative = absolute.strip_prefix(&workdir).map_err(|_| GitError::InvalidPath)?;
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Content of a file as committed at HEAD; `None` when HEAD is unborn or doesn't contain the file
+    pub fn head_content(&self, path: &str) -> Result<Option<String>, GitError> {
+        let repo = self.open(path)?;
+        let relative = self.relative_path(&repo, path)?;
+        let Ok(head) = repo.head().and_then(|head| head.peel_to_tree()) else {
+            return Ok(None);
+        };
+        let entry = match head.get_path(Path::new(&relative)) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = repo.find_blob(entry.id())?;
+        if blob.is_binary() {
+            return Err(GitError::Git(format!("{} is a binary file", relative)));
+        }
+        Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+    }
+}
+
+/// Directory containing `path`, `.` for a bare file name
+fn parent_directory(path: &Path) -> &Path {
+    path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+impl Default for GitService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,203 @@
+/**
+ * Remote management and fetch with progress reporting
+ */
+
+use git2::{AutotagOption, Config, Cred, CredentialType, FetchOptions, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::{GitError, GitService};
+use crate::notifications::{NotificationService, ProgressHandle};
+
+/// Event carrying transfer progress of a running fetch
+pub const FETCH_PROGRESS_EVENT: &str = "git://fetch-progress";
+
+/// Credential attempts before giving up; libgit2 keeps asking while a credential is rejected
+const MAX_CREDENTIAL_ATTEMPTS: usize = 3;
+
+/// A configured remote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: Option<String>,
+    pub push_url: Option<String>,
+}
+
+/// Credentials supplied by the frontend for HTTPS remotes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitCredentials {
+    pub username: Option<String>,
+    pub token: Option<String>,
+}
+
+/// Transfer progress of a fetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchProgress {
+    pub remote: String,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+/// Outcome of a completed fetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchSummary {
+    pub remote: String,
+    pub received_objects: usize,
+    pub received_bytes: usize,
+    pub updated_refs: Vec<String>,
+}
+
+impl GitService {
+    /// List configured remotes
+    pub fn list_remotes(&self, path: &str) -> Result<Vec<RemoteInfo>, GitError> {
+        let repo = self.open(path)?;
+        let names = repo.remotes()?;
+
+        let mut remotes = Vec::new();
+        for name in names.iter().flatten() {
+            let remote = repo.find_remote(name)?;
+            remotes.push(RemoteInfo {
+                name: name.to_string(),
+                url: remote.url().map(|url| url.to_string()),
+                push_url: remote.pushurl().map(|url| url.to_string()),
+            });
+        }
+        Ok(remotes)
+    }
+
+    /// Add a remote with the default fetch refspec
+    pub fn add_remote(&self, path: &str, name: &str, url: &str) -> Result<RemoteInfo, GitError> {
+        let repo = self.open(path)?;
+        let remote = repo.remote(name, url)?;
+        Ok(RemoteInfo {
+            name: name.to_string(),
+            url: remote.url().map(|url| url.to_string()),
+            push_url: None,
+        })
+    }
+
+    /// Remove a remote and its remote-tracking branches
+    pub fn remove_remote(&self, path: &str, name: &str) -> Result<(), GitError> {
+        let repo = self.open(path)?;
+        repo.find_remote(name).map_err(|_| GitError::NotFound(name.to_string()))?;
+        repo.remote_delete(name)?;
+        Ok(())
+    }
+
+    /// Fetch a remote, emitting `git://fetch-progress` events while objects are transferred. Progress is also
+    /// reported as a notification, which can cancel the fetch.
+    pub fn fetch(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        remote_name: &str,
+        credentials: GitCredentials,
+    ) -> Result<FetchSummary, GitError> {
+        let notification = app.state::<NotificationService>().start_progress(
+            app,
+            "git",
+            &format!("Fetching {}", remote_name),
+            true,
+        );
+        let result = self.run_fetch(app, path, remote_name, credentials, &notification);
+        match &result {
+            Ok(summary) => notification.complete(Some(&format!("{} refs updated", summary.updated_refs.len()))),
+            Err(e) => notification.fail(&e.to_string()),
+        }
+        result
+    }
+
+    fn run_fetch(
+        &self,
+        app: &AppHandle,
+        path: &str,
+        remote_name: &str,
+        credentials: GitCredentials,
+        notification: &ProgressHandle,
+    ) -> Result<FetchSummary, GitError> {
+        let repo = self.open(path)?;
+        let mut remote = repo
+            .find_remote(remote_name)
+            .map_err(|_| GitError::NotFound(remote_name.to_string()))?;
+
+        let mut updated_refs = Vec::new();
+        let stats = {
+            let mut callbacks = credential_callbacks(repo.config().ok(), credentials);
+            let mut last_percent = None;
+            callbacks.transfer_progress(|progress| {
+                let total = progress.total_objects();
+                let percent = (progress.received_objects() + progress.indexed_objects()) * 100 / (total * 2).max(1);
+                // Throttle to one event per percent
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    let _ = app.emit(
+                        FETCH_PROGRESS_EVENT,
+                        FetchProgress {
+                            remote: remote_name.to_string(),
+                            received_objects: progress.received_objects(),
+                            indexed_objects: progress.indexed_objects(),
+                            total_objects: total,
+                            received_bytes: progress.received_bytes(),
+                        },
+                    );
+                    notification.report(Some(percent as u8), None);
+                }
+                // Returning false aborts the transfer
+                !notification.is_cancelled()
+            });
+            callbacks.update_tips(|name, _, _| {
+                updated_refs.push(name.to_string());
+                true
+            });
+
+            let mut options = FetchOptions::new();
+            options.remote_callbacks(callbacks).download_tags(AutotagOption::Auto);
+            // Empty refspecs use the remote's configured fetch refspecs
+            remote.fetch::<&str>(&[], Some(&mut options), None)?;
+            remote.stats()
+        };
+
+        Ok(FetchSummary {
+            remote: remote_name.to_string(),
+            received_objects: stats.received_objects(),
+            received_bytes: stats.received_bytes(),
+            updated_refs,
+        })
+    }
+}
+
+/// Authentication via the SSH agent, a supplied HTTPS token, or the configured credential helper
+pub(crate) fn credential_callbacks(config: Option<Config>, credentials: GitCredentials) -> RemoteCallbacks<'static> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        attempts += 1;
+        if attempts > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::from_str("Authentication failed"));
+        }
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(credentials.username.as_deref().or(username_from_url).unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(token) = credentials.token.as_deref() {
+                let username = credentials.username.as_deref().or(username_from_url).unwrap_or("git");
+                return Cred::userpass_plaintext(username, token);
+            }
+            if let Some(config) = config.as_ref() {
+                return Cred::credential_helper(config, url, username_from_url);
+            }
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(git2::Error::from_str("No supported authentication method"))
+    });
+    callbacks
+}
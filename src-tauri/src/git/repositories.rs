@@ -0,0 +1,121 @@
+/**
+ * Discovery of independent repositories inside a workspace
+ * Powers the multi-repo source control view
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::{GitError, GitService};
+
+/// Directories that never contain repositories worth reporting and are expensive to walk
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "target", ".venv", "venv", "dist", "build"];
+
+/// How deep below the workspace root to look for repositories
+const MAX_DISCOVERY_DEPTH: usize = 6;
+
+/// Status summary of one repository in a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryStatus {
+    pub path: String,
+    pub name: String,
+    pub branch: String,
+    pub dirty_count: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    pub remote_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Whether a `.git` entry marks an independent repository rather than a submodule
+fn is_independent_repository(git_entry: &Path) -> bool {
+    if git_entry.is_dir() {
+        return true;
+    }
+
+    // Submodules and worktrees use a `.git` file pointing at the real git dir
+    fs::read_to_string(git_entry)
+        .ok()
+        .and_then(|content| content.strip_prefix("gitdir:").map(|dir| dir.trim().replace('\\', "/")))
+        .map(|gitdir| !gitdir.contains("/modules/"))
+        .unwrap_or(false)
+}
+
+impl GitService {
+    /// Find the roots of all independent repositories under a workspace, sorted by path
+    pub fn discover_repositories(&self, workspace: &str) -> Result<Vec<String>, GitError> {
+        let root = Path::new(workspace);
+        if !root.is_dir() {
+            return Err(GitError::InvalidPath);
+        }
+
+        let mut roots = Vec::new();
+        let walker = WalkDir::new(root)
+            .max_depth(MAX_DISCOVERY_DEPTH)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_str().unwrap_or("");
+                !(entry.file_type().is_dir() && (name == ".git" || SKIPPED_DIRECTORIES.contains(&name)))
+            });
+
+        for entry in walker.filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+            if is_independent_repository(&entry.path().join(".git")) {
+                roots.push(entry.path().to_string_lossy().to_string());
+            }
+        }
+
+        roots.sort();
+        Ok(roots)
+    }
+
+    /// Status of one repository; failures are reported inline so one broken repo
+    /// doesn't hide the rest of the dashboard
+    pub fn repository_status(&self, path: &str) -> RepositoryStatus {
+        let name = Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path)
+            .to_string();
+
+        let status = self.open(path).and_then(|repo| {
+            let branch = self.branch_name(&repo);
+            let (ahead, behind) = self.ahead_behind(&repo, &branch).unwrap_or((0, 0));
+            Ok(RepositoryStatus {
+                path: path.to_string(),
+                name: name.clone(),
+                dirty_count: self.dirty_count(&repo)?,
+                remote_url: self.origin_url(&repo),
+                branch,
+                ahead,
+                behind,
+                error: None,
+            })
+        });
+
+        status.unwrap_or_else(|err| RepositoryStatus {
+            path: path.to_string(),
+            name,
+            branch: String::new(),
+            dirty_count: 0,
+            ahead: 0,
+            behind: 0,
+            remote_url: None,
+            error: Some(err.to_string()),
+        })
+    }
+
+    /// Aggregated status for every repository in a workspace
+    pub fn workspace_repositories_status(&self, workspace: &str) -> Result<Vec<RepositoryStatus>, GitError> {
+        Ok(self
+            .discover_repositories(workspace)?
+            .iter()
+            .map(|root| self.repository_status(root))
+            .collect())
+    }
+}
@@ -0,0 +1,201 @@
+/**
+ * Glob matching for CodeForge IDE
+ * One implementation of include/exclude glob patterns for every feature that selects files by pattern (task
+ * watchers, file nesting, the expand_glob command), and a walker that only visits the directories the
+ * patterns can match in
+ *
+ * Patterns are relative to a root and use `/` separators. `*` and `?` stop at `/`, `**` crosses directories,
+ * and `{a,b}` alternates. As in `.gitignore`, a pattern without `/` matches names at any depth and an
+ * excluded directory excludes everything in it.
+ */
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Results returned when the caller sets no limit
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+
+/// Characters that make a path component a pattern rather than a literal name
+const GLOB_META: &[char] = &['*', '?', '[', '{'];
+
+/// Error types for glob patterns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GlobError {
+    InvalidPattern { pattern: String, message: String },
+}
+
+impl std::fmt::Display for GlobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GlobError::InvalidPattern { pattern, message } => {
+                write!(f, "Invalid glob pattern '{}': {}", pattern, message)
+            }
+        }
+    }
+}
+
+/// Whether `text` uses glob syntax rather than naming a path literally
+pub fn is_pattern(text: &str) -> bool {
+    text.contains(GLOB_META)
+}
+
+/// Compile patterns into one set
+pub fn build_set(patterns: &[String], case_insensitive: bool) -> Result<GlobSet, GlobError> {
+    let invalid = |pattern: &str, e: globset::Error| GlobError::InvalidPattern {
+        pattern: pattern.to_string(),
+        message: e.kind().to_string(),
+    };
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let anchored = match pattern.contains('/') {
+            true => pattern.trim_start_matches("./").to_string(),
+            false => format!("**/{}", pattern),
+        };
+        let glob = GlobBuilder::new(&anchored)
+            .literal_separator(true)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| invalid(pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| invalid(&patterns.join(", "), e))
+}
+
+/// Include and exclude patterns compiled together
+pub struct GlobMatcher {
+    include: GlobSet,
+    exclude: GlobSet,
+    /// Directories below which the include patterns can match, relative to the root; `None` when a pattern
+    /// can match anywhere
+    bases: Option<Vec<PathBuf>>,
+}
+
+impl GlobMatcher {
+    pub fn new(patterns: &[String], exclude: &[String], case_insensitive: bool) -> Result<Self, GlobError> {
+        // `dir/**` excludes the directory itself too, so the walk never enters it
+        let exclude: Vec<String> = exclude
+            .iter()
+            .flat_map(|pattern| {
+                let directory = pattern.strip_suffix("/**").map(str::to_string);
+                std::iter::once(pattern.clone()).chain(directory)
+            })
+            .collect();
+        Ok(GlobMatcher {
+            include: build_set(patterns, case_insensitive)?,
+            exclude: build_set(&exclude, case_insensitive)?,
+            bases: literal_bases(patterns),
+        })
+    }
+
+    /// Whether a path relative to the root matches an include pattern and no exclude pattern. Excluded
+    /// ancestors aren't checked here; `expand` never visits them.
+    pub fn is_match(&self, relative: &Path) -> bool {
+        self.include.is_match(relative) && !self.exclude.is_match(relative)
+    }
+}
+
+/// The literal leading directories of each pattern (`src` for `src/**/*.rs`), with bases nested in other
+/// bases dropped
+fn literal_bases(patterns: &[String]) -> Option<Vec<PathBuf>> {
+    let mut bases: Vec<PathBuf> = Vec::new();
+    for pattern in patterns {
+        let components: Vec<&str> = pattern.trim_start_matches("./").split('/').collect();
+        // The last component names the entries themselves, not a directory to walk
+        let literal: PathBuf = components[..components.len() - 1]
+            .iter()
+            .take_while(|component| !is_pattern(component))
+            .collect();
+        let escapes = literal.components().any(|component| !matches!(component, Component::Normal(_)));
+        if literal.as_os_str().is_empty() || escapes {
+            return None;
+        }
+        bases.push(literal);
+    }
+    bases.sort();
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for base in bases {
+        if !kept.iter().any(|outer| base.starts_with(outer)) {
+            kept.push(base);
+        }
+    }
+    Some(kept)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlobOptions {
+    #[serde(default)]
+    pub include_hidden: bool,
+    /// Also match files `.gitignore` and the like exclude
+    #[serde(default)]
+    pub include_ignored: bool,
+    #[serde(default)]
+    pub include_directories: bool,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// At most this many results; defaults to 10000
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+/// Result of `expand`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlobExpansion {
+    pub root: String,
+    /// Relative to `root` with `/` separators, sorted
+    pub matches: Vec<String>,
+    /// More entries matched than `max_results`
+    pub truncated: bool,
+    /// Entries looked at, to show how much the patterns narrowed the walk
+    pub visited: usize,
+}
+
+fn relative_string(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Find the entries under `root` that `matcher` matches, walking only the directories its patterns can
+/// match in and skipping excluded directories entirely
+pub fn expand(root: &Path, matcher: &GlobMatcher, options: &GlobOptions) -> GlobExpansion {
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let mut expansion = GlobExpansion {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let starts: Vec<PathBuf> = match &matcher.bases {
+        Some(bases) => bases.iter().map(|base| root.join(base)).filter(|start| start.is_dir()).collect(),
+        None => vec![root.to_path_buf()],
+    };
+
+    'walk: for start in starts {
+        let walk_root = root.to_path_buf();
+        let exclude = matcher.exclude.clone();
+        let walker = WalkBuilder::new(&start)
+            .standard_filters(!options.include_ignored)
+            .hidden(!options.include_hidden)
+            .filter_entry(move |entry| {
+                let relative = entry.path().strip_prefix(&walk_root).unwrap_or(entry.path());
+                let is_directory = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+                !(is_directory && exclude.is_match(relative))
+            })
+            .build();
+        for entry in walker.flatten() {
+            expansion.visited += 1;
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            let is_directory = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+            if relative.as_os_str().is_empty() || (is_directory && !options.include_directories) {
+                continue;
+            }
+            if matcher.is_match(relative) {
+                if expansion.matches.len() == max_results {
+                    expansion.truncated = true;
+                    break 'walk;
+                }
+                expansion.matches.push(relative_string(relative));
+            }
+        }
+    }
+    expansion.matches.sort();
+    expansion
+}
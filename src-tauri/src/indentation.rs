@@ -0,0 +1,174 @@
+/**
+ * Indentation detection and conversion
+ * Guesses whether a file is indented with tabs or spaces and how wide, and rewrites leading whitespace from
+ * one style to the other for the "Convert Indentation" command
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Indentation steps considered when guessing the width of space indentation
+const MAX_INDENT_WIDTH: usize = 8;
+
+/// Delimiters of strings that can span lines; whitespace inside them is content, not indentation
+const MULTILINE_STRING_DELIMITERS: &[&str] = &["\"\"\"", "'''", "`"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    Tabs,
+    Spaces,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Indentation {
+    pub style: IndentStyle,
+    /// Spaces per level; `None` for tabs, or when no steps between lines could be measured
+    pub width: Option<usize>,
+    pub tab_lines: u64,
+    pub space_lines: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndentationConversion {
+    pub path: String,
+    pub changed_lines: usize,
+    /// Indentation detected after the conversion
+    pub indentation: Option<Indentation>,
+}
+
+/// Collects indentation evidence one line at a time
+#[derive(Default)]
+pub struct IndentationCounter {
+    tab_lines: u64,
+    space_lines: u64,
+    /// How often consecutive space-indented lines differ by each number of spaces
+    steps: [u64; MAX_INDENT_WIDTH + 1],
+    previous_spaces: Option<usize>,
+}
+
+impl IndentationCounter {
+    pub fn record(&mut self, line: &str) {
+        let content = line.trim_start_matches([' ', '\t']);
+        // Blank lines say nothing; ` * ` continuation lines of block comments are off by one on purpose
+        if content.is_empty() || content.starts_with('*') {
+            return;
+        }
+        let leading = &line[..line.len() - content.len()];
+        if leading.starts_with('\t') {
+            self.tab_lines += 1;
+            self.previous_spaces = None;
+            return;
+        }
+        if leading.contains('\t') {
+            self.previous_spaces = None;
+            return;
+        }
+        let spaces = leading.len();
+        if spaces > 0 {
+            self.space_lines += 1;
+        }
+        if let Some(previous) = self.previous_spaces {
+            let step = spaces.abs_diff(previous);
+            if (2..=MAX_INDENT_WIDTH).contains(&step) {
+                self.steps[step] += 1;
+            }
+        }
+        self.previous_spaces = Some(spaces);
+    }
+
+    /// The prevailing style; `None` when no line is indented
+    pub fn indentation(&self) -> Option<Indentation> {
+        let style = match (self.tab_lines, self.space_lines) {
+            (0, 0) => return None,
+            (tabs, spaces) if tabs > spaces => IndentStyle::Tabs,
+            _ => IndentStyle::Spaces,
+        };
+        // The most common step; ties go to the narrower width, since 4-space code also steps by 8
+        let width = match style {
+            IndentStyle::Tabs => None,
+            IndentStyle::Spaces => (2..=MAX_INDENT_WIDTH)
+                .filter(|width| self.steps[*width] > 0)
+                .max_by(|a, b| self.steps[*a].cmp(&self.steps[*b]).then(b.cmp(a))),
+        };
+        Some(Indentation {
+            style,
+            width,
+            tab_lines: self.tab_lines,
+            space_lines: self.space_lines,
+        })
+    }
+}
+
+pub fn detect_indentation(content: &str) -> Option<Indentation> {
+    let mut counter = IndentationCounter::default();
+    content.lines().for_each(|line| counter.record(line));
+    counter.indentation()
+}
+
+/// Leading whitespace of `line` in the target style, or `None` when it already matches. Tabs advance to the
+/// next multiple of `width`. Converting to tabs keeps columns that aren't a whole level as spaces, so
+/// alignment (e.g. of ` * ` comment lines or wrapped arguments) survives.
+fn convert_leading(line: &str, style: IndentStyle, width: usize) -> Option<String> {
+    let content = line.trim_start_matches([' ', '\t']);
+    let leading = &line[..line.len() - content.len()];
+    if content.trim_end().is_empty() {
+        return None;
+    }
+    let columns = leading.chars().fold(0, |column, c| match c {
+        '\t' => column + width - column % width,
+        _ => column + 1,
+    });
+    let converted = match style {
+        IndentStyle::Spaces => " ".repeat(columns),
+        IndentStyle::Tabs => format!("{}{}", "\t".repeat(columns / width), " ".repeat(columns % width)),
+    };
+    (converted != leading).then(|| format!("{}{}", converted, content))
+}
+
+/// The multi-line string still open after `line`, given the one open before it. Escapes aren't parsed, so
+/// this is a best effort that errs towards leaving lines alone.
+fn open_string_after(line: &str, mut open: Option<&'static str>) -> Option<&'static str> {
+    let mut rest = line;
+    loop {
+        match open {
+            Some(delimiter) => match rest.find(delimiter) {
+                Some(end) => {
+                    rest = &rest[end + delimiter.len()..];
+                    open = None;
+                }
+                None => return open,
+            },
+            None => {
+                let (start, delimiter) = MULTILINE_STRING_DELIMITERS
+                    .iter()
+                    .filter_map(|delimiter| rest.find(delimiter).map(|start| (start, *delimiter)))
+                    .min_by_key(|(start, delimiter)| (*start, std::cmp::Reverse(delimiter.len())))?;
+                rest = &rest[start + delimiter.len()..];
+                open = Some(delimiter);
+            }
+        }
+    }
+}
+
+/// Rewrite the indentation of every line to `style`, returning the new content and how many lines changed.
+/// Only leading whitespace is touched, and lines continuing a multi-line string are left as they are.
+pub fn convert_indentation(content: &str, style: IndentStyle, width: usize) -> (String, usize) {
+    let width = width.clamp(1, MAX_INDENT_WIDTH);
+    let mut converted = String::with_capacity(content.len());
+    let mut changed_lines = 0;
+    let mut open_string = None;
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[body.len()..];
+        match open_string.is_none().then(|| convert_leading(body, style, width)).flatten() {
+            Some(body) => {
+                converted.push_str(&body);
+                converted.push_str(ending);
+                changed_lines += 1;
+            }
+            None => converted.push_str(line),
+        }
+        open_string = open_string_after(body, open_string);
+    }
+    (converted, changed_lines)
+}
@@ -0,0 +1,411 @@
+/**
+ * Keymap Service for CodeForge IDE
+ * Built-in keybindings plus validated user overrides persisted in the app config dir
+ */
+
+use crate::atomic_file::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event broadcast to every window after keybindings change
+pub const KEYBINDINGS_CHANGED_EVENT: &str = "keymap://changed";
+
+const KEYBINDINGS_FILE: &str = "keybindings.json";
+
+/// Longest supported chord sequence, e.g. `Ctrl+K Ctrl+S`
+const MAX_CHORD_PARTS: usize = 2;
+
+/// Modifiers in canonical order
+const MODIFIERS: [&str; 4] = ["Ctrl", "Shift", "Alt", "Meta"];
+
+const NAMED_KEYS: &[&str] = &[
+    "Enter", "Escape", "Tab", "Space", "Backspace", "Delete", "Insert", "Home", "End", "PageUp", "PageDown", "Up",
+    "Down", "Left", "Right",
+];
+
+const PUNCTUATION_KEYS: &str = "`-=[]\\;',./";
+
+/// Built-in bindings; `CmdOrCtrl` becomes Meta on macOS and Ctrl elsewhere
+const DEFAULT_KEYBINDINGS: &[(&str, &str, Option<&str>)] = &[
+    ("workbench.action.showCommands", "CmdOrCtrl+Shift+P", None),
+    ("workbench.action.quickOpen", "CmdOrCtrl+P", None),
+    ("workbench.action.openRecent", "CmdOrCtrl+R", None),
+    ("workbench.action.files.newUntitledFile", "CmdOrCtrl+N", None),
+    ("workbench.action.files.openFile", "CmdOrCtrl+O", None),
+    ("workbench.action.files.save", "CmdOrCtrl+S", None),
+    ("workbench.action.files.saveAll", "CmdOrCtrl+Alt+S", None),
+    ("workbench.action.closeActiveEditor", "CmdOrCtrl+W", None),
+    ("workbench.action.toggleSidebarVisibility", "CmdOrCtrl+B", None),
+    ("workbench.action.togglePanel", "CmdOrCtrl+J", None),
+    ("workbench.action.terminal.toggleTerminal", "Ctrl+`", None),
+    ("workbench.action.openSettings", "CmdOrCtrl+,", None),
+    ("workbench.action.openKeybindings", "CmdOrCtrl+K CmdOrCtrl+S", None),
+    ("workbench.action.findInFiles", "CmdOrCtrl+Shift+F", None),
+    ("workbench.view.scm", "Ctrl+Shift+G", None),
+    ("workbench.action.splitEditor", "CmdOrCtrl+\\", None),
+    ("editor.action.find", "CmdOrCtrl+F", Some("editorFocus")),
+    ("editor.action.replace", "CmdOrCtrl+H", Some("editorFocus")),
+    ("editor.action.commentLine", "CmdOrCtrl+/", Some("editorTextFocus")),
+    ("editor.action.formatDocument", "Shift+Alt+F", Some("editorTextFocus")),
+    ("editor.action.rename", "F2", Some("editorTextFocus")),
+    ("editor.action.revealDefinition", "F12", Some("editorTextFocus")),
+    ("editor.action.gotoLine", "Ctrl+G", Some("editorFocus")),
+    ("editor.action.copyLinesDown", "Shift+Alt+Down", Some("editorTextFocus")),
+    ("editor.action.moveLinesUp", "Alt+Up", Some("editorTextFocus")),
+    ("editor.action.moveLinesDown", "Alt+Down", Some("editorTextFocus")),
+    ("editor.action.selectAll", "CmdOrCtrl+A", Some("editorFocus")),
+    ("undo", "CmdOrCtrl+Z", None),
+    ("redo", "CmdOrCtrl+Shift+Z", None),
+    ("workbench.action.debug.start", "F5", None),
+    ("workbench.action.debug.stepOver", "F10", Some("inDebugMode")),
+    ("workbench.action.debug.stepInto", "F11", Some("inDebugMode")),
+    ("editor.debug.action.toggleBreakpoint", "F9", Some("editorTextFocus")),
+];
+
+/// Error types for keymap operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum KeymapError {
+    NoConfigDirectory,
+    IOError(String),
+    InvalidChord(String),
+    UnknownCommand(String),
+}
+
+impl std::fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KeymapError::NoConfigDirectory => write!(f, "App config directory is unavailable"),
+            KeymapError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            KeymapError::InvalidChord(msg) => write!(f, "Invalid key chord: {}", msg),
+            KeymapError::UnknownCommand(command) => write!(f, "Unknown command: {}", command),
+        }
+    }
+}
+
+/// Where a binding comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeybindingSource {
+    Default,
+    User,
+}
+
+/// A user override; `key: None` removes the command's binding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindingOverride {
+    pub command: String,
+    pub key: Option<String>,
+    #[serde(default)]
+    pub when: Option<String>,
+}
+
+/// An active binding with its normalized chord
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keybinding {
+    pub command: String,
+    pub key: String,
+    pub when: Option<String>,
+    pub source: KeybindingSource,
+}
+
+/// Two bindings that fire on the same keys in overlapping contexts; a single chord
+/// that is the prefix of a two-part chord also conflicts because it shadows it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindingConflict {
+    pub key: String,
+    pub commands: Vec<String>,
+}
+
+/// Resolved keymap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keymap {
+    pub bindings: Vec<Keybinding>,
+    pub conflicts: Vec<KeybindingConflict>,
+}
+
+/// Parse and normalize a chord sequence such as `ctrl+shift+p` or `Ctrl+K Ctrl+S`
+pub fn normalize_chord(chord: &str) -> Result<String, KeymapError> {
+    let parts: Vec<&str> = chord.split_whitespace().collect();
+    if parts.is_empty() {
+        return Err(KeymapError::InvalidChord("empty chord".to_string()));
+    }
+    if parts.len() > MAX_CHORD_PARTS {
+        return Err(KeymapError::InvalidChord(format!(
+            "'{}' has more than {} parts",
+            chord, MAX_CHORD_PARTS
+        )));
+    }
+    parts.iter().map(|part| normalize_part(part)).collect::<Result<Vec<_>, _>>().map(|parts| parts.join(" "))
+}
+
+fn normalize_part(part: &str) -> Result<String, KeymapError> {
+    // `+` itself is a valid key, so split on `+` only between tokens
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut rest = part;
+    while let Some(index) = rest[1.min(rest.len())..].find('+').map(|index| index + 1) {
+        tokens.push(&rest[..index]);
+        rest = &rest[index + 1..];
+    }
+    tokens.push(rest);
+
+    let (key, modifiers) = tokens.split_last().expect("at least one token");
+    let mut present = [false; 4];
+    for modifier in modifiers {
+        let index = match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => 0,
+            "shift" => 1,
+            "alt" | "option" | "opt" => 2,
+            "meta" | "cmd" | "command" | "super" | "win" => 3,
+            "cmdorctrl" | "commandorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    3
+                } else {
+                    0
+                }
+            }
+            _ => return Err(KeymapError::InvalidChord(format!("unknown modifier '{}' in '{}'", modifier, part))),
+        };
+        if present[index] {
+            return Err(KeymapError::InvalidChord(format!("duplicate modifier '{}' in '{}'", modifier, part)));
+        }
+        present[index] = true;
+    }
+
+    let key = normalize_key(key).ok_or_else(|| KeymapError::InvalidChord(format!("unknown key in '{}'", part)))?;
+    let mut normalized: Vec<&str> = MODIFIERS
+        .iter()
+        .zip(present)
+        .filter_map(|(modifier, present)| present.then_some(*modifier))
+        .collect();
+    normalized.push(&key);
+    Ok(normalized.join("+"))
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => return Some(c.to_ascii_uppercase().to_string()),
+        (Some(c), None) if PUNCTUATION_KEYS.contains(c) || c == '+' => return Some(c.to_string()),
+        _ => {}
+    }
+
+    let lower = key.to_lowercase();
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&number).then(|| format!("F{}", number));
+    }
+    let alias = match lower.as_str() {
+        "esc" => "escape",
+        "return" => "enter",
+        "del" => "delete",
+        "ins" => "insert",
+        "arrowup" => "up",
+        "arrowdown" => "down",
+        "arrowleft" => "left",
+        "arrowright" => "right",
+        "pgup" => "pageup",
+        "pgdn" => "pagedown",
+        "plus" => return Some("+".to_string()),
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|named| named.to_lowercase() == alias)
+        .map(|named| named.to_string())
+}
+
+/// Whether two `when` clauses can hold at the same time; without an expression evaluator,
+/// only different non-empty clauses are treated as disjoint
+fn contexts_overlap(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+fn find_conflicts(bindings: &[Keybinding]) -> Vec<KeybindingConflict> {
+    let mut conflicts: HashMap<String, Vec<String>> = HashMap::new();
+    for (index, binding) in bindings.iter().enumerate() {
+        for other in &bindings[index + 1..] {
+            if other.command == binding.command || !contexts_overlap(&binding.when, &other.when) {
+                continue;
+            }
+            let shadows = |short: &str, long: &str| long.starts_with(short) && long[short.len()..].starts_with(' ');
+            let key = if binding.key == other.key || shadows(&binding.key, &other.key) {
+                &binding.key
+            } else if shadows(&other.key, &binding.key) {
+                &other.key
+            } else {
+                continue;
+            };
+            let commands = conflicts.entry(key.clone()).or_default();
+            for command in [&binding.command, &other.command] {
+                if !commands.contains(command) {
+                    commands.push(command.clone());
+                }
+            }
+        }
+    }
+
+    let mut conflicts: Vec<KeybindingConflict> = conflicts
+        .into_iter()
+        .map(|(key, commands)| KeybindingConflict { key, commands })
+        .collect();
+    conflicts.sort_by(|a, b| a.key.cmp(&b.key));
+    conflicts
+}
+
+pub struct KeymapService {
+    overrides: Arc<Mutex<Option<Vec<KeybindingOverride>>>>,
+}
+
+impl KeymapService {
+    pub fn new() -> Self {
+        Self {
+            overrides: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn keybindings_path(app: &AppHandle) -> Result<PathBuf, KeymapError> {
+        let config_dir = app.path().app_config_dir().map_err(|_| KeymapError::NoConfigDirectory)?;
+        Ok(config_dir.join(KEYBINDINGS_FILE))
+    }
+
+    /// User overrides; entries with invalid chords are skipped so one typo doesn't disable the keymap
+    fn load_overrides(&self, app: &AppHandle) -> Result<Vec<KeybindingOverride>, KeymapError> {
+        let mut cached = self.overrides.lock().unwrap();
+        if let Some(overrides) = cached.as_ref() {
+            return Ok(overrides.clone());
+        }
+
+        let overrides: Vec<KeybindingOverride> = match fs::read_to_string(Self::keybindings_path(app)?) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(KeymapError::IOError(e.to_string())),
+        };
+        let overrides: Vec<KeybindingOverride> = overrides
+            .into_iter()
+            .filter_map(|mut entry| {
+                if let Some(key) = &entry.key {
+                    entry.key = Some(normalize_chord(key).ok()?);
+                }
+                Some(entry)
+            })
+            .collect();
+
+        *cached = Some(overrides.clone());
+        Ok(overrides)
+    }
+
+    /// Defaults with user overrides applied, plus any remaining conflicts
+    pub fn keybindings(&self, app: &AppHandle) -> Result<Keymap, KeymapError> {
+        let overrides = self.load_overrides(app)?;
+        let mut bindings: Vec<Keybinding> = DEFAULT_KEYBINDINGS
+            .iter()
+            .filter(|(command, _, _)| !overrides.iter().any(|entry| entry.command == *command))
+            .filter_map(|(command, key, when)| {
+                Some(Keybinding {
+                    command: command.to_string(),
+                    key: normalize_chord(key).ok()?,
+                    when: when.map(str::to_string),
+                    source: KeybindingSource::Default,
+                })
+            })
+            .collect();
+        bindings.extend(overrides.into_iter().filter_map(|entry| {
+            Some(Keybinding {
+                command: entry.command,
+                key: entry.key?,
+                when: entry.when,
+                source: KeybindingSource::User,
+            })
+        }));
+        bindings.sort_by(|a, b| a.command.cmp(&b.command));
+
+        Ok(Keymap {
+            conflicts: find_conflicts(&bindings),
+            bindings,
+        })
+    }
+
+    /// Bind `command` to `key` (or unbind it with `None`) and persist the override
+    ///
+    /// With `replace_conflicts`, commands already bound to the same keys in an overlapping
+    /// context are unbound so the new binding wins.
+    pub fn update_keybinding(
+        &self,
+        app: &AppHandle,
+        command: &str,
+        key: Option<&str>,
+        when: Option<&str>,
+        replace_conflicts: bool,
+    ) -> Result<Keymap, KeymapError> {
+        if command.trim().is_empty() {
+            return Err(KeymapError::UnknownCommand(command.to_string()));
+        }
+        let key = key.map(normalize_chord).transpose()?;
+        let when = when.map(str::trim).filter(|when| !when.is_empty()).map(str::to_string);
+
+        let mut overrides = self.load_overrides(app)?;
+        overrides.retain(|entry| entry.command != command);
+        overrides.push(KeybindingOverride {
+            command: command.to_string(),
+            key: key.clone(),
+            when: when.clone(),
+        });
+
+        if replace_conflicts {
+            if let Some(key) = &key {
+                let displaced: Vec<String> = self
+                    .keybindings(app)?
+                    .bindings
+                    .into_iter()
+                    .filter(|binding| {
+                        binding.command != command && binding.key == *key && contexts_overlap(&binding.when, &when)
+                    })
+                    .map(|binding| binding.command)
+                    .collect();
+                overrides.retain(|entry| !displaced.contains(&entry.command));
+                overrides.extend(displaced.into_iter().map(|command| KeybindingOverride {
+                    command,
+                    key: None,
+                    when: None,
+                }));
+            }
+        }
+
+        self.store(app, overrides)?;
+        let keymap = self.keybindings(app)?;
+        let _ = app.emit(KEYBINDINGS_CHANGED_EVENT, keymap.clone());
+        Ok(keymap)
+    }
+
+    /// Drop the user override of a command, restoring its default binding
+    pub fn reset_keybinding(&self, app: &AppHandle, command: &str) -> Result<Keymap, KeymapError> {
+        let mut overrides = self.load_overrides(app)?;
+        overrides.retain(|entry| entry.command != command);
+        self.store(app, overrides)?;
+        let keymap = self.keybindings(app)?;
+        let _ = app.emit(KEYBINDINGS_CHANGED_EVENT, keymap.clone());
+        Ok(keymap)
+    }
+
+    fn store(&self, app: &AppHandle, overrides: Vec<KeybindingOverride>) -> Result<(), KeymapError> {
+        let path = Self::keybindings_path(app)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| KeymapError::IOError(e.to_string()))?;
+        }
+        let content = serde_json::to_string_pretty(&overrides).map_err(|e| KeymapError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(|e| KeymapError::IOError(e.to_string()))?;
+        *self.overrides.lock().unwrap() = Some(overrides);
+        Ok(())
+    }
+}
+
+impl Default for KeymapService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,308 @@
+/**
+ * Launch Configuration Service for CodeForge IDE
+ * Loads run/debug configurations from `.codeforge/launch.json` and resolves them into debug requests
+ */
+
+use crate::debug::{builtin_adapter, AdapterCommand, DebugLaunchRequest, DebugRequestKind};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Location of the launch file relative to the workspace root
+pub const LAUNCH_FILE: &str = ".codeforge/launch.json";
+
+/// Error types for launch configuration operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LaunchConfigError {
+    InvalidFile(String),
+    NotFound(String),
+    Validation(Vec<String>),
+    UnresolvedVariable(String),
+}
+
+impl std::fmt::Display for LaunchConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LaunchConfigError::InvalidFile(msg) => write!(f, "Invalid launch file: {}", msg),
+            LaunchConfigError::NotFound(name) => write!(f, "Launch configuration not found: {}", name),
+            LaunchConfigError::Validation(problems) => {
+                write!(f, "Invalid launch configuration: {}", problems.join("; "))
+            }
+            LaunchConfigError::UnresolvedVariable(name) => write!(f, "Cannot resolve variable ${{{}}}", name),
+        }
+    }
+}
+
+/// A run/debug configuration; fields other than the ones below are passed to the adapter as-is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchConfig {
+    pub name: String,
+    /// Debug type such as `lldb`, `python`, or `node`
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: DebugRequestKind,
+    /// Task to run (by label) before the session starts
+    #[serde(default)]
+    pub pre_launch_task: Option<String>,
+    /// Overrides the built-in adapter for the debug type
+    #[serde(default)]
+    pub adapter: Option<AdapterCommand>,
+    #[serde(flatten)]
+    pub configuration: Map<String, Value>,
+    /// Problems found while validating; configurations with problems can't be resolved
+    #[serde(default, skip_deserializing)]
+    pub problems: Vec<String>,
+}
+
+/// A configuration with its variables substituted, ready for `debug_start`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedLaunchConfig {
+    pub request: DebugLaunchRequest,
+    pub pre_launch_task: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LaunchFile {
+    #[serde(default)]
+    configurations: Vec<LaunchConfig>,
+}
+
+/// Values available to `${...}` references
+pub struct VariableContext {
+    pub workspace_folder: PathBuf,
+    /// File open in the active editor
+    pub file: Option<PathBuf>,
+}
+
+impl VariableContext {
+    fn resolve(&self, name: &str) -> Result<String, LaunchConfigError> {
+        let unresolved = || LaunchConfigError::UnresolvedVariable(name.to_string());
+        if let Some(variable) = name.strip_prefix("env:") {
+            return Ok(std::env::var(variable).unwrap_or_default());
+        }
+
+        let text = |path: &Path| path.to_string_lossy().to_string();
+        match name {
+            "workspaceFolder" | "cwd" => return Ok(text(&self.workspace_folder)),
+            "workspaceFolderBasename" => {
+                return Ok(self.workspace_folder.file_name().map(|n| text(Path::new(n))).unwrap_or_default())
+            }
+            "pathSeparator" => return Ok(MAIN_SEPARATOR.to_string()),
+            _ => {}
+        }
+
+        let file = self.file.as_deref().ok_or_else(unresolved)?;
+        Ok(match name {
+            "file" => text(file),
+            "relativeFile" => text(file.strip_prefix(&self.workspace_folder).unwrap_or(file)),
+            "fileBasename" => file.file_name().map(|n| text(Path::new(n))).unwrap_or_default(),
+            "fileBasenameNoExtension" => file.file_stem().map(|n| text(Path::new(n))).unwrap_or_default(),
+            "fileExtname" => file.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default(),
+            "fileDirname" => file.parent().map(text).unwrap_or_default(),
+            _ => return Err(unresolved()),
+        })
+    }
+
+    /// Replace every `${name}` in `text`; `$` not followed by `{` is kept
+    pub fn substitute(&self, text: &str) -> Result<String, LaunchConfigError> {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                return Err(LaunchConfigError::UnresolvedVariable(rest[start + 2..].to_string()));
+            };
+            result.push_str(&self.resolve(&rest[start + 2..start + end])?);
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// Substitute variables in every string inside a JSON value
+    pub fn substitute_value(&self, value: &Value) -> Result<Value, LaunchConfigError> {
+        Ok(match value {
+            Value::String(text) => Value::String(self.substitute(text)?),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.substitute_value(item)).collect::<Result<_, _>>()?)
+            }
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.substitute_value(value)?)))
+                    .collect::<Result<_, LaunchConfigError>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+}
+
+/// Variable names referenced in a JSON value
+fn referenced_variables(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::String(text) => {
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("${") {
+                let Some(end) = rest[start..].find('}') else {
+                    break;
+                };
+                names.push(rest[start + 2..start + end].to_string());
+                rest = &rest[start + end + 1..];
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| referenced_variables(item, names)),
+        Value::Object(fields) => fields.values().for_each(|value| referenced_variables(value, names)),
+        _ => {}
+    }
+}
+
+const KNOWN_VARIABLES: &[&str] = &[
+    "workspaceFolder",
+    "workspaceFolderBasename",
+    "cwd",
+    "pathSeparator",
+    "file",
+    "relativeFile",
+    "fileBasename",
+    "fileBasenameNoExtension",
+    "fileExtname",
+    "fileDirname",
+];
+
+/// Problems that make a configuration unusable regardless of the active file
+fn validate(config: &LaunchConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    if config.name.trim().is_empty() {
+        problems.push("name must not be empty".to_string());
+    }
+    if config.adapter.is_none() && builtin_adapter(&config.debug_type).is_none() {
+        problems.push(format!("no debug adapter for type '{}'", config.debug_type));
+    }
+    match config.request {
+        DebugRequestKind::Launch => {
+            let has_target = ["program", "module", "runtimeExecutable"];
+            if !has_target.iter().any(|key| config.configuration.contains_key(*key)) {
+                problems.push("launch configurations need a program or module".to_string());
+            }
+        }
+        DebugRequestKind::Attach => {
+            let has_target = ["port", "processId", "pid"];
+            if !has_target.iter().any(|key| config.configuration.contains_key(*key)) {
+                problems.push("attach configurations need a port or process id".to_string());
+            }
+        }
+    }
+
+    let mut names = Vec::new();
+    referenced_variables(&Value::Object(config.configuration.clone()), &mut names);
+    for name in names {
+        if !name.starts_with("env:") && !KNOWN_VARIABLES.contains(&name.as_str()) {
+            problems.push(format!("unknown variable ${{{}}}", name));
+        }
+    }
+    problems
+}
+
+type LaunchFileCache = Arc<Mutex<HashMap<String, (Option<SystemTime>, Vec<LaunchConfig>)>>>;
+
+pub struct LaunchConfigService {
+    cache: LaunchFileCache,
+}
+
+impl LaunchConfigService {
+    pub fn new() -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Configurations of a workspace with their validation problems; a missing file means none
+    pub fn list_configs(&self, workspace: &str) -> Result<Vec<LaunchConfig>, LaunchConfigError> {
+        let path = Path::new(workspace).join(LAUNCH_FILE);
+        let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((cached_modified, configs)) = cache.get(workspace) {
+            if *cached_modified == modified {
+                return Ok(configs.clone());
+            }
+        }
+
+        let mut configs = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| LaunchConfigError::InvalidFile(e.to_string()))?;
+            let file: LaunchFile = serde_json::from_str(&content)
+                .map_err(|e| LaunchConfigError::InvalidFile(format!("{}: {}", LAUNCH_FILE, e)))?;
+            file.configurations
+        } else {
+            Vec::new()
+        };
+
+        let mut seen = HashSet::new();
+        for config in &mut configs {
+            config.problems = validate(config);
+            if !seen.insert(config.name.clone()) {
+                config.problems.push(format!("duplicate configuration name '{}'", config.name));
+            }
+        }
+
+        cache.insert(workspace.to_string(), (modified, configs.clone()));
+        Ok(configs)
+    }
+
+    /// Substitute variables in a configuration and turn it into a debug request
+    pub fn resolve_config(
+        &self,
+        workspace: &str,
+        name: &str,
+        active_file: Option<&str>,
+    ) -> Result<ResolvedLaunchConfig, LaunchConfigError> {
+        let config = self
+            .list_configs(workspace)?
+            .into_iter()
+            .find(|config| config.name == name)
+            .ok_or_else(|| LaunchConfigError::NotFound(name.to_string()))?;
+        if !config.problems.is_empty() {
+            return Err(LaunchConfigError::Validation(config.problems));
+        }
+
+        let context = VariableContext {
+            workspace_folder: PathBuf::from(workspace),
+            file: active_file.map(PathBuf::from),
+        };
+        let Value::Object(mut configuration) = context.substitute_value(&Value::Object(config.configuration))?
+        else {
+            unreachable!("substituting an object yields an object");
+        };
+        let cwd = match configuration.get("cwd").and_then(Value::as_str) {
+            Some(cwd) => cwd.to_string(),
+            None => workspace.to_string(),
+        };
+        configuration.insert("cwd".to_string(), Value::String(cwd.clone()));
+        configuration.insert("name".to_string(), Value::String(config.name.clone()));
+        configuration.insert("type".to_string(), Value::String(config.debug_type.clone()));
+
+        Ok(ResolvedLaunchConfig {
+            request: DebugLaunchRequest {
+                name: config.name,
+                debug_type: config.debug_type,
+                request: config.request,
+                adapter: config.adapter,
+                configuration,
+                breakpoints: HashMap::new(),
+                cwd: Some(cwd),
+                workspace: Some(workspace.to_string()),
+            },
+            pre_launch_task: config.pre_launch_task,
+        })
+    }
+}
+
+impl Default for LaunchConfigService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
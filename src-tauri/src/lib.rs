@@ -1,38 +1,539 @@
 // CodeForge IDE - Core Application Module
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
+mod activity;
+mod atomic_file;
+mod autosave;
+mod backup;
+mod bookmarks;
+mod bulk_rename;
+mod checksum;
+mod clock;
+mod clipboard;
+mod command_registry;
 mod commands;
+mod crash_reports;
+mod database;
+mod debug;
+mod decorations;
+mod desktop;
+mod devcontainer;
+mod diagnostics;
+mod diff;
+mod disk_usage;
+mod editorconfig;
+mod environment;
+mod extended_attributes;
+mod file_clipboard;
+mod file_history;
+mod file_icons;
+mod file_import;
+mod file_locks;
+mod file_nesting;
+mod file_stats;
 mod file_system;
+mod file_templates;
+mod file_type;
+mod formatter;
+mod fs_undo;
+mod fs_provider;
+mod fs_watch;
+mod git;
+mod globs;
+mod indentation;
+mod jsonc;
+mod keymap;
+mod launch;
+mod license_headers;
+mod logging;
+mod merge;
+mod mounts;
+mod navigation;
+mod notifications;
+mod operation_log;
+mod path_utils;
+mod performance;
+mod plugins;
+mod port_forwarding;
+mod ports;
+mod preview_server;
+mod problem_matcher;
+mod project_templates;
+mod recent;
+mod regex_tester;
+mod resource_usage;
+mod rest_client;
+mod save_pipeline;
+mod session;
+mod settings;
+mod snippets;
+mod spellcheck;
+mod syntax;
+mod system_info;
+mod tail;
+mod tasks;
+mod terminal;
+mod theme;
 mod types;
 mod utils;
+mod window_manager;
+mod workspace_edit;
+mod workspace_stats;
+mod wsl;
 
+use activity::ActivityService;
+use autosave::AutoSaveService;
+use backup::BackupService;
+use bookmarks::BookmarkStore;
+use clipboard::ClipboardService;
+use command_registry::CommandRegistry;
 use commands::*;
+use crash_reports::CrashReportService;
+use database::DatabaseService;
+use debug::{BreakpointStore, DebugService};
+use devcontainer::DevContainerService;
+use diagnostics::DiagnosticsService;
+use file_clipboard::FileClipboardService;
+use file_history::FileHistoryService;
+use file_locks::{FileLockChange, FileLockService, FILE_LOCK_CHANGED_EVENT};
 use file_system::FileSystemService;
+use fs_undo::FsUndoService;
+use git::GitService;
+use keymap::KeymapService;
+use launch::LaunchConfigService;
+use logging::LogService;
+use notifications::NotificationService;
+use operation_log::OperationLogService;
+use performance::PerformanceService;
+use plugins::PluginService;
+use port_forwarding::PortService;
+use preview_server::PreviewServerService;
+use project_templates::ProjectTemplateService;
+use recent::RecentService;
+use rest_client::HttpClientService;
+use session::SessionService;
+use settings::SettingsService;
+use snippets::SnippetService;
+use spellcheck::SpellcheckService;
+use syntax::SyntaxService;
+use tail::TailService;
+use tasks::TaskService;
+use tauri::{Emitter, Manager};
+use terminal::TerminalService;
+use theme::ThemeService;
+use window_manager::WindowManager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(LogService::new())
+        .manage(CrashReportService::new())
+        .manage(PerformanceService::new())
+        .manage(NotificationService::new())
         .manage(FileSystemService::new())
-        .invoke_handler(tauri::generate_handler![
+        .manage(FileClipboardService::new())
+        .manage(FsUndoService::new())
+        .manage(FileLockService::new())
+        .manage(ClipboardService::new())
+        .manage(SyntaxService::new())
+        .manage(SpellcheckService::new())
+        .manage(GitService::new())
+        .manage(TerminalService::new())
+        .manage(TaskService::new())
+        .manage(DevContainerService::new())
+        .manage(PortService::new())
+        .manage(PreviewServerService::new())
+        .manage(HttpClientService::new())
+        .manage(DatabaseService::new())
+        .manage(TailService::new())
+        .manage(SessionService::new())
+        .manage(SettingsService::new())
+        .manage(ActivityService::new())
+        .manage(KeymapService::new())
+        .manage(RecentService::new())
+        .manage(AutoSaveService::new())
+        .manage(BackupService::new())
+        .manage(FileHistoryService::new())
+        .manage(OperationLogService::new())
+        .manage(DiagnosticsService::new())
+        .manage(DebugService::new())
+        .manage(BreakpointStore::new())
+        .manage(BookmarkStore::new())
+        .manage(LaunchConfigService::new())
+        .manage(PluginService::new())
+        .manage(ThemeService::new())
+        .manage(SnippetService::new())
+        .manage(ProjectTemplateService::new())
+        .manage(WindowManager::new())
+        .manage(CommandRegistry::new())
+        .setup(|app| {
+            // Without a log file the IDE still works; there is just nothing to show in the Output panel
+            if let Err(e) = app.state::<LogService>().init(app.handle()) {
+                eprintln!("{}", e);
+            }
+            if let Err(e) = app.state::<CrashReportService>().install_panic_hook(app.handle()) {
+                tracing::warn!(error = %e, "crash reporting is unavailable");
+            }
+            app.state::<PortService>().start_detection(app.handle());
+            tracing::info!(version = env!("CARGO_PKG_VERSION"), "CodeForge started");
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            // Auto-save also flushes when focus leaves the window
+            tauri::WindowEvent::Focused(false) => {
+                window.state::<AutoSaveService>().flush_all(window.app_handle());
+            }
+            // Hot exit: make sure the latest unsaved content is on disk before the window goes away
+            tauri::WindowEvent::CloseRequested { .. } => {
+                if let Err(e) = window.state::<BackupService>().flush(window.app_handle()) {
+                    tracing::error!(window = window.label(), error = %e, "flushing backups on close failed");
+                }
+            }
+            // Locks and the workspace die with the window that held them
+            tauri::WindowEvent::Destroyed => {
+                for lock in window.state::<FileLockService>().release_owner(window.label()) {
+                    let change = FileLockChange {
+                        path: lock.path,
+                        lock: None,
+                    };
+                    let _ = window.app_handle().emit(FILE_LOCK_CHANGED_EVENT, change);
+                }
+                // Stop allowing the window's workspace once no other window has it open
+                let windows = window.state::<WindowManager>();
+                if let Some(workspace) = windows.forget(window.label()) {
+                    if windows.window_for(&workspace).is_none() {
+                        let _ = window.state::<FileSystemService>().remove_workspace_root(&workspace);
+                    }
+                }
+            }
+            // Paths dropped from the OS become readable so the explorer can import them
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                let fs = window.state::<FileSystemService>();
+                for path in paths {
+                    let _ = fs.allow_path(&path.to_string_lossy());
+                }
+            }
+            _ => {}
+        })
+        .invoke_handler(performance::instrument(tauri::generate_handler![
             // File system commands
             read_file_content,
             write_file_content,
             create_file,
+            create_file_from_template,
             create_directory,
             delete_file,
             delete_directory,
             rename_file,
+            bulk_rename,
             copy_file,
             move_file,
+            move_to_trash,
+            undo_last_fs_operation,
+            redo_fs_operation,
+            lock_file,
+            unlock_file,
+            query_lock,
             list_directory,
+            list_directory_page,
             get_file_metadata,
             watch_directory,
             stop_watching_directory,
+            watch_file,
+            stop_watching_file,
+            list_directory_watches,
+            add_workspace_root,
+            remove_workspace_root,
+            allow_file_system_path,
+            get_file_system_scope,
+            get_file_operation_config,
+            set_file_operation_config,
+            copy_directory,
+            create_symlink,
+            read_link_target,
+            set_permissions,
+            set_readonly,
+            list_extended_attributes,
+            get_extended_attribute,
+            set_extended_attribute,
+            remove_extended_attribute,
+            compute_directory_size,
+            hash_file,
+            import_paths,
+            read_file_hex,
+            detect_file_type,
+            get_file_stats,
+            get_icon_theme_map,
+            clipboard_copy_paths,
+            clipboard_cut_paths,
+            get_file_clipboard,
+            clear_file_clipboard,
+            clipboard_paste,
+            clipboard_read_text,
+            clipboard_write_text,
+            clipboard_read_image,
+            // Desktop integration commands
+            show_open_dialog,
+            show_save_dialog,
+            reveal_in_file_manager,
+            open_with_default_app,
+            // Syntax commands
+            get_highlight_tokens,
+            get_document_symbols,
+            get_folding_ranges,
+            prepare_workspace_rename,
+            export_file,
+            render_markdown,
+            // Spell checking commands
+            spellcheck_text,
+            get_spellcheck_words,
+            add_spellcheck_word,
+            remove_spellcheck_word,
+            // Project template commands
+            list_project_templates,
+            create_project_from_template,
+            // License header commands
+            check_license_headers,
+            apply_license_headers,
+            // Debug commands
+            debug_start,
+            debug_stop,
+            debug_sessions,
+            debug_set_breakpoints,
+            debug_continue,
+            debug_step_over,
+            debug_step_in,
+            debug_step_out,
+            debug_pause,
+            debug_threads,
+            debug_stack_trace,
+            debug_scopes,
+            debug_variables,
+            debug_evaluate,
+            list_breakpoints,
+            add_breakpoint,
+            remove_breakpoint,
+            toggle_breakpoint,
+            // Command palette commands
+            list_commands,
+            execute_command,
+            // Crash report commands
+            list_crash_reports,
+            get_crash_report,
+            delete_crash_report,
+            submit_crash_report,
+            // Decoration commands
+            get_document_decorations,
+            // Diagnostics commands
+            get_diagnostics,
+            clear_diagnostics,
+            match_problems,
+            // Diff commands
+            compute_diff,
+            diff_files,
+            diff_against_disk,
+            diff_against_git_head,
+            merge_three_way,
+            // Environment commands
+            load_env_file,
+            get_merged_env,
+            set_env_override,
+            // File history commands
+            record_file_version,
+            get_file_history,
+            get_file_version_content,
+            restore_file_version,
+            // Operation log commands
+            get_operation_log,
+            // Path commands
+            normalize_path,
+            relativize_path,
+            join_paths,
+            expand_path,
+            convert_path_separators,
+            validate_file_name,
+            expand_glob,
+            // Git commands
+            git_workspace_repositories,
+            git_conflicted_files,
+            git_conflict_versions,
+            git_blame,
+            git_list_branches,
+            git_create_branch,
+            git_checkout,
+            git_delete_branch,
+            git_merge_branch,
+            git_commit_graph,
+            git_commit_details,
+            git_list_remotes,
+            git_add_remote,
+            git_remove_remote,
+            git_fetch,
+            git_clone,
+            git_cancel_clone,
+            git_diff_hunks,
+            git_stage_hunk,
+            git_revert_hunk,
+            add_to_gitignore,
+            is_path_ignored,
+            // Log tail commands
+            tail_file,
+            stop_tail,
+            // Log commands
+            get_log_entries,
+            get_log_levels,
+            set_log_level,
+            // Notification commands
+            list_notifications,
+            cancel_notification,
+            dismiss_notification,
+            // Performance commands
+            get_performance_stats,
+            reset_performance_stats,
+            // Plugin commands
+            scan_plugins,
+            list_plugins,
+            activate_plugin,
+            get_plugin_commands,
+            get_plugin_file_systems,
+            notify_file_opened,
+            execute_plugin_command,
+            search_extensions,
+            install_extension,
+            uninstall_extension,
+            // Port commands
+            list_listening_ports,
+            kill_port_process,
+            list_ports,
+            set_port_label,
+            forward_port,
+            stop_port_forward,
+            list_port_forwards,
+            open_port_in_browser,
+            // Preview server commands
+            start_preview_server,
+            stop_preview_server,
+            list_preview_servers,
+            // Recent history commands
+            record_recent_file,
+            record_recent_workspace,
+            get_recent_files,
+            get_recent_workspaces,
+            pin_recent_entry,
+            remove_recent_entry,
+            clear_history,
+            // Regex playground commands
+            test_regex,
+            // Resource usage commands
+            get_resource_usage,
+            // REST client commands
+            parse_http_requests,
+            send_http_request,
+            // Database explorer commands
+            open_sqlite,
+            close_database,
+            list_open_databases,
+            list_tables,
+            count_rows,
+            run_query,
+            // Session commands
+            save_session,
+            restore_session,
+            clear_session,
+            push_location,
+            navigate_back,
+            navigate_forward,
+            get_navigation_state,
+            // Settings commands
+            get_preferences,
+            update_preferences,
+            reset_preferences,
+            get_effective_settings,
+            // Snippet commands
+            get_snippets,
+            save_snippet,
+            delete_snippet,
+            parse_snippet_body,
+            // Auto-save commands
+            register_dirty_buffer,
+            discard_dirty_buffer,
+            flush_dirty_buffers,
+            get_pending_auto_saves,
+            // Backup commands
+            backup_buffer,
+            flush_backups,
+            discard_backup,
+            list_recoverable_buffers,
+            recover_buffer,
+            // Bookmark commands
+            list_bookmarks,
+            add_bookmark,
+            update_bookmark,
+            remove_bookmark,
+            clear_bookmarks,
+            // Activity commands
+            start_activity_tracking,
+            stop_activity_tracking,
+            record_file_activity,
+            get_file_activity,
+            // Indentation commands
+            detect_indentation,
+            convert_indentation,
+            // Keymap commands
+            get_keybindings,
+            update_keybinding,
+            reset_keybinding,
+            validate_key_chord,
+            // Launch configuration commands
+            list_launch_configs,
+            resolve_launch_config,
+            // Task commands
+            list_tasks,
+            run_task,
+            get_task_run,
+            stop_task,
+            start_watch_task,
+            stop_watch_task,
+            list_watch_tasks,
+            // Dev container commands
+            get_dev_container_config,
+            open_dev_container,
+            stop_dev_container,
+            list_dev_containers,
+            // Terminal commands
+            search_terminal_output,
+            export_terminal_output,
+            clear_terminal_output,
+            close_terminal_session,
+            set_terminal_cwd,
+            set_terminal_process,
+            get_terminal_launch,
+            detect_terminal_links,
+            // Theme commands
+            list_themes,
+            get_theme,
+            list_icon_themes,
+            get_icon_theme,
+            start_theme_watch,
+            stop_theme_watch,
+            // Window commands
+            open_new_window,
+            list_windows,
+            focus_window,
+            get_window_workspace,
+            set_window_workspace,
+            // Workspace edit commands
+            apply_workspace_edit,
+            // Workspace statistics commands
+            get_workspace_stats,
             // Utility commands
             get_system_info,
+            get_mount_info,
+            list_wsl_distros,
             greet
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
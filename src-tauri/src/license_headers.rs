@@ -0,0 +1,342 @@
+/**
+ * License headers for CodeForge IDE
+ * Finds source files whose leading comment is missing the configured `license_header` or carries an older
+ * version of it, and builds the edits that put the current header in place, in each language's comment style
+ *
+ * `{{year}}` in the header matches any year or year range, so files aren't reported just because the year
+ * turned; other placeholders are compared as they would be written into a new file.
+ */
+
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::file_templates::{self, comment};
+use crate::project_templates::substitute;
+use crate::settings::{SettingsLayer, SettingsService};
+use crate::syntax::SourceRange;
+use crate::workspace_edit::{TextEdit, WorkspaceEditOperation};
+use crate::workspace_stats::{comment_syntax, CommentSyntax};
+
+/// Larger files are generated or data, not source that needs a header
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Extensions with comment syntax where a header would be wrong: strict JSON has no comments, and a comment
+/// at the top of a Markdown file is rendered as an empty line
+const EXCLUDED_EXTENSIONS: &[&str] = &["json", "md", "markdown"];
+
+/// Words that mark an existing leading comment as a license header, which is then reported as outdated
+/// rather than missing
+const LICENSE_MARKERS: &[&str] = &["copyright", "license", "licence", "spdx-license-identifier"];
+
+/// Doc comments document the code below them and are never part of a header
+const DOC_COMMENT_PREFIXES: &[&str] = &["///", "//!"];
+
+/// Stands in for `{{year}}` while the rest of the header is escaped into a pattern
+const YEAR_SENTINEL: &str = "\u{0}year\u{0}";
+
+/// A year, or a list or range of years such as `2019-2024` or `2021, 2023`
+const YEAR_PATTERN: &str = r"\d{4}(?:\s*[-,–]\s*\d{4})*";
+
+/// Error types for license header operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LicenseHeaderError {
+    NotConfigured,
+    UnsupportedFile(String),
+    Settings(String),
+    IOError(String),
+}
+
+impl std::fmt::Display for LicenseHeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LicenseHeaderError::NotConfigured => write!(f, "No license header is configured"),
+            LicenseHeaderError::UnsupportedFile(path) => {
+                write!(f, "License headers are not supported for {}", path)
+            }
+            LicenseHeaderError::Settings(msg) => write!(f, "Reading the license header failed: {}", msg),
+            LicenseHeaderError::IOError(msg) => write!(f, "IO Error: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderStatus {
+    Current,
+    Missing,
+    /// The file starts with a license comment that isn't the configured header
+    Outdated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderCheck {
+    pub path: String,
+    pub status: HeaderStatus,
+    /// Zero-based lines of the existing header comment, end exclusive
+    pub header_lines: Option<(usize, usize)>,
+}
+
+/// Result of `check_workspace`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicenseHeaderReport {
+    pub root: String,
+    /// Files with a missing or outdated header
+    pub files: Vec<HeaderCheck>,
+    pub checked: usize,
+    pub current: usize,
+    /// Files in languages without comments, binary, not UTF-8, or too large
+    pub skipped: usize,
+}
+
+/// The configured header, prepared for comparing and writing
+pub struct LicenseHeader {
+    template: String,
+    variables: BTreeMap<String, String>,
+}
+
+impl LicenseHeader {
+    /// `template` is the `license_header` preference; values that don't depend on the file (year, author)
+    /// are resolved once for the workspace
+    pub fn new(template: &str, workspace: &Path) -> Result<LicenseHeader, LicenseHeaderError> {
+        if template.trim().is_empty() {
+            return Err(LicenseHeaderError::NotConfigured);
+        }
+        Ok(LicenseHeader {
+            template: template.to_string(),
+            variables: file_templates::template_variables(workspace, None, Some(workspace), ""),
+        })
+    }
+
+    /// The `license_header` preference in effect for `workspace`
+    pub fn configured(app: &AppHandle, workspace: &Path) -> Result<LicenseHeader, LicenseHeaderError> {
+        let workspace_path = workspace.to_string_lossy();
+        let effective = app
+            .state::<SettingsService>()
+            .effective_settings(app, SettingsLayer::Workspace, Some(&workspace_path))
+            .map_err(|e| LicenseHeaderError::Settings(e.to_string()))?;
+        let template = effective.settings.get("license_header").and_then(|setting| setting.value.as_str());
+        LicenseHeader::new(template.unwrap_or_default(), workspace)
+    }
+
+    fn variables_for(&self, path: &Path, workspace: &Path) -> BTreeMap<String, String> {
+        let mut variables = self.variables.clone();
+        variables.extend(file_templates::path_variables(path, Some(workspace)));
+        variables
+    }
+
+    /// The header text for `path`, without comment markers
+    fn text(&self, path: &Path, workspace: &Path) -> String {
+        substitute(&self.template, &self.variables_for(path, workspace))
+    }
+
+    fn pattern(&self, path: &Path, workspace: &Path) -> Regex {
+        let mut variables = self.variables_for(path, workspace);
+        variables.insert("year".to_string(), YEAR_SENTINEL.to_string());
+        let expected = normalize(&substitute(&self.template, &variables));
+        let pattern = regex::escape(&expected).replace(YEAR_SENTINEL, YEAR_PATTERN);
+        Regex::new(&format!("^{}$", pattern)).expect("escaped header is a valid pattern")
+    }
+}
+
+/// Trimmed lines without leading and trailing blank ones, so indentation and trailing spaces don't count
+fn normalize(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |end| end + 1);
+    lines[start..end].join("\n")
+}
+
+/// Lines that have to stay above a header: a shebang, an XML declaration, a Python encoding declaration
+fn prologue_lines(lines: &[&str]) -> usize {
+    let mut count = 0;
+    if lines.first().is_some_and(|line| line.starts_with("#!") || line.starts_with("<?xml")) {
+        count = 1;
+    }
+    let encoding = lines.get(count).is_some_and(|line| {
+        line.starts_with('#') && (line.contains("coding:") || line.contains("coding="))
+    });
+    if encoding {
+        count += 1;
+    }
+    count
+}
+
+/// The comment starting at line `start`, if any: its line range (end exclusive) and text without markers
+fn leading_comment(lines: &[&str], start: usize, syntax: CommentSyntax) -> Option<((usize, usize), String)> {
+    let first = lines.get(start)?.trim_start();
+    // Block comments first, since Lua's `--[[` also starts like a `--` line comment
+    if let Some((open, close)) = syntax.block.filter(|(open, _)| first.starts_with(open)) {
+        let mut text = Vec::new();
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            let mut line = line.trim();
+            if index == start {
+                line = &line[open.len()..];
+            }
+            let end = line.find(close);
+            if let Some(end) = end {
+                line = &line[..end];
+            }
+            // ` * ` continuation markers of C-style comments
+            let line = line.strip_prefix('*').map(str::trim_start).unwrap_or(line);
+            text.push(line.to_string());
+            if end.is_some() {
+                return Some(((start, index + 1), text.join("\n")));
+            }
+        }
+        // Unterminated: not a comment this tool should touch
+        return None;
+    }
+
+    let prefix = syntax.line?;
+    let text: Vec<&str> = lines[start..]
+        .iter()
+        .map(|line| line.trim_start())
+        .take_while(|line| {
+            line.starts_with(prefix) && !DOC_COMMENT_PREFIXES.iter().any(|doc| line.starts_with(doc))
+        })
+        .map(|line| {
+            let line = &line[prefix.len()..];
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect();
+    match text.is_empty() {
+        true => None,
+        false => Some(((start, start + text.len()), text.join("\n"))),
+    }
+}
+
+/// Comment syntax for a file that can carry a header
+fn header_syntax(path: &Path) -> Option<CommentSyntax> {
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    if extension.is_some_and(|extension| EXCLUDED_EXTENSIONS.contains(&extension.as_str())) {
+        return None;
+    }
+    comment_syntax(path, None)
+}
+
+/// Compare the start of `content` with the header, and say where the header is or should go
+fn check_content(
+    content: &str,
+    syntax: CommentSyntax,
+    pattern: &Regex,
+) -> (HeaderStatus, Option<(usize, usize)>, usize) {
+    let lines: Vec<&str> = content.lines().collect();
+    let prologue = prologue_lines(&lines);
+    let start = (prologue..lines.len()).find(|&index| !lines[index].trim().is_empty()).unwrap_or(lines.len());
+    let Some((range, text)) = leading_comment(&lines, start, syntax) else {
+        return (HeaderStatus::Missing, None, prologue);
+    };
+    if pattern.is_match(&normalize(&text)) {
+        return (HeaderStatus::Current, Some(range), prologue);
+    }
+    let lowercase = text.to_lowercase();
+    match LICENSE_MARKERS.iter().any(|marker| lowercase.contains(marker)) {
+        true => (HeaderStatus::Outdated, Some(range), prologue),
+        false => (HeaderStatus::Missing, None, prologue),
+    }
+}
+
+fn read_source(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    let content = String::from_utf8(fs::read(path).ok()?).ok()?;
+    (!content.contains('\0')).then_some(content)
+}
+
+/// Check every source file under `root` that git doesn't ignore
+pub fn check_workspace(root: &Path, header: &LicenseHeader) -> LicenseHeaderReport {
+    let mut report = LicenseHeaderReport {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let files = WalkBuilder::new(root)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()));
+    for entry in files {
+        let path = entry.path();
+        let (Some(syntax), Some(content)) = (header_syntax(path), read_source(path)) else {
+            report.skipped += 1;
+            continue;
+        };
+        report.checked += 1;
+        let (status, header_lines, _) = check_content(&content, syntax, &header.pattern(path, root));
+        match status {
+            HeaderStatus::Current => report.current += 1,
+            _ => report.files.push(HeaderCheck {
+                path: path.to_string_lossy().to_string(),
+                status,
+                header_lines,
+            }),
+        }
+    }
+    report.files.sort_by(|a, b| a.path.cmp(&b.path));
+    report
+}
+
+/// The edit that gives `path` the current header, or `None` when it already has it
+pub fn header_edit(
+    path: &Path,
+    workspace: &Path,
+    header: &LicenseHeader,
+) -> Result<Option<WorkspaceEditOperation>, LicenseHeaderError> {
+    let unsupported = || LicenseHeaderError::UnsupportedFile(path.to_string_lossy().to_string());
+    let syntax = header_syntax(path).ok_or_else(unsupported)?;
+    let bytes = fs::read(path).map_err(|e| LicenseHeaderError::IOError(e.to_string()))?;
+    let content = String::from_utf8(bytes).map_err(|_| unsupported())?;
+
+    let (status, header_lines, prologue) = check_content(&content, syntax, &header.pattern(path, workspace));
+    let line_break = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let commented = comment(&header.text(path, workspace), syntax).replace('\n', line_break);
+    let line_count = content.lines().count();
+    let position = |line: usize, column: usize| SourceRange {
+        start_line: line,
+        start_column: column,
+        end_line: line,
+        end_column: column,
+    };
+    let edit = match (status, header_lines) {
+        (HeaderStatus::Current, _) => return Ok(None),
+        // Replaced up to the start of the line after it, so the line breaks that follow are kept as they are
+        (_, Some((start, end))) if end < line_count || content.ends_with('\n') => TextEdit {
+            range: SourceRange {
+                end_line: end,
+                end_column: 0,
+                ..position(start, 0)
+            },
+            new_text: commented,
+        },
+        // A header on the last line of a file without a final line break
+        (_, Some((start, end))) => TextEdit {
+            range: SourceRange {
+                end_line: end - 1,
+                end_column: usize::MAX,
+                ..position(start, 0)
+            },
+            new_text: commented.trim_end_matches(['\r', '\n']).to_string(),
+        },
+        (_, None) if prologue < line_count => TextEdit {
+            range: position(prologue, 0),
+            new_text: format!("{}{}", commented, line_break),
+        },
+        // Nothing below the header, or only a shebang without a line break
+        (_, None) => {
+            let prefix = if content.is_empty() || content.ends_with('\n') { "" } else { line_break };
+            TextEdit {
+                range: position(line_count.saturating_sub(1), usize::MAX),
+                new_text: format!("{}{}", prefix, commented),
+            }
+        }
+    };
+    Ok(Some(WorkspaceEditOperation::Edit {
+        path: path.to_string_lossy().to_string(),
+        edits: vec![edit],
+    }))
+}
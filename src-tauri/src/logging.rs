@@ -0,0 +1,326 @@
+/**
+ * Structured logging for CodeForge IDE
+ * Backend logs go through `tracing` to JSON-lines files in the app data dir, rotated daily, and are read back
+ * for the Output panel. Levels can be raised per subsystem at runtime to diagnose one area without noise.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Rotation, RollingFileAppender};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Directory under the app data dir holding the log files
+const LOGS_DIR: &str = "logs";
+
+/// Log files are named `codeforge.<date>.log`
+const LOG_FILE_PREFIX: &str = "codeforge";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily files kept, oldest deleted first
+const MAX_LOG_FILES: usize = 7;
+
+/// Entries returned when no tail is requested
+const DEFAULT_TAIL: usize = 1000;
+
+/// Error types for logging operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LogError {
+    NoDataDirectory,
+    NotInitialized,
+    IOError(String),
+    InitFailed(String),
+}
+
+impl std::fmt::Display for LogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            LogError::NotInitialized => write!(f, "Logging has not been initialized"),
+            LogError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            LogError::InitFailed(msg) => write!(f, "Failed to initialize logging: {}", msg),
+        }
+    }
+}
+
+/// Ordered from least to most verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    fn parse(level: &str) -> Option<LogLevel> {
+        match level.to_ascii_uppercase().as_str() {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+}
+
+/// Level for all logs, and the subsystems logging at a different one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevels {
+    pub default: LogLevel,
+    pub subsystems: BTreeMap<String, LogLevel>,
+}
+
+impl LogLevels {
+    /// Filter directives, e.g. `info,codeforge2_lib::git=debug`
+    fn directives(&self) -> String {
+        let crate_name = env!("CARGO_CRATE_NAME");
+        let mut directives = vec![self.default.directive().to_string()];
+        directives.extend(
+            self.subsystems
+                .iter()
+                .map(|(subsystem, level)| format!("{}::{}={}", crate_name, subsystem, level.directive())),
+        );
+        directives.join(",")
+    }
+}
+
+/// One log line as the Output panel shows it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// RFC 3339 time the event was logged
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// Backend module that logged it, e.g. `git` or `plugins`; other crates appear under their own name
+    pub subsystem: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields logged alongside the message
+    pub fields: Map<String, Value>,
+}
+
+/// Which entries `entries` returns
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// Most verbose level to include
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+    #[serde(default)]
+    pub subsystem: Option<String>,
+    /// Case-insensitive text the message has to contain
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.level.is_none_or(|level| entry.level <= level)
+            && self.subsystem.as_ref().is_none_or(|subsystem| &entry.subsystem == subsystem)
+            && self
+                .text
+                .as_ref()
+                .is_none_or(|text| entry.message.to_lowercase().contains(&text.to_lowercase()))
+    }
+}
+
+/// `codeforge2_lib::git::remotes` belongs to `git`; `wasmtime::runtime` to `wasmtime`
+fn subsystem(target: &str) -> String {
+    let mut segments = target.split("::");
+    let first = segments.next().unwrap_or_default();
+    match first == env!("CARGO_CRATE_NAME") {
+        true => segments.next().unwrap_or(first).to_string(),
+        false => first.to_string(),
+    }
+}
+
+/// Parse a line written by the JSON formatter; lines cut short by a crash are skipped
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let mut event: Map<String, Value> = serde_json::from_str(line).ok()?;
+    let level = LogLevel::parse(event.get("level")?.as_str()?)?;
+    let target = event.get("target").and_then(Value::as_str).unwrap_or_default().to_string();
+    let mut fields = match event.remove("fields") {
+        Some(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let message = match fields.remove("message") {
+        Some(Value::String(message)) => message,
+        Some(message) => message.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: event.get("timestamp").and_then(Value::as_str).unwrap_or_default().to_string(),
+        level,
+        subsystem: subsystem(&target),
+        target,
+        message,
+        fields,
+    })
+}
+
+/// Log files, newest first; the date in their names sorts them
+fn log_files(dir: &Path) -> Result<Vec<PathBuf>, LogError> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| LogError::IOError(e.to_string()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// The last `count` lines of the newest log file as written, for attaching to crash reports
+pub fn recent_lines(dir: &Path, count: usize) -> Vec<String> {
+    let Some(content) = log_files(dir).ok().and_then(|files| fs::read_to_string(files.first()?).ok()) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].iter().map(|line| line.to_string()).collect()
+}
+
+struct ActiveLogging {
+    dir: PathBuf,
+    filter: reload::Handle<EnvFilter, Registry>,
+    /// Flushes buffered lines to the file when dropped
+    _guard: WorkerGuard,
+}
+
+pub struct LogService {
+    active: Arc<Mutex<Option<ActiveLogging>>>,
+    levels: Arc<Mutex<LogLevels>>,
+}
+
+impl LogService {
+    pub fn new() -> Self {
+        Self {
+            active: Arc::new(Mutex::new(None)),
+            levels: Arc::new(Mutex::new(LogLevels {
+                default: LogLevel::Info,
+                subsystems: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Install the global subscriber writing to the log directory. Debug builds also log to stderr.
+    pub fn init(&self, app: &AppHandle) -> Result<(), LogError> {
+        let dir = app.path().app_data_dir().map_err(|_| LogError::NoDataDirectory)?.join(LOGS_DIR);
+        fs::create_dir_all(&dir).map_err(|e| LogError::IOError(e.to_string()))?;
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(&dir)
+            .map_err(|e| LogError::InitFailed(e.to_string()))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        let directives = self.levels.lock().unwrap().directives();
+        let (filter, filter_handle) = reload::Layer::new(EnvFilter::new(directives));
+        let file_layer = fmt::layer().json().with_current_span(false).with_span_list(false).with_writer(writer);
+        let stderr_layer = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stderr));
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(file_layer)
+            .with(stderr_layer)
+            .try_init()
+            .map_err(|e| LogError::InitFailed(e.to_string()))?;
+
+        *self.active.lock().unwrap() = Some(ActiveLogging {
+            dir,
+            filter: filter_handle,
+            _guard: guard,
+        });
+        Ok(())
+    }
+
+    /// Directory the log files are written to, once initialized
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.active.lock().unwrap().as_ref().map(|active| active.dir.clone())
+    }
+
+    pub fn levels(&self) -> LogLevels {
+        self.levels.lock().unwrap().clone()
+    }
+
+    /// Set the level of one subsystem, or the default when `subsystem` is `None`. A subsystem given no level
+    /// follows the default again; the default given none goes back to `Info`.
+    pub fn set_level(&self, level: Option<LogLevel>, subsystem: Option<&str>) -> Result<LogLevels, LogError> {
+        let levels = {
+            let mut levels = self.levels.lock().unwrap();
+            match (subsystem, level) {
+                (Some(subsystem), Some(level)) => {
+                    levels.subsystems.insert(subsystem.to_string(), level);
+                }
+                (Some(subsystem), None) => {
+                    levels.subsystems.remove(subsystem);
+                }
+                (None, level) => levels.default = level.unwrap_or(LogLevel::Info),
+            }
+            levels.clone()
+        };
+        if let Some(active) = self.active.lock().unwrap().as_ref() {
+            active
+                .filter
+                .reload(EnvFilter::new(levels.directives()))
+                .map_err(|e| LogError::InitFailed(e.to_string()))?;
+        }
+        tracing::info!(directives = %levels.directives(), "log level changed");
+        Ok(levels)
+    }
+
+    /// The last `tail` entries matching `filter`, oldest first, reading back through older files as needed
+    pub fn entries(&self, filter: &LogFilter, tail: Option<usize>) -> Result<Vec<LogEntry>, LogError> {
+        let dir = match self.active.lock().unwrap().as_ref() {
+            Some(active) => active.dir.clone(),
+            None => return Err(LogError::NotInitialized),
+        };
+        let tail = tail.unwrap_or(DEFAULT_TAIL);
+        let mut entries: Vec<LogEntry> = Vec::new();
+        for file in log_files(&dir)? {
+            if entries.len() >= tail {
+                break;
+            }
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            let mut matching: Vec<LogEntry> =
+                content.lines().filter_map(parse_entry).filter(|entry| filter.matches(entry)).collect();
+            matching.append(&mut entries);
+            entries = matching;
+        }
+        let excess = entries.len().saturating_sub(tail);
+        entries.drain(..excess);
+        Ok(entries)
+    }
+}
+
+impl Default for LogService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
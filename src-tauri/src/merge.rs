@@ -0,0 +1,177 @@
+/**
+ * Three-way text merge for CodeForge IDE
+ * diff3-style merge that reports conflicts as structured regions for the merge editor
+ */
+
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, DiffOp, TextDiff};
+
+/// Conflict marker labels written into the merged text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeLabels {
+    pub ours: String,
+    pub base: String,
+    pub theirs: String,
+}
+
+impl Default for MergeLabels {
+    fn default() -> Self {
+        Self {
+            ours: "ours".to_string(),
+            base: "base".to_string(),
+            theirs: "theirs".to_string(),
+        }
+    }
+}
+
+/// An unresolved region; line numbers are 0-based into the merged text and
+/// cover the conflict markers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub ours: String,
+    pub base: String,
+    pub theirs: String,
+}
+
+/// Result of a three-way merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub merged: String,
+    pub has_conflicts: bool,
+    pub conflicts: Vec<ConflictRegion>,
+}
+
+/// For every base line, the index of the matching line in the other text, if unchanged
+fn base_matches(base: &str, other: &str, base_len: usize) -> Vec<Option<usize>> {
+    let diff = TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_lines(base, other);
+
+    let mut matches = vec![None; base_len];
+    for op in diff.ops() {
+        if let DiffOp::Equal { old_index, new_index, len } = *op {
+            for offset in 0..len {
+                matches[old_index + offset] = Some(new_index + offset);
+            }
+        }
+    }
+    matches
+}
+
+struct MergeWriter {
+    merged: String,
+    line_count: usize,
+}
+
+impl MergeWriter {
+    fn push_lines(&mut self, lines: &[&str]) {
+        for line in lines {
+            self.push_line(line);
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        // Markers must start on a fresh line even if the previous chunk lacked a final newline
+        if !self.merged.is_empty() && !self.merged.ends_with('\n') {
+            self.merged.push('\n');
+        }
+        self.merged.push_str(line);
+        self.line_count += 1;
+    }
+}
+
+/// Terminate the last line so a missing final newline doesn't turn it into a change
+fn with_final_newline(text: &str) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text.to_string()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// Merge `ours` and `theirs`, both derived from `base`
+pub fn merge_three_way(base: &str, ours: &str, theirs: &str, labels: &MergeLabels) -> MergeResult {
+    let keep_final_newline = ours.is_empty() || ours.ends_with('\n');
+    let base = &with_final_newline(base);
+    let ours = &with_final_newline(ours);
+    let theirs = &with_final_newline(theirs);
+
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let our_lines: Vec<&str> = ours.split_inclusive('\n').collect();
+    let their_lines: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let ours_match = base_matches(base, ours, base_lines.len());
+    let theirs_match = base_matches(base, theirs, base_lines.len());
+
+    let mut writer = MergeWriter {
+        merged: String::with_capacity(ours.len().max(theirs.len())),
+        line_count: 0,
+    };
+    let mut conflicts = Vec::new();
+    let (mut i, mut j, mut k) = (0, 0, 0);
+
+    loop {
+        // Copy lines that are unchanged on both sides
+        while i < base_lines.len() && ours_match[i] == Some(j) && theirs_match[i] == Some(k) {
+            writer.push_line(base_lines[i]);
+            i += 1;
+            j += 1;
+            k += 1;
+        }
+
+        // Next base line that both sides kept marks the end of the changed chunk
+        let sync = (i..base_lines.len()).find_map(|index| match (ours_match[index], theirs_match[index]) {
+            (Some(o), Some(t)) => Some((index, o, t)),
+            _ => None,
+        });
+        let (next_i, next_j, next_k) = sync.unwrap_or((base_lines.len(), our_lines.len(), their_lines.len()));
+
+        if next_i == i && next_j == j && next_k == k {
+            break;
+        }
+
+        let base_chunk = &base_lines[i..next_i];
+        let our_chunk = &our_lines[j..next_j];
+        let their_chunk = &their_lines[k..next_k];
+
+        if our_chunk == base_chunk {
+            writer.push_lines(their_chunk);
+        } else if their_chunk == base_chunk || our_chunk == their_chunk {
+            writer.push_lines(our_chunk);
+        } else {
+            let start_line = writer.line_count;
+            writer.push_line(&format!("<<<<<<< {}\n", labels.ours));
+            writer.push_lines(our_chunk);
+            writer.push_line(&format!("||||||| {}\n", labels.base));
+            writer.push_lines(base_chunk);
+            writer.push_line("=======\n");
+            writer.push_lines(their_chunk);
+            writer.push_line(&format!(">>>>>>> {}\n", labels.theirs));
+
+            conflicts.push(ConflictRegion {
+                start_line,
+                end_line: writer.line_count - 1,
+                ours: our_chunk.concat(),
+                base: base_chunk.concat(),
+                theirs: their_chunk.concat(),
+            });
+        }
+
+        i = next_i;
+        j = next_j;
+        k = next_k;
+    }
+
+    let mut merged = writer.merged;
+    if !keep_final_newline && !conflicts.iter().any(|c| c.end_line + 1 == writer.line_count) {
+        merged.pop();
+    }
+
+    MergeResult {
+        merged,
+        has_conflicts: !conflicts.is_empty(),
+        conflicts,
+    }
+}
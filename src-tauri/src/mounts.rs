@@ -0,0 +1,334 @@
+/**
+ * Mount lookup for CodeForge IDE
+ * Finds the file system a path is on, to tell network shares and VM shared folders (NFS, SMB, WSL's
+ * Windows drives) from local disks: native watchers miss changes made on the other side of those, and
+ * indexing them is slow. Also reports the space left on it, so saves can be warned about before they fail.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use sysinfo::Disks;
+
+/// Free space below which `MountInfo::low_space` is set
+const LOW_SPACE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// File system types whose changes can come from another machine or from the host of a VM
+const NETWORK_FILE_SYSTEMS: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb", "smb2", "smb3", "smbfs", "afpfs", "webdav", "davfs", "fuse.davfs", "afs",
+    "ncpfs", "ceph", "glusterfs", "fuse.glusterfs", "lustre", "gpfs", "fuse.sshfs", "sshfs", "fuse.rclone",
+    "fuse.s3fs", "9p", "drvfs", "virtiofs", "vboxsf", "vmhgfs", "fuse.vmhgfs-fuse", "prl_fs",
+];
+
+/// The mount a path is on
+#[derive(Debug, Clone)]
+pub struct Mount {
+    /// What is mounted: a device, a share like `//server/share`, or a drive
+    pub source: String,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+}
+
+pub fn is_network_file_system(file_system: &str) -> bool {
+    let file_system = file_system.to_lowercase();
+    NETWORK_FILE_SYSTEMS.contains(&file_system.as_str())
+}
+
+/// The mount `path` is on, by the longest mount point that contains it; `None` when the mounts can't be
+/// read
+pub fn mount_of(path: &Path) -> Option<Mount> {
+    let path = platform::canonical(path);
+    platform::mounts()
+        .into_iter()
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
+/// Whether `path` is on a network share or a VM shared folder
+pub fn is_network_path(path: &Path) -> bool {
+    if platform::is_network_path(path) {
+        return true;
+    }
+    mount_of(path).is_some_and(|mount| is_network_file_system(&mount.file_system))
+}
+
+/// What `mount_info` reports about the file system a path is on; sizes in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountInfo {
+    pub path: String,
+    pub mount_point: String,
+    pub source: String,
+    pub file_system: String,
+    /// A network share or VM shared folder: watch by polling, index with care
+    pub network: bool,
+    pub removable: bool,
+    /// `None` when the file system doesn't report its size
+    pub total: Option<u64>,
+    pub available: Option<u64>,
+    /// Less than 512 MiB free
+    pub low_space: bool,
+}
+
+/// `path` itself, or its nearest ancestor that exists, so a file that's about to be created can be asked
+/// about
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|ancestor| ancestor.exists())
+}
+
+/// The file system `path` is on. Sizes come from the disk list, or from the platform for the network file
+/// systems the disk list leaves out.
+pub fn mount_info(path: &Path) -> Option<MountInfo> {
+    let existing = existing_ancestor(path)?;
+    let network = is_network_path(existing);
+    let mount = mount_of(existing);
+    let disks = Disks::new_with_refreshed_list();
+    let disk = mount
+        .as_ref()
+        .and_then(|mount| disks.list().iter().find(|disk| disk.mount_point() == mount.mount_point))
+        .filter(|disk| disk.total_space() > 0);
+
+    let (total, available) = match disk {
+        Some(disk) => (Some(disk.total_space()), Some(disk.available_space())),
+        None => match platform::capacity(existing) {
+            Some((total, available)) => (Some(total), Some(available)),
+            None => (None, None),
+        },
+    };
+    let mount_point = mount.as_ref().map(|mount| mount.mount_point.to_string_lossy().to_string());
+    Some(MountInfo {
+        path: path.to_string_lossy().to_string(),
+        mount_point: mount_point.unwrap_or_default(),
+        source: mount.as_ref().map(|mount| mount.source.clone()).unwrap_or_default(),
+        file_system: mount.as_ref().map(|mount| mount.file_system.clone()).unwrap_or_default(),
+        network,
+        removable: disk.is_some_and(|disk| disk.is_removable()),
+        total,
+        available,
+        low_space: available.is_some_and(|available| available < LOW_SPACE_BYTES),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Mount;
+    use std::path::{Path, PathBuf};
+
+    /// Undo the octal escapes `/proc/mounts` uses for spaces, tabs, newlines, and backslashes
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut output = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let octal = bytes.get(i + 1..i + 4).and_then(|digits| {
+                let digits = std::str::from_utf8(digits).ok()?;
+                u8::from_str_radix(digits, 8).ok()
+            });
+            match (bytes[i], octal) {
+                (b'\\', Some(byte)) => {
+                    output.push(byte);
+                    i += 4;
+                }
+                (byte, _) => {
+                    output.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&output).to_string()
+    }
+
+    pub fn canonical(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn mounts() -> Vec<Mount> {
+        let Ok(content) = std::fs::read_to_string("/proc/self/mounts") else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter_map(|line| {
+                // /dev/sda1 /home ext4 rw,relatime 0 0
+                let mut fields = line.split_whitespace();
+                let source = unescape(fields.next()?);
+                let mount_point = PathBuf::from(unescape(fields.next()?));
+                let file_system = fields.next()?.to_string();
+                Some(Mount {
+                    source,
+                    mount_point,
+                    file_system,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_network_path(_path: &Path) -> bool {
+        false
+    }
+
+    /// Total and free bytes from `df`, for mounts the disk list skips
+    pub fn capacity(path: &Path) -> Option<(u64, u64)> {
+        super::df_capacity(path)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Mount;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    pub fn canonical(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn mounts() -> Vec<Mount> {
+        let Ok(output) = Command::new("mount").output() else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // //user@server/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)
+                let (source, rest) = line.split_once(" on ")?;
+                let (mount_point, options) = rest.rsplit_once(" (")?;
+                let file_system = options.split([',', ')']).next()?.trim().to_string();
+                Some(Mount {
+                    source: source.to_string(),
+                    mount_point: PathBuf::from(mount_point),
+                    file_system,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_network_path(_path: &Path) -> bool {
+        false
+    }
+
+    /// Total and free bytes from `df`, for mounts the disk list skips
+    pub fn capacity(path: &Path) -> Option<(u64, u64)> {
+        super::df_capacity(path)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Mount;
+    use std::path::{Component, Path, PathBuf, Prefix};
+    use std::process::Command;
+
+    /// One line per drive: letter, `DriveType` (4 is a mapped network drive), file system, and share
+    const DRIVES_SCRIPT: &str = "Get-CimInstance Win32_LogicalDisk | ForEach-Object { \
+        \"$($_.DeviceID)|$($_.DriveType)|$($_.FileSystem)|$($_.ProviderName)\" }";
+
+    /// Canonical paths of drives start with `\\?\`, which drive mount points don't have
+    pub fn canonical(path: &Path) -> PathBuf {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let drive_path = canonical
+            .to_str()
+            .and_then(|text| text.strip_prefix(r"\\?\"))
+            .filter(|rest| !rest.starts_with("UNC\\"))
+            .map(PathBuf::from);
+        drive_path.unwrap_or(canonical)
+    }
+
+    pub fn mounts() -> Vec<Mount> {
+        let Ok(output) = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", DRIVES_SCRIPT])
+            .output()
+        else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // Z:|4|NTFS|\\server\share
+                let mut fields = line.trim().split('|');
+                let drive = fields.next()?.to_string();
+                let drive_type = fields.next()?;
+                let file_system = fields.next().unwrap_or_default();
+                let provider = fields.next().unwrap_or_default();
+                Some(Mount {
+                    source: if provider.is_empty() { drive.clone() } else { provider.to_string() },
+                    mount_point: PathBuf::from(format!("{}\\", drive)),
+                    file_system: match drive_type {
+                        "4" => "smb".to_string(),
+                        _ => file_system.to_string(),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// UNC paths, including `\\wsl$\` and `\\wsl.localhost\`, are shares
+    pub fn is_network_path(path: &Path) -> bool {
+        match path.components().next() {
+            Some(Component::Prefix(prefix)) => {
+                matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..))
+            }
+            _ => false,
+        }
+    }
+
+    /// Total and free bytes of the drive `path` is on, for drives the disk list skips; `None` for UNC paths
+    pub fn capacity(path: &Path) -> Option<(u64, u64)> {
+        let drive = match path.components().next() {
+            Some(Component::Prefix(prefix)) => match prefix.kind() {
+                Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => format!("{}:", letter as char),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let script = format!(
+            "$d = Get-CimInstance Win32_LogicalDisk -Filter \"DeviceID='{}'\"; \"$($d.Size)|$($d.FreeSpace)\"",
+            drive
+        );
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let (total, available) = text.trim().split_once('|')?;
+        Some((total.parse().ok()?, available.parse().ok()?))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::Mount;
+    use std::path::{Path, PathBuf};
+
+    pub fn canonical(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn mounts() -> Vec<Mount> {
+        Vec::new()
+    }
+
+    pub fn is_network_path(_path: &Path) -> bool {
+        false
+    }
+
+    pub fn capacity(_path: &Path) -> Option<(u64, u64)> {
+        None
+    }
+}
+
+/// Total and free bytes from POSIX `df`, whose second line is
+/// `server:/export  1048576  524288  524288  50%  /mnt/export` in 1024-byte blocks
+#[cfg(unix)]
+fn df_capacity(path: &Path) -> Option<(u64, u64)> {
+    let output = std::process::Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().nth(1)?;
+    // The source and the mount point can contain spaces, so find the columns by the `%` between them
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let percent = fields.iter().skip(4).position(|field| field.ends_with('%'))? + 4;
+    let (total, available) = (fields[percent - 3], fields[percent - 1]);
+    let total = total.parse::<u64>().ok().filter(|total| *total > 0)?;
+    Some((total * 1024, available.parse::<u64>().ok()? * 1024))
+}
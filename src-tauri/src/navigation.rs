@@ -0,0 +1,139 @@
+/**
+ * Navigation history for Go Back / Go Forward
+ * A bounded stack of editor locations per workspace, saved with the workspace session. Jumps made by the
+ * language server (definition, references) and by the user share one history.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::clock::now_millis;
+
+/// Oldest locations are dropped beyond this many
+const MAX_HISTORY: usize = 50;
+
+/// Cursor moves within this many lines of the current location replace it instead of adding a new one
+const MERGE_LINES: usize = 10;
+
+/// What moved the cursor to a location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NavigationSource {
+    /// Clicks, keyboard, or opening a file
+    #[default]
+    Manual,
+    Definition,
+    References,
+    Search,
+    Symbol,
+    Diagnostic,
+}
+
+/// A location as pushed by the editor; `line` and `column` are zero-based
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavigationLocation {
+    pub path: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+    #[serde(default)]
+    pub source: NavigationSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavigationEntry {
+    #[serde(flatten)]
+    pub location: NavigationLocation,
+    /// Unix time in milliseconds
+    pub visited_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NavigationHistory {
+    pub entries: Vec<NavigationEntry>,
+    /// Index of the current location in `entries`
+    pub current: usize,
+}
+
+/// History with what Go Back and Go Forward would do, for the editor to enable its buttons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavigationState {
+    pub location: Option<NavigationEntry>,
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+impl NavigationHistory {
+    fn current_entry(&self) -> Option<&NavigationEntry> {
+        self.entries.get(self.current)
+    }
+
+    pub fn state(&self) -> NavigationState {
+        NavigationState {
+            location: self.current_entry().cloned(),
+            can_go_back: self.current > 0,
+            can_go_forward: self.current + 1 < self.entries.len(),
+        }
+    }
+
+    /// Record a visit. A manual move close to the current manual location only updates it, so scrolling and
+    /// typing don't flood the history, while jumps from the language server or search always get their own
+    /// entry. A new entry discards the forward history, as in a browser.
+    pub fn push(&mut self, location: NavigationLocation) {
+        let entry = NavigationEntry {
+            location,
+            visited_at: now_millis(),
+        };
+        if let Some(current) = self.current_entry() {
+            let nearby = current.location.path == entry.location.path
+                && current.location.line.abs_diff(entry.location.line) <= MERGE_LINES;
+            let manual = current.location.source == NavigationSource::Manual
+                && entry.location.source == NavigationSource::Manual;
+            if nearby && (manual || current.location.line == entry.location.line) {
+                self.entries[self.current] = entry;
+                return;
+            }
+        }
+
+        self.entries.truncate(self.current + 1);
+        self.entries.push(entry);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY);
+        }
+        self.current = self.entries.len() - 1;
+    }
+
+    /// Move `steps` back (negative) or forward, skipping locations whose files were deleted. Returns the
+    /// new location, or `None` when there is nothing in that direction.
+    pub fn navigate(&mut self, steps: isize) -> Option<NavigationEntry> {
+        let mut index = self.current;
+        let mut remaining = steps.unsigned_abs();
+        while remaining > 0 {
+            index = match steps < 0 {
+                true => index.checked_sub(1)?,
+                false => Some(index + 1).filter(|index| *index < self.entries.len())?,
+            };
+            if Path::new(&self.entries[index].location.path).is_file() {
+                remaining -= 1;
+            }
+        }
+        self.current = index;
+        self.current_entry().cloned()
+    }
+
+    /// Drop locations in files that no longer exist, keeping the current location where it was if possible
+    pub fn prune_missing_files(&mut self) {
+        let current = self.current;
+        let mut index = 0;
+        let mut removed_before = 0;
+        self.entries.retain(|entry| {
+            let keep = Path::new(&entry.location.path).is_file();
+            if !keep && index < current {
+                removed_before += 1;
+            }
+            index += 1;
+            keep
+        });
+        self.current = current.saturating_sub(removed_before).min(self.entries.len().saturating_sub(1));
+    }
+}
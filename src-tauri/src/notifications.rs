@@ -0,0 +1,296 @@
+/**
+ * Notifications and progress reporting for CodeForge IDE
+ * Backend subsystems report messages and long-running work here instead of on their own event channels; every
+ * change is emitted as `notification://update` so the frontend renders toasts and progress bars one way.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::now_millis;
+
+/// Event carrying every new, updated, and dismissed notification
+pub const NOTIFICATION_EVENT: &str = "notification://update";
+
+/// Finished notifications kept for the notification center, oldest dropped first
+const MAX_FINISHED_NOTIFICATIONS: usize = 50;
+
+/// Error types for notification operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NotificationError {
+    NotFound(String),
+    NotCancellable(String),
+}
+
+impl std::fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NotificationError::NotFound(id) => write!(f, "Notification not found: {}", id),
+            NotificationError::NotCancellable(id) => write!(f, "Notification cannot be cancelled: {}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationStatus {
+    /// Work still running; `progress` is updated as it goes
+    Active,
+    Completed,
+    Failed,
+    Cancelled,
+    /// Closed by the user; sent once so every window removes it
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: String,
+    /// Subsystem that raised it, e.g. `git` or `extensions`
+    pub source: String,
+    pub title: String,
+    pub message: Option<String>,
+    pub severity: NotificationSeverity,
+    pub status: NotificationStatus,
+    /// Percent done, or `None` while the total is unknown
+    pub progress: Option<u8>,
+    pub cancellable: bool,
+    /// Set once the user asked to cancel; the work stops at its next check
+    pub cancel_requested: bool,
+    /// Unix time in milliseconds
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+struct Entry {
+    notification: Notification,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Reports on one piece of long-running work. Finish it with `complete` or `fail`; a handle dropped while
+/// still active fails its notification so no progress bar is left spinning.
+pub struct ProgressHandle {
+    app: AppHandle,
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    finished: bool,
+}
+
+impl ProgressHandle {
+    /// Whether the user asked to cancel
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// The flag `cancel` sets, for work that already checks its own cancellation flag
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Update the percentage and, when given, the message. Unchanged values emit nothing, so this is cheap
+    /// to call from tight progress callbacks.
+    pub fn report(&self, progress: Option<u8>, message: Option<&str>) {
+        let progress = progress.map(|percent| percent.min(100));
+        self.app.state::<NotificationService>().update(&self.app, &self.id, |notification| {
+            let changed = notification.progress != progress
+                || message.is_some_and(|message| notification.message.as_deref() != Some(message));
+            notification.progress = progress;
+            if let Some(message) = message {
+                notification.message = Some(message.to_string());
+            }
+            changed
+        });
+    }
+
+    /// Finish successfully, or as cancelled when a cancel was requested
+    pub fn complete(mut self, message: Option<&str>) {
+        let status = match self.is_cancelled() {
+            true => NotificationStatus::Cancelled,
+            false => NotificationStatus::Completed,
+        };
+        self.finish(status, NotificationSeverity::Info, message);
+    }
+
+    /// Finish with an error, or as cancelled when the error came from a requested cancel
+    pub fn fail(mut self, error: &str) {
+        match self.is_cancelled() {
+            true => self.finish(NotificationStatus::Cancelled, NotificationSeverity::Info, None),
+            false => {
+                tracing::warn!(notification = %self.id, error = %error, "background operation failed");
+                self.finish(NotificationStatus::Failed, NotificationSeverity::Error, Some(error))
+            }
+        }
+    }
+
+    fn finish(&mut self, status: NotificationStatus, severity: NotificationSeverity, message: Option<&str>) {
+        self.finished = true;
+        let notifications = self.app.state::<NotificationService>();
+        notifications.update(&self.app, &self.id, |notification| {
+            notification.status = status;
+            notification.severity = severity;
+            if status == NotificationStatus::Completed {
+                notification.progress = Some(100);
+            }
+            if let Some(message) = message {
+                notification.message = Some(message.to_string());
+            }
+            true
+        });
+        notifications.trim_finished();
+    }
+}
+
+impl Drop for ProgressHandle {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finish(NotificationStatus::Failed, NotificationSeverity::Error, Some("Stopped unexpectedly"));
+        }
+    }
+}
+
+pub struct NotificationService {
+    notifications: Arc<Mutex<HashMap<String, Entry>>>,
+    next_id: AtomicU64,
+}
+
+impl NotificationService {
+    pub fn new() -> Self {
+        Self {
+            notifications: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Assign an id to `notification`, store it, and emit it; returns the id and its cancel flag
+    fn insert(&self, app: &AppHandle, mut notification: Notification) -> (String, Arc<AtomicBool>) {
+        let id = format!("notification-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+        notification.id = id.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _ = app.emit(NOTIFICATION_EVENT, notification.clone());
+        self.notifications.lock().unwrap().insert(
+            notification.id.clone(),
+            Entry {
+                notification,
+                cancelled: cancelled.clone(),
+            },
+        );
+        (id, cancelled)
+    }
+
+    /// Apply `change` and emit the result when it reports a change. Dismissed notifications are gone and
+    /// ignore further updates.
+    fn update(&self, app: &AppHandle, id: &str, change: impl FnOnce(&mut Notification) -> bool) {
+        let updated = {
+            let mut notifications = self.notifications.lock().unwrap();
+            let Some(entry) = notifications.get_mut(id) else {
+                return;
+            };
+            if !change(&mut entry.notification) {
+                return;
+            }
+            entry.notification.updated_at = now_millis();
+            entry.notification.clone()
+        };
+        let _ = app.emit(NOTIFICATION_EVENT, updated);
+    }
+
+    fn trim_finished(&self) {
+        let mut notifications = self.notifications.lock().unwrap();
+        let mut finished: Vec<(u64, String)> = notifications
+            .values()
+            .filter(|entry| entry.notification.status != NotificationStatus::Active)
+            .map(|entry| (entry.notification.updated_at, entry.notification.id.clone()))
+            .collect();
+        let excess = finished.len().saturating_sub(MAX_FINISHED_NOTIFICATIONS);
+        finished.sort();
+        for (_, id) in finished.into_iter().take(excess) {
+            notifications.remove(&id);
+        }
+    }
+
+    /// Start reporting progress of long-running work
+    pub fn start_progress(
+        &self,
+        app: &AppHandle,
+        source: &str,
+        title: &str,
+        cancellable: bool,
+    ) -> ProgressHandle {
+        let now = now_millis();
+        let notification = Notification {
+            id: String::new(),
+            source: source.to_string(),
+            title: title.to_string(),
+            message: None,
+            severity: NotificationSeverity::Info,
+            status: NotificationStatus::Active,
+            progress: None,
+            cancellable,
+            cancel_requested: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let (id, cancelled) = self.insert(app, notification);
+        ProgressHandle {
+            app: app.clone(),
+            id,
+            cancelled,
+            finished: false,
+        }
+    }
+
+    /// Current notifications, oldest first
+    pub fn list(&self) -> Vec<Notification> {
+        let mut notifications: Vec<Notification> =
+            self.notifications.lock().unwrap().values().map(|entry| entry.notification.clone()).collect();
+        notifications.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        notifications
+    }
+
+    /// Ask the work behind an active notification to stop
+    pub fn cancel(&self, app: &AppHandle, id: &str) -> Result<(), NotificationError> {
+        {
+            let notifications = self.notifications.lock().unwrap();
+            let entry = notifications.get(id).ok_or_else(|| NotificationError::NotFound(id.to_string()))?;
+            if !entry.notification.cancellable || entry.notification.status != NotificationStatus::Active {
+                return Err(NotificationError::NotCancellable(id.to_string()));
+            }
+            entry.cancelled.store(true, Ordering::SeqCst);
+        }
+        self.update(app, id, |notification| {
+            notification.cancel_requested = true;
+            true
+        });
+        Ok(())
+    }
+
+    /// Remove a notification. Work still running keeps going, just without a visible notification.
+    pub fn dismiss(&self, app: &AppHandle, id: &str) -> Result<(), NotificationError> {
+        let entry = self
+            .notifications
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| NotificationError::NotFound(id.to_string()))?;
+        let mut notification = entry.notification;
+        notification.status = NotificationStatus::Dismissed;
+        notification.updated_at = now_millis();
+        let _ = app.emit(NOTIFICATION_EVENT, notification);
+        Ok(())
+    }
+}
+
+impl Default for NotificationService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
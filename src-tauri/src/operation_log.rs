@@ -0,0 +1,255 @@
+/**
+ * Operation log for CodeForge IDE
+ * Journal of destructive file operations (deletes, overwrites, renames, moves, and bulk replaces) kept per
+ * workspace in the app data dir, so users can see what changed and restore it from local history
+ */
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+use crate::file_history::{FileHistoryService, VersionSource};
+use crate::file_import::{ImportResult, ImportStrategy};
+use crate::file_system::FileSystemService;
+use crate::session::workspace_key;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app data dir holding one log per workspace
+const OPERATIONS_DIR: &str = "operations";
+
+/// Log for operations on paths outside every workspace root
+const OUTSIDE_WORKSPACE_LOG: &str = "outside-workspace.json";
+
+/// Entries kept per log, oldest dropped first
+const MAX_LOG_ENTRIES: usize = 1000;
+
+/// Error types for operation log access
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OperationLogError {
+    NoDataDirectory,
+    IOError(String),
+}
+
+impl std::fmt::Display for OperationLogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OperationLogError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            OperationLogError::IOError(msg) => write!(f, "IO Error: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    Delete,
+    Overwrite,
+    Rename,
+    Move,
+    /// Text replaced across files, e.g. by a refactoring
+    BulkReplace,
+}
+
+/// Local history version holding a file's content from just before the operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationSnapshot {
+    pub path: String,
+    pub version_id: String,
+}
+
+/// One logged operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationRecord {
+    pub id: String,
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub kind: OperationKind,
+    /// Affected paths; for renames and moves, where they were
+    pub paths: Vec<String>,
+    /// For renames and moves, where each of `paths` ended up
+    #[serde(default)]
+    pub destinations: Vec<String>,
+    /// The command that made the change, e.g. `delete_file`
+    pub source: String,
+    /// OS account the IDE ran as
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub snapshots: Vec<OperationSnapshot>,
+}
+
+impl OperationRecord {
+    pub fn new(kind: OperationKind, paths: Vec<String>, source: &str) -> Self {
+        let timestamp = now_millis();
+        Self {
+            id: format!("{}-{}", timestamp, workspace_key(&paths.join("\n"))),
+            timestamp,
+            kind,
+            paths,
+            destinations: Vec::new(),
+            source: source.to_string(),
+            user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+            snapshots: Vec::new(),
+        }
+    }
+
+    pub fn with_destinations(mut self, destinations: Vec<String>) -> Self {
+        self.destinations = destinations;
+        self
+    }
+
+    pub fn with_snapshots(mut self, snapshots: Vec<OperationSnapshot>) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+}
+
+fn io_error(e: std::io::Error) -> OperationLogError {
+    OperationLogError::IOError(e.to_string())
+}
+
+fn log_path(app: &AppHandle, workspace: Option<&Path>) -> Result<PathBuf, OperationLogError> {
+    let data_dir = app.path().app_data_dir().map_err(|_| OperationLogError::NoDataDirectory)?;
+    let file_name = match workspace {
+        Some(workspace) => format!("{}.json", workspace_key(&workspace.to_string_lossy())),
+        None => OUTSIDE_WORKSPACE_LOG.to_string(),
+    };
+    Ok(data_dir.join(OPERATIONS_DIR).join(file_name))
+}
+
+fn load_log(path: &Path) -> Vec<OperationRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Copy the current content of the files among `paths` into local history so the operation can be undone.
+/// Directories, missing files, and files too large for history are left out.
+pub fn snapshot_files(app: &AppHandle, paths: &[String]) -> Vec<OperationSnapshot> {
+    let history = app.state::<FileHistoryService>();
+    paths
+        .iter()
+        .filter(|path| Path::new(path).is_file())
+        .filter_map(|path| {
+            // An unchanged file isn't copied again; its latest version already holds the content
+            let version = match history.record_version(app, path, VersionSource::Operation).ok()? {
+                Some(version) => version,
+                None => history.history(app, path).ok()?.into_iter().next()?,
+            };
+            Some(OperationSnapshot {
+                path: path.clone(),
+                version_id: version.id,
+            })
+        })
+        .collect()
+}
+
+/// Log `record` in the workspace of its first path. Logging is best effort and never fails the operation
+/// being logged.
+pub fn log_operation(app: &AppHandle, record: OperationRecord) {
+    let workspace = record.paths.first().and_then(|path| app.state::<FileSystemService>().workspace_root(path));
+    let source = record.source.clone();
+    if let Err(e) = app.state::<OperationLogService>().record(app, workspace.as_deref(), record) {
+        tracing::warn!(source = %source, error = %e, "logging file operation failed");
+    }
+}
+
+/// Entries an import or paste with `ImportStrategy::Overwrite` may replace, snapshotted before it runs
+pub struct PendingOverwrites {
+    targets: Vec<String>,
+    snapshots: Vec<OperationSnapshot>,
+}
+
+impl PendingOverwrites {
+    pub fn prepare(app: &AppHandle, sources: &[String], destination: &str, strategy: ImportStrategy) -> Self {
+        if strategy != ImportStrategy::Overwrite {
+            return Self {
+                targets: Vec::new(),
+                snapshots: Vec::new(),
+            };
+        }
+        let targets: Vec<String> = sources
+            .iter()
+            .filter_map(|source| Path::new(source).file_name())
+            .map(|name| Path::new(destination).join(name))
+            .filter(|target| fs::symlink_metadata(target).is_ok())
+            .map(|target| target.to_string_lossy().to_string())
+            .collect();
+        let snapshots = snapshot_files(app, &targets);
+        Self { targets, snapshots }
+    }
+
+    /// Log the targets that `result` shows were actually replaced
+    pub fn log(self, app: &AppHandle, result: &ImportResult, source: &str) {
+        let replaced: Vec<String> = self
+            .targets
+            .into_iter()
+            .filter(|target| result.imported.iter().any(|imported| &imported.destination == target))
+            .collect();
+        if replaced.is_empty() {
+            return;
+        }
+        let snapshots = self
+            .snapshots
+            .into_iter()
+            .filter(|snapshot| replaced.contains(&snapshot.path))
+            .collect();
+        let record = OperationRecord::new(OperationKind::Overwrite, replaced, source).with_snapshots(snapshots);
+        log_operation(app, record);
+    }
+}
+
+pub struct OperationLogService {
+    /// Serializes appends so concurrent operations don't drop each other's entries
+    lock: Arc<Mutex<()>>,
+}
+
+impl OperationLogService {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Append to the log of `workspace`, or of paths outside every workspace when `None`
+    pub fn record(
+        &self,
+        app: &AppHandle,
+        workspace: Option<&Path>,
+        record: OperationRecord,
+    ) -> Result<(), OperationLogError> {
+        let path = log_path(app, workspace)?;
+        let _guard = self.lock.lock().unwrap();
+        let mut records = load_log(&path);
+        records.push(record);
+        let excess = records.len().saturating_sub(MAX_LOG_ENTRIES);
+        records.drain(..excess);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let content =
+            serde_json::to_string_pretty(&records).map_err(|e| OperationLogError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(io_error)
+    }
+
+    /// Logged operations of a workspace, newest first
+    pub fn operations(
+        &self,
+        app: &AppHandle,
+        workspace: Option<&Path>,
+        limit: Option<usize>,
+    ) -> Result<Vec<OperationRecord>, OperationLogError> {
+        let path = log_path(app, workspace)?;
+        let _guard = self.lock.lock().unwrap();
+        Ok(load_log(&path).into_iter().rev().take(limit.unwrap_or(usize::MAX)).collect())
+    }
+}
+
+impl Default for OperationLogService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
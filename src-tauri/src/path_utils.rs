@@ -0,0 +1,321 @@
+/**
+ * Path utilities for CodeForge IDE
+ * Lexical path operations done the way the backend does them, so the frontend doesn't need its own
+ * implementations: normalizing, relativizing, joining, expanding `~` and environment variables, converting
+ * separators, and checking whether a name is a legal file name on each OS
+ *
+ * Nothing here touches the file system; `..` is resolved by text, not by following symlinks.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Longest file name, in bytes on Linux and macOS and in UTF-16 units on Windows
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Characters Windows doesn't allow in names, besides control characters
+const WINDOWS_RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names Windows reserves, with or without an extension
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Error types for path operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PathError {
+    NotInWorkspace(String),
+    /// The paths are on different drives or one is relative, so neither can be reached from the other
+    NoRelativePath { path: String, base: String },
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PathError::NotInWorkspace(path) => write!(f, "Path is not in a workspace: {}", path),
+            PathError::NoRelativePath { path, base } => {
+                write!(f, "No relative path leads from {} to {}", base, path)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Windows,
+    Macos,
+    Linux,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 3] = [Platform::Windows, Platform::Macos, Platform::Linux];
+
+    pub fn current() -> Platform {
+        if cfg!(windows) {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::Macos
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeparatorStyle {
+    /// `/`
+    Posix,
+    /// `\`
+    Windows,
+    /// The separator of the OS the IDE runs on
+    Native,
+}
+
+/// Why a name isn't allowed, and on which platforms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNameProblem {
+    pub platforms: Vec<Platform>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNameCheck {
+    pub name: String,
+    /// Legal on every platform checked
+    pub valid: bool,
+    pub problems: Vec<FileNameProblem>,
+}
+
+/// Result of `expand`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpandedPath {
+    pub path: String,
+    /// Variables referenced but not set; their references are left in `path` as written
+    pub unresolved: Vec<String>,
+}
+
+/// Resolve `.` and `..` and collapse repeated separators. `..` that would climb above a root is dropped;
+/// leading `..` of a relative path is kept. An empty result is `.`.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => normalized.push(component.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => {
+                normalized.pop();
+                depth -= 1;
+            }
+            Component::ParentDir if path.has_root() => {}
+            Component::ParentDir => normalized.push(".."),
+            Component::Normal(name) => {
+                normalized.push(name);
+                depth += 1;
+            }
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+/// `path` relative to `base`, with `/` separators, e.g. `../src/main.rs`; `.` when they're the same
+pub fn relativize(path: &Path, base: &Path) -> Result<String, PathError> {
+    let path = normalize(path);
+    let base = normalize(base);
+    let no_relative_path = || PathError::NoRelativePath {
+        path: path.to_string_lossy().to_string(),
+        base: base.to_string_lossy().to_string(),
+    };
+    let is_root = |component: &Component| matches!(component, Component::Prefix(_) | Component::RootDir);
+    let path_components: Vec<Component> = path.components().filter(|c| *c != Component::CurDir).collect();
+    let base_components: Vec<Component> = base.components().filter(|c| *c != Component::CurDir).collect();
+    let path_roots: Vec<&Component> = path_components.iter().take_while(|c| is_root(c)).collect();
+    let base_roots: Vec<&Component> = base_components.iter().take_while(|c| is_root(c)).collect();
+    // A base with unresolved `..` leads somewhere unknown
+    if path_roots != base_roots || base_components.contains(&Component::ParentDir) {
+        return Err(no_relative_path());
+    }
+
+    let common = path_components.iter().zip(&base_components).take_while(|(a, b)| a == b).count();
+    let parts: Vec<String> = std::iter::repeat_n("..".to_string(), base_components.len() - common)
+        .chain(path_components[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()))
+        .collect();
+    match parts.is_empty() {
+        true => Ok(".".to_string()),
+        false => Ok(parts.join("/")),
+    }
+}
+
+/// Join `segments` onto `base` and normalize; an absolute segment replaces everything before it, as with
+/// `Path::join`
+pub fn join(base: &Path, segments: &[String]) -> PathBuf {
+    let mut joined = base.to_path_buf();
+    for segment in segments {
+        joined.push(segment);
+    }
+    normalize(&joined)
+}
+
+/// The home directory from the environment
+pub fn home_dir() -> Option<String> {
+    std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok().filter(|home| !home.is_empty())
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_variable_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && name.chars().all(is_name_char)
+}
+
+/// Expand a leading `~` to the home directory and `$NAME`, `${NAME}`, and `%NAME%` to variables from `env`,
+/// then the process environment. References to unset variables are kept as written, since `%` and `$` are
+/// legal in file names.
+pub fn expand(path: &str, env: &HashMap<String, String>) -> ExpandedPath {
+    let lookup = |name: &str| env.get(name).cloned().or_else(|| std::env::var(name).ok());
+    let mut unresolved = Vec::new();
+    let mut output = String::with_capacity(path.len());
+
+    let mut rest = path;
+    if rest == "~" || rest.starts_with("~/") || rest.starts_with("~\\") {
+        match home_dir() {
+            Some(home) => {
+                output.push_str(home.trim_end_matches(['/', '\\']));
+                rest = &rest[1..];
+            }
+            None => unresolved.push("HOME".to_string()),
+        }
+    }
+
+    while let Some(start) = rest.find(['$', '%']) {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let reference = if let Some(braced) = tail.strip_prefix("${") {
+            braced.find('}').map(|end| (&braced[..end], end + 3))
+        } else if let Some(percent) = tail.strip_prefix('%') {
+            percent.find('%').map(|end| (&percent[..end], end + 2))
+        } else {
+            let name_length = tail[1..].find(|c: char| !is_name_char(c)).unwrap_or(tail.len() - 1);
+            Some((&tail[1..1 + name_length], name_length + 1))
+        };
+        let reference = reference.filter(|(name, _)| is_variable_name(name));
+        match reference {
+            Some((name, length)) => {
+                match lookup(name) {
+                    Some(value) => output.push_str(&value),
+                    None => {
+                        if !unresolved.iter().any(|unset| unset == name) {
+                            unresolved.push(name.to_string());
+                        }
+                        output.push_str(&tail[..length]);
+                    }
+                }
+                rest = &tail[length..];
+            }
+            None => {
+                output.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    ExpandedPath {
+        path: output,
+        unresolved,
+    }
+}
+
+/// Rewrite every `/` and `\` as the separator of `style`
+pub fn convert_separators(path: &str, style: SeparatorStyle) -> String {
+    let separator = match style {
+        SeparatorStyle::Posix => '/',
+        SeparatorStyle::Windows => '\\',
+        SeparatorStyle::Native => std::path::MAIN_SEPARATOR,
+    };
+    path.replace(['/', '\\'], &separator.to_string())
+}
+
+fn platform_problems(name: &str, platform: Platform) -> Vec<String> {
+    let mut problems = Vec::new();
+    if name.contains('/') {
+        problems.push("contains '/'".to_string());
+    }
+    if name.contains('\0') {
+        problems.push("contains a NUL character".to_string());
+    }
+    match platform {
+        Platform::Windows => {
+            let reserved: String = WINDOWS_RESERVED_CHARS
+                .iter()
+                .filter(|c| **c != '/' && name.contains(**c))
+                .map(|c| format!(" {}", c))
+                .collect();
+            if !reserved.is_empty() {
+                problems.push(format!("contains characters Windows reserves:{}", reserved));
+            }
+            if name.chars().any(|c| c != '\0' && c.is_control()) {
+                problems.push("contains control characters".to_string());
+            }
+            let stem = name.split('.').next().unwrap_or(name).trim_end();
+            if WINDOWS_RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+                problems.push(format!("'{}' is a device name Windows reserves", stem.to_uppercase()));
+            }
+            if name.ends_with(' ') || name.ends_with('.') {
+                problems.push("ends with a space or a period, which Windows drops".to_string());
+            }
+            if name.encode_utf16().count() > MAX_NAME_LENGTH {
+                problems.push(format!("is longer than {} characters", MAX_NAME_LENGTH));
+            }
+        }
+        Platform::Macos | Platform::Linux => {
+            // Finder shows `/` in names as `:`, so `:` can't be used there
+            if platform == Platform::Macos && name.contains(':') {
+                problems.push("contains ':'".to_string());
+            }
+            if name.len() > MAX_NAME_LENGTH {
+                problems.push(format!("is longer than {} bytes", MAX_NAME_LENGTH));
+            }
+        }
+    }
+    problems
+}
+
+/// Check `name` as a single file or folder name on each of `platforms`; problems shared by several
+/// platforms are reported once
+pub fn validate_file_name(name: &str, platforms: &[Platform]) -> FileNameCheck {
+    let mut problems: Vec<FileNameProblem> = Vec::new();
+    if name.is_empty() || name == "." || name == ".." {
+        problems.push(FileNameProblem {
+            platforms: platforms.to_vec(),
+            message: match name.is_empty() {
+                true => "is empty".to_string(),
+                false => format!("'{}' is reserved", name),
+            },
+        });
+    } else {
+        for platform in platforms {
+            for message in platform_problems(name, *platform) {
+                match problems.iter_mut().find(|problem| problem.message == message) {
+                    Some(problem) => problem.platforms.push(*platform),
+                    None => problems.push(FileNameProblem {
+                        platforms: vec![*platform],
+                        message,
+                    }),
+                }
+            }
+        }
+    }
+    FileNameCheck {
+        name: name.to_string(),
+        valid: problems.is_empty(),
+        problems,
+    }
+}
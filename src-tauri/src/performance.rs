@@ -0,0 +1,202 @@
+/**
+ * IPC performance statistics for CodeForge IDE
+ * Wraps the command handler to count invocations, time them, and measure their payloads per command, so slow
+ * or chatty calls between the UI and the backend can be found without a profiler.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, Runtime};
+
+/// Upper bounds of the latency histogram buckets in milliseconds; a last bucket catches everything slower
+const LATENCY_BUCKETS_MS: [f64; 10] = [1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0];
+
+/// Calls at or above this duration are logged as slow
+const SLOW_COMMAND_MS: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    /// Upper bound in milliseconds; `None` for the last, unbounded bucket
+    pub le_ms: Option<f64>,
+    pub count: u64,
+}
+
+/// Statistics of one command since startup or the last reset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub invocations: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    /// Upper bounds of the buckets holding the median and 95th percentile call
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub histogram: Vec<LatencyBucket>,
+    /// Serialized size of the arguments
+    pub total_payload_bytes: u64,
+    pub max_payload_bytes: u64,
+    /// Unix time in milliseconds of the latest call
+    pub last_invoked_at: u64,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    invocations: u64,
+    total: Duration,
+    max: Duration,
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    total_payload_bytes: u64,
+    max_payload_bytes: u64,
+    last_invoked_at: u64,
+}
+
+impl Accumulator {
+    fn record(&mut self, elapsed: Duration, payload_bytes: u64) {
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| elapsed_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+        self.invocations += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        self.total_payload_bytes += payload_bytes;
+        self.max_payload_bytes = self.max_payload_bytes.max(payload_bytes);
+        self.last_invoked_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+    }
+
+    /// Upper bound of the bucket containing the call at `quantile`; `None` when it is the unbounded bucket
+    fn quantile_bound(&self, quantile: f64) -> Option<f64> {
+        let rank = ((self.invocations as f64) * quantile).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKETS_MS.get(index).copied();
+            }
+        }
+        None
+    }
+
+    fn stats(&self, command: &str) -> CommandStats {
+        let total_ms = self.total.as_secs_f64() * 1000.0;
+        CommandStats {
+            command: command.to_string(),
+            invocations: self.invocations,
+            total_ms,
+            mean_ms: total_ms / self.invocations.max(1) as f64,
+            max_ms: self.max.as_secs_f64() * 1000.0,
+            p50_ms: self.quantile_bound(0.5),
+            p95_ms: self.quantile_bound(0.95),
+            histogram: self
+                .buckets
+                .iter()
+                .enumerate()
+                .map(|(index, count)| LatencyBucket {
+                    le_ms: LATENCY_BUCKETS_MS.get(index).copied(),
+                    count: *count,
+                })
+                .collect(),
+            total_payload_bytes: self.total_payload_bytes,
+            max_payload_bytes: self.max_payload_bytes,
+            last_invoked_at: self.last_invoked_at,
+        }
+    }
+}
+
+/// Counts bytes written without keeping them
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn payload_bytes(body: &InvokeBody) -> u64 {
+    match body {
+        InvokeBody::Json(value) => {
+            let mut counter = ByteCounter(0);
+            let _ = serde_json::to_writer(&mut counter, value);
+            counter.0
+        }
+        InvokeBody::Raw(bytes) => bytes.len() as u64,
+    }
+}
+
+/// Measure every call going through `handler`. The time recorded is how long the handler holds the IPC
+/// thread: the whole call for synchronous commands, which run on the main thread and are what stall the UI,
+/// but only the dispatch for async commands, whose work continues on the async runtime.
+pub fn instrument<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let payload_bytes = payload_bytes(invoke.message.payload());
+        let webview = invoke.message.webview();
+        let started = Instant::now();
+        let handled = handler(invoke);
+        let elapsed = started.elapsed();
+        if let Some(performance) = webview.try_state::<PerformanceService>() {
+            performance.record(&command, elapsed, payload_bytes);
+        }
+        handled
+    }
+}
+
+pub struct PerformanceService {
+    commands: Arc<Mutex<HashMap<String, Accumulator>>>,
+}
+
+impl PerformanceService {
+    pub fn new() -> Self {
+        Self {
+            commands: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn record(&self, command: &str, elapsed: Duration, payload_bytes: u64) {
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        if elapsed_ms >= SLOW_COMMAND_MS {
+            tracing::debug!(command, elapsed_ms, payload_bytes, "slow command");
+        }
+        self.commands.lock().unwrap().entry(command.to_string()).or_default().record(elapsed, payload_bytes);
+    }
+
+    /// Statistics per command, the commands taking the most time in total first
+    pub fn stats(&self) -> Vec<CommandStats> {
+        let mut stats: Vec<CommandStats> = self
+            .commands
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(command, accumulator)| accumulator.stats(command))
+            .collect();
+        stats.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms).then_with(|| a.command.cmp(&b.command)));
+        stats
+    }
+
+    pub fn reset(&self) {
+        self.commands.lock().unwrap().clear();
+    }
+}
+
+impl Default for PerformanceService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,240 @@
+/**
+ * File systems contributed by plugins
+ * Serves paths with a contributed scheme by passing each operation to the plugin's `fs_request` export as
+ * JSON, e.g. `{"op": "read", "uri": "memfs://scratch/a.txt", "authority": "scratch", "path": "/a.txt"}`.
+ *
+ * The plugin replies `{"ok": value}` or `{"error": {"code": "notFound", "message": "..."}}`. The value is a
+ * stat (`{"kind": "file", "size": 12, "modified": 1700000000, "permissions": 420}`) for `stat`, base64
+ * content for `read`, and a list of stats with a `name` each for `list`. Other operations reply
+ * `{"ok": null}` or nothing. `write` sends base64 `content`; `remove` sends `recursive`; `rename` sends the
+ * destination as `target` along with `overwrite`.
+ */
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::{FileSystemContribution, PluginService};
+use crate::fs_provider::{EntryKind, FileSystemProvider, OpenProvider, ProviderEntry, ProviderStat, RemoteUri};
+use crate::types::FileSystemError;
+
+/// A file system contributed by a plugin, with the plugin providing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginFileSystem {
+    pub plugin_id: String,
+    #[serde(flatten)]
+    pub contribution: FileSystemContribution,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Operation {
+    Stat,
+    Read,
+    Write { content: String },
+    List,
+    CreateDirectory,
+    Remove { recursive: bool },
+    Rename { target: String, overwrite: bool },
+}
+
+#[derive(Debug, Serialize)]
+struct Request {
+    #[serde(flatten)]
+    operation: Operation,
+    uri: String,
+    authority: String,
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Reply {
+    Ok(serde_json::Value),
+    Error(ReplyError),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ErrorCode {
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    /// Also for a file where a directory was expected, or the other way around
+    InvalidPath,
+    Unsupported,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplyError {
+    code: ErrorCode,
+    #[serde(default)]
+    message: String,
+}
+
+impl From<ReplyError> for FileSystemError {
+    fn from(error: ReplyError) -> Self {
+        match error.code {
+            ErrorCode::NotFound => FileSystemError::NotFound,
+            ErrorCode::AlreadyExists => FileSystemError::AlreadyExists,
+            ErrorCode::PermissionDenied => FileSystemError::PermissionDenied,
+            ErrorCode::InvalidPath => FileSystemError::InvalidPath,
+            ErrorCode::Unsupported => FileSystemError::Unsupported(error.message),
+            ErrorCode::Other => FileSystemError::IOError(error.message),
+        }
+    }
+}
+
+/// A stat as plugins send it; everything but the kind is optional
+#[derive(Debug, Deserialize)]
+struct PluginStat {
+    kind: EntryKind,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    modified: Option<u64>,
+    #[serde(default)]
+    permissions: Option<u32>,
+}
+
+impl From<PluginStat> for ProviderStat {
+    fn from(stat: PluginStat) -> Self {
+        let default_permissions = match stat.kind {
+            EntryKind::Directory => 0o755,
+            _ => 0o644,
+        };
+        ProviderStat {
+            kind: stat.kind,
+            size: stat.size,
+            modified: stat.modified,
+            permissions: stat.permissions.unwrap_or(default_permissions),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginEntry {
+    name: String,
+    #[serde(flatten)]
+    stat: PluginStat,
+}
+
+/// One authority of a plugin's file system, e.g. one bucket of `s3://`
+pub struct PluginProvider {
+    app: AppHandle,
+    scheme: String,
+    authority: String,
+    read_only: bool,
+}
+
+impl PluginProvider {
+    /// Opens a provider for each authority of a contributed file system
+    pub fn opener(app: AppHandle, contribution: &FileSystemContribution) -> OpenProvider {
+        let read_only = contribution.read_only;
+        Arc::new(move |uri: &RemoteUri| {
+            let provider: Arc<dyn FileSystemProvider> = Arc::new(PluginProvider {
+                app: app.clone(),
+                scheme: uri.scheme.clone(),
+                authority: uri.authority.clone(),
+                read_only,
+            });
+            Ok(provider)
+        })
+    }
+
+    fn request(&self, path: &str, operation: Operation) -> Result<serde_json::Value, FileSystemError> {
+        let uri = RemoteUri {
+            scheme: self.scheme.clone(),
+            authority: self.authority.clone(),
+            path: path.to_string(),
+        };
+        let request = Request {
+            operation,
+            uri: uri.to_string(),
+            authority: self.authority.clone(),
+            path: path.to_string(),
+        };
+        let request = serde_json::to_string(&request).map_err(|e| FileSystemError::UnknownError(e.to_string()))?;
+        let reply = self
+            .app
+            .state::<PluginService>()
+            .file_system_request(&self.app, &self.scheme, &request)
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        let Some(reply) = reply else {
+            return Ok(serde_json::Value::Null);
+        };
+        let reply: Reply = serde_json::from_str(&reply)
+            .map_err(|e| FileSystemError::IOError(format!("{}:// replied with {}", self.scheme, e)))?;
+        match reply {
+            Reply::Ok(value) => Ok(value),
+            Reply::Error(error) => Err(error.into()),
+        }
+    }
+
+    fn parse<T: for<'de> Deserialize<'de>>(&self, value: serde_json::Value) -> Result<T, FileSystemError> {
+        serde_json::from_value(value)
+            .map_err(|e| FileSystemError::IOError(format!("{}:// replied with {}", self.scheme, e)))
+    }
+
+    fn check_writable(&self) -> Result<(), FileSystemError> {
+        match self.read_only {
+            true => Err(FileSystemError::PermissionDenied),
+            false => Ok(()),
+        }
+    }
+}
+
+impl FileSystemProvider for PluginProvider {
+    fn name(&self) -> String {
+        format!("{}://{}", self.scheme, self.authority)
+    }
+
+    fn stat(&self, path: &str) -> Result<ProviderStat, FileSystemError> {
+        let stat: PluginStat = self.parse(self.request(path, Operation::Stat)?)?;
+        Ok(stat.into())
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, FileSystemError> {
+        let content: String = self.parse(self.request(path, Operation::Read)?)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| FileSystemError::IOError(format!("{}:// replied with {}", self.scheme, e)))
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), FileSystemError> {
+        self.check_writable()?;
+        let content = base64::engine::general_purpose::STANDARD.encode(content);
+        self.request(path, Operation::Write { content }).map(|_| ())
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<ProviderEntry>, FileSystemError> {
+        let entries: Vec<PluginEntry> = self.parse(self.request(path, Operation::List)?)?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| !entry.name.is_empty() && !entry.name.contains('/'))
+            .map(|entry| ProviderEntry {
+                name: entry.name,
+                stat: entry.stat.into(),
+            })
+            .collect())
+    }
+
+    fn create_directory(&self, path: &str) -> Result<(), FileSystemError> {
+        self.check_writable()?;
+        self.request(path, Operation::CreateDirectory).map(|_| ())
+    }
+
+    fn remove(&self, path: &str, recursive: bool) -> Result<(), FileSystemError> {
+        self.check_writable()?;
+        self.request(path, Operation::Remove { recursive }).map(|_| ())
+    }
+
+    fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileSystemError> {
+        self.check_writable()?;
+        let target = to.to_string();
+        self.request(from, Operation::Rename { target, overwrite }).map(|_| ())
+    }
+}
@@ -0,0 +1,351 @@
+/**
+ * WASM plugin host
+ * Instantiates plugin modules with wasmtime and implements the `codeforge` host API (v1)
+ *
+ * Guest modules export `memory` and `alloc(len) -> ptr`, plus any of `activate()`, `on_file_open(ptr, len)`,
+ * `on_command(ptr, len, args_ptr, args_len) -> i64`, and, for plugins contributing file systems,
+ * `fs_request(ptr, len) -> i64`. Strings cross the boundary as UTF-8 in guest memory; results are packed as
+ * `ptr << 32 | len`, with 0 for "no result" and -1 for errors.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use wasmtime::{
+    AsContext, AsContextMut, Caller, Engine, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+use super::PluginError;
+
+/// Log lines written by plugins
+pub const PLUGIN_LOG_EVENT: &str = "plugin://log";
+/// Notification a plugin asks the UI to show
+pub const PLUGIN_MESSAGE_EVENT: &str = "plugin://message";
+/// Status bar item a plugin created or updated; empty text removes it
+pub const PLUGIN_STATUS_ITEM_EVENT: &str = "plugin://status-item";
+
+/// Host API namespace guest modules import from
+const HOST_MODULE: &str = "codeforge";
+
+/// Instruction budget for one call into a plugin, so a runaway plugin can't hang the backend
+const FUEL_PER_CALL: u64 = 2_000_000_000;
+
+/// Linear memory cap per plugin
+const MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Largest file a plugin can read through the host API
+const MAX_READ_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Severity of plugin log lines and messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PluginMessageLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl PluginMessageLevel {
+    fn from_code(code: i32) -> Self {
+        match code {
+            1 => PluginMessageLevel::Warning,
+            2.. => PluginMessageLevel::Error,
+            _ => PluginMessageLevel::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginMessage {
+    pub plugin_id: String,
+    pub level: PluginMessageLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginStatusItem {
+    pub plugin_id: String,
+    pub id: String,
+    pub text: String,
+}
+
+pub(super) struct HostState {
+    app: AppHandle,
+    plugin_id: String,
+    /// File access is confined to the workspace; without one, plugins can't touch files
+    workspace: Option<PathBuf>,
+    limits: StoreLimits,
+}
+
+pub(super) struct WasmPlugin {
+    store: Store<HostState>,
+    instance: Instance,
+    memory: Memory,
+}
+
+fn trap(e: wasmtime::Error) -> PluginError {
+    PluginError::Trap(format!("{:#}", e))
+}
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> wasmtime::Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .ok_or_else(|| wasmtime::Error::msg("plugin does not export its memory"))
+}
+
+fn read_guest_string(memory: &Memory, store: impl AsContext, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let start = ptr as u32 as usize;
+    let bytes = memory
+        .data(&store)
+        .get(start..start + len as u32 as usize)
+        .ok_or_else(|| wasmtime::Error::msg("string out of bounds of plugin memory"))?;
+    Ok(String::from_utf8_lossy(bytes).to_string())
+}
+
+/// Copy bytes into memory allocated by the guest's `alloc`, returning the packed pointer and length
+fn write_guest_bytes(
+    mut store: impl AsContextMut,
+    instance_alloc: wasmtime::TypedFunc<i32, i32>,
+    memory: &Memory,
+    bytes: &[u8],
+) -> wasmtime::Result<i64> {
+    let len = i32::try_from(bytes.len()).map_err(|_| wasmtime::Error::msg("value too large for plugin memory"))?;
+    let ptr = instance_alloc.call(&mut store, len)?;
+    memory.write(&mut store, ptr as u32 as usize, bytes)?;
+    Ok(((ptr as u32 as i64) << 32) | len as u32 as i64)
+}
+
+/// Resolve a path a plugin asked for, refusing anything outside the workspace
+fn confine(state: &HostState, path: &str) -> Option<PathBuf> {
+    let workspace = state.workspace.as_ref()?.canonicalize().ok()?;
+    let path = workspace.join(path);
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        // A dangling symlink would be followed on write, wherever it points
+        Err(_) if fs::symlink_metadata(&path).is_ok() => return None,
+        // New files: the parent has to exist inside the workspace
+        Err(_) => path.parent()?.canonicalize().ok()?.join(path.file_name()?),
+    };
+    resolved.starts_with(&workspace).then_some(resolved)
+}
+
+fn emit_message(
+    caller: &mut Caller<'_, HostState>,
+    event: &str,
+    level: i32,
+    ptr: i32,
+    len: i32,
+) -> wasmtime::Result<()> {
+    let memory = guest_memory(caller)?;
+    let message = read_guest_string(&memory, &*caller, ptr, len)?;
+    let state = caller.data();
+    let _ = state.app.emit(
+        event,
+        PluginMessage {
+            plugin_id: state.plugin_id.clone(),
+            level: PluginMessageLevel::from_code(level),
+            message,
+        },
+    );
+    Ok(())
+}
+
+fn host_linker(engine: &Engine) -> Result<Linker<HostState>, PluginError> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(HOST_MODULE, "log", |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+            emit_message(&mut caller, PLUGIN_LOG_EVENT, level, ptr, len)
+        })
+        .map_err(trap)?;
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "show_message",
+            |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+                emit_message(&mut caller, PLUGIN_MESSAGE_EVENT, level, ptr, len)
+            },
+        )
+        .map_err(trap)?;
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "set_status_item",
+            |mut caller: Caller<'_, HostState>, id_ptr: i32, id_len: i32, text_ptr: i32, text_len: i32| {
+                let memory = guest_memory(&mut caller)?;
+                let id = read_guest_string(&memory, &caller, id_ptr, id_len)?;
+                let text = read_guest_string(&memory, &caller, text_ptr, text_len)?;
+                let state = caller.data();
+                let _ = state.app.emit(
+                    PLUGIN_STATUS_ITEM_EVENT,
+                    PluginStatusItem {
+                        plugin_id: state.plugin_id.clone(),
+                        id,
+                        text,
+                    },
+                );
+                Ok(())
+            },
+        )
+        .map_err(trap)?;
+    linker
+        .func_wrap(HOST_MODULE, "read_file", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            let memory = guest_memory(&mut caller)?;
+            let path = read_guest_string(&memory, &caller, ptr, len)?;
+            let Some(path) = confine(caller.data(), &path) else {
+                return Ok(-1);
+            };
+            let readable =
+                fs::metadata(&path).is_ok_and(|metadata| metadata.is_file() && metadata.len() <= MAX_READ_BYTES);
+            let Some(content) = readable.then(|| fs::read(&path).ok()).flatten() else {
+                return Ok(-1);
+            };
+            let alloc = caller
+                .get_export("alloc")
+                .and_then(|export| export.into_func())
+                .ok_or_else(|| wasmtime::Error::msg("plugin does not export alloc"))?
+                .typed::<i32, i32>(&caller)?;
+            write_guest_bytes(&mut caller, alloc, &memory, &content)
+        })
+        .map_err(trap)?;
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "write_file",
+            |mut caller: Caller<'_, HostState>, path_ptr: i32, path_len: i32, data_ptr: i32, data_len: i32| {
+                let memory = guest_memory(&mut caller)?;
+                let path = read_guest_string(&memory, &caller, path_ptr, path_len)?;
+                let start = data_ptr as u32 as usize;
+                let Some(data) = memory.data(&caller).get(start..start + data_len as u32 as usize) else {
+                    return Ok(-1);
+                };
+                let data = data.to_vec();
+                let Some(path) = confine(caller.data(), &path) else {
+                    return Ok(-1);
+                };
+                Ok(if fs::write(path, data).is_ok() { 0 } else { -1 })
+            },
+        )
+        .map_err(trap)?;
+    Ok(linker)
+}
+
+impl WasmPlugin {
+    /// Compile and instantiate a plugin module; nothing in it runs until `activate`
+    pub fn load(
+        engine: &Engine,
+        app: &AppHandle,
+        plugin_id: &str,
+        module_path: &Path,
+        workspace: Option<PathBuf>,
+    ) -> Result<Self, PluginError> {
+        let module =
+            Module::from_file(engine, module_path).map_err(|e| PluginError::LoadFailed(format!("{:#}", e)))?;
+        let state = HostState {
+            app: app.clone(),
+            plugin_id: plugin_id.to_string(),
+            workspace,
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build(),
+        };
+        let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(trap)?;
+
+        let linker = host_linker(engine)?;
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| PluginError::LoadFailed(format!("{:#}", e)))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| PluginError::LoadFailed("plugin does not export its memory".to_string()))?;
+        Ok(Self { store, instance, memory })
+    }
+
+    pub fn set_workspace(&mut self, workspace: Option<PathBuf>) {
+        self.store.data_mut().workspace = workspace;
+    }
+
+    fn refuel(&mut self) -> Result<(), PluginError> {
+        self.store.set_fuel(FUEL_PER_CALL).map_err(trap)
+    }
+
+    fn exports(&mut self, name: &str) -> bool {
+        self.instance.get_func(&mut self.store, name).is_some()
+    }
+
+    fn write_string(&mut self, text: &str) -> Result<(i32, i32), PluginError> {
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .map_err(trap)?;
+        let packed = write_guest_bytes(&mut self.store, alloc, &self.memory, text.as_bytes()).map_err(trap)?;
+        Ok(((packed >> 32) as i32, packed as i32))
+    }
+
+    /// Run the plugin's `activate` export, if it has one
+    pub fn activate(&mut self) -> Result<(), PluginError> {
+        if !self.exports("activate") {
+            return Ok(());
+        }
+        self.refuel()?;
+        let activate = self.instance.get_typed_func::<(), ()>(&mut self.store, "activate").map_err(trap)?;
+        activate.call(&mut self.store, ()).map_err(trap)
+    }
+
+    pub fn on_file_open(&mut self, path: &str) -> Result<(), PluginError> {
+        if !self.exports("on_file_open") {
+            return Ok(());
+        }
+        self.refuel()?;
+        let (ptr, len) = self.write_string(path)?;
+        let hook = self
+            .instance
+            .get_typed_func::<(i32, i32), ()>(&mut self.store, "on_file_open")
+            .map_err(trap)?;
+        hook.call(&mut self.store, (ptr, len)).map_err(trap)
+    }
+
+    /// Pass a JSON file system request to the plugin, returning its JSON reply
+    pub fn on_file_system_request(&mut self, request: &str) -> Result<Option<String>, PluginError> {
+        if !self.exports("fs_request") {
+            return Err(PluginError::Trap("plugin does not export fs_request".to_string()));
+        }
+        self.refuel()?;
+        let (ptr, len) = self.write_string(request)?;
+        let hook = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&mut self.store, "fs_request")
+            .map_err(trap)?;
+        let packed = hook.call(&mut self.store, (ptr, len)).map_err(trap)?;
+        self.read_result(packed, "fs_request")
+    }
+
+    fn read_result(&self, packed: i64, call: &str) -> Result<Option<String>, PluginError> {
+        match packed {
+            0 => Ok(None),
+            -1 => Err(PluginError::Trap(format!("{} failed", call))),
+            packed => read_guest_string(&self.memory, &self.store, (packed >> 32) as i32, packed as i32)
+                .map(Some)
+                .map_err(trap),
+        }
+    }
+
+    /// Run a contributed command; the plugin may return a string result
+    pub fn on_command(&mut self, command: &str, args: &str) -> Result<Option<String>, PluginError> {
+        if !self.exports("on_command") {
+            return Err(PluginError::CommandNotFound(command.to_string()));
+        }
+        self.refuel()?;
+        let (command_ptr, command_len) = self.write_string(command)?;
+        let (args_ptr, args_len) = self.write_string(args)?;
+        let hook = self
+            .instance
+            .get_typed_func::<(i32, i32, i32, i32), i64>(&mut self.store, "on_command")
+            .map_err(trap)?;
+        let packed = hook
+            .call(&mut self.store, (command_ptr, command_len, args_ptr, args_len))
+            .map_err(trap)?;
+        self.read_result(packed, &format!("command '{}'", command))
+    }
+}
@@ -0,0 +1,229 @@
+/**
+ * Plugin manifests (`plugin.json`) and activation events
+ */
+
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path};
+
+use super::PluginError;
+use crate::fs_provider;
+
+/// Manifest file at the root of every plugin directory
+pub const MANIFEST_FILE: &str = "plugin.json";
+
+/// Version of the host API exposed to plugins; manifests may not ask for a newer one
+pub const HOST_API_VERSION: u32 = 1;
+
+fn default_api_version() -> u32 {
+    HOST_API_VERSION
+}
+
+/// A command a plugin adds to the command palette
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandContribution {
+    pub command: String,
+    pub title: String,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// A URI scheme whose paths a plugin serves, e.g. `zip` for `zip:///home/me/a.zip/src/lib.rs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemContribution {
+    pub scheme: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Writes fail with permission denied without reaching the plugin
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginContributions {
+    #[serde(default)]
+    pub commands: Vec<CommandContribution>,
+    #[serde(default)]
+    pub file_systems: Vec<FileSystemContribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    /// Unique id in `publisher.name` form
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub publisher: String,
+    /// WASM module, relative to the plugin directory
+    pub main: String,
+    #[serde(default = "default_api_version")]
+    pub api_version: u32,
+    /// When to activate: `*`, `onStartup`, `onFileOpen:<glob>`, `onLanguage:<id>`, `onCommand:<id>`,
+    /// `onFileSystem:<scheme>`, or `workspaceContains:<glob>`
+    #[serde(default)]
+    pub activation_events: Vec<String>,
+    #[serde(default)]
+    pub contributes: PluginContributions,
+}
+
+/// Something that happened in the IDE that may activate plugins
+#[derive(Debug, Clone, Copy)]
+pub enum PluginTrigger<'a> {
+    Startup,
+    FileOpen { path: &'a str, language: Option<&'a str> },
+    Command(&'a str),
+    /// A path with a plugin's scheme was accessed
+    FileSystem(&'a str),
+    Workspace(&'a Path),
+}
+
+/// Parsed activation event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivationEvent {
+    Startup,
+    FileOpen(String),
+    Language(String),
+    Command(String),
+    FileSystem(String),
+    WorkspaceContains(String),
+}
+
+impl ActivationEvent {
+    pub fn parse(event: &str) -> Result<Self, String> {
+        if event == "*" || event == "onStartup" {
+            return Ok(ActivationEvent::Startup);
+        }
+        let (kind, argument) = event
+            .split_once(':')
+            .filter(|(_, argument)| !argument.is_empty())
+            .ok_or_else(|| format!("unknown activation event '{}'", event))?;
+        let argument = argument.to_string();
+        match kind {
+            "onFileOpen" | "workspaceContains" => {
+                Glob::new(&argument).map_err(|e| format!("{}: {}", event, e))?;
+                Ok(if kind == "onFileOpen" {
+                    ActivationEvent::FileOpen(argument)
+                } else {
+                    ActivationEvent::WorkspaceContains(argument)
+                })
+            }
+            "onLanguage" => Ok(ActivationEvent::Language(argument)),
+            "onCommand" => Ok(ActivationEvent::Command(argument)),
+            "onFileSystem" => Ok(ActivationEvent::FileSystem(argument.to_ascii_lowercase())),
+            _ => Err(format!("unknown activation event '{}'", event)),
+        }
+    }
+
+    pub fn matches(&self, trigger: PluginTrigger) -> bool {
+        let glob_matches = |pattern: &str, path: &Path| {
+            Glob::new(pattern).map(|glob| glob.compile_matcher().is_match(path)).unwrap_or(false)
+        };
+        match (self, trigger) {
+            (ActivationEvent::Startup, PluginTrigger::Startup) => true,
+            (ActivationEvent::FileOpen(pattern), PluginTrigger::FileOpen { path, .. }) => {
+                // Patterns without a directory part match the file name anywhere
+                let path = Path::new(path);
+                let name_matches =
+                    || path.file_name().is_some_and(|name| glob_matches(pattern, Path::new(name)));
+                glob_matches(pattern, path) || (!pattern.contains('/') && name_matches())
+            }
+            (ActivationEvent::Language(language), PluginTrigger::FileOpen { language: Some(opened), .. }) => {
+                language == opened
+            }
+            (ActivationEvent::Command(command), PluginTrigger::Command(invoked)) => command == invoked,
+            (ActivationEvent::FileSystem(scheme), PluginTrigger::FileSystem(accessed)) => scheme == accessed,
+            (ActivationEvent::WorkspaceContains(pattern), PluginTrigger::Workspace(workspace)) => {
+                Glob::new(pattern)
+                    .map(|glob| glob.compile_matcher())
+                    .map(|matcher| {
+                        walkdir::WalkDir::new(workspace)
+                            .max_depth(3)
+                            .into_iter()
+                            .filter_map(|entry| entry.ok())
+                            .any(|entry| entry.path().strip_prefix(workspace).is_ok_and(|p| matcher.is_match(p)))
+                    })
+                    .unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `publisher.name`, lowercase letters, digits, and dashes
+pub fn is_valid_id(id: &str) -> bool {
+    let parts: Vec<&str> = id.split('.').collect();
+    parts.len() >= 2
+        && parts.iter().all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+}
+
+/// A URI scheme a plugin may serve: letters, digits, `+`, `-`, and `.`, starting with a letter, and not
+/// `file` or a scheme the IDE serves itself
+pub fn is_valid_scheme(scheme: &str) -> bool {
+    let reserved = ["file", fs_provider::SSH_SCHEME];
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_lowercase())
+        && scheme.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+        && !reserved.contains(&scheme)
+}
+
+/// `major.minor.patch` with an optional pre-release suffix
+pub fn is_valid_version(version: &str) -> bool {
+    let core = version.split_once('-').map_or(version, |(core, _)| core);
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Read and validate the manifest of a plugin directory
+pub fn load_manifest(dir: &Path) -> Result<(PluginManifest, Vec<ActivationEvent>), PluginError> {
+    let path = dir.join(MANIFEST_FILE);
+    let invalid = |message: String| PluginError::InvalidManifest {
+        path: path.to_string_lossy().to_string(),
+        message,
+    };
+
+    let content = fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+    let manifest: PluginManifest = serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+
+    if !is_valid_id(&manifest.id) {
+        return Err(invalid(format!("id '{}' must look like publisher.name", manifest.id)));
+    }
+    if !is_valid_version(&manifest.version) {
+        return Err(invalid(format!("version '{}' must be major.minor.patch", manifest.version)));
+    }
+    if manifest.api_version > HOST_API_VERSION {
+        return Err(invalid(format!(
+            "requires host API {}, this version provides {}",
+            manifest.api_version, HOST_API_VERSION
+        )));
+    }
+    let main = Path::new(&manifest.main);
+    if !main.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(invalid(format!("main '{}' must be inside the plugin directory", manifest.main)));
+    }
+    if !dir.join(main).is_file() {
+        return Err(invalid(format!("main '{}' does not exist", manifest.main)));
+    }
+
+    for contribution in &manifest.contributes.file_systems {
+        if !is_valid_scheme(&contribution.scheme) {
+            return Err(invalid(format!("file system scheme '{}' can't be used", contribution.scheme)));
+        }
+    }
+
+    let activation_events = manifest
+        .activation_events
+        .iter()
+        .map(|event| ActivationEvent::parse(event))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(invalid)?;
+    Ok((manifest, activation_events))
+}
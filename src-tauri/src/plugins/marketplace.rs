@@ -0,0 +1,284 @@
+/**
+ * Extension marketplace
+ * Searches the configured registry and installs verified plugin packages into versioned directories
+ *
+ * Registry API:
+ * - `GET {registry}/extensions?query=...` returns `{ "extensions": [ExtensionSummary] }`
+ * - `GET {registry}/extensions/{id}/{version|latest}` returns an `ExtensionRelease`
+ *
+ * Packages are zip archives with `plugin.json` at their root. Both URLs must be `https://`, and a release is
+ * only installed when its `signature` is the registry key's ed25519 signature of
+ * `codeforge-extension\n{id}\n{version}\n{sha256}`, so neither the package nor what it claims to be can be
+ * swapped, e.g. for an older signed version.
+ */
+
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use crate::atomic_file::write_atomic;
+use super::manifest::{self, is_valid_id, is_valid_version};
+use super::PluginError;
+use crate::clock::now_millis;
+
+/// File inside `plugins/<id>/` naming the installed version directory in use
+pub const ACTIVE_VERSION_FILE: &str = ".active";
+
+/// Largest package that will be downloaded
+const MAX_PACKAGE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Largest total size a package may unpack to
+const MAX_UNPACKED_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Registry listing entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub publisher: String,
+    #[serde(default)]
+    pub description: String,
+    pub latest_version: String,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    /// Version installed locally, if any
+    #[serde(default, skip_deserializing)]
+    pub installed_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    extensions: Vec<ExtensionSummary>,
+}
+
+/// A downloadable version of an extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionRelease {
+    pub id: String,
+    pub version: String,
+    pub download_url: String,
+    /// Hex SHA-256 of the package
+    pub sha256: String,
+    /// Base64 ed25519 signature of the release's `signed_message`
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+impl ExtensionRelease {
+    /// What the registry key signs: the release's identity together with the package checksum
+    fn signed_message(&self) -> String {
+        let sha256 = self.sha256.trim().to_ascii_lowercase();
+        format!("codeforge-extension\n{}\n{}\n{}", self.id, self.version, sha256)
+    }
+}
+
+/// Registry location and the key releases must be signed with; without a key it can only be searched
+pub struct Registry {
+    pub url: String,
+    pub key: Option<String>,
+}
+
+fn download_error(e: impl std::fmt::Display) -> PluginError {
+    PluginError::DownloadFailed(e.to_string())
+}
+
+/// `url` parsed, if it's `https://`
+fn https_url(url: &str) -> Result<reqwest::Url, PluginError> {
+    let url = reqwest::Url::parse(url).map_err(download_error)?;
+    if url.scheme() != "https" {
+        return Err(PluginError::DownloadFailed(format!("{} is not an https URL", url)));
+    }
+    Ok(url)
+}
+
+async fn get_json<T: for<'de> Deserialize<'de>>(url: reqwest::Url) -> Result<T, PluginError> {
+    let response = reqwest::Client::new().get(url).send().await.map_err(download_error)?;
+    let response = response.error_for_status().map_err(download_error)?;
+    let body = response.bytes().await.map_err(download_error)?;
+    serde_json::from_slice(&body).map_err(download_error)
+}
+
+impl Registry {
+    fn endpoint(&self, path: &str) -> Result<reqwest::Url, PluginError> {
+        https_url(&format!("{}/{}", self.url.trim_end_matches('/'), path))
+    }
+
+    pub async fn search(&self, query: &str) -> Result<Vec<ExtensionSummary>, PluginError> {
+        let mut url = self.endpoint("extensions")?;
+        url.query_pairs_mut().append_pair("query", query);
+        let response: SearchResponse = get_json(url).await?;
+        Ok(response.extensions)
+    }
+
+    /// Release metadata for a version, or the newest one when `version` is `None`
+    pub async fn release(&self, id: &str, version: Option<&str>) -> Result<ExtensionRelease, PluginError> {
+        if !is_valid_id(id) {
+            return Err(PluginError::NotFound(id.to_string()));
+        }
+        let version = version.unwrap_or("latest");
+        if version != "latest" && !is_valid_version(version) {
+            return Err(PluginError::DownloadFailed(format!("invalid version '{}'", version)));
+        }
+        let url = self.endpoint(&format!("extensions/{}/{}", id, version))?;
+        let release: ExtensionRelease = get_json(url).await?;
+        if release.id != id || !is_valid_version(&release.version) {
+            return Err(PluginError::DownloadFailed(format!(
+                "registry answered with {}@{} for {}@{}",
+                release.id, release.version, id, version
+            )));
+        }
+        Ok(release)
+    }
+
+    pub async fn download(&self, release: &ExtensionRelease) -> Result<Vec<u8>, PluginError> {
+        let url = https_url(&release.download_url)?;
+        let response = reqwest::Client::new().get(url).send().await.map_err(download_error)?;
+        let mut response = response.error_for_status().map_err(download_error)?;
+        let too_large = || PluginError::DownloadFailed("package is too large".to_string());
+        if response.content_length().is_some_and(|length| length > MAX_PACKAGE_BYTES) {
+            return Err(too_large());
+        }
+        // The declared length may be missing or wrong, so the cap is enforced while reading
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+            if (bytes.len() + chunk.len()) as u64 > MAX_PACKAGE_BYTES {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Check the package against the release checksum, and the release against its signature
+    pub fn verify(&self, release: &ExtensionRelease, package: &[u8]) -> Result<(), PluginError> {
+        let digest: String = Sha256::digest(package).iter().map(|byte| format!("{:02x}", byte)).collect();
+        if !digest.eq_ignore_ascii_case(release.sha256.trim()) {
+            return Err(PluginError::IntegrityCheckFailed(format!(
+                "checksum mismatch: expected {}, got {}",
+                release.sha256, digest
+            )));
+        }
+
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let invalid = |message: &str| PluginError::IntegrityCheckFailed(message.to_string());
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| invalid("extension_registry_key is not set, so the package can't be verified"))?;
+        let key: [u8; 32] = base64
+            .decode(key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("extension_registry_key is not a base64 ed25519 public key"))?;
+        let key =
+            VerifyingKey::from_bytes(&key).map_err(|_| invalid("extension_registry_key is not a valid key"))?;
+        let signature = release.signature.as_deref().ok_or_else(|| invalid("release is not signed"))?;
+        let signature: [u8; 64] = base64
+            .decode(signature.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("malformed release signature"))?;
+        key.verify(release.signed_message().as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| invalid("release signature does not match the registry key"))
+    }
+}
+
+/// Version directory of a versioned install (`plugins/<id>/<version>/`), if `dir` is one
+pub fn active_install_dir(dir: &Path) -> Option<PathBuf> {
+    let version = fs::read_to_string(dir.join(ACTIVE_VERSION_FILE)).ok()?;
+    let version = version.trim();
+    is_valid_version(version).then(|| dir.join(version))
+}
+
+/// Unpack a verified package into `plugins/<id>/<version>/`, make it the active version, and drop
+/// all but the previous version
+pub fn install_package(
+    plugins_dir: &Path,
+    release: &ExtensionRelease,
+    package: &[u8],
+) -> Result<PathBuf, PluginError> {
+    let io_error = |e: std::io::Error| PluginError::IOError(e.to_string());
+    let extension_dir = plugins_dir.join(&release.id);
+    fs::create_dir_all(&extension_dir).map_err(io_error)?;
+
+    let stamp = now_millis();
+    let staging = extension_dir.join(format!(".{}-{}.partial", release.version, stamp));
+    let unpacked = unpack(package, &staging).and_then(|_| {
+        let (manifest, _) = manifest::load_manifest(&staging)?;
+        if manifest.id != release.id || manifest.version != release.version {
+            return Err(PluginError::IntegrityCheckFailed(format!(
+                "package contains {}@{}, expected {}@{}",
+                manifest.id, manifest.version, release.id, release.version
+            )));
+        }
+        Ok(())
+    });
+    if let Err(e) = unpacked {
+        let _ = fs::remove_dir_all(&staging);
+        // Only succeeds when nothing else was ever installed under this id
+        let _ = fs::remove_dir(&extension_dir);
+        return Err(e);
+    }
+
+    let previous = active_install_dir(&extension_dir);
+    let target = extension_dir.join(&release.version);
+    if target.exists() {
+        fs::remove_dir_all(&target).map_err(io_error)?;
+    }
+    fs::rename(&staging, &target).map_err(io_error)?;
+    let active_path = extension_dir.join(ACTIVE_VERSION_FILE);
+    write_atomic(&active_path, &release.version).map_err(io_error)?;
+
+    // Keep the previous version around so a bad update can be rolled back by hand
+    for entry in fs::read_dir(&extension_dir).map_err(io_error)?.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.is_dir() && path != target && Some(&path) != previous.as_ref() {
+            let _ = fs::remove_dir_all(&path);
+        }
+    }
+    Ok(target)
+}
+
+fn unpack(package: &[u8], target: &Path) -> Result<(), PluginError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| PluginError::IntegrityCheckFailed(format!("invalid package: {}", e));
+    let mut archive = zip::ZipArchive::new(Cursor::new(package)).map_err(|e| invalid(&e))?;
+    fs::create_dir_all(target).map_err(|e| PluginError::IOError(e.to_string()))?;
+
+    let mut unpacked_bytes = 0u64;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| invalid(&e))?;
+        let relative = file
+            .enclosed_name()
+            .ok_or_else(|| invalid(&format!("unsafe path {}", file.name())))?;
+        let path = target.join(relative);
+        if file.is_dir() {
+            fs::create_dir_all(&path).map_err(|e| PluginError::IOError(e.to_string()))?;
+            continue;
+        }
+
+        // Declared sizes can lie, so the limit is enforced on the bytes actually read
+        let mut content = Vec::new();
+        (&mut file)
+            .take(MAX_UNPACKED_BYTES - unpacked_bytes + 1)
+            .read_to_end(&mut content)
+            .map_err(|e| invalid(&e))?;
+        unpacked_bytes += content.len() as u64;
+        if unpacked_bytes > MAX_UNPACKED_BYTES {
+            return Err(invalid(&"package unpacks to more than the size limit"));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| PluginError::IOError(e.to_string()))?;
+        }
+        fs::write(&path, content).map_err(|e| PluginError::IOError(e.to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,549 @@
+/**
+ * Plugin Service for CodeForge IDE
+ * Discovers WASM plugins in the app data dir and activates them on the events their manifests declare
+ */
+
+mod file_system;
+mod host;
+mod manifest;
+mod marketplace;
+
+pub use file_system::{PluginFileSystem, PluginProvider};
+pub use host::{PluginMessage, PluginMessageLevel, PLUGIN_LOG_EVENT, PLUGIN_MESSAGE_EVENT};
+pub use manifest::{
+    ActivationEvent, CommandContribution, FileSystemContribution, PluginContributions, PluginManifest,
+    PluginTrigger, HOST_API_VERSION, MANIFEST_FILE,
+};
+pub use marketplace::ExtensionSummary;
+
+use crate::file_system::FileSystemService;
+use crate::notifications::{NotificationService, ProgressHandle};
+use crate::settings::SettingsService;
+use host::WasmPlugin;
+use marketplace::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use wasmtime::{Config, Engine};
+
+/// Event emitted when a plugin is activated or fails to activate
+pub const PLUGIN_STATE_EVENT: &str = "plugin://state";
+
+/// Directory under the app data dir holding one directory per plugin
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// Error types for plugin operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PluginError {
+    NoDataDirectory,
+    IOError(String),
+    InvalidManifest { path: String, message: String },
+    NotFound(String),
+    CommandNotFound(String),
+    FileSystemNotFound(String),
+    LoadFailed(String),
+    Trap(String),
+    NoRegistry,
+    DownloadFailed(String),
+    IntegrityCheckFailed(String),
+    Cancelled,
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PluginError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            PluginError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            PluginError::InvalidManifest { path, message } => {
+                write!(f, "Invalid plugin manifest {}: {}", path, message)
+            }
+            PluginError::NotFound(id) => write!(f, "Plugin not found: {}", id),
+            PluginError::CommandNotFound(command) => write!(f, "No plugin provides command '{}'", command),
+            PluginError::FileSystemNotFound(scheme) => write!(f, "No plugin provides {}:// paths", scheme),
+            PluginError::LoadFailed(msg) => write!(f, "Failed to load plugin: {}", msg),
+            PluginError::Trap(msg) => write!(f, "Plugin error: {}", msg),
+            PluginError::NoRegistry => write!(f, "No extension registry is configured"),
+            PluginError::DownloadFailed(msg) => write!(f, "Extension download failed: {}", msg),
+            PluginError::IntegrityCheckFailed(msg) => write!(f, "Extension verification failed: {}", msg),
+            PluginError::Cancelled => write!(f, "Extension install was cancelled"),
+        }
+    }
+}
+
+/// Lifecycle state of a discovered plugin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PluginState {
+    Inactive,
+    Active,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub manifest: PluginManifest,
+    pub dir: String,
+    pub state: PluginState,
+}
+
+/// Plugin directory that could not be loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginLoadError {
+    pub dir: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginScanResult {
+    pub plugins: Vec<PluginInfo>,
+    pub errors: Vec<PluginLoadError>,
+}
+
+/// A contributed command together with the plugin providing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginCommand {
+    pub plugin_id: String,
+    #[serde(flatten)]
+    pub contribution: CommandContribution,
+}
+
+struct LoadedPlugin {
+    manifest: PluginManifest,
+    dir: PathBuf,
+    activation_events: Vec<ActivationEvent>,
+    state: PluginState,
+    instance: Option<WasmPlugin>,
+}
+
+impl LoadedPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            manifest: self.manifest.clone(),
+            dir: self.dir.to_string_lossy().to_string(),
+            state: self.state.clone(),
+        }
+    }
+
+    fn provides_command(&self, command: &str) -> bool {
+        self.manifest.contributes.commands.iter().any(|contribution| contribution.command == command)
+    }
+
+    fn provides_file_system(&self, scheme: &str) -> bool {
+        self.manifest.contributes.file_systems.iter().any(|contribution| contribution.scheme == scheme)
+    }
+}
+
+pub struct PluginService {
+    engine: Engine,
+    plugins: Arc<Mutex<HashMap<String, LoadedPlugin>>>,
+    workspace: Arc<Mutex<Option<PathBuf>>>,
+    /// Schemes registered with the file system service for plugins
+    schemes: Arc<Mutex<Vec<String>>>,
+}
+
+impl PluginService {
+    pub fn new() -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config).expect("default wasmtime configuration is valid"),
+            plugins: Arc::new(Mutex::new(HashMap::new())),
+            workspace: Arc::new(Mutex::new(None)),
+            schemes: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn plugins_dir(app: &AppHandle) -> Result<PathBuf, PluginError> {
+        let data_dir = app.path().app_data_dir().map_err(|_| PluginError::NoDataDirectory)?;
+        Ok(data_dir.join(PLUGINS_DIR))
+    }
+
+    /// Re-read the plugins directory and activate startup and workspace plugins; plugins whose
+    /// directory and version are unchanged keep running
+    pub fn scan(&self, app: &AppHandle, workspace: Option<PathBuf>) -> Result<PluginScanResult, PluginError> {
+        let dir = Self::plugins_dir(app)?;
+        fs::create_dir_all(&dir).map_err(|e| PluginError::IOError(e.to_string()))?;
+        let entries = fs::read_dir(&dir).map_err(|e| PluginError::IOError(e.to_string()))?;
+
+        let mut errors = Vec::new();
+        let mut discovered = HashMap::new();
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let entry_dir = entry.path();
+            if !entry_dir.is_dir() {
+                continue;
+            }
+            // Marketplace installs live in `<id>/<version>/`; side-loaded plugins sit directly in the directory
+            let plugin_dir = match entry_dir.join(MANIFEST_FILE).exists() {
+                true => entry_dir,
+                false => marketplace::active_install_dir(&entry_dir).unwrap_or(entry_dir),
+            };
+            match manifest::load_manifest(&plugin_dir) {
+                Ok((manifest, activation_events)) => {
+                    if discovered.contains_key(&manifest.id) {
+                        errors.push(PluginLoadError {
+                            dir: plugin_dir.to_string_lossy().to_string(),
+                            message: format!("another plugin already uses the id '{}'", manifest.id),
+                        });
+                        continue;
+                    }
+                    discovered.insert(manifest.id.clone(), (manifest, activation_events, plugin_dir));
+                }
+                Err(e) => errors.push(PluginLoadError {
+                    dir: plugin_dir.to_string_lossy().to_string(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        {
+            let mut plugins = self.plugins.lock().unwrap();
+            let mut previous = std::mem::take(&mut *plugins);
+            for (id, (manifest, activation_events, plugin_dir)) in discovered {
+                let unchanged = |existing: &LoadedPlugin| {
+                    existing.dir == plugin_dir && existing.manifest.version == manifest.version
+                };
+                let plugin = match previous.remove(&id) {
+                    Some(existing) if unchanged(&existing) => existing,
+                    _ => LoadedPlugin {
+                        manifest,
+                        dir: plugin_dir,
+                        activation_events,
+                        state: PluginState::Inactive,
+                        instance: None,
+                    },
+                };
+                plugins.insert(id, plugin);
+            }
+        }
+
+        self.register_file_systems(app);
+        self.set_workspace(workspace.clone());
+        self.trigger(app, PluginTrigger::Startup);
+        if let Some(workspace) = &workspace {
+            self.trigger(app, PluginTrigger::Workspace(workspace));
+        }
+
+        let mut plugins = self.list();
+        plugins.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+        Ok(PluginScanResult { plugins, errors })
+    }
+
+    /// Scan again with the current workspace, e.g. after installing or removing a plugin
+    pub fn rescan(&self, app: &AppHandle) -> Result<PluginScanResult, PluginError> {
+        let workspace = self.workspace.lock().unwrap().clone();
+        self.scan(app, workspace)
+    }
+
+    /// Workspace plugins may read and write files in
+    pub fn set_workspace(&self, workspace: Option<PathBuf>) {
+        *self.workspace.lock().unwrap() = workspace.clone();
+        for plugin in self.plugins.lock().unwrap().values_mut() {
+            if let Some(instance) = plugin.instance.as_mut() {
+                instance.set_workspace(workspace.clone());
+            }
+        }
+    }
+
+    pub fn list(&self) -> Vec<PluginInfo> {
+        let mut plugins: Vec<PluginInfo> =
+            self.plugins.lock().unwrap().values().map(LoadedPlugin::info).collect();
+        plugins.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+        plugins
+    }
+
+    /// Commands contributed by all discovered plugins, active or not
+    pub fn contributed_commands(&self) -> Vec<PluginCommand> {
+        let plugins = self.plugins.lock().unwrap();
+        let mut commands: Vec<PluginCommand> = plugins
+            .values()
+            .flat_map(|plugin| {
+                plugin.manifest.contributes.commands.iter().map(|contribution| PluginCommand {
+                    plugin_id: plugin.manifest.id.clone(),
+                    contribution: contribution.clone(),
+                })
+            })
+            .collect();
+        commands.sort_by(|a, b| a.contribution.command.cmp(&b.contribution.command));
+        commands
+    }
+
+    /// File systems contributed by all discovered plugins, active or not
+    pub fn contributed_file_systems(&self) -> Vec<PluginFileSystem> {
+        let plugins = self.plugins.lock().unwrap();
+        let mut file_systems: Vec<PluginFileSystem> = plugins
+            .values()
+            .flat_map(|plugin| {
+                plugin.manifest.contributes.file_systems.iter().map(|contribution| PluginFileSystem {
+                    plugin_id: plugin.manifest.id.clone(),
+                    contribution: contribution.clone(),
+                })
+            })
+            .collect();
+        file_systems.sort_by(|a, b| {
+            (&a.contribution.scheme, &a.plugin_id).cmp(&(&b.contribution.scheme, &b.plugin_id))
+        });
+        file_systems
+    }
+
+    /// Route the schemes of contributed file systems to their plugins, and stop routing schemes no plugin
+    /// contributes anymore. When plugins claim the same scheme, the first by id gets it.
+    fn register_file_systems(&self, app: &AppHandle) {
+        let fs = app.state::<FileSystemService>();
+        let contributed = self.contributed_file_systems();
+        let mut schemes = self.schemes.lock().unwrap();
+        for scheme in schemes.drain(..) {
+            if !contributed.iter().any(|file_system| file_system.contribution.scheme == scheme) {
+                fs.unregister_scheme(&scheme);
+            }
+        }
+        for file_system in contributed {
+            let scheme = &file_system.contribution.scheme;
+            if schemes.contains(scheme) {
+                tracing::warn!(
+                    plugin = %file_system.plugin_id,
+                    scheme = %scheme,
+                    "file system scheme already taken"
+                );
+                continue;
+            }
+            fs.register_scheme(scheme, PluginProvider::opener(app.clone(), &file_system.contribution));
+            schemes.push(scheme.clone());
+        }
+    }
+
+    /// Pass a file system request to the plugin serving `scheme`, activating it first when needed
+    pub fn file_system_request(
+        &self,
+        app: &AppHandle,
+        scheme: &str,
+        request: &str,
+    ) -> Result<Option<String>, PluginError> {
+        self.trigger(app, PluginTrigger::FileSystem(scheme));
+        let mut plugins = self.plugins.lock().unwrap();
+        let mut providing: Vec<&mut LoadedPlugin> =
+            plugins.values_mut().filter(|plugin| plugin.provides_file_system(scheme)).collect();
+        providing.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+        let plugin = providing
+            .into_iter()
+            .next()
+            .ok_or_else(|| PluginError::FileSystemNotFound(scheme.to_string()))?;
+        self.activate_loaded(app, plugin)?;
+        match plugin.instance.as_mut() {
+            Some(instance) => instance.on_file_system_request(request),
+            None => Err(PluginError::FileSystemNotFound(scheme.to_string())),
+        }
+    }
+
+    /// Load and activate a plugin if it isn't already; a plugin that failed stays failed until rescanned
+    fn activate_loaded(&self, app: &AppHandle, plugin: &mut LoadedPlugin) -> Result<(), PluginError> {
+        match &plugin.state {
+            PluginState::Active => return Ok(()),
+            PluginState::Failed(message) => return Err(PluginError::LoadFailed(message.clone())),
+            PluginState::Inactive => {}
+        }
+
+        let workspace = self.workspace.lock().unwrap().clone();
+        let result = WasmPlugin::load(
+            &self.engine,
+            app,
+            &plugin.manifest.id,
+            &plugin.dir.join(&plugin.manifest.main),
+            workspace,
+        )
+        .and_then(|mut instance| instance.activate().map(|_| instance));
+        match result {
+            Ok(instance) => {
+                plugin.instance = Some(instance);
+                plugin.state = PluginState::Active;
+            }
+            Err(e) => plugin.state = PluginState::Failed(e.to_string()),
+        }
+        let _ = app.emit(PLUGIN_STATE_EVENT, plugin.info());
+        match &plugin.state {
+            PluginState::Failed(message) => Err(PluginError::LoadFailed(message.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn activate(&self, app: &AppHandle, id: &str) -> Result<PluginInfo, PluginError> {
+        let mut plugins = self.plugins.lock().unwrap();
+        let plugin = plugins.get_mut(id).ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+        self.activate_loaded(app, plugin)?;
+        Ok(plugin.info())
+    }
+
+    /// Activate every plugin with a matching activation event; failures are reported through
+    /// `plugin://state` rather than failing the triggering action
+    pub fn trigger(&self, app: &AppHandle, trigger: PluginTrigger) {
+        let mut plugins = self.plugins.lock().unwrap();
+        for plugin in plugins.values_mut() {
+            let matches = plugin.activation_events.iter().any(|event| event.matches(trigger));
+            if plugin.state == PluginState::Inactive && matches {
+                if let Err(e) = self.activate_loaded(app, plugin) {
+                    tracing::warn!(plugin = %plugin.manifest.id, error = %e, "plugin activation failed");
+                }
+            }
+        }
+    }
+
+    /// Backend hook for a file being opened in an editor
+    pub fn file_opened(&self, app: &AppHandle, path: &str, language: Option<&str>) {
+        self.trigger(app, PluginTrigger::FileOpen { path, language });
+        let mut plugins = self.plugins.lock().unwrap();
+        for plugin in plugins.values_mut() {
+            let Some(instance) = plugin.instance.as_mut() else {
+                continue;
+            };
+            if let Err(e) = instance.on_file_open(path) {
+                let _ = app.emit(
+                    PLUGIN_LOG_EVENT,
+                    PluginMessage {
+                        plugin_id: plugin.manifest.id.clone(),
+                        level: PluginMessageLevel::Error,
+                        message: e.to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Run a contributed command, activating its plugin first when needed; when several plugins
+    /// contribute the same command, the one with the lowest id runs it
+    pub fn execute_command(
+        &self,
+        app: &AppHandle,
+        command: &str,
+        args: &str,
+    ) -> Result<Option<String>, PluginError> {
+        self.trigger(app, PluginTrigger::Command(command));
+        let mut plugins = self.plugins.lock().unwrap();
+        let mut providing: Vec<&mut LoadedPlugin> =
+            plugins.values_mut().filter(|plugin| plugin.provides_command(command)).collect();
+        providing.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+        let plugin = providing
+            .into_iter()
+            .next()
+            .ok_or_else(|| PluginError::CommandNotFound(command.to_string()))?;
+        self.activate_loaded(app, plugin)?;
+        match plugin.instance.as_mut() {
+            Some(instance) => instance.on_command(command, args),
+            None => Err(PluginError::CommandNotFound(command.to_string())),
+        }
+    }
+}
+
+/// Registry from the `extension_registry_url` / `extension_registry_key` preferences
+fn registry(app: &AppHandle) -> Result<Registry, PluginError> {
+    let preferences = app
+        .state::<SettingsService>()
+        .get_preferences(app)
+        .map_err(|e| PluginError::IOError(e.to_string()))?;
+    if preferences.extension_registry_url.is_empty() {
+        return Err(PluginError::NoRegistry);
+    }
+    let key = Some(preferences.extension_registry_key).filter(|key| !key.trim().is_empty());
+    Ok(Registry {
+        url: preferences.extension_registry_url,
+        key,
+    })
+}
+
+impl PluginService {
+    /// Search the registry, marking extensions that are already installed
+    pub async fn search_extensions(app: &AppHandle, query: &str) -> Result<Vec<ExtensionSummary>, PluginError> {
+        let mut extensions = registry(app)?.search(query).await?;
+        let installed = app.state::<PluginService>().list();
+        for extension in &mut extensions {
+            extension.installed_version = installed
+                .iter()
+                .find(|plugin| plugin.manifest.id == extension.id)
+                .map(|plugin| plugin.manifest.version.clone());
+        }
+        Ok(extensions)
+    }
+
+    /// Download, verify, and unpack an extension (the latest version when `version` is `None`), then load it.
+    /// Progress is reported as a notification, which can cancel the install until unpacking starts.
+    pub async fn install_extension(
+        app: &AppHandle,
+        id: &str,
+        version: Option<&str>,
+    ) -> Result<PluginInfo, PluginError> {
+        let title = format!("Installing {}", id);
+        let notification = app.state::<NotificationService>().start_progress(app, "extensions", &title, true);
+        let result = Self::run_install(app, id, version, &notification).await;
+        match &result {
+            Ok(plugin) => notification.complete(Some(&format!("Installed version {}", plugin.manifest.version))),
+            Err(e) => notification.fail(&e.to_string()),
+        }
+        result
+    }
+
+    async fn run_install(
+        app: &AppHandle,
+        id: &str,
+        version: Option<&str>,
+        notification: &ProgressHandle,
+    ) -> Result<PluginInfo, PluginError> {
+        let check_cancelled = || match notification.is_cancelled() {
+            true => Err(PluginError::Cancelled),
+            false => Ok(()),
+        };
+        let registry = registry(app)?;
+        notification.report(None, Some("Resolving version"));
+        let release = registry.release(id, version).await?;
+        check_cancelled()?;
+        notification.report(Some(10), Some(&format!("Downloading {}", release.version)));
+        let package = registry.download(&release).await?;
+        check_cancelled()?;
+        notification.report(Some(70), Some("Verifying package"));
+        registry.verify(&release, &package)?;
+        check_cancelled()?;
+        notification.report(Some(80), Some("Unpacking"));
+
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let plugins = app.state::<PluginService>();
+            marketplace::install_package(&Self::plugins_dir(&app)?, &release, &package)?;
+            plugins.rescan(&app)?;
+            plugins
+                .list()
+                .into_iter()
+                .find(|plugin| plugin.manifest.id == release.id)
+                .ok_or_else(|| PluginError::NotFound(release.id.clone()))
+        })
+        .await
+        .map_err(|e| PluginError::LoadFailed(e.to_string()))?
+    }
+
+    /// Unload a plugin and delete it from disk, including every installed version
+    pub fn uninstall(&self, app: &AppHandle, id: &str) -> Result<(), PluginError> {
+        let plugins_dir = Self::plugins_dir(app)?;
+        let plugin = self
+            .plugins
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| PluginError::NotFound(id.to_string()))?;
+        let dir: &Path = match plugin.dir.parent() {
+            Some(parent) if parent != plugins_dir => parent,
+            _ => &plugin.dir,
+        };
+        if dir.starts_with(&plugins_dir) && dir != plugins_dir {
+            fs::remove_dir_all(dir).map_err(|e| PluginError::IOError(e.to_string()))?;
+        }
+        self.register_file_systems(app);
+        let mut info = plugin.info();
+        info.state = PluginState::Inactive;
+        let _ = app.emit(PLUGIN_STATE_EVENT, info);
+        Ok(())
+    }
+}
+
+impl Default for PluginService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
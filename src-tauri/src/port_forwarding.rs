@@ -0,0 +1,446 @@
+/**
+ * Ports of dev servers, and forwarding them
+ * Traces listening ports to the terminal or task whose processes opened them, keeps the labels users give
+ * ports, and forwards local ports to ports on the machine a workspace is on: through `ssh -W` for `ssh://`
+ * workspaces, through `docker exec` for containers, and directly for workspaces on this machine.
+ *
+ * Forwarded ports listen on 127.0.0.1 only, so they aren't shared with the network.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::file_system::FileSystemService;
+use crate::fs_provider::{FileSystemProvider, RemoteUri};
+use crate::ports::{self, ListeningPort};
+use crate::terminal::{SessionProcess, TerminalService};
+
+/// Event emitted when a terminal or task starts listening on a port
+pub const PORT_OPENED_EVENT: &str = "ports://opened";
+
+/// How often ports are scanned while terminals or tasks run
+const DETECTION_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often a forward checks for new connections and for being stopped
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How far up the process tree a port's owner is traced to a session
+const MAX_ANCESTORS: usize = 64;
+
+/// Bytes relayed per read; small writes go out as soon as they arrive, which interactive protocols need
+const RELAY_BUFFER_SIZE: usize = 16 * 1024;
+
+/// What forwarded connections reach on the other machine
+const FORWARD_HOST: &str = "localhost";
+
+/// Error types for port operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PortError {
+    Scan(String),
+    ForwardNotFound(String),
+    Bind { port: u16, message: String },
+    Workspace(String),
+    Browser(String),
+}
+
+impl std::fmt::Display for PortError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PortError::Scan(msg) => write!(f, "Scanning ports failed: {}", msg),
+            PortError::ForwardNotFound(id) => write!(f, "Port forward not found: {}", id),
+            PortError::Bind { port, message } => write!(f, "Can't listen on port {}: {}", port, message),
+            PortError::Workspace(msg) => write!(f, "Can't forward from the workspace: {}", msg),
+            PortError::Browser(msg) => write!(f, "Can't open the browser: {}", msg),
+        }
+    }
+}
+
+/// A listening port, with the session that opened it and the user's label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedPort {
+    #[serde(flatten)]
+    pub listener: ListeningPort,
+    /// Terminal session whose process, or one of its descendants, listens on the port
+    pub session_id: Option<String>,
+    /// What that session runs, e.g. a task's label
+    pub session_title: Option<String>,
+    pub label: Option<String>,
+    /// The forward listening on the port, when it's the local end of one
+    pub forward_id: Option<String>,
+}
+
+/// A local port forwarded to a port on a workspace's machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortForward {
+    pub id: String,
+    /// Workspace whose machine connections go to; this machine when `None`
+    pub workspace: Option<String>,
+    pub remote_host: String,
+    pub remote_port: u16,
+    pub local_port: u16,
+    pub label: Option<String>,
+    /// Where the forwarded port is reached locally
+    pub url: String,
+    /// Connections open right now
+    pub connections: usize,
+}
+
+/// Where a forward sends its connections
+#[derive(Clone)]
+enum ForwardTarget {
+    Direct { host: String, port: u16 },
+    /// Through processes the provider starts on its machine
+    Tunnel {
+        provider: Arc<dyn FileSystemProvider>,
+        host: String,
+        port: u16,
+    },
+}
+
+struct Forward {
+    forward: PortForward,
+    stopped: Arc<AtomicBool>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl Forward {
+    fn snapshot(&self, labels: &HashMap<u16, String>) -> PortForward {
+        PortForward {
+            label: labels.get(&self.forward.local_port).cloned(),
+            connections: self.connections.load(Ordering::SeqCst),
+            ..self.forward.clone()
+        }
+    }
+}
+
+pub struct PortService {
+    /// Labels by local port
+    labels: Arc<Mutex<HashMap<u16, String>>>,
+    forwards: Arc<Mutex<HashMap<String, Forward>>>,
+    next_forward_id: AtomicU64,
+    detecting: AtomicBool,
+}
+
+impl PortService {
+    pub fn new() -> Self {
+        Self {
+            labels: Arc::new(Mutex::new(HashMap::new())),
+            forwards: Arc::new(Mutex::new(HashMap::new())),
+            next_forward_id: AtomicU64::new(1),
+            detecting: AtomicBool::new(false),
+        }
+    }
+
+    /// Listening ports, with the sessions that opened them
+    pub fn list_ports(&self, terminal: &TerminalService) -> Result<Vec<DetectedPort>, PortError> {
+        let listeners = ports::list_listening_ports().map_err(PortError::Scan)?;
+        let sessions = terminal.session_processes();
+        let owners = session_owners(&sessions, listeners.iter().filter_map(|listener| listener.pid));
+        let labels = self.labels.lock().unwrap();
+        let forwards = self.forwards.lock().unwrap();
+        Ok(listeners
+            .into_iter()
+            .map(|listener| {
+                let session = listener.pid.and_then(|pid| owners.get(&pid));
+                DetectedPort {
+                    session_id: session.map(|session| session.session_id.clone()),
+                    session_title: session.and_then(|session| session.title.clone()),
+                    label: labels.get(&listener.port).cloned(),
+                    forward_id: forwards
+                        .values()
+                        .find(|forward| forward.forward.local_port == listener.port)
+                        .map(|forward| forward.forward.id.clone()),
+                    listener,
+                }
+            })
+            .collect())
+    }
+
+    /// Name a port, e.g. "API server", or clear its name with `None`
+    pub fn set_label(&self, port: u16, label: Option<String>) {
+        let mut labels = self.labels.lock().unwrap();
+        match label.filter(|label| !label.trim().is_empty()) {
+            Some(label) => labels.insert(port, label),
+            None => labels.remove(&port),
+        };
+    }
+
+    /// Forward a local port to `remote_port` on the machine of `workspace`, or of this machine without one.
+    /// Without `local_port` the remote port's number is used when it's free, and any free port otherwise.
+    pub fn forward(
+        &self,
+        fs: &FileSystemService,
+        workspace: Option<&str>,
+        remote_port: u16,
+        local_port: Option<u16>,
+        label: Option<String>,
+    ) -> Result<PortForward, PortError> {
+        let remote = workspace.is_some_and(|workspace| RemoteUri::parse(workspace).is_some());
+        let target = match workspace {
+            Some(workspace) if remote => {
+                let (provider, _) = fs.provider(workspace).map_err(|e| PortError::Workspace(e.to_string()))?;
+                // Fails for providers that can't run processes, before anything listens
+                provider.tunnel(FORWARD_HOST, remote_port).map_err(|e| PortError::Workspace(e.to_string()))?;
+                ForwardTarget::Tunnel {
+                    provider,
+                    host: FORWARD_HOST.to_string(),
+                    port: remote_port,
+                }
+            }
+            _ => ForwardTarget::Direct {
+                host: FORWARD_HOST.to_string(),
+                port: remote_port,
+            },
+        };
+
+        let listener = match local_port {
+            Some(port) => bind(port)?,
+            None => bind(remote_port).or_else(|_| bind(0))?,
+        };
+        let local_port = listener.local_addr().map_err(|e| PortError::Bind {
+            port: local_port.unwrap_or(0),
+            message: e.to_string(),
+        })?;
+        let local_port = local_port.port();
+        if let Some(label) = label {
+            self.set_label(local_port, Some(label));
+        }
+
+        let id = format!("forward-{}", self.next_forward_id.fetch_add(1, Ordering::SeqCst));
+        let forward = Forward {
+            forward: PortForward {
+                id: id.clone(),
+                workspace: workspace.map(str::to_string),
+                remote_host: FORWARD_HOST.to_string(),
+                remote_port,
+                local_port,
+                label: None,
+                url: browser_url(local_port),
+                connections: 0,
+            },
+            stopped: Arc::new(AtomicBool::new(false)),
+            connections: Arc::new(AtomicUsize::new(0)),
+        };
+        let (stopped, connections) = (forward.stopped.clone(), forward.connections.clone());
+        thread::spawn(move || serve(listener, target, stopped, connections));
+
+        let snapshot = forward.snapshot(&self.labels.lock().unwrap());
+        self.forwards.lock().unwrap().insert(id, forward);
+        Ok(snapshot)
+    }
+
+    /// Stop listening on a forward's local port; open connections finish on their own
+    pub fn stop_forward(&self, id: &str) -> Result<(), PortError> {
+        let mut forwards = self.forwards.lock().unwrap();
+        let forward = forwards.remove(id).ok_or_else(|| PortError::ForwardNotFound(id.to_string()))?;
+        forward.stopped.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Forwards, by local port
+    pub fn list_forwards(&self) -> Vec<PortForward> {
+        let labels = self.labels.lock().unwrap();
+        let mut forwards: Vec<PortForward> =
+            self.forwards.lock().unwrap().values().map(|forward| forward.snapshot(&labels)).collect();
+        forwards.sort_by_key(|forward| forward.local_port);
+        forwards
+    }
+
+    /// Open `http://localhost:<port>` in the default browser. A port forwarded from another machine opens
+    /// at its local end.
+    pub fn open_in_browser(&self, app: &AppHandle, port: u16) -> Result<String, PortError> {
+        let local_port = {
+            let forwards = self.forwards.lock().unwrap();
+            let local = forwards.values().any(|forward| forward.forward.local_port == port);
+            let forwarded = forwards.values().find(|forward| forward.forward.remote_port == port);
+            match forwarded {
+                Some(forward) if !local => forward.forward.local_port,
+                _ => port,
+            }
+        };
+        let url = browser_url(local_port);
+        app.opener().open_url(&url, None::<&str>).map_err(|e| PortError::Browser(e.to_string()))?;
+        Ok(url)
+    }
+
+    /// Watch for ports opened by terminals and tasks in the background, emitting `ports://opened` for each
+    /// new one. Scans only happen while some session has a process.
+    pub fn start_detection(&self, app: &AppHandle) {
+        if self.detecting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app = app.clone();
+        thread::spawn(move || {
+            let mut known: HashSet<(u16, Option<u32>)> = HashSet::new();
+            loop {
+                thread::sleep(DETECTION_INTERVAL);
+                let terminal = app.state::<TerminalService>();
+                if terminal.session_processes().is_empty() {
+                    known.clear();
+                    continue;
+                }
+                let detected = match app.state::<PortService>().list_ports(&terminal) {
+                    Ok(detected) => detected,
+                    Err(e) => {
+                        tracing::debug!(error = %e, "port detection failed");
+                        continue;
+                    }
+                };
+                let opened: Vec<DetectedPort> = detected
+                    .into_iter()
+                    .filter(|port| port.session_id.is_some() && port.forward_id.is_none())
+                    .collect();
+                let current: HashSet<(u16, Option<u32>)> =
+                    opened.iter().map(|port| (port.listener.port, port.listener.pid)).collect();
+                for port in opened {
+                    if !known.contains(&(port.listener.port, port.listener.pid)) {
+                        let _ = app.emit(PORT_OPENED_EVENT, port);
+                    }
+                }
+                known = current;
+            }
+        });
+    }
+}
+
+impl Default for PortService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn browser_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+fn bind(port: u16) -> Result<TcpListener, PortError> {
+    TcpListener::bind(("127.0.0.1", port)).map_err(|e| PortError::Bind {
+        port,
+        message: e.to_string(),
+    })
+}
+
+/// The session each of `pids` belongs to, by the nearest ancestor that is a session's process
+fn session_owners(sessions: &[SessionProcess], pids: impl Iterator<Item = u32>) -> HashMap<u32, SessionProcess> {
+    let by_pid: HashMap<u32, &SessionProcess> = sessions.iter().map(|session| (session.pid, session)).collect();
+    if by_pid.is_empty() {
+        return HashMap::new();
+    }
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    let mut owners = HashMap::new();
+    for pid in pids {
+        let mut current = Some(Pid::from_u32(pid));
+        for _ in 0..MAX_ANCESTORS {
+            let Some(ancestor) = current else {
+                break;
+            };
+            if let Some(session) = by_pid.get(&ancestor.as_u32()) {
+                owners.insert(pid, (*session).clone());
+                break;
+            }
+            current = system.process(ancestor).and_then(|process| process.parent());
+        }
+    }
+    owners
+}
+
+/// Accept connections until stopped, passing each to the target
+fn serve(listener: TcpListener, target: ForwardTarget, stopped: Arc<AtomicBool>, connections: Arc<AtomicUsize>) {
+    if let Err(e) = listener.set_nonblocking(true) {
+        tracing::warn!(error = %e, "port forward can't accept connections");
+        return;
+    }
+    while !stopped.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((client, _)) => {
+                let (target, connections) = (target.clone(), connections.clone());
+                thread::spawn(move || {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    if let Err(e) = connect(client, &target) {
+                        tracing::debug!(error = %e, "forwarded connection failed");
+                    }
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_INTERVAL),
+            Err(e) => {
+                tracing::warn!(error = %e, "port forward stopped accepting connections");
+                return;
+            }
+        }
+    }
+}
+
+/// Relay one connection to the target until either side closes it
+fn connect(client: TcpStream, target: &ForwardTarget) -> io::Result<()> {
+    client.set_nonblocking(false)?;
+    match target {
+        ForwardTarget::Direct { host, port } => {
+            let upstream = TcpStream::connect((host.as_str(), *port))?;
+            let (client_reader, upstream_reader) = (client.try_clone()?, upstream.try_clone()?);
+            let sending = thread::spawn(move || relay(client_reader, upstream, |upstream| {
+                let _ = upstream.shutdown(Shutdown::Write);
+            }));
+            relay(upstream_reader, client, |client| {
+                let _ = client.shutdown(Shutdown::Write);
+            });
+            let _ = sending.join();
+        }
+        ForwardTarget::Tunnel { provider, host, port } => {
+            let mut child = provider
+                .tunnel(host, *port)
+                .map_err(|e| io::Error::other(e.to_string()))?
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()?;
+            let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+                (Some(stdin), Some(stdout)) => (stdin, stdout),
+                _ => return finish(child),
+            };
+            let client_reader = client.try_clone()?;
+            // Closing the tunnel's input tells it the client is done
+            let sending = thread::spawn(move || relay(client_reader, stdin, drop));
+            relay(stdout, client, |client| {
+                let _ = client.shutdown(Shutdown::Both);
+            });
+            let _ = sending.join();
+            return finish(child);
+        }
+    }
+    Ok(())
+}
+
+/// Copy everything from `from` to `to`, then let `close` end `to`
+fn relay<R: Read, W: Write>(mut from: R, mut to: W, close: impl FnOnce(W)) {
+    let mut buffer = [0u8; RELAY_BUFFER_SIZE];
+    loop {
+        match from.read(&mut buffer) {
+            Ok(0) => break,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+            Ok(read) => {
+                if to.write_all(&buffer[..read]).and_then(|_| to.flush()).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    close(to);
+}
+
+fn finish(mut child: Child) -> io::Result<()> {
+    let _ = child.kill();
+    child.wait().map(|_| ())
+}
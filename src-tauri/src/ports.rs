@@ -0,0 +1,253 @@
+/**
+ * Listening port scanner for the Ports panel
+ * Lists TCP listeners with their owning processes (where the OS lets us see them)
+ */
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// A TCP socket in the LISTEN state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListeningPort {
+    pub port: u16,
+    pub address: String,
+    pub protocol: String,
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+    pub url: String,
+}
+
+fn listening_port(
+    port: u16,
+    address: String,
+    protocol: &str,
+    pid: Option<u32>,
+    process_name: Option<String>,
+) -> ListeningPort {
+    ListeningPort {
+        url: format!("http://localhost:{}", port),
+        port,
+        address,
+        protocol: protocol.to_string(),
+        pid,
+        process_name,
+    }
+}
+
+/// List listening TCP ports, sorted by port number
+pub fn list_listening_ports() -> Result<Vec<ListeningPort>, String> {
+    let mut ports = platform::scan()?;
+    ports.sort_by(|a, b| a.port.cmp(&b.port).then(a.protocol.cmp(&b.protocol)));
+    // IPv4 and IPv6 listeners of the same process are one service for the user
+    ports.dedup_by(|a, b| a.port == b.port && a.pid == b.pid);
+    Ok(ports)
+}
+
+/// Terminate the process owning a port
+pub fn kill_process(pid: u32) -> Result<(), String> {
+    let status = if cfg!(windows) {
+        Command::new("taskkill").args(["/PID", &pid.to_string(), "/F"]).status()
+    } else {
+        Command::new("kill").args(["-TERM", &pid.to_string()]).status()
+    }
+    .map_err(|e| e.to_string())?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to terminate process {}", pid))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{listening_port, ListeningPort};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    /// TCP state code for LISTEN in /proc/net/tcp
+    const TCP_LISTEN: &str = "0A";
+
+    pub fn scan() -> Result<Vec<ListeningPort>, String> {
+        let owners = socket_owners();
+        let mut ports = Vec::new();
+        for (file, protocol) in [("/proc/net/tcp", "tcp"), ("/proc/net/tcp6", "tcp6")] {
+            let Ok(table) = fs::read_to_string(file) else {
+                continue;
+            };
+            for line in table.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 10 || fields[3] != TCP_LISTEN {
+                    continue;
+                }
+                let Some((address, port)) = parse_address(fields[1]) else {
+                    continue;
+                };
+                let owner = fields[9].parse::<u64>().ok().and_then(|inode| owners.get(&inode));
+                ports.push(listening_port(
+                    port,
+                    address,
+                    protocol,
+                    owner.map(|(pid, _)| *pid),
+                    owner.map(|(_, name)| name.clone()),
+                ));
+            }
+        }
+        Ok(ports)
+    }
+
+    /// Decode a `HEXADDR:HEXPORT` pair from /proc/net/tcp{,6}
+    fn parse_address(field: &str) -> Option<(String, u16)> {
+        let (address, port) = field.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+        let address = match address.len() {
+            8 => Ipv4Addr::from(u32::from_str_radix(address, 16).ok()?.swap_bytes()).to_string(),
+            32 => {
+                // Four little-endian 32-bit words
+                let mut bytes = [0u8; 16];
+                for (word_index, chunk) in address.as_bytes().chunks(8).enumerate() {
+                    let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+                    bytes[word_index * 4..word_index * 4 + 4].copy_from_slice(&word.to_le_bytes());
+                }
+                Ipv6Addr::from(bytes).to_string()
+            }
+            _ => return None,
+        };
+        Some((address, port))
+    }
+
+    /// Map socket inodes to the owning process; processes of other users are skipped silently
+    fn socket_owners() -> HashMap<u64, (u32, String)> {
+        let mut owners = HashMap::new();
+        let Ok(processes) = fs::read_dir("/proc") else {
+            return owners;
+        };
+
+        for process in processes.flatten() {
+            let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(descriptors) = fs::read_dir(process.path().join("fd")) else {
+                continue;
+            };
+            let name = fs::read_to_string(process.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_default();
+
+            for descriptor in descriptors.flatten() {
+                let Ok(target) = fs::read_link(descriptor.path()) else {
+                    continue;
+                };
+                let target = target.to_string_lossy();
+                if let Some(inode) = target
+                    .strip_prefix("socket:[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|inode| inode.parse::<u64>().ok())
+                {
+                    owners.insert(inode, (pid, name.clone()));
+                }
+            }
+        }
+        owners
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{listening_port, ListeningPort};
+    use std::process::Command;
+
+    pub fn scan() -> Result<Vec<ListeningPort>, String> {
+        let output = Command::new("lsof")
+            .args(["-nP", "-iTCP", "-sTCP:LISTEN"])
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        let mut ports = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines().skip(1) {
+            // COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME (LISTEN)
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 9 {
+                continue;
+            }
+            let Some((address, port)) = fields[8].rsplit_once(':') else {
+                continue;
+            };
+            let Ok(port) = port.parse() else {
+                continue;
+            };
+            let protocol = if fields[4] == "IPv6" { "tcp6" } else { "tcp" };
+            ports.push(listening_port(
+                port,
+                address.trim_matches(['[', ']']).to_string(),
+                protocol,
+                fields[1].parse().ok(),
+                Some(fields[0].to_string()),
+            ));
+        }
+        Ok(ports)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{listening_port, ListeningPort};
+    use std::collections::HashMap;
+    use std::process::Command;
+
+    pub fn scan() -> Result<Vec<ListeningPort>, String> {
+        let output = Command::new("netstat")
+            .args(["-ano", "-p", "TCP"])
+            .output()
+            .map_err(|e| e.to_string())?;
+        let names = process_names();
+
+        let mut ports = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            // Proto Local-Address Foreign-Address State PID
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 || fields[0] != "TCP" || fields[3] != "LISTENING" {
+                continue;
+            }
+            let Some((address, port)) = fields[1].rsplit_once(':') else {
+                continue;
+            };
+            let Ok(port) = port.parse() else {
+                continue;
+            };
+            let pid: Option<u32> = fields[4].parse().ok();
+            let protocol = if address.starts_with('[') { "tcp6" } else { "tcp" };
+            ports.push(listening_port(
+                port,
+                address.trim_matches(['[', ']']).to_string(),
+                protocol,
+                pid,
+                pid.and_then(|pid| names.get(&pid).cloned()),
+            ));
+        }
+        Ok(ports)
+    }
+
+    fn process_names() -> HashMap<u32, String> {
+        let Ok(output) = Command::new("tasklist").args(["/FO", "CSV", "/NH"]).output() else {
+            return HashMap::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split("\",\"").map(|f| f.trim_matches('"')).collect();
+                Some((fields.get(1)?.parse().ok()?, fields.first()?.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::ListeningPort;
+
+    pub fn scan() -> Result<Vec<ListeningPort>, String> {
+        Err("Port scanning is not supported on this platform".to_string())
+    }
+}
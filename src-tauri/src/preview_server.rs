@@ -0,0 +1,492 @@
+/**
+ * Static preview server
+ * Serves a workspace directory on localhost so HTML pages and simple web projects can be viewed in a
+ * browser or the preview pane without installing a dev server. Directories serve their `index.html`, or a
+ * listing without one.
+ *
+ * HTML pages get a small script that reloads them when anything under the root changes, which a watcher
+ * on the root reports to the pages over server-sent events. Files are read fresh on every request and
+ * sent with `Cache-Control: no-store`, so a reload always shows what's on disk.
+ *
+ * The server listens on 127.0.0.1 only, and never serves files outside its root, even through symlinks.
+ * Requests must name it as `localhost` or `127.0.0.1` in their Host header, so a page whose domain was
+ * rebound to 127.0.0.1 can't read the workspace.
+ */
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::file_type::mime_from_extension;
+
+/// Path of the server-sent event stream pages listen to for reloads
+const LIVE_RELOAD_PATH: &str = "/__codeforge/live-reload";
+
+/// Added to HTML pages, before `</body>` when they have one
+const LIVE_RELOAD_SCRIPT: &str =
+    "<script>new EventSource(\"/__codeforge/live-reload\").onmessage = () => location.reload();</script>";
+
+/// Quiet period after the last change before pages reload, so a save of many files reloads once
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How often the server checks for new connections and for being stopped
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest request head read before the connection is dropped
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a write to a client may block, so a page that stopped reading can't stall the server
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error types for the preview server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PreviewError {
+    InvalidRoot(String),
+    Bind { port: u16, message: String },
+    Watch(String),
+    NotRunning(u16),
+}
+
+impl std::fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PreviewError::InvalidRoot(msg) => write!(f, "Can't serve the directory: {}", msg),
+            PreviewError::Bind { port, message } => write!(f, "Can't listen on port {}: {}", port, message),
+            PreviewError::Watch(msg) => write!(f, "Can't watch for changes: {}", msg),
+            PreviewError::NotRunning(port) => write!(f, "No preview server on port {}", port),
+        }
+    }
+}
+
+/// A running preview server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewServerInfo {
+    pub root: String,
+    pub port: u16,
+    pub url: String,
+}
+
+enum ReloadMessage {
+    Changed,
+    Stop,
+}
+
+struct PreviewServer {
+    info: PreviewServerInfo,
+    stopped: Arc<AtomicBool>,
+    control: Sender<ReloadMessage>,
+    _watcher: RecommendedWatcher,
+}
+
+pub struct PreviewServerService {
+    /// By port
+    servers: Mutex<HashMap<u16, PreviewServer>>,
+}
+
+impl PreviewServerService {
+    pub fn new() -> Self {
+        Self {
+            servers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serve `root` on `port`, or on any free port when it's 0 or `None`. A root that's already served
+    /// keeps its server, on whichever port that has.
+    pub fn start(&self, root: &Path, port: Option<u16>) -> Result<PreviewServerInfo, PreviewError> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| PreviewError::InvalidRoot(format!("{}: {}", root.display(), e)))?;
+        if !root.is_dir() {
+            return Err(PreviewError::InvalidRoot(format!("{} isn't a directory", root.display())));
+        }
+        let root_text = root.to_string_lossy().to_string();
+        let mut servers = self.servers.lock().unwrap();
+        if let Some(server) = servers.values().find(|server| server.info.root == root_text) {
+            return Ok(server.info.clone());
+        }
+
+        let requested = port.unwrap_or(0);
+        let listener = TcpListener::bind(("127.0.0.1", requested))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|e| PreviewError::Bind {
+                port: requested,
+                message: e.to_string(),
+            })?;
+        let port = listener.local_addr().map(|address| address.port()).map_err(|e| PreviewError::Bind {
+            port: requested,
+            message: e.to_string(),
+        })?;
+
+        let clients: Arc<Mutex<Vec<TcpStream>>> = Arc::new(Mutex::new(Vec::new()));
+        let (control, watcher) = watch_root(&root, clients.clone())?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = stopped.clone();
+        let serve_root = root.clone();
+        thread::spawn(move || serve(listener, serve_root, clients, thread_stopped));
+
+        let info = PreviewServerInfo {
+            root: root_text,
+            port,
+            url: format!("http://localhost:{}/", port),
+        };
+        tracing::info!(root = %info.root, port, "preview server started");
+        servers.insert(
+            port,
+            PreviewServer {
+                info: info.clone(),
+                stopped,
+                control,
+                _watcher: watcher,
+            },
+        );
+        Ok(info)
+    }
+
+    /// Stop the server on `port`; pages it served stop reloading
+    pub fn stop(&self, port: u16) -> Result<(), PreviewError> {
+        let server = self.servers.lock().unwrap().remove(&port).ok_or(PreviewError::NotRunning(port))?;
+        server.stopped.store(true, Ordering::SeqCst);
+        let _ = server.control.send(ReloadMessage::Stop);
+        tracing::info!(root = %server.info.root, port, "preview server stopped");
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<PreviewServerInfo> {
+        let mut servers: Vec<PreviewServerInfo> =
+            self.servers.lock().unwrap().values().map(|server| server.info.clone()).collect();
+        servers.sort_by_key(|server| server.port);
+        servers
+    }
+
+    /// Running servers, one watcher each
+    pub fn watcher_count(&self) -> usize {
+        self.servers.lock().unwrap().len()
+    }
+}
+
+impl Default for PreviewServerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Watch `root` and tell the pages listening on `clients` to reload after changes settle
+fn watch_root(
+    root: &Path,
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+) -> Result<(Sender<ReloadMessage>, RecommendedWatcher), PreviewError> {
+    let (control, messages) = mpsc::channel();
+    let notify_control = control.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        if let Ok(event) = result {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                let _ = notify_control.send(ReloadMessage::Changed);
+            }
+        }
+    })
+    .map_err(|e| PreviewError::Watch(e.to_string()))?;
+    watcher.watch(root, RecursiveMode::Recursive).map_err(|e| PreviewError::Watch(e.to_string()))?;
+
+    thread::spawn(move || {
+        while let Ok(ReloadMessage::Changed) = messages.recv() {
+            loop {
+                match messages.recv_timeout(RELOAD_DEBOUNCE) {
+                    Ok(ReloadMessage::Changed) => continue,
+                    Ok(ReloadMessage::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                    Err(RecvTimeoutError::Timeout) => break,
+                }
+            }
+            // Pages that went away fail the write and are dropped; writing outside the lock lets new pages
+            // subscribe meanwhile
+            let mut listening = std::mem::take(&mut *clients.lock().unwrap());
+            listening.retain_mut(|client| client.write_all(b"data: reload\n\n").is_ok());
+            clients.lock().unwrap().extend(listening);
+        }
+    });
+    Ok((control, watcher))
+}
+
+/// Accept connections until stopped, answering each on its own thread
+fn serve(listener: TcpListener, root: PathBuf, clients: Arc<Mutex<Vec<TcpStream>>>, stopped: Arc<AtomicBool>) {
+    while !stopped.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (root, clients) = (root.clone(), clients.clone());
+                thread::spawn(move || {
+                    if let Err(e) = respond(stream, &root, &clients) {
+                        tracing::debug!(error = %e, "preview request failed");
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_INTERVAL),
+            Err(e) => {
+                tracing::warn!(error = %e, "preview server stopped accepting connections");
+                return;
+            }
+        }
+    }
+    // Pages still listening see the stream end
+    clients.lock().unwrap().clear();
+}
+
+struct Response {
+    status: &'static str,
+    content_type: String,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &str, body: Vec<u8>) -> Self {
+        Response {
+            status,
+            content_type: content_type.to_string(),
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn text(status: &'static str, message: &str) -> Self {
+        Response::new(status, "text/plain; charset=utf-8", format!("{}\n", message).into_bytes())
+    }
+}
+
+/// The parts of a request head the server looks at
+struct Request {
+    method: String,
+    target: String,
+    host: Option<String>,
+}
+
+/// Answer one request; the connection closes afterwards, except for reload streams
+fn respond(mut stream: TcpStream, root: &Path, clients: &Mutex<Vec<TcpStream>>) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let Some(Request { method, target, host }) = read_request(&stream)? else {
+        return write_response(&mut stream, Response::text("400 Bad Request", "Bad request"), false);
+    };
+    let port = stream.local_addr()?.port();
+    let local_host = host.is_some_and(|host| {
+        host.eq_ignore_ascii_case(&format!("localhost:{}", port)) || host == format!("127.0.0.1:{}", port)
+    });
+    if !local_host {
+        return write_response(&mut stream, Response::text("403 Forbidden", "Unknown host"), false);
+    }
+    let head = method == "HEAD";
+    if method != "GET" && !head {
+        let mut response = Response::text("405 Method Not Allowed", "Only GET and HEAD are supported");
+        response.headers.push(("Allow", "GET, HEAD".to_string()));
+        return write_response(&mut stream, response, head);
+    }
+
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+    if path == LIVE_RELOAD_PATH {
+        stream.write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n\
+            Connection: keep-alive\r\n\r\n: connected\n\n",
+        )?;
+        stream.set_read_timeout(None)?;
+        clients.lock().unwrap().push(stream);
+        return Ok(());
+    }
+    let response = match percent_decode(path) {
+        Some(path) => file_response(root, &path),
+        None => Response::text("400 Bad Request", "Bad request"),
+    };
+    write_response(&mut stream, response, head)
+}
+
+/// The request line and Host header, after reading the whole head; `None` for a malformed one
+fn read_request(stream: &TcpStream) -> io::Result<Option<Request>> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_HEAD as u64));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut host = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    Ok(match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) if target.starts_with('/') => Some(Request {
+            method: method.to_string(),
+            target: target.to_string(),
+            host,
+        }),
+        _ => None,
+    })
+}
+
+fn write_response(stream: &mut TcpStream, response: Response, head: bool) -> io::Result<()> {
+    let mut header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+        Connection: close\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        header.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    header.push_str("\r\n");
+    stream.write_all(header.as_bytes())?;
+    if !head {
+        stream.write_all(&response.body)?;
+    }
+    stream.flush()
+}
+
+/// The file or directory listing at the decoded URL `path` under `root`
+fn file_response(root: &Path, path: &str) -> Response {
+    let mut file = root.to_path_buf();
+    for component in Path::new(path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => file.push(part),
+            Component::CurDir => {}
+            _ => return Response::text("403 Forbidden", "Forbidden"),
+        }
+    }
+    // Symlinks are followed only as far as they stay under the root
+    let file = match file.canonicalize() {
+        Ok(file) if file.starts_with(root) => file,
+        Ok(_) => return Response::text("403 Forbidden", "Forbidden"),
+        Err(_) => return Response::text("404 Not Found", "Not found"),
+    };
+
+    if file.is_dir() {
+        // Relative links in the page resolve against the directory only with the trailing slash
+        if !path.ends_with('/') {
+            let mut response = Response::text("301 Moved Permanently", "Moved");
+            response.headers.push(("Location", format!("{}/", percent_encode(path))));
+            return response;
+        }
+        let index = file.join("index.html");
+        if !index.is_file() {
+            return listing(&file, path);
+        }
+        return serve_file(&index);
+    }
+    serve_file(&file)
+}
+
+fn serve_file(file: &Path) -> Response {
+    let content = match fs::read(file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            return Response::text("403 Forbidden", "Forbidden");
+        }
+        Err(_) => return Response::text("404 Not Found", "Not found"),
+    };
+    let extension = file.extension().map(|extension| extension.to_string_lossy()).unwrap_or_default();
+    let mime = mime_from_extension(&extension).map(|(mime, _)| mime).unwrap_or("application/octet-stream");
+    if mime == "text/html" {
+        return Response::new("200 OK", "text/html; charset=utf-8", with_live_reload(content));
+    }
+    let content_type = if mime.starts_with("text/") || mime == "application/json" {
+        format!("{}; charset=utf-8", mime)
+    } else {
+        mime.to_string()
+    };
+    Response::new("200 OK", &content_type, content)
+}
+
+fn with_live_reload(mut html: Vec<u8>) -> Vec<u8> {
+    let lower = html.to_ascii_lowercase();
+    let end = lower.windows(7).rposition(|window| window == b"</body>").unwrap_or(html.len());
+    html.splice(end..end, LIVE_RELOAD_SCRIPT.bytes());
+    html
+}
+
+/// An HTML listing of a directory without an `index.html`
+fn listing(directory: &Path, path: &str) -> Response {
+    let mut entries: Vec<(String, bool)> = match fs::read_dir(directory) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| {
+                let is_dir = entry.path().is_dir();
+                (entry.file_name().to_string_lossy().to_string(), is_dir)
+            })
+            .collect(),
+        Err(_) => return Response::text("403 Forbidden", "Forbidden"),
+    };
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase())));
+
+    let title = escape_html(path);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title></head><body>\n\
+        <h1>{}</h1>\n<ul>\n",
+        title, title
+    );
+    if path != "/" {
+        html.push_str("<li><a href=\"../\">../</a></li>\n");
+    }
+    for (name, is_dir) in entries {
+        let suffix = if is_dir { "/" } else { "" };
+        html.push_str(&format!(
+            "<li><a href=\"{}{}\">{}{}</a></li>\n",
+            percent_encode(&name),
+            suffix,
+            escape_html(&name),
+            suffix
+        ));
+    }
+    html.push_str("</ul>\n</body></html>\n");
+    Response::new("200 OK", "text/html; charset=utf-8", with_live_reload(html.into_bytes()))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Decode `%XX` escapes; `None` when they don't make UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = text.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Escape what a URL path can't hold as it is
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,315 @@
+/**
+ * Problem matchers for CodeForge IDE
+ * Regex-configurable extraction of diagnostics from compiler and build tool output
+ */
+
+use crate::diagnostics::{Diagnostic, DiagnosticSeverity};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap());
+
+/// One line pattern; the numbers are the regex capture groups providing each field
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemPattern {
+    pub regexp: String,
+    #[serde(default)]
+    pub file: Option<usize>,
+    #[serde(default)]
+    pub line: Option<usize>,
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    #[serde(default)]
+    pub severity: Option<usize>,
+    #[serde(default)]
+    pub code: Option<usize>,
+    #[serde(default)]
+    pub message: Option<usize>,
+}
+
+/// A matcher whose patterns must match consecutive lines; the last one completes a diagnostic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemMatcherConfig {
+    /// Reported as the diagnostic source, e.g. `rustc`
+    pub owner: String,
+    /// Severity used when the patterns don't capture one (`error`, `warning`, `info`)
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A matcher reference in a task: the name of a built-in matcher or an inline definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProblemMatcherSpec {
+    Named(String),
+    Inline(ProblemMatcherConfig),
+}
+
+impl From<&str> for ProblemMatcherSpec {
+    fn from(name: &str) -> Self {
+        ProblemMatcherSpec::Named(name.to_string())
+    }
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ProblemPattern>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ProblemPattern),
+        Many(Vec<ProblemPattern>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(pattern) => vec![pattern],
+        OneOrMany::Many(patterns) => patterns,
+    })
+}
+
+fn pattern(regexp: &str, groups: [Option<usize>; 6]) -> ProblemPattern {
+    let [file, line, column, severity, code, message] = groups;
+    ProblemPattern {
+        regexp: regexp.to_string(),
+        file,
+        line,
+        column,
+        severity,
+        code,
+        message,
+        ..Default::default()
+    }
+}
+
+fn matcher(owner: &str, severity: Option<&str>, patterns: Vec<ProblemPattern>) -> ProblemMatcherConfig {
+    ProblemMatcherConfig {
+        owner: owner.to_string(),
+        severity: severity.map(str::to_string),
+        pattern: patterns,
+    }
+}
+
+/// Definitions of a built-in matcher; some tools print more than one format
+fn builtin(name: &str) -> Option<Vec<ProblemMatcherConfig>> {
+    Some(match name {
+        // `error[E0425]: cannot find value` followed by `  --> src/main.rs:2:5`
+        "$rustc" => vec![matcher(
+            "rustc",
+            None,
+            vec![
+                pattern(r"^(error|warning)(?:\[(\w+)\])?: (.+)$", [None, None, None, Some(1), Some(2), Some(3)]),
+                pattern(r"^\s*--> (.+?):(\d+):(\d+)$", [Some(1), Some(2), Some(3), None, None, None]),
+            ],
+        )],
+        // `src/a.ts(3,7): error TS2322: ...` and the pretty `src/a.ts:3:7 - error TS2322: ...`
+        "$tsc" => vec![
+            matcher(
+                "tsc",
+                None,
+                vec![pattern(
+                    r"^(.+?)\((\d+),(\d+)\): (error|warning) (TS\d+): (.+)$",
+                    [Some(1), Some(2), Some(3), Some(4), Some(5), Some(6)],
+                )],
+            ),
+            matcher(
+                "tsc",
+                None,
+                vec![pattern(
+                    r"^(.+?):(\d+):(\d+) - (error|warning) (TS\d+): (.+)$",
+                    [Some(1), Some(2), Some(3), Some(4), Some(5), Some(6)],
+                )],
+            ),
+        ],
+        // `main.c:4:12: error: ...` as printed by gcc and clang
+        "$gcc" => vec![matcher(
+            "gcc",
+            None,
+            vec![pattern(
+                r"^(.+?):(\d+):(\d+): (fatal error|error|warning|note): (.+)$",
+                [Some(1), Some(2), Some(3), Some(4), None, Some(5)],
+            )],
+        )],
+        // `src/a.js: line 3, col 7, Error - Unexpected var (no-var)`
+        "$eslint-compact" => vec![matcher(
+            "eslint",
+            None,
+            vec![pattern(
+                r"^(.+?): line (\d+), col (\d+), (Error|Warning) - (.+?)(?: \((.+)\))?$",
+                [Some(1), Some(2), Some(3), Some(4), Some(6), Some(5)],
+            )],
+        )],
+        // `./main.go:4:2: undefined: x`
+        "$go" => vec![matcher(
+            "go",
+            Some("error"),
+            vec![pattern(r"^([^\s:][^:]*\.go):(\d+):(\d+): (.+)$", [Some(1), Some(2), Some(3), None, None, Some(4)])],
+        )],
+        _ => return None,
+    })
+}
+
+struct CompiledMatcher {
+    owner: String,
+    severity: DiagnosticSeverity,
+    patterns: Vec<(Regex, ProblemPattern)>,
+}
+
+/// Fields captured so far by a multi-line matcher
+#[derive(Default)]
+struct PartialDiagnostic {
+    file: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<DiagnosticSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl PartialDiagnostic {
+    fn capture(&mut self, regex: &Regex, pattern: &ProblemPattern, line: &str) -> bool {
+        let Some(captures) = regex.captures(line) else {
+            return false;
+        };
+        let text = |group: Option<usize>| {
+            group
+                .and_then(|group| captures.get(group))
+                .map(|capture| capture.as_str().trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |group: Option<usize>| text(group).and_then(|text| text.parse().ok());
+
+        self.file = text(pattern.file).or(self.file.take());
+        self.line = number(pattern.line).or(self.line);
+        self.column = number(pattern.column).or(self.column);
+        self.end_line = number(pattern.end_line).or(self.end_line);
+        self.end_column = number(pattern.end_column).or(self.end_column);
+        self.severity = text(pattern.severity).map(|s| DiagnosticSeverity::parse(&s)).or(self.severity);
+        self.code = text(pattern.code).or(self.code.take());
+        self.message = text(pattern.message).or(self.message.take());
+        true
+    }
+}
+
+/// Compile matcher references, resolving built-in names
+pub fn compile_matchers(specs: &[ProblemMatcherSpec]) -> Result<Vec<ProblemMatcherConfig>, String> {
+    let mut configs = Vec::new();
+    for spec in specs {
+        match spec {
+            ProblemMatcherSpec::Named(name) => {
+                configs.extend(builtin(name).ok_or_else(|| format!("Unknown problem matcher: {}", name))?);
+            }
+            ProblemMatcherSpec::Inline(config) => {
+                if config.pattern.is_empty() {
+                    return Err(format!("Problem matcher {} has no patterns", config.owner));
+                }
+                configs.push(config.clone());
+            }
+        }
+    }
+    Ok(configs)
+}
+
+/// Feeds output line by line through a set of matchers, tracking progress through multi-line patterns
+pub struct ProblemCollector {
+    matchers: Vec<CompiledMatcher>,
+    progress: Vec<(usize, PartialDiagnostic)>,
+    cwd: PathBuf,
+}
+
+impl ProblemCollector {
+    /// Relative file paths in the output are resolved against `cwd`
+    pub fn new(specs: &[ProblemMatcherSpec], cwd: &Path) -> Result<Self, String> {
+        let mut matchers = Vec::new();
+        for config in compile_matchers(specs)? {
+            let mut patterns = Vec::new();
+            for pattern in config.pattern {
+                let regex = Regex::new(&pattern.regexp)
+                    .map_err(|e| format!("Invalid pattern in problem matcher {}: {}", config.owner, e))?;
+                patterns.push((regex, pattern));
+            }
+            matchers.push(CompiledMatcher {
+                severity: config
+                    .severity
+                    .as_deref()
+                    .map(DiagnosticSeverity::parse)
+                    .unwrap_or(DiagnosticSeverity::Error),
+                owner: config.owner,
+                patterns,
+            });
+        }
+
+        Ok(Self {
+            progress: matchers.iter().map(|_| (0, PartialDiagnostic::default())).collect(),
+            matchers,
+            cwd: cwd.to_path_buf(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    fn resolve(&self, file: &str) -> String {
+        let path = self.cwd.join(file);
+        let path: PathBuf = path.components().filter(|c| *c != Component::CurDir).collect();
+        path.to_string_lossy().to_string()
+    }
+
+    /// Process one line of output, returning a diagnostic once a matcher completes
+    pub fn feed_line(&mut self, line: &str) -> Option<Diagnostic> {
+        let line = ANSI_ESCAPE.replace_all(line.trim_end_matches(['\r', '\n']), "");
+        for index in 0..self.matchers.len() {
+            let matcher = &self.matchers[index];
+            let (step, partial) = &mut self.progress[index];
+
+            // Continue a multi-line match, or start over from the first pattern
+            let (regex, pattern) = &matcher.patterns[*step];
+            if *step == 0 || !partial.capture(regex, pattern, &line) {
+                *step = 0;
+                *partial = PartialDiagnostic::default();
+                let (regex, pattern) = &matcher.patterns[0];
+                if !partial.capture(regex, pattern, &line) {
+                    continue;
+                }
+            }
+            *step += 1;
+            if *step < matcher.patterns.len() {
+                continue;
+            }
+
+            *step = 0;
+            let partial = std::mem::take(partial);
+            // Lines such as rustc's "warning: 2 warnings emitted" never get a location
+            let (Some(file), Some(message)) = (partial.file, partial.message) else {
+                continue;
+            };
+            return Some(Diagnostic {
+                file: self.resolve(&file),
+                line: partial.line.unwrap_or(1),
+                column: partial.column.unwrap_or(1),
+                end_line: partial.end_line,
+                end_column: partial.end_column,
+                severity: partial.severity.unwrap_or(matcher.severity),
+                message,
+                code: partial.code,
+                source: matcher.owner.clone(),
+            });
+        }
+        None
+    }
+
+    /// Run the matchers over a complete output
+    pub fn collect(&mut self, output: &str) -> Vec<Diagnostic> {
+        output.lines().filter_map(|line| self.feed_line(line)).collect()
+    }
+}
@@ -0,0 +1,5 @@
+export function greet(name) {
+  return `Hello, ${name}!`;
+}
+
+console.log(greet("{{project_name}}"));
@@ -0,0 +1,540 @@
+/**
+ * Project templates for CodeForge IDE
+ * File > New Project: a few built-in starters (Rust binary and library, Node, Python) plus user templates from
+ * the app config `project-templates` directory, written out with `{{variable}}` substitution
+ *
+ * A user template is a directory whose files are copied as they are, with placeholders replaced in both
+ * text contents and paths. An optional `template.json` next to them names and describes the template and
+ * declares its variables.
+ */
+
+use crate::activity::civil_date;
+use crate::jsonc;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use walkdir::WalkDir;
+
+/// Directory under the app config dir holding user templates, one subdirectory each
+pub const TEMPLATES_DIR: &str = "project-templates";
+
+/// Optional manifest in a user template's directory; never copied into projects
+pub const TEMPLATE_MANIFEST: &str = "template.json";
+
+/// Files containing a NUL byte this early are treated as binary and copied without substitution
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Error types for project template operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProjectTemplateError {
+    NoConfigDirectory,
+    IOError(String),
+    NotFound(String),
+    InvalidTemplate(String),
+    InvalidPath(String),
+    DestinationNotEmpty(String),
+    MissingVariable(String),
+}
+
+impl std::fmt::Display for ProjectTemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProjectTemplateError::NoConfigDirectory => write!(f, "App config directory is unavailable"),
+            ProjectTemplateError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            ProjectTemplateError::NotFound(id) => write!(f, "Project template not found: {}", id),
+            ProjectTemplateError::InvalidTemplate(msg) => write!(f, "Invalid project template: {}", msg),
+            ProjectTemplateError::InvalidPath(path) => write!(f, "Invalid path in project template: {}", path),
+            ProjectTemplateError::DestinationNotEmpty(path) => write!(f, "Destination is not empty: {}", path),
+            ProjectTemplateError::MissingVariable(name) => write!(f, "A value is required for {}", name),
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> ProjectTemplateError {
+    ProjectTemplateError::IOError(e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Builtin,
+    User,
+}
+
+/// A variable the New Project dialog asks for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// May itself contain placeholders, e.g. `{{project_name}}-server`
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub language: Option<String>,
+    pub source: TemplateSource,
+    /// Directory of a user template
+    pub path: Option<String>,
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// `template.json` as written by hand
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TemplateManifest {
+    name: Option<String>,
+    description: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    variables: Vec<TemplateVariable>,
+}
+
+/// Result of `create_project`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedProject {
+    pub template: String,
+    pub destination: String,
+    /// Files written, relative to `destination` with `/` separators
+    pub files: Vec<String>,
+    /// Every variable value used, including defaults
+    pub variables: BTreeMap<String, String>,
+}
+
+struct BuiltinTemplate {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    language: &'static str,
+    /// Destination path (with placeholders) and contents
+    files: &'static [(&'static str, &'static str)],
+}
+
+const README: &str = include_str!("builtin/README.md");
+const RUST_GITIGNORE: &str = include_str!("builtin/rust/gitignore");
+
+const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "rust-bin",
+        name: "Rust Application",
+        description: "A Cargo binary crate",
+        language: "rust",
+        files: &[
+            ("Cargo.toml", include_str!("builtin/rust/Cargo-bin.toml")),
+            ("src/main.rs", include_str!("builtin/rust/main.rs")),
+            (".gitignore", RUST_GITIGNORE),
+            ("README.md", README),
+        ],
+    },
+    BuiltinTemplate {
+        id: "rust-lib",
+        name: "Rust Library",
+        description: "A Cargo library crate with a unit test",
+        language: "rust",
+        files: &[
+            ("Cargo.toml", include_str!("builtin/rust/Cargo-lib.toml")),
+            ("src/lib.rs", include_str!("builtin/rust/lib.rs")),
+            (".gitignore", RUST_GITIGNORE),
+            ("README.md", README),
+        ],
+    },
+    BuiltinTemplate {
+        id: "node",
+        name: "Node.js Application",
+        description: "An npm package using ES modules",
+        language: "javascript",
+        files: &[
+            ("package.json", include_str!("builtin/node/package.json")),
+            ("index.js", include_str!("builtin/node/index.js")),
+            (".gitignore", include_str!("builtin/node/gitignore")),
+            ("README.md", README),
+        ],
+    },
+    BuiltinTemplate {
+        id: "python",
+        name: "Python Package",
+        description: "A pyproject.toml package with a src layout and a console script",
+        language: "python",
+        files: &[
+            ("pyproject.toml", include_str!("builtin/python/pyproject.toml")),
+            ("src/{{module_name}}/__init__.py", include_str!("builtin/python/__init__.py")),
+            ("src/{{module_name}}/__main__.py", include_str!("builtin/python/__main__.py")),
+            (".gitignore", include_str!("builtin/python/gitignore")),
+            ("README.md", README),
+        ],
+    },
+];
+
+impl BuiltinTemplate {
+    fn info(&self) -> ProjectTemplate {
+        let variable = |name: &str, description: &str, default: Option<&str>, required: bool| TemplateVariable {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            default: default.map(str::to_string),
+            required,
+        };
+        let mut variables = vec![
+            variable("project_name", "Project name", Some("{{folder_name}}"), true),
+            variable("description", "One-line description", Some(""), false),
+        ];
+        if self.id == "node" {
+            variables.push(variable("author", "Author", Some("{{git_user_name}}"), false));
+        }
+        ProjectTemplate {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            description: Some(self.description.to_string()),
+            language: Some(self.language.to_string()),
+            source: TemplateSource::Builtin,
+            path: None,
+            variables,
+        }
+    }
+}
+
+fn placeholder_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+/// Replace `{{name}}` placeholders; unknown names are left alone, so templates can contain other `{{...}}`
+/// syntax such as Handlebars or GitHub Actions expressions
+pub fn substitute(text: &str, variables: &BTreeMap<String, String>) -> String {
+    placeholder_pattern()
+        .replace_all(text, |captures: &Captures| match variables.get(&captures[1]) {
+            Some(value) => value.clone(),
+            None => captures[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// `My Project` -> `my-project`, the form package managers want
+fn package_name(project_name: &str) -> String {
+    let mut name = String::new();
+    for c in project_name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.is_empty() && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    name.trim_end_matches('-').to_string()
+}
+
+/// `my-project` -> `my_project`, usable as a Rust crate or Python module name
+fn module_name(package_name: &str) -> String {
+    let name = package_name.replace('-', "_");
+    match name.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("_{}", name),
+        _ => name,
+    }
+}
+
+/// `user.name` and `user.email` from the git config that applies in `dir`: its repository's config when it
+/// is in one, otherwise the global config. Empty when unset.
+pub(crate) fn git_identity(dir: Option<&Path>) -> (String, String) {
+    let repository_config = dir
+        .and_then(|dir| git2::Repository::discover(dir).ok())
+        .and_then(|repository| repository.config().ok());
+    let config = repository_config.or_else(|| git2::Config::open_default().ok());
+    let value = |key: &str| config.as_ref().and_then(|config| config.get_string(key).ok()).unwrap_or_default();
+    (value("user.name"), value("user.email"))
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`
+pub(crate) fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0);
+    civil_date(days as i64)
+}
+
+/// Values every template can use without declaring them
+fn standard_variables(destination: &Path) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    let folder_name = destination.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    variables.insert("folder_name".to_string(), folder_name);
+
+    let (name, email) = git_identity(None);
+    variables.insert("git_user_name".to_string(), name);
+    variables.insert("git_user_email".to_string(), email);
+
+    let date = today();
+    variables.insert("year".to_string(), date[..4].to_string());
+    variables.insert("date".to_string(), date);
+    variables
+}
+
+/// Fill in the values for a project: standard values, then what the user entered, then the declared
+/// defaults. `package_name` and `module_name` follow `project_name` unless given explicitly.
+fn resolve_variables(
+    template: &ProjectTemplate,
+    destination: &Path,
+    given: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, ProjectTemplateError> {
+    let mut variables = standard_variables(destination);
+    variables.extend(given.into_iter().filter(|(_, value)| !value.is_empty()));
+    for variable in &template.variables {
+        if variables.contains_key(&variable.name) {
+            continue;
+        }
+        let value =
+            variable.default.as_deref().map(|default| substitute(default, &variables)).unwrap_or_default();
+        if value.is_empty() && variable.required {
+            return Err(ProjectTemplateError::MissingVariable(variable.name.clone()));
+        }
+        variables.insert(variable.name.clone(), value);
+    }
+
+    let project_name =
+        variables.get("project_name").cloned().unwrap_or_else(|| variables["folder_name"].clone());
+    let package = variables.get("package_name").cloned().unwrap_or_else(|| package_name(&project_name));
+    if package.is_empty() {
+        return Err(ProjectTemplateError::MissingVariable("package_name".to_string()));
+    }
+    variables.entry("module_name".to_string()).or_insert_with(|| module_name(&package));
+    variables.insert("package_name".to_string(), package);
+    variables.insert("project_name".to_string(), project_name);
+    Ok(variables)
+}
+
+/// Substitute a template-relative path, refusing results that would land outside the project
+fn target_path(
+    template_path: &str,
+    variables: &BTreeMap<String, String>,
+) -> Result<PathBuf, ProjectTemplateError> {
+    let substituted = substitute(template_path, variables);
+    let relative = PathBuf::from(&substituted);
+    let valid = relative.components().count() > 0
+        && relative.components().all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(ProjectTemplateError::InvalidPath(substituted));
+    }
+    Ok(relative)
+}
+
+fn is_empty_dir(path: &Path) -> Result<bool, ProjectTemplateError> {
+    Ok(fs::read_dir(path).map_err(io_error)?.next().is_none())
+}
+
+/// Remove what a failed `create_project` wrote: everything inside `destination`, which was empty before, and
+/// `destination` itself when it didn't exist
+fn clean_up(destination: &Path, created_destination: bool) {
+    let result = match created_destination {
+        true => fs::remove_dir_all(destination),
+        false => fs::read_dir(destination).and_then(|entries| {
+            entries.flatten().try_for_each(|entry| match entry.path().is_dir() {
+                true => fs::remove_dir_all(entry.path()),
+                false => fs::remove_file(entry.path()),
+            })
+        }),
+    };
+    if let Err(e) = result {
+        tracing::warn!(path = %destination.display(), error = %e, "cleaning up a failed project failed");
+    }
+}
+
+pub struct ProjectTemplateService;
+
+impl ProjectTemplateService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn templates_dir(app: &AppHandle) -> Result<PathBuf, ProjectTemplateError> {
+        let config_dir = app.path().app_config_dir().map_err(|_| ProjectTemplateError::NoConfigDirectory)?;
+        Ok(config_dir.join(TEMPLATES_DIR))
+    }
+
+    fn read_user_template(dir: &Path) -> Result<ProjectTemplate, ProjectTemplateError> {
+        let id = dir.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let manifest_path = dir.join(TEMPLATE_MANIFEST);
+        let manifest = match fs::read_to_string(&manifest_path) {
+            Ok(content) => serde_json::from_str::<TemplateManifest>(&jsonc::strip(&content)).map_err(|e| {
+                ProjectTemplateError::InvalidTemplate(format!("{}: {}", manifest_path.to_string_lossy(), e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TemplateManifest::default(),
+            Err(e) => return Err(io_error(e)),
+        };
+        Ok(ProjectTemplate {
+            name: manifest.name.unwrap_or_else(|| id.clone()),
+            id,
+            description: manifest.description,
+            language: manifest.language,
+            source: TemplateSource::User,
+            path: Some(dir.to_string_lossy().to_string()),
+            variables: manifest.variables,
+        })
+    }
+
+    /// Built-in templates followed by user templates; a user template with a built-in's id replaces it.
+    /// Templates with a broken manifest are skipped with a warning so one bad directory doesn't hide the rest.
+    pub fn list_templates(&self, app: &AppHandle) -> Result<Vec<ProjectTemplate>, ProjectTemplateError> {
+        let mut user_templates = Vec::new();
+        let dir = Self::templates_dir(app)?;
+        match fs::read_dir(&dir) {
+            Ok(entries) => {
+                for entry in entries.flatten().filter(|entry| entry.path().is_dir()) {
+                    match Self::read_user_template(&entry.path()) {
+                        Ok(template) => user_templates.push(template),
+                        Err(e) => {
+                            tracing::warn!(path = %entry.path().display(), error = %e, "skipping template")
+                        }
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(io_error(e)),
+        }
+        user_templates.sort_by_key(|template| template.name.to_lowercase());
+
+        let mut templates: Vec<ProjectTemplate> = BUILTIN_TEMPLATES
+            .iter()
+            .filter(|builtin| !user_templates.iter().any(|template| template.id == builtin.id))
+            .map(BuiltinTemplate::info)
+            .collect();
+        templates.extend(user_templates);
+        Ok(templates)
+    }
+
+    fn find_template(&self, app: &AppHandle, id: &str) -> Result<ProjectTemplate, ProjectTemplateError> {
+        self.list_templates(app)?
+            .into_iter()
+            .find(|template| template.id == id)
+            .ok_or_else(|| ProjectTemplateError::NotFound(id.to_string()))
+    }
+
+    /// Write `template` into `destination`, which must not exist or be an empty directory. On failure
+    /// whatever was written is removed again, so a retry starts from the same state.
+    pub fn create_project(
+        &self,
+        app: &AppHandle,
+        template_id: &str,
+        destination: &Path,
+        variables: BTreeMap<String, String>,
+    ) -> Result<CreatedProject, ProjectTemplateError> {
+        let template = self.find_template(app, template_id)?;
+        if destination.exists() && (!destination.is_dir() || !is_empty_dir(destination)?) {
+            return Err(ProjectTemplateError::DestinationNotEmpty(destination.to_string_lossy().to_string()));
+        }
+        let variables = resolve_variables(&template, destination, variables)?;
+
+        let created_destination = !destination.exists();
+        fs::create_dir_all(destination).map_err(io_error)?;
+        let written = match &template.path {
+            None => Self::write_builtin(template_id, destination, &variables),
+            Some(source) => Self::write_user(Path::new(source), destination, &variables),
+        };
+        let mut files = match written {
+            Ok(files) => files,
+            Err(e) => {
+                clean_up(destination, created_destination);
+                return Err(e);
+            }
+        };
+        files.sort();
+        tracing::info!(
+            template = %template_id,
+            path = %destination.display(),
+            files = files.len(),
+            "created project"
+        );
+        Ok(CreatedProject {
+            template: template_id.to_string(),
+            destination: destination.to_string_lossy().to_string(),
+            files,
+            variables,
+        })
+    }
+
+    fn write_file(
+        destination: &Path,
+        relative: &Path,
+        content: &[u8],
+        files: &mut Vec<String>,
+    ) -> Result<(), ProjectTemplateError> {
+        let target = destination.join(relative);
+        if target.exists() {
+            // Two template paths substituted to the same file
+            return Err(ProjectTemplateError::InvalidPath(relative.to_string_lossy().to_string()));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(&target, content).map_err(io_error)?;
+        files.push(relative.to_string_lossy().replace('\\', "/"));
+        Ok(())
+    }
+
+    fn write_builtin(
+        id: &str,
+        destination: &Path,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<Vec<String>, ProjectTemplateError> {
+        let builtin = BUILTIN_TEMPLATES
+            .iter()
+            .find(|builtin| builtin.id == id)
+            .ok_or_else(|| ProjectTemplateError::NotFound(id.to_string()))?;
+        let mut files = Vec::new();
+        for (path, content) in builtin.files {
+            let relative = target_path(path, variables)?;
+            Self::write_file(destination, &relative, substitute(content, variables).as_bytes(), &mut files)?;
+        }
+        Ok(files)
+    }
+
+    /// Copy a user template. Text files get placeholders replaced; binary files and files that aren't UTF-8
+    /// are copied byte for byte. Symlinks are skipped and unix permissions are kept, so scripts stay
+    /// executable.
+    fn write_user(
+        source: &Path,
+        destination: &Path,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<Vec<String>, ProjectTemplateError> {
+        let mut files = Vec::new();
+        let walker = WalkDir::new(source)
+            .min_depth(1)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git");
+        for entry in walker {
+            let entry = entry.map_err(|e| ProjectTemplateError::IOError(e.to_string()))?;
+            let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+            if relative == Path::new(TEMPLATE_MANIFEST) || !entry.file_type().is_file() {
+                continue;
+            }
+            let target = target_path(&relative.to_string_lossy(), variables)?;
+            let bytes = fs::read(entry.path()).map_err(io_error)?;
+            let binary = bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0);
+            let content = match std::str::from_utf8(&bytes) {
+                Ok(text) if !binary => substitute(text, variables).into_bytes(),
+                _ => bytes,
+            };
+            Self::write_file(destination, &target, &content, &mut files)?;
+            let permissions = entry.metadata().map_err(|e| ProjectTemplateError::IOError(e.to_string()))?;
+            fs::set_permissions(destination.join(&target), permissions.permissions()).map_err(io_error)?;
+        }
+        if files.is_empty() {
+            return Err(ProjectTemplateError::InvalidTemplate(format!(
+                "{} has no files",
+                source.to_string_lossy()
+            )));
+        }
+        Ok(files)
+    }
+}
+
+impl Default for ProjectTemplateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,262 @@
+/**
+ * Recently opened files and workspaces for CodeForge IDE
+ * Most-recently-used history with pinning, persisted in the app data dir
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use crate::atomic_file::write_atomic;
+use crate::clock::now_millis;
+
+const RECENT_FILE: &str = "recent.json";
+
+/// Unpinned entries kept per list; pinned entries are never evicted
+const MAX_RECENT_ENTRIES: usize = 50;
+
+/// Error types for recent history operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecentError {
+    NoDataDirectory,
+    IOError(String),
+    NotFound(String),
+}
+
+impl std::fmt::Display for RecentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RecentError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            RecentError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            RecentError::NotFound(path) => write!(f, "Not in recent history: {}", path),
+        }
+    }
+}
+
+/// Which history list an operation applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecentKind {
+    File,
+    Workspace,
+}
+
+/// A recently opened file or workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentEntry {
+    pub path: String,
+    pub name: String,
+    /// Workspace the file was opened in; always `None` for workspaces
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// Unix time in milliseconds
+    pub last_opened: u64,
+    pub open_count: u64,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Whether the path still exists; computed when the list is returned
+    #[serde(default, skip_deserializing)]
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RecentHistory {
+    #[serde(default)]
+    files: Vec<RecentEntry>,
+    #[serde(default)]
+    workspaces: Vec<RecentEntry>,
+}
+
+impl RecentHistory {
+    fn list_mut(&mut self, kind: RecentKind) -> &mut Vec<RecentEntry> {
+        match kind {
+            RecentKind::File => &mut self.files,
+            RecentKind::Workspace => &mut self.workspaces,
+        }
+    }
+}
+
+/// A missing or corrupt history file starts a fresh history rather than failing the picker
+fn load_history(path: &Path) -> RecentHistory {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Pinned entries first, then most recently opened
+fn sort_entries(entries: &mut [RecentEntry]) {
+    entries.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.last_opened.cmp(&a.last_opened)));
+}
+
+/// Drop the oldest unpinned entries beyond the limit
+fn evict(entries: &mut Vec<RecentEntry>) {
+    sort_entries(entries);
+    let mut unpinned = 0;
+    entries.retain(|entry| {
+        if entry.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_RECENT_ENTRIES
+    });
+}
+
+pub struct RecentService {
+    history: Arc<Mutex<Option<RecentHistory>>>,
+}
+
+impl RecentService {
+    pub fn new() -> Self {
+        Self {
+            history: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn history_path(app: &AppHandle) -> Result<PathBuf, RecentError> {
+        let data_dir = app.path().app_data_dir().map_err(|_| RecentError::NoDataDirectory)?;
+        Ok(data_dir.join(RECENT_FILE))
+    }
+
+    /// Run `update` against the loaded history and persist the result
+    fn modify<T>(
+        &self,
+        app: &AppHandle,
+        update: impl FnOnce(&mut RecentHistory) -> Result<T, RecentError>,
+    ) -> Result<T, RecentError> {
+        let path = Self::history_path(app)?;
+        let mut cached = self.history.lock().unwrap();
+        let history = cached.get_or_insert_with(|| load_history(&path));
+        let result = update(history)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| RecentError::IOError(e.to_string()))?;
+        }
+        let content = serde_json::to_string_pretty(history).map_err(|e| RecentError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(|e| RecentError::IOError(e.to_string()))?;
+        Ok(result)
+    }
+
+    fn entries(&self, app: &AppHandle, kind: RecentKind) -> Result<Vec<RecentEntry>, RecentError> {
+        let path = Self::history_path(app)?;
+        let mut entries = self
+            .history
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| load_history(&path))
+            .list_mut(kind)
+            .clone();
+
+        sort_entries(&mut entries);
+        for entry in &mut entries {
+            entry.exists = Path::new(&entry.path).exists();
+        }
+        Ok(entries)
+    }
+
+    /// Move a path to the top of its list, adding it if needed
+    pub fn record(
+        &self,
+        app: &AppHandle,
+        kind: RecentKind,
+        path: &str,
+        workspace: Option<&str>,
+    ) -> Result<(), RecentError> {
+        let name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let workspace = workspace.filter(|_| kind == RecentKind::File).map(str::to_string);
+
+        self.modify(app, |history| {
+            let entries = history.list_mut(kind);
+            match entries.iter_mut().find(|entry| entry.path == path) {
+                Some(entry) => {
+                    entry.last_opened = now_millis();
+                    entry.open_count += 1;
+                    if workspace.is_some() {
+                        entry.workspace = workspace;
+                    }
+                }
+                None => entries.push(RecentEntry {
+                    path: path.to_string(),
+                    name,
+                    workspace,
+                    last_opened: now_millis(),
+                    open_count: 1,
+                    pinned: false,
+                    exists: true,
+                }),
+            }
+            evict(entries);
+            Ok(())
+        })
+    }
+
+    /// Recent files, pinned first, optionally limited to those opened in one workspace
+    pub fn recent_files(
+        &self,
+        app: &AppHandle,
+        workspace: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RecentEntry>, RecentError> {
+        let mut entries = self.entries(app, RecentKind::File)?;
+        if let Some(workspace) = workspace {
+            entries.retain(|entry| {
+                entry.workspace.as_deref() == Some(workspace) || Path::new(&entry.path).starts_with(workspace)
+            });
+        }
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Recent workspaces, pinned first
+    pub fn recent_workspaces(&self, app: &AppHandle, limit: usize) -> Result<Vec<RecentEntry>, RecentError> {
+        let mut entries = self.entries(app, RecentKind::Workspace)?;
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Pin or unpin an entry so it stays at the top and is never evicted
+    pub fn set_pinned(&self, app: &AppHandle, kind: RecentKind, path: &str, pinned: bool) -> Result<(), RecentError> {
+        self.modify(app, |history| {
+            let entries = history.list_mut(kind);
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.path == path)
+                .ok_or_else(|| RecentError::NotFound(path.to_string()))?;
+            entry.pinned = pinned;
+            evict(entries);
+            Ok(())
+        })
+    }
+
+    /// Remove a single entry, e.g. one whose path no longer exists
+    pub fn remove(&self, app: &AppHandle, kind: RecentKind, path: &str) -> Result<(), RecentError> {
+        self.modify(app, |history| {
+            history.list_mut(kind).retain(|entry| entry.path != path);
+            Ok(())
+        })
+    }
+
+    /// Clear one list, or both when `kind` is `None`; pinned entries survive unless `include_pinned`
+    pub fn clear(&self, app: &AppHandle, kind: Option<RecentKind>, include_pinned: bool) -> Result<(), RecentError> {
+        self.modify(app, |history| {
+            let kinds = match kind {
+                Some(kind) => vec![kind],
+                None => vec![RecentKind::File, RecentKind::Workspace],
+            };
+            for kind in kinds {
+                history.list_mut(kind).retain(|entry| entry.pinned && !include_pinned);
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Default for RecentService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
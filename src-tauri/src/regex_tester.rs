@@ -0,0 +1,266 @@
+/**
+ * Regex playground backend
+ * Evaluates a pattern with the same engine as workspace search, or fancy-regex for lookaround/backreferences
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Stop collecting matches past this many, so pathological patterns stay responsive
+const MAX_MATCHES: usize = 10_000;
+
+/// Backtracking budget for the fancy-regex engine
+const FANCY_BACKTRACK_LIMIT: usize = 1_000_000;
+
+/// Regex engine to evaluate with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegexEngine {
+    /// The `regex` crate, as used by workspace search
+    #[default]
+    Standard,
+    /// `fancy-regex`, adding lookaround and backreferences
+    Fancy,
+}
+
+/// Flags of a regex test
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegexTestOptions {
+    #[serde(default)]
+    pub engine: RegexEngine,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub multi_line: bool,
+    #[serde(default)]
+    pub dot_matches_new_line: bool,
+    #[serde(default)]
+    pub ignore_whitespace: bool,
+}
+
+/// A capture group of a match; offsets are UTF-16 code units into the sample text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexGroup {
+    pub index: usize,
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// A match with its capture groups; groups that did not participate are omitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexMatch {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub groups: Vec<RegexGroup>,
+}
+
+/// A pattern error with the offending span of the pattern in UTF-16 code units, when known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexPatternError {
+    pub message: String,
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+/// Outcome of evaluating a pattern against sample text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegexTestResult {
+    pub matches: Vec<RegexMatch>,
+    /// Name of each capture group, index 0 being the whole match
+    pub group_names: Vec<Option<String>>,
+    pub truncated: bool,
+    pub error: Option<RegexPatternError>,
+}
+
+/// Converts increasing byte offsets of a string into UTF-16 offsets
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, byte: 0, utf16: 0 }
+    }
+
+    fn convert(&mut self, byte: usize) -> usize {
+        if byte < self.byte {
+            // Groups may start before the previous group ended
+            return self.text[..byte].encode_utf16().count();
+        }
+        self.utf16 += self.text[self.byte..byte].encode_utf16().count();
+        self.byte = byte;
+        self.utf16
+    }
+}
+
+fn utf16_len(text: &str, byte: usize) -> usize {
+    text[..byte.min(text.len())].encode_utf16().count()
+}
+
+/// Evaluate `pattern` against `text`; invalid patterns are reported in `error` rather than failing
+pub fn test_regex(pattern: &str, text: &str, options: &RegexTestOptions) -> RegexTestResult {
+    match options.engine {
+        RegexEngine::Standard => test_standard(pattern, text, options),
+        RegexEngine::Fancy => test_fancy(pattern, text, options),
+    }
+}
+
+fn test_standard(pattern: &str, text: &str, options: &RegexTestOptions) -> RegexTestResult {
+    let regex = match regex::RegexBuilder::new(pattern)
+        .case_insensitive(options.case_insensitive)
+        .multi_line(options.multi_line)
+        .dot_matches_new_line(options.dot_matches_new_line)
+        .ignore_whitespace(options.ignore_whitespace)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(e) => return error_result(standard_error(pattern, options, e)),
+    };
+
+    let group_names: Vec<Option<String>> = regex.capture_names().map(|name| name.map(str::to_string)).collect();
+    let mut offsets = Utf16Offsets::new(text);
+    let mut result = RegexTestResult::default();
+
+    for captures in regex.captures_iter(text) {
+        if result.matches.len() == MAX_MATCHES {
+            result.truncated = true;
+            break;
+        }
+        let spans = (0..captures.len()).map(|index| captures.get(index).map(|m| (m.start(), m.end())));
+        result.matches.push(build_match(text, spans, &group_names, &mut offsets));
+    }
+    result.group_names = group_names;
+    result
+}
+
+/// Re-parse a rejected pattern to locate the error, as `regex::Error` only carries a rendered message
+fn standard_error(pattern: &str, options: &RegexTestOptions, error: regex::Error) -> RegexPatternError {
+    let span = regex_syntax::ParserBuilder::new()
+        .case_insensitive(options.case_insensitive)
+        .multi_line(options.multi_line)
+        .dot_matches_new_line(options.dot_matches_new_line)
+        .ignore_whitespace(options.ignore_whitespace)
+        .build()
+        .parse(pattern)
+        .err()
+        .and_then(|e| match e {
+            regex_syntax::Error::Parse(e) => Some(*e.span()),
+            regex_syntax::Error::Translate(e) => Some(*e.span()),
+            _ => None,
+        });
+
+    match (span, error) {
+        (Some(span), regex::Error::Syntax(message)) => RegexPatternError {
+            // The rendered message repeats the pattern with a caret; keep only the description
+            message: message
+                .lines()
+                .find_map(|line| line.strip_prefix("error: "))
+                .unwrap_or(&message)
+                .to_string(),
+            start: Some(utf16_len(pattern, span.start.offset)),
+            end: Some(utf16_len(pattern, span.end.offset)),
+        },
+        (_, error) => RegexPatternError {
+            message: error.to_string(),
+            start: None,
+            end: None,
+        },
+    }
+}
+
+fn test_fancy(pattern: &str, text: &str, options: &RegexTestOptions) -> RegexTestResult {
+    let regex = match fancy_regex::RegexBuilder::new(pattern)
+        .case_insensitive(options.case_insensitive)
+        .multi_line(options.multi_line)
+        .dot_matches_new_line(options.dot_matches_new_line)
+        .ignore_whitespace(options.ignore_whitespace)
+        .backtrack_limit(FANCY_BACKTRACK_LIMIT)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(fancy_regex::Error::ParseError(position, e)) => {
+            let start = utf16_len(pattern, position);
+            return error_result(RegexPatternError {
+                message: e.to_string(),
+                start: Some(start),
+                end: Some(start + 1),
+            });
+        }
+        Err(e) => {
+            return error_result(RegexPatternError {
+                message: e.to_string(),
+                start: None,
+                end: None,
+            })
+        }
+    };
+
+    let group_names: Vec<Option<String>> = regex.capture_names().map(|name| name.map(str::to_string)).collect();
+    let mut offsets = Utf16Offsets::new(text);
+    let mut result = RegexTestResult::default();
+
+    for captures in regex.captures_iter(text) {
+        let captures = match captures {
+            Ok(captures) => captures,
+            Err(e) => {
+                // Backtrack limit exceeded; keep what matched so far
+                result.error = Some(RegexPatternError {
+                    message: e.to_string(),
+                    start: None,
+                    end: None,
+                });
+                break;
+            }
+        };
+        if result.matches.len() == MAX_MATCHES {
+            result.truncated = true;
+            break;
+        }
+        let spans = (0..captures.len()).map(|index| captures.get(index).map(|m| (m.start(), m.end())));
+        result.matches.push(build_match(text, spans, &group_names, &mut offsets));
+    }
+    result.group_names = group_names;
+    result
+}
+
+fn build_match(
+    text: &str,
+    mut spans: impl Iterator<Item = Option<(usize, usize)>>,
+    group_names: &[Option<String>],
+    offsets: &mut Utf16Offsets,
+) -> RegexMatch {
+    let (start, end) = spans.next().flatten().unwrap_or((0, 0));
+    let match_start = offsets.convert(start);
+    let groups: Vec<(usize, usize, usize)> = spans
+        .enumerate()
+        .filter_map(|(index, span)| span.map(|(group_start, group_end)| (index + 1, group_start, group_end)))
+        .collect();
+
+    let groups = groups
+        .into_iter()
+        .map(|(index, group_start, group_end)| RegexGroup {
+            index,
+            name: group_names.get(index).cloned().flatten(),
+            start: offsets.convert(group_start),
+            end: offsets.convert(group_end),
+            text: text[group_start..group_end].to_string(),
+        })
+        .collect();
+
+    RegexMatch {
+        start: match_start,
+        end: offsets.convert(end),
+        text: text[start..end].to_string(),
+        groups,
+    }
+}
+
+fn error_result(error: RegexPatternError) -> RegexTestResult {
+    RegexTestResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,265 @@
+/**
+ * Resource usage for the Process Explorer panel
+ * Memory and threads of the backend and every process it started, with the watchers and sessions each
+ * subsystem holds open, so a runaway subsystem stands out
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::activity::ActivityService;
+use crate::bookmarks::BookmarkStore;
+use crate::debug::{BreakpointStore, DebugService};
+use crate::file_system::FileSystemService;
+use crate::plugins::{PluginService, PluginState};
+use crate::preview_server::PreviewServerService;
+use crate::tail::TailService;
+use crate::tasks::TaskService;
+use crate::terminal::TerminalService;
+use crate::theme::ThemeService;
+
+/// One process as the OS reports it
+struct ProcessSample {
+    pid: u32,
+    parent_pid: Option<u32>,
+    name: String,
+    rss_bytes: Option<u64>,
+    threads: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    /// Resident memory; `None` where the OS does not report it
+    pub rss_bytes: Option<u64>,
+    pub threads: Option<u32>,
+    /// Subsystem that started the process (or an ancestor of it), e.g. `debug`, when known
+    pub subsystem: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// The backend process itself
+    pub process: ProcessUsage,
+    /// Every process descending from the backend (debug adapters, tasks, language servers), largest first
+    pub children: Vec<ProcessUsage>,
+    pub children_rss_bytes: u64,
+    /// Open file system watchers per subsystem
+    pub watchers: BTreeMap<String, usize>,
+    pub watcher_count: usize,
+    pub active_plugins: usize,
+    pub debug_sessions: usize,
+    pub terminal_sessions: usize,
+    /// Unix time in milliseconds the sample was taken
+    pub sampled_at: u64,
+}
+
+fn process_usage(sample: &ProcessSample, subsystem: Option<String>) -> ProcessUsage {
+    ProcessUsage {
+        pid: sample.pid,
+        parent_pid: sample.parent_pid,
+        name: sample.name.clone(),
+        rss_bytes: sample.rss_bytes,
+        threads: sample.threads,
+        subsystem,
+    }
+}
+
+/// Descendants of `root` with the subsystem each belongs to: its own when listed in `owners`, otherwise that
+/// of its nearest listed ancestor
+fn descendants(samples: &[ProcessSample], root: u32, owners: &HashMap<u32, String>) -> Vec<ProcessUsage> {
+    let mut children: HashMap<u32, Vec<&ProcessSample>> = HashMap::new();
+    for sample in samples {
+        if let Some(parent) = sample.parent_pid.filter(|parent| *parent != sample.pid) {
+            children.entry(parent).or_default().push(sample);
+        }
+    }
+    let mut found = Vec::new();
+    let mut pending: Vec<(u32, Option<String>)> = vec![(root, None)];
+    while let Some((pid, subsystem)) = pending.pop() {
+        for child in children.get(&pid).into_iter().flatten() {
+            let subsystem = owners.get(&child.pid).cloned().or_else(|| subsystem.clone());
+            found.push(process_usage(child, subsystem.clone()));
+            pending.push((child.pid, subsystem));
+        }
+    }
+    found
+}
+
+fn watcher_counts(app: &AppHandle) -> BTreeMap<String, usize> {
+    BTreeMap::from([
+        ("activity".to_string(), app.state::<ActivityService>().watcher_count()),
+        ("bookmarks".to_string(), app.state::<BookmarkStore>().watcher_count()),
+        ("breakpoints".to_string(), app.state::<BreakpointStore>().watcher_count()),
+        ("file_system".to_string(), app.state::<FileSystemService>().watcher_count()),
+        ("preview".to_string(), app.state::<PreviewServerService>().watcher_count()),
+        ("tail".to_string(), app.state::<TailService>().watcher_count()),
+        ("tasks".to_string(), app.state::<TaskService>().watcher_count()),
+        ("theme".to_string(), app.state::<ThemeService>().watcher_count()),
+    ])
+}
+
+/// Sample the backend's resource usage. Shells out on macOS and Windows, so call it off the main thread.
+pub fn resource_usage(app: &AppHandle) -> Result<ResourceUsage, String> {
+    let pid = std::process::id();
+    let samples = platform::processes()?;
+    let own = samples
+        .iter()
+        .find(|sample| sample.pid == pid)
+        .ok_or_else(|| format!("Process {} not found", pid))?;
+
+    let owners: HashMap<u32, String> = app
+        .state::<DebugService>()
+        .adapter_pids()
+        .into_iter()
+        .map(|adapter_pid| (adapter_pid, "debug".to_string()))
+        .collect();
+    let mut children = descendants(&samples, pid, &owners);
+    children.sort_by(|a, b| b.rss_bytes.cmp(&a.rss_bytes).then(a.pid.cmp(&b.pid)));
+
+    let watchers = watcher_counts(app);
+    Ok(ResourceUsage {
+        process: process_usage(own, None),
+        children_rss_bytes: children.iter().filter_map(|child| child.rss_bytes).sum(),
+        children,
+        watcher_count: watchers.values().sum(),
+        watchers,
+        active_plugins: app
+            .state::<PluginService>()
+            .list()
+            .iter()
+            .filter(|plugin| plugin.state == PluginState::Active)
+            .count(),
+        debug_sessions: app.state::<DebugService>().list_sessions().len(),
+        terminal_sessions: app.state::<TerminalService>().session_count(),
+        sampled_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::ProcessSample;
+    use std::fs;
+
+    pub fn processes() -> Result<Vec<ProcessSample>, String> {
+        let entries = fs::read_dir("/proc").map_err(|e| e.to_string())?;
+        Ok(entries
+            .flatten()
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse().ok()?;
+                // Processes can exit between listing and reading
+                let status = fs::read_to_string(entry.path().join("status")).ok()?;
+                Some(parse_status(pid, &status))
+            })
+            .collect())
+    }
+
+    /// Read the fields we need from /proc/<pid>/status; kernel threads have no `VmRSS`
+    fn parse_status(pid: u32, status: &str) -> ProcessSample {
+        let field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                .map(str::trim)
+        };
+        ProcessSample {
+            pid,
+            parent_pid: field("PPid").and_then(|ppid| ppid.parse().ok()),
+            name: field("Name").unwrap_or_default().to_string(),
+            rss_bytes: field("VmRSS")
+                .and_then(|rss| rss.trim_end_matches("kB").trim().parse::<u64>().ok())
+                .map(|kilobytes| kilobytes * 1024),
+            threads: field("Threads").and_then(|threads| threads.parse().ok()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ProcessSample;
+    use std::process::Command;
+
+    pub fn processes() -> Result<Vec<ProcessSample>, String> {
+        let output = Command::new("ps")
+            .args(["-A", "-o", "pid=,ppid=,rss=,comm="])
+            .output()
+            .map_err(|e| e.to_string())?;
+        let own_pid = std::process::id();
+        let mut samples: Vec<ProcessSample> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // PID PPID RSS(KB) COMMAND; the command may contain spaces
+                let mut fields = line.split_whitespace();
+                let pid = fields.next()?.parse().ok()?;
+                let parent_pid = fields.next()?.parse().ok();
+                let rss_bytes = fields.next()?.parse::<u64>().ok().map(|kilobytes| kilobytes * 1024);
+                let command = fields.collect::<Vec<&str>>().join(" ");
+                Some(ProcessSample {
+                    pid,
+                    parent_pid,
+                    name: command.rsplit('/').next().unwrap_or_default().to_string(),
+                    rss_bytes,
+                    threads: None,
+                })
+            })
+            .collect();
+        // `ps -A` has no thread column; count them only for the backend rather than once per process
+        if let Some(own) = samples.iter_mut().find(|sample| sample.pid == own_pid) {
+            own.threads = thread_count(own_pid);
+        }
+        Ok(samples)
+    }
+
+    /// `ps -M` prints a header and then one line per thread
+    fn thread_count(pid: u32) -> Option<u32> {
+        let output = Command::new("ps").args(["-M", "-p", &pid.to_string()]).output().ok()?;
+        let lines = String::from_utf8_lossy(&output.stdout).lines().count();
+        (lines > 1).then(|| (lines - 1) as u32)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::ProcessSample;
+    use std::process::Command;
+
+    /// One `pid,parent pid,working set,threads,name` line per process
+    const PROCESS_QUERY: &str = "Get-CimInstance Win32_Process | ForEach-Object { \
+        \"$($_.ProcessId),$($_.ParentProcessId),$($_.WorkingSetSize),$($_.ThreadCount),$($_.Name)\" }";
+
+    pub fn processes() -> Result<Vec<ProcessSample>, String> {
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", PROCESS_QUERY])
+            .output()
+            .map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.trim().splitn(5, ',');
+                Some(ProcessSample {
+                    pid: fields.next()?.parse().ok()?,
+                    parent_pid: fields.next()?.parse().ok(),
+                    rss_bytes: fields.next()?.parse().ok(),
+                    threads: fields.next()?.parse().ok(),
+                    name: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::ProcessSample;
+
+    pub fn processes() -> Result<Vec<ProcessSample>, String> {
+        Err("Resource usage is not supported on this platform".to_string())
+    }
+}
@@ -0,0 +1,255 @@
+/**
+ * REST client for CodeForge IDE
+ * Runs requests from `.http` / `.rest` files and captures the response with timing
+ *
+ * Requests go through clients kept per set of options, so requests to the same host reuse connections.
+ */
+
+mod parser;
+mod response;
+
+pub use parser::{parse_http_file, HttpFile, HttpHeader, HttpRequestDefinition};
+pub use response::ResponseBody;
+
+use parser::substitute_variables;
+use response::decode_body;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Largest response body read; bigger ones fail instead of filling memory
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Error types for REST client operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RestClientError {
+    RequestNotFound(usize),
+    InvalidRequest(String),
+    InvalidOptions(String),
+    TimedOut(u64),
+    ConnectTimedOut(u64),
+    ResponseTooLarge(u64),
+    RequestFailed(String),
+}
+
+impl std::fmt::Display for RestClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RestClientError::RequestNotFound(index) => write!(f, "No request at index {}", index),
+            RestClientError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
+            RestClientError::InvalidOptions(msg) => write!(f, "Invalid client options: {}", msg),
+            RestClientError::TimedOut(ms) => write!(f, "Request timed out after {} ms", ms),
+            RestClientError::ConnectTimedOut(ms) => write!(f, "Connecting timed out after {} ms", ms),
+            RestClientError::ResponseTooLarge(limit) => {
+                write!(f, "Response body is larger than {} MiB", limit / (1024 * 1024))
+            }
+            RestClientError::RequestFailed(msg) => write!(f, "Request failed: {}", msg),
+        }
+    }
+}
+
+/// How requests are sent
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpClientOptions {
+    /// Limit on the whole request, from connecting to the end of the body; 0 for none
+    pub timeout_ms: u64,
+    /// 0 for none
+    pub connect_timeout_ms: u64,
+    /// Proxy URL for every request, e.g. `http://proxy:3128` or `socks5://localhost:1080`; the
+    /// `HTTP_PROXY` / `HTTPS_PROXY` environment variables apply when unset
+    pub proxy: Option<String>,
+    pub follow_redirects: bool,
+    /// Accept self-signed and otherwise invalid certificates, for local servers
+    pub accept_invalid_certificates: bool,
+}
+
+impl Default for HttpClientOptions {
+    fn default() -> Self {
+        HttpClientOptions {
+            timeout_ms: 30_000,
+            connect_timeout_ms: 10_000,
+            proxy: None,
+            follow_redirects: true,
+            accept_invalid_certificates: false,
+        }
+    }
+}
+
+/// Captured response of an executed request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    /// Where the response came from, after redirects
+    pub url: String,
+    pub headers: Vec<HttpHeader>,
+    #[serde(flatten)]
+    pub body: ResponseBody,
+    pub size: usize,
+    /// Until the status and headers arrived
+    pub headers_ms: u64,
+    /// Until the whole body arrived
+    pub duration_ms: u64,
+    /// The request as sent, after variable substitution
+    pub request: HttpRequestDefinition,
+}
+
+pub struct HttpClientService {
+    clients: Mutex<HashMap<HttpClientOptions, reqwest::Client>>,
+}
+
+impl HttpClientService {
+    pub fn new() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Substitute variables in the `index`-th request of a file and execute it
+    pub async fn send_request(
+        &self,
+        content: &str,
+        index: usize,
+        environment: &HashMap<String, String>,
+        options: &HttpClientOptions,
+    ) -> Result<HttpResponse, RestClientError> {
+        let request = resolve_request(content, index, environment)?;
+        let client = self.client(options)?;
+        execute(&client, request, options).await
+    }
+
+    fn client(&self, options: &HttpClientOptions) -> Result<reqwest::Client, RestClientError> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(options) {
+            return Ok(client.clone());
+        }
+        let redirects = if options.follow_redirects {
+            reqwest::redirect::Policy::default()
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        let mut builder = reqwest::Client::builder()
+            .redirect(redirects)
+            .danger_accept_invalid_certs(options.accept_invalid_certificates);
+        if options.timeout_ms > 0 {
+            builder = builder.timeout(Duration::from_millis(options.timeout_ms));
+        }
+        if options.connect_timeout_ms > 0 {
+            builder = builder.connect_timeout(Duration::from_millis(options.connect_timeout_ms));
+        }
+        if let Some(proxy) = options.proxy.as_deref().map(str::trim).filter(|proxy| !proxy.is_empty()) {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| RestClientError::InvalidOptions(e.to_string()))?;
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build().map_err(|e| RestClientError::InvalidOptions(e.to_string()))?;
+        clients.insert(options.clone(), client.clone());
+        Ok(client)
+    }
+}
+
+impl Default for HttpClientService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `index`-th request of a file, with its variables substituted
+fn resolve_request(
+    content: &str,
+    index: usize,
+    environment: &HashMap<String, String>,
+) -> Result<HttpRequestDefinition, RestClientError> {
+    let file = parse_http_file(content);
+    let definition = file
+        .requests
+        .get(index)
+        .ok_or(RestClientError::RequestNotFound(index))?;
+    let resolve = |text: &str| substitute_variables(text, &file.variables, environment);
+
+    let request = HttpRequestDefinition {
+        name: definition.name.clone(),
+        method: definition.method.clone(),
+        url: resolve(&definition.url),
+        headers: definition
+            .headers
+            .iter()
+            .map(|header| HttpHeader {
+                name: header.name.clone(),
+                value: resolve(&header.value),
+            })
+            .collect(),
+        body: definition.body.as_deref().map(resolve),
+        line: definition.line,
+    };
+    Ok(request)
+}
+
+async fn execute(
+    client: &reqwest::Client,
+    request: HttpRequestDefinition,
+    options: &HttpClientOptions,
+) -> Result<HttpResponse, RestClientError> {
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .map_err(|_| RestClientError::InvalidRequest(format!("unknown method {}", request.method)))?;
+    let url = reqwest::Url::parse(&request.url).map_err(|e| RestClientError::InvalidRequest(e.to_string()))?;
+
+    let mut builder = client.request(method, url);
+    for header in &request.headers {
+        builder = builder.header(&header.name, &header.value);
+    }
+    if let Some(body) = &request.body {
+        builder = builder.body(body.clone());
+    }
+
+    let failed = |e: reqwest::Error| {
+        if e.is_connect() && e.is_timeout() {
+            RestClientError::ConnectTimedOut(options.connect_timeout_ms)
+        } else if e.is_timeout() {
+            RestClientError::TimedOut(options.timeout_ms)
+        } else {
+            RestClientError::RequestFailed(e.to_string())
+        }
+    };
+    let started = Instant::now();
+    let mut response = builder.send().await.map_err(failed)?;
+    let headers_ms = started.elapsed().as_millis() as u64;
+    let status = response.status();
+    let url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string());
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| HttpHeader {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).to_string(),
+        })
+        .collect();
+    if response.content_length().is_some_and(|length| length > MAX_RESPONSE_BYTES) {
+        return Err(RestClientError::ResponseTooLarge(MAX_RESPONSE_BYTES));
+    }
+    // Chunked responses don't announce their length, so the limit is checked as the body arrives
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(failed)? {
+        if (bytes.len() + chunk.len()) as u64 > MAX_RESPONSE_BYTES {
+            return Err(RestClientError::ResponseTooLarge(MAX_RESPONSE_BYTES));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        url,
+        headers,
+        body: decode_body(content_type.as_deref(), &bytes),
+        size: bytes.len(),
+        headers_ms,
+        duration_ms: started.elapsed().as_millis() as u64,
+        request,
+    })
+}
@@ -0,0 +1,219 @@
+/**
+ * Parser for `.http` / `.rest` request files
+ * Follows the VS Code REST Client format: `###` separates requests, `@name = value` declares variables
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const METHODS: [&str; 9] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE", "CONNECT"];
+
+/// A request header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// A request as written in the file, before variable substitution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequestDefinition {
+    /// Set by a `# @name` comment above the request
+    pub name: Option<String>,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HttpHeader>,
+    pub body: Option<String>,
+    /// 1-based line of the request line, for code lenses
+    pub line: usize,
+}
+
+/// Parsed contents of a request file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpFile {
+    pub variables: HashMap<String, String>,
+    pub requests: Vec<HttpRequestDefinition>,
+}
+
+#[derive(PartialEq)]
+enum Section {
+    RequestLine,
+    Headers,
+    Body,
+}
+
+/// Parse the text of a request file; blocks without a request line are skipped
+pub fn parse_http_file(content: &str) -> HttpFile {
+    let mut file = HttpFile::default();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("###") {
+            parse_block(&block, &mut file);
+            block.clear();
+        } else {
+            block.push((index + 1, line));
+        }
+    }
+    parse_block(&block, &mut file);
+    file
+}
+
+fn parse_block(lines: &[(usize, &str)], file: &mut HttpFile) {
+    let mut section = Section::RequestLine;
+    let mut name = None;
+    let mut request: Option<HttpRequestDefinition> = None;
+    let mut body: Vec<&str> = Vec::new();
+
+    for &(line_number, line) in lines {
+        let trimmed = line.trim();
+        if section != Section::Body {
+            if let Some(comment) = comment_text(trimmed) {
+                if let Some(request_name) = comment.strip_prefix("@name") {
+                    name = Some(request_name.trim().to_string());
+                }
+                continue;
+            }
+        }
+
+        match section {
+            Section::RequestLine => {
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if let Some((variable, value)) = parse_variable(trimmed) {
+                    file.variables.insert(variable, value);
+                    continue;
+                }
+                let (method, url) = parse_request_line(trimmed);
+                request = Some(HttpRequestDefinition {
+                    name: name.take(),
+                    method,
+                    url,
+                    headers: Vec::new(),
+                    body: None,
+                    line: line_number,
+                });
+                section = Section::Headers;
+            }
+            Section::Headers => {
+                let Some(request) = request.as_mut() else {
+                    continue;
+                };
+                if trimmed.is_empty() {
+                    section = Section::Body;
+                } else if let Some((header, value)) = trimmed.split_once(':') {
+                    request.headers.push(HttpHeader {
+                        name: header.trim().to_string(),
+                        value: value.trim().to_string(),
+                    });
+                } else if trimmed.starts_with('?') || trimmed.starts_with('&') {
+                    // Query parameters continued on the following lines
+                    request.url.push_str(trimmed);
+                }
+            }
+            Section::Body => body.push(line),
+        }
+    }
+
+    if let Some(mut request) = request {
+        while body.last().is_some_and(|line| line.trim().is_empty()) {
+            body.pop();
+        }
+        if !body.is_empty() {
+            request.body = Some(body.join("\n"));
+        }
+        file.requests.push(request);
+    }
+}
+
+fn comment_text(line: &str) -> Option<&str> {
+    line.strip_prefix("//")
+        .or_else(|| line.strip_prefix('#'))
+        .map(|comment| comment.trim())
+}
+
+/// `@name = value` file variable
+fn parse_variable(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.strip_prefix('@')?.split_once('=')?;
+    Some((name.trim().to_string(), value.trim().to_string()))
+}
+
+/// `METHOD URL [HTTP/version]`; a bare URL is a GET
+fn parse_request_line(line: &str) -> (String, String) {
+    let mut parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() > 1 && parts.last().is_some_and(|part| part.starts_with("HTTP/")) {
+        parts.pop();
+    }
+
+    match parts.first() {
+        Some(method) if METHODS.contains(&method.to_uppercase().as_str()) => {
+            (method.to_uppercase(), parts[1..].join(" "))
+        }
+        _ => ("GET".to_string(), parts.join(" ")),
+    }
+}
+
+/// Replace `{{variable}}` references; environment values override file variables
+pub fn substitute_variables(
+    text: &str,
+    file_variables: &HashMap<String, String>,
+    environment: &HashMap<String, String>,
+) -> String {
+    let mut result = text.to_string();
+    // Variables may reference other variables; bound the passes so cycles terminate
+    for _ in 0..8 {
+        let next = substitute_once(&result, file_variables, environment);
+        if next == result {
+            break;
+        }
+        result = next;
+    }
+    result
+}
+
+fn substitute_once(
+    text: &str,
+    file_variables: &HashMap<String, String>,
+    environment: &HashMap<String, String>,
+) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let reference = &rest[start + 2..start + 2 + end];
+        match resolve_variable(reference.trim(), file_variables, environment) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..start + 4 + end]),
+        }
+        rest = &rest[start + 4 + end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn resolve_variable(
+    reference: &str,
+    file_variables: &HashMap<String, String>,
+    environment: &HashMap<String, String>,
+) -> Option<String> {
+    if let Some(system) = reference.strip_prefix('$') {
+        let mut parts = system.split_whitespace();
+        return match parts.next()? {
+            "timestamp" => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .map(|duration| duration.as_secs().to_string()),
+            "processEnv" => std::env::var(parts.next()?).ok(),
+            _ => None,
+        };
+    }
+    environment
+        .get(reference)
+        .or_else(|| file_variables.get(reference))
+        .cloned()
+}
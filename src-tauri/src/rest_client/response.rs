@@ -0,0 +1,62 @@
+/**
+ * Response bodies of the REST client
+ * Works out what a body is from its Content-Type, or from its bytes when the server didn't say, so the
+ * panel can pretty-print JSON, render images and show everything else as text or a hex dump
+ */
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::file_type::detect_content_type;
+
+/// Application types that are text whatever the bytes look like
+const TEXT_APPLICATION_TYPES: [&str; 8] = [
+    "application/json",
+    "application/xml",
+    "application/javascript",
+    "application/ecmascript",
+    "application/x-www-form-urlencoded",
+    "application/graphql",
+    "application/yaml",
+    "application/toml",
+];
+
+/// A response body, decoded for display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseBody {
+    /// Media type from the Content-Type header without its parameters, or detected from the bytes
+    pub content_type: String,
+    pub is_binary: bool,
+    /// The text, or base64 of the bytes when `is_binary`
+    pub body: String,
+}
+
+pub fn decode_body(content_type_header: Option<&str>, bytes: &[u8]) -> ResponseBody {
+    let declared = content_type_header
+        .and_then(|header| header.split(';').next())
+        .map(|media_type| media_type.trim().to_ascii_lowercase())
+        .filter(|media_type| !media_type.is_empty() && media_type != "application/octet-stream");
+    let detected = detect_content_type(bytes);
+    let (content_type, is_binary) = match declared {
+        Some(media_type) if is_text_type(&media_type) => (media_type, false),
+        Some(media_type) => (media_type, detected.is_binary),
+        None => (detected.mime_type, detected.is_binary),
+    };
+    let body = if is_binary {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    };
+    ResponseBody {
+        content_type,
+        is_binary,
+        body,
+    }
+}
+
+fn is_text_type(media_type: &str) -> bool {
+    media_type.starts_with("text/")
+        || media_type.ends_with("+json")
+        || media_type.ends_with("+xml")
+        || TEXT_APPLICATION_TYPES.contains(&media_type)
+}
@@ -0,0 +1,296 @@
+/**
+ * Save pipeline for CodeForge IDE
+ * Steps applied to editor content before it is written: formatting, trimming trailing whitespace, ensuring a
+ * final newline, and normalizing line endings. Preferences (user and workspace) choose the defaults and the
+ * file's EditorConfig properties override them.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::editorconfig;
+use crate::environment;
+use crate::file_system::FileSystemService;
+use crate::formatter::{self, FormatError};
+use crate::settings::{SettingsLayer, SettingsService};
+use crate::types::{FileOperationResult, FormatterConfig};
+
+/// Formatter timeout when the setting is missing; the setting itself is validated to this range
+const DEFAULT_FORMAT_TIMEOUT_MS: u64 = 3000;
+const FORMAT_TIMEOUT_RANGE_MS: std::ops::RangeInclusive<u64> = 100..=60_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl EndOfLine {
+    fn parse(value: &str) -> Option<EndOfLine> {
+        match value {
+            "lf" => Some(EndOfLine::Lf),
+            "crlf" => Some(EndOfLine::Crlf),
+            "cr" => Some(EndOfLine::Cr),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            EndOfLine::Lf => "\n",
+            EndOfLine::Crlf => "\r\n",
+            EndOfLine::Cr => "\r",
+        }
+    }
+}
+
+/// Transforms that apply to one file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveTransforms {
+    pub trim_trailing_whitespace: bool,
+    pub insert_final_newline: bool,
+    /// `None` keeps the line endings as they are
+    pub end_of_line: Option<EndOfLine>,
+    /// Formatter run before the other steps; `None` unless format on save is on and one is configured for
+    /// the file's extension
+    pub formatter: Option<FormatterConfig>,
+    pub format_timeout_ms: u64,
+    /// `.editorconfig` files that contributed
+    pub editorconfig_files: Vec<String>,
+}
+
+/// What the pipeline changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppliedTransforms {
+    pub trimmed_lines: usize,
+    pub final_newline_added: bool,
+    pub line_endings_normalized: usize,
+}
+
+impl AppliedTransforms {
+    pub fn changed(&self) -> bool {
+        self.trimmed_lines > 0 || self.final_newline_added || self.line_endings_normalized > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatStatus {
+    Formatted,
+    /// The formatter ran and left the content as it was
+    Unchanged,
+    /// The formatter can't be run by the backend
+    Unavailable,
+    TimedOut,
+    Failed,
+}
+
+/// Which formatter ran and how it went; on anything but `Formatted` or `Unchanged` the file is saved
+/// unformatted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatReport {
+    /// `lsp`, or the command of an external formatter
+    pub formatter: String,
+    pub status: FormatStatus,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of `write_file_content`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFile {
+    #[serde(flatten)]
+    pub result: FileOperationResult,
+    pub transforms: SaveTransforms,
+    /// `None` when no formatter ran
+    pub format: Option<FormatReport>,
+    pub applied: AppliedTransforms,
+    /// The content as written, when the pipeline changed it, so the editor can update its buffer
+    pub content: Option<String>,
+}
+
+/// Resolve the transforms for `path` from the preferences of its workspace and its EditorConfig. Unreadable
+/// settings fall back to the defaults rather than failing the save.
+pub fn resolve_transforms(app: &AppHandle, path: &str) -> SaveTransforms {
+    let workspace = app.state::<FileSystemService>().workspace_root(path);
+    let workspace = workspace.as_ref().map(|root| root.to_string_lossy().to_string());
+    let scope = match workspace {
+        Some(_) => SettingsLayer::Workspace,
+        None => SettingsLayer::User,
+    };
+    let settings = app
+        .state::<SettingsService>()
+        .effective_settings(app, scope, workspace.as_deref())
+        .map(|effective| effective.settings)
+        .unwrap_or_else(|e| {
+            tracing::warn!(path = %path, error = %e, "reading save settings failed, using defaults");
+            Default::default()
+        });
+    let setting = |key: &str| settings.get(key).map(|setting| &setting.value);
+
+    let mut transforms = SaveTransforms {
+        trim_trailing_whitespace: setting("trim_trailing_whitespace").and_then(Value::as_bool).unwrap_or(false),
+        insert_final_newline: setting("insert_final_newline").and_then(Value::as_bool).unwrap_or(false),
+        end_of_line: setting("end_of_line").and_then(Value::as_str).and_then(EndOfLine::parse),
+        formatter: None,
+        format_timeout_ms: setting("format_on_save_timeout")
+            .and_then(Value::as_u64)
+            .unwrap_or(DEFAULT_FORMAT_TIMEOUT_MS)
+            .clamp(*FORMAT_TIMEOUT_RANGE_MS.start(), *FORMAT_TIMEOUT_RANGE_MS.end()),
+        editorconfig_files: Vec::new(),
+    };
+    let extension = Path::new(path).extension().map(|extension| extension.to_string_lossy().to_lowercase());
+    if setting("format_on_save").and_then(Value::as_bool).unwrap_or(false) {
+        let formatter = extension.and_then(|extension| setting("formatters")?.get(extension)).cloned();
+        transforms.formatter = formatter.and_then(|formatter| match serde_json::from_value(formatter) {
+            Ok(formatter) => Some(formatter),
+            Err(e) => {
+                tracing::warn!(path = %path, error = %e, "ignoring invalid formatter setting");
+                None
+            }
+        });
+    }
+    let editorconfig = editorconfig::resolve(Path::new(path));
+    if let Some(trim) = editorconfig.get_bool("trim_trailing_whitespace") {
+        transforms.trim_trailing_whitespace = trim;
+    }
+    if let Some(insert) = editorconfig.get_bool("insert_final_newline") {
+        transforms.insert_final_newline = insert;
+    }
+    if let Some(end_of_line) = editorconfig.get("end_of_line").and_then(EndOfLine::parse) {
+        transforms.end_of_line = Some(end_of_line);
+    }
+    transforms.editorconfig_files =
+        editorconfig.files.iter().map(|file| file.to_string_lossy().to_string()).collect();
+    transforms
+}
+
+/// Split into lines and their line breaks, treating `\r\n`, `\n`, and a lone `\r` as one break each
+fn split_lines(content: &str) -> Vec<(&str, &str)> {
+    let mut lines = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let Some(end) = rest.find(['\n', '\r']) else {
+            lines.push((rest, ""));
+            break;
+        };
+        let break_length = if rest[end..].starts_with("\r\n") { 2 } else { 1 };
+        lines.push((&rest[..end], &rest[end..end + break_length]));
+        rest = &rest[end + break_length..];
+    }
+    lines
+}
+
+/// Apply `transforms` to `content`, returning the new content and what changed
+pub fn apply(content: &str, transforms: &SaveTransforms) -> (String, AppliedTransforms) {
+    let mut applied = AppliedTransforms::default();
+    let lines = split_lines(content);
+    // A file without line breaks that needs a final one gets the target ending, or LF
+    let default_break = transforms
+        .end_of_line
+        .map(EndOfLine::as_str)
+        .or_else(|| lines.iter().map(|(_, line_break)| *line_break).find(|line_break| !line_break.is_empty()))
+        .unwrap_or("\n");
+
+    let mut output = String::with_capacity(content.len() + 1);
+    for (line, line_break) in &lines {
+        let kept = match transforms.trim_trailing_whitespace {
+            true => line.trim_end_matches([' ', '\t']),
+            false => line,
+        };
+        if kept.len() != line.len() {
+            applied.trimmed_lines += 1;
+        }
+        output.push_str(kept);
+        match transforms.end_of_line {
+            Some(end_of_line) if !line_break.is_empty() && *line_break != end_of_line.as_str() => {
+                applied.line_endings_normalized += 1;
+                output.push_str(end_of_line.as_str());
+            }
+            _ => output.push_str(line_break),
+        }
+    }
+    let ends_with_break = lines.last().is_some_and(|(_, line_break)| !line_break.is_empty());
+    if transforms.insert_final_newline && !lines.is_empty() && !ends_with_break {
+        output.push_str(default_break);
+        applied.final_newline_added = true;
+    }
+    (output, applied)
+}
+
+/// Run the formatter in `transforms` over `content`. Whatever goes wrong, the content comes back usable: as
+/// formatted, or as it was, with the report saying why.
+pub fn format(
+    app: &AppHandle,
+    path: &str,
+    content: String,
+    transforms: &SaveTransforms,
+) -> (String, Option<FormatReport>) {
+    let Some(formatter) = &transforms.formatter else {
+        return (content, None);
+    };
+    let (command, args) = match formatter {
+        FormatterConfig::External { command, args } => (command, args),
+        FormatterConfig::Lsp => {
+            let report = FormatReport {
+                formatter: "lsp".to_string(),
+                status: FormatStatus::Unavailable,
+                message: Some("Language servers run in the editor, which formats before saving".to_string()),
+                duration_ms: 0,
+            };
+            return (content, Some(report));
+        }
+    };
+
+    // Formatters run from the workspace root, where they find their config files, with the workspace env
+    let workspace = app.state::<FileSystemService>().workspace_root(path);
+    let file = Path::new(path);
+    let cwd = workspace.as_deref().or_else(|| file.parent()).unwrap_or(Path::new("."));
+    let env = workspace
+        .as_ref()
+        .map(|workspace| environment::workspace_env(&workspace.to_string_lossy()))
+        .transpose()
+        .unwrap_or_else(|e| {
+            tracing::warn!(path = %path, error = %e, "reading workspace env for formatter failed");
+            None
+        })
+        .unwrap_or_default();
+
+    let started = Instant::now();
+    let timeout = Duration::from_millis(transforms.format_timeout_ms);
+    let result = formatter::run_external(command, args, file, cwd, &env, &content, timeout);
+    let mut report = FormatReport {
+        formatter: command.clone(),
+        status: FormatStatus::Formatted,
+        message: None,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    match result {
+        // Empty output from a non-empty buffer is far more likely a misconfigured formatter than intended
+        Ok(formatted) if formatted.is_empty() && !content.is_empty() => {
+            report.status = FormatStatus::Failed;
+            report.message = Some("Formatter produced no output".to_string());
+            (content, Some(report))
+        }
+        Ok(formatted) if formatted == content => {
+            report.status = FormatStatus::Unchanged;
+            (content, Some(report))
+        }
+        Ok(formatted) => (formatted, Some(report)),
+        Err(e) => {
+            tracing::warn!(path = %path, formatter = %command, error = %e, "formatting failed, saving as is");
+            report.status = match e {
+                FormatError::SpawnFailed(_) => FormatStatus::Unavailable,
+                FormatError::Timeout(_) => FormatStatus::TimedOut,
+                _ => FormatStatus::Failed,
+            };
+            report.message = Some(e.to_string());
+            (content, Some(report))
+        }
+    }
+}
@@ -0,0 +1,258 @@
+/**
+ * Session Service for CodeForge IDE
+ * Persists per-workspace editor state (open tabs, cursors, layout) in the app data dir
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::atomic_file::write_atomic;
+use crate::navigation::{NavigationHistory, NavigationLocation, NavigationState};
+
+/// Directory under the app data dir holding one session file per workspace
+const SESSIONS_DIR: &str = "sessions";
+
+/// Error types for session operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SessionError {
+    NoDataDirectory,
+    IOError(String),
+    InvalidSession(String),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SessionError::NoDataDirectory => write!(f, "App data directory is unavailable"),
+            SessionError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            SessionError::InvalidSession(msg) => write!(f, "Invalid session file: {}", msg),
+        }
+    }
+}
+
+/// Zero-based cursor position
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CursorPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An open editor tab
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTab {
+    pub path: String,
+    #[serde(default)]
+    pub cursor: CursorPosition,
+    #[serde(default)]
+    pub scroll_top: f64,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Editor group the tab belongs to when the editor is split
+    #[serde(default)]
+    pub group: usize,
+}
+
+/// Visibility and size of the workbench panels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub sidebar_visible: bool,
+    pub sidebar_width: f64,
+    pub active_sidebar_view: Option<String>,
+    pub panel_visible: bool,
+    pub panel_height: f64,
+    pub active_panel: Option<String>,
+    pub editor_group_sizes: Vec<f64>,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            sidebar_visible: true,
+            sidebar_width: 260.0,
+            active_sidebar_view: None,
+            panel_visible: false,
+            panel_height: 240.0,
+            active_panel: None,
+            editor_group_sizes: Vec::new(),
+        }
+    }
+}
+
+/// Saved state of one workspace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSession {
+    pub workspace: String,
+    #[serde(default)]
+    pub open_tabs: Vec<SessionTab>,
+    #[serde(default)]
+    pub active_file: Option<String>,
+    #[serde(default)]
+    pub layout: PanelLayout,
+    /// Kept by the backend through `push_location`; whatever the editor sends when saving is ignored
+    #[serde(default)]
+    pub navigation: NavigationHistory,
+    /// Unix time in milliseconds, set when saved
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+pub struct SessionService {
+    sessions: Arc<Mutex<HashMap<String, WorkspaceSession>>>,
+}
+
+/// Stable key for a workspace path (FNV-1a), independent of the Rust hasher, for per-workspace files
+pub(crate) fn workspace_key(workspace: &str) -> String {
+    let hash = workspace.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn session_file_name(workspace: &str) -> String {
+    format!("{}.json", workspace_key(workspace))
+}
+
+fn sessions_dir(app: &AppHandle) -> Result<PathBuf, SessionError> {
+    let data_dir = app.path().app_data_dir().map_err(|_| SessionError::NoDataDirectory)?;
+    Ok(data_dir.join(SESSIONS_DIR))
+}
+
+impl SessionService {
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Persist the session of a workspace, keeping the navigation history the backend already has for it
+    pub fn save_session(&self, app: &AppHandle, mut session: WorkspaceSession) -> Result<(), SessionError> {
+        session.navigation = self
+            .load_session(app, &session.workspace)?
+            .map(|stored| stored.navigation)
+            .unwrap_or_default();
+        self.store_session(app, session)
+    }
+
+    fn store_session(&self, app: &AppHandle, mut session: WorkspaceSession) -> Result<(), SessionError> {
+        session.saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        let dir = sessions_dir(app)?;
+        fs::create_dir_all(&dir).map_err(|e| SessionError::IOError(e.to_string()))?;
+        let content =
+            serde_json::to_string_pretty(&session).map_err(|e| SessionError::InvalidSession(e.to_string()))?;
+
+        let path = dir.join(session_file_name(&session.workspace));
+        write_atomic(&path, content).map_err(|e| SessionError::IOError(e.to_string()))?;
+
+        self.sessions.lock().unwrap().insert(session.workspace.clone(), session);
+        Ok(())
+    }
+
+    /// The session of a workspace as last saved, from the cache or disk
+    fn load_session(&self, app: &AppHandle, workspace: &str) -> Result<Option<WorkspaceSession>, SessionError> {
+        if let Some(session) = self.sessions.lock().unwrap().get(workspace) {
+            return Ok(Some(session.clone()));
+        }
+        let path = sessions_dir(app)?.join(session_file_name(workspace));
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SessionError::IOError(e.to_string())),
+        };
+        serde_json::from_str(&content).map(Some).map_err(|e| SessionError::InvalidSession(e.to_string()))
+    }
+
+    /// Load the saved session of a workspace, dropping tabs whose files no longer exist
+    pub fn restore_session(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+    ) -> Result<Option<WorkspaceSession>, SessionError> {
+        Ok(self.load_session(app, workspace)?.map(prune_missing_files))
+    }
+
+    /// Apply `change` to the navigation history of a workspace and persist it with the session
+    fn update_navigation<T>(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        change: impl FnOnce(&mut NavigationHistory) -> T,
+    ) -> Result<T, SessionError> {
+        let mut session = self.load_session(app, workspace)?.unwrap_or_else(|| WorkspaceSession {
+            workspace: workspace.to_string(),
+            ..Default::default()
+        });
+        let result = change(&mut session.navigation);
+        self.store_session(app, session)?;
+        Ok(result)
+    }
+
+    /// Record a location the editor moved to
+    pub fn push_location(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        location: NavigationLocation,
+    ) -> Result<NavigationState, SessionError> {
+        self.update_navigation(app, workspace, |navigation| {
+            navigation.push(location);
+            navigation.state()
+        })
+    }
+
+    /// Step back (negative `steps`) or forward through the navigation history; `None` when there is nowhere
+    /// to go in that direction
+    pub fn navigate(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        steps: isize,
+    ) -> Result<Option<NavigationState>, SessionError> {
+        self.update_navigation(app, workspace, |navigation| {
+            navigation.navigate(steps).map(|_| navigation.state())
+        })
+    }
+
+    pub fn navigation_state(&self, app: &AppHandle, workspace: &str) -> Result<NavigationState, SessionError> {
+        let session = self.load_session(app, workspace)?;
+        Ok(session.map(|session| session.navigation).unwrap_or_default().state())
+    }
+
+    /// Forget the saved session of a workspace
+    pub fn clear_session(&self, app: &AppHandle, workspace: &str) -> Result<(), SessionError> {
+        self.sessions.lock().unwrap().remove(workspace);
+        let path = sessions_dir(app)?.join(session_file_name(workspace));
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(SessionError::IOError(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn prune_missing_files(mut session: WorkspaceSession) -> WorkspaceSession {
+    session.open_tabs.retain(|tab| Path::new(&tab.path).is_file());
+    session.navigation.prune_missing_files();
+    if session
+        .active_file
+        .as_ref()
+        .is_some_and(|active| !session.open_tabs.iter().any(|tab| &tab.path == active))
+    {
+        session.active_file = session.open_tabs.first().map(|tab| tab.path.clone());
+    }
+    session
+}
+
+impl Default for SessionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
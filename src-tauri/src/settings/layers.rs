@@ -0,0 +1,138 @@
+/**
+ * Layered settings resolution: built-in defaults, user preferences, then workspace overrides
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::{to_object, SettingsError, SettingsService};
+use crate::types::AppPreferences;
+
+/// Workspace overrides, relative to the workspace root
+pub const WORKSPACE_SETTINGS_FILE: &str = ".codeforge/settings.json";
+
+/// Where a setting value comes from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SettingsLayer {
+    Default,
+    User,
+    Workspace,
+}
+
+/// The highest layer to include when resolving settings
+pub type SettingsScope = SettingsLayer;
+
+/// A resolved setting and the layers that define it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveSetting {
+    pub value: Value,
+    pub source: SettingsLayer,
+    /// Lower layers whose value for this key is shadowed
+    pub overridden: Vec<SettingsLayer>,
+}
+
+/// All settings resolved for a scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveSettings {
+    pub scope: SettingsScope,
+    pub workspace: Option<String>,
+    pub settings: BTreeMap<String, EffectiveSetting>,
+    /// Values that were ignored, e.g. a string where a number is expected
+    pub warnings: Vec<String>,
+}
+
+fn same_type(expected: &Value, actual: &Value) -> bool {
+    matches!(
+        (expected, actual),
+        (Value::Null, _)
+            | (Value::Bool(_), Value::Bool(_))
+            | (Value::Number(_), Value::Number(_))
+            | (Value::String(_), Value::String(_))
+            | (Value::Array(_), Value::Array(_))
+            | (Value::Object(_), Value::Object(_))
+    )
+}
+
+/// Read the workspace settings file; a missing file is an empty layer
+pub fn load_workspace_settings(workspace: &str) -> Result<Map<String, Value>, SettingsError> {
+    let path = Path::new(workspace).join(WORKSPACE_SETTINGS_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(SettingsError::IOError(e.to_string())),
+    };
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(settings)) => Ok(settings),
+        Ok(_) => Err(SettingsError::Validation(vec![format!(
+            "{} must contain a JSON object",
+            WORKSPACE_SETTINGS_FILE
+        )])),
+        Err(e) => Err(SettingsError::Validation(vec![format!("{}: {}", WORKSPACE_SETTINGS_FILE, e)])),
+    }
+}
+
+impl SettingsService {
+    /// Merge the layers up to `scope`, recording which layer supplied each value
+    ///
+    /// Keys unknown to the built-in defaults are passed through, so extensions can keep
+    /// their own settings in the same files.
+    pub fn effective_settings(
+        &self,
+        app: &AppHandle,
+        scope: SettingsScope,
+        workspace: Option<&str>,
+    ) -> Result<EffectiveSettings, SettingsError> {
+        let defaults = to_object(&AppPreferences::default());
+        let mut layers = vec![(SettingsLayer::Default, defaults.clone())];
+        if scope >= SettingsLayer::User {
+            layers.push((SettingsLayer::User, self.user_settings(app)?));
+        }
+        if scope >= SettingsLayer::Workspace {
+            let workspace = workspace.ok_or_else(|| {
+                SettingsError::Validation(vec!["workspace scope requires a workspace path".to_string()])
+            })?;
+            layers.push((SettingsLayer::Workspace, load_workspace_settings(workspace)?));
+        }
+
+        let mut settings: BTreeMap<String, EffectiveSetting> = BTreeMap::new();
+        let mut warnings = Vec::new();
+        for (layer, values) in layers {
+            for (key, value) in values {
+                if let Some(expected) = defaults.get(&key) {
+                    if !same_type(expected, &value) {
+                        warnings.push(format!("{:?} setting '{}' has the wrong type and was ignored", layer, key));
+                        continue;
+                    }
+                }
+                match settings.get_mut(&key) {
+                    Some(setting) => {
+                        setting.overridden.push(setting.source);
+                        setting.value = value;
+                        setting.source = layer;
+                    }
+                    None => {
+                        settings.insert(
+                            key,
+                            EffectiveSetting {
+                                value,
+                                source: layer,
+                                overridden: Vec::new(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(EffectiveSettings {
+            scope,
+            workspace: workspace.map(str::to_string),
+            settings,
+            warnings,
+        })
+    }
+}
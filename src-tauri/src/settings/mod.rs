@@ -0,0 +1,235 @@
+/**
+ * Settings Service for CodeForge IDE
+ * Persists AppPreferences as JSON in the app config dir and keeps all windows in sync
+ */
+
+mod layers;
+
+pub use layers::{EffectiveSettings, SettingsLayer, SettingsScope};
+
+use crate::atomic_file::write_atomic;
+use crate::file_nesting::parent_pattern_problem;
+use crate::types::{AppPreferences, FormatterConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event broadcast to every window after preferences change
+pub const PREFERENCES_CHANGED_EVENT: &str = "settings://preferences-changed";
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+/// Error types for settings operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SettingsError {
+    NoConfigDirectory,
+    IOError(String),
+    Validation(Vec<String>),
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SettingsError::NoConfigDirectory => write!(f, "App config directory is unavailable"),
+            SettingsError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            SettingsError::Validation(problems) => write!(f, "Invalid preferences: {}", problems.join("; ")),
+        }
+    }
+}
+
+/// Range checks serde's type checks can't express
+fn validate(preferences: &AppPreferences) -> Result<(), SettingsError> {
+    let mut problems = Vec::new();
+    if preferences.theme.trim().is_empty() {
+        problems.push("theme must not be empty".to_string());
+    }
+    if preferences.font_family.trim().is_empty() {
+        problems.push("font_family must not be empty".to_string());
+    }
+    if !(6..=72).contains(&preferences.font_size) {
+        problems.push(format!("font_size must be between 6 and 72, got {}", preferences.font_size));
+    }
+    if !(1..=16).contains(&preferences.tab_size) {
+        problems.push(format!("tab_size must be between 1 and 16, got {}", preferences.tab_size));
+    }
+    if !(100..=60_000).contains(&preferences.auto_save_delay) {
+        problems.push(format!(
+            "auto_save_delay must be between 100 and 60000 ms, got {}",
+            preferences.auto_save_delay
+        ));
+    }
+    if !["auto", "lf", "crlf", "cr"].contains(&preferences.end_of_line.as_str()) {
+        problems.push(format!(
+            "end_of_line must be one of auto, lf, crlf, cr, got {}",
+            preferences.end_of_line
+        ));
+    }
+    if !["auto", "native", "polling"].contains(&preferences.file_watcher_mode.as_str()) {
+        problems.push(format!(
+            "file_watcher_mode must be one of auto, native, polling, got {}",
+            preferences.file_watcher_mode
+        ));
+    }
+    if !(250..=60_000).contains(&preferences.file_watcher_poll_interval) {
+        problems.push(format!(
+            "file_watcher_poll_interval must be between 250 and 60000 ms, got {}",
+            preferences.file_watcher_poll_interval
+        ));
+    }
+
+    if !(100..=60_000).contains(&preferences.format_on_save_timeout) {
+        problems.push(format!(
+            "format_on_save_timeout must be between 100 and 60000 ms, got {}",
+            preferences.format_on_save_timeout
+        ));
+    }
+    let nesting_patterns = preferences.file_nesting_patterns.keys();
+    problems.extend(nesting_patterns.filter_map(|parent| parent_pattern_problem(parent)));
+    for (extension, formatter) in &preferences.formatters {
+        if let FormatterConfig::External { command, .. } = formatter {
+            if command.trim().is_empty() {
+                problems.push(format!("formatter for '{}' must name a command", extension));
+            }
+        }
+    }
+
+    let is_http = |url: &str| url.starts_with("https://") || url.starts_with("http://");
+    // Extensions run code, so where they come from must not be open to tampering on the way
+    let registry_url = &preferences.extension_registry_url;
+    if !registry_url.is_empty() && !registry_url.starts_with("https://") {
+        problems.push(format!("extension_registry_url must be an https URL, got {}", registry_url));
+    }
+    let crash_report_url = &preferences.crash_report_url;
+    if !crash_report_url.is_empty() && !is_http(crash_report_url) {
+        problems.push(format!("crash_report_url must be an http(s) URL, got {}", crash_report_url));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(SettingsError::Validation(problems))
+    }
+}
+
+/// Overlay `patch` onto the fields of `base`, rejecting unknown keys
+fn merge_fields(base: &mut Map<String, Value>, patch: Map<String, Value>, strict: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (key, value) in patch {
+        if base.contains_key(&key) {
+            base.insert(key, value);
+        } else if strict {
+            problems.push(format!("unknown preference '{}'", key));
+        }
+    }
+    problems
+}
+
+pub(crate) fn to_object(preferences: &AppPreferences) -> Map<String, Value> {
+    match serde_json::to_value(preferences) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+fn from_object(object: Map<String, Value>) -> Result<AppPreferences, SettingsError> {
+    serde_json::from_value(Value::Object(object)).map_err(|e| SettingsError::Validation(vec![e.to_string()]))
+}
+
+pub struct SettingsService {
+    preferences: Arc<Mutex<Option<AppPreferences>>>,
+}
+
+impl SettingsService {
+    pub fn new() -> Self {
+        Self {
+            preferences: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn preferences_path(app: &AppHandle) -> Result<PathBuf, SettingsError> {
+        let config_dir = app.path().app_config_dir().map_err(|_| SettingsError::NoConfigDirectory)?;
+        Ok(config_dir.join(PREFERENCES_FILE))
+    }
+
+    /// Values stored in the user preferences file, without defaults
+    pub fn user_settings(&self, app: &AppHandle) -> Result<Map<String, Value>, SettingsError> {
+        let path = Self::preferences_path(app)?;
+        match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(Value::Object(stored)) => Ok(stored),
+                _ => Ok(Map::new()),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Map::new()),
+            Err(e) => Err(SettingsError::IOError(e.to_string())),
+        }
+    }
+
+    /// Current preferences; stored values are merged over defaults, invalid ones fall back to defaults
+    pub fn get_preferences(&self, app: &AppHandle) -> Result<AppPreferences, SettingsError> {
+        let mut cached = self.preferences.lock().unwrap();
+        if let Some(preferences) = cached.as_ref() {
+            return Ok(preferences.clone());
+        }
+
+        let defaults = AppPreferences::default();
+        let mut merged = to_object(&defaults);
+        // Fields added in newer versions get defaults; fields from older versions are dropped
+        merge_fields(&mut merged, self.user_settings(app)?, false);
+        let preferences = from_object(merged)
+            .ok()
+            .filter(|preferences| validate(preferences).is_ok())
+            .unwrap_or(defaults);
+
+        *cached = Some(preferences.clone());
+        Ok(preferences)
+    }
+
+    /// Apply a partial update, validate it, persist, and notify all windows
+    pub fn update_preferences(&self, app: &AppHandle, patch: Value) -> Result<AppPreferences, SettingsError> {
+        let Value::Object(patch) = patch else {
+            return Err(SettingsError::Validation(vec!["update must be a JSON object".to_string()]));
+        };
+
+        let mut merged = to_object(&self.get_preferences(app)?);
+        let problems = merge_fields(&mut merged, patch, true);
+        if !problems.is_empty() {
+            return Err(SettingsError::Validation(problems));
+        }
+        let preferences = from_object(merged)?;
+        validate(&preferences)?;
+
+        self.store(app, preferences)
+    }
+
+    /// Restore the default preferences
+    pub fn reset_preferences(&self, app: &AppHandle) -> Result<AppPreferences, SettingsError> {
+        self.store(app, AppPreferences::default())
+    }
+
+    fn store(&self, app: &AppHandle, preferences: AppPreferences) -> Result<AppPreferences, SettingsError> {
+        let path = Self::preferences_path(app)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| SettingsError::IOError(e.to_string()))?;
+        }
+        // Only values that differ from the defaults are written, so the file reflects what the user chose
+        let defaults = to_object(&AppPreferences::default());
+        let mut overrides = to_object(&preferences);
+        overrides.retain(|key, value| defaults.get(key) != Some(value));
+        let content = serde_json::to_string_pretty(&Value::Object(overrides))
+            .map_err(|e| SettingsError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(|e| SettingsError::IOError(e.to_string()))?;
+
+        *self.preferences.lock().unwrap() = Some(preferences.clone());
+        let _ = app.emit(PREFERENCES_CHANGED_EVENT, preferences.clone());
+        Ok(preferences)
+    }
+}
+
+impl Default for SettingsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,275 @@
+/**
+ * Snippet Service for CodeForge IDE
+ * User snippets stored per language in the app config `snippets` directory, in the same format as
+ * VS Code snippet files so existing collections can be copied in
+ *
+ * `snippets/<language>.json` applies to one language; `snippets/global.json` applies to every
+ * language, or to the comma-separated languages in an entry's `scope`.
+ */
+
+mod syntax;
+
+pub use syntax::{parse_snippet, ParsedSnippet};
+
+use crate::atomic_file::write_atomic;
+use crate::jsonc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// Directory under the app config dir holding snippet files
+pub const SNIPPETS_DIR: &str = "snippets";
+
+/// Pseudo-language whose snippets apply to every language
+pub const GLOBAL_SNIPPETS: &str = "global";
+
+/// Error types for snippet operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SnippetError {
+    NoConfigDirectory,
+    IOError(String),
+    InvalidLanguage(String),
+    InvalidFile(String),
+    InvalidSnippet(String),
+    NotFound(String),
+    Syntax { position: usize, message: String },
+}
+
+impl std::fmt::Display for SnippetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SnippetError::NoConfigDirectory => write!(f, "App config directory is unavailable"),
+            SnippetError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            SnippetError::InvalidLanguage(language) => write!(f, "Invalid language id: {}", language),
+            SnippetError::InvalidFile(msg) => write!(f, "Invalid snippets file: {}", msg),
+            SnippetError::InvalidSnippet(msg) => write!(f, "Invalid snippet: {}", msg),
+            SnippetError::NotFound(name) => write!(f, "Snippet not found: {}", name),
+            SnippetError::Syntax { position, message } => {
+                write!(f, "Snippet syntax error at character {}: {}", position, message)
+            }
+        }
+    }
+}
+
+/// Snippet files allow either a single string or a list for `prefix` and `body`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrList::One(value) => vec![value],
+            StringOrList::Many(values) => values,
+        }
+    }
+
+    fn from_vec(mut values: Vec<String>) -> Self {
+        if values.len() == 1 {
+            StringOrList::One(values.remove(0))
+        } else {
+            StringOrList::Many(values)
+        }
+    }
+}
+
+/// A snippet entry as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnippetDefinition {
+    prefix: StringOrList,
+    body: StringOrList,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+}
+
+/// A snippet as edited in the snippet editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetInput {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    pub body: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Only used in `global`: languages the snippet applies to, comma-separated
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    /// File the snippet comes from: a language id or `global`
+    pub source: String,
+    pub prefixes: Vec<String>,
+    pub body: String,
+    pub description: Option<String>,
+    pub scope: Option<String>,
+    /// `None` when the entry or its body is malformed; see `error`
+    pub parsed: Option<ParsedSnippet>,
+    pub error: Option<String>,
+}
+
+/// Language ids become file names, so they are kept to a safe character set
+fn is_valid_language(language: &str) -> bool {
+    !language.is_empty()
+        && !language.starts_with('.')
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+' | '.'))
+}
+
+fn in_scope(scope: Option<&str>, language: &str) -> bool {
+    scope.is_none_or(|scope| {
+        let mut languages = scope.split(',').map(str::trim).filter(|s| !s.is_empty()).peekable();
+        languages.peek().is_none() || languages.any(|scoped| scoped == language)
+    })
+}
+
+fn to_snippet(source: &str, name: String, value: Value) -> Snippet {
+    let definition = match serde_json::from_value::<SnippetDefinition>(value) {
+        Ok(definition) => definition,
+        Err(e) => {
+            return Snippet {
+                name,
+                source: source.to_string(),
+                prefixes: Vec::new(),
+                body: String::new(),
+                description: None,
+                scope: None,
+                parsed: None,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    let body = definition.body.into_vec().join("\n");
+    let parsed = parse_snippet(&body);
+    Snippet {
+        name,
+        source: source.to_string(),
+        prefixes: definition.prefix.into_vec(),
+        body,
+        description: definition.description,
+        scope: definition.scope,
+        error: parsed.as_ref().err().map(|e| e.to_string()),
+        parsed: parsed.ok(),
+    }
+}
+
+pub struct SnippetService;
+
+impl SnippetService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn snippets_path(app: &AppHandle, language: &str) -> Result<PathBuf, SnippetError> {
+        if !is_valid_language(language) {
+            return Err(SnippetError::InvalidLanguage(language.to_string()));
+        }
+        let config_dir = app.path().app_config_dir().map_err(|_| SnippetError::NoConfigDirectory)?;
+        Ok(config_dir.join(SNIPPETS_DIR).join(format!("{}.json", language)))
+    }
+
+    fn read_file(app: &AppHandle, language: &str) -> Result<Map<String, Value>, SnippetError> {
+        let path = Self::snippets_path(app, language)?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+            Err(e) => return Err(SnippetError::IOError(e.to_string())),
+        };
+        serde_json::from_str(&jsonc::strip(&content))
+            .map_err(|e| SnippetError::InvalidFile(format!("{}: {}", path.to_string_lossy(), e)))
+    }
+
+    /// Rewrites the whole file; comments in hand-edited files are not preserved
+    fn write_file(app: &AppHandle, language: &str, snippets: &Map<String, Value>) -> Result<(), SnippetError> {
+        let path = Self::snippets_path(app, language)?;
+        let io_error = |e: std::io::Error| SnippetError::IOError(e.to_string());
+        if snippets.is_empty() {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+                _ => Ok(()),
+            };
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let content =
+            serde_json::to_string_pretty(snippets).map_err(|e| SnippetError::IOError(e.to_string()))?;
+        write_atomic(&path, content).map_err(io_error)
+    }
+
+    /// Snippets for a language, followed by the global snippets scoped to it
+    pub fn get_snippets(&self, app: &AppHandle, language: &str) -> Result<Vec<Snippet>, SnippetError> {
+        let mut snippets: Vec<Snippet> = Self::read_file(app, language)?
+            .into_iter()
+            .map(|(name, value)| to_snippet(language, name, value))
+            .collect();
+        if language != GLOBAL_SNIPPETS {
+            snippets.extend(
+                Self::read_file(app, GLOBAL_SNIPPETS)?
+                    .into_iter()
+                    .map(|(name, value)| to_snippet(GLOBAL_SNIPPETS, name, value))
+                    .filter(|snippet| in_scope(snippet.scope.as_deref(), language)),
+            );
+        }
+        Ok(snippets)
+    }
+
+    /// Add or replace the snippet with the same name; the body must parse
+    pub fn save_snippet(
+        &self,
+        app: &AppHandle,
+        language: &str,
+        snippet: SnippetInput,
+    ) -> Result<Snippet, SnippetError> {
+        if snippet.name.trim().is_empty() {
+            return Err(SnippetError::InvalidSnippet("name is empty".to_string()));
+        }
+        let prefixes: Vec<String> = snippet
+            .prefixes
+            .into_iter()
+            .map(|prefix| prefix.trim().to_string())
+            .filter(|prefix| !prefix.is_empty())
+            .collect();
+        if prefixes.is_empty() {
+            return Err(SnippetError::InvalidSnippet("at least one prefix is required".to_string()));
+        }
+        parse_snippet(&snippet.body)?;
+
+        // Bodies are stored one line per entry, which is what people expect when editing the file by hand
+        let lines = snippet.body.split('\n').map(|line| line.trim_end_matches('\r').to_string()).collect();
+        let definition = SnippetDefinition {
+            prefix: StringOrList::from_vec(prefixes),
+            body: StringOrList::from_vec(lines),
+            description: snippet.description.filter(|d| !d.trim().is_empty()),
+            scope: snippet.scope.filter(|s| !s.trim().is_empty()),
+        };
+        let value = serde_json::to_value(&definition).map_err(|e| SnippetError::IOError(e.to_string()))?;
+
+        let mut snippets = Self::read_file(app, language)?;
+        snippets.insert(snippet.name.clone(), value.clone());
+        Self::write_file(app, language, &snippets)?;
+        Ok(to_snippet(language, snippet.name, value))
+    }
+
+    pub fn delete_snippet(&self, app: &AppHandle, language: &str, name: &str) -> Result<(), SnippetError> {
+        let mut snippets = Self::read_file(app, language)?;
+        if snippets.remove(name).is_none() {
+            return Err(SnippetError::NotFound(name.to_string()));
+        }
+        Self::write_file(app, language, &snippets)
+    }
+}
+
+impl Default for SnippetService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
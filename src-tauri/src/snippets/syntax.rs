@@ -0,0 +1,237 @@
+/**
+ * TextMate snippet syntax: tab stops, placeholders, choices, and variables
+ *
+ * - `$1`, `${1}` tab stop; `$0` is the final cursor position
+ * - `${1:default}` placeholder, which may nest other parts
+ * - `${1|one,two|}` choice
+ * - `$NAME`, `${NAME}`, `${NAME:default}` variable
+ * - `\$`, `\}`, and `\\` escape; inside choices `\,` and `\|` do too
+ */
+
+use serde::{Deserialize, Serialize};
+
+use super::SnippetError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SnippetPart {
+    Text { value: String },
+    TabStop { index: u32 },
+    Placeholder { index: u32, children: Vec<SnippetPart> },
+    Choice { index: u32, options: Vec<String> },
+    Variable { name: String, default: Option<Vec<SnippetPart>> },
+}
+
+/// A snippet body ready for the editor to expand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedSnippet {
+    pub parts: Vec<SnippetPart>,
+    /// Tab stop indexes in visiting order; `0` comes last and is always present
+    pub tab_stops: Vec<u32>,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+fn push_text(parts: &mut Vec<SnippetPart>, c: char) {
+    if let Some(SnippetPart::Text { value }) = parts.last_mut() {
+        value.push(c);
+    } else {
+        parts.push(SnippetPart::Text { value: c.to_string() });
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> SnippetError {
+        SnippetError::Syntax {
+            position: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    /// Parts up to the end of input, or up to the unescaped `}` closing a placeholder when `nested`
+    fn parse_parts(&mut self, nested: bool) -> Result<Vec<SnippetPart>, SnippetError> {
+        let mut parts = Vec::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '}' if nested => return Ok(parts),
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(escaped @ ('$' | '}' | '\\')) => {
+                            push_text(&mut parts, escaped);
+                            self.pos += 1;
+                        }
+                        _ => push_text(&mut parts, '\\'),
+                    }
+                }
+                '$' => {
+                    let start = self.pos;
+                    match self.parse_dollar()? {
+                        Some(part) => parts.push(part),
+                        None => {
+                            // Not a snippet construct, so the `$` is literal text
+                            self.pos = start + 1;
+                            push_text(&mut parts, '$');
+                        }
+                    }
+                }
+                c => {
+                    push_text(&mut parts, c);
+                    self.pos += 1;
+                }
+            }
+        }
+        if nested {
+            return Err(self.error("unterminated placeholder, expected '}'"));
+        }
+        Ok(parts)
+    }
+
+    fn parse_index(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect::<String>().parse().ok()
+    }
+
+    fn parse_name(&mut self) -> Option<String> {
+        let start = self.pos;
+        if !self.peek().is_some_and(|c| c == '_' || c.is_ascii_alphabetic()) {
+            return None;
+        }
+        while self.peek().is_some_and(|c| c == '_' || c.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        Some(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn expect_close(&mut self) -> Result<(), SnippetError> {
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error("expected '}'"))
+        }
+    }
+
+    /// Parse the construct starting at a `$`, or `None` when it is just a dollar sign
+    fn parse_dollar(&mut self) -> Result<Option<SnippetPart>, SnippetError> {
+        self.pos += 1;
+        if let Some(index) = self.parse_index() {
+            return Ok(Some(SnippetPart::TabStop { index }));
+        }
+        if let Some(name) = self.parse_name() {
+            return Ok(Some(SnippetPart::Variable { name, default: None }));
+        }
+        if self.peek() != Some('{') {
+            return Ok(None);
+        }
+        self.pos += 1;
+
+        if let Some(index) = self.parse_index() {
+            return match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    Ok(Some(SnippetPart::TabStop { index }))
+                }
+                Some(':') => {
+                    self.pos += 1;
+                    let children = self.parse_parts(true)?;
+                    self.expect_close()?;
+                    Ok(Some(SnippetPart::Placeholder { index, children }))
+                }
+                Some('|') => {
+                    self.pos += 1;
+                    let options = self.parse_choice()?;
+                    Ok(Some(SnippetPart::Choice { index, options }))
+                }
+                Some('/') => Err(self.error("transforms are not supported")),
+                _ => Err(self.error("expected '}', ':' or '|' after the tab stop number")),
+            };
+        }
+        if let Some(name) = self.parse_name() {
+            return match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    Ok(Some(SnippetPart::Variable { name, default: None }))
+                }
+                Some(':') => {
+                    self.pos += 1;
+                    let default = self.parse_parts(true)?;
+                    self.expect_close()?;
+                    Ok(Some(SnippetPart::Variable {
+                        name,
+                        default: Some(default),
+                    }))
+                }
+                Some('/') => Err(self.error("transforms are not supported")),
+                _ => Err(self.error("expected '}' or ':' after the variable name")),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Options of `${1|a,b|}`, positioned after the first `|`
+    fn parse_choice(&mut self) -> Result<Vec<String>, SnippetError> {
+        let mut options = vec![String::new()];
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => match self.peek() {
+                    Some(escaped @ ('$' | '}' | '\\' | ',' | '|')) => {
+                        options.last_mut().unwrap().push(escaped);
+                        self.pos += 1;
+                    }
+                    _ => options.last_mut().unwrap().push('\\'),
+                },
+                ',' => options.push(String::new()),
+                '|' if self.peek() == Some('}') => {
+                    self.pos += 1;
+                    return Ok(options);
+                }
+                c => options.last_mut().unwrap().push(c),
+            }
+        }
+        Err(self.error("unterminated choice, expected '|}'"))
+    }
+}
+
+fn collect_tab_stops(parts: &[SnippetPart], tab_stops: &mut Vec<u32>) {
+    for part in parts {
+        match part {
+            SnippetPart::TabStop { index } | SnippetPart::Choice { index, .. } => tab_stops.push(*index),
+            SnippetPart::Placeholder { index, children } => {
+                tab_stops.push(*index);
+                collect_tab_stops(children, tab_stops);
+            }
+            SnippetPart::Variable { default: Some(default), .. } => collect_tab_stops(default, tab_stops),
+            SnippetPart::Text { .. } | SnippetPart::Variable { default: None, .. } => {}
+        }
+    }
+}
+
+/// Parse a snippet body into parts the editor can expand
+pub fn parse_snippet(body: &str) -> Result<ParsedSnippet, SnippetError> {
+    let mut parser = Parser {
+        chars: body.chars().collect(),
+        pos: 0,
+    };
+    let parts = parser.parse_parts(false)?;
+
+    let mut tab_stops = Vec::new();
+    collect_tab_stops(&parts, &mut tab_stops);
+    tab_stops.retain(|index| *index != 0);
+    tab_stops.sort_unstable();
+    tab_stops.dedup();
+    // Without an explicit `$0` the cursor ends up after the inserted text
+    tab_stops.push(0);
+    Ok(ParsedSnippet { parts, tab_stops })
+}
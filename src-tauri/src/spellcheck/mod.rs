@@ -0,0 +1,378 @@
+/**
+ * Spell checking for CodeForge IDE
+ * Checks text against bundled English and software dictionaries plus a word list kept in the workspace.
+ * Languages with a tree-sitter grammar only have their comments and strings checked; anything else is
+ * checked as prose.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tree_sitter::Tree;
+
+use crate::syntax::SyntaxService;
+
+/// Words accepted in a workspace, one per line, relative to the workspace root
+pub const WORKSPACE_DICTIONARY_FILE: &str = ".codeforge/dictionary.txt";
+
+/// Bundled word lists, most frequent words first so suggestions can prefer common words
+const BUNDLED_DICTIONARIES: &[&str] = &[
+    include_str!("dictionaries/en.txt"),
+    include_str!("dictionaries/software.txt"),
+];
+
+/// Shorter words are mostly abbreviations and aren't checked
+const MIN_WORD_LENGTH: usize = 3;
+const MAX_EDIT_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Chunks containing these look like URLs, paths, emails, or placeholders rather than words
+const NON_PROSE_MARKERS: &[&str] = &["://", "@", "/", "\\", "$", "%", "{", "}"];
+
+/// Error types for spell checking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SpellcheckError {
+    InvalidWord(String),
+    ParseFailed(String),
+    IOError(String),
+}
+
+impl std::fmt::Display for SpellcheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SpellcheckError::InvalidWord(word) => write!(f, "Not a single word: '{}'", word),
+            SpellcheckError::ParseFailed(msg) => write!(f, "Failed to parse document: {}", msg),
+            SpellcheckError::IOError(msg) => write!(f, "IO Error: {}", msg),
+        }
+    }
+}
+
+/// A word not found in any dictionary; zero-based line with UTF-16 columns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Misspelling {
+    pub word: String,
+    pub line: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+    /// Closest dictionary words, best first, in the case of the misspelled word
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellcheckResult {
+    /// Whether checking was limited to comments and strings
+    pub scoped: bool,
+    pub checked_words: usize,
+    pub misspellings: Vec<Misspelling>,
+}
+
+struct Dictionary {
+    /// Position in the bundled lists, lower is more common
+    ranks: HashMap<&'static str, usize>,
+    /// Words by length, for finding suggestions
+    by_length: Vec<Vec<&'static str>>,
+}
+
+impl Dictionary {
+    fn bundled() -> Self {
+        let mut ranks = HashMap::new();
+        let mut by_length: Vec<Vec<&'static str>> = Vec::new();
+        for word in BUNDLED_DICTIONARIES.iter().flat_map(|dictionary| dictionary.lines()) {
+            if word.is_empty() || ranks.contains_key(word) {
+                continue;
+            }
+            ranks.insert(word, ranks.len());
+            if by_length.len() <= word.len() {
+                by_length.resize(word.len() + 1, Vec::new());
+            }
+            by_length[word.len()].push(word);
+        }
+        Self { ranks, by_length }
+    }
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions, and swaps of neighbours, or
+/// `None` when it is more than `max`
+fn edit_distance(a: &[u8], b: &[u8], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut before_previous: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        if current.iter().min().is_some_and(|&lowest| lowest > max) {
+            return None;
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[b.len()]).filter(|&distance| distance <= max)
+}
+
+/// `suggestion` in the case of `word`: all caps, capitalized, or as listed
+fn match_case(word: &str, suggestion: &str) -> String {
+    let mut chars = word.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    if first_upper && word.len() > 1 && chars.all(char::is_uppercase) {
+        return suggestion.to_uppercase();
+    }
+    let mut suggestion_chars = suggestion.chars();
+    match (first_upper, suggestion_chars.next()) {
+        (true, Some(first)) => first.to_uppercase().chain(suggestion_chars).collect(),
+        _ => suggestion.to_string(),
+    }
+}
+
+/// Split a run of letters into the words of a camelCase or PascalCase identifier; `HTTPServer` gives
+/// `HTTP` and `Server`. Offsets are relative to the run.
+fn split_identifier(run: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = run.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (offset, c) = chars[i];
+        let previous = chars[i - 1].1;
+        let next_lower = chars.get(i + 1).is_some_and(|(_, next)| next.is_lowercase());
+        if c.is_uppercase() && (previous.is_lowercase() || (previous.is_uppercase() && next_lower)) {
+            parts.push(start..offset);
+            start = offset;
+        }
+    }
+    parts.push(start..run.len());
+    parts
+}
+
+/// Byte ranges of the words in `text`, offset by `base`
+fn words(text: &str, base: usize) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for chunk in text.split(char::is_whitespace) {
+        let chunk_base = base + (chunk.as_ptr() as usize - text.as_ptr() as usize);
+        if chunk.is_empty() || NON_PROSE_MARKERS.iter().any(|marker| chunk.contains(marker)) {
+            continue;
+        }
+        // Runs of letters and apostrophes; digits, underscores, and punctuation end a word
+        let mut rest = chunk;
+        let mut offset = 0;
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic()) {
+            let run_length = rest[start..]
+                .find(|c: char| !c.is_alphabetic() && c != '\'')
+                .unwrap_or(rest.len() - start);
+            let run = &rest[start..start + run_length];
+            // Words in other scripts or with accents aren't in the dictionaries
+            if run.is_ascii() {
+                for part in split_identifier(run) {
+                    let word = &run[part.clone()];
+                    let trimmed_start = word.len() - word.trim_start_matches('\'').len();
+                    let trimmed = word.trim_matches('\'');
+                    let trimmed = trimmed.strip_suffix("'s").unwrap_or(trimmed);
+                    let word_start = chunk_base + offset + start + part.start + trimmed_start;
+                    words.push(word_start..word_start + trimmed.len());
+                }
+            }
+            offset += start + run_length;
+            rest = &rest[start + run_length..];
+        }
+    }
+    words
+}
+
+/// Byte ranges of the comments and strings in a syntax tree; HTML text counts as prose too
+fn prose_ranges(tree: &Tree) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let kind = cursor.node().kind();
+        let is_prose = kind.contains("comment") || kind.contains("string") || kind == "text";
+        if is_prose {
+            ranges.push(cursor.node().byte_range());
+        }
+        if !is_prose && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return ranges;
+            }
+        }
+    }
+}
+
+/// Path of the workspace word list
+fn dictionary_path(workspace: &str) -> PathBuf {
+    Path::new(workspace).join(WORKSPACE_DICTIONARY_FILE)
+}
+
+/// Words accepted in a workspace; `#` starts a comment line
+pub fn workspace_words(workspace: &str) -> Result<Vec<String>, SpellcheckError> {
+    match fs::read_to_string(dictionary_path(workspace)) {
+        Ok(content) => Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(SpellcheckError::IOError(e.to_string())),
+    }
+}
+
+fn store_workspace_words(workspace: &str, words: &[String]) -> Result<(), SpellcheckError> {
+    let path = dictionary_path(workspace);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| SpellcheckError::IOError(e.to_string()))?;
+    }
+    let content = words.iter().map(|word| format!("{}\n", word)).collect::<String>();
+    fs::write(&path, content).map_err(|e| SpellcheckError::IOError(e.to_string()))
+}
+
+/// Accept `word` in a workspace; adding a word that is already there changes nothing
+pub fn add_workspace_word(workspace: &str, word: &str) -> Result<Vec<String>, SpellcheckError> {
+    let word = word.trim();
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return Err(SpellcheckError::InvalidWord(word.to_string()));
+    }
+    let mut words = workspace_words(workspace)?;
+    if !words.iter().any(|existing| existing.eq_ignore_ascii_case(word)) {
+        words.push(word.to_string());
+        words.sort_by_key(|word| word.to_lowercase());
+        store_workspace_words(workspace, &words)?;
+    }
+    Ok(words)
+}
+
+pub fn remove_workspace_word(workspace: &str, word: &str) -> Result<Vec<String>, SpellcheckError> {
+    let mut words = workspace_words(workspace)?;
+    let count = words.len();
+    words.retain(|existing| !existing.eq_ignore_ascii_case(word.trim()));
+    if words.len() != count {
+        store_workspace_words(workspace, &words)?;
+    }
+    Ok(words)
+}
+
+pub struct SpellcheckService {
+    dictionary: OnceLock<Dictionary>,
+}
+
+impl SpellcheckService {
+    pub fn new() -> Self {
+        Self {
+            dictionary: OnceLock::new(),
+        }
+    }
+
+    fn dictionary(&self) -> &Dictionary {
+        self.dictionary.get_or_init(Dictionary::bundled)
+    }
+
+    /// Closest words to `word` (lowercase) by edit distance, then by how common they are
+    fn suggestions(&self, word: &str, custom: &[String]) -> Vec<String> {
+        let dictionary = self.dictionary();
+        let lengths = word.len().saturating_sub(MAX_EDIT_DISTANCE)..=word.len() + MAX_EDIT_DISTANCE;
+        let bundled = lengths
+            .filter_map(|length| dictionary.by_length.get(length))
+            .flatten()
+            .map(|candidate| (*candidate, dictionary.ranks[candidate]));
+        // Workspace words come first among equally close ones
+        let custom = custom.iter().map(|candidate| (candidate.as_str(), 0));
+
+        let mut scored: Vec<(usize, usize, &str)> = custom
+            .chain(bundled)
+            .filter_map(|(candidate, rank)| {
+                let distance = edit_distance(word.as_bytes(), candidate.as_bytes(), MAX_EDIT_DISTANCE)?;
+                Some((distance, rank, candidate))
+            })
+            .collect();
+        scored.sort();
+        let mut seen = HashSet::new();
+        scored
+            .into_iter()
+            .filter(|(_, _, candidate)| seen.insert(candidate.to_lowercase()))
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, _, candidate)| candidate.to_string())
+            .collect()
+    }
+
+    /// Check `content`. `language` is a syntax language id; with a grammar for it only comments and strings
+    /// are checked. Words listed for `workspace` are accepted.
+    pub fn check(
+        &self,
+        syntax: &SyntaxService,
+        content: &str,
+        language: Option<&str>,
+        workspace: Option<&str>,
+    ) -> Result<SpellcheckResult, SpellcheckError> {
+        let custom = match workspace {
+            Some(workspace) => workspace_words(workspace)?,
+            None => Vec::new(),
+        };
+        let accepted: HashSet<String> = custom.iter().map(|word| word.to_lowercase()).collect();
+
+        let grammar = language.and_then(|language| syntax.registry().get(language));
+        let ranges = match &grammar {
+            Some(grammar) => {
+                let tree = syntax
+                    .parse(grammar, content)
+                    .map_err(|e| SpellcheckError::ParseFailed(e.to_string()))?;
+                prose_ranges(&tree)
+            }
+            None => std::iter::once(0..content.len()).collect(),
+        };
+
+        let dictionary = self.dictionary();
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        let mut suggestions: HashMap<String, Vec<String>> = HashMap::new();
+        let mut result = SpellcheckResult {
+            scoped: grammar.is_some(),
+            checked_words: 0,
+            misspellings: Vec::new(),
+        };
+        for range in ranges {
+            for word_range in words(&content[range.clone()], range.start) {
+                let word = &content[word_range.clone()];
+                // Acronyms such as `HTTP` are left alone
+                if word.len() < MIN_WORD_LENGTH || word.chars().all(|c| c.is_uppercase() || c == '\'') {
+                    continue;
+                }
+                result.checked_words += 1;
+                let lower = word.to_lowercase();
+                if dictionary.ranks.contains_key(lower.as_str()) || accepted.contains(&lower) {
+                    continue;
+                }
+                let line = line_starts.partition_point(|&start| start <= word_range.start) - 1;
+                let column = |offset: usize| content[line_starts[line]..offset].encode_utf16().count();
+                let suggested = suggestions
+                    .entry(lower.clone())
+                    .or_insert_with(|| self.suggestions(&lower, &custom));
+                result.misspellings.push(Misspelling {
+                    word: word.to_string(),
+                    line,
+                    start_column: column(word_range.start),
+                    end_column: column(word_range.end),
+                    suggestions: suggested.iter().map(|suggestion| match_case(word, suggestion)).collect(),
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Default for SpellcheckService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,375 @@
+/**
+ * Export of source files to HTML or PDF with syntax highlighting and line numbers
+ * For sharing snippets and printing code reviews
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::highlight::{self, HighlightToken};
+use super::{SyntaxError, SyntaxService};
+use crate::types::{FileOperationResult, FileSystemError};
+
+const TAB_WIDTH: usize = 4;
+
+/// PDF page geometry in points (A4)
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 40.0;
+const FONT_SIZE: f32 = 9.0;
+const LINE_HEIGHT: f32 = 11.0;
+/// Advance width of a Courier glyph as a fraction of the font size
+const COURIER_ADVANCE: f32 = 0.6;
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// A visual PDF row: the line number on the first row of a line, and colored runs
+type PdfRow<'a> = (Option<usize>, Vec<(Option<&'a str>, String)>);
+
+/// A run of text on one line sharing a token type
+pub(super) struct Segment {
+    pub(super) text: String,
+    pub(super) token_type: Option<String>,
+}
+
+/// Print-friendly (light background) color for a highlight capture name
+fn token_color(token_type: &str) -> (u8, u8, u8) {
+    match token_type.split('.').next().unwrap_or(token_type) {
+        "keyword" | "conditional" | "repeat" | "include" | "storageclass" => (0xaf, 0x00, 0xdb),
+        "string" | "character" => (0xa3, 0x15, 0x15),
+        "comment" => (0x00, 0x80, 0x00),
+        "number" | "float" | "boolean" | "constant" => (0x09, 0x86, 0x58),
+        "function" | "method" | "constructor" => (0x79, 0x5e, 0x26),
+        "type" | "namespace" | "module" | "tag" => (0x26, 0x7f, 0x99),
+        "property" | "field" | "attribute" | "label" => (0x00, 0x10, 0x80),
+        "variable" | "parameter" => (0x00, 0x10, 0x80),
+        "operator" | "punctuation" => (0x3b, 0x3b, 0x3b),
+        _ => (0x00, 0x00, 0x00),
+    }
+}
+
+const DEFAULT_COLOR: (u8, u8, u8) = (0x00, 0x00, 0x00);
+const LINE_NUMBER_COLOR: (u8, u8, u8) = (0x99, 0x99, 0x99);
+
+impl SyntaxService {
+    /// Render a file to HTML or PDF, written to `destination` or next to the source file
+    ///
+    /// Files without a grammar are exported without highlighting.
+    pub fn export_file(
+        &self,
+        path: &str,
+        format: ExportFormat,
+        destination: Option<&str>,
+    ) -> Result<FileOperationResult, SyntaxError> {
+        let source_path = Path::new(path);
+        let source = fs::read_to_string(source_path).map_err(map_io_error)?;
+        let tokens = match self.registry.for_path(source_path) {
+            Some(grammar) => {
+                let tree = self.parse(&grammar, &source)?;
+                highlight::highlight(&self.registry, &grammar, &tree, &source, None)?
+            }
+            None => Vec::new(),
+        };
+
+        let lines = segment_lines(&source, &tokens);
+        let title = source_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let output = match format {
+            ExportFormat::Html => render_html(&title, &lines).into_bytes(),
+            ExportFormat::Pdf => render_pdf(&title, &lines),
+        };
+
+        let destination = match destination {
+            Some(destination) => destination.to_string(),
+            None => format!("{}.{}", path, format.extension()),
+        };
+        fs::write(&destination, output).map_err(map_io_error)?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: format!("Exported {} to {}", title, destination),
+            path: Some(destination),
+            error_code: None,
+        })
+    }
+}
+
+fn map_io_error(e: io::Error) -> SyntaxError {
+    SyntaxError::FileSystem(match e.kind() {
+        io::ErrorKind::NotFound => FileSystemError::NotFound,
+        io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+        _ => FileSystemError::IOError(e.to_string()),
+    })
+}
+
+/// Split every line into highlighted segments, expanding tabs
+pub(super) fn segment_lines(source: &str, tokens: &[HighlightToken]) -> Vec<Vec<Segment>> {
+    let mut lines: Vec<Vec<Segment>> = Vec::new();
+    let mut token_index = 0;
+
+    for (line_number, line) in source.lines().enumerate() {
+        // UTF-16 column -> byte offset
+        let mut byte_offsets = Vec::with_capacity(line.len() + 1);
+        for (offset, c) in line.char_indices() {
+            byte_offsets.extend(std::iter::repeat_n(offset, c.len_utf16()));
+        }
+        byte_offsets.push(line.len());
+        let byte_at = |column: usize| byte_offsets[column.min(byte_offsets.len() - 1)];
+
+        let mut segments = Vec::new();
+        let mut cursor = 0;
+        while token_index < tokens.len() && tokens[token_index].line < line_number {
+            token_index += 1;
+        }
+        while token_index < tokens.len() && tokens[token_index].line == line_number {
+            let token = &tokens[token_index];
+            let start = byte_at(token.start_column).max(cursor);
+            let end = byte_at(token.start_column + token.length);
+            if start > cursor {
+                segments.push(Segment {
+                    text: line[cursor..start].to_string(),
+                    token_type: None,
+                });
+            }
+            if end > start {
+                segments.push(Segment {
+                    text: line[start..end].to_string(),
+                    token_type: Some(token.token_type.clone()),
+                });
+                cursor = end;
+            }
+            token_index += 1;
+        }
+        if cursor < line.len() {
+            segments.push(Segment {
+                text: line[cursor..].to_string(),
+                token_type: None,
+            });
+        }
+
+        expand_tabs(&mut segments);
+        lines.push(segments);
+    }
+    lines
+}
+
+fn expand_tabs(segments: &mut [Segment]) {
+    let mut column = 0;
+    for segment in segments {
+        if !segment.text.contains('\t') {
+            column += segment.text.chars().count();
+            continue;
+        }
+        let mut expanded = String::with_capacity(segment.text.len());
+        for c in segment.text.chars() {
+            if c == '\t' {
+                let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            } else {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+        segment.text = expanded;
+    }
+}
+
+pub(super) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_html(title: &str, lines: &[Vec<Segment>]) -> String {
+    let gutter = lines.len().to_string().len();
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+         body {{ margin: 0; background: #fff; }}\n\
+         pre {{ font: 12px/1.4 Menlo, Consolas, 'Courier New', monospace; margin: 16px; \
+         white-space: pre-wrap; }}\n\
+         .ln {{ color: #999; user-select: none; display: inline-block; min-width: {}ch; margin-right: 2ch; \
+         text-align: right; }}\n</style>\n</head>\n<body>\n<pre>",
+        escape_html(title),
+        gutter
+    );
+
+    for (index, segments) in lines.iter().enumerate() {
+        let _ = write!(html, "<span class=\"ln\">{}</span>", index + 1);
+        for segment in segments {
+            match &segment.token_type {
+                Some(token_type) => {
+                    let (r, g, b) = token_color(token_type);
+                    let style = if token_type.starts_with("comment") { "font-style: italic; " } else { "" };
+                    let _ = write!(
+                        html,
+                        "<span style=\"{}color: #{:02x}{:02x}{:02x}\">{}</span>",
+                        style,
+                        r,
+                        g,
+                        b,
+                        escape_html(&segment.text)
+                    );
+                }
+                None => html.push_str(&escape_html(&segment.text)),
+            }
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+/// Encode text as a PDF literal string in WinAnsi; characters outside Latin-1 become `?`
+fn pdf_string(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len() + 2);
+    encoded.push('(');
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            ' '..='~' => encoded.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(encoded, "\\{:03o}", c as u32);
+            }
+            _ => encoded.push('?'),
+        }
+    }
+    encoded.push(')');
+    encoded
+}
+
+fn pdf_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("{:.3} {:.3} {:.3} rg", r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+/// Lay out lines on A4 pages in Courier, wrapping long lines, and serialize a PDF document
+fn render_pdf(title: &str, lines: &[Vec<Segment>]) -> Vec<u8> {
+    let gutter = lines.len().to_string().len() + 2;
+    let glyph_width = FONT_SIZE * COURIER_ADVANCE;
+    let columns = (((PAGE_WIDTH - 2.0 * PAGE_MARGIN) / glyph_width) as usize).saturating_sub(gutter).max(20);
+    // First row is the page header
+    let rows_per_page = ((PAGE_HEIGHT - 2.0 * PAGE_MARGIN) / LINE_HEIGHT) as usize - 2;
+
+    let mut rows: Vec<PdfRow> = Vec::new();
+    for (index, segments) in lines.iter().enumerate() {
+        let mut row: Vec<(Option<&str>, String)> = Vec::new();
+        let mut width = 0;
+        let mut first = true;
+        for segment in segments {
+            for c in segment.text.chars() {
+                if width == columns {
+                    rows.push((first.then_some(index + 1), std::mem::take(&mut row)));
+                    first = false;
+                    width = 0;
+                }
+                match row.last_mut() {
+                    Some((token_type, text)) if *token_type == segment.token_type.as_deref() => text.push(c),
+                    _ => row.push((segment.token_type.as_deref(), c.to_string())),
+                }
+                width += 1;
+            }
+        }
+        rows.push((first.then_some(index + 1), row));
+    }
+
+    let pages: Vec<_> = rows.chunks(rows_per_page.max(1)).collect();
+    let page_count = pages.len().max(1);
+    let mut contents = Vec::with_capacity(page_count);
+    for page_index in 0..page_count {
+        let mut stream = String::new();
+        let top = PAGE_HEIGHT - PAGE_MARGIN;
+        let _ = writeln!(
+            stream,
+            "BT /F1 {} Tf {} {} {} Td {} Tj ET",
+            FONT_SIZE,
+            pdf_color(LINE_NUMBER_COLOR),
+            PAGE_MARGIN,
+            top,
+            pdf_string(&format!("{}  -  page {} of {}", title, page_index + 1, page_count))
+        );
+
+        let page_rows = pages.get(page_index).copied().unwrap_or(&[]);
+        for (row_index, (line_number, segments)) in page_rows.iter().enumerate() {
+            let y = top - LINE_HEIGHT * (row_index as f32 + 2.0);
+            let _ = write!(stream, "BT {} {} Td ", PAGE_MARGIN, y);
+            let number = line_number.map(|number| number.to_string()).unwrap_or_default();
+            let _ = write!(
+                stream,
+                "/F1 {} Tf {} {} Tj ",
+                FONT_SIZE,
+                pdf_color(LINE_NUMBER_COLOR),
+                pdf_string(&format!("{:>width$}  ", number, width = gutter - 2))
+            );
+            for (token_type, text) in segments {
+                let color = token_type.map(token_color).unwrap_or(DEFAULT_COLOR);
+                let font = if token_type.is_some_and(|t| t.starts_with("comment")) { "/F2" } else { "/F1" };
+                let _ = write!(stream, "{} {} Tf {} {} Tj ", font, FONT_SIZE, pdf_color(color), pdf_string(text));
+            }
+            stream.push_str("ET\n");
+        }
+        contents.push(stream);
+    }
+
+    // Objects: 1 catalog, 2 page tree, 3-4 fonts, then a page and its content stream per page
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..page_count).map(|i| format!("{} 0 R", 5 + i * 2)).collect::<Vec<_>>().join(" "),
+            page_count
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Oblique /Encoding /WinAnsiEncoding >>".to_string(),
+    ];
+    for (index, stream) in contents.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> \
+             /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            6 + index * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", stream.len(), stream));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", index + 1, object);
+    }
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    pdf.into_bytes()
+}
@@ -0,0 +1,196 @@
+/**
+ * Grammar registry for the syntax service
+ * Maps language ids and file extensions to tree-sitter grammars and queries
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tree_sitter::{Language, Query};
+
+use super::SyntaxError;
+
+/// A tree-sitter grammar together with the queries bundled for it
+pub struct LanguageGrammar {
+    pub id: &'static str,
+    pub extensions: &'static [&'static str],
+    pub language: Language,
+    pub highlights_query: String,
+    pub tags_query: Option<String>,
+}
+
+/// Registry of every grammar compiled into the backend
+pub struct GrammarRegistry {
+    grammars: HashMap<&'static str, Arc<LanguageGrammar>>,
+    highlight_queries: Mutex<HashMap<&'static str, Arc<Query>>>,
+    tags_queries: Mutex<HashMap<&'static str, Arc<Query>>>,
+}
+
+impl GrammarRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            grammars: HashMap::new(),
+            highlight_queries: Mutex::new(HashMap::new()),
+            tags_queries: Mutex::new(HashMap::new()),
+        };
+
+        registry.register(LanguageGrammar {
+            id: "rust",
+            extensions: &["rs"],
+            language: tree_sitter_rust::LANGUAGE.into(),
+            highlights_query: tree_sitter_rust::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: Some(tree_sitter_rust::TAGS_QUERY.to_string()),
+        });
+        registry.register(LanguageGrammar {
+            id: "javascript",
+            extensions: &["js", "jsx", "mjs", "cjs"],
+            language: tree_sitter_javascript::LANGUAGE.into(),
+            highlights_query: format!(
+                "{}\n{}",
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+                tree_sitter_javascript::JSX_HIGHLIGHT_QUERY
+            ),
+            tags_query: Some(tree_sitter_javascript::TAGS_QUERY.to_string()),
+        });
+        // The TypeScript queries only cover TS-specific syntax and extend the JavaScript ones
+        registry.register(LanguageGrammar {
+            id: "typescript",
+            extensions: &["ts", "mts", "cts"],
+            language: tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            highlights_query: format!(
+                "{}\n{}",
+                tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                tree_sitter_javascript::HIGHLIGHT_QUERY
+            ),
+            tags_query: Some(format!(
+                "{}\n{}",
+                tree_sitter_typescript::TAGS_QUERY,
+                tree_sitter_javascript::TAGS_QUERY
+            )),
+        });
+        registry.register(LanguageGrammar {
+            id: "typescriptreact",
+            extensions: &["tsx"],
+            language: tree_sitter_typescript::LANGUAGE_TSX.into(),
+            highlights_query: format!(
+                "{}\n{}\n{}",
+                tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+                tree_sitter_javascript::JSX_HIGHLIGHT_QUERY
+            ),
+            tags_query: Some(format!(
+                "{}\n{}",
+                tree_sitter_typescript::TAGS_QUERY,
+                tree_sitter_javascript::TAGS_QUERY
+            )),
+        });
+        registry.register(LanguageGrammar {
+            id: "python",
+            extensions: &["py", "pyi", "pyw"],
+            language: tree_sitter_python::LANGUAGE.into(),
+            highlights_query: tree_sitter_python::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: Some(tree_sitter_python::TAGS_QUERY.to_string()),
+        });
+        registry.register(LanguageGrammar {
+            id: "go",
+            extensions: &["go"],
+            language: tree_sitter_go::LANGUAGE.into(),
+            highlights_query: tree_sitter_go::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: Some(tree_sitter_go::TAGS_QUERY.to_string()),
+        });
+        registry.register(LanguageGrammar {
+            id: "c",
+            extensions: &["c", "h"],
+            language: tree_sitter_c::LANGUAGE.into(),
+            highlights_query: tree_sitter_c::HIGHLIGHT_QUERY.to_string(),
+            tags_query: Some(tree_sitter_c::TAGS_QUERY.to_string()),
+        });
+        registry.register(LanguageGrammar {
+            id: "json",
+            extensions: &["json", "jsonc"],
+            language: tree_sitter_json::LANGUAGE.into(),
+            highlights_query: tree_sitter_json::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: None,
+        });
+        registry.register(LanguageGrammar {
+            id: "css",
+            extensions: &["css"],
+            language: tree_sitter_css::LANGUAGE.into(),
+            highlights_query: tree_sitter_css::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: None,
+        });
+        registry.register(LanguageGrammar {
+            id: "html",
+            extensions: &["html", "htm"],
+            language: tree_sitter_html::LANGUAGE.into(),
+            highlights_query: tree_sitter_html::HIGHLIGHTS_QUERY.to_string(),
+            tags_query: None,
+        });
+
+        registry
+    }
+
+    /// Register a grammar, replacing any grammar with the same id
+    pub fn register(&mut self, grammar: LanguageGrammar) {
+        self.grammars.insert(grammar.id, Arc::new(grammar));
+    }
+
+    /// Look up a grammar by language id
+    pub fn get(&self, language_id: &str) -> Option<Arc<LanguageGrammar>> {
+        self.grammars.get(language_id).cloned()
+    }
+
+    /// Resolve the grammar for a file based on its extension
+    pub fn for_path(&self, path: &Path) -> Option<Arc<LanguageGrammar>> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        self.grammars
+            .values()
+            .find(|grammar| grammar.extensions.contains(&extension.as_str()))
+            .cloned()
+    }
+
+    /// Ids of all registered languages, sorted
+    pub fn language_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.grammars.keys().map(|id| id.to_string()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Get the compiled highlights query for a grammar, compiling it on first use
+    pub fn highlights_query(&self, grammar: &LanguageGrammar) -> Result<Arc<Query>, SyntaxError> {
+        let mut cache = self.highlight_queries.lock().unwrap();
+        if let Some(query) = cache.get(grammar.id) {
+            return Ok(query.clone());
+        }
+
+        let query = Query::new(&grammar.language, &grammar.highlights_query)
+            .map_err(|e| SyntaxError::QueryError(format!("{}: {}", grammar.id, e)))?;
+        let query = Arc::new(query);
+        cache.insert(grammar.id, query.clone());
+        Ok(query)
+    }
+
+    /// Get the compiled tags query for a grammar, if it ships one
+    pub fn tags_query(&self, grammar: &LanguageGrammar) -> Result<Option<Arc<Query>>, SyntaxError> {
+        let Some(source) = grammar.tags_query.as_deref() else {
+            return Ok(None);
+        };
+
+        let mut cache = self.tags_queries.lock().unwrap();
+        if let Some(query) = cache.get(grammar.id) {
+            return Ok(Some(query.clone()));
+        }
+
+        let query = Query::new(&grammar.language, source)
+            .map_err(|e| SyntaxError::QueryError(format!("{}: {}", grammar.id, e)))?;
+        let query = Arc::new(query);
+        cache.insert(grammar.id, query.clone());
+        Ok(Some(query))
+    }
+}
+
+impl Default for GrammarRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,178 @@
+/**
+ * Syntax highlighting on top of tree-sitter highlight queries
+ * Produces flat, single-line token spans the frontend editor can paint directly
+ */
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Point, QueryCursor, StreamingIterator, Tree};
+
+use super::grammars::{GrammarRegistry, LanguageGrammar};
+use super::SyntaxError;
+
+/// Inclusive, zero-based range of lines to highlight
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// A highlighted span on a single line
+///
+/// Columns are measured in UTF-16 code units to match JavaScript string offsets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighlightToken {
+    pub line: usize,
+    pub start_column: usize,
+    pub length: usize,
+    pub token_type: String,
+}
+
+/// Highlighting response for a document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighlightResult {
+    pub path: String,
+    pub language: String,
+    pub tokens: Vec<HighlightToken>,
+}
+
+/// Byte offsets of the start of every line in a document
+pub(crate) struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(source: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { starts }
+    }
+
+    pub(crate) fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Byte range of a line, excluding its line terminator
+    pub(crate) fn line_bounds(&self, source: &str, line: usize) -> (usize, usize) {
+        let start = self.starts[line];
+        let mut end = self.starts.get(line + 1).map(|next| next - 1).unwrap_or(source.len());
+        if end > start && source.as_bytes()[end - 1] == b'\r' {
+            end -= 1;
+        }
+        (start, end)
+    }
+}
+
+/// Convert a byte offset within a line into a UTF-16 column
+pub(crate) fn utf16_column(line_text: &str, byte_offset: usize) -> usize {
+    line_text[..byte_offset.min(line_text.len())].encode_utf16().count()
+}
+
+/// Compute highlight tokens for a parsed document
+pub fn highlight(
+    registry: &GrammarRegistry,
+    grammar: &LanguageGrammar,
+    tree: &Tree,
+    source: &str,
+    range: Option<LineRange>,
+) -> Result<Vec<HighlightToken>, SyntaxError> {
+    let query = registry.highlights_query(grammar)?;
+    let names = query.capture_names();
+    let lines = LineIndex::new(source);
+
+    let mut cursor = QueryCursor::new();
+    if let Some(range) = range {
+        cursor.set_point_range(
+            Point::new(range.start_line, 0)..Point::new(range.end_line.saturating_add(1), 0),
+        );
+    }
+
+    // Gather every capture first; query order alone doesn't give us a stable precedence
+    let mut spans = Vec::new();
+    let mut captures = cursor.captures(&query, tree.root_node(), source.as_bytes());
+    while let Some((query_match, capture_index)) = captures.next() {
+        let capture = query_match.captures[*capture_index];
+        let name = names[capture.index as usize];
+        if name.starts_with('_') || name.starts_with("local") || name.starts_with("injection") {
+            continue;
+        }
+        let node = capture.node;
+        if node.start_byte() == node.end_byte() {
+            continue;
+        }
+        spans.push((node.start_byte(), node.end_byte(), query_match.pattern_index, name));
+    }
+
+    // Outer spans before the spans nested in them; for the same node the earlier pattern wins, matching
+    // tree-sitter's own highlighter
+    spans.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+    // The innermost capture wins its range and the enclosing ones keep the segments around it
+    let mut tokens = Vec::new();
+    let mut open: Vec<(usize, usize, &str)> = Vec::new();
+    let mut painted_until = 0;
+    for (start, end, _, name) in spans {
+        while let Some(&(_, outer_end, outer_name)) = open.last().filter(|outer| outer.1 <= start) {
+            push_line_tokens(&mut tokens, &lines, source, painted_until, outer_end, outer_name, range);
+            painted_until = painted_until.max(outer_end);
+            open.pop();
+        }
+        let mut end = end;
+        if let Some(&(outer_start, outer_end, outer_name)) = open.last() {
+            if outer_start == start && outer_end == end {
+                continue;
+            }
+            push_line_tokens(&mut tokens, &lines, source, painted_until, start, outer_name, range);
+            // Spans crossing the end of their enclosing span are cut there to keep the nesting
+            end = end.min(outer_end);
+        }
+        painted_until = start;
+        open.push((start, end, name));
+    }
+    while let Some((_, end, name)) = open.pop() {
+        push_line_tokens(&mut tokens, &lines, source, painted_until, end, name, range);
+        painted_until = painted_until.max(end);
+    }
+
+    Ok(tokens)
+}
+
+/// Split a byte span into per-line tokens
+fn push_line_tokens(
+    tokens: &mut Vec<HighlightToken>,
+    lines: &LineIndex,
+    source: &str,
+    start: usize,
+    end: usize,
+    token_type: &str,
+    range: Option<LineRange>,
+) {
+    let first_line = lines.starts.partition_point(|&s| s <= start) - 1;
+
+    for line in first_line..lines.line_count() {
+        let (line_start, line_end) = lines.line_bounds(source, line);
+        if line_start >= end {
+            break;
+        }
+        if let Some(range) = range {
+            if line < range.start_line || line > range.end_line {
+                continue;
+            }
+        }
+
+        let span_start = start.max(line_start);
+        let span_end = end.min(line_end);
+        if span_start >= span_end {
+            continue;
+        }
+
+        let line_text = &source[line_start..line_end];
+        let start_column = utf16_column(line_text, span_start - line_start);
+        let end_column = utf16_column(line_text, span_end - line_start);
+        tokens.push(HighlightToken {
+            line,
+            start_column,
+            length: end_column - start_column,
+            token_type: token_type.to_string(),
+        });
+    }
+}
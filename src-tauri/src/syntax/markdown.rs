@@ -0,0 +1,266 @@
+/**
+ * Markdown rendering for the preview pane
+ * CommonMark with the GitHub extensions people write READMEs with (tables, task lists, strikethrough,
+ * footnotes and `> [!NOTE]` alerts), and fenced code highlighted by the same grammars as the editor.
+ *
+ * The HTML is safe to show as it is: raw HTML in the document comes out as text, and links and images
+ * only keep URLs without a scheme or with one that can't run script. Headings get GitHub-style ids so
+ * `#section` links and the outline work.
+ */
+
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::export::{escape_html, segment_lines};
+use super::highlight;
+use super::{LanguageGrammar, SyntaxService};
+
+/// URL schemes links may use; anything else, like `javascript:`, is dropped
+const LINK_SCHEMES: [&str; 4] = ["http", "https", "mailto", "tel"];
+
+/// URL schemes images may use, besides `data:image/...`
+const IMAGE_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// How a document is rendered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownOptions {
+    /// Highlight fenced code whose language has a grammar
+    pub highlight_code: bool,
+    /// Curly quotes, dashes and ellipses
+    pub smart_punctuation: bool,
+    /// Prefix for relative link and image URLs, e.g. the preview server's URL of the document's directory
+    pub base_url: Option<String>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            highlight_code: true,
+            smart_punctuation: false,
+            base_url: None,
+        }
+    }
+}
+
+/// A heading of the document, for its outline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownHeading {
+    pub level: usize,
+    pub text: String,
+    /// The heading's `id` in the HTML
+    pub id: String,
+}
+
+/// A rendered document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub headings: Vec<MarkdownHeading>,
+}
+
+impl SyntaxService {
+    /// Render Markdown to sanitized HTML
+    pub fn render_markdown(&self, source: &str, options: &MarkdownOptions) -> RenderedMarkdown {
+        let mut parser_options = Options::ENABLE_TABLES
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_GFM
+            | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+        if options.smart_punctuation {
+            parser_options |= Options::ENABLE_SMART_PUNCTUATION;
+        }
+
+        let mut events = Vec::new();
+        let mut headings = Vec::new();
+        let mut slugs = HashMap::new();
+        let mut parser = Parser::new_ext(source, parser_options);
+        while let Some(event) = parser.next() {
+            match event {
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    let mut code = String::new();
+                    for event in parser.by_ref() {
+                        match event {
+                            Event::Text(text) => code.push_str(&text),
+                            Event::End(TagEnd::CodeBlock) => break,
+                            _ => {}
+                        }
+                    }
+                    let language = match &kind {
+                        CodeBlockKind::Fenced(info) => fence_language(info),
+                        CodeBlockKind::Indented => None,
+                    };
+                    events.push(Event::Html(self.code_block(&code, language, options.highlight_code).into()));
+                }
+                Event::Start(Tag::Heading { level, classes, attrs, .. }) => {
+                    let mut content = Vec::new();
+                    let mut text = String::new();
+                    for event in parser.by_ref() {
+                        match event {
+                            Event::End(TagEnd::Heading(_)) => break,
+                            Event::Text(ref part) | Event::Code(ref part) => text.push_str(part),
+                            _ => {}
+                        }
+                        content.push(event);
+                    }
+                    let id = unique_slug(&text, &mut slugs);
+                    headings.push(MarkdownHeading {
+                        level: level as usize,
+                        text,
+                        id: id.clone(),
+                    });
+                    events.push(Event::Start(Tag::Heading {
+                        level,
+                        id: Some(id.into()),
+                        classes,
+                        attrs,
+                    }));
+                    events.extend(content.into_iter().map(|event| sanitize(event, options)));
+                    events.push(Event::End(TagEnd::Heading(level)));
+                }
+                // Front matter is for tools, not readers
+                Event::Start(Tag::MetadataBlock(_)) => {
+                    for event in parser.by_ref() {
+                        if matches!(event, Event::End(TagEnd::MetadataBlock(_))) {
+                            break;
+                        }
+                    }
+                }
+                event => events.push(sanitize(event, options)),
+            }
+        }
+
+        let mut output = String::with_capacity(source.len() * 3 / 2);
+        html::push_html(&mut output, events.into_iter());
+        RenderedMarkdown { html: output, headings }
+    }
+
+    /// A `<pre>` of code, with tokens as `hl-<type>` spans when it's highlighted
+    fn code_block(&self, code: &str, language: Option<&str>, highlight_code: bool) -> String {
+        let grammar = language.filter(|_| highlight_code).and_then(|language| self.code_grammar(language));
+        let language_class = language
+            .map(|language| format!(" class=\"language-{}\"", escape_html(language)))
+            .unwrap_or_default();
+        let tokens = grammar.and_then(|grammar| {
+            let tree = self.parse(&grammar, code).ok()?;
+            highlight::highlight(&self.registry, &grammar, &tree, code, None).ok()
+        });
+        let Some(tokens) = tokens else {
+            return format!("<pre><code{}>{}</code></pre>\n", language_class, escape_html(code));
+        };
+
+        let mut html = format!("<pre><code{}>", language_class);
+        for segments in segment_lines(code, &tokens) {
+            for segment in segments {
+                match &segment.token_type {
+                    Some(token_type) => {
+                        let category = token_type.split('.').next().unwrap_or(token_type);
+                        html.push_str(&format!(
+                            "<span class=\"hl-{}\">{}</span>",
+                            escape_html(category),
+                            escape_html(&segment.text)
+                        ));
+                    }
+                    None => html.push_str(&escape_html(&segment.text)),
+                }
+            }
+            html.push('\n');
+        }
+        html.push_str("</code></pre>\n");
+        html
+    }
+
+    /// The grammar for a fence's language, by language id or by file extension (`rust` or `rs`)
+    fn code_grammar(&self, language: &str) -> Option<Arc<LanguageGrammar>> {
+        let language = language.to_lowercase();
+        self.registry
+            .get(&language)
+            .or_else(|| self.registry.for_path(Path::new(&format!("code.{}", language))))
+    }
+}
+
+/// The language of a fence's info string: `rust` for "```rust,ignore" or "```rust {.numbered}"
+fn fence_language(info: &str) -> Option<&str> {
+    info.split([',', ' ', '\t', '{']).next().filter(|language| !language.is_empty())
+}
+
+/// Raw HTML becomes text, and unsafe link and image URLs are dropped
+fn sanitize<'a>(event: Event<'a>, options: &MarkdownOptions) -> Event<'a> {
+    match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url, &LINK_SCHEMES, false, options),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url, &IMAGE_SCHEMES, true, options),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+fn safe_url<'a>(url: CowStr<'a>, schemes: &[&str], image: bool, options: &MarkdownOptions) -> CowStr<'a> {
+    // Browsers ignore whitespace and control characters in a scheme, so `java\tscript:` is still one
+    let compact: String = url.chars().filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control()).collect();
+    let scheme = compact
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')));
+    match scheme {
+        Some(scheme) if schemes.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed)) => url,
+        Some(scheme) if image && scheme.eq_ignore_ascii_case("data") => {
+            if compact[5..].to_ascii_lowercase().starts_with("image/") {
+                url
+            } else {
+                CowStr::Borrowed("")
+            }
+        }
+        Some(_) => CowStr::Borrowed(""),
+        None => match &options.base_url {
+            Some(base) if !url.starts_with(['#', '/']) => {
+                format!("{}/{}", base.trim_end_matches('/'), url).into()
+            }
+            _ => url,
+        },
+    }
+}
+
+/// GitHub's heading ids: lowercase, punctuation dropped, spaces as dashes, and `-1`, `-2`... for repeats
+fn unique_slug(text: &str, slugs: &mut HashMap<String, usize>) -> String {
+    let slug: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect();
+    let count = slugs.entry(slug.clone()).or_insert(0);
+    let id = match *count {
+        0 => slug,
+        repeat => format!("{}-{}", slug, repeat),
+    };
+    *count += 1;
+    id
+}
@@ -0,0 +1,129 @@
+/**
+ * Syntax Service for CodeForge IDE
+ * Tree-sitter parsing shared by backend features that need to understand source code
+ */
+
+mod export;
+mod grammars;
+mod highlight;
+mod markdown;
+mod outline;
+mod rename;
+
+pub use export::ExportFormat;
+pub use grammars::{GrammarRegistry, LanguageGrammar};
+pub use highlight::{HighlightResult, HighlightToken, LineRange};
+pub use markdown::{MarkdownOptions, RenderedMarkdown};
+pub use outline::{DocumentSymbol, FoldingRange, SourceRange};
+pub use rename::WorkspaceRenamePreview;
+
+use crate::types::FileSystemError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use tree_sitter::{Parser, Tree};
+
+/// Error types for syntax operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SyntaxError {
+    UnsupportedLanguage(String),
+    ParseFailed,
+    QueryError(String),
+    InvalidIdentifier(String),
+    FileSystem(FileSystemError),
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SyntaxError::UnsupportedLanguage(path) => write!(f, "No grammar available for {}", path),
+            SyntaxError::ParseFailed => write!(f, "Failed to parse document"),
+            SyntaxError::QueryError(msg) => write!(f, "Invalid query: {}", msg),
+            SyntaxError::InvalidIdentifier(name) => write!(f, "Not a valid identifier: {}", name),
+            SyntaxError::FileSystem(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<FileSystemError> for SyntaxError {
+    fn from(err: FileSystemError) -> Self {
+        SyntaxError::FileSystem(err)
+    }
+}
+
+pub struct SyntaxService {
+    registry: GrammarRegistry,
+}
+
+impl SyntaxService {
+    pub fn new() -> Self {
+        Self {
+            registry: GrammarRegistry::new(),
+        }
+    }
+
+    pub fn registry(&self) -> &GrammarRegistry {
+        &self.registry
+    }
+
+    /// Parse source text with the given grammar
+    pub fn parse(&self, grammar: &LanguageGrammar, source: &str) -> Result<Tree, SyntaxError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&grammar.language)
+            .map_err(|e| SyntaxError::QueryError(e.to_string()))?;
+        parser.parse(source, None).ok_or(SyntaxError::ParseFailed)
+    }
+
+    /// Read a file from disk and resolve its grammar
+    pub fn load_document(&self, path: &str) -> Result<(Arc<LanguageGrammar>, String), SyntaxError> {
+        let file_path = Path::new(path);
+        let grammar = self
+            .registry
+            .for_path(file_path)
+            .ok_or_else(|| SyntaxError::UnsupportedLanguage(path.to_string()))?;
+
+        let source = fs::read_to_string(file_path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => FileSystemError::NotFound,
+            io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+            _ => FileSystemError::IOError(e.to_string()),
+        })?;
+
+        Ok((grammar, source))
+    }
+
+    /// Highlight a file, optionally restricted to a range of lines
+    pub fn highlight_file(&self, path: &str, range: Option<LineRange>) -> Result<HighlightResult, SyntaxError> {
+        let (grammar, source) = self.load_document(path)?;
+        let tree = self.parse(&grammar, &source)?;
+        let tokens = highlight::highlight(&self.registry, &grammar, &tree, &source, range)?;
+
+        Ok(HighlightResult {
+            path: path.to_string(),
+            language: grammar.id.to_string(),
+            tokens,
+        })
+    }
+
+    /// Build the symbol outline for a file
+    pub fn document_symbols(&self, path: &str) -> Result<Vec<DocumentSymbol>, SyntaxError> {
+        let (grammar, source) = self.load_document(path)?;
+        let tree = self.parse(&grammar, &source)?;
+        outline::document_symbols(&self.registry, &grammar, &tree, &source)
+    }
+
+    /// Compute the foldable regions of a file
+    pub fn folding_ranges(&self, path: &str) -> Result<Vec<FoldingRange>, SyntaxError> {
+        let (grammar, source) = self.load_document(path)?;
+        let tree = self.parse(&grammar, &source)?;
+        Ok(outline::folding_ranges(&tree))
+    }
+}
+
+impl Default for SyntaxService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
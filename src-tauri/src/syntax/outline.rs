@@ -0,0 +1,274 @@
+/**
+ * Document outline and folding ranges derived from tree-sitter trees
+ */
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Point, QueryCursor, StreamingIterator, Tree};
+
+use super::grammars::{GrammarRegistry, LanguageGrammar};
+use super::highlight::{utf16_column, LineIndex};
+use super::SyntaxError;
+
+/// Zero-based source range with UTF-16 columns
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SourceRange {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// A symbol in the document outline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: String,
+    pub range: SourceRange,
+    pub selection_range: SourceRange,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// A foldable region, inclusive of both lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FoldingRange {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub kind: Option<String>,
+}
+
+/// Node kinds that group import statements into a single fold
+const IMPORT_KINDS: &[&str] = &[
+    "use_declaration",
+    "import_statement",
+    "import_from_statement",
+    "import_declaration",
+    "preproc_include",
+    "extern_crate_declaration",
+];
+
+/// Node kinds that are foldable even though they aren't delimited by brackets
+const BLOCK_KINDS: &[&str] = &["block", "element", "script_element", "style_element"];
+
+fn source_range(lines: &LineIndex, source: &str, start: Point, end: Point) -> SourceRange {
+    let column = |point: Point| {
+        let (line_start, line_end) = lines.line_bounds(source, point.row);
+        utf16_column(&source[line_start..line_end], point.column)
+    };
+
+    SourceRange {
+        start_line: start.row,
+        start_column: column(start),
+        end_line: end.row,
+        end_column: column(end),
+    }
+}
+
+/// Refine the generic tags-query kind using the concrete node kind
+fn symbol_kind(capture_kind: &str, node: Node) -> String {
+    let node_kind = node.kind();
+    if node_kind.contains("struct") {
+        "struct".to_string()
+    } else if node_kind.contains("enum") {
+        "enum".to_string()
+    } else if node_kind.contains("trait") || node_kind.contains("interface") {
+        "interface".to_string()
+    } else if node_kind.contains("type_item") || node_kind.contains("type_alias") {
+        "type".to_string()
+    } else {
+        capture_kind.to_string()
+    }
+}
+
+/// Build the symbol outline for a parsed document using the grammar's tags query
+pub fn document_symbols(
+    registry: &GrammarRegistry,
+    grammar: &LanguageGrammar,
+    tree: &Tree,
+    source: &str,
+) -> Result<Vec<DocumentSymbol>, SyntaxError> {
+    let Some(query) = registry.tags_query(grammar)? else {
+        return Ok(Vec::new());
+    };
+    let names = query.capture_names();
+    let lines = LineIndex::new(source);
+
+    let mut definitions: Vec<(Node, Node, String)> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let mut definition = None;
+        let mut name = None;
+        for capture in query_match.captures {
+            let capture_name = names[capture.index as usize];
+            if let Some(kind) = capture_name.strip_prefix("definition.") {
+                definition = Some((capture.node, kind));
+            } else if capture_name == "name" {
+                name = Some(capture.node);
+            }
+        }
+
+        if let (Some((node, kind)), Some(name_node)) = (definition, name) {
+            // Several patterns can match the same node; the first (most specific) one wins
+            if definitions.iter().any(|(existing, _, _)| existing.id() == node.id()) {
+                continue;
+            }
+            definitions.push((node, name_node, symbol_kind(kind, node)));
+        }
+    }
+
+    definitions.sort_by(|a, b| {
+        a.0.start_byte()
+            .cmp(&b.0.start_byte())
+            .then(b.0.end_byte().cmp(&a.0.end_byte()))
+    });
+
+    // Nest symbols by range containment using a stack of open parents
+    let mut roots: Vec<DocumentSymbol> = Vec::new();
+    let mut stack: Vec<(usize, DocumentSymbol)> = Vec::new();
+    for (node, name_node, kind) in definitions {
+        while let Some((end_byte, _)) = stack.last() {
+            if node.start_byte() < *end_byte {
+                break;
+            }
+            let (_, finished) = stack.pop().unwrap();
+            attach(&mut roots, &mut stack, finished);
+        }
+
+        let name = name_node
+            .utf8_text(source.as_bytes())
+            .unwrap_or_default()
+            .to_string();
+        stack.push((
+            node.end_byte(),
+            DocumentSymbol {
+                name,
+                kind,
+                range: source_range(&lines, source, node.start_position(), node.end_position()),
+                selection_range: source_range(
+                    &lines,
+                    source,
+                    name_node.start_position(),
+                    name_node.end_position(),
+                ),
+                children: Vec::new(),
+            },
+        ));
+    }
+    while let Some((_, finished)) = stack.pop() {
+        attach(&mut roots, &mut stack, finished);
+    }
+
+    Ok(roots)
+}
+
+fn attach(roots: &mut Vec<DocumentSymbol>, stack: &mut [(usize, DocumentSymbol)], symbol: DocumentSymbol) {
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.push(symbol),
+        None => roots.push(symbol),
+    }
+}
+
+/// Compute folding ranges for a parsed document
+pub fn folding_ranges(tree: &Tree) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    let mut import_run: Option<(usize, usize)> = None;
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+
+    loop {
+        let node = cursor.node();
+
+        if !visited_children {
+            if IMPORT_KINDS.contains(&node.kind()) {
+                import_run = match import_run {
+                    Some((start, end)) if node.start_position().row <= end + 1 => {
+                        Some((start, node.end_position().row))
+                    }
+                    previous => {
+                        push_import_fold(&mut ranges, previous);
+                        Some((node.start_position().row, node.end_position().row))
+                    }
+                };
+            } else if let Some(range) = fold_for_node(node) {
+                ranges.push(range);
+            }
+        }
+
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+            continue;
+        }
+        if !cursor.goto_parent() {
+            break;
+        }
+        visited_children = true;
+    }
+    push_import_fold(&mut ranges, import_run);
+
+    // Editors can only fold once per start line; keep the widest region
+    ranges.sort_by(|a, b| a.start_line.cmp(&b.start_line).then(b.end_line.cmp(&a.end_line)));
+    ranges.dedup_by_key(|range| range.start_line);
+    ranges
+}
+
+fn push_import_fold(ranges: &mut Vec<FoldingRange>, run: Option<(usize, usize)>) {
+    if let Some((start, end)) = run {
+        if end > start {
+            ranges.push(FoldingRange {
+                start_line: start,
+                end_line: end,
+                kind: Some("imports".to_string()),
+            });
+        }
+    }
+}
+
+fn fold_for_node(node: Node) -> Option<FoldingRange> {
+    let mut start_line = node.start_position().row;
+    let mut end_line = node.end_position().row;
+    if end_line <= start_line {
+        return None;
+    }
+
+    if node.kind().contains("comment") {
+        return Some(FoldingRange {
+            start_line,
+            end_line,
+            kind: Some("comment".to_string()),
+        });
+    }
+
+    if !node.is_named() {
+        return None;
+    }
+
+    let delimited = node.child(0).is_some_and(|first| matches!(first.kind(), "{" | "[" | "("));
+    if !delimited && !BLOCK_KINDS.contains(&node.kind()) {
+        return None;
+    }
+
+    // Indentation-based blocks (Python) start on the line after their header
+    if !delimited {
+        if let Some(header) = node.prev_sibling() {
+            start_line = header.end_position().row;
+        }
+    }
+
+    // Keep a closing delimiter on its own line visible when folded
+    if delimited {
+        if let Some(last) = node.child(node.child_count() - 1) {
+            if matches!(last.kind(), "}" | "]" | ")") && last.start_position().row == end_line {
+                end_line -= 1;
+            }
+        }
+    }
+
+    (end_line > start_line).then_some(FoldingRange {
+        start_line,
+        end_line,
+        kind: None,
+    })
+}
@@ -0,0 +1,176 @@
+/**
+ * Textual rename fallback for languages without a running language server
+ * Finds whole-word occurrences across a workspace, skipping comments and strings
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tree_sitter::Tree;
+use walkdir::WalkDir;
+
+use super::highlight::{utf16_column, LineIndex};
+use super::outline::SourceRange;
+use super::{SyntaxError, SyntaxService};
+
+/// Directories never searched for occurrences
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "target", ".venv", "venv", "dist", "build"];
+
+/// Files larger than this are assumed to be generated and left alone
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// A single replacement; `preview` is the original text of the line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameEdit {
+    pub range: SourceRange,
+    pub new_text: String,
+    pub preview: String,
+}
+
+/// Replacements within one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRenameEdits {
+    pub path: String,
+    pub edits: Vec<RenameEdit>,
+}
+
+/// Proposed workspace-wide rename, shown to the user before anything is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRenamePreview {
+    pub old_name: String,
+    pub new_name: String,
+    pub files: Vec<FileRenameEdits>,
+    pub total_edits: usize,
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| is_identifier_char(first) && !first.is_ascii_digit())
+        && chars.all(is_identifier_char)
+}
+
+/// Whether a byte offset lies inside a comment or string literal (but not an interpolation within one)
+fn in_comment_or_string(tree: &Tree, offset: usize) -> bool {
+    let mut node = tree.root_node().descendant_for_byte_range(offset, offset);
+    while let Some(current) = node {
+        let kind = current.kind();
+        if kind.contains("substitution") || kind.contains("interpolation") {
+            return false;
+        }
+        if kind.contains("comment") || kind.contains("string") || kind == "char_literal" {
+            return true;
+        }
+        node = current.parent();
+    }
+    false
+}
+
+impl SyntaxService {
+    /// Collect whole-word occurrences of `old_name` in files of the same language as `path`
+    ///
+    /// Occurrences in comments and strings are skipped for languages with a grammar; files of
+    /// other languages are matched by extension and renamed purely textually.
+    pub fn prepare_workspace_rename(
+        &self,
+        workspace: &str,
+        path: &str,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<WorkspaceRenamePreview, SyntaxError> {
+        if !is_identifier(old_name) {
+            return Err(SyntaxError::InvalidIdentifier(old_name.to_string()));
+        }
+        if !is_identifier(new_name) {
+            return Err(SyntaxError::InvalidIdentifier(new_name.to_string()));
+        }
+
+        let origin = Path::new(path);
+        let grammar = self.registry.for_path(origin);
+        let extension = origin.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+
+        let walker = WalkDir::new(workspace).follow_links(false).into_iter().filter_entry(|entry| {
+            let name = entry.file_name().to_str().unwrap_or("");
+            entry.depth() == 0
+                || !(entry.file_type().is_dir() && (name.starts_with('.') || SKIPPED_DIRECTORIES.contains(&name)))
+        });
+
+        let mut files = Vec::new();
+        for entry in walker.flatten() {
+            if !entry.file_type().is_file() || entry.metadata().map(|m| m.len() > MAX_FILE_SIZE).unwrap_or(true) {
+                continue;
+            }
+            let same_language = match &grammar {
+                Some(grammar) => self.registry.for_path(entry.path()).is_some_and(|g| g.id == grammar.id),
+                None => {
+                    let entry_extension = entry.path().extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+                    entry_extension.is_some() && entry_extension == extension
+                }
+            };
+            if !same_language {
+                continue;
+            }
+            // Binary or non-UTF-8 files are not source code
+            let Ok(source) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            if !source.contains(old_name) {
+                continue;
+            }
+
+            let tree = match &grammar {
+                Some(grammar) => Some(self.parse(grammar, &source)?),
+                None => None,
+            };
+            let edits = rename_edits(&source, tree.as_ref(), old_name, new_name);
+            if !edits.is_empty() {
+                files.push(FileRenameEdits {
+                    path: entry.path().to_string_lossy().to_string(),
+                    edits,
+                });
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(WorkspaceRenamePreview {
+            old_name: old_name.to_string(),
+            new_name: new_name.to_string(),
+            total_edits: files.iter().map(|file| file.edits.len()).sum(),
+            files,
+        })
+    }
+}
+
+fn rename_edits(source: &str, tree: Option<&Tree>, old_name: &str, new_name: &str) -> Vec<RenameEdit> {
+    let index = LineIndex::new(source);
+    let mut edits = Vec::new();
+
+    for line in 0..index.line_count() {
+        let (line_start, line_end) = index.line_bounds(source, line);
+        let line_text = &source[line_start..line_end];
+
+        for (offset, _) in line_text.match_indices(old_name) {
+            let end = offset + old_name.len();
+            let whole_word = !line_text[..offset].chars().next_back().is_some_and(is_identifier_char)
+                && !line_text[end..].chars().next().is_some_and(is_identifier_char);
+            if !whole_word || tree.is_some_and(|tree| in_comment_or_string(tree, line_start + offset)) {
+                continue;
+            }
+
+            edits.push(RenameEdit {
+                range: SourceRange {
+                    start_line: line,
+                    start_column: utf16_column(line_text, offset),
+                    end_line: line,
+                    end_column: utf16_column(line_text, end),
+                },
+                new_text: new_name.to_string(),
+                preview: line_text.to_string(),
+            });
+        }
+    }
+    edits
+}
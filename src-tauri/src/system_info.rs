@@ -0,0 +1,140 @@
+/**
+ * System information for the About/Diagnostics panel
+ * OS, CPU, memory, disks, and graphics adapters, gathered so users can paste them into bug reports
+ */
+
+use std::path::MAIN_SEPARATOR;
+use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
+
+use crate::path_utils;
+use crate::types::{CpuInfo, DiskInfo, MemoryInfo, SystemInfo};
+
+fn cpu_info(system: &System) -> CpuInfo {
+    let first = system.cpus().first();
+    CpuInfo {
+        model: first.map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default(),
+        vendor: first.map(|cpu| cpu.vendor_id().to_string()).unwrap_or_default(),
+        physical_cores: System::physical_core_count(),
+        logical_cores: system.cpus().len(),
+        frequency_mhz: first.map(|cpu| cpu.frequency()).unwrap_or(0),
+    }
+}
+
+/// Mounted disks, skipping pseudo file systems that report no size
+fn disks() -> Vec<DiskInfo> {
+    Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|disk| disk.total_space() > 0)
+        .map(|disk| DiskInfo {
+            name: disk.name().to_string_lossy().to_string(),
+            mount_point: disk.mount_point().to_string_lossy().to_string(),
+            file_system: disk.file_system().to_string_lossy().to_string(),
+            kind: disk.kind().to_string(),
+            removable: disk.is_removable(),
+            total: disk.total_space(),
+            available: disk.available_space(),
+        })
+        .collect()
+}
+
+/// Gather everything; GPU detection shells out, so call it off the main thread
+pub fn system_info() -> SystemInfo {
+    let system = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing().with_frequency())
+            .with_memory(MemoryRefreshKind::everything()),
+    );
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        platform: std::env::consts::FAMILY.to_string(),
+        hostname: System::host_name().unwrap_or_default(),
+        username: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default(),
+        home_dir: path_utils::home_dir(),
+        current_dir: std::env::current_dir().ok().map(|dir| dir.to_string_lossy().to_string()),
+        temp_dir: std::env::temp_dir().to_string_lossy().to_string(),
+        path_separator: MAIN_SEPARATOR.to_string(),
+        os_version: System::long_os_version(),
+        kernel_version: System::kernel_version(),
+        cpu: cpu_info(&system),
+        memory: MemoryInfo {
+            total: system.total_memory(),
+            available: system.available_memory(),
+            swap_total: system.total_swap(),
+            swap_used: system.used_swap(),
+        },
+        disks: disks(),
+        gpus: platform::gpus(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    /// Device classes `lspci` lists graphics adapters under
+    const GPU_CLASSES: &[&str] = &["VGA compatible controller", "3D controller", "Display controller"];
+
+    pub fn gpus() -> Vec<String> {
+        let Ok(output) = Command::new("lspci").output() else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // 00:02.0 VGA compatible controller: Intel Corporation UHD Graphics 620 (rev 07)
+                let (_, rest) = line.split_once(' ')?;
+                let (class, device) = rest.split_once(": ")?;
+                GPU_CLASSES.contains(&class).then(|| device.trim().to_string())
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    pub fn gpus() -> Vec<String> {
+        let Ok(output) = Command::new("system_profiler").arg("SPDisplaysDataType").output() else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Chipset Model:").map(|model| model.trim().to_string()))
+            .collect()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::process::Command;
+
+    pub fn gpus() -> Vec<String> {
+        let Ok(output) = Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name }",
+            ])
+            .output()
+        else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn gpus() -> Vec<String> {
+        Vec::new()
+    }
+}
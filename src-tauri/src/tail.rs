@@ -0,0 +1,239 @@
+/**
+ * Log tailing for CodeForge IDE
+ * Returns the end of a file and follows appended lines, surviving rotation and truncation
+ */
+
+use crate::file_system::map_io_error;
+use crate::types::FileSystemError;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying lines appended to a followed file
+pub const TAIL_EVENT: &str = "tail://lines";
+
+/// Size of the blocks read backwards when looking for the last lines
+const TAIL_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Initial tail of a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailResult {
+    pub path: String,
+    pub lines: Vec<String>,
+    pub size: u64,
+    /// Id of the follow session when `follow` was requested
+    pub tail_id: Option<String>,
+}
+
+/// Lines appended to a followed file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailEvent {
+    pub tail_id: String,
+    pub path: String,
+    pub lines: Vec<String>,
+    /// The file was truncated or replaced and is being read from the start again
+    pub reset: bool,
+}
+
+struct FollowState {
+    offset: u64,
+    identity: Option<u64>,
+    /// Bytes of the unterminated last line, kept raw so a UTF-8 sequence split across reads decodes whole
+    partial: Vec<u8>,
+}
+
+pub struct TailService {
+    watchers: Arc<Mutex<HashMap<String, RecommendedWatcher>>>,
+    next_id: AtomicU64,
+}
+
+/// Identifies the file behind a path so rotation (rename + recreate) can be detected
+#[cfg(unix)]
+fn file_identity(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_identity(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+}
+
+/// Read the last `count` lines of a file by scanning backwards from the end
+fn read_last_lines(file: &mut File, size: u64, count: usize) -> Result<Vec<String>, FileSystemError> {
+    if count == 0 || size == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut start = size;
+    let mut buffer = Vec::new();
+    loop {
+        let read_from = start.saturating_sub(TAIL_CHUNK_SIZE);
+        let mut chunk = vec![0; (start - read_from) as usize];
+        file.seek(SeekFrom::Start(read_from)).map_err(map_io_error)?;
+        file.read_exact(&mut chunk).map_err(map_io_error)?;
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+        start = read_from;
+
+        // One extra newline is needed because the last line is usually newline-terminated
+        let newlines = buffer.iter().filter(|&&b| b == b'\n').count();
+        if start == 0 || newlines > count {
+            break;
+        }
+    }
+
+    let text = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = text.strip_suffix('\n').unwrap_or(&text).split('\n').collect();
+    // The first line may be cut off when we stopped mid-file
+    let skip = lines.len().saturating_sub(count);
+    Ok(lines[skip..]
+        .iter()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect())
+}
+
+/// Read everything appended since the last read, detecting truncation and rotation
+fn read_appended(path: &Path, state: &mut FollowState) -> Option<(Vec<String>, bool)> {
+    let metadata = fs::metadata(path).ok()?;
+    let identity = file_identity(&metadata);
+
+    let reset = metadata.len() < state.offset || identity != state.identity;
+    if reset {
+        state.offset = 0;
+        state.identity = identity;
+        state.partial.clear();
+    }
+    if metadata.len() == state.offset {
+        return reset.then(|| (Vec::new(), true));
+    }
+
+    let mut file = File::open(path).ok()?;
+    file.seek(SeekFrom::Start(state.offset)).ok()?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended).ok()?;
+    state.offset += appended.len() as u64;
+
+    state.partial.extend_from_slice(&appended);
+    let mut lines = Vec::new();
+    while let Some(newline) = state.partial.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = state.partial.drain(..=newline).collect();
+        lines.push(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']).to_string());
+    }
+
+    Some((lines, reset))
+}
+
+impl TailService {
+    pub fn new() -> Self {
+        Self {
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Return the last `lines` lines of a file and optionally follow it
+    pub fn tail_file(&self, app: &AppHandle, path: &str, lines: usize, follow: bool) -> Result<TailResult, FileSystemError> {
+        let file_path = PathBuf::from(path);
+        if !file_path.is_file() {
+            return Err(FileSystemError::NotFound);
+        }
+
+        let mut file = File::open(&file_path).map_err(map_io_error)?;
+        let metadata = file.metadata().map_err(map_io_error)?;
+        let size = metadata.len();
+        let tail = read_last_lines(&mut file, size, lines)?;
+
+        let tail_id = if follow {
+            Some(self.follow(app, file_path, size, file_identity(&metadata))?)
+        } else {
+            None
+        };
+
+        Ok(TailResult {
+            path: path.to_string(),
+            lines: tail,
+            size,
+            tail_id,
+        })
+    }
+
+    /// Watch the file's directory so that rotation (delete + recreate) is seen as well as appends
+    fn follow(&self, app: &AppHandle, path: PathBuf, offset: u64, identity: Option<u64>) -> Result<String, FileSystemError> {
+        let tail_id = format!("tail-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+        let directory = path.parent().map(Path::to_path_buf).ok_or(FileSystemError::InvalidPath)?;
+
+        let state = Mutex::new(FollowState {
+            offset,
+            identity,
+            partial: Vec::new(),
+        });
+        let app = app.clone();
+        let event_tail_id = tail_id.clone();
+        let file_name = path.file_name().map(|name| name.to_os_string());
+
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            if !event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name) {
+                return;
+            }
+
+            let mut state = state.lock().unwrap();
+            if let Some((lines, reset)) = read_appended(&path, &mut state) {
+                if lines.is_empty() && !reset {
+                    return;
+                }
+                let _ = app.emit(
+                    TAIL_EVENT,
+                    TailEvent {
+                        tail_id: event_tail_id.clone(),
+                        path: path.to_string_lossy().to_string(),
+                        lines,
+                        reset,
+                    },
+                );
+            }
+        })
+        .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| FileSystemError::IOError(e.to_string()))?;
+
+        self.watchers.lock().unwrap().insert(tail_id.clone(), watcher);
+        Ok(tail_id)
+    }
+
+    /// Stop following a file
+    pub fn stop_tail(&self, tail_id: &str) -> Result<(), FileSystemError> {
+        self.watchers
+            .lock()
+            .unwrap()
+            .remove(tail_id)
+            .map(|_| ())
+            .ok_or(FileSystemError::NotFound)
+    }
+
+    /// Files being followed, one watcher each
+    pub fn watcher_count(&self) -> usize {
+        self.watchers.lock().unwrap().len()
+    }
+}
+
+impl Default for TailService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
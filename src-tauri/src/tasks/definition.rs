@@ -0,0 +1,110 @@
+/**
+ * Task definitions loaded from `.codeforge/tasks.json`
+ */
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+use super::TaskError;
+use crate::fs_provider::{self, FileSystemProvider};
+use crate::types::FileSystemError;
+use crate::problem_matcher::ProblemMatcherSpec;
+
+/// Location of the task file relative to the workspace root
+pub const TASKS_FILE: &str = ".codeforge/tasks.json";
+
+/// How a task's command is launched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskType {
+    /// Run through the platform shell (`sh -c` / `cmd /C`)
+    #[default]
+    Shell,
+    /// Execute the command directly with its arguments
+    Process,
+}
+
+/// Order in which a task's dependencies run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependsOrder {
+    #[default]
+    Parallel,
+    Sequence,
+}
+
+/// Where a task definition came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSource {
+    /// Defined in the workspace task file
+    #[default]
+    Workspace,
+    Npm,
+    Cargo,
+    Make,
+}
+
+/// A runnable task; a task with no command is a compound task that only runs its dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDefinition {
+    pub label: String,
+    #[serde(default, rename = "type")]
+    pub task_type: TaskType,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Matchers applied to the task's output: built-in names like `"$rustc"`, inline
+    /// definitions, or a list of either
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub problem_matcher: Vec<ProblemMatcherSpec>,
+    #[serde(default, skip_deserializing)]
+    pub source: TaskSource,
+}
+
+/// Accept either a single matcher or a list of matchers
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ProblemMatcherSpec>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ProblemMatcherSpec),
+        Many(Vec<ProblemMatcherSpec>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct TasksFile {
+    #[serde(default)]
+    tasks: Vec<TaskDefinition>,
+}
+
+/// Read the task definitions of a workspace at `root` on `provider`; a missing file means no tasks
+pub fn load_task_file(
+    provider: &dyn FileSystemProvider,
+    root: &str,
+) -> Result<Vec<TaskDefinition>, TaskError> {
+    let content = match provider.read(&fs_provider::join_path(root, TASKS_FILE)) {
+        Ok(content) => content,
+        Err(FileSystemError::NotFound) => return Ok(Vec::new()),
+        Err(e) => return Err(TaskError::InvalidDefinition(e.to_string())),
+    };
+    let content = String::from_utf8(content).map_err(|e| TaskError::InvalidDefinition(e.to_string()))?;
+    let file: TasksFile = serde_json::from_str(&content)
+        .map_err(|e| TaskError::InvalidDefinition(format!("{}: {}", TASKS_FILE, e)))?;
+    Ok(file.tasks)
+}
@@ -0,0 +1,143 @@
+/**
+ * Auto-detected tasks from npm scripts, Cargo manifests, and Makefiles
+ */
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use super::definition::{DependsOrder, TaskDefinition, TaskSource, TaskType};
+use crate::problem_matcher::ProblemMatcherSpec;
+
+/// A rule line such as `build: deps`; `:=` assignments and pattern rules are excluded
+static MAKE_TARGET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z0-9_][A-Za-z0-9_.\-/]*)\s*:([^=]|$)").unwrap());
+
+const CARGO_COMMANDS: &[&str] = &["build", "check", "test", "clippy", "run"];
+
+fn detected_task(
+    label: String,
+    command: &str,
+    args: Vec<String>,
+    source: TaskSource,
+    problem_matcher: Vec<ProblemMatcherSpec>,
+) -> TaskDefinition {
+    TaskDefinition {
+        label,
+        // Shell lookup resolves `npm.cmd` and friends on Windows
+        task_type: TaskType::Shell,
+        command: Some(command.to_string()),
+        args,
+        cwd: None,
+        env: HashMap::new(),
+        depends_on: Vec::new(),
+        depends_order: DependsOrder::default(),
+        problem_matcher,
+        source,
+    }
+}
+
+/// Every task that can be inferred from build files in the workspace root
+pub fn detect_tasks(workspace: &str) -> Vec<TaskDefinition> {
+    let root = Path::new(workspace);
+    let mut tasks = Vec::new();
+    tasks.extend(detect_npm(root));
+    tasks.extend(detect_cargo(root));
+    tasks.extend(detect_make(root));
+    tasks
+}
+
+fn detect_npm(root: &Path) -> Vec<TaskDefinition> {
+    let Some(manifest) = fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    let Some(scripts) = manifest.get("scripts").and_then(|scripts| scripts.as_object()) else {
+        return Vec::new();
+    };
+
+    let manager = if root.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if root.join("yarn.lock").exists() {
+        "yarn"
+    } else if root.join("bun.lockb").exists() || root.join("bun.lock").exists() {
+        "bun"
+    } else {
+        "npm"
+    };
+
+    scripts
+        .iter()
+        .map(|(name, script)| {
+            let script = script.as_str().unwrap_or_default();
+            let matcher = if script.contains("tsc") { vec!["$tsc".into()] } else { Vec::new() };
+            detected_task(
+                format!("{}: {}", manager, name),
+                manager,
+                vec!["run".to_string(), name.clone()],
+                TaskSource::Npm,
+                matcher,
+            )
+        })
+        .collect()
+}
+
+fn detect_cargo(root: &Path) -> Vec<TaskDefinition> {
+    let Ok(manifest) = fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    // `cargo run` only makes sense with a binary target
+    let has_binary = root.join("src/main.rs").exists() || manifest.contains("[[bin]]");
+
+    CARGO_COMMANDS
+        .iter()
+        .filter(|command| **command != "run" || has_binary)
+        .map(|command| {
+            detected_task(
+                format!("cargo: {}", command),
+                "cargo",
+                vec![command.to_string()],
+                TaskSource::Cargo,
+                vec!["$rustc".into()],
+            )
+        })
+        .collect()
+}
+
+fn detect_make(root: &Path) -> Vec<TaskDefinition> {
+    let Some(content) = ["GNUmakefile", "makefile", "Makefile"]
+        .iter()
+        .find_map(|name| fs::read_to_string(root.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut targets: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let Some(captures) = MAKE_TARGET.captures(line) else {
+            continue;
+        };
+        let target = captures[1].to_string();
+        // Special targets such as .PHONY start with a dot and are excluded by the pattern
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+
+    targets
+        .into_iter()
+        .map(|target| {
+            detected_task(
+                format!("make: {}", target),
+                "make",
+                vec![target],
+                TaskSource::Make,
+                vec!["$gcc".into()],
+            )
+        })
+        .collect()
+}
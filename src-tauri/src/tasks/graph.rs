@@ -0,0 +1,52 @@
+/**
+ * Dependency validation for compound tasks
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use super::definition::TaskDefinition;
+use super::TaskError;
+
+/// Check that every task reachable from `root` exists and that there are no cycles.
+/// Returns the labels of all tasks the run will involve.
+pub fn resolve_dependencies(
+    tasks: &HashMap<String, TaskDefinition>,
+    root: &str,
+) -> Result<Vec<String>, TaskError> {
+    let mut visiting = Vec::new();
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    visit(tasks, root, &mut visiting, &mut visited, &mut order)?;
+    Ok(order)
+}
+
+fn visit(
+    tasks: &HashMap<String, TaskDefinition>,
+    label: &str,
+    visiting: &mut Vec<String>,
+    visited: &mut HashSet<String>,
+    order: &mut Vec<String>,
+) -> Result<(), TaskError> {
+    if visited.contains(label) {
+        return Ok(());
+    }
+    if let Some(start) = visiting.iter().position(|l| l == label) {
+        let mut cycle = visiting[start..].to_vec();
+        cycle.push(label.to_string());
+        return Err(TaskError::DependencyCycle(cycle.join(" -> ")));
+    }
+
+    let task = tasks
+        .get(label)
+        .ok_or_else(|| TaskError::NotFound(label.to_string()))?;
+
+    visiting.push(label.to_string());
+    for dependency in &task.depends_on {
+        visit(tasks, dependency, visiting, visited, order)?;
+    }
+    visiting.pop();
+
+    visited.insert(label.to_string());
+    order.push(label.to_string());
+    Ok(())
+}
@@ -0,0 +1,245 @@
+/**
+ * Task Service for CodeForge IDE
+ * Runs workspace tasks, orchestrating compound tasks and their dependencies
+ *
+ * Tasks of a workspace on another machine run there, through the workspace's file system provider.
+ */
+
+mod definition;
+mod detect;
+mod graph;
+mod runner;
+mod watch;
+
+pub use definition::{TaskDefinition, TaskSource, TaskType, TASKS_FILE};
+pub use runner::{TaskRunSummary, TaskState, TaskStatus};
+pub use watch::{WatchTaskStatus, DEFAULT_WATCH_DEBOUNCE_MS};
+
+use crate::environment;
+use crate::file_system::FileSystemService;
+use crate::fs_provider::RemoteUri;
+use runner::TaskRun;
+use watch::WatchTask;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Event emitted whenever a task changes state
+pub const TASK_STATUS_EVENT: &str = "task://status";
+
+/// Finished runs whose summary stays available
+const MAX_FINISHED_RUNS: usize = 50;
+
+/// Error types for task operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TaskError {
+    NotFound(String),
+    RunNotFound(String),
+    WatchNotFound(String),
+    DependencyCycle(String),
+    InvalidDefinition(String),
+    Environment(String),
+    Workspace(String),
+    Watch(String),
+}
+
+impl std::fmt::Display for TaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TaskError::NotFound(label) => write!(f, "Task not found: {}", label),
+            TaskError::RunNotFound(id) => write!(f, "Task run not found: {}", id),
+            TaskError::WatchNotFound(id) => write!(f, "Watch task not found: {}", id),
+            TaskError::DependencyCycle(cycle) => write!(f, "Task dependency cycle: {}", cycle),
+            TaskError::InvalidDefinition(msg) => write!(f, "Invalid task definition: {}", msg),
+            TaskError::Environment(msg) => write!(f, "Invalid workspace environment: {}", msg),
+            TaskError::Workspace(msg) => write!(f, "Workspace unavailable: {}", msg),
+            TaskError::Watch(msg) => write!(f, "Can't watch the workspace: {}", msg),
+        }
+    }
+}
+
+pub struct TaskService {
+    runs: Arc<Mutex<HashMap<String, Arc<TaskRun>>>>,
+    /// Ids of finished runs still in `runs`, oldest first
+    finished: Arc<Mutex<VecDeque<String>>>,
+    watches: Arc<Mutex<HashMap<String, WatchTask>>>,
+    next_run_id: AtomicU64,
+}
+
+impl TaskService {
+    pub fn new() -> Self {
+        Self {
+            runs: Arc::new(Mutex::new(HashMap::new())),
+            finished: Arc::new(Mutex::new(VecDeque::new())),
+            watches: Arc::new(Mutex::new(HashMap::new())),
+            next_run_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Task definitions available in a workspace
+    pub fn list_definitions(
+        &self,
+        fs: &FileSystemService,
+        workspace: &str,
+    ) -> Result<Vec<TaskDefinition>, TaskError> {
+        let (provider, root) = fs.provider(workspace).map_err(|e| TaskError::Workspace(e.to_string()))?;
+        definition::load_task_file(provider.as_ref(), &root)
+    }
+
+    /// Tasks from the task file followed by auto-detected npm, cargo, and make tasks;
+    /// a task file entry with the same label replaces the detected one. Tasks of URI workspaces come
+    /// from their task file only.
+    pub fn list_tasks(&self, fs: &FileSystemService, workspace: &str) -> Result<Vec<TaskDefinition>, TaskError> {
+        let mut tasks = self.list_definitions(fs, workspace)?;
+        if RemoteUri::parse(workspace).is_some() {
+            return Ok(tasks);
+        }
+        let detected: Vec<TaskDefinition> = detect::detect_tasks(workspace)
+            .into_iter()
+            .filter(|task| !tasks.iter().any(|defined| defined.label == task.label))
+            .collect();
+        tasks.extend(detected);
+        Ok(tasks)
+    }
+
+    /// Start a task and its dependencies in the background, returning the run id
+    pub fn run_task(&self, app: &AppHandle, workspace: &str, label: &str) -> Result<String, TaskError> {
+        let fs = app.state::<FileSystemService>();
+        let (provider, root) = fs.provider(workspace).map_err(|e| TaskError::Workspace(e.to_string()))?;
+        let remote = RemoteUri::parse(workspace).is_some();
+        // Variables from the workspace `.env` files apply unless the task sets them itself
+        let workspace_env = match remote {
+            true => HashMap::new(),
+            false => environment::workspace_env(workspace).map_err(|e| TaskError::Environment(e.to_string()))?,
+        };
+        let definitions: HashMap<String, TaskDefinition> = self
+            .list_tasks(&fs, workspace)?
+            .into_iter()
+            .map(|mut task| {
+                for (name, value) in &workspace_env {
+                    task.env.entry(name.clone()).or_insert_with(|| value.clone());
+                }
+                (task.label.clone(), task)
+            })
+            .collect();
+        let order = graph::resolve_dependencies(&definitions, label)?;
+
+        let run_id = format!("run-{}", self.next_run_id.fetch_add(1, Ordering::SeqCst));
+        let run = Arc::new(TaskRun::new(
+            run_id.clone(),
+            label.to_string(),
+            PathBuf::from(root),
+            provider,
+            remote,
+            definitions,
+            order,
+        ));
+        self.runs.lock().unwrap().insert(run_id.clone(), run.clone());
+
+        let app = app.clone();
+        let runs = self.runs.clone();
+        let finished = self.finished.clone();
+        std::thread::spawn(move || {
+            run.execute_root(&app);
+            // Keep the outcome of recent runs around for `run_summary` and drop older ones
+            let mut finished = finished.lock().unwrap();
+            finished.push_back(run.id.clone());
+            while finished.len() > MAX_FINISHED_RUNS {
+                if let Some(expired) = finished.pop_front() {
+                    runs.lock().unwrap().remove(&expired);
+                }
+            }
+        });
+
+        Ok(run_id)
+    }
+
+    /// Current status of a run and every task it involves
+    pub fn run_summary(&self, run_id: &str) -> Result<TaskRunSummary, TaskError> {
+        let runs = self.runs.lock().unwrap();
+        let run = runs.get(run_id).ok_or_else(|| TaskError::RunNotFound(run_id.to_string()))?;
+        Ok(run.summary())
+    }
+
+    /// Cancel a run: running processes are killed and pending tasks never start
+    pub fn stop_run(&self, run_id: &str) -> Result<(), TaskError> {
+        let runs = self.runs.lock().unwrap();
+        let run = runs.get(run_id).ok_or_else(|| TaskError::RunNotFound(run_id.to_string()))?;
+        run.cancel();
+        Ok(())
+    }
+
+    /// Bind a task to glob patterns so it re-runs when matching files change
+    pub fn start_watch_task(
+        &self,
+        app: &AppHandle,
+        workspace: &str,
+        label: &str,
+        patterns: Vec<String>,
+        debounce_ms: Option<u64>,
+    ) -> Result<WatchTaskStatus, TaskError> {
+        if RemoteUri::parse(workspace).is_some() {
+            return Err(TaskError::Workspace("watch tasks need a workspace on this machine".to_string()));
+        }
+        let tasks = self.list_tasks(&app.state::<FileSystemService>(), workspace)?;
+        if !tasks.iter().any(|task| task.label == label) {
+            return Err(TaskError::NotFound(label.to_string()));
+        }
+
+        let id = format!("watch-{}", self.next_run_id.fetch_add(1, Ordering::SeqCst));
+        let watch = watch::start_watch_task(
+            app,
+            id.clone(),
+            workspace,
+            label,
+            patterns,
+            debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS),
+        )?;
+        let status = watch.status();
+        self.watches.lock().unwrap().insert(id, watch);
+        Ok(status)
+    }
+
+    /// Stop a watch task; a run it already started keeps going
+    pub fn stop_watch_task(&self, id: &str) -> Result<(), TaskError> {
+        let watch = self
+            .watches
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or_else(|| TaskError::WatchNotFound(id.to_string()))?;
+        watch.stop();
+        Ok(())
+    }
+
+    /// Status of every active watch task, including the outcome of its latest run
+    pub fn list_watch_tasks(&self) -> Vec<WatchTaskStatus> {
+        let watches = self.watches.lock().unwrap();
+        let mut statuses: Vec<WatchTaskStatus> = watches
+            .values()
+            .map(|watch| {
+                let mut status = watch.status();
+                if let Some(run_id) = &status.last_run_id {
+                    status.last_run_status = self.run_summary(run_id).ok().map(|summary| summary.status);
+                }
+                status
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.id.cmp(&b.id));
+        statuses
+    }
+
+    /// Active watch tasks, one watcher each
+    pub fn watcher_count(&self) -> usize {
+        self.watches.lock().unwrap().len()
+    }
+}
+
+impl Default for TaskService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
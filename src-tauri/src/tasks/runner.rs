@@ -0,0 +1,465 @@
+/**
+ * Task execution with dependency orchestration
+ * Dependencies run before their dependents, each task at most once per run,
+ * and a failed prerequisite cancels everything that depends on it
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::definition::{DependsOrder, TaskDefinition, TaskType};
+use super::TASK_STATUS_EVENT;
+use crate::clock::now_millis;
+use crate::diagnostics::{Diagnostic, DiagnosticsService};
+use crate::fs_provider::{self, FileSystemProvider};
+use crate::types::FileSystemError;
+use crate::problem_matcher::ProblemCollector;
+use crate::terminal::TerminalService;
+
+/// How often a running process is polled for exit or cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lifecycle state of a task within a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl TaskStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, TaskStatus::Succeeded | TaskStatus::Failed | TaskStatus::Cancelled)
+    }
+}
+
+/// State of one task within a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskState {
+    pub label: String,
+    pub status: TaskStatus,
+    pub exit_code: Option<i32>,
+    /// Terminal session receiving the task's output
+    pub session_id: String,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    /// Diagnostics its problem matchers extracted from the output; filled in once the task finishes
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Aggregated status of a run and all tasks it involves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRunSummary {
+    pub run_id: String,
+    pub label: String,
+    pub status: TaskStatus,
+    pub tasks: Vec<TaskState>,
+}
+
+/// Event payload emitted whenever a task in a run changes state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatusEvent {
+    pub run_id: String,
+    pub task: TaskState,
+}
+
+struct RunState {
+    tasks: HashMap<String, TaskState>,
+    /// Tasks some thread has taken responsibility for executing
+    claimed: HashSet<String>,
+}
+
+pub(super) struct TaskRun {
+    pub id: String,
+    pub root: String,
+    /// Path of the workspace on the provider
+    workspace: PathBuf,
+    /// Where the workspace's processes run
+    provider: Arc<dyn FileSystemProvider>,
+    /// The provider is on another machine, whose paths are `/`-separated
+    remote: bool,
+    definitions: HashMap<String, TaskDefinition>,
+    order: Vec<String>,
+    state: Mutex<RunState>,
+    changed: Condvar,
+    cancelled: AtomicBool,
+}
+
+impl TaskRun {
+    pub fn new(
+        id: String,
+        root: String,
+        workspace: PathBuf,
+        provider: Arc<dyn FileSystemProvider>,
+        remote: bool,
+        definitions: HashMap<String, TaskDefinition>,
+        order: Vec<String>,
+    ) -> Self {
+        let tasks = order
+            .iter()
+            .map(|label| {
+                let state = TaskState {
+                    label: label.clone(),
+                    status: TaskStatus::Pending,
+                    exit_code: None,
+                    session_id: format!("task-{}-{}", id, label),
+                    started_at: None,
+                    finished_at: None,
+                    diagnostics: Vec::new(),
+                };
+                (label.clone(), state)
+            })
+            .collect();
+
+        Self {
+            id,
+            root,
+            workspace,
+            provider,
+            remote,
+            definitions,
+            order,
+            state: Mutex::new(RunState {
+                tasks,
+                claimed: HashSet::new(),
+            }),
+            changed: Condvar::new(),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn summary(&self) -> TaskRunSummary {
+        let state = self.state.lock().unwrap();
+        let tasks: Vec<TaskState> = self
+            .order
+            .iter()
+            .filter_map(|label| state.tasks.get(label).cloned())
+            .collect();
+        let status = state
+            .tasks
+            .get(&self.root)
+            .map(|task| task.status)
+            .unwrap_or(TaskStatus::Pending);
+
+        TaskRunSummary {
+            run_id: self.id.clone(),
+            label: self.root.clone(),
+            status,
+            tasks,
+        }
+    }
+
+    /// Execute the root task and everything it depends on
+    pub fn execute_root(self: &Arc<Self>, app: &AppHandle) {
+        self.execute(app, &self.root.clone());
+
+        // Prerequisites skipped because an earlier one failed never started
+        let skipped: Vec<String> = {
+            let state = self.state.lock().unwrap();
+            state
+                .tasks
+                .values()
+                .filter(|task| task.status == TaskStatus::Pending)
+                .map(|task| task.label.clone())
+                .collect()
+        };
+        for label in skipped {
+            self.update(app, &label, TaskStatus::Cancelled, None);
+        }
+    }
+
+    fn execute(self: &Arc<Self>, app: &AppHandle, label: &str) -> TaskStatus {
+        {
+            let mut state = self.state.lock().unwrap();
+            if !state.claimed.insert(label.to_string()) {
+                // Another branch of the graph already runs this task; wait for its outcome
+                loop {
+                    let status = state.tasks[label].status;
+                    if status.is_finished() {
+                        return status;
+                    }
+                    state = self.changed.wait(state).unwrap();
+                }
+            }
+        }
+
+        let definition = &self.definitions[label];
+        let dependencies = self.execute_dependencies(app, definition);
+
+        let (status, exit_code) = if dependencies != TaskStatus::Succeeded || self.cancelled.load(Ordering::SeqCst) {
+            (TaskStatus::Cancelled, None)
+        } else if definition.command.is_some() {
+            self.run_command(app, definition)
+        } else {
+            (TaskStatus::Succeeded, None)
+        };
+
+        self.update(app, label, status, exit_code);
+        status
+    }
+
+    fn execute_dependencies(self: &Arc<Self>, app: &AppHandle, definition: &TaskDefinition) -> TaskStatus {
+        match definition.depends_order {
+            DependsOrder::Sequence => {
+                for dependency in &definition.depends_on {
+                    let status = self.execute(app, dependency);
+                    if status != TaskStatus::Succeeded {
+                        return status;
+                    }
+                }
+                TaskStatus::Succeeded
+            }
+            DependsOrder::Parallel => {
+                let statuses: Vec<TaskStatus> = thread::scope(|scope| {
+                    let handles: Vec<_> = definition
+                        .depends_on
+                        .iter()
+                        .map(|dependency| scope.spawn(move || self.execute(app, dependency)))
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| handle.join().unwrap_or(TaskStatus::Failed))
+                        .collect()
+                });
+
+                if statuses.iter().all(|status| *status == TaskStatus::Succeeded) {
+                    TaskStatus::Succeeded
+                } else if statuses.contains(&TaskStatus::Failed) {
+                    TaskStatus::Failed
+                } else {
+                    TaskStatus::Cancelled
+                }
+            }
+        }
+    }
+
+    fn update(&self, app: &AppHandle, label: &str, status: TaskStatus, exit_code: Option<i32>) {
+        let task = {
+            let mut state = self.state.lock().unwrap();
+            let Some(task) = state.tasks.get_mut(label) else {
+                return;
+            };
+            task.status = status;
+            task.exit_code = exit_code;
+            match status {
+                TaskStatus::Running => task.started_at = Some(now_millis()),
+                _ if status.is_finished() => task.finished_at = Some(now_millis()),
+                _ => {}
+            }
+            task.clone()
+        };
+        self.changed.notify_all();
+
+        let _ = app.emit(
+            TASK_STATUS_EVENT,
+            TaskStatusEvent {
+                run_id: self.id.clone(),
+                task,
+            },
+        );
+    }
+
+    fn build_command(&self, definition: &TaskDefinition) -> Result<Command, FileSystemError> {
+        let program = definition.command.clone().unwrap_or_default();
+        let cwd = self.task_cwd(definition);
+        let cwd = cwd.to_string_lossy();
+        let mut command = match definition.task_type {
+            TaskType::Process => self.provider.command(&program, &definition.args, &cwd, &definition.env),
+            TaskType::Shell => {
+                let line = std::iter::once(program)
+                    .chain(definition.args.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                self.provider.shell_command(&line, &cwd, &definition.env)
+            }
+        }?;
+
+        command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        // Its own process group, so cancelling also reaches whatever the shell started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        Ok(command)
+    }
+
+    fn task_cwd(&self, definition: &TaskDefinition) -> PathBuf {
+        match definition.cwd.as_deref() {
+            Some(cwd) if self.remote && cwd.starts_with('/') => PathBuf::from(cwd),
+            Some(cwd) if self.remote => {
+                PathBuf::from(fs_provider::join_path(&self.workspace.to_string_lossy(), cwd))
+            }
+            Some(cwd) if Path::new(cwd).is_absolute() => PathBuf::from(cwd),
+            Some(cwd) => self.workspace.join(cwd),
+            None => self.workspace.clone(),
+        }
+    }
+
+    fn run_command(&self, app: &AppHandle, definition: &TaskDefinition) -> (TaskStatus, Option<i32>) {
+        let session_id = self.state.lock().unwrap().tasks[&definition.label].session_id.clone();
+        let terminal = app.state::<TerminalService>();
+        terminal.set_session_cwd(&session_id, &self.task_cwd(definition).to_string_lossy());
+
+        let spawned = self.build_command(definition).and_then(|mut command| {
+            command.spawn().map_err(|e| FileSystemError::IOError(e.to_string()))
+        });
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                terminal.publish_output(app, &session_id, &format!("Failed to start task: {}\n", e));
+                return (TaskStatus::Failed, None);
+            }
+        };
+        terminal.set_session_process(&session_id, child.id(), Some(&definition.label));
+        self.update(app, &definition.label, TaskStatus::Running, None);
+
+        let collector = ProblemCollector::new(&definition.problem_matcher, &self.task_cwd(definition))
+            .unwrap_or_else(|e| {
+                terminal.publish_output(app, &session_id, &format!("{}\n", e));
+                ProblemCollector::new(&[], &self.workspace).expect("no matchers always compile")
+            });
+        // Diagnostics of the previous run of this task are stale now
+        let owner = format!("task:{}", definition.label);
+        app.state::<DiagnosticsService>().clear(app, &owner);
+        let matcher = Arc::new(OutputMatcher {
+            owner,
+            collector: Mutex::new(collector),
+            diagnostics: Mutex::new(Vec::new()),
+        });
+        let readers = forward_output(app, &session_id, &mut child, &matcher);
+        let result = self.wait(&mut child);
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        let diagnostics = std::mem::take(&mut *matcher.diagnostics.lock().unwrap());
+        if let Some(task) = self.state.lock().unwrap().tasks.get_mut(&definition.label) {
+            task.diagnostics = diagnostics;
+        }
+        result
+    }
+
+    fn wait(&self, child: &mut Child) -> (TaskStatus, Option<i32>) {
+        loop {
+            match child.try_wait() {
+                Ok(Some(exit)) if exit.success() => return (TaskStatus::Succeeded, exit.code()),
+                Ok(Some(exit)) => return (TaskStatus::Failed, exit.code()),
+                Ok(None) if self.cancelled.load(Ordering::SeqCst) => {
+                    kill_process_tree(child);
+                    return (TaskStatus::Cancelled, None);
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(_) => return (TaskStatus::Failed, None),
+            }
+        }
+    }
+}
+
+/// Kill a task's process along with its descendants, which would otherwise keep its output pipes open
+fn kill_process_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+    let status = if cfg!(windows) {
+        Command::new("taskkill").args(["/PID", &pid, "/T", "/F"]).status()
+    } else {
+        Command::new("kill").args(["-KILL", "--", &format!("-{}", pid)]).status()
+    };
+    if !matches!(status, Ok(status) if status.success()) {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
+
+/// Problem matching state shared by a task's stdout and stderr readers
+struct OutputMatcher {
+    owner: String,
+    collector: Mutex<ProblemCollector>,
+    diagnostics: Mutex<Vec<Diagnostic>>,
+}
+
+impl OutputMatcher {
+    /// Match every complete line in `pending`, leaving a trailing partial line in place
+    fn scan(&self, app: &AppHandle, pending: &mut String, flush: bool) {
+        let mut collector = self.collector.lock().unwrap();
+        if collector.is_empty() {
+            pending.clear();
+            return;
+        }
+        loop {
+            let line: String = match pending.find('\n') {
+                Some(newline) => pending.drain(..=newline).collect(),
+                None if flush && !pending.is_empty() => std::mem::take(pending),
+                None => break,
+            };
+            if let Some(diagnostic) = collector.feed_line(&line) {
+                self.diagnostics.lock().unwrap().push(diagnostic.clone());
+                app.state::<DiagnosticsService>().add(app, &self.owner, diagnostic);
+            }
+        }
+    }
+}
+
+/// Stream a child's stdout and stderr into its terminal session, running problem matchers on each line
+fn forward_output(
+    app: &AppHandle,
+    session_id: &str,
+    child: &mut Child,
+    matcher: &Arc<OutputMatcher>,
+) -> Vec<thread::JoinHandle<()>> {
+    let streams: Vec<Box<dyn Read + Send>> = [
+        child.stdout.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child.stderr.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    streams
+        .into_iter()
+        .map(|mut stream| {
+            let app = app.clone();
+            let session_id = session_id.to_string();
+            let matcher = matcher.clone();
+            thread::spawn(move || {
+                let terminal = app.state::<TerminalService>();
+                let mut buffer = [0u8; 4096];
+                let mut carry = Vec::new();
+                let mut pending_line = String::new();
+                while let Ok(read) = stream.read(&mut buffer) {
+                    if read == 0 {
+                        break;
+                    }
+                    carry.extend_from_slice(&buffer[..read]);
+                    // Hold back an incomplete UTF-8 sequence until the rest arrives
+                    let valid = match std::str::from_utf8(&carry) {
+                        Ok(_) => carry.len(),
+                        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                        Err(_) => carry.len(),
+                    };
+                    let chunk: Vec<u8> = carry.drain(..valid).collect();
+                    let text = String::from_utf8_lossy(&chunk);
+                    terminal.publish_output(&app, &session_id, &text);
+                    pending_line.push_str(&text);
+                    matcher.scan(&app, &mut pending_line, false);
+                }
+                if !carry.is_empty() {
+                    let text = String::from_utf8_lossy(&carry);
+                    terminal.publish_output(&app, &session_id, &text);
+                    pending_line.push_str(&text);
+                }
+                matcher.scan(&app, &mut pending_line, true);
+            })
+        })
+        .collect()
+}
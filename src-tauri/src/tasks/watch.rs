@@ -0,0 +1,185 @@
+/**
+ * Watch tasks: re-run a task whenever files matching its globs change
+ */
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::now_millis;
+use crate::globs::GlobMatcher;
+
+use super::{TaskError, TaskService, TaskStatus};
+
+/// Event emitted whenever a watch task is triggered or changes state
+pub const WATCH_TASK_EVENT: &str = "task://watch";
+
+/// Quiet period after the last matching change before the task re-runs
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 300;
+
+/// How often a restart checks whether the cancelled run has exited
+const RESTART_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest a restart waits for the cancelled run before starting the new one anyway
+const RESTART_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Status of a watch task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchTaskStatus {
+    pub id: String,
+    pub label: String,
+    pub workspace: String,
+    pub patterns: Vec<String>,
+    pub debounce_ms: u64,
+    pub active: bool,
+    pub trigger_count: u64,
+    pub last_triggered_at: Option<u64>,
+    pub last_run_id: Option<String>,
+    pub last_run_status: Option<TaskStatus>,
+    pub error: Option<String>,
+}
+
+enum WatchMessage {
+    Changed,
+    Stop,
+}
+
+pub(super) struct WatchTask {
+    status: Arc<Mutex<WatchTaskStatus>>,
+    control: Sender<WatchMessage>,
+    _watcher: RecommendedWatcher,
+}
+
+impl WatchTask {
+    pub fn status(&self) -> WatchTaskStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn stop(&self) {
+        self.status.lock().unwrap().active = false;
+        let _ = self.control.send(WatchMessage::Stop);
+    }
+}
+
+/// Start watching `patterns` under `workspace` and re-run `label` on matching changes
+pub(super) fn start_watch_task(
+    app: &AppHandle,
+    id: String,
+    workspace: &str,
+    label: &str,
+    patterns: Vec<String>,
+    debounce_ms: u64,
+) -> Result<WatchTask, TaskError> {
+    let globs =
+        GlobMatcher::new(&patterns, &[], false).map_err(|e| TaskError::InvalidDefinition(e.to_string()))?;
+    let root = PathBuf::from(workspace);
+    let (control, messages) = mpsc::channel();
+
+    let notify_control = control.clone();
+    let watch_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+            return;
+        }
+        let matched = event.paths.iter().any(|path| {
+            let relative = path.strip_prefix(&watch_root).unwrap_or(path);
+            globs.is_match(relative)
+        });
+        if matched {
+            let _ = notify_control.send(WatchMessage::Changed);
+        }
+    })
+    .map_err(|e| TaskError::Watch(e.to_string()))?;
+    watcher
+        .watch(Path::new(workspace), RecursiveMode::Recursive)
+        .map_err(|e| TaskError::Watch(e.to_string()))?;
+
+    let status = Arc::new(Mutex::new(WatchTaskStatus {
+        id,
+        label: label.to_string(),
+        workspace: workspace.to_string(),
+        patterns,
+        debounce_ms,
+        active: true,
+        trigger_count: 0,
+        last_triggered_at: None,
+        last_run_id: None,
+        last_run_status: None,
+        error: None,
+    }));
+
+    let thread_status = status.clone();
+    let app = app.clone();
+    thread::spawn(move || {
+        let debounce = Duration::from_millis(debounce_ms);
+        while let Ok(WatchMessage::Changed) = messages.recv() {
+            // Coalesce bursts of changes (e.g. a formatter rewriting many files)
+            loop {
+                match messages.recv_timeout(debounce) {
+                    Ok(WatchMessage::Changed) => continue,
+                    Ok(WatchMessage::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                    Err(RecvTimeoutError::Timeout) => break,
+                }
+            }
+            trigger(&app, &thread_status);
+        }
+    });
+
+    Ok(WatchTask {
+        status,
+        control,
+        _watcher: watcher,
+    })
+}
+
+/// Re-run the bound task, restarting it if the previous run is still going
+fn trigger(app: &AppHandle, status: &Arc<Mutex<WatchTaskStatus>>) {
+    let tasks = app.state::<TaskService>();
+    let (workspace, label, previous_run) = {
+        let status = status.lock().unwrap();
+        (status.workspace.clone(), status.label.clone(), status.last_run_id.clone())
+    };
+
+    if let Some(previous_run) = previous_run {
+        let running =
+            |run_id: &str| tasks.run_summary(run_id).is_ok_and(|summary| !summary.status.is_finished());
+        if running(&previous_run) {
+            let _ = tasks.stop_run(&previous_run);
+            // Both runs would otherwise share the task's terminal session and output files
+            let started = Instant::now();
+            while running(&previous_run) {
+                if started.elapsed() >= RESTART_TIMEOUT {
+                    tracing::warn!(run_id = %previous_run, "cancelled task run still going; restarting anyway");
+                    break;
+                }
+                thread::sleep(RESTART_POLL_INTERVAL);
+            }
+        }
+    }
+
+    let result = tasks.run_task(app, &workspace, &label);
+    let snapshot = {
+        let mut status = status.lock().unwrap();
+        status.trigger_count += 1;
+        status.last_triggered_at = Some(now_millis());
+        match result {
+            Ok(run_id) => {
+                status.last_run_id = Some(run_id);
+                status.last_run_status = Some(TaskStatus::Pending);
+                status.error = None;
+            }
+            Err(e) => status.error = Some(e.to_string()),
+        }
+        status.clone()
+    };
+
+    let _ = app.emit(WATCH_TASK_EVENT, snapshot);
+}
@@ -0,0 +1,167 @@
+/**
+ * Link detection in terminal output
+ * Finds URLs and file:line:col references so build errors become clickable
+ */
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// How long a path's existence is trusted; builds create and delete files as they run
+const EXISTS_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Paths remembered per cache before expired ones are dropped
+const MAX_CACHED_PATHS: usize = 4096;
+
+/// Kind of link found in terminal output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminalLinkKind {
+    Url,
+    File,
+}
+
+/// A link annotation on one line of terminal output
+///
+/// Columns are UTF-16 offsets into the rendered (escape-free) line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalLink {
+    pub kind: TerminalLinkKind,
+    pub line_number: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+    pub text: String,
+    pub target: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub exists: bool,
+}
+
+fn url_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"\b(?:https?|file)://[^\s<>"'`]+"#).unwrap())
+}
+
+fn file_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(concat!(
+            // Python tracebacks: File "path", line 12
+            r#"File "(?P<py_path>[^"]+)", line (?P<py_line>\d+)"#,
+            // path:line:col, path:line, and tsc-style path(line,col)
+            r"|(?P<path>(?:[A-Za-z]:)?[\w.~@+\-/\\]*[\w\-]\.[A-Za-z0-9]+)",
+            r"(?::(?P<line>\d+)(?::(?P<col>\d+))?|\((?P<paren_line>\d+),(?P<paren_col>\d+)\))?",
+        ))
+        .unwrap()
+    })
+}
+
+/// Trailing characters that usually belong to the surrounding prose, not the link
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', ')', ']', '}', '!', '?'];
+
+/// Column of a byte offset in UTF-16 code units, the way the frontend indexes strings
+pub(super) fn utf16_offset(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset].encode_utf16().count()
+}
+
+/// Remembers whether paths found in output exist, so a path repeated across lines is checked once
+#[derive(Default)]
+pub struct PathExistsCache {
+    entries: HashMap<PathBuf, (bool, Instant)>,
+}
+
+impl PathExistsCache {
+    pub fn exists(&mut self, path: &Path) -> bool {
+        if let Some((exists, checked_at)) = self.entries.get(path) {
+            if checked_at.elapsed() < EXISTS_CACHE_TTL {
+                return *exists;
+            }
+        }
+        if self.entries.len() >= MAX_CACHED_PATHS {
+            self.entries.retain(|_, (_, checked_at)| checked_at.elapsed() < EXISTS_CACHE_TTL);
+            if self.entries.len() >= MAX_CACHED_PATHS {
+                self.entries.clear();
+            }
+        }
+        let exists = path.exists();
+        self.entries.insert(path.to_path_buf(), (exists, Instant::now()));
+        exists
+    }
+}
+
+/// Resolve a path found in output against the session's working directory
+fn resolve_path(raw: &str, cwd: Option<&Path>) -> PathBuf {
+    let path = Path::new(raw);
+    match cwd {
+        Some(cwd) if path.is_relative() => cwd.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Detect links in a single rendered line of output
+pub fn detect_links(
+    line_number: usize,
+    line: &str,
+    cwd: Option<&Path>,
+    paths: &mut PathExistsCache,
+) -> Vec<TerminalLink> {
+    let mut links = Vec::new();
+    let mut url_spans = Vec::new();
+
+    for url in url_pattern().find_iter(line) {
+        let text = url.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+        let end = url.start() + text.len();
+        url_spans.push(url.start()..end);
+        links.push(TerminalLink {
+            kind: TerminalLinkKind::Url,
+            line_number,
+            start_column: utf16_offset(line, url.start()),
+            end_column: utf16_offset(line, end),
+            text: text.to_string(),
+            target: text.to_string(),
+            line: None,
+            column: None,
+            exists: true,
+        });
+    }
+
+    for captures in file_pattern().captures_iter(line) {
+        let whole = captures.get(0).unwrap();
+        if url_spans.iter().any(|span| span.contains(&whole.start())) {
+            continue;
+        }
+
+        let (path, line_ref, column_ref) = match captures.name("py_path") {
+            Some(py_path) => (py_path.as_str(), captures.name("py_line"), None),
+            None => (
+                captures.name("path").map(|m| m.as_str()).unwrap_or_default(),
+                captures.name("line").or_else(|| captures.name("paren_line")),
+                captures.name("col").or_else(|| captures.name("paren_col")),
+            ),
+        };
+
+        let resolved = resolve_path(path, cwd);
+        let exists = paths.exists(&resolved);
+        // Without a line number, only trust the match if it names a real file (avoids "v1.2" noise)
+        if line_ref.is_none() && !exists {
+            continue;
+        }
+
+        links.push(TerminalLink {
+            kind: TerminalLinkKind::File,
+            line_number,
+            start_column: utf16_offset(line, whole.start()),
+            end_column: utf16_offset(line, whole.end()),
+            text: whole.as_str().to_string(),
+            target: resolved.to_string_lossy().to_string(),
+            line: line_ref.and_then(|m| m.as_str().parse().ok()),
+            column: column_ref.and_then(|m| m.as_str().parse().ok()),
+            exists,
+        });
+    }
+
+    links.sort_by_key(|link| link.start_column);
+    links
+}
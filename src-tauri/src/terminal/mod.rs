@@ -0,0 +1,194 @@
+/**
+ * Terminal Service for CodeForge IDE
+ * Keeps per-session scrollback so output can be searched and exported from the backend
+ */
+
+mod links;
+mod scrollback;
+
+pub use links::{detect_links, PathExistsCache, TerminalLink};
+pub use scrollback::{render_line, ScrollbackBuffer, ScrollbackMatch, ScrollbackQuery, DEFAULT_SCROLLBACK_LINES};
+
+use crate::types::{FileOperationResult, FileSystemError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted for every chunk of terminal output
+pub const TERMINAL_OUTPUT_EVENT: &str = "terminal://output";
+
+/// Payload of a terminal output event: the raw chunk plus links found in the lines it completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalOutputEvent {
+    pub session_id: String,
+    pub data: String,
+    pub links: Vec<TerminalLink>,
+}
+
+struct TerminalSession {
+    scrollback: ScrollbackBuffer,
+    cwd: Option<PathBuf>,
+    process: Option<SessionProcess>,
+    paths: PathExistsCache,
+}
+
+/// The process running in a terminal session: its shell, or a task's command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionProcess {
+    pub session_id: String,
+    pub pid: u32,
+    /// What the session runs, e.g. a task's label
+    pub title: Option<String>,
+}
+
+pub struct TerminalService {
+    sessions: Arc<Mutex<HashMap<String, TerminalSession>>>,
+    max_lines: usize,
+}
+
+impl TerminalService {
+    pub fn new() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            max_lines: DEFAULT_SCROLLBACK_LINES,
+        }
+    }
+
+    fn new_session(&self) -> TerminalSession {
+        TerminalSession {
+            scrollback: ScrollbackBuffer::new(self.max_lines),
+            cwd: None,
+            process: None,
+            paths: PathExistsCache::default(),
+        }
+    }
+
+    /// Record output produced by a session, creating its buffer on first use.
+    /// Returns links detected in the lines completed by this chunk.
+    pub fn record_output(&self, session_id: &str, chunk: &str) -> Vec<TerminalLink> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| self.new_session());
+
+        let TerminalSession { scrollback, cwd, paths, .. } = session;
+        scrollback
+            .push(chunk)
+            .iter()
+            .flat_map(|(line_number, line)| detect_links(*line_number, line, cwd.as_deref(), paths))
+            .collect()
+    }
+
+    /// Record output and forward it to the frontend together with its link annotations
+    pub fn publish_output(&self, app: &AppHandle, session_id: &str, chunk: &str) {
+        let links = self.record_output(session_id, chunk);
+        let _ = app.emit(
+            TERMINAL_OUTPUT_EVENT,
+            TerminalOutputEvent {
+                session_id: session_id.to_string(),
+                data: chunk.to_string(),
+                links,
+            },
+        );
+    }
+
+    /// Set the working directory used to resolve relative paths in a session's output
+    pub fn set_session_cwd(&self, session_id: &str, cwd: &str) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| self.new_session());
+        session.cwd = Some(PathBuf::from(cwd));
+    }
+
+    /// Working directory of a session, if known
+    pub fn session_cwd(&self, session_id: &str) -> Option<PathBuf> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.get(session_id).and_then(|session| session.cwd.clone())
+    }
+
+    /// Record the process a session runs, so what it does (like the ports it opens) can be traced to it
+    pub fn set_session_process(&self, session_id: &str, pid: u32, title: Option<&str>) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| self.new_session());
+        session.process = Some(SessionProcess {
+            session_id: session_id.to_string(),
+            pid,
+            title: title.map(str::to_string),
+        });
+    }
+
+    /// Processes of the open sessions that reported one
+    pub fn session_processes(&self) -> Vec<SessionProcess> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions.values().filter_map(|session| session.process.clone()).collect()
+    }
+
+    /// Sessions with scrollback
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Drop a session's scrollback when the session closes
+    pub fn close_session(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+
+    /// Clear a session's scrollback while keeping the session
+    pub fn clear_scrollback(&self, session_id: &str) -> Result<(), FileSystemError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get_mut(session_id).ok_or(FileSystemError::NotFound)?;
+        session.scrollback.clear();
+        Ok(())
+    }
+
+    /// Search a session's scrollback
+    pub fn search(&self, session_id: &str, query: &ScrollbackQuery) -> Result<Vec<ScrollbackMatch>, FileSystemError> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(session_id).ok_or(FileSystemError::NotFound)?;
+        session
+            .scrollback
+            .search(query)
+            .map_err(|e| FileSystemError::UnknownError(format!("Invalid search pattern: {}", e)))
+    }
+
+    /// Write a session's scrollback to a file as plain text
+    pub fn export(&self, session_id: &str, destination: &str) -> Result<FileOperationResult, FileSystemError> {
+        let sessions = self.sessions.lock().unwrap();
+        let buffer = &sessions.get(session_id).ok_or(FileSystemError::NotFound)?.scrollback;
+
+        let path = Path::new(destination);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        }
+
+        let file = File::create(path).map_err(|e| match e.kind() {
+            io::ErrorKind::PermissionDenied => FileSystemError::PermissionDenied,
+            _ => FileSystemError::IOError(e.to_string()),
+        })?;
+        let mut writer = BufWriter::new(file);
+        for (_, line) in buffer.lines() {
+            writeln!(writer, "{}", line).map_err(|e| FileSystemError::IOError(e.to_string()))?;
+        }
+        writer.flush().map_err(|e| FileSystemError::IOError(e.to_string()))?;
+
+        Ok(FileOperationResult {
+            success: true,
+            message: format!("Exported {} lines", buffer.line_count()),
+            path: Some(destination.to_string()),
+            error_code: None,
+        })
+    }
+}
+
+impl Default for TerminalService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+/**
+ * Bounded scrollback buffer for terminal sessions
+ * Stores plain-text lines (escape sequences stripped) for search and export
+ */
+
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::links::utf16_offset;
+
+/// Default number of lines retained per session
+pub const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+
+/// Longest unterminated line kept before it is stored as if a newline followed
+const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+/// A line of scrollback matching a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollbackMatch {
+    pub line_number: usize,
+    pub text: String,
+    /// Start and end UTF-16 columns of each match
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Parameters for searching scrollback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollbackQuery {
+    pub pattern: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub max_results: Option<usize>,
+}
+
+pub struct ScrollbackBuffer {
+    lines: VecDeque<String>,
+    pending: String,
+    max_lines: usize,
+    /// Absolute number of the oldest retained line, so line numbers stay stable as lines are evicted
+    first_line_number: usize,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            pending: String::new(),
+            max_lines: max_lines.max(1),
+            first_line_number: 0,
+        }
+    }
+
+    /// Append a raw output chunk from the terminal stream, returning the lines it completed
+    pub fn push(&mut self, chunk: &str) -> Vec<(usize, String)> {
+        self.pending.push_str(chunk);
+
+        let mut completed = Vec::new();
+        while let Some(newline) = self.pending.find('\n') {
+            let raw: String = self.pending.drain(..=newline).collect();
+            completed.push(self.complete_line(raw.trim_end_matches('\n')));
+        }
+        // Text before a carriage return is overwritten, which keeps progress bars from piling up
+        let rewind = self.pending.strip_suffix('\r').unwrap_or(&self.pending).rfind('\r');
+        if let Some(index) = rewind {
+            self.pending.drain(..index);
+        }
+        if self.pending.len() > MAX_PENDING_BYTES {
+            let raw = std::mem::take(&mut self.pending);
+            completed.push(self.complete_line(&raw));
+        }
+
+        while self.lines.len() > self.max_lines {
+            self.lines.pop_front();
+            self.first_line_number += 1;
+        }
+
+        completed
+    }
+
+    fn complete_line(&mut self, raw: &str) -> (usize, String) {
+        let line = render_line(raw);
+        let line_number = self.first_line_number + self.lines.len();
+        self.lines.push_back(line.clone());
+        (line_number, line)
+    }
+
+    /// All retained lines, including the unterminated last line
+    pub fn lines(&self) -> impl Iterator<Item = (usize, String)> + '_ {
+        let pending = (!self.pending.is_empty()).then(|| render_line(&self.pending));
+        self.lines
+            .iter()
+            .cloned()
+            .chain(pending)
+            .enumerate()
+            .map(move |(i, line)| (self.first_line_number + i, line))
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len() + usize::from(!self.pending.is_empty())
+    }
+
+    pub fn clear(&mut self) {
+        self.first_line_number += self.line_count();
+        self.lines.clear();
+        self.pending.clear();
+    }
+
+    /// Search retained lines for a literal or regex pattern
+    pub fn search(&self, query: &ScrollbackQuery) -> Result<Vec<ScrollbackMatch>, regex::Error> {
+        let pattern = if query.regex {
+            query.pattern.clone()
+        } else {
+            regex::escape(&query.pattern)
+        };
+        let matcher = RegexBuilder::new(&pattern)
+            .case_insensitive(!query.case_sensitive)
+            .build()?;
+
+        let limit = query.max_results.unwrap_or(usize::MAX);
+        let mut results = Vec::new();
+        for (line_number, text) in self.lines() {
+            if results.len() >= limit {
+                break;
+            }
+            let ranges: Vec<(usize, usize)> = matcher
+                .find_iter(&text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (utf16_offset(&text, m.start()), utf16_offset(&text, m.end())))
+                .collect();
+            if !ranges.is_empty() {
+                results.push(ScrollbackMatch {
+                    line_number,
+                    text,
+                    ranges,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Strip escape sequences and apply carriage returns the way a terminal would display the line
+pub fn render_line(raw: &str) -> String {
+    let text = strip_ansi(raw);
+    let text = text.strip_suffix('\r').unwrap_or(&text);
+    // A bare carriage return rewinds the cursor; progress bars rely on this
+    match text.rfind('\r') {
+        Some(index) => text[index + 1..].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Remove CSI, OSC, and two-character escape sequences
+pub fn strip_ansi(raw: &str) -> String {
+    let mut output = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameters until a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ST (ESC \)
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    output
+}
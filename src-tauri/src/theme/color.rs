@@ -0,0 +1,271 @@
+/**
+ * Color themes: workbench colors and token styles, with `extends` inheritance
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::ThemeError;
+
+/// Whether a theme is meant for a dark or light background
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeKind {
+    #[default]
+    Dark,
+    Light,
+    #[serde(alias = "hc")]
+    HighContrast,
+}
+
+/// Scopes a token rule applies to: a single scope, a comma-separated list, or an array
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TokenScope {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl TokenScope {
+    fn scopes(&self) -> Vec<String> {
+        match self {
+            TokenScope::One(scope) => {
+                scope.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+            }
+            TokenScope::Many(scopes) => scopes.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// Space-separated `italic`, `bold`, `underline`, `strikethrough`; empty clears inherited styles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_style: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TokenRule {
+    #[serde(default)]
+    scope: Option<TokenScope>,
+    #[serde(default)]
+    settings: TokenStyle,
+}
+
+/// A color theme file as written by the user
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorThemeFile {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, rename = "type")]
+    pub kind: Option<ThemeKind>,
+    /// Id of the theme this one builds on
+    #[serde(default, alias = "include")]
+    pub extends: Option<String>,
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+    #[serde(default)]
+    token_colors: Vec<TokenRule>,
+}
+
+/// A fully resolved theme, ready for the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompiledTheme {
+    pub id: String,
+    pub name: String,
+    pub kind: ThemeKind,
+    /// Theme ids from the root of the inheritance chain down to this theme
+    pub chain: Vec<String>,
+    pub colors: BTreeMap<String, String>,
+    /// Style per scope; a child's rule replaces the parent's for the same scope
+    pub token_styles: BTreeMap<String, TokenStyle>,
+    /// Problems that were skipped, e.g. malformed colors
+    pub warnings: Vec<String>,
+}
+
+const FONT_STYLES: &[&str] = &["italic", "bold", "underline", "strikethrough"];
+
+/// `#rgb`, `#rgba`, `#rrggbb`, or `#rrggbbaa`
+pub fn is_valid_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn check_color(color: &Option<String>, context: &str, warnings: &mut Vec<String>) -> Option<String> {
+    let color = color.as_ref()?;
+    if is_valid_color(color) {
+        Some(color.to_lowercase())
+    } else {
+        warnings.push(format!("{}: '{}' is not a color", context, color));
+        None
+    }
+}
+
+/// `(key, color)` pairs of a built-in theme
+type ColorTable = &'static [(&'static str, &'static str)];
+
+/// Built-in bases user themes can extend
+pub(super) fn builtin_theme(id: &str) -> Option<ColorThemeFile> {
+    let (kind, colors, tokens): (ThemeKind, ColorTable, ColorTable) = match id {
+        "dark" => (
+            ThemeKind::Dark,
+            &[
+                ("editor.background", "#1e1e1e"),
+                ("editor.foreground", "#d4d4d4"),
+                ("editor.selectionBackground", "#264f78"),
+                ("editorLineNumber.foreground", "#858585"),
+                ("sideBar.background", "#252526"),
+                ("statusBar.background", "#007acc"),
+            ],
+            &[
+                ("comment", "#6a9955"),
+                ("keyword", "#569cd6"),
+                ("string", "#ce9178"),
+                ("number", "#b5cea8"),
+                ("function", "#dcdcaa"),
+                ("type", "#4ec9b0"),
+                ("variable", "#9cdcfe"),
+            ],
+        ),
+        "light" => (
+            ThemeKind::Light,
+            &[
+                ("editor.background", "#ffffff"),
+                ("editor.foreground", "#000000"),
+                ("editor.selectionBackground", "#add6ff"),
+                ("editorLineNumber.foreground", "#237893"),
+                ("sideBar.background", "#f3f3f3"),
+                ("statusBar.background", "#007acc"),
+            ],
+            &[
+                ("comment", "#008000"),
+                ("keyword", "#0000ff"),
+                ("string", "#a31515"),
+                ("number", "#098658"),
+                ("function", "#795e26"),
+                ("type", "#267f99"),
+                ("variable", "#001080"),
+            ],
+        ),
+        _ => return None,
+    };
+    Some(ColorThemeFile {
+        name: Some(if kind == ThemeKind::Dark { "Dark" } else { "Light" }.to_string()),
+        kind: Some(kind),
+        extends: None,
+        colors: colors.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        token_colors: tokens
+            .iter()
+            .map(|(scope, foreground)| TokenRule {
+                scope: Some(TokenScope::One(scope.to_string())),
+                settings: TokenStyle {
+                    foreground: Some(foreground.to_string()),
+                    ..Default::default()
+                },
+            })
+            .collect(),
+    })
+}
+
+/// Resolve `id` and everything it extends into one theme
+pub(super) fn compile(
+    id: &str,
+    load: &dyn Fn(&str) -> Result<ColorThemeFile, ThemeError>,
+) -> Result<CompiledTheme, ThemeError> {
+    // Walk up the chain first so cycles are caught before anything is merged
+    let mut chain: Vec<(String, ColorThemeFile)> = Vec::new();
+    let mut next = Some(id.to_string());
+    while let Some(current) = next {
+        if chain.iter().any(|(seen, _)| *seen == current) {
+            let mut cycle: Vec<&str> = chain.iter().map(|(seen, _)| seen.as_str()).collect();
+            cycle.push(&current);
+            return Err(ThemeError::InheritanceCycle(cycle.join(" -> ")));
+        }
+        let file = load(&current)?;
+        next = file.extends.clone();
+        chain.push((current, file));
+    }
+    chain.reverse();
+
+    let mut warnings = Vec::new();
+    let mut colors = BTreeMap::new();
+    let mut token_styles: BTreeMap<String, TokenStyle> = BTreeMap::new();
+    let mut kind = None;
+    let mut name = None;
+    for (theme_id, file) in &chain {
+        for (key, value) in &file.colors {
+            let context = format!("{}: colors.{}", theme_id, key);
+            if let Some(color) = check_color(&Some(value.clone()), &context, &mut warnings) {
+                colors.insert(key.clone(), color);
+            }
+        }
+
+        for (index, rule) in file.token_colors.iter().enumerate() {
+            let context = format!("{}: tokenColors[{}]", theme_id, index);
+            let mut style = TokenStyle {
+                foreground: check_color(&rule.settings.foreground, &context, &mut warnings),
+                background: check_color(&rule.settings.background, &context, &mut warnings),
+                font_style: None,
+            };
+            if let Some(font_style) = &rule.settings.font_style {
+                let (known, unknown): (Vec<&str>, Vec<&str>) =
+                    font_style.split_whitespace().partition(|word| FONT_STYLES.contains(word));
+                if !unknown.is_empty() {
+                    warnings.push(format!("{}: unknown font style '{}'", context, unknown.join(" ")));
+                }
+                style.font_style = Some(known.join(" "));
+            }
+
+            // A rule without a scope styles plain text, like `editor.foreground`
+            let scopes =
+                rule.scope.as_ref().map(TokenScope::scopes).unwrap_or_else(|| vec![String::new()]);
+            for scope in scopes {
+                token_styles.insert(scope, style.clone());
+            }
+        }
+        kind = file.kind.or(kind);
+        name = file.name.clone().or(name);
+    }
+
+    Ok(CompiledTheme {
+        id: id.to_string(),
+        name: name.unwrap_or_else(|| id.to_string()),
+        kind: kind.unwrap_or_default(),
+        chain: chain.into_iter().map(|(theme_id, _)| theme_id).collect(),
+        colors,
+        token_styles,
+        warnings,
+    })
+}
+
+/// Theme ids whose inheritance chain includes `changed`
+pub(super) fn dependents(changed: &str, parents: &HashMap<String, Option<String>>) -> Vec<String> {
+    let mut affected: Vec<String> = parents
+        .keys()
+        .filter(|id| {
+            let mut current = Some((*id).clone());
+            let mut steps = 0;
+            while let Some(theme) = current {
+                if theme == changed {
+                    return true;
+                }
+                steps += 1;
+                if steps > parents.len() {
+                    return false;
+                }
+                current = parents.get(&theme).cloned().flatten();
+            }
+            false
+        })
+        .cloned()
+        .collect();
+    affected.sort();
+    affected
+}
@@ -0,0 +1,113 @@
+/**
+ * Icon theme packs: `themes/<id>/icon-theme.json` plus the icon files it references
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use super::ThemeError;
+
+/// Manifest inside every icon theme pack directory
+pub const ICON_THEME_FILE: &str = "icon-theme.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconDefinition {
+    /// Relative to the pack directory in the file, absolute once compiled
+    pub icon_path: String,
+}
+
+/// An icon theme as written by the user; mappings name entries of `icon_definitions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IconThemeFile {
+    pub name: Option<String>,
+    pub icon_definitions: BTreeMap<String, IconDefinition>,
+    pub file: Option<String>,
+    pub folder: Option<String>,
+    pub folder_expanded: Option<String>,
+    pub file_extensions: BTreeMap<String, String>,
+    pub file_names: BTreeMap<String, String>,
+    pub folder_names: BTreeMap<String, String>,
+    pub language_ids: BTreeMap<String, String>,
+}
+
+/// A validated icon theme with icon paths resolved to absolute paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompiledIconTheme {
+    pub id: String,
+    pub name: String,
+    pub theme: IconThemeFile,
+    pub warnings: Vec<String>,
+}
+
+/// Check references and icon files, dropping whatever is broken and noting it as a warning
+pub(super) fn compile(id: &str, dir: &Path, mut theme: IconThemeFile) -> Result<CompiledIconTheme, ThemeError> {
+    let mut warnings = Vec::new();
+
+    let mut definitions = BTreeMap::new();
+    for (name, definition) in std::mem::take(&mut theme.icon_definitions) {
+        let relative = Path::new(&definition.icon_path);
+        // Packs are third-party content, so icons may not point outside the pack
+        let inside_pack =
+            relative.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !inside_pack {
+            warnings.push(format!("iconDefinitions.{}: '{}' is outside the pack", name, definition.icon_path));
+            continue;
+        }
+        let path = dir.join(relative);
+        if !path.is_file() {
+            warnings.push(format!("iconDefinitions.{}: '{}' does not exist", name, definition.icon_path));
+            continue;
+        }
+        definitions.insert(
+            name,
+            IconDefinition {
+                icon_path: path.to_string_lossy().to_string(),
+            },
+        );
+    }
+    if definitions.is_empty() {
+        return Err(ThemeError::Invalid {
+            theme: id.to_string(),
+            message: "no usable icon definitions".to_string(),
+        });
+    }
+
+    let mut check = |field: &str, reference: Option<String>| {
+        let reference = reference?;
+        if definitions.contains_key(&reference) {
+            Some(reference)
+        } else {
+            warnings.push(format!("{}: unknown icon '{}'", field, reference));
+            None
+        }
+    };
+    theme.file = check("file", theme.file.take());
+    theme.folder = check("folder", theme.folder.take());
+    theme.folder_expanded = check("folderExpanded", theme.folder_expanded.take());
+    for (field, map) in [
+        ("fileExtensions", &mut theme.file_extensions),
+        ("fileNames", &mut theme.file_names),
+        ("folderNames", &mut theme.folder_names),
+        ("languageIds", &mut theme.language_ids),
+    ] {
+        *map = std::mem::take(map)
+            .into_iter()
+            .filter_map(|(key, icon)| {
+                let icon = check(&format!("{}.{}", field, key), Some(icon))?;
+                // Lookups are case-insensitive on the frontend; extensions are stored without the dot
+                Some((key.trim_start_matches('.').to_lowercase(), icon))
+            })
+            .collect();
+    }
+    theme.icon_definitions = definitions;
+
+    Ok(CompiledIconTheme {
+        id: id.to_string(),
+        name: theme.name.clone().unwrap_or_else(|| id.to_string()),
+        theme,
+        warnings,
+    })
+}
@@ -0,0 +1,380 @@
+/**
+ * Theme Service for CodeForge IDE
+ * Loads color themes and icon theme packs from the app config `themes` directory, resolves inheritance,
+ * and reloads them live while they are being edited
+ *
+ * Layout:
+ * - `themes/<id>.json` is a color theme
+ * - `themes/<id>/icon-theme.json` is an icon theme pack, with its icons alongside
+ */
+
+mod color;
+mod icons;
+
+pub use color::{CompiledTheme, ThemeKind, TokenStyle};
+pub use icons::{CompiledIconTheme, IconThemeFile, ICON_THEME_FILE};
+
+use crate::jsonc;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use color::ColorThemeFile;
+
+/// Event carrying recompiled themes after a theme file changes on disk
+pub const THEME_CHANGED_EVENT: &str = "theme://changed";
+
+/// Directory under the app config dir holding user themes
+pub const THEMES_DIR: &str = "themes";
+
+/// Color themes that ship with the IDE and can be extended
+pub const BUILTIN_THEMES: [&str; 2] = ["dark", "light"];
+
+/// Editors often save in several steps (truncate, write, rename), so changes are coalesced
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Error types for theme operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ThemeError {
+    NoConfigDirectory,
+    IOError(String),
+    NotFound(String),
+    Invalid { theme: String, message: String },
+    InheritanceCycle(String),
+}
+
+impl std::fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ThemeError::NoConfigDirectory => write!(f, "App config directory is unavailable"),
+            ThemeError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            ThemeError::NotFound(id) => write!(f, "Theme not found: {}", id),
+            ThemeError::Invalid { theme, message } => write!(f, "Invalid theme {}: {}", theme, message),
+            ThemeError::InheritanceCycle(chain) => write!(f, "Theme inheritance cycle: {}", chain),
+        }
+    }
+}
+
+/// Theme picker entry; broken themes are listed with their error so they can be fixed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSummary {
+    pub id: String,
+    pub name: String,
+    pub kind: Option<ThemeKind>,
+    pub extends: Option<String>,
+    pub builtin: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconThemeSummary {
+    pub id: String,
+    pub name: String,
+    pub error: Option<String>,
+}
+
+/// One theme affected by a change on disk; `theme` is `None` when it no longer compiles or was deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ThemeChange {
+    Color {
+        id: String,
+        theme: Option<CompiledTheme>,
+        error: Option<String>,
+    },
+    Icon {
+        id: String,
+        theme: Option<CompiledIconTheme>,
+        error: Option<String>,
+    },
+}
+
+/// Theme ids become file names, so they are kept to a safe character set
+fn is_valid_theme_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn read_jsonc<T: for<'de> Deserialize<'de>>(id: &str, path: &Path) -> Result<T, ThemeError> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => ThemeError::NotFound(id.to_string()),
+        _ => ThemeError::IOError(e.to_string()),
+    })?;
+    serde_json::from_str(&jsonc::strip(&content)).map_err(|e| ThemeError::Invalid {
+        theme: id.to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn load_color_theme_file(dir: &Path, id: &str) -> Result<ColorThemeFile, ThemeError> {
+    if let Some(builtin) = color::builtin_theme(id) {
+        return Ok(builtin);
+    }
+    if !is_valid_theme_id(id) {
+        return Err(ThemeError::NotFound(id.to_string()));
+    }
+    read_jsonc(id, &dir.join(format!("{}.json", id)))
+}
+
+fn compile_color_theme(dir: &Path, id: &str) -> Result<CompiledTheme, ThemeError> {
+    color::compile(id, &|theme_id: &str| load_color_theme_file(dir, theme_id))
+}
+
+fn compile_icon_theme(dir: &Path, id: &str) -> Result<CompiledIconTheme, ThemeError> {
+    if !is_valid_theme_id(id) {
+        return Err(ThemeError::NotFound(id.to_string()));
+    }
+    let pack_dir = dir.join(id);
+    let file: IconThemeFile = read_jsonc(id, &pack_dir.join(ICON_THEME_FILE))?;
+    icons::compile(id, &pack_dir, file)
+}
+
+/// Ids of the user color themes in the themes directory
+fn user_theme_ids(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                .filter(|id| is_valid_theme_id(id))
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+/// Ids of the icon theme packs in the themes directory
+fn icon_theme_ids(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.join(ICON_THEME_FILE).is_file())
+                .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+                .filter(|id| is_valid_theme_id(id))
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+/// Recompile every theme affected by changes to `paths`
+fn reload(dir: &Path, paths: &BTreeSet<PathBuf>) -> Vec<ThemeChange> {
+    let mut changed_colors = BTreeSet::new();
+    let mut changed_icons = BTreeSet::new();
+    for path in paths {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let mut components = relative.components();
+        let Some(first) = components.next() else {
+            continue;
+        };
+        let first = Path::new(first.as_os_str());
+        if components.next().is_some() {
+            // Anything inside a pack (manifest or icon) affects that icon theme
+            changed_icons.insert(first.to_string_lossy().to_string());
+        } else if first.extension().is_some_and(|ext| ext == "json") {
+            if let Some(stem) = first.file_stem() {
+                changed_colors.insert(stem.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    let parents: HashMap<String, Option<String>> = user_theme_ids(dir)
+        .into_iter()
+        .map(|id| {
+            let extends = load_color_theme_file(dir, &id).ok().and_then(|file| file.extends);
+            (id, extends)
+        })
+        .collect();
+    let mut affected: BTreeSet<String> = BTreeSet::new();
+    let user_themes =
+        changed_colors.iter().filter(|id| is_valid_theme_id(id) && !BUILTIN_THEMES.contains(&id.as_str()));
+    for id in user_themes {
+        affected.insert(id.clone());
+        affected.extend(color::dependents(id, &parents));
+    }
+
+    let mut changes: Vec<ThemeChange> = affected
+        .into_iter()
+        .map(|id| {
+            let result = compile_color_theme(dir, &id);
+            ThemeChange::Color {
+                id,
+                error: result.as_ref().err().map(|e| e.to_string()),
+                theme: result.ok(),
+            }
+        })
+        .collect();
+    changes.extend(changed_icons.into_iter().filter(|id| is_valid_theme_id(id)).map(|id| {
+        let result = compile_icon_theme(dir, &id);
+        ThemeChange::Icon {
+            id,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            theme: result.ok(),
+        }
+    }));
+    changes
+}
+
+pub struct ThemeService {
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+}
+
+impl ThemeService {
+    pub fn new() -> Self {
+        Self {
+            watcher: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The themes directory, created on first use so users can find where to put their files
+    pub fn themes_dir(app: &AppHandle) -> Result<PathBuf, ThemeError> {
+        let config_dir = app.path().app_config_dir().map_err(|_| ThemeError::NoConfigDirectory)?;
+        let dir = config_dir.join(THEMES_DIR);
+        fs::create_dir_all(&dir).map_err(|e| ThemeError::IOError(e.to_string()))?;
+        Ok(dir)
+    }
+
+    /// Built-in and user color themes
+    pub fn list_themes(&self, app: &AppHandle) -> Result<Vec<ThemeSummary>, ThemeError> {
+        let dir = Self::themes_dir(app)?;
+        let summary = |id: String, builtin: bool| match compile_color_theme(&dir, &id) {
+            Ok(theme) => ThemeSummary {
+                extends: load_color_theme_file(&dir, &id).ok().and_then(|file| file.extends),
+                id: theme.id,
+                name: theme.name,
+                kind: Some(theme.kind),
+                builtin,
+                error: None,
+            },
+            Err(e) => ThemeSummary {
+                name: id.clone(),
+                id,
+                kind: None,
+                extends: None,
+                builtin,
+                error: Some(e.to_string()),
+            },
+        };
+
+        let mut themes: Vec<ThemeSummary> =
+            BUILTIN_THEMES.iter().map(|id| summary(id.to_string(), true)).collect();
+        for id in user_theme_ids(&dir) {
+            if BUILTIN_THEMES.contains(&id.as_str()) {
+                themes.push(ThemeSummary {
+                    name: id.clone(),
+                    error: Some(format!("'{}' is the id of a built-in theme; rename the file", id)),
+                    id,
+                    kind: None,
+                    extends: None,
+                    builtin: false,
+                });
+            } else {
+                themes.push(summary(id, false));
+            }
+        }
+        Ok(themes)
+    }
+
+    /// Compile a color theme with everything it extends
+    pub fn get_theme(&self, app: &AppHandle, id: &str) -> Result<CompiledTheme, ThemeError> {
+        compile_color_theme(&Self::themes_dir(app)?, id)
+    }
+
+    pub fn list_icon_themes(&self, app: &AppHandle) -> Result<Vec<IconThemeSummary>, ThemeError> {
+        let dir = Self::themes_dir(app)?;
+        Ok(icon_theme_ids(&dir)
+            .into_iter()
+            .map(|id| match compile_icon_theme(&dir, &id) {
+                Ok(theme) => IconThemeSummary {
+                    id: theme.id,
+                    name: theme.name,
+                    error: None,
+                },
+                Err(e) => IconThemeSummary {
+                    name: id.clone(),
+                    id,
+                    error: Some(e.to_string()),
+                },
+            })
+            .collect())
+    }
+
+    pub fn get_icon_theme(&self, app: &AppHandle, id: &str) -> Result<CompiledIconTheme, ThemeError> {
+        compile_icon_theme(&Self::themes_dir(app)?, id)
+    }
+
+    /// Watch the themes directory and emit recompiled themes as they are edited
+    pub fn start_watch(&self, app: &AppHandle) -> Result<(), ThemeError> {
+        let mut current = self.watcher.lock().unwrap();
+        if current.is_some() {
+            return Ok(());
+        }
+
+        let dir = Self::themes_dir(app)?;
+        let (sender, paths) = mpsc::channel::<Vec<PathBuf>>();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                let _ = sender.send(event.paths);
+            }
+        })
+        .map_err(|e| ThemeError::IOError(e.to_string()))?;
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .map_err(|e| ThemeError::IOError(e.to_string()))?;
+
+        // The thread ends when the watcher (and with it the sender) is dropped by `stop_watch`
+        let app = app.clone();
+        thread::spawn(move || {
+            while let Ok(first) = paths.recv() {
+                let mut changed: BTreeSet<PathBuf> = first.into_iter().collect();
+                loop {
+                    match paths.recv_timeout(RELOAD_DEBOUNCE) {
+                        Ok(more) => changed.extend(more),
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                let changes = reload(&dir, &changed);
+                if !changes.is_empty() {
+                    let _ = app.emit(THEME_CHANGED_EVENT, changes);
+                }
+            }
+        });
+
+        *current = Some(watcher);
+        Ok(())
+    }
+
+    pub fn stop_watch(&self) {
+        self.watcher.lock().unwrap().take();
+    }
+
+    pub fn watcher_count(&self) -> usize {
+        usize::from(self.watcher.lock().unwrap().is_some())
+    }
+}
+
+impl Default for ThemeService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,7 +3,7 @@
  * Defines common data structures used across the application
  */
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// File metadata information
@@ -15,8 +15,13 @@ pub struct FileMetadata {
     pub is_directory: bool,
     pub is_file: bool,
     pub is_symlink: bool,
+    /// Where the symlink points, as stored in the link
+    pub symlink_target: Option<String>,
     pub readonly: bool,
     pub hidden: bool,
+    /// Owner and group names on Unix
+    pub owner: Option<String>,
+    pub group: Option<String>,
     pub created: Option<u64>,
     pub modified: Option<u64>,
     pub accessed: Option<u64>,
@@ -31,10 +36,14 @@ pub struct DirectoryEntry {
     pub name: String,
     pub path: String,
     pub is_directory: bool,
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
     pub size: Option<u64>,
     pub modified: Option<u64>,
     pub permissions: String,
     pub icon: String,
+    /// Files grouped under this one by the file nesting rules; they aren't listed separately
+    pub children_nested: Vec<DirectoryEntry>,
 }
 
 /// File operation result
@@ -56,6 +65,27 @@ pub struct FileContent {
     pub is_binary: bool,
 }
 
+/// One row of a hex dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexRow {
+    pub offset: u64,
+    pub hex: String,
+    /// Printable ASCII, with `.` for everything else
+    pub ascii: String,
+}
+
+/// A window of a file as hex rows, for the hex viewer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexDump {
+    pub path: String,
+    pub offset: u64,
+    /// Bytes actually read; less than requested at the end of the file
+    pub length: u64,
+    pub total_size: u64,
+    pub bytes_per_row: usize,
+    pub rows: Vec<HexRow>,
+}
+
 /// Directory listing response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectoryListing {
@@ -63,9 +93,55 @@ pub struct DirectoryListing {
     pub entries: Vec<DirectoryEntry>,
     pub total_count: usize,
     pub hidden_count: usize,
+    /// Entries left out by the filter
+    pub filtered_count: usize,
     pub error: Option<String>,
 }
 
+/// Key a directory listing is sorted by; directories always come first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectorySortKey {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    /// By extension, then name
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DirectorySort {
+    pub key: DirectorySortKey,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// Which entries a directory listing includes; an empty filter includes everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectoryFilter {
+    /// Case-insensitive globs matched against entry names, e.g. `*.test.ts`; any may match
+    pub patterns: Vec<String>,
+    /// Extensions without the dot, e.g. `rs`; any may match
+    pub extensions: Vec<String>,
+    /// Apply the filter to directories as well instead of always listing them
+    pub filter_directories: bool,
+}
+
+/// One page of a directory listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryPage {
+    pub path: String,
+    pub entries: Vec<DirectoryEntry>,
+    /// Pass back to get the next page; `None` on the last page
+    pub cursor: Option<String>,
+    pub total_count: usize,
+    pub hidden_count: usize,
+    /// Entries left out by the filter
+    pub filtered_count: usize,
+}
+
 /// File watcher event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchEvent {
@@ -96,6 +172,45 @@ pub struct SystemInfo {
     pub current_dir: Option<String>,
     pub temp_dir: String,
     pub path_separator: String,
+    /// e.g. `Ubuntu 24.04` or `Windows 11 Pro`
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub disks: Vec<DiskInfo>,
+    /// Graphics adapters as the OS names them
+    pub gpus: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuInfo {
+    pub model: String,
+    pub vendor: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub frequency_mhz: u64,
+}
+
+/// Sizes in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryInfo {
+    pub total: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+}
+
+/// A mounted disk; sizes in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    /// `HDD`, `SSD`, or `Unknown`
+    pub kind: String,
+    pub removable: bool,
+    pub total: u64,
+    pub available: u64,
 }
 
 /// Configuration for file operations
@@ -104,6 +219,7 @@ pub struct FileOperationConfig {
     pub overwrite: bool,
     pub create_parent_dirs: bool,
     pub preserve_permissions: bool,
+    /// Read, copy and stat through symlinks instead of acting on the links themselves; off by default
     pub follow_symlinks: bool,
 }
 
@@ -114,6 +230,11 @@ pub enum FileSystemError {
     PermissionDenied,
     AlreadyExists,
     InvalidPath,
+    /// The path is outside the open workspace roots and was not explicitly allowed
+    AccessDenied(String),
+    Unsupported(String),
+    /// The file changed on disk since it was read, carrying what is on disk now
+    ConflictingChange { disk_content: String, modified: u64 },
     IOError(String),
     UnknownError(String),
 }
@@ -125,6 +246,11 @@ impl std::fmt::Display for FileSystemError {
             FileSystemError::PermissionDenied => write!(f, "Permission denied"),
             FileSystemError::AlreadyExists => write!(f, "File or directory already exists"),
             FileSystemError::InvalidPath => write!(f, "Invalid path"),
+            FileSystemError::AccessDenied(path) => {
+                write!(f, "Access denied: {} is outside the open workspace", path)
+            }
+            FileSystemError::Unsupported(msg) => write!(f, "Not supported: {}", msg),
+            FileSystemError::ConflictingChange { .. } => write!(f, "File was changed on disk since it was read"),
             FileSystemError::IOError(msg) => write!(f, "IO Error: {}", msg),
             FileSystemError::UnknownError(msg) => write!(f, "Unknown error: {}", msg),
         }
@@ -192,6 +318,21 @@ pub struct GitInfo {
     pub behind: usize,
 }
 
+/// Formatter used for a file type when formatting on save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FormatterConfig {
+    /// A program that reads the buffer on stdin and writes the formatted text to stdout; `${file}` in `args`
+    /// is replaced with the path of the file
+    External {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// The language server of the file, which the editor talks to
+    Lsp,
+}
+
 /// Application preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppPreferences {
@@ -201,8 +342,76 @@ pub struct AppPreferences {
     pub tab_size: u8,
     pub word_wrap: bool,
     pub show_hidden_files: bool,
+    /// Group companion files under the file they belong to in the explorer, by `file_nesting_patterns`
+    pub file_nesting: bool,
+    /// Parent file name patterns with at most one `*`, each mapped to comma-separated child patterns in which
+    /// `${capture}` is what the `*` matched, e.g. `"*.js": "${capture}.js.map"`
+    pub file_nesting_patterns: BTreeMap<String, String>,
+    /// How file watchers learn about changes: "native" events, "polling", or "auto" to poll only on network
+    /// shares and VM shared folders, where native events miss changes made elsewhere
+    pub file_watcher_mode: String,
+    /// Milliseconds between scans when polling
+    pub file_watcher_poll_interval: u32,
+    /// When polling, compare file contents and not just modification times and sizes
+    pub file_watcher_poll_contents: bool,
     pub auto_save: bool,
     pub auto_save_delay: u32,
+    /// Strip spaces and tabs at the end of lines when saving; `.editorconfig` takes precedence
+    pub trim_trailing_whitespace: bool,
+    /// End files with a line break when saving; `.editorconfig` takes precedence
+    pub insert_final_newline: bool,
+    /// Line endings written on save: "lf", "crlf", "cr", or "auto" to keep them as they are
+    pub end_of_line: String,
+    /// Run the formatter configured for a file's extension before saving it
+    pub format_on_save: bool,
+    /// Milliseconds a formatter gets before the file is saved unformatted
+    pub format_on_save_timeout: u32,
+    /// Formatters by file extension, without the dot
+    pub formatters: BTreeMap<String, FormatterConfig>,
+    /// License text put at the top of new files, and checked for by the license header tool; `{{year}}` and
+    /// the other file template variables are replaced. Empty for none.
+    pub license_header: String,
+    /// Boilerplate for new files by file extension (without the dot) or language id. Placeholders such as
+    /// `{{file_name}}`, `{{date}}`, and `{{author}}` are replaced; `{{license_header}}` becomes the license
+    /// header as a comment.
+    pub file_templates: BTreeMap<String, String>,
+    /// Base `https://` URL of the extension registry; empty disables the marketplace
+    pub extension_registry_url: String,
+    /// Base64 ed25519 key extension releases must be signed with; installing needs one
+    pub extension_registry_key: String,
+    /// Endpoint crash reports are posted to when the user chooses to submit one; empty disables submitting
+    pub crash_report_url: String,
+}
+
+impl Default for AppPreferences {
+    fn default() -> Self {
+        Self {
+            theme: "dark".to_string(),
+            font_family: "'JetBrains Mono', Menlo, Consolas, monospace".to_string(),
+            font_size: 14,
+            tab_size: 4,
+            word_wrap: false,
+            show_hidden_files: false,
+            file_nesting: false,
+            file_nesting_patterns: crate::file_nesting::default_patterns(),
+            file_watcher_mode: "auto".to_string(),
+            file_watcher_poll_interval: 2000,
+            file_watcher_poll_contents: false,
+            auto_save: false,
+            auto_save_delay: 1000,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            end_of_line: "auto".to_string(),
+            format_on_save: false,
+            format_on_save_timeout: 3000,
+            formatters: BTreeMap::new(),
+            license_header: String::new(),
+            file_templates: BTreeMap::new(),
+            extension_registry_url: String::new(),
+            extension_registry_key: String::new(),
+            crash_report_url: String::new(),
+        }
+    }
 }
 
 /// Command execution result
@@ -0,0 +1,150 @@
+/**
+ * Window management for CodeForge IDE
+ * Opens additional IDE windows, each with its own workspace, and tracks which window has which workspace
+ * open so the same folder is focused rather than opened twice
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+/// Labels of windows opened after the main one; capabilities grant these the same permissions
+const WINDOW_LABEL_PREFIX: &str = "workspace-";
+
+const DEFAULT_WINDOW_SIZE: (f64, f64) = (1200.0, 800.0);
+
+/// Error types for window operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WindowError {
+    NotFound(String),
+    WindowError(String),
+}
+
+impl std::fmt::Display for WindowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WindowError::NotFound(label) => write!(f, "Window not found: {}", label),
+            WindowError::WindowError(msg) => write!(f, "Window Error: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub label: String,
+    pub title: String,
+    pub workspace: Option<String>,
+    pub focused: bool,
+}
+
+/// Workspaces are compared by their resolved path
+fn normalize_workspace(workspace: &str) -> String {
+    Path::new(workspace)
+        .canonicalize()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| workspace.to_string())
+}
+
+fn show_and_focus(window: &WebviewWindow) -> Result<(), WindowError> {
+    let window_error = |e: tauri::Error| WindowError::WindowError(e.to_string());
+    window.unminimize().map_err(window_error)?;
+    window.show().map_err(window_error)?;
+    window.set_focus().map_err(window_error)
+}
+
+pub struct WindowManager {
+    /// Workspace open in each window, by window label
+    workspaces: Arc<Mutex<HashMap<String, String>>>,
+    next_window: AtomicU64,
+}
+
+impl WindowManager {
+    pub fn new() -> Self {
+        Self {
+            workspaces: Arc::new(Mutex::new(HashMap::new())),
+            next_window: AtomicU64::new(1),
+        }
+    }
+
+    pub fn workspace(&self, label: &str) -> Option<String> {
+        self.workspaces.lock().unwrap().get(label).cloned()
+    }
+
+    /// Record the workspace a window has open, or that it has none
+    pub fn set_workspace(&self, label: &str, workspace: Option<&str>) {
+        let mut workspaces = self.workspaces.lock().unwrap();
+        match workspace {
+            Some(workspace) => workspaces.insert(label.to_string(), normalize_workspace(workspace)),
+            None => workspaces.remove(label),
+        };
+    }
+
+    /// Label of the window that has `workspace` open
+    pub fn window_for(&self, workspace: &str) -> Option<String> {
+        let workspace = normalize_workspace(workspace);
+        self.workspaces
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, open)| **open == workspace)
+            .map(|(label, _)| label.clone())
+    }
+
+    /// Forget a closed window, returning the workspace it had open
+    pub fn forget(&self, label: &str) -> Option<String> {
+        self.workspaces.lock().unwrap().remove(label)
+    }
+
+    /// Open a new window, optionally with a workspace. A workspace already open in another window focuses
+    /// that window instead. Returns the label of the window showing the workspace.
+    pub fn open_window(&self, app: &AppHandle, workspace: Option<&str>) -> Result<String, WindowError> {
+        if let Some(label) = workspace.and_then(|workspace| self.window_for(workspace)) {
+            if let Some(window) = app.get_webview_window(&label) {
+                show_and_focus(&window)?;
+                return Ok(label);
+            }
+        }
+
+        let label = format!("{}{}", WINDOW_LABEL_PREFIX, self.next_window.fetch_add(1, Ordering::SeqCst));
+        let title = workspace
+            .and_then(|workspace| Path::new(workspace).file_name())
+            .map(|name| format!("{} - CodeForge", name.to_string_lossy()))
+            .unwrap_or_else(|| "CodeForge".to_string());
+        WebviewWindowBuilder::new(app, &label, WebviewUrl::App("index.html".into()))
+            .title(title)
+            .inner_size(DEFAULT_WINDOW_SIZE.0, DEFAULT_WINDOW_SIZE.1)
+            .build()
+            .map_err(|e| WindowError::WindowError(e.to_string()))?;
+        self.set_workspace(&label, workspace);
+        Ok(label)
+    }
+
+    pub fn list(&self, app: &AppHandle) -> Vec<WindowInfo> {
+        let mut windows: Vec<WindowInfo> = app
+            .webview_windows()
+            .into_iter()
+            .map(|(label, window)| WindowInfo {
+                title: window.title().unwrap_or_default(),
+                workspace: self.workspace(&label),
+                focused: window.is_focused().unwrap_or(false),
+                label,
+            })
+            .collect();
+        windows.sort_by(|a, b| a.label.cmp(&b.label));
+        windows
+    }
+
+    pub fn focus(&self, app: &AppHandle, label: &str) -> Result<(), WindowError> {
+        let window = app.get_webview_window(label).ok_or_else(|| WindowError::NotFound(label.to_string()))?;
+        show_and_focus(&window)
+    }
+}
+
+impl Default for WindowManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,403 @@
+/**
+ * Workspace edits for CodeForge IDE
+ * Applies a batch of text edits and file create/rename/delete operations all-or-nothing, as needed for
+ * language server renames and refactorings that touch many files
+ *
+ * Operations run in order and each one records how to undo itself. Deleted and overwritten files are
+ * moved aside rather than removed until the whole batch has succeeded, so a failure part way through
+ * can put every file back exactly as it was.
+ */
+
+use crate::syntax::SourceRange;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A replacement within a document; columns are UTF-16 code units, as sent by language servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub range: SourceRange,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WorkspaceEditOperation {
+    /// Edit an existing file
+    Edit { path: String, edits: Vec<TextEdit> },
+    /// Create a file, optionally with content
+    Create {
+        path: String,
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        overwrite: bool,
+        #[serde(default)]
+        ignore_if_exists: bool,
+    },
+    /// Rename or move a file or directory
+    Rename {
+        old_path: String,
+        new_path: String,
+        #[serde(default)]
+        overwrite: bool,
+        #[serde(default)]
+        ignore_if_exists: bool,
+    },
+    /// Delete a file, or a directory when `recursive`
+    Delete {
+        path: String,
+        #[serde(default)]
+        recursive: bool,
+        #[serde(default)]
+        ignore_if_not_exists: bool,
+    },
+}
+
+impl WorkspaceEditOperation {
+    /// Every path the operation reads or writes
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            WorkspaceEditOperation::Edit { path, .. }
+            | WorkspaceEditOperation::Create { path, .. }
+            | WorkspaceEditOperation::Delete { path, .. } => vec![path],
+            WorkspaceEditOperation::Rename { old_path, new_path, .. } => vec![old_path, new_path],
+        }
+    }
+}
+
+/// Files touched by an applied workspace edit, so open editors can reload them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceEditResult {
+    pub edited: Vec<String>,
+    pub created: Vec<String>,
+    pub renamed: Vec<(String, String)>,
+    pub deleted: Vec<String>,
+}
+
+/// Error types for workspace edits; `index` is the position of the failing operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkspaceEditError {
+    InvalidOperation { index: usize, message: String },
+    OperationFailed { index: usize, message: String },
+    /// The batch failed and some changes could not be undone
+    RollbackFailed { index: usize, message: String, errors: Vec<String> },
+}
+
+impl std::fmt::Display for WorkspaceEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WorkspaceEditError::InvalidOperation { index, message } => {
+                write!(f, "Invalid workspace edit operation {}: {}", index, message)
+            }
+            WorkspaceEditError::OperationFailed { index, message } => {
+                write!(f, "Workspace edit operation {} failed, no changes were made: {}", index, message)
+            }
+            WorkspaceEditError::RollbackFailed { index, message, errors } => write!(
+                f,
+                "Workspace edit operation {} failed ({}) and could not be fully undone: {}",
+                index,
+                message,
+                errors.join("; ")
+            ),
+        }
+    }
+}
+
+/// How to undo one completed step
+enum UndoStep {
+    /// Restore a file's previous bytes
+    Restore { path: PathBuf, content: Vec<u8> },
+    /// Remove a file created by the edit
+    RemoveFile(PathBuf),
+    /// Remove a directory created by the edit, if it is still empty
+    RemoveDirectory(PathBuf),
+    /// Move something back to where it was
+    Move { from: PathBuf, to: PathBuf },
+    /// Put back a file or directory that was moved aside; removed for good on commit
+    Unstash { stash: PathBuf, path: PathBuf },
+}
+
+enum Failure {
+    Invalid(String),
+    Failed(String),
+}
+
+fn io_failure(path: &Path, e: std::io::Error) -> Failure {
+    Failure::Failed(format!("{}: {}", path.to_string_lossy(), e))
+}
+
+/// Byte offset of a UTF-16 position; columns past the end of the line clamp to its end
+fn byte_offset(source: &str, line_starts: &[usize], line: usize, column: usize) -> Option<usize> {
+    let start = *line_starts.get(line)?;
+    let end = line_starts.get(line + 1).map_or(source.len(), |next| next - 1);
+    let text = source[start..end].strip_suffix('\r').unwrap_or(&source[start..end]);
+
+    let mut units = 0;
+    for (offset, c) in text.char_indices() {
+        if units >= column {
+            return Some(start + offset);
+        }
+        units += c.len_utf16();
+    }
+    Some(start + text.len())
+}
+
+/// Apply non-overlapping edits to a document
+pub fn apply_text_edits(source: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut line_starts = vec![0];
+    line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+
+    let mut spans = Vec::with_capacity(edits.len());
+    for (order, edit) in edits.iter().enumerate() {
+        let range = edit.range;
+        let start = byte_offset(source, &line_starts, range.start_line, range.start_column);
+        let end = byte_offset(source, &line_starts, range.end_line, range.end_column);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => spans.push((start, end, order)),
+            _ => {
+                return Err(format!(
+                    "edit {}:{}-{}:{} is outside the document",
+                    range.start_line, range.start_column, range.end_line, range.end_column
+                ))
+            }
+        }
+    }
+
+    // Inserts at the same position keep the order they were given in
+    spans.sort_by_key(|&(start, end, order)| (start, end, order));
+    if spans.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+        return Err("edits overlap".to_string());
+    }
+
+    let mut output = String::with_capacity(source.len());
+    let mut position = 0;
+    for (start, end, order) in spans {
+        output.push_str(&source[position..start]);
+        output.push_str(&edits[order].new_text);
+        position = end;
+    }
+    output.push_str(&source[position..]);
+    Ok(output)
+}
+
+/// Write through a temporary file so a crash never leaves a half-written document
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    fs::write(&temp_path, content)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        let _ = fs::set_permissions(&temp_path, metadata.permissions());
+    }
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+struct Transaction {
+    stamp: u128,
+    stashed: usize,
+    undo: Vec<UndoStep>,
+    result: WorkspaceEditResult,
+}
+
+impl Transaction {
+    /// Create missing parent directories, remembering them so rollback can remove them again
+    fn create_parents(&mut self, path: &Path) -> Result<(), Failure> {
+        let mut missing = Vec::new();
+        let mut current = path.parent();
+        while let Some(dir) = current.filter(|dir| !dir.as_os_str().is_empty() && !dir.exists()) {
+            missing.push(dir.to_path_buf());
+            current = dir.parent();
+        }
+        for dir in missing.into_iter().rev() {
+            fs::create_dir(&dir).map_err(|e| io_failure(&dir, e))?;
+            self.undo.push(UndoStep::RemoveDirectory(dir));
+        }
+        Ok(())
+    }
+
+    /// Move `path` next to itself under a hidden name until the batch commits
+    fn stash(&mut self, path: &Path) -> Result<(), Failure> {
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let stash_name = format!(".{}.codeforge-edit-{}-{}", file_name, self.stamp, self.stashed);
+        let stash = path.with_file_name(stash_name);
+        self.stashed += 1;
+        fs::rename(path, &stash).map_err(|e| io_failure(path, e))?;
+        self.undo.push(UndoStep::Unstash {
+            stash,
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    fn apply(&mut self, operation: &WorkspaceEditOperation) -> Result<(), Failure> {
+        match operation {
+            WorkspaceEditOperation::Edit { path, edits } => {
+                let file_path = PathBuf::from(path);
+                let original = fs::read(&file_path).map_err(|e| io_failure(&file_path, e))?;
+                let source = String::from_utf8(original)
+                    .map_err(|_| Failure::Invalid(format!("{} is not a UTF-8 text file", path)))?;
+                let edited =
+                    apply_text_edits(&source, edits).map_err(|e| Failure::Invalid(format!("{}: {}", path, e)))?;
+                if edited == source {
+                    return Ok(());
+                }
+                write_atomic(&file_path, edited.as_bytes()).map_err(|e| io_failure(&file_path, e))?;
+                self.undo.push(UndoStep::Restore {
+                    path: file_path,
+                    content: source.into_bytes(),
+                });
+                self.result.edited.push(path.clone());
+            }
+            WorkspaceEditOperation::Create {
+                path,
+                content,
+                overwrite,
+                ignore_if_exists,
+            } => {
+                let file_path = PathBuf::from(path);
+                if file_path.exists() {
+                    if *ignore_if_exists && !overwrite {
+                        return Ok(());
+                    }
+                    if !overwrite {
+                        return Err(Failure::Invalid(format!("{} already exists", path)));
+                    }
+                    if !file_path.is_file() {
+                        return Err(Failure::Invalid(format!("{} is a directory", path)));
+                    }
+                    self.stash(&file_path)?;
+                }
+                self.create_parents(&file_path)?;
+                fs::write(&file_path, content.as_deref().unwrap_or(""))
+                    .map_err(|e| io_failure(&file_path, e))?;
+                self.undo.push(UndoStep::RemoveFile(file_path));
+                self.result.created.push(path.clone());
+            }
+            WorkspaceEditOperation::Rename {
+                old_path,
+                new_path,
+                overwrite,
+                ignore_if_exists,
+            } => {
+                let from = PathBuf::from(old_path);
+                let to = PathBuf::from(new_path);
+                if !from.exists() {
+                    return Err(Failure::Invalid(format!("{} does not exist", old_path)));
+                }
+                if to.starts_with(&from) && to != from {
+                    return Err(Failure::Invalid(format!("cannot move {} into itself", old_path)));
+                }
+                if to.exists() {
+                    if *ignore_if_exists && !overwrite {
+                        return Ok(());
+                    }
+                    if !overwrite {
+                        return Err(Failure::Invalid(format!("{} already exists", new_path)));
+                    }
+                    self.stash(&to)?;
+                }
+                self.create_parents(&to)?;
+                fs::rename(&from, &to).map_err(|e| io_failure(&from, e))?;
+                self.undo.push(UndoStep::Move { from: to, to: from });
+                self.result.renamed.push((old_path.clone(), new_path.clone()));
+            }
+            WorkspaceEditOperation::Delete {
+                path,
+                recursive,
+                ignore_if_not_exists,
+            } => {
+                let file_path = PathBuf::from(path);
+                if !file_path.exists() {
+                    return if *ignore_if_not_exists {
+                        Ok(())
+                    } else {
+                        Err(Failure::Invalid(format!("{} does not exist", path)))
+                    };
+                }
+                let non_empty = fs::read_dir(&file_path).is_ok_and(|mut entries| entries.next().is_some());
+                if file_path.is_dir() && !recursive && non_empty {
+                    return Err(Failure::Invalid(format!("{} is not empty", path)));
+                }
+                self.stash(&file_path)?;
+                self.result.deleted.push(path.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Undo completed steps newest first, collecting anything that could not be undone
+    fn rollback(self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for step in self.undo.into_iter().rev() {
+            let (path, outcome) = match step {
+                UndoStep::Restore { path, content } => {
+                    let outcome = write_atomic(&path, &content);
+                    (path, outcome)
+                }
+                UndoStep::RemoveFile(path) => {
+                    let outcome = fs::remove_file(&path);
+                    (path, outcome)
+                }
+                UndoStep::RemoveDirectory(path) => {
+                    let outcome = fs::remove_dir(&path);
+                    (path, outcome)
+                }
+                UndoStep::Move { from: source, to: path } | UndoStep::Unstash { stash: source, path } => {
+                    let outcome = fs::rename(&source, &path);
+                    (path, outcome)
+                }
+            };
+            if let Err(e) = outcome {
+                errors.push(format!("{}: {}", path.to_string_lossy(), e));
+            }
+        }
+        errors
+    }
+
+    /// Drop the stashed originals now that every operation succeeded
+    fn commit(self) -> WorkspaceEditResult {
+        for step in self.undo {
+            if let UndoStep::Unstash { stash, .. } = step {
+                let _ = if stash.is_dir() {
+                    fs::remove_dir_all(&stash)
+                } else {
+                    fs::remove_file(&stash)
+                };
+            }
+        }
+        self.result
+    }
+}
+
+/// Apply every operation or none of them
+pub fn apply_workspace_edit(
+    operations: &[WorkspaceEditOperation],
+) -> Result<WorkspaceEditResult, WorkspaceEditError> {
+    let mut transaction = Transaction {
+        stamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0),
+        stashed: 0,
+        undo: Vec::new(),
+        result: WorkspaceEditResult::default(),
+    };
+
+    for (index, operation) in operations.iter().enumerate() {
+        if let Err(failure) = transaction.apply(operation) {
+            let errors = transaction.rollback();
+            let (invalid, message) = match failure {
+                Failure::Invalid(message) => (true, message),
+                Failure::Failed(message) => (false, message),
+            };
+            return Err(if !errors.is_empty() {
+                WorkspaceEditError::RollbackFailed { index, message, errors }
+            } else if invalid {
+                WorkspaceEditError::InvalidOperation { index, message }
+            } else {
+                WorkspaceEditError::OperationFailed { index, message }
+            });
+        }
+    }
+    Ok(transaction.commit())
+}
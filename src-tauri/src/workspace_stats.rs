@@ -0,0 +1,472 @@
+/**
+ * Workspace statistics for the project dashboard
+ * Counts code, comment, and blank lines per language (tokei-style, by extension and comment syntax), finds the
+ * largest files, and summarizes the repository history
+ */
+
+use git2::{Repository, Sort};
+use ignore::{DirEntry, ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::file_system::map_io_error;
+use crate::git::GitService;
+use crate::types::{FileSystemError, GitInfo};
+
+/// Event carrying the partial breakdown while `compute_workspace_stats` runs
+pub const WORKSPACE_STATS_PROGRESS_EVENT: &str = "workspace://stats-progress";
+
+/// How often progress is reported while a walk is running
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Files listed in `largest_files`
+const LARGEST_FILES: usize = 20;
+
+/// Larger files are generated or data more often than source, and are only counted as files
+const MAX_COUNTED_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+struct Language {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+const fn language(
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+) -> Language {
+    Language {
+        name,
+        extensions,
+        line_comments,
+        block_comment,
+    }
+}
+
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+const XML_BLOCK: Option<(&str, &str)> = Some(("<!--", "-->"));
+
+const LANGUAGES: &[Language] = &[
+    language("Rust", &["rs"], &["//"], C_BLOCK),
+    language("TypeScript", &["ts", "tsx", "mts", "cts"], &["//"], C_BLOCK),
+    language("JavaScript", &["js", "jsx", "mjs", "cjs"], &["//"], C_BLOCK),
+    language("Python", &["py", "pyi", "pyw"], &["#"], None),
+    language("Go", &["go"], &["//"], C_BLOCK),
+    language("C", &["c", "h"], &["//"], C_BLOCK),
+    language("C++", &["cc", "cpp", "cxx", "hh", "hpp", "hxx"], &["//"], C_BLOCK),
+    language("C#", &["cs"], &["//"], C_BLOCK),
+    language("Java", &["java"], &["//"], C_BLOCK),
+    language("Kotlin", &["kt", "kts"], &["//"], C_BLOCK),
+    language("Swift", &["swift"], &["//"], C_BLOCK),
+    language("Scala", &["scala", "sc"], &["//"], C_BLOCK),
+    language("Dart", &["dart"], &["//"], C_BLOCK),
+    language("PHP", &["php"], &["//", "#"], C_BLOCK),
+    language("Ruby", &["rb", "rake"], &["#"], Some(("=begin", "=end"))),
+    language("Lua", &["lua"], &["--"], Some(("--[[", "]]"))),
+    language("Haskell", &["hs"], &["--"], Some(("{-", "-}"))),
+    language("Elixir", &["ex", "exs"], &["#"], None),
+    language("Shell", &["sh", "bash", "zsh", "fish"], &["#"], None),
+    language("PowerShell", &["ps1", "psm1"], &["#"], Some(("<#", "#>"))),
+    language("SQL", &["sql"], &["--"], C_BLOCK),
+    language("HTML", &["html", "htm"], &[], XML_BLOCK),
+    language("XML", &["xml", "svg", "xsd", "xsl"], &[], XML_BLOCK),
+    language("Vue", &["vue"], &["//"], XML_BLOCK),
+    language("Svelte", &["svelte"], &["//"], XML_BLOCK),
+    language("CSS", &["css"], &[], C_BLOCK),
+    language("SCSS", &["scss", "sass"], &["//"], C_BLOCK),
+    language("Less", &["less"], &["//"], C_BLOCK),
+    language("JSON", &["json", "jsonc"], &["//"], C_BLOCK),
+    language("YAML", &["yml", "yaml"], &["#"], None),
+    language("TOML", &["toml"], &["#"], None),
+    language("Markdown", &["md", "markdown"], &[], XML_BLOCK),
+    language("Makefile", &["mk", "mak"], &["#"], None),
+    language("Dockerfile", &["dockerfile"], &["#"], None),
+];
+
+/// Files recognized by name rather than extension
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Makefile", "Makefile"),
+    ("GNUmakefile", "Makefile"),
+    ("Dockerfile", "Dockerfile"),
+    ("Rakefile", "Ruby"),
+    ("Gemfile", "Ruby"),
+];
+
+fn detect_language(path: &Path) -> Option<&'static Language> {
+    let file_name = path.file_name()?.to_str()?;
+    if let Some((_, name)) = FILE_NAMES.iter().find(|(file, _)| *file == file_name) {
+        return LANGUAGES.iter().find(|language| language.name == *name);
+    }
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES.iter().find(|language| language.extensions.contains(&extension.as_str()))
+}
+
+/// Comment markers of a language, for tools that write comments (file templates, license headers)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentSyntax {
+    pub line: Option<&'static str>,
+    pub block: Option<(&'static str, &'static str)>,
+}
+
+/// Comment markers for a file by its name or extension, falling back to a language name such as the
+/// editor's language id (`rust`, `python`)
+pub fn comment_syntax(path: &Path, language: Option<&str>) -> Option<CommentSyntax> {
+    let language = detect_language(path).or_else(|| {
+        let name = language?;
+        LANGUAGES.iter().find(|language| language.name.eq_ignore_ascii_case(name))
+    })?;
+    Some(CommentSyntax {
+        line: language.line_comments.first().copied(),
+        block: language.block_comment,
+    })
+}
+
+/// Line counts of one language
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: u64,
+    pub lines: u64,
+    pub code: u64,
+    pub comments: u64,
+    pub blanks: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargestFile {
+    /// Relative to the workspace root
+    pub path: String,
+    pub language: String,
+    pub lines: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStats {
+    #[serde(flatten)]
+    pub info: GitInfo,
+    /// Commits reachable from HEAD
+    pub commit_count: u64,
+    /// Distinct author emails among those commits
+    pub contributors: u64,
+    /// Unix time in seconds of the oldest and newest commit
+    pub first_commit_at: Option<i64>,
+    pub last_commit_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceStatsProgress {
+    pub root: String,
+    pub files_scanned: u64,
+    /// Breakdown so far, most code first
+    pub languages: Vec<LanguageStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceStats {
+    pub root: String,
+    /// Most code first
+    pub languages: Vec<LanguageStats>,
+    /// Sum over all languages
+    pub total: LanguageStats,
+    /// Source files with the most lines, largest first
+    pub largest_files: Vec<LargestFile>,
+    /// Files in no known language, binary, or too large to count
+    pub other_files: u64,
+    /// `None` outside a repository
+    pub git: Option<GitStats>,
+    /// Entries that couldn't be read and are missing from the totals
+    pub errors: u64,
+}
+
+#[derive(Default)]
+struct LineCounts {
+    lines: u64,
+    code: u64,
+    comments: u64,
+    blanks: u64,
+}
+
+/// Classify each line as code, comment, or blank. Strings aren't parsed, so comment markers inside them
+/// can miscount a line; tokei-style counters accept the same approximation.
+fn count_lines(content: &str, language: &Language) -> LineCounts {
+    let mut counts = LineCounts::default();
+    let mut in_block = false;
+    for line in content.lines() {
+        counts.lines += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            counts.blanks += 1;
+            continue;
+        }
+        let is_comment = match language.block_comment {
+            Some((_, close)) if in_block => match trimmed.find(close) {
+                Some(end) => {
+                    in_block = false;
+                    let rest = trimmed[end + close.len()..].trim();
+                    rest.is_empty() || language.line_comments.iter().any(|prefix| rest.starts_with(prefix))
+                }
+                None => true,
+            },
+            // Before line comments, since Lua's `--[[` starts like a `--` comment
+            Some((open, close)) if trimmed.starts_with(open) => match trimmed[open.len()..].find(close) {
+                Some(end) => trimmed[open.len() + end + close.len()..].trim().is_empty(),
+                None => {
+                    in_block = true;
+                    true
+                }
+            },
+            _ if language.line_comments.iter().any(|prefix| trimmed.starts_with(prefix)) => true,
+            Some((open, close)) => {
+                // Code that opens a block comment it doesn't close
+                if let Some(start) = trimmed.find(open) {
+                    in_block = !trimmed[start + open.len()..].contains(close);
+                }
+                false
+            }
+            None => false,
+        };
+        match is_comment {
+            true => counts.comments += 1,
+            false => counts.code += 1,
+        }
+    }
+    counts
+}
+
+#[derive(Default)]
+struct Accumulated {
+    languages: HashMap<&'static str, LanguageStats>,
+    largest: Vec<LargestFile>,
+    other_files: u64,
+}
+
+impl Accumulated {
+    fn add(&mut self, language: &Language, relative_path: String, bytes: u64, counts: LineCounts) {
+        let stats = self.languages.entry(language.name).or_insert_with(|| LanguageStats {
+            language: language.name.to_string(),
+            ..LanguageStats::default()
+        });
+        stats.files += 1;
+        stats.lines += counts.lines;
+        stats.code += counts.code;
+        stats.comments += counts.comments;
+        stats.blanks += counts.blanks;
+        stats.bytes += bytes;
+
+        let smallest_kept = self.largest.last().map(|file| file.lines).unwrap_or(0);
+        if self.largest.len() < LARGEST_FILES || counts.lines > smallest_kept {
+            let position = self.largest.partition_point(|file| file.lines >= counts.lines);
+            self.largest.insert(
+                position,
+                LargestFile {
+                    path: relative_path,
+                    language: language.name.to_string(),
+                    lines: counts.lines,
+                    bytes,
+                },
+            );
+            self.largest.truncate(LARGEST_FILES);
+        }
+    }
+
+    /// Languages with the most code first
+    fn languages(&self) -> Vec<LanguageStats> {
+        let mut languages: Vec<LanguageStats> = self.languages.values().cloned().collect();
+        languages.sort_by(|a, b| b.code.cmp(&a.code).then_with(|| a.language.cmp(&b.language)));
+        languages
+    }
+}
+
+/// Hands each walker thread a visitor that adds its files to the shared totals
+struct StatsVisitorBuilder<'s> {
+    root: &'s Path,
+    accumulated: &'s Mutex<Accumulated>,
+    files: &'s AtomicU64,
+    errors: &'s AtomicU64,
+}
+
+impl<'s> ParallelVisitorBuilder<'s> for StatsVisitorBuilder<'s> {
+    fn build(&mut self) -> Box<dyn ParallelVisitor + 's> {
+        Box::new(StatsVisitor {
+            root: self.root,
+            accumulated: self.accumulated,
+            files: self.files,
+            errors: self.errors,
+        })
+    }
+}
+
+struct StatsVisitor<'s> {
+    root: &'s Path,
+    accumulated: &'s Mutex<Accumulated>,
+    files: &'s AtomicU64,
+    errors: &'s AtomicU64,
+}
+
+impl StatsVisitor<'_> {
+    fn record(&self, entry: &DirEntry) {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            return;
+        }
+        self.files.fetch_add(1, Ordering::Relaxed);
+        let Some(language) = detect_language(entry.path()) else {
+            self.accumulated.lock().unwrap().other_files += 1;
+            return;
+        };
+        let bytes = match entry.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(_) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        };
+        let content = match bytes > MAX_COUNTED_FILE_SIZE {
+            true => None,
+            false => match fs::read(entry.path()) {
+                Ok(content) => Some(content),
+                Err(_) => {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            },
+        };
+        let Some(content) = content.filter(|content| !content.contains(&0)) else {
+            self.accumulated.lock().unwrap().other_files += 1;
+            return;
+        };
+        let counts = count_lines(&String::from_utf8_lossy(&content), language);
+        let relative_path = entry.path().strip_prefix(self.root).unwrap_or(entry.path());
+        self.accumulated
+            .lock()
+            .unwrap()
+            .add(language, relative_path.to_string_lossy().to_string(), bytes, counts);
+    }
+}
+
+impl ParallelVisitor for StatsVisitor<'_> {
+    fn visit(&mut self, entry: Result<DirEntry, ignore::Error>) -> WalkState {
+        match entry {
+            Ok(entry) => self.record(&entry),
+            Err(_) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        WalkState::Continue
+    }
+}
+
+/// History summary of the repository containing `root`
+fn git_stats(git: &GitService, repo: &Repository) -> Option<GitStats> {
+    let info = git.repository_info(repo).ok()?;
+    let mut stats = GitStats {
+        info,
+        commit_count: 0,
+        contributors: 0,
+        first_commit_at: None,
+        last_commit_at: None,
+    };
+    let mut walk = repo.revwalk().ok()?;
+    walk.set_sorting(Sort::TIME).ok()?;
+    // An empty repository has no HEAD yet
+    if walk.push_head().is_err() {
+        return Some(stats);
+    }
+    let mut authors = HashSet::new();
+    for oid in walk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let time = commit.time().seconds();
+        stats.commit_count += 1;
+        stats.last_commit_at = Some(stats.last_commit_at.map_or(time, |last| last.max(time)));
+        stats.first_commit_at = Some(stats.first_commit_at.map_or(time, |first| first.min(time)));
+        authors.insert(commit.author().email().unwrap_or_default().to_lowercase());
+    }
+    stats.contributors = authors.len() as u64;
+    Some(stats)
+}
+
+/// Count the workspace under `root`, calling `on_progress` with the breakdown so far while it is walked.
+/// Ignore rules apply and `.git` directories are skipped.
+pub fn compute_workspace_stats(
+    root: &Path,
+    git: &GitService,
+    on_progress: impl Fn(WorkspaceStatsProgress) + Sync,
+) -> Result<WorkspaceStats, FileSystemError> {
+    if !fs::metadata(root).map_err(map_io_error)?.is_dir() {
+        return Err(FileSystemError::InvalidPath);
+    }
+    let display_root = root.to_string_lossy().to_string();
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .follow_links(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build_parallel();
+
+    let accumulated = Mutex::new(Accumulated::default());
+    let files = AtomicU64::new(0);
+    let errors = AtomicU64::new(0);
+    let snapshot = || WorkspaceStatsProgress {
+        root: display_root.clone(),
+        files_scanned: files.load(Ordering::Relaxed),
+        languages: accumulated.lock().unwrap().languages(),
+    };
+    let done = AtomicBool::new(false);
+
+    std::thread::scope(|scope| {
+        let reporter = scope.spawn(|| {
+            while !done.load(Ordering::SeqCst) {
+                std::thread::park_timeout(PROGRESS_INTERVAL);
+                if !done.load(Ordering::SeqCst) {
+                    on_progress(snapshot());
+                }
+            }
+        });
+        walker.visit(&mut StatsVisitorBuilder {
+            root,
+            accumulated: &accumulated,
+            files: &files,
+            errors: &errors,
+        });
+        done.store(true, Ordering::SeqCst);
+        reporter.thread().unpark();
+    });
+
+    let progress = snapshot();
+    on_progress(progress.clone());
+    let accumulated = accumulated.into_inner().unwrap();
+    let total = progress.languages.iter().fold(
+        LanguageStats {
+            language: "Total".to_string(),
+            ..LanguageStats::default()
+        },
+        |mut total, stats| {
+            total.files += stats.files;
+            total.lines += stats.lines;
+            total.code += stats.code;
+            total.comments += stats.comments;
+            total.blanks += stats.blanks;
+            total.bytes += stats.bytes;
+            total
+        },
+    );
+    Ok(WorkspaceStats {
+        root: progress.root,
+        languages: progress.languages,
+        total,
+        largest_files: accumulated.largest,
+        other_files: accumulated.other_files,
+        git: git.open(&display_root).ok().and_then(|repo| git_stats(git, &repo)),
+        errors: errors.load(Ordering::Relaxed),
+    })
+}
@@ -0,0 +1,89 @@
+/**
+ * WSL distro detection
+ * Lists the distros installed on Windows, so they can be offered as places to open folders from. Folders
+ * inside a distro are opened through Windows' share of it, and their terminals and tasks run inside the
+ * distro (see `fs_provider::WslProvider`); watching them polls, as for any network share.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::fs_provider::WslPath;
+
+/// An installed WSL distro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WslDistro {
+    pub name: String,
+    /// WSL 1 or 2
+    pub version: u8,
+    /// What `wsl.exe` without `--distribution` starts
+    pub default: bool,
+    pub running: bool,
+    /// The distro's root as Windows reaches it, e.g. `\\wsl.localhost\Ubuntu\`
+    pub root: String,
+}
+
+/// Installed distros, the default first; none where WSL isn't available
+pub fn list_distros() -> Result<Vec<WslDistro>, String> {
+    if !cfg!(windows) {
+        return Ok(Vec::new());
+    }
+    // A missing `wsl.exe`, or one that fails because WSL isn't enabled, means no distros
+    let Some(listing) = wsl_output(&["--list", "--verbose"]) else {
+        return Ok(Vec::new());
+    };
+    let running = wsl_output(&["--list", "--running", "--quiet"]).unwrap_or_default();
+    let running: Vec<&str> = running.lines().map(str::trim).filter(|name| !name.is_empty()).collect();
+    let mut distros = parse_distros(&listing, &running);
+    distros.sort_by_key(|distro| !distro.default);
+    Ok(distros)
+}
+
+/// Output of `wsl.exe` with `args`, or `None` if it fails
+fn wsl_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("wsl.exe").args(args).env("WSL_UTF8", "1").output().ok()?;
+    output.status.success().then(|| decode(&output.stdout))
+}
+
+/// `wsl.exe` writes UTF-16 unless `WSL_UTF8` is honored, which older versions don't
+fn decode(output: &[u8]) -> String {
+    if output.len() >= 2 && output.iter().skip(1).step_by(2).all(|&byte| byte == 0) {
+        let units: Vec<u16> =
+            output.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units).trim_start_matches('\u{feff}').to_string()
+    } else {
+        String::from_utf8_lossy(output).to_string()
+    }
+}
+
+/// Rows of `wsl --list --verbose` after its (localized) header: `* Ubuntu  Running  2`, where the state is
+/// localized too, so whether a distro runs comes from `running` instead
+fn parse_distros(listing: &str, running: &[&str]) -> Vec<WslDistro> {
+    listing
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let line = line.trim();
+            let (default, line) = match line.strip_prefix('*') {
+                Some(rest) => (true, rest.trim_start()),
+                None => (false, line),
+            };
+            // Names have no spaces; the state and version follow
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let version = fields.last()?.parse().ok()?;
+            let root = WslPath {
+                distro: name.clone(),
+                path: "/".to_string(),
+            }
+            .to_windows();
+            Some(WslDistro {
+                running: running.iter().any(|running| running.eq_ignore_ascii_case(&name)),
+                name,
+                version,
+                default,
+                root,
+            })
+        })
+        .collect()
+}